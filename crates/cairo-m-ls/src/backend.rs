@@ -1,15 +1,17 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::Duration;
 
-use cairo_m_compiler_parser::{SourceFile, Upcast};
+use cairo_m_compiler_parser::{SourceFile, Upcast, parse_file};
 use cairo_m_compiler_semantic::DefinitionKind;
 use cairo_m_compiler_semantic::db::module_semantic_index;
-use cairo_m_compiler_semantic::semantic_index::DefinitionId;
+use cairo_m_compiler_semantic::semantic_index::{
+    DefinitionId, resolve_definition_doc, resolve_imported_item,
+};
 use cairo_m_compiler_semantic::type_resolution::definition_semantic_type;
-use cairo_m_compiler_semantic::types::TypeId;
+use cairo_m_compiler_semantic::types::{TypeData, TypeId};
 use chumsky::span::Span; // for SimpleSpan::new
 use dashmap::DashMap;
 use salsa::Setter;
@@ -19,10 +21,25 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
+use crate::call_hierarchy::{
+    call_hierarchy_item, function_at, incoming_calls, item_function, outgoing_calls,
+};
+use crate::completion::{
+    CompletionContext, completion_context, field_completions, field_type, keyword_completions,
+    markdown_documentation, symbol_sort_text,
+};
+use crate::config::Config;
 use crate::db::{AnalysisDatabase, AnalysisDatabaseSwapper};
-use crate::diagnostics::{DiagnosticsController, DiagnosticsRequest, ProjectDiagnostics};
+use crate::diagnostics::controller::convert_cairo_diagnostic;
+use crate::diagnostics::{
+    DiagnosticsController, DiagnosticsRequest, DocumentVersions, FileDiagnostics,
+    ProjectDiagnostics, is_parse_diagnostic,
+};
+use crate::inlay_hints::inlay_hints;
 use crate::lsp_ext::{ServerStatus, ServerStatusNotification, ServerStatusParams};
 use crate::project::{ProjectController, ProjectModel, ProjectUpdate, ProjectUpdateRequest};
+use crate::ranges::{folding_ranges, selection_range};
+use crate::signature_help::{find_call_context, signature_information};
 
 /// LSP Backend for Cairo-M
 ///
@@ -44,6 +61,18 @@ pub struct Backend {
     project_model: Arc<ProjectModel>,
     /// Diagnostics controller for background diagnostic computation
     diagnostics_controller: Option<DiagnosticsController>,
+    /// Diagnostics last published by the diagnostics controller
+    diagnostics_state: Arc<ProjectDiagnostics>,
+    /// Latest version of each open document
+    document_versions: Arc<DocumentVersions>,
+    /// Whether the client pulls diagnostics, in which case they are not pushed to it
+    pull_diagnostics: Arc<AtomicBool>,
+    /// User settings, updated by `workspace/didChangeConfiguration`
+    config: Arc<RwLock<Config>>,
+    /// Capabilities of the client, set on initialization
+    client_capabilities: OnceLock<ClientCapabilities>,
+    /// Root of the first workspace folder, against which relative settings paths are resolved
+    workspace_root: OnceLock<PathBuf>,
     /// Database swapper for memory management
     /// This field is intentionally kept alive to maintain the background swapping task.
     /// The swapper spawns a task that periodically resets the database to prevent
@@ -76,11 +105,19 @@ impl Backend {
         // Create project model
         let project_model = Arc::new(ProjectModel::new());
 
+        // Create document versions, shared with the controller to drop outdated results
+        let document_versions = Arc::new(DocumentVersions::new());
+
+        // Create user settings, replaced on initialization and configuration changes
+        let config = Arc::new(RwLock::new(Config::default()));
+
         // Create diagnostics controller
         let diagnostics_controller = DiagnosticsController::new(
             Arc::clone(&db),
             Arc::clone(&diagnostics_state),
             Arc::clone(&project_model),
+            Arc::clone(&document_versions),
+            Arc::clone(&config),
             diag_tx,
         );
 
@@ -94,8 +131,11 @@ impl Backend {
             std::time::Duration::from_secs(300),
         );
 
+        let pull_diagnostics = Arc::new(AtomicBool::new(false));
+
         // Spawn dedicated task for continuous diagnostics monitoring
         let client_clone = client.clone();
+        let pull_diagnostics_clone = Arc::clone(&pull_diagnostics);
         tokio::spawn(async move {
             while let Some(response) = diag_rx.recv().await {
                 // Handle special URIs or publish
@@ -114,6 +154,18 @@ impl Backend {
                                 status: ServerStatus::AnalysisFinished,
                             })
                             .await;
+                        if pull_diagnostics_clone.load(Ordering::Relaxed) {
+                            // Ask the client to pull the new results, without waiting for it
+                            let client = client_clone.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = client.workspace_diagnostic_refresh().await {
+                                    tracing::debug!("Failed to refresh diagnostics: {:?}", e);
+                                }
+                            });
+                        }
+                    }
+                    _ if pull_diagnostics_clone.load(Ordering::Relaxed) => {
+                        // Stored by the controller, served when the client pulls them
                     }
                     _ => {
                         client_clone
@@ -130,7 +182,7 @@ impl Backend {
         });
 
         // Spawn dedicated task for continuous project update monitoring
-        let max_file_size = project_model.limits.max_file_size;
+        let config_clone = Arc::clone(&config);
         let client_clone2: Client = client.clone();
         let project_model_clone = Arc::clone(&project_model);
         let db_clone = Arc::clone(&db);
@@ -142,6 +194,10 @@ impl Backend {
 
         tokio::spawn(async move {
             while let Some(update) = project_rx.recv().await {
+                let max_file_size = config_clone
+                    .read()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .max_file_size;
                 match update {
                     ProjectUpdate::Project {
                         project,
@@ -179,7 +235,6 @@ impl Backend {
                                         // Check file size before reading
                                         if let Ok(metadata) = std::fs::metadata(path) {
                                             let file_size = metadata.len() as usize;
-                                            // Default 10MB limit - could be made configurable
                                             if file_size > max_file_size {
                                                 tracing::warn!(
                                                     "File {:?} exceeds size limit ({} > {} bytes), skipping",
@@ -298,13 +353,12 @@ impl Backend {
                                         // Check file size before reading
                                         if let Ok(metadata) = std::fs::metadata(&file_path_clone) {
                                             let file_size = metadata.len() as usize;
-                                            const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
-                                            if file_size > MAX_FILE_SIZE {
+                                            if file_size > max_file_size {
                                                 tracing::error!(
                                                     "File {:?} exceeds size limit ({} > {} bytes)",
                                                     file_path_clone,
                                                     file_size,
-                                                    MAX_FILE_SIZE
+                                                    max_file_size
                                                 );
                                                 return Err(format!(
                                                     "File exceeds size limit: {} bytes",
@@ -425,6 +479,12 @@ impl Backend {
             project_controller: Some(project_controller),
             project_model,
             diagnostics_controller: Some(diagnostics_controller),
+            diagnostics_state,
+            document_versions,
+            pull_diagnostics,
+            config,
+            client_capabilities: OnceLock::new(),
+            workspace_root: OnceLock::new(),
             _db_swapper: Some(db_swapper),
             debounce_timers: Arc::new(DashMap::new()),
             debounce_delay_ms: Arc::new(AtomicU64::new(300)), // Default to 300ms
@@ -533,6 +593,23 @@ impl Backend {
         source.len()
     }
 
+    /// Range covering an entire document
+    fn document_range(text: &str) -> Range {
+        let line_count = text.lines().count();
+        let last_line_len = text.lines().last().map(|l| l.len()).unwrap_or(0);
+
+        Range {
+            start: Position {
+                line: 0,
+                character: 0,
+            },
+            end: Position {
+                line: (line_count.saturating_sub(1)) as u32,
+                character: last_line_len as u32,
+            },
+        }
+    }
+
     /// Get the semantic crate for a file URL
     async fn get_semantic_crate_for_file(
         &self,
@@ -588,6 +665,200 @@ impl Backend {
         self.debounce_timers.insert(uri, handle);
     }
 
+    /// Publish the parse diagnostics of a file right after a change.
+    ///
+    /// Semantic diagnostics from the last background validation are kept until the debounced
+    /// validation replaces them, so that they do not flicker while typing. Clients pulling
+    /// diagnostics get them on their next pull.
+    async fn publish_parse_diagnostics(
+        &self,
+        uri: Url,
+        version: i32,
+        parse_diagnostics: Vec<Diagnostic>,
+    ) {
+        let mut diagnostics = parse_diagnostics;
+        diagnostics.extend(
+            self.diagnostics_state
+                .get_diagnostics(&uri)
+                .await
+                .into_iter()
+                .filter(|diagnostic| !is_parse_diagnostic(diagnostic)),
+        );
+
+        if self.pull_diagnostics.load(Ordering::Relaxed) {
+            self.diagnostics_state
+                .set_diagnostics(&uri, diagnostics)
+                .await;
+            return;
+        }
+
+        self.client
+            .publish_diagnostics(uri, diagnostics, Some(version))
+            .await;
+    }
+
+    /// Load the projects at the root of the workspace folders, so that all of their files are
+    /// validated without being opened.
+    fn load_workspace_projects(&self, folders: &[WorkspaceFolder]) {
+        let Some(controller) = &self.project_controller else {
+            return;
+        };
+        for folder in folders {
+            let Ok(root) = folder.uri.to_file_path() else {
+                continue;
+            };
+            let manifest = root.join(cairo_m_project::MANIFEST_FILE_NAME);
+            if manifest.is_file() {
+                if let Err(e) = controller.update(ProjectUpdateRequest::UpdateForFile {
+                    file_path: manifest,
+                }) {
+                    tracing::debug!("Failed to send project update request: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Report of the diagnostics of a file, unchanged if the client already has them
+    fn document_report(
+        file: Option<FileDiagnostics>,
+        previous_result_id: Option<&str>,
+    ) -> DocumentDiagnosticReport {
+        match file {
+            Some(file) if previous_result_id == Some(file.result_id.as_str()) => {
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id: file.result_id,
+                    },
+                })
+            }
+            file => DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: file.as_ref().map(|file| file.result_id.clone()),
+                    items: file.map(|file| file.diagnostics).unwrap_or_default(),
+                },
+            }),
+        }
+    }
+
+    /// Current user settings
+    fn config(&self) -> Config {
+        self.config
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Returns true if the client advertised a capability
+    fn client_supports(
+        &self,
+        capability: impl FnOnce(&ClientCapabilities) -> Option<bool>,
+    ) -> bool {
+        self.client_capabilities
+            .get()
+            .and_then(capability)
+            .unwrap_or(false)
+    }
+
+    /// Apply settings from a `workspace/didChangeConfiguration` notification
+    async fn apply_settings(&self, settings: &serde_json::Value) {
+        match Config::from_settings(settings) {
+            Ok(config) => self.update_config(config).await,
+            Err(e) => {
+                self.client
+                    .show_message(MessageType::WARNING, format!("Ignoring settings: {e}"))
+                    .await;
+            }
+        }
+    }
+
+    /// Replace the user settings, and update the analysis depending on them
+    async fn update_config(&self, config: Config) {
+        let previous = {
+            let mut current = self
+                .config
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            std::mem::replace(&mut *current, config.clone())
+        };
+
+        let crates = self.project_model.all_crates().await;
+        if previous.max_file_size != config.max_file_size {
+            // Reloading the projects loads the files that were too large so far
+            if let Some(controller) = &self.project_controller {
+                for krate in &crates {
+                    let Some(file_path) = krate
+                        .main_file
+                        .clone()
+                        .or_else(|| krate.files.keys().next().cloned())
+                    else {
+                        continue;
+                    };
+                    if let Err(e) =
+                        controller.update(ProjectUpdateRequest::UpdateForFile { file_path })
+                    {
+                        tracing::debug!("Failed to send project update request: {}", e);
+                    }
+                }
+            }
+        } else if previous.lints != config.lints {
+            // Validation results are cached, only the lint levels applied to them change
+            if let Some(controller) = &self.diagnostics_controller {
+                self.client
+                    .send_notification::<ServerStatusNotification>(ServerStatusParams {
+                        status: ServerStatus::AnalysisStarted,
+                    })
+                    .await;
+                for krate in &crates {
+                    let Some(project_crate) = self
+                        .project_model
+                        .get_project_crate_for_root(&krate.info.root)
+                        .await
+                    else {
+                        continue;
+                    };
+                    if let Err(e) =
+                        controller.request(DiagnosticsRequest::ProjectChanged { project_crate })
+                    {
+                        tracing::debug!(
+                            "Failed to send project changed diagnostics request: {}",
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        if previous.inlay_hints != config.inlay_hints
+            && self.client_supports(|caps| {
+                caps.workspace
+                    .as_ref()?
+                    .inlay_hint
+                    .as_ref()?
+                    .refresh_support
+            })
+        {
+            if let Err(e) = self.client.inlay_hint_refresh().await {
+                tracing::debug!("Failed to refresh inlay hints: {:?}", e);
+            }
+        }
+    }
+
+    /// Formatter options of the user settings, falling back to the defaults if they are invalid
+    async fn formatter_config(&self) -> cairo_m_formatter::FormatterConfig {
+        match self
+            .config()
+            .formatter_config(self.workspace_root.get().map(PathBuf::as_path))
+        {
+            Ok(config) => config,
+            Err(e) => {
+                self.client.show_message(MessageType::WARNING, e).await;
+                cairo_m_formatter::FormatterConfig::default()
+            }
+        }
+    }
+
     /// Run semantic validation and publish diagnostics.
     ///
     /// This now delegates to the DiagnosticsController for background computation.
@@ -611,7 +882,7 @@ impl Backend {
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         // Check for initialization options
-        if let Some(options) = params.initialization_options {
+        if let Some(options) = &params.initialization_options {
             if let Some(debounce) = options.get("debounce_ms") {
                 if let Some(debounce_value) = debounce.as_u64() {
                     self.debounce_delay_ms
@@ -621,17 +892,78 @@ impl LanguageServer for Backend {
             // Note: db_swap_interval_ms would need to be handled during Backend construction
             // as the AnalysisDatabaseSwapper is created there. For testing purposes,
             // we'll need to make this configurable via a different mechanism.
+
+            match Config::from_settings(options) {
+                Ok(config) => {
+                    *self
+                        .config
+                        .write()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner()) = config;
+                }
+                Err(e) => tracing::warn!("Ignoring initialization options: {}", e),
+            }
         }
+
+        let workspace_root = params
+            .workspace_folders
+            .iter()
+            .flatten()
+            .map(|folder| &folder.uri)
+            .chain(params.root_uri.as_ref())
+            .find_map(|uri| uri.to_file_path().ok());
+        if let Some(root) = workspace_root {
+            let _ = self.workspace_root.set(root);
+        }
+        let _ = self.client_capabilities.set(params.capabilities.clone());
+
+        // Clients pulling diagnostics get those of the whole workspace, opened files or not
+        let pull_diagnostics = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some());
+        self.pull_diagnostics
+            .store(pull_diagnostics, Ordering::Relaxed);
+        if pull_diagnostics {
+            self.load_workspace_projects(params.workspace_folders.as_deref().unwrap_or_default());
+        }
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
                     TextDocumentSyncKind::FULL,
                 )),
-                completion_provider: Some(CompletionOptions::default()),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![".".to_string(), ":".to_string()]),
+                    ..Default::default()
+                }),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 definition_provider: Some(OneOf::Left(true)),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 document_range_formatting_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
+                        ..Default::default()
+                    },
+                )),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some("cairo-m".to_string()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
                 ..Default::default()
             },
             ..Default::default()
@@ -653,6 +985,7 @@ impl LanguageServer for Backend {
         tracing::debug!("Did open: {}", uri);
         let content = params.text_document.text;
         let version = params.text_document.version;
+        self.document_versions.record(&uri, version);
 
         let path = match uri.to_file_path() {
             Ok(p) => p,
@@ -697,22 +1030,116 @@ impl LanguageServer for Backend {
         self.run_diagnostics(uri, Some(version)).await;
     }
 
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        // Clients may only notify of the change, for the settings to be pulled
+        if params.settings.is_null()
+            && self.client_supports(|caps| caps.workspace.as_ref()?.configuration)
+        {
+            let item = ConfigurationItem {
+                scope_uri: None,
+                section: Some(crate::config::SETTINGS_SECTION.to_string()),
+            };
+            match self.client.configuration(vec![item]).await {
+                Ok(mut settings) => {
+                    self.apply_settings(&settings.pop().unwrap_or_default())
+                        .await;
+                }
+                Err(e) => tracing::debug!("Failed to pull settings: {:?}", e),
+            }
+            return;
+        }
+
+        self.apply_settings(&params.settings).await;
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let file = self
+            .diagnostics_state
+            .get_file_diagnostics(&params.text_document.uri)
+            .await;
+        Ok(DocumentDiagnosticReportResult::Report(
+            Self::document_report(file, params.previous_result_id.as_deref()),
+        ))
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        let previous_result_ids: HashMap<Url, String> = params
+            .previous_result_ids
+            .into_iter()
+            .map(|previous| (previous.uri, previous.value))
+            .collect();
+
+        let items = self
+            .diagnostics_state
+            .all_file_diagnostics()
+            .await
+            .into_iter()
+            .map(|(uri, file)| {
+                let previous_result_id = previous_result_ids.get(&uri).map(String::as_str);
+                match Self::document_report(Some(file), previous_result_id) {
+                    DocumentDiagnosticReport::Full(report) => {
+                        WorkspaceDocumentDiagnosticReport::Full(
+                            WorkspaceFullDocumentDiagnosticReport {
+                                uri,
+                                version: None,
+                                full_document_diagnostic_report: report
+                                    .full_document_diagnostic_report,
+                            },
+                        )
+                    }
+                    DocumentDiagnosticReport::Unchanged(report) => {
+                        WorkspaceDocumentDiagnosticReport::Unchanged(
+                            WorkspaceUnchangedDocumentDiagnosticReport {
+                                uri,
+                                version: None,
+                                unchanged_document_diagnostic_report: report
+                                    .unchanged_document_diagnostic_report,
+                            },
+                        )
+                    }
+                }
+            })
+            .collect();
+
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri;
         let version = params.text_document.version;
+        // Record the version first, so that in-flight results for older versions are dropped
+        self.document_versions.record(&uri, version);
 
         if let Some(change) = params.content_changes.into_iter().next() {
             // Update the SourceFile with new content. This is the key to
-            // incremental compilation.
+            // incremental compilation. Setting the text cancels any background
+            // validation still running on the previous revision.
             if let Some(source) = self.source_files.get(&uri).map(|s| *s.value()) {
                 let change_text = change.text.clone();
                 let join_handle = self.safe_db_access_mut(move |db| {
-                    source.set_text(db).to(change_text);
+                    source.set_text(db).to(change_text.clone());
+
+                    // Parsing a single file is cheap enough to be done on every change
+                    parse_file(db, source)
+                        .diagnostics
+                        .iter()
+                        .map(|diagnostic| convert_cairo_diagnostic(&change_text, diagnostic))
+                        .collect::<Vec<_>>()
                 });
 
                 match join_handle.await {
-                    Ok(_) => {
-                        // Schedule debounced diagnostics instead of immediate run
+                    Ok(parse_diagnostics) => {
+                        self.publish_parse_diagnostics(uri.clone(), version, parse_diagnostics)
+                            .await;
+                        // Schedule debounced semantic validation instead of immediate run
                         self.schedule_debounced_diagnostics(uri, Some(version));
                     }
                     Err(e) => {
@@ -837,6 +1264,39 @@ impl LanguageServer for Backend {
                 for (_def_idx, def) in index.all_definitions() {
                     if def.full_span.start <= offset && offset <= def.full_span.end {
                         if let DefinitionKind::Use(use_ref) = &def.kind {
+                            // On an imported item, follow re-exports to its definition
+                            let on_item = [Some(&use_ref.item), use_ref.alias.as_ref()]
+                                .into_iter()
+                                .flatten()
+                                .any(|name| {
+                                    name.span().start <= offset && offset <= name.span().end
+                                });
+                            if on_item {
+                                let (_, imported_def, imported_file) = resolve_imported_item(
+                                    db.upcast(),
+                                    crate_id,
+                                    use_ref.imported_module.value(),
+                                    use_ref.item.value(),
+                                )?;
+                                let def_uri = self
+                                    .get_uri_from_path_str(imported_file.file_path(db))
+                                    .ok()?;
+                                let def_content = imported_file.text(db);
+                                return Some(Location {
+                                    uri: def_uri,
+                                    range: Range {
+                                        start: self.offset_to_position(
+                                            def_content,
+                                            imported_def.name_span.start,
+                                        ),
+                                        end: self.offset_to_position(
+                                            def_content,
+                                            imported_def.name_span.end,
+                                        ),
+                                    },
+                                });
+                            }
+
                             // Try to find the module file
                             let _module_path = format!("{}.cm", use_ref.imported_module.value());
 
@@ -944,6 +1404,12 @@ impl LanguageServer for Backend {
                         }
                     }
 
+                    if let Some(doc) =
+                        resolve_definition_doc(db.upcast(), crate_id, def_file, def_idx)
+                    {
+                        hover_text.push_str(&format!("\n\n---\n\n{doc}"));
+                    }
+
                     Some(Hover {
                         contents: HoverContents::Markup(MarkupContent {
                             kind: MarkupKind::Markdown,
@@ -982,6 +1448,7 @@ impl LanguageServer for Backend {
         let completion_items = match self.safe_db_access_sync(|db| {
             let content = source.text(db);
             let offset = self.position_to_offset(content, position);
+            let context = completion_context(content, offset);
 
             // Determine which module this file belongs to in the project
             let file_path = uri.to_file_path().ok();
@@ -991,6 +1458,50 @@ impl LanguageServer for Backend {
                 .and_then(|stem| stem.to_str())
                 .map(|s| s.to_string())?;
 
+            // Items of the module named in a `use` path, or the modules themselves
+            if let CompletionContext::UsePath { module } = &context {
+                let mut items = Vec::new();
+                match module {
+                    None => {
+                        for name in crate_id.modules(db).keys() {
+                            if *name != module_name {
+                                items.push(CompletionItem {
+                                    label: name.clone(),
+                                    kind: Some(CompletionItemKind::MODULE),
+                                    ..Default::default()
+                                });
+                            }
+                        }
+                    }
+                    Some(module) => {
+                        let module_file = *crate_id.modules(db).get(module)?;
+                        let module_index =
+                            module_semantic_index(db.upcast(), crate_id, module.clone()).ok()?;
+                        let root_scope = module_index.root_scope()?;
+                        for (def_idx, def) in module_index.definitions_in_scope(root_scope) {
+                            let kind = match def.kind {
+                                DefinitionKind::Function(_) => CompletionItemKind::FUNCTION,
+                                DefinitionKind::Const(_) => CompletionItemKind::CONSTANT,
+                                DefinitionKind::Struct(_) => CompletionItemKind::STRUCT,
+                                _ => continue,
+                            };
+                            let def_id = DefinitionId::new(db, module_file, def_idx);
+                            let type_id = definition_semantic_type(db.upcast(), crate_id, def_id);
+                            items.push(CompletionItem {
+                                label: def.name.clone(),
+                                kind: Some(kind),
+                                detail: Some(TypeId::format_type(db.upcast(), type_id)),
+                                documentation: module_index
+                                    .definition_doc(def_idx)
+                                    .map(markdown_documentation),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+                return Some(items);
+            }
+
             let index = module_semantic_index(db.upcast(), crate_id, module_name).ok()?;
 
             // Find the scope at the cursor position.
@@ -1015,9 +1526,38 @@ impl LanguageServer for Backend {
                 best_scope.or_else(|| index.root_scope())?
             };
 
+            // Fields of the type of the receiver, resolved segment by segment
+            if let CompletionContext::FieldAccess {
+                receiver,
+                receiver_start,
+            } = &context
+            {
+                let root = &receiver[0];
+                let root_span = chumsky::span::SimpleSpan::new(
+                    (),
+                    *receiver_start..*receiver_start + root.len(),
+                );
+                let (def_idx, _def, def_file) = index.resolve_name_with_imports_at_position(
+                    db.upcast(),
+                    crate_id,
+                    source,
+                    root,
+                    current_scope,
+                    root_span,
+                )?;
+                let def_id = DefinitionId::new(db, def_file, def_idx);
+                let mut type_id = definition_semantic_type(db.upcast(), crate_id, def_id);
+                for field in &receiver[1..] {
+                    type_id = field_type(db.upcast(), type_id, field)?;
+                }
+                return Some(field_completions(db.upcast(), type_id));
+            }
+
             let mut items = Vec::new();
             let mut seen_names = std::collections::HashSet::new();
             let mut scope = Some(current_scope);
+            // Symbols of inner scopes are ranked first
+            let mut scope_depth = 0;
 
             while let Some(scope_id) = scope {
                 for (def_idx, def) in index.definitions_in_scope(scope_id) {
@@ -1052,44 +1592,27 @@ impl LanguageServer for Backend {
                         };
 
                         items.push(CompletionItem {
+                            sort_text: Some(symbol_sort_text(scope_depth, &name)),
                             label: name,
                             kind: Some(kind),
                             detail: Some(type_str),
-                            documentation: None,
+                            documentation: resolve_definition_doc(
+                                db.upcast(),
+                                crate_id,
+                                source,
+                                def_idx,
+                            )
+                            .map(markdown_documentation),
                             ..Default::default()
                         });
                     }
                 }
                 scope = index.scope(scope_id).and_then(|s| s.parent);
+                scope_depth += 1;
             }
 
-            // Add keywords
-            let keywords = vec![
-                ("if", CompletionItemKind::KEYWORD),
-                ("else", CompletionItemKind::KEYWORD),
-                ("while", CompletionItemKind::KEYWORD),
-                ("loop", CompletionItemKind::KEYWORD),
-                ("for", CompletionItemKind::KEYWORD),
-                ("break", CompletionItemKind::KEYWORD),
-                ("continue", CompletionItemKind::KEYWORD),
-                ("return", CompletionItemKind::KEYWORD),
-                ("let", CompletionItemKind::KEYWORD),
-                ("local", CompletionItemKind::KEYWORD),
-                ("const", CompletionItemKind::KEYWORD),
-                ("fn", CompletionItemKind::KEYWORD),
-                ("struct", CompletionItemKind::KEYWORD),
-                ("true", CompletionItemKind::KEYWORD),
-                ("false", CompletionItemKind::KEYWORD),
-                ("felt", CompletionItemKind::KEYWORD),
-            ];
-
-            for (keyword, kind) in keywords {
-                items.push(CompletionItem {
-                    label: keyword.to_string(),
-                    kind: Some(kind),
-                    ..Default::default()
-                });
-            }
+            // Add the keywords valid at the cursor
+            items.extend(keyword_completions(&context));
 
             Some(items)
         }) {
@@ -1100,6 +1623,224 @@ impl LanguageServer for Backend {
         Ok(Some(CompletionResponse::Array(completion_items)))
     }
 
+    #[allow(clippy::significant_drop_tightening)]
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        // Get crate for cross-file analysis
+        let crate_id = match self.get_semantic_crate_for_file(&uri).await {
+            Some(crate_id) => crate_id,
+            None => return Ok(None),
+        };
+
+        // Retrieve the SourceFile from our map.
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        let signature_help = self.safe_db_access_sync(|db| {
+            let content = source.text(db);
+            let offset = self.position_to_offset(content, position);
+            let call = find_call_context(content, offset)?;
+
+            // Determine which module this file belongs to in the project
+            let file_path = uri.to_file_path().ok();
+            let module_name = file_path
+                .as_ref()
+                .and_then(|p| p.file_stem())
+                .and_then(|stem| stem.to_str())
+                .map(|s| s.to_string())?;
+
+            let index = module_semantic_index(db.upcast(), crate_id, module_name).ok()?;
+
+            // Resolve the callee from its usage, falling back to the module scope when the
+            // call being typed is not part of the index yet
+            let callee_span =
+                chumsky::span::SimpleSpan::new((), call.callee_span.start..call.callee_span.end);
+            let scope_id = index
+                .identifier_usages()
+                .iter()
+                .find(|usage| usage.span.start == callee_span.start && usage.name == call.callee)
+                .map(|usage| usage.scope_id)
+                .or_else(|| index.root_scope())?;
+            let (def_idx, _def, def_file) = index.resolve_name_with_imports_at_position(
+                db.upcast(),
+                crate_id,
+                source,
+                &call.callee,
+                scope_id,
+                callee_span,
+            )?;
+
+            let def_id = DefinitionId::new(db, def_file, def_idx);
+            let type_id = definition_semantic_type(db.upcast(), crate_id, def_id);
+            let TypeData::Function(signature) = type_id.data(db) else {
+                return None;
+            };
+
+            Some(SignatureHelp {
+                signatures: vec![signature_information(
+                    db.upcast(),
+                    &call.callee,
+                    *signature,
+                    call.active_parameter,
+                )],
+                active_signature: Some(0),
+                active_parameter: Some(call.active_parameter),
+            })
+        });
+
+        Ok(signature_help.flatten())
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let uri = params.text_document.uri;
+
+        // Retrieve the SourceFile from our map.
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        let ranges = self.safe_db_access_sync(|db| {
+            let content = source.text(db);
+            let parsed = parse_file(db, source);
+            folding_ranges(content, &parsed.module)
+        });
+
+        Ok(ranges)
+    }
+
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let uri = params.text_document.uri;
+
+        // Retrieve the SourceFile from our map.
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        let ranges = self.safe_db_access_sync(|db| {
+            let content = source.text(db);
+            let parsed = parse_file(db, source);
+            params
+                .positions
+                .iter()
+                .map(|position| {
+                    let offset = self.position_to_offset(content, *position);
+                    // Positions outside of any node select an empty range
+                    selection_range(content, &parsed.module, offset).unwrap_or(SelectionRange {
+                        range: Range {
+                            start: *position,
+                            end: *position,
+                        },
+                        parent: None,
+                    })
+                })
+                .collect()
+        });
+
+        Ok(ranges)
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        // Get crate for cross-file analysis
+        let crate_id = match self.get_semantic_crate_for_file(&uri).await {
+            Some(crate_id) => crate_id,
+            None => return Ok(None),
+        };
+
+        // Retrieve the SourceFile from our map.
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        let item = self.safe_db_access_sync(|db| {
+            let offset = self.position_to_offset(source.text(db), position);
+            let function = function_at(db.upcast(), crate_id, source, offset)?;
+            call_hierarchy_item(db.upcast(), crate_id, &function)
+        });
+
+        Ok(item.flatten().map(|item| vec![item]))
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let Some(function) = item_function(&params.item) else {
+            return Ok(None);
+        };
+        let Some(crate_id) = self.get_semantic_crate_for_file(&params.item.uri).await else {
+            return Ok(None);
+        };
+
+        Ok(self.safe_db_access_sync(|db| incoming_calls(db.upcast(), crate_id, &function)))
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let Some(function) = item_function(&params.item) else {
+            return Ok(None);
+        };
+        let Some(crate_id) = self.get_semantic_crate_for_file(&params.item.uri).await else {
+            return Ok(None);
+        };
+
+        Ok(self.safe_db_access_sync(|db| outgoing_calls(db.upcast(), crate_id, &function)))
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let uri = params.text_document.uri;
+
+        // Get crate for cross-file analysis
+        let crate_id = match self.get_semantic_crate_for_file(&uri).await {
+            Some(crate_id) => crate_id,
+            None => return Ok(None),
+        };
+
+        // Retrieve the SourceFile from our map.
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        let config = self.config().inlay_hints;
+        let hints = self.safe_db_access_sync(|db| {
+            let content = source.text(db);
+            let range = self.position_to_offset(content, params.range.start)
+                ..self.position_to_offset(content, params.range.end);
+
+            let module_name = uri.to_file_path().ok()?.file_stem()?.to_str()?.to_string();
+            let index = module_semantic_index(db.upcast(), crate_id, module_name).ok()?;
+            Some(inlay_hints(
+                db.upcast(),
+                crate_id,
+                source,
+                &index,
+                content,
+                range,
+                config,
+            ))
+        });
+
+        Ok(hints.flatten())
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let uri = params.text_document.uri;
 
@@ -1110,8 +1851,8 @@ impl LanguageServer for Backend {
         };
 
         // Format the file using the formatter
+        let config = self.formatter_config().await;
         let formatted_text = self.safe_db_access_sync(|db| {
-            let config = cairo_m_formatter::FormatterConfig::default();
             cairo_m_formatter::format_source_file(db.upcast(), source, &config)
         });
 
@@ -1127,24 +1868,9 @@ impl LanguageServer for Backend {
             None => return Ok(None),
         };
 
-        // Calculate the range that covers the entire document
-        let line_count = current.lines().count();
-        let last_line_len = current.lines().last().map(|l| l.len()).unwrap_or(0);
-
-        let range = Range {
-            start: Position {
-                line: 0,
-                character: 0,
-            },
-            end: Position {
-                line: (line_count.saturating_sub(1)) as u32,
-                character: last_line_len as u32,
-            },
-        };
-
         // Return a single text edit that replaces the entire document
         Ok(Some(vec![TextEdit {
-            range,
+            range: Self::document_range(&current),
             new_text: formatted,
         }]))
     }
@@ -1161,4 +1887,60 @@ impl LanguageServer for Backend {
         };
         self.formatting(formatting_params).await
     }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+
+        // Only organize imports is provided, which also matches a request for `source`
+        let kind = CodeActionKind::SOURCE_ORGANIZE_IMPORTS;
+        if let Some(only) = &params.context.only {
+            if !only
+                .iter()
+                .any(|requested| kind.as_str().starts_with(requested.as_str()))
+            {
+                return Ok(None);
+            }
+        }
+
+        let source = match self.source_files.get(&uri) {
+            Some(entry) => *entry.value(),
+            None => return Ok(None),
+        };
+
+        // Modules of the project are grouped after other imports
+        let project_modules = match self.get_semantic_crate_for_file(&uri).await {
+            Some(crate_id) => self
+                .safe_db_access_sync(|db| crate_id.modules(db).keys().cloned().collect())
+                .unwrap_or_default(),
+            None => vec![],
+        };
+
+        let config = cairo_m_formatter::FormatterConfig {
+            organize_imports: true,
+            project_modules,
+            ..self.formatter_config().await
+        };
+        let edit = self.safe_db_access_sync(|db| {
+            let current = source.text(db);
+            let organized = cairo_m_formatter::format_source_file(db.upcast(), source, &config);
+            (organized != *current).then(|| TextEdit {
+                range: Self::document_range(current),
+                new_text: organized,
+            })
+        });
+
+        let Some(Some(edit)) = edit else {
+            return Ok(None);
+        };
+
+        Ok(Some(vec![CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Organize imports".to_string(),
+            kind: Some(kind),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri, vec![edit])])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })]))
+    }
 }
@@ -0,0 +1,174 @@
+//! Call hierarchy over the crate-wide call graph
+//!
+//! Items of the hierarchy are the functions and methods of the crate, identified across requests
+//! by the [`FunctionRef`] stored in their `data`. Incoming and outgoing calls are read from the
+//! call graph of the semantic crate, so that they follow calls across modules.
+
+use std::collections::BTreeMap;
+
+use cairo_m_compiler_semantic::call_graph::{CallSite, FunctionRef, crate_call_graph};
+use cairo_m_compiler_semantic::db::{module_name_for_file, module_semantic_index};
+use cairo_m_compiler_semantic::{Crate, Definition, DefinitionKind, File, SemanticDb};
+use chumsky::span::SimpleSpan;
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Range, SymbolKind,
+};
+
+use crate::diagnostics::controller::offset_to_position;
+use crate::utils::get_uri_from_path_str;
+
+/// Identity of a function, as stored in the `data` of its call hierarchy item
+#[derive(Serialize, Deserialize)]
+struct ItemData {
+    module: String,
+    name: String,
+}
+
+/// Returns the function whose name is at `offset` in `file`, either at its definition or at a
+/// call to it
+pub fn function_at(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    offset: usize,
+) -> Option<FunctionRef> {
+    let module = module_name_for_file(db, crate_id, file)?;
+    let index = module_semantic_index(db, crate_id, module.clone()).ok()?;
+
+    let definition = index.all_definitions().find(|(_, def)| {
+        matches!(def.kind, DefinitionKind::Function(_)) && contains(def.name_span, offset)
+    });
+    if let Some((_, def)) = definition {
+        return Some(FunctionRef {
+            module,
+            name: def.name.clone(),
+        });
+    }
+
+    crate_call_graph(db, crate_id)
+        .calls()
+        .iter()
+        .find(|call| call.caller.module == module && contains(call.span, offset))
+        .map(|call| call.callee.clone())
+}
+
+/// Builds the call hierarchy item of a function
+pub fn call_hierarchy_item(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    function: &FunctionRef,
+) -> Option<CallHierarchyItem> {
+    let (file, def) = function_definition(db, crate_id, function)?;
+    let source = file.text(db);
+    let kind = if function.name.contains("::") {
+        SymbolKind::METHOD
+    } else {
+        SymbolKind::FUNCTION
+    };
+
+    Some(CallHierarchyItem {
+        name: function.name.clone(),
+        kind,
+        tags: None,
+        detail: Some(function.module.clone()),
+        uri: get_uri_from_path_str(file.file_path(db)).ok()?,
+        range: range(source, def.full_span),
+        selection_range: range(source, def.name_span),
+        data: serde_json::to_value(ItemData {
+            module: function.module.clone(),
+            name: function.name.clone(),
+        })
+        .ok(),
+    })
+}
+
+/// Reads the function of a call hierarchy item built by [`call_hierarchy_item`]
+pub fn item_function(item: &CallHierarchyItem) -> Option<FunctionRef> {
+    let data: ItemData = serde_json::from_value(item.data.clone()?).ok()?;
+    Some(FunctionRef {
+        module: data.module,
+        name: data.name,
+    })
+}
+
+/// Calls made to `function`, grouped by caller
+pub fn incoming_calls(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    function: &FunctionRef,
+) -> Vec<CallHierarchyIncomingCall> {
+    let graph = crate_call_graph(db, crate_id);
+    group_calls(db, crate_id, graph.incoming_calls(function), |call| {
+        &call.caller
+    })
+    .into_iter()
+    .map(|(from, from_ranges)| CallHierarchyIncomingCall { from, from_ranges })
+    .collect()
+}
+
+/// Calls made by `function`, grouped by callee
+pub fn outgoing_calls(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    function: &FunctionRef,
+) -> Vec<CallHierarchyOutgoingCall> {
+    let graph = crate_call_graph(db, crate_id);
+    group_calls(db, crate_id, graph.outgoing_calls(function), |call| {
+        &call.callee
+    })
+    .into_iter()
+    .map(|(to, from_ranges)| CallHierarchyOutgoingCall { to, from_ranges })
+    .collect()
+}
+
+/// Groups calls by the function selected by `key`, with the ranges of the calls in the module
+/// of their caller
+fn group_calls<'a>(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    calls: impl Iterator<Item = &'a CallSite>,
+    key: impl Fn(&'a CallSite) -> &'a FunctionRef,
+) -> Vec<(CallHierarchyItem, Vec<Range>)> {
+    let mut grouped: BTreeMap<&FunctionRef, Vec<Range>> = BTreeMap::new();
+    for call in calls {
+        let Some(file) = crate_id.modules(db).get(&call.caller.module) else {
+            continue;
+        };
+        grouped
+            .entry(key(call))
+            .or_default()
+            .push(range(file.text(db), call.span));
+    }
+
+    grouped
+        .into_iter()
+        .filter_map(|(function, ranges)| {
+            Some((call_hierarchy_item(db, crate_id, function)?, ranges))
+        })
+        .collect()
+}
+
+/// Finds the definition of a function in the root scope of its module
+fn function_definition(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    function: &FunctionRef,
+) -> Option<(File, Definition)> {
+    let file = *crate_id.modules(db).get(&function.module)?;
+    let index = module_semantic_index(db, crate_id, function.module.clone()).ok()?;
+    let def_idx = index.latest_definition_index_by_name(index.root_scope()?, &function.name)?;
+    let def = index.definition(def_idx)?;
+    matches!(def.kind, DefinitionKind::Function(_)).then(|| (file, def.clone()))
+}
+
+const fn contains(span: SimpleSpan<usize>, offset: usize) -> bool {
+    span.start <= offset && offset <= span.end
+}
+
+fn range(source: &str, span: SimpleSpan<usize>) -> Range {
+    Range {
+        start: offset_to_position(source, span.start),
+        end: offset_to_position(source, span.end),
+    }
+}
@@ -0,0 +1,256 @@
+//! Context-aware completion
+//!
+//! The completion context is determined from the source text before the cursor, as the code
+//! being typed usually does not parse: after `receiver.` the fields of the receiver type are
+//! suggested, in a `use` path the items of the imported module, and elsewhere the symbols in
+//! scope, innermost first, along with the keywords valid at that position.
+
+use cairo_m_compiler_semantic::SemanticDb;
+use cairo_m_compiler_semantic::types::{TypeData, TypeId};
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, Documentation, MarkupContent, MarkupKind,
+};
+
+/// Keywords starting a statement or an item
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "if", "else", "while", "loop", "for", "break", "continue", "return", "let", "local", "const",
+    "fn", "struct", "impl", "use",
+];
+
+/// Keywords valid inside an expression
+const EXPRESSION_KEYWORDS: &[&str] = &["true", "false"];
+
+/// Kind of completion expected at the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompletionContext {
+    /// After `receiver.`, with the path of the receiver, e.g. `["p", "pos"]` for `p.pos.`
+    FieldAccess {
+        receiver: Vec<String>,
+        /// Byte offset of the start of the receiver
+        receiver_start: usize,
+    },
+    /// In the path of a `use` statement, with the module typed so far, if any
+    UsePath { module: Option<String> },
+    /// At the start of a statement
+    Statement,
+    /// Inside an expression
+    Expression,
+}
+
+const fn is_ident_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
+/// Determines the completion context at `offset`
+///
+/// The identifier being typed at the cursor, if any, is not part of the context.
+pub fn completion_context(source: &str, offset: usize) -> CompletionContext {
+    let Some(before) = source.get(..offset) else {
+        return CompletionContext::Expression;
+    };
+    let head = before.trim_end_matches(is_ident_char);
+
+    if head.ends_with('.') {
+        let mut receiver = Vec::new();
+        let mut rest = head;
+        while let Some(prefix) = rest.strip_suffix('.') {
+            let segment_start = prefix.trim_end_matches(is_ident_char).len();
+            let segment = &prefix[segment_start..];
+            if segment.is_empty() {
+                break;
+            }
+            receiver.push(segment.to_string());
+            rest = &prefix[..segment_start];
+        }
+        receiver.reverse();
+
+        return match receiver.first() {
+            Some(root) if !root.starts_with(|ch: char| ch.is_ascii_digit()) => {
+                CompletionContext::FieldAccess {
+                    receiver,
+                    receiver_start: rest.len(),
+                }
+            }
+            _ => CompletionContext::Expression,
+        };
+    }
+
+    let line = head.rsplit('\n').next().unwrap_or_default().trim_start();
+    if let Some(path) = line.strip_prefix("use ") {
+        let path = path.split('{').next().unwrap_or_default().trim();
+        let module = path
+            .strip_suffix("::")
+            .filter(|module| !module.is_empty())
+            .map(str::to_string);
+        return CompletionContext::UsePath { module };
+    }
+
+    match head.trim_end().chars().next_back() {
+        None | Some(';' | '{' | '}') => CompletionContext::Statement,
+        Some(_) => CompletionContext::Expression,
+    }
+}
+
+/// Keyword completions valid in the given context, ranked after symbols
+pub fn keyword_completions(context: &CompletionContext) -> Vec<CompletionItem> {
+    let keywords: Vec<&str> = match context {
+        CompletionContext::Statement => STATEMENT_KEYWORDS
+            .iter()
+            .chain(EXPRESSION_KEYWORDS)
+            .copied()
+            .collect(),
+        CompletionContext::Expression => EXPRESSION_KEYWORDS.to_vec(),
+        CompletionContext::FieldAccess { .. } | CompletionContext::UsePath { .. } => vec![],
+    };
+
+    keywords
+        .into_iter()
+        .map(|keyword| CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            sort_text: Some(format!("1_{keyword}")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Wraps the doc comment of a definition as the markdown documentation of its completion item
+pub fn markdown_documentation(doc: impl Into<String>) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value: doc.into(),
+    })
+}
+
+/// Sort key ranking a symbol by the distance between its scope and the cursor scope
+pub fn symbol_sort_text(scope_depth: usize, name: &str) -> String {
+    format!("0_{scope_depth:03}_{name}")
+}
+
+/// Type of the field `name` of a struct or tuple type
+pub fn field_type<'db>(
+    db: &'db dyn SemanticDb,
+    type_id: TypeId<'db>,
+    name: &str,
+) -> Option<TypeId<'db>> {
+    match type_id.data(db) {
+        TypeData::Struct(struct_id) => struct_id
+            .fields(db)
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, field_type)| *field_type),
+        TypeData::Tuple(types) => types.get(name.parse::<usize>().ok()?).copied(),
+        _ => None,
+    }
+}
+
+/// Field completions of a struct or tuple type
+pub fn field_completions<'db>(
+    db: &'db dyn SemanticDb,
+    type_id: TypeId<'db>,
+) -> Vec<CompletionItem> {
+    let fields: Vec<(String, TypeId<'db>)> = match type_id.data(db) {
+        TypeData::Struct(struct_id) => struct_id.fields(db).clone(),
+        TypeData::Tuple(types) => types
+            .iter()
+            .enumerate()
+            .map(|(i, element_type)| (i.to_string(), *element_type))
+            .collect(),
+        _ => vec![],
+    };
+
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, field_type))| CompletionItem {
+            label: name,
+            kind: Some(CompletionItemKind::FIELD),
+            detail: Some(TypeId::format_type(db, field_type)),
+            // Keep declaration order
+            sort_text: Some(format!("{i:03}")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_at_caret(source: &str) -> CompletionContext {
+        let offset = source.find("<caret>").unwrap();
+        completion_context(&source.replace("<caret>", ""), offset)
+    }
+
+    #[test]
+    fn test_field_access_context() {
+        assert_eq!(
+            context_at_caret("    let x = p.<caret>"),
+            CompletionContext::FieldAccess {
+                receiver: vec!["p".to_string()],
+                receiver_start: 12,
+            }
+        );
+        assert_eq!(
+            context_at_caret("    return a.pos.x<caret>"),
+            CompletionContext::FieldAccess {
+                receiver: vec!["a".to_string(), "pos".to_string()],
+                receiver_start: 11,
+            }
+        );
+        assert_eq!(
+            context_at_caret("let x = 1.<caret>"),
+            CompletionContext::Expression
+        );
+    }
+
+    #[test]
+    fn test_use_path_context() {
+        assert_eq!(
+            context_at_caret("use ma<caret>"),
+            CompletionContext::UsePath { module: None }
+        );
+        assert_eq!(
+            context_at_caret("use math::ad<caret>"),
+            CompletionContext::UsePath {
+                module: Some("math".to_string())
+            }
+        );
+        assert_eq!(
+            context_at_caret("use math::{add, <caret>"),
+            CompletionContext::UsePath {
+                module: Some("math".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_statement_and_expression_context() {
+        assert_eq!(
+            context_at_caret("fn main() {\n    re<caret>"),
+            CompletionContext::Statement
+        );
+        assert_eq!(
+            context_at_caret("    let x = 1;\n    <caret>"),
+            CompletionContext::Statement
+        );
+        assert_eq!(context_at_caret("<caret>"), CompletionContext::Statement);
+        assert_eq!(
+            context_at_caret("    let x = y + <caret>"),
+            CompletionContext::Expression
+        );
+    }
+
+    #[test]
+    fn test_keyword_completions() {
+        let labels = |context| {
+            keyword_completions(&context)
+                .into_iter()
+                .map(|item| item.label)
+                .collect::<Vec<_>>()
+        };
+        assert!(labels(CompletionContext::Statement).contains(&"let".to_string()));
+        assert_eq!(labels(CompletionContext::Expression), vec!["true", "false"]);
+        assert!(labels(CompletionContext::UsePath { module: None }).is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+//! User settings of the language server
+//!
+//! Settings are read from the `initializationOptions` of the `initialize` request, and updated
+//! by `workspace/didChangeConfiguration` notifications. Both accept the settings either at the
+//! top level or in a `cairo-m` section, as clients usually scope them by extension, and in
+//! snake case or in the camel case of editor settings.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use cairo_m_compiler::{LintLevel, resolve_lint_levels};
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+use cairo_m_formatter::FormatterConfig;
+use cairo_m_formatter::config::NewlineStyle;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::project::model::ProjectResourceLimits;
+
+/// Name of the section holding the settings of the language server
+pub const SETTINGS_SECTION: &str = "cairo-m";
+
+/// Other accepted names of the settings section
+const SETTINGS_SECTION_ALIASES: &[&str] = &["cairo_m"];
+
+/// User settings of the language server
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// TOML file with the formatter options, relative to the workspace root if not absolute
+    #[serde(alias = "formatterConfigPath")]
+    pub formatter_config_path: Option<PathBuf>,
+    /// Lint levels, keyed by diagnostic code name or `warnings`, as the `[lints]` table of a
+    /// manifest, over which they take precedence
+    pub lints: BTreeMap<String, LintLevel>,
+    /// Size in bytes above which files are not loaded for analysis
+    #[serde(alias = "maxFileSize")]
+    pub max_file_size: usize,
+    /// Which inlay hints are shown
+    #[serde(alias = "inlayHints")]
+    pub inlay_hints: InlayHintsConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            formatter_config_path: None,
+            lints: BTreeMap::new(),
+            max_file_size: ProjectResourceLimits::default().max_file_size,
+            inlay_hints: InlayHintsConfig::default(),
+        }
+    }
+}
+
+/// Toggles of the inlay hints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct InlayHintsConfig {
+    /// Show the inferred type of variables declared without a type annotation
+    #[serde(alias = "typeHints")]
+    pub type_hints: bool,
+    /// Show the parameter names of call arguments
+    #[serde(alias = "parameterHints")]
+    pub parameter_hints: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+        }
+    }
+}
+
+/// Formatter options of a formatter configuration file, unset ones keeping their default
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormatterFile {
+    max_width: Option<usize>,
+    indent_width: Option<u32>,
+    trailing_comma: Option<bool>,
+    newline_style: Option<NewlineStyleName>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NewlineStyleName {
+    Auto,
+    Unix,
+    Windows,
+}
+
+impl From<NewlineStyleName> for NewlineStyle {
+    fn from(style: NewlineStyleName) -> Self {
+        match style {
+            NewlineStyleName::Auto => Self::Auto,
+            NewlineStyleName::Unix => Self::Unix,
+            NewlineStyleName::Windows => Self::Windows,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the settings from an `initializationOptions` or `settings` value
+    ///
+    /// Settings missing from the value keep their default.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error message if a setting has an invalid value.
+    pub fn from_settings(settings: &Value) -> Result<Self, String> {
+        let settings = std::iter::once(SETTINGS_SECTION)
+            .chain(SETTINGS_SECTION_ALIASES.iter().copied())
+            .find_map(|section| settings.get(section))
+            .unwrap_or(settings);
+        if settings.is_null() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_json::from_value(settings.clone())
+            .map_err(|e| format!("Invalid settings: {e}"))?;
+        config.lint_levels()?;
+        Ok(config)
+    }
+
+    /// Lint levels per diagnostic code set by the `lints` setting
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error message if a lint is neither `warnings` nor a diagnostic code name.
+    pub fn lint_levels(&self) -> Result<BTreeMap<DiagnosticCode, LintLevel>, String> {
+        resolve_lint_levels(&self.lints, BTreeMap::new()).map_err(|e| e.to_string())
+    }
+
+    /// Formatter options, read from the formatter configuration file if set
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error message if the file can't be read or holds invalid options.
+    pub fn formatter_config(
+        &self,
+        workspace_root: Option<&Path>,
+    ) -> Result<FormatterConfig, String> {
+        let mut config = FormatterConfig::default();
+        let Some(path) = &self.formatter_config_path else {
+            return Ok(config);
+        };
+
+        let path = match workspace_root {
+            Some(root) if path.is_relative() => root.join(path),
+            _ => path.clone(),
+        };
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let file: FormatterFile = toml::from_str(&content)
+            .map_err(|e| format!("Invalid formatter configuration {}: {e}", path.display()))?;
+
+        if let Some(max_width) = file.max_width {
+            config.max_width = max_width;
+        }
+        if let Some(indent_width) = file.indent_width {
+            config.indent_width = indent_width;
+        }
+        if let Some(trailing_comma) = file.trailing_comma {
+            config.trailing_comma = trailing_comma;
+        }
+        if let Some(newline_style) = file.newline_style {
+            config.newline_style = newline_style.into();
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_settings_parsing() {
+        let config = Config::from_settings(&json!({
+            "cairo_m": {
+                "max_file_size": 1024,
+                "lints": { "unused_variable": "allow" },
+                "inlay_hints": { "parameter_hints": false }
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.max_file_size, 1024);
+        assert_eq!(
+            config.lint_levels().unwrap(),
+            BTreeMap::from([(DiagnosticCode::UnusedVariable, LintLevel::Allow)])
+        );
+        assert!(config.inlay_hints.type_hints);
+        assert!(!config.inlay_hints.parameter_hints);
+        assert_eq!(config.formatter_config_path, None);
+
+        // Editor settings are in camel case
+        let config = Config::from_settings(&json!({
+            "cairo-m": {
+                "languageServer": { "path": "" },
+                "maxFileSize": 2048,
+                "inlayHints": { "typeHints": false }
+            }
+        }))
+        .unwrap();
+        assert_eq!(config.max_file_size, 2048);
+        assert!(!config.inlay_hints.type_hints);
+
+        // Settings may also be passed without a section, next to unrelated options
+        let config =
+            Config::from_settings(&json!({ "debounce_ms": 0, "max_file_size": 1 })).unwrap();
+        assert_eq!(config.max_file_size, 1);
+        assert_eq!(
+            Config::from_settings(&Value::Null).unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        assert!(Config::from_settings(&json!({ "max_file_size": "large" })).is_err());
+        assert!(Config::from_settings(&json!({ "lints": { "unknown_lint": "deny" } })).is_err());
+    }
+
+    #[test]
+    fn test_formatter_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("fmt.toml"),
+            "max_width = 80\nindent_width = 2\nnewline_style = \"unix\"\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            formatter_config_path: Some(PathBuf::from("fmt.toml")),
+            ..Default::default()
+        };
+        let formatter = config.formatter_config(Some(dir.path())).unwrap();
+        assert_eq!(formatter.max_width, 80);
+        assert_eq!(formatter.indent_width, 2);
+        assert_eq!(formatter.newline_style, NewlineStyle::Unix);
+        assert!(!formatter.trailing_comma);
+
+        std::fs::write(dir.path().join("fmt.toml"), "tab_width = 2\n").unwrap();
+        assert!(config.formatter_config(Some(dir.path())).is_err());
+    }
+}
@@ -1,16 +1,20 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
 
+use cairo_m_compiler::{LintLevel, apply_lint_levels, resolve_lint_levels};
+use cairo_m_compiler_diagnostics::{DiagnosticCode, DiagnosticCollection};
 use cairo_m_compiler_semantic::delta_diagnostics::DeltaDiagnosticsTracker;
+use cairo_m_project::discover_project;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, Url};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, Url};
 use tracing::{debug, error};
 
+use crate::config::Config;
 use crate::db::{AnalysisDatabase, ProjectCrate, ProjectCrateExt};
-use crate::diagnostics::state::ProjectDiagnostics;
+use crate::diagnostics::state::{DocumentVersions, ProjectDiagnostics};
 use crate::project::ProjectModel;
 use crate::utils::{get_path_from_diagnostic, get_uri_from_path_str};
 
@@ -47,10 +51,19 @@ impl DiagnosticsController {
     /// Note: This implementation uses delta-based diagnostics tracking via DeltaDiagnosticsTracker
     /// rather than full recomputation. The delta system leverages Salsa's revision tracking to
     /// only recompute diagnostics for changed modules, providing significant performance improvements.
+    ///
+    /// Validation runs on a snapshot of the database, so that edits do not wait for it: an edit
+    /// cancels the in-flight validation, and results computed for an outdated version of the
+    /// changed document (see [`DocumentVersions`]) are dropped instead of being published.
+    ///
+    /// The lint levels of the user settings and of the project manifest are applied to the
+    /// results, read from `config` on each computation.
     pub fn new(
         db: Arc<Mutex<AnalysisDatabase>>,
         diagnostics_state: Arc<ProjectDiagnostics>,
         project_model: Arc<ProjectModel>,
+        document_versions: Arc<DocumentVersions>,
+        config: Arc<RwLock<Config>>,
         response_sender: UnboundedSender<DiagnosticsResponse>,
     ) -> Self {
         let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
@@ -65,16 +78,25 @@ impl DiagnosticsController {
                 debug!("Received request: {:?}", request);
                 match request {
                     DiagnosticsRequest::FileChanged { uri, version } => {
-                        Self::compute_file_diagnostics_delta(
-                            &db,
-                            &diagnostics_state,
-                            &project_model,
-                            uri,
-                            version,
-                            &response_sender,
-                            &mut delta_tracker,
-                        )
-                        .await;
+                        if document_versions.is_current(&uri, version) {
+                            Self::compute_file_diagnostics_delta(
+                                &db,
+                                &diagnostics_state,
+                                &project_model,
+                                &document_versions,
+                                &config,
+                                uri,
+                                version,
+                                &response_sender,
+                                &mut delta_tracker,
+                            )
+                            .await;
+                        } else {
+                            debug!(
+                                "Skipping diagnostics for outdated version {:?} of {}",
+                                version, uri
+                            );
+                        }
                         // Signal completion of work
                         if let Err(e) = response_sender.send(DiagnosticsResponse {
                             uri: Url::parse("internal://analysis-finished").unwrap(),
@@ -91,6 +113,8 @@ impl DiagnosticsController {
                         Self::compute_project_diagnostics_delta(
                             &db,
                             &diagnostics_state,
+                            &document_versions,
+                            &config,
                             project_crate,
                             &response_sender,
                             None,
@@ -132,10 +156,13 @@ impl DiagnosticsController {
     }
 
     /// Compute diagnostics for a single file using delta tracking
+    #[allow(clippy::too_many_arguments)]
     async fn compute_file_diagnostics_delta(
         db: &Arc<Mutex<AnalysisDatabase>>,
         diagnostics_state: &Arc<ProjectDiagnostics>,
         project_model: &Arc<ProjectModel>,
+        document_versions: &DocumentVersions,
+        config: &RwLock<Config>,
         uri: Url,
         version: Option<i32>,
         response_sender: &UnboundedSender<DiagnosticsResponse>,
@@ -147,9 +174,11 @@ impl DiagnosticsController {
             Self::compute_project_diagnostics_delta(
                 db,
                 diagnostics_state,
+                document_versions,
+                config,
                 project_crate,
                 response_sender,
-                version,
+                Some((&uri, version)),
                 delta_tracker,
             )
             .await;
@@ -172,20 +201,24 @@ impl DiagnosticsController {
     }
 
     /// Compute diagnostics for an entire project using delta tracking (async wrapper)
+    ///
+    /// `origin` is the document whose change triggered the computation, with its version.
+    /// Nothing is published if the computation is cancelled by an edit, or if the document
+    /// has changed since.
+    #[allow(clippy::too_many_arguments)]
     async fn compute_project_diagnostics_delta(
         db: &Arc<Mutex<AnalysisDatabase>>,
         diagnostics_state: &Arc<ProjectDiagnostics>,
+        document_versions: &DocumentVersions,
+        config: &RwLock<Config>,
         project_crate: ProjectCrate,
         response_sender: &UnboundedSender<DiagnosticsResponse>,
-        version: Option<i32>,
+        origin: Option<(&Url, Option<i32>)>,
         delta_tracker: &mut DeltaDiagnosticsTracker,
     ) {
-        let diagnostics_state_clone = Arc::clone(diagnostics_state);
-        let response_sender_clone = response_sender.clone();
-
-        // We need to move the delta tracker computation to a blocking task
-        // Since we can't move the mutable reference, we'll do the computation here
-        let result = {
+        // Validate on a snapshot of the database, releasing the lock right away. Mutating the
+        // database cancels the queries running on the snapshot instead of waiting for them.
+        let snapshot = {
             let db_guard = match db.lock() {
                 Ok(guard) => guard,
                 Err(poisoned) => {
@@ -193,29 +226,69 @@ impl DiagnosticsController {
                     poisoned.into_inner()
                 }
             };
+            db_guard.clone()
+        };
+        let settings_lint_levels = config
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .lint_levels()
+            .unwrap_or_default();
+
+        // The tracker is moved into the blocking task and handed back once it completes
+        let mut tracker = std::mem::take(delta_tracker);
+        let result = tokio::task::spawn_blocking(move || {
+            let result = salsa::Cancelled::catch(std::panic::AssertUnwindSafe(|| {
+                let semantic_crate = project_crate.to_semantic_crate(&snapshot);
+
+                // Use delta diagnostics tracker to get only changed module diagnostics
+                let diagnostics_collection =
+                    tracker.get_project_diagnostics(&snapshot, semantic_crate);
+                let lint_levels =
+                    Self::lint_levels(project_crate.root_dir(&snapshot), settings_lint_levels);
+                let diagnostics_collection = DiagnosticCollection::new(apply_lint_levels(
+                    diagnostics_collection.all().to_vec(),
+                    &lint_levels,
+                ));
+
+                // Get file contents for LSP conversion
+                let files = project_crate.files(&snapshot);
+                let mut files_with_content = HashMap::new();
+                for (path, source_file) in files {
+                    let content = source_file.text(&snapshot).to_string();
+                    files_with_content.insert(path.clone(), content);
+                }
 
-            let semantic_crate = project_crate.to_semantic_crate(&*db_guard);
-
-            // Use delta diagnostics tracker to get only changed module diagnostics
-            let diagnostics_collection =
-                delta_tracker.get_project_diagnostics(&*db_guard, semantic_crate);
+                (files_with_content, diagnostics_collection)
+            }));
+            (tracker, result)
+        })
+        .await;
 
-            // Get file contents for LSP conversion
-            let files = project_crate.files(&*db_guard);
-            let mut files_with_content = HashMap::new();
-            for (path, source_file) in files {
-                let content = source_file.text(&*db_guard).to_string();
-                files_with_content.insert(path.clone(), content);
+        let result = match result {
+            Ok((tracker, result)) => {
+                *delta_tracker = tracker;
+                result
             }
-            drop(db_guard);
-
-            (files_with_content, diagnostics_collection)
+            Err(e) => {
+                error!(
+                    "Failed to spawn blocking task for delta diagnostics: {:?}",
+                    e
+                );
+                return;
+            }
+        };
+        let Ok((files_with_content, diagnostics_collection)) = result else {
+            debug!("Diagnostics computation cancelled by a newer change");
+            return;
         };
 
         // Process the results with unconstrained wrapper to prevent cancellation
         let diagnostics_result = tokio::task::unconstrained(async {
             tokio::task::spawn_blocking(move || {
-                Self::compute_project_diagnostics_delta_sync(result.0, result.1)
+                Self::compute_project_diagnostics_delta_sync(
+                    files_with_content,
+                    diagnostics_collection,
+                )
             })
             .await
             .unwrap_or_else(|e| {
@@ -228,22 +301,51 @@ impl DiagnosticsController {
         })
         .await;
 
+        if let Some((uri, version)) = origin {
+            if !document_versions.is_current(uri, version) {
+                debug!(
+                    "Dropping diagnostics computed for outdated version {:?} of {}",
+                    version, uri
+                );
+                return;
+            }
+        }
+
         // Handle the result and publish diagnostics
         if let Ok(diagnostics_by_file) = diagnostics_result {
             Self::publish_diagnostics(
                 diagnostics_by_file,
-                &diagnostics_state_clone,
-                &response_sender_clone,
-                version,
+                diagnostics_state,
+                response_sender,
+                origin.and_then(|(_, version)| version),
             )
             .await;
         }
     }
 
+    /// Lint levels of a project: those of its manifest, overridden by the user settings
+    fn lint_levels(
+        root_dir: &Path,
+        settings_lint_levels: BTreeMap<DiagnosticCode, LintLevel>,
+    ) -> BTreeMap<DiagnosticCode, LintLevel> {
+        let manifest_lints = discover_project(root_dir)
+            .ok()
+            .flatten()
+            .map(|project| project.config.lints)
+            .unwrap_or_default();
+        match resolve_lint_levels(&manifest_lints, settings_lint_levels.clone()) {
+            Ok(lint_levels) => lint_levels,
+            Err(e) => {
+                debug!("Ignoring the lints of the manifest: {}", e);
+                settings_lint_levels
+            }
+        }
+    }
+
     /// Process delta diagnostics results (synchronous version)
     fn compute_project_diagnostics_delta_sync(
         files_with_content: HashMap<PathBuf, String>,
-        diagnostics_collection: cairo_m_compiler_diagnostics::DiagnosticCollection,
+        diagnostics_collection: DiagnosticCollection,
     ) -> Result<HashMap<Url, Vec<Diagnostic>>, String> {
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             // Convert delta diagnostics to LSP format
@@ -401,8 +503,8 @@ pub fn convert_cairo_diagnostic(
     Diagnostic {
         range,
         severity: Some(severity),
-        code: None,
         code_description: None,
+        code: Some(NumberOrString::Number(u32::from(diag.code) as i32)),
         source: Some("cairo-m".to_string()),
         message: diag.message.clone(),
         related_information: None,
@@ -411,8 +513,14 @@ pub fn convert_cairo_diagnostic(
     }
 }
 
+/// Returns true if an LSP diagnostic was produced by the parser rather than by semantic
+/// validation, parse-related codes being below 1000
+pub fn is_parse_diagnostic(diagnostic: &Diagnostic) -> bool {
+    matches!(diagnostic.code, Some(NumberOrString::Number(code)) if code < 1000)
+}
+
 /// Convert byte offset to LSP Position
-fn offset_to_position(source: &str, offset: usize) -> Position {
+pub(crate) fn offset_to_position(source: &str, offset: usize) -> Position {
     let mut line = 0;
     let mut character = 0;
 
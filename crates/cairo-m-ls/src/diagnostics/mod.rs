@@ -1,5 +1,5 @@
 pub mod controller;
 pub mod state;
 
-pub use controller::{DiagnosticsController, DiagnosticsRequest};
-pub use state::ProjectDiagnostics;
+pub use controller::{DiagnosticsController, DiagnosticsRequest, is_parse_diagnostic};
+pub use state::{DocumentVersions, FileDiagnostics, ProjectDiagnostics};
@@ -1,13 +1,29 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+use dashmap::DashMap;
 use tokio::sync::RwLock;
 use tower_lsp::lsp_types::{Diagnostic, Url};
 use tracing::debug;
 
+/// Diagnostics of a file, with the id of the result they were reported in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiagnostics {
+    /// Identifies this set of diagnostics, so that clients pulling them again can be told they
+    /// are unchanged
+    pub result_id: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 /// Thread-safe storage for project-wide diagnostics
+///
+/// Files keep their entry once validated, even without diagnostics, so that clients pulling
+/// workspace diagnostics learn that their previous errors are gone.
 pub struct ProjectDiagnostics {
     /// Map from file URL to diagnostics
-    diagnostics: RwLock<HashMap<Url, Vec<Diagnostic>>>,
+    diagnostics: RwLock<HashMap<Url, FileDiagnostics>>,
+    /// Next result id to hand out
+    next_result_id: AtomicU64,
 }
 
 impl ProjectDiagnostics {
@@ -15,19 +31,58 @@ impl ProjectDiagnostics {
     pub fn new() -> Self {
         Self {
             diagnostics: RwLock::new(HashMap::new()),
+            next_result_id: AtomicU64::new(0),
         }
     }
 
     /// Set diagnostics for a specific file
+    ///
+    /// The result id of the file only changes if its diagnostics do.
     pub async fn set_diagnostics(&self, uri: &Url, diagnostics: Vec<Diagnostic>) {
         debug!("Setting {} diagnostics for {}", diagnostics.len(), uri);
 
         let mut map = self.diagnostics.write().await;
-        if diagnostics.is_empty() {
-            map.remove(uri);
-        } else {
-            map.insert(uri.clone(), diagnostics);
+        if map
+            .get(uri)
+            .is_some_and(|entry| entry.diagnostics == diagnostics)
+        {
+            return;
         }
+        let result_id = self
+            .next_result_id
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string();
+        map.insert(
+            uri.clone(),
+            FileDiagnostics {
+                result_id,
+                diagnostics,
+            },
+        );
+    }
+
+    /// Get the diagnostics last stored for a specific file
+    pub async fn get_diagnostics(&self, uri: &Url) -> Vec<Diagnostic> {
+        let map = self.diagnostics.read().await;
+        map.get(uri)
+            .map(|entry| entry.diagnostics.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the diagnostics last stored for a specific file, with their result id
+    ///
+    /// Returns `None` if the file has not been validated yet.
+    pub async fn get_file_diagnostics(&self, uri: &Url) -> Option<FileDiagnostics> {
+        let map = self.diagnostics.read().await;
+        map.get(uri).cloned()
+    }
+
+    /// Get the diagnostics last stored for every validated file
+    pub async fn all_file_diagnostics(&self) -> Vec<(Url, FileDiagnostics)> {
+        let map = self.diagnostics.read().await;
+        map.iter()
+            .map(|(uri, entry)| (uri.clone(), entry.clone()))
+            .collect()
     }
 
     /// Clear diagnostics for all files in a project
@@ -49,3 +104,41 @@ impl Default for ProjectDiagnostics {
         Self::new()
     }
 }
+
+/// Latest known version of each open document
+///
+/// Background diagnostics are keyed by the version of the document that triggered them, so
+/// that results computed for an outdated version are dropped instead of being published.
+pub struct DocumentVersions {
+    versions: DashMap<Url, i32>,
+}
+
+impl DocumentVersions {
+    /// Create a new DocumentVersions instance
+    pub fn new() -> Self {
+        Self {
+            versions: DashMap::new(),
+        }
+    }
+
+    /// Record the latest version of a document
+    pub fn record(&self, uri: &Url, version: i32) {
+        self.versions.insert(uri.clone(), version);
+    }
+
+    /// Returns true if `version` is still the latest version of the document
+    ///
+    /// Requests without a version, or for documents that are not tracked, are always current.
+    pub fn is_current(&self, uri: &Url, version: Option<i32>) -> bool {
+        match (version, self.versions.get(uri)) {
+            (Some(version), Some(latest)) => version >= *latest,
+            _ => true,
+        }
+    }
+}
+
+impl Default for DocumentVersions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
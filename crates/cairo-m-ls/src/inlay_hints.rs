@@ -0,0 +1,110 @@
+//! Inlay hints for inferred variable types and call argument names
+//!
+//! Hints are derived from the semantic index of the module: variables declared without a type
+//! annotation are followed by their inferred type, and the arguments of calls to functions
+//! resolved in scope are preceded by the name of their parameter.
+
+use std::ops::Range;
+
+use cairo_m_compiler_parser::parser::Expression;
+use cairo_m_compiler_semantic::type_resolution::definition_semantic_type;
+use cairo_m_compiler_semantic::{
+    Crate, DefinitionId, DefinitionKind, File, SemanticDb, SemanticIndex, TypeId,
+};
+use tower_lsp::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel};
+
+use crate::config::InlayHintsConfig;
+use crate::diagnostics::controller::offset_to_position;
+
+/// Computes the inlay hints of a module within the byte range `range` of its source
+pub fn inlay_hints(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    index: &SemanticIndex,
+    source: &str,
+    range: Range<usize>,
+    config: InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let mut hints = Vec::new();
+
+    if config.type_hints {
+        for (def_idx, def) in index.all_definitions() {
+            let annotated = match &def.kind {
+                DefinitionKind::Let(let_def) => let_def.explicit_type_ast.is_some(),
+                DefinitionKind::LoopVariable(_) => false,
+                _ => continue,
+            };
+            if annotated || !range.contains(&def.name_span.end) {
+                continue;
+            }
+
+            let def_id = DefinitionId::new(db, file, def_idx);
+            let type_id = definition_semantic_type(db, crate_id, def_id);
+            if !type_id.data(db).is_concrete() {
+                continue;
+            }
+            hints.push(hint(
+                source,
+                def.name_span.end,
+                format!(": {}", TypeId::format_type(db, type_id)),
+                InlayHintKind::TYPE,
+            ));
+        }
+    }
+
+    if config.parameter_hints {
+        for (_, expr) in index.all_expressions() {
+            let Expression::FunctionCall { callee, args } = &expr.ast_node else {
+                continue;
+            };
+            let Expression::Identifier(name) = callee.value() else {
+                continue;
+            };
+            let Some((_, def, _)) = index.resolve_name_with_imports_at_position(
+                db,
+                crate_id,
+                file,
+                name.value(),
+                expr.scope_id,
+                name.span(),
+            ) else {
+                continue;
+            };
+            let DefinitionKind::Function(function) = &def.kind else {
+                continue;
+            };
+
+            for (arg, (param, _)) in args.iter().zip(&function.params_ast) {
+                // Arguments named after their parameter need no hint
+                if matches!(arg.value(), Expression::Identifier(ident) if ident.value() == param)
+                    || !range.contains(&arg.span().start)
+                {
+                    continue;
+                }
+                hints.push(hint(
+                    source,
+                    arg.span().start,
+                    format!("{param}:"),
+                    InlayHintKind::PARAMETER,
+                ));
+            }
+        }
+    }
+
+    hints.sort_by_key(|hint| (hint.position.line, hint.position.character));
+    hints
+}
+
+fn hint(source: &str, offset: usize, label: String, kind: InlayHintKind) -> InlayHint {
+    InlayHint {
+        position: offset_to_position(source, offset),
+        label: InlayHintLabel::String(label),
+        kind: Some(kind),
+        text_edits: None,
+        tooltip: None,
+        padding_left: None,
+        padding_right: Some(kind == InlayHintKind::PARAMETER),
+        data: None,
+    }
+}
@@ -4,11 +4,17 @@
 //! Language server library public exports.
 
 // Re-export modules needed for testing
+pub mod call_hierarchy;
+pub mod completion;
+pub mod config;
 pub mod db;
 pub mod diagnostics;
+pub mod inlay_hints;
 pub mod lsp_ext;
 pub mod lsp_tracing;
 pub mod project;
+pub mod ranges;
+pub mod signature_help;
 pub mod utils;
 
 // Re-export the Backend struct for testing
@@ -0,0 +1,428 @@
+//! Folding and selection ranges
+//!
+//! Both are derived from the spans of the parsed AST: functions, struct bodies and blocks
+//! can be folded, as well as runs of line comments and of `use` statements, and the
+//! selection expands through the nodes enclosing the cursor, from the innermost expression
+//! up to the top-level item.
+
+use cairo_m_compiler_parser::parser::{
+    Expression, FunctionDef, ParsedModule, Spanned, Statement, TopLevelItem,
+};
+use chumsky::span::SimpleSpan;
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind, Range, SelectionRange};
+
+use crate::diagnostics::controller::offset_to_position;
+
+/// Builds a folding range spanning `span`, if it covers more than one line
+fn folding_range(
+    source: &str,
+    span: SimpleSpan<usize>,
+    kind: Option<FoldingRangeKind>,
+) -> Option<FoldingRange> {
+    let start = offset_to_position(source, span.start);
+    let end = offset_to_position(source, span.end);
+    (start.line < end.line).then_some(FoldingRange {
+        start_line: start.line,
+        start_character: None,
+        end_line: end.line,
+        end_character: None,
+        kind,
+        collapsed_text: None,
+    })
+}
+
+/// Folding ranges of a module: items, blocks, comment runs and import runs
+pub fn folding_ranges(source: &str, module: &ParsedModule) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+
+    // Consecutive `use` statements fold together
+    let mut imports: Option<SimpleSpan<usize>> = None;
+    for item in module.items() {
+        if let TopLevelItem::Use(use_stmt) = item {
+            let span = use_stmt.span();
+            imports = Some(imports.map_or(span, |imports| (imports.start..span.end).into()));
+            continue;
+        }
+        if let Some(span) = imports.take() {
+            ranges.extend(folding_range(source, span, Some(FoldingRangeKind::Imports)));
+        }
+
+        match item {
+            TopLevelItem::Function(function) => {
+                function_folding_ranges(source, function, &mut ranges);
+            }
+            TopLevelItem::Impl(impl_def) => {
+                ranges.extend(folding_range(source, impl_def.span(), None));
+                for method in &impl_def.value().methods {
+                    function_folding_ranges(source, method, &mut ranges);
+                }
+            }
+            TopLevelItem::Struct(struct_def) => {
+                ranges.extend(folding_range(source, struct_def.span(), None));
+            }
+            TopLevelItem::Const(_) | TopLevelItem::Use(_) => {}
+        }
+    }
+    if let Some(span) = imports {
+        ranges.extend(folding_range(source, span, Some(FoldingRangeKind::Imports)));
+    }
+
+    // Runs of line comments
+    let mut comment_start = None;
+    let mut offset = 0;
+    let mut previous_end = 0;
+    for line in source.split_inclusive('\n') {
+        let is_comment = line.trim_start().starts_with("//");
+        match (is_comment, comment_start) {
+            (true, None) => comment_start = Some(offset),
+            (false, Some(start)) => {
+                comment_start = None;
+                let span = (start..previous_end).into();
+                ranges.extend(folding_range(source, span, Some(FoldingRangeKind::Comment)));
+            }
+            _ => {}
+        }
+        previous_end = offset + line.trim_end().len();
+        offset += line.len();
+    }
+    if let Some(start) = comment_start {
+        let span = (start..previous_end).into();
+        ranges.extend(folding_range(source, span, Some(FoldingRangeKind::Comment)));
+    }
+
+    ranges.sort_by_key(|range| (range.start_line, range.end_line));
+    ranges
+}
+
+/// Collects the folding ranges of a function and of the blocks in its body
+fn function_folding_ranges(
+    source: &str,
+    function: &Spanned<FunctionDef>,
+    ranges: &mut Vec<FoldingRange>,
+) {
+    ranges.extend(folding_range(source, function.span(), None));
+    for statement in &function.value().body {
+        statement_folding_ranges(source, statement, ranges);
+    }
+}
+
+/// Collects the folding ranges of the blocks nested in a statement
+fn statement_folding_ranges(
+    source: &str,
+    statement: &Spanned<Statement>,
+    ranges: &mut Vec<FoldingRange>,
+) {
+    if let Statement::Block(statements) = statement.value() {
+        ranges.extend(folding_range(source, statement.span(), None));
+        for statement in statements {
+            statement_folding_ranges(source, statement, ranges);
+        }
+        return;
+    }
+    for child in statement_children(statement.value()) {
+        statement_folding_ranges(source, child, ranges);
+    }
+}
+
+/// Statements nested in a control flow statement
+fn statement_children(statement: &Statement) -> Vec<&Spanned<Statement>> {
+    match statement {
+        Statement::If {
+            then_block,
+            else_block,
+            ..
+        } => std::iter::once(then_block.as_ref())
+            .chain(else_block.as_deref())
+            .collect(),
+        Statement::Block(statements) => statements.iter().collect(),
+        Statement::Loop { body } | Statement::While { body, .. } => vec![body.as_ref()],
+        Statement::For {
+            init, step, body, ..
+        } => vec![init.as_ref(), step.as_ref(), body.as_ref()],
+        Statement::Let { .. }
+        | Statement::Const(_)
+        | Statement::Assignment { .. }
+        | Statement::CompoundAssignment { .. }
+        | Statement::Return { .. }
+        | Statement::Expression(_)
+        | Statement::Break
+        | Statement::Continue => vec![],
+    }
+}
+
+/// Expressions directly contained in a statement
+fn statement_expressions(statement: &Statement) -> Vec<&Spanned<Expression>> {
+    match statement {
+        Statement::Let { value, .. } => vec![value],
+        Statement::Const(const_def) => vec![&const_def.value],
+        Statement::Assignment { lhs, rhs } | Statement::CompoundAssignment { lhs, rhs, .. } => {
+            vec![lhs, rhs]
+        }
+        Statement::Return { value } => value.iter().collect(),
+        Statement::If { condition, .. } | Statement::While { condition, .. } => vec![condition],
+        Statement::For { condition, .. } => vec![condition],
+        Statement::Expression(expr) => vec![expr],
+        Statement::Block(_) | Statement::Loop { .. } | Statement::Break | Statement::Continue => {
+            vec![]
+        }
+    }
+}
+
+/// Sub-expressions of an expression
+fn expression_children(expr: &Expression) -> Vec<&Spanned<Expression>> {
+    match expr {
+        Expression::UnaryOp { expr, .. }
+        | Expression::Cast { expr, .. }
+        | Expression::Parenthesized(expr) => vec![expr.as_ref()],
+        Expression::BinaryOp { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+        Expression::FunctionCall { callee, args } => {
+            std::iter::once(callee.as_ref()).chain(args).collect()
+        }
+        Expression::MemberAccess { object, .. } => vec![object.as_ref()],
+        Expression::IndexAccess { array, index } => vec![array.as_ref(), index.as_ref()],
+        Expression::StructLiteral { fields, .. } => fields.iter().map(|(_, value)| value).collect(),
+        Expression::Tuple(elements) | Expression::ArrayLiteral(elements) => {
+            elements.iter().collect()
+        }
+        Expression::TupleIndex { tuple, .. } => vec![tuple.as_ref()],
+        Expression::ArrayRepeat { element, .. } => vec![element.as_ref()],
+        Expression::New { count, .. } => vec![count.as_ref()],
+        // The statements of a block are not expressions
+        Expression::Block { value, .. } => vec![value.as_ref()],
+        Expression::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => vec![
+            condition.as_ref(),
+            then_branch.as_ref(),
+            else_branch.as_ref(),
+        ],
+        Expression::Literal(..)
+        | Expression::BooleanLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::Identifier(_) => vec![],
+    }
+}
+
+const fn contains(span: SimpleSpan<usize>, offset: usize) -> bool {
+    span.start <= offset && offset <= span.end
+}
+
+/// Collects the spans of the expressions enclosing `offset`, outermost first
+fn expression_spans(expr: &Spanned<Expression>, offset: usize, spans: &mut Vec<SimpleSpan<usize>>) {
+    if !contains(expr.span(), offset) {
+        return;
+    }
+    spans.push(expr.span());
+    match expr.value() {
+        Expression::Identifier(name) | Expression::MemberAccess { field: name, .. }
+            if contains(name.span(), offset) =>
+        {
+            spans.push(name.span());
+        }
+        _ => {}
+    }
+    // Siblings may touch at `offset`: only the first one is expanded
+    if let Some(child) = expression_children(expr.value())
+        .into_iter()
+        .find(|child| contains(child.span(), offset))
+    {
+        expression_spans(child, offset, spans);
+    }
+}
+
+/// Collects the spans of the statements and expressions enclosing `offset`, outermost first
+fn statement_spans(
+    statement: &Spanned<Statement>,
+    offset: usize,
+    spans: &mut Vec<SimpleSpan<usize>>,
+) {
+    if !contains(statement.span(), offset) {
+        return;
+    }
+    spans.push(statement.span());
+    if let Some(expr) = statement_expressions(statement.value())
+        .into_iter()
+        .find(|expr| contains(expr.span(), offset))
+    {
+        expression_spans(expr, offset, spans);
+    } else if let Some(child) = statement_children(statement.value())
+        .into_iter()
+        .find(|child| contains(child.span(), offset))
+    {
+        statement_spans(child, offset, spans);
+    }
+}
+
+/// Spans of the nodes of a function enclosing `offset`, outermost first
+fn function_spans(
+    function: &Spanned<FunctionDef>,
+    offset: usize,
+    spans: &mut Vec<SimpleSpan<usize>>,
+) {
+    spans.push(function.span());
+    let function = function.value();
+    if contains(function.name.span(), offset) {
+        spans.push(function.name.span());
+    }
+    for param in &function.params {
+        if contains(param.name.span(), offset) {
+            spans.push(param.name.span());
+        }
+    }
+    if let Some(statement) = function
+        .body
+        .iter()
+        .find(|statement| contains(statement.span(), offset))
+    {
+        statement_spans(statement, offset, spans);
+    }
+}
+
+/// Spans of the AST nodes enclosing `offset`, outermost first
+fn enclosing_spans(module: &ParsedModule, offset: usize) -> Vec<SimpleSpan<usize>> {
+    let mut spans = Vec::new();
+    for item in module.items() {
+        match item {
+            TopLevelItem::Function(function) if contains(function.span(), offset) => {
+                function_spans(function, offset, &mut spans);
+            }
+            TopLevelItem::Impl(impl_def) if contains(impl_def.span(), offset) => {
+                spans.push(impl_def.span());
+                let impl_def = impl_def.value();
+                if contains(impl_def.type_name.span(), offset) {
+                    spans.push(impl_def.type_name.span());
+                }
+                if let Some(method) = impl_def
+                    .methods
+                    .iter()
+                    .find(|method| contains(method.span(), offset))
+                {
+                    function_spans(method, offset, &mut spans);
+                }
+            }
+            TopLevelItem::Struct(struct_def) if contains(struct_def.span(), offset) => {
+                spans.push(struct_def.span());
+                for (name, _) in &struct_def.value().fields {
+                    if contains(name.span(), offset) {
+                        spans.push(name.span());
+                    }
+                }
+            }
+            TopLevelItem::Const(const_def) if contains(const_def.span(), offset) => {
+                spans.push(const_def.span());
+                expression_spans(&const_def.value().value, offset, &mut spans);
+            }
+            TopLevelItem::Use(use_stmt) if contains(use_stmt.span(), offset) => {
+                spans.push(use_stmt.span());
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Builds the selection range at `offset`, expanding from the innermost enclosing node
+///
+/// Returns `None` if no node encloses the offset.
+pub fn selection_range(
+    source: &str,
+    module: &ParsedModule,
+    offset: usize,
+) -> Option<SelectionRange> {
+    let mut spans = enclosing_spans(module, offset);
+    spans.dedup();
+
+    spans.into_iter().fold(None, |parent, span| {
+        Some(SelectionRange {
+            range: Range {
+                start: offset_to_position(source, span.start),
+                end: offset_to_position(source, span.end),
+            },
+            parent: parent.map(Box::new),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_m_compiler_parser::{SourceFile, parse_file};
+
+    use super::*;
+    use crate::db::AnalysisDatabase;
+
+    const SOURCE: &str = r#"use math::add;
+use math::sub;
+
+// Entry point of the program,
+// returning the sum.
+fn main() -> felt {
+    let x = add(1, 2);
+    if (x == 3) {
+        return x;
+    }
+    return 0;
+}
+
+struct Point {
+    x: felt,
+}
+"#;
+
+    fn parse(source: &str) -> ParsedModule {
+        let db = AnalysisDatabase::default();
+        let file = SourceFile::new(&db, source.to_string(), "main.cm".to_string());
+        parse_file(&db, file).module
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let ranges: Vec<_> = folding_ranges(SOURCE, &parse(SOURCE))
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 1, Some(FoldingRangeKind::Imports)),
+                (3, 4, Some(FoldingRangeKind::Comment)),
+                (5, 11, None),
+                (7, 9, None),
+                (13, 15, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_selection_range() {
+        let offset = SOURCE.find("add(1, 2)").unwrap() + "add(1, ".len();
+        let mut range = selection_range(SOURCE, &parse(SOURCE), offset);
+
+        let mut expansions = Vec::new();
+        while let Some(current) = range {
+            expansions.push((
+                current.range.start.line,
+                current.range.start.character,
+                current.range.end.line,
+                current.range.end.character,
+            ));
+            range = current.parent.map(|parent| *parent);
+        }
+
+        assert_eq!(
+            expansions,
+            vec![
+                // `2`
+                (6, 19, 6, 20),
+                // `add(1, 2)`
+                (6, 12, 6, 21),
+                // `let x = add(1, 2);`
+                (6, 4, 6, 22),
+                // `fn main`
+                (5, 0, 11, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Signature help for function calls
+//!
+//! The call surrounding the cursor is found by scanning the source text backwards, so that
+//! signature help keeps working while the arguments being typed do not parse yet. The callee
+//! is then resolved through the semantic index, and its signature is rendered with the slot
+//! count of each parameter: `u32` and struct arguments are expanded to several slots at the
+//! call site.
+
+use std::ops::Range;
+
+use cairo_m_compiler_semantic::SemanticDb;
+use cairo_m_compiler_semantic::types::{FunctionSignatureId, TypeData, TypeId};
+use tower_lsp::lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel,
+    SignatureInformation,
+};
+
+/// A call surrounding the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallContext {
+    /// Name of the called function
+    pub callee: String,
+    /// Byte span of the callee name in the source
+    pub callee_span: Range<usize>,
+    /// Index of the argument under the cursor
+    pub active_parameter: u32,
+}
+
+/// Finds the innermost call whose argument list contains `offset`
+///
+/// Returns `None` if the cursor is not inside the parentheses of a call, e.g. in a
+/// parenthesized expression or outside of any parentheses.
+pub fn find_call_context(source: &str, offset: usize) -> Option<CallContext> {
+    let before = source.get(..offset)?;
+    let mut depth = 0usize;
+    let mut active_parameter = 0;
+    let mut open_paren = None;
+
+    for (i, ch) in before.char_indices().rev() {
+        match ch {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => {
+                open_paren = Some(i);
+                break;
+            }
+            '[' | '{' if depth == 0 => return None,
+            '(' | '[' | '{' => depth -= 1,
+            ';' if depth == 0 => return None,
+            ',' if depth == 0 => active_parameter += 1,
+            _ => {}
+        }
+    }
+
+    let callee_end = before[..open_paren?].trim_end().len();
+    let callee_start = before[..callee_end]
+        .rfind(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+        .map_or(0, |i| i + 1);
+    let callee = &before[callee_start..callee_end];
+    if callee.is_empty() || callee.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(CallContext {
+        callee: callee.to_string(),
+        callee_span: callee_start..callee_end,
+        active_parameter,
+    })
+}
+
+/// Number of slots a value of the given type takes when passed as an argument
+///
+/// Mirrors the value sizes used by the compiler when lowering calls.
+pub fn value_slots<'db>(db: &'db dyn SemanticDb, type_id: TypeId<'db>) -> usize {
+    match type_id.data(db) {
+        TypeData::U32 => 2,
+        TypeData::Tuple(types) => types.iter().map(|t| value_slots(db, *t)).sum(),
+        TypeData::Struct(struct_id) => struct_id
+            .fields(db)
+            .iter()
+            .map(|(_, field_type)| value_slots(db, *field_type))
+            .sum(),
+        TypeData::FixedArray { element_type, size } => value_slots(db, element_type) * size,
+        TypeData::Felt
+        | TypeData::Bool
+        | TypeData::Pointer { .. }
+        | TypeData::Function(_)
+        | TypeData::Unknown
+        | TypeData::Error => 1,
+    }
+}
+
+/// Formats a number of slots, e.g. `1 slot` or `3 slots`
+fn slots(count: usize) -> String {
+    if count == 1 {
+        "1 slot".to_string()
+    } else {
+        format!("{count} slots")
+    }
+}
+
+/// Builds the signature information of a function, e.g. `fn add(a: felt, b: u32) -> felt`
+///
+/// Parameters are labelled with their offsets in the signature, and parameters taking more
+/// than one slot are documented with their slot count.
+pub fn signature_information<'db>(
+    db: &'db dyn SemanticDb,
+    name: &str,
+    signature: FunctionSignatureId<'db>,
+    active_parameter: u32,
+) -> SignatureInformation {
+    let mut label = format!("fn {name}(");
+    let mut parameters = Vec::new();
+    let mut total_slots = 0;
+
+    for (i, (param_name, param_type)) in signature.params(db).iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len() as u32;
+        label.push_str(&format!(
+            "{}: {}",
+            param_name,
+            TypeId::format_type(db, *param_type)
+        ));
+        let end = label.len() as u32;
+
+        let param_slots = value_slots(db, *param_type);
+        total_slots += param_slots;
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: (param_slots > 1)
+                .then(|| Documentation::String(format!("passed as {}", slots(param_slots)))),
+        });
+    }
+    label.push(')');
+
+    let return_type = signature.return_type(db);
+    if !matches!(return_type.data(db), TypeData::Tuple(types) if types.is_empty()) {
+        label.push_str(&format!(" -> {}", TypeId::format_type(db, return_type)));
+    }
+
+    SignatureInformation {
+        label,
+        documentation: Some(Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("Arguments take {}", slots(total_slots)),
+        })),
+        parameters: Some(parameters),
+        active_parameter: Some(active_parameter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_at_caret(source: &str) -> Option<CallContext> {
+        let offset = source.find("<caret>").unwrap();
+        find_call_context(&source.replace("<caret>", ""), offset)
+    }
+
+    #[test]
+    fn test_find_call_context() {
+        let context = context_at_caret("let x = add(1, <caret>").unwrap();
+        assert_eq!(context.callee, "add");
+        assert_eq!(context.callee_span, 8..11);
+        assert_eq!(context.active_parameter, 1);
+
+        let context = context_at_caret("add(<caret>2, 3)").unwrap();
+        assert_eq!(context.callee, "add");
+        assert_eq!(context.active_parameter, 0);
+    }
+
+    #[test]
+    fn test_find_call_context_skips_nested_expressions() {
+        let context =
+            context_at_caret("f(g(1, 2), [3, 4], Point { x: 1, y: 2 }, (5, 6), <caret>").unwrap();
+        assert_eq!(context.callee, "f");
+        assert_eq!(context.active_parameter, 4);
+
+        let context = context_at_caret("f(1, g(2, <caret>))").unwrap();
+        assert_eq!(context.callee, "g");
+        assert_eq!(context.active_parameter, 1);
+    }
+
+    #[test]
+    fn test_find_call_context_outside_call() {
+        assert_eq!(context_at_caret("let x = (1 + <caret>"), None);
+        assert_eq!(context_at_caret("f(1);\nlet x = <caret>"), None);
+        assert_eq!(context_at_caret("fn main() {\n    <caret>"), None);
+        assert_eq!(context_at_caret("let x = [1, <caret>"), None);
+    }
+}
@@ -0,0 +1,128 @@
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    Position, TextDocumentIdentifier, TextDocumentPositionParams,
+};
+
+use super::support::{Fixture, MockClient, client_capabilities, start_mock_client};
+
+const MAIN: &str = r#"use math::double;
+
+fn main() -> felt {
+    return helper(1) + double(2);
+}
+
+fn helper(x: felt) -> felt {
+    return double(x);
+}
+"#;
+
+const MATH: &str = r#"fn double(x: felt) -> felt {
+    return square(x) + x;
+}
+
+fn square(x: felt) -> felt {
+    return x * x;
+}
+"#;
+
+async fn start() -> MockClient {
+    let fixture = Fixture::new();
+    fixture.add_cairom_toml("test_project");
+    fixture.add_file("src/main.cm", MAIN);
+    fixture.add_file("src/math.cm", MATH);
+
+    let client = start_mock_client(fixture, client_capabilities::base(), serde_json::json!({}))
+        .await
+        .unwrap();
+    client
+        .open_and_wait_for_analysis("src/main.cm")
+        .await
+        .unwrap();
+    client
+}
+
+async fn prepare(client: &MockClient, file: &str, position: Position) -> Vec<CallHierarchyItem> {
+    let params = CallHierarchyPrepareParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: client.file_url(file),
+            },
+            position,
+        },
+        work_done_progress_params: Default::default(),
+    };
+    client
+        .send_request::<lsp_types::request::CallHierarchyPrepare>(params)
+        .await
+        .unwrap()
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_call_hierarchy_across_modules() {
+    let client = start().await;
+
+    // Preparing on a call to an imported function yields its definition in the other module
+    let items = prepare(&client, "src/main.cm", Position::new(3, 24)).await;
+    assert_eq!(items.len(), 1);
+    let double = items[0].clone();
+    assert_eq!(double.name, "double");
+    assert!(double.uri.path().ends_with("src/math.cm"));
+    assert_eq!(double.selection_range.start, Position::new(0, 3));
+
+    let incoming: Option<Vec<CallHierarchyIncomingCall>> = client
+        .send_request::<lsp_types::request::CallHierarchyIncomingCalls>(
+            CallHierarchyIncomingCallsParams {
+                item: double.clone(),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+    let incoming = incoming.unwrap();
+    let callers: Vec<_> = incoming
+        .iter()
+        .map(|call| call.from.name.as_str())
+        .collect();
+    assert_eq!(callers, vec!["helper", "main"]);
+    assert!(
+        incoming
+            .iter()
+            .all(|call| call.from.uri.path().ends_with("src/main.cm"))
+    );
+    assert_eq!(incoming[1].from_ranges[0].start, Position::new(3, 23));
+
+    let outgoing: Option<Vec<CallHierarchyOutgoingCall>> = client
+        .send_request::<lsp_types::request::CallHierarchyOutgoingCalls>(
+            CallHierarchyOutgoingCallsParams {
+                item: double,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .await
+        .unwrap();
+    let outgoing = outgoing.unwrap();
+    assert_eq!(outgoing.len(), 1);
+    assert_eq!(outgoing[0].to.name, "square");
+    assert_eq!(outgoing[0].from_ranges[0].start, Position::new(1, 11));
+}
+
+#[tokio::test]
+async fn test_prepare_call_hierarchy_on_definition() {
+    let client = start().await;
+
+    let items = prepare(&client, "src/main.cm", Position::new(6, 5)).await;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "helper");
+    assert!(items[0].uri.path().ends_with("src/main.cm"));
+
+    // Nothing to prepare away from functions
+    assert!(
+        prepare(&client, "src/main.cm", Position::new(7, 18))
+            .await
+            .is_empty()
+    );
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use lsp_types::{
+    DidChangeConfigurationParams, InlayHint, InlayHintLabel, InlayHintParams, Position, Range,
+    TextDocumentIdentifier, WorkDoneProgressParams,
+};
+
+use super::support::{Fixture, MockClient, client_capabilities, start_mock_client};
+
+const SOURCE: &str = r#"
+fn add(a: felt, b: felt) -> felt {
+    return a + b;
+}
+
+fn main() -> felt {
+    let unused = 1;
+    let b = 2;
+    let sum = add(3, b);
+    return sum;
+}
+"#;
+
+async fn start() -> MockClient {
+    let fixture = Fixture::new();
+    fixture.add_cairom_toml("test_project");
+    fixture.add_file("src/main.cm", SOURCE);
+
+    let client = start_mock_client(fixture, client_capabilities::base(), serde_json::json!({}))
+        .await
+        .unwrap();
+    client
+        .open_and_wait_for_analysis("src/main.cm")
+        .await
+        .unwrap();
+    client
+}
+
+async fn inlay_hint_labels(client: &MockClient) -> Vec<String> {
+    let params = InlayHintParams {
+        text_document: TextDocumentIdentifier {
+            uri: client.file_url("src/main.cm"),
+        },
+        range: Range::new(Position::new(0, 0), Position::new(12, 0)),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    let hints: Option<Vec<InlayHint>> = client
+        .send_request::<lsp_types::request::InlayHintRequest>(params)
+        .await
+        .unwrap();
+    hints
+        .unwrap_or_default()
+        .into_iter()
+        .map(|hint| match hint.label {
+            InlayHintLabel::String(label) => label,
+            InlayHintLabel::LabelParts(parts) => parts.into_iter().map(|part| part.value).collect(),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_lint_levels_are_applied_on_configuration_change() {
+    let client = start().await;
+    let uri = client.file_url("src/main.cm").to_string();
+
+    let diagnostics = client.wait_for_diagnostics_default(&uri).await.unwrap();
+    assert!(
+        diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("Unused variable"))
+    );
+
+    client
+        .send_notification::<lsp_types::notification::DidChangeConfiguration>(
+            DidChangeConfigurationParams {
+                settings: serde_json::json!({
+                    "cairo_m": { "lints": { "unused_variable": "allow" } }
+                }),
+            },
+        )
+        .await
+        .unwrap();
+
+    // The project is revalidated in the background
+    let mut remaining = 50;
+    loop {
+        let diagnostics = client.wait_for_diagnostics_default(&uri).await.unwrap();
+        if !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("Unused variable"))
+        {
+            break;
+        }
+        remaining -= 1;
+        assert!(remaining > 0, "Unused variable warning still reported");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_inlay_hints_follow_settings() {
+    let client = start().await;
+
+    let labels = inlay_hint_labels(&client).await;
+    assert!(labels.contains(&": felt".to_string()));
+    assert!(labels.contains(&"a:".to_string()));
+    // Arguments named after their parameter have no hint
+    assert!(!labels.contains(&"b:".to_string()));
+
+    client
+        .send_notification::<lsp_types::notification::DidChangeConfiguration>(
+            DidChangeConfigurationParams {
+                settings: serde_json::json!({
+                    "cairo_m": { "inlay_hints": { "parameter_hints": false } }
+                }),
+            },
+        )
+        .await
+        .unwrap();
+
+    let labels = inlay_hint_labels(&client).await;
+    assert!(labels.contains(&": felt".to_string()));
+    assert!(!labels.contains(&"a:".to_string()));
+
+    client.shutdown().await.unwrap();
+}
@@ -1,8 +1,13 @@
 use anyhow::Result;
-use lsp_types::Diagnostic;
+use lsp_types::{
+    Diagnostic, PartialResultParams, PreviousResultId, WorkDoneProgressParams,
+    WorkspaceDiagnosticParams, WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
+};
 use serde_json::Value;
 
-use super::support::{Cursors, MockClient, Transformer};
+use super::support::{
+    Cursors, Fixture, MockClient, Transformer, client_capabilities, start_mock_client,
+};
 use crate::support::insta::test_transform;
 /// Simple diagnostics transformer for testing
 pub struct DiagnosticsTransformer;
@@ -72,3 +77,99 @@ fn main() {
 "#
     );
 }
+
+#[tokio::test]
+async fn test_workspace_diagnostics_include_unopened_files() {
+    let fixture = Fixture::new();
+    fixture.add_cairom_toml("test_project");
+    fixture.add_file(
+        "src/main.cm",
+        r#"
+fn main() -> felt {
+    return 1;
+}
+"#,
+    );
+    fixture.add_file(
+        "src/broken.cm",
+        r#"
+fn broken() -> felt {
+    return missing;
+}
+"#,
+    );
+
+    let client = start_mock_client(fixture, client_capabilities::base(), serde_json::json!({}))
+        .await
+        .unwrap();
+    client
+        .open_and_wait_for_analysis("src/main.cm")
+        .await
+        .unwrap();
+
+    let params = WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids: vec![],
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let WorkspaceDiagnosticReportResult::Report(report) = client
+        .send_request::<lsp_types::request::WorkspaceDiagnosticRequest>(params)
+        .await
+        .unwrap()
+    else {
+        panic!("Expected a full workspace report");
+    };
+
+    let broken_uri = client.file_url("src/broken.cm");
+    let broken = report
+        .items
+        .iter()
+        .find_map(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(report) if report.uri == broken_uri => {
+                Some(report)
+            }
+            _ => None,
+        })
+        .expect("Expected a report for the unopened file");
+    assert!(
+        broken
+            .full_document_diagnostic_report
+            .items
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("Undeclared variable"))
+    );
+
+    // Pulling again with the result ids received reports every file as unchanged
+    let params = WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids: report
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                WorkspaceDocumentDiagnosticReport::Full(report) => Some(PreviousResultId {
+                    uri: report.uri.clone(),
+                    value: report.full_document_diagnostic_report.result_id.clone()?,
+                }),
+                WorkspaceDocumentDiagnosticReport::Unchanged(_) => None,
+            })
+            .collect(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let WorkspaceDiagnosticReportResult::Report(report) = client
+        .send_request::<lsp_types::request::WorkspaceDiagnosticRequest>(params)
+        .await
+        .unwrap()
+    else {
+        panic!("Expected a full workspace report");
+    };
+    assert!(
+        report
+            .items
+            .iter()
+            .all(|item| matches!(item, WorkspaceDocumentDiagnosticReport::Unchanged(_)))
+    );
+
+    client.shutdown().await.unwrap();
+}
@@ -40,6 +40,45 @@ fn main() {
     });
 }
 
+#[tokio::test]
+async fn test_hover_shows_doc_comment_of_imported_function() {
+    let fixture = Fixture::new();
+    fixture.add_cairom_toml("test_project");
+    fixture.add_file(
+        "src/main.cm",
+        r#"
+use utils::helper_foo;
+
+fn main() {
+    let result = helper_foo(42);
+}
+"#,
+    );
+    fixture.add_file(
+        "src/utils.cm",
+        r#"
+/// Doubles `x`.
+fn helper_foo(x: felt) -> felt {
+    return x * 2;
+}
+"#,
+    );
+
+    let (_, cursors) = extract_cursors(
+        r#"
+use utils::helper_foo;
+
+fn main() {
+    let result = helper_<caret>foo(42);
+}
+"#,
+    );
+
+    test_transform!(HoverTransformer, fixture, cursors, |result: &str| {
+        assert!(result.contains("Doubles `x`."), "{}", result);
+    });
+}
+
 #[ignore = "TODO: Type info not available for user-defined types"]
 #[tokio::test]
 async fn test_hover_on_imported_type() {
@@ -1,7 +1,10 @@
 mod support;
 
+mod call_hierarchy_test;
+mod config_test;
 mod diagnostics_test;
 mod formatting_test;
+mod signature_help_test;
 mod simple_test;
 
 mod goto_definition;
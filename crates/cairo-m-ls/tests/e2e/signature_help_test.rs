@@ -0,0 +1,145 @@
+use anyhow::Result;
+use lsp_types::{
+    Documentation, ParameterLabel, SignatureHelp, SignatureHelpParams, TextDocumentIdentifier,
+    TextDocumentPositionParams,
+};
+use serde_json::Value;
+
+use super::support::{Cursors, MockClient, Transformer};
+use crate::support::insta::test_transform;
+
+/// Transformer for testing signature help
+pub struct SignatureHelpTransformer;
+
+#[async_trait::async_trait]
+impl Transformer for SignatureHelpTransformer {
+    async fn transform(
+        client: &mut MockClient,
+        cursors: Cursors,
+        _config: Option<Value>,
+    ) -> Result<String> {
+        let position = cursors.assert_single_caret();
+
+        let params = SignatureHelpParams {
+            context: None,
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: client.file_url(Self::main_file()),
+                },
+                position,
+            },
+            work_done_progress_params: Default::default(),
+        };
+
+        let response: Option<SignatureHelp> = client
+            .send_request::<lsp_types::request::SignatureHelpRequest>(params)
+            .await?;
+
+        Ok(match response {
+            Some(help) => format_signature_help(&help),
+            None => "No signature help".to_string(),
+        })
+    }
+}
+
+fn documentation_text(documentation: &Documentation) -> &str {
+    match documentation {
+        Documentation::String(text) => text,
+        Documentation::MarkupContent(markup) => &markup.value,
+    }
+}
+
+fn format_signature_help(help: &SignatureHelp) -> String {
+    let mut result = String::new();
+    for signature in &help.signatures {
+        result.push_str(&signature.label);
+        result.push('\n');
+
+        let active = signature
+            .active_parameter
+            .and_then(|index| signature.parameters.as_ref()?.get(index as usize));
+        if let Some(parameter) = active {
+            let label = match &parameter.label {
+                ParameterLabel::Simple(label) => label.clone(),
+                ParameterLabel::LabelOffsets([start, end]) => {
+                    signature.label[*start as usize..*end as usize].to_string()
+                }
+            };
+            result.push_str(&format!("active parameter: {label}"));
+            if let Some(documentation) = &parameter.documentation {
+                result.push_str(&format!(" ({})", documentation_text(documentation)));
+            }
+            result.push('\n');
+        }
+
+        if let Some(documentation) = &signature.documentation {
+            result.push_str(documentation_text(documentation));
+            result.push('\n');
+        }
+    }
+    result.trim_end().to_string()
+}
+
+#[tokio::test]
+async fn test_signature_help_active_parameter() {
+    test_transform!(
+        SignatureHelpTransformer,
+        r#"
+fn add(a: felt, b: felt) -> felt {
+    return a + b;
+}
+
+fn main() -> felt {
+    let sum = add(1, <caret>2);
+    return sum;
+}
+"#,
+        @r"
+    fn add(a: felt, b: felt) -> felt
+    active parameter: b: felt
+    Arguments take 2 slots
+    "
+    );
+}
+
+#[tokio::test]
+async fn test_signature_help_slot_expansion() {
+    test_transform!(
+        SignatureHelpTransformer,
+        r#"
+struct Point {
+    x: felt,
+    y: u32,
+}
+
+fn scale(p: Point, factor: felt) -> felt {
+    return p.x * factor;
+}
+
+fn main() -> felt {
+    let p = Point { x: 1, y: 2 };
+    let n = scale(p<caret>, 2);
+    return n;
+}
+"#,
+        @r"
+    fn scale(p: Point, factor: felt) -> felt
+    active parameter: p: Point (passed as 3 slots)
+    Arguments take 4 slots
+    "
+    );
+}
+
+#[tokio::test]
+async fn test_signature_help_outside_call() {
+    test_transform!(
+        SignatureHelpTransformer,
+        r#"
+fn main() -> felt {
+    let x = (1 + <caret>2);
+    return x;
+}
+"#,
+        @"No signature help"
+    );
+}
@@ -89,3 +89,71 @@ fn main() {
     // Graceful shutdown
     client.shutdown().await.unwrap();
 }
+
+#[tokio::test]
+async fn test_parse_diagnostics_published_on_change() {
+    let fixture = Fixture::new();
+    fixture.add_cairom_toml("test_project");
+    fixture.add_file(
+        "src/main.cm",
+        r#"
+fn main() {
+    return;
+}
+"#,
+    );
+
+    // Debounce semantic validation long enough that only parse diagnostics can arrive
+    let caps = client_capabilities::base();
+    let config = serde_json::json!({
+        "cairo_m": {
+            "debounce_ms": 60000,
+            "db_swap_interval_ms": 3600000 // 1 hour
+        }
+    });
+
+    let client = start_mock_client(fixture, caps, config).await.unwrap();
+    client
+        .open_and_wait_for_analysis("src/main.cm")
+        .await
+        .unwrap();
+
+    let main_uri = client.file_url("src/main.cm");
+    client
+        .send_notification::<lsp_types::notification::DidChangeTextDocument>(
+            lsp_types::DidChangeTextDocumentParams {
+                text_document: lsp_types::VersionedTextDocumentIdentifier {
+                    uri: main_uri.clone(),
+                    version: 1,
+                },
+                content_changes: vec![lsp_types::TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: "fn main() {\n    let = 1;\n    return;\n}\n".to_string(),
+                }],
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut diagnostics = vec![];
+    for _ in 0..50 {
+        diagnostics = client
+            .wait_for_diagnostics_default(main_uri.as_str())
+            .await
+            .unwrap();
+        if !diagnostics.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .all(cairo_m_ls::diagnostics::is_parse_diagnostic)
+    );
+
+    client.shutdown().await.unwrap();
+}
@@ -1,7 +1,11 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::{M31, P};
 
 /// Untyped input value that can be interpreted based on AbiType
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InputValue {
     /// A numeric value that can be interpreted as felt, u32, bool, or pointer based on AbiType
     Number(i64),
@@ -69,6 +73,69 @@ impl TryFrom<CairoMValue> for M31 {
     }
 }
 
+impl CairoMValue {
+    /// Displays the value with felts in their balanced representation, followed by their
+    /// hexadecimal encoding (e.g. `-5 (0x7ffffffa)`).
+    pub const fn display_signed(&self) -> SignedDisplay<'_> {
+        SignedDisplay(self)
+    }
+
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, signed: bool) -> fmt::Result {
+        let fmt_list = |f: &mut fmt::Formatter<'_>, values: &[Self]| {
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                value.fmt_with(f, signed)?;
+            }
+            Ok(())
+        };
+        match self {
+            Self::Felt(value) if signed => write!(f, "{} ({:#x})", m31_to_i64(*value), value.0),
+            Self::Felt(value) => write!(f, "{}", value.0),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::U32(value) => write!(f, "{value}"),
+            Self::Pointer(value) => write!(f, "ptr({:#x})", value.0),
+            Self::Tuple(values) => {
+                write!(f, "(")?;
+                fmt_list(f, values)?;
+                write!(f, ")")
+            }
+            Self::Struct(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    write!(f, "{}{name}: ", if i > 0 { ", " } else { " " })?;
+                    value.fmt_with(f, signed)?;
+                }
+                write!(f, "{}}}", if fields.is_empty() { "" } else { " " })
+            }
+            Self::Array(values) => {
+                write!(f, "[")?;
+                fmt_list(f, values)?;
+                write!(f, "]")
+            }
+            Self::Unit => write!(f, "()"),
+        }
+    }
+}
+
+impl fmt::Display for CairoMValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, false)
+    }
+}
+
+/// Display of a [`CairoMValue`] with felts in their balanced representation.
+/// Created by [`CairoMValue::display_signed`].
+#[derive(Debug, Clone, Copy)]
+pub struct SignedDisplay<'a>(&'a CairoMValue);
+
+impl fmt::Display for SignedDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_with(f, true)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AbiCodecError {
     #[error("Type/value mismatch at {0}")]
@@ -79,8 +146,8 @@ pub enum AbiCodecError {
     DynamicArrayUnsupported,
     #[error("Unexpected trailing or insufficient return data")]
     TrailingOrInsufficientData,
-    #[error("Parse error: {0}")]
-    ParseError(String),
+    #[error("Parse error at position {position}: {message}")]
+    ParseError { position: usize, message: String },
 }
 
 /// Convert an i64 to an M31
@@ -95,13 +162,28 @@ pub const fn m31_from_i64(n: i64) -> M31 {
     M31::reduce(m as u64)
 }
 
+/// Convert an M31 to its balanced representation, in `[-(P - 1) / 2, (P - 1) / 2]`
+pub const fn m31_to_i64(value: M31) -> i64 {
+    if value.0 > P / 2 {
+        value.0 as i64 - P as i64
+    } else {
+        value.0 as i64
+    }
+}
+
 /// Parse an argument string into an InputValue (supports nesting)
 ///
 /// Supported grammar (positional structs):
 ///   Value := Number | Bool | Array | Tuple | Struct
+///   Number := '-'? (Hex | Decimal)
+///   Hex := ('0x' | '0X') HexDigit+
+///   Decimal := Digit+ ('.' Digit+)? (('e' | 'E') '+'? Digit+)?
 ///   Array := '[' (Value (',' Value)*)? ']'
 ///   Tuple := '(' (Value (',' Value)*)? ')'
 ///   Struct := '{' (Value (',' Value)*)? '}'
+///
+/// Decimals in scientific notation, such as `1e6` or `2.5e3`, must denote an integer, and
+/// numbers must fit in an `i64`. Errors report the character offset of the failure.
 pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
     struct Parser<'a> {
         src: &'a [u8],
@@ -128,13 +210,32 @@ pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
             c
         }
         fn skip_ws(&mut self) {
-            while let Some(b) = self.peek() {
-                if (b as char).is_whitespace() {
-                    self.i += 1;
-                } else {
-                    break;
-                }
+            // Any Unicode whitespace separates values
+            let rest = std::str::from_utf8(&self.src[self.i..]).unwrap_or_default();
+            self.i += rest.len() - rest.trim_start().len();
+        }
+        /// Builds a parse error at byte index `at`, reported as a character offset
+        fn error(&self, at: usize, message: String) -> AbiCodecError {
+            // The parser only stops on character boundaries
+            let position = std::str::from_utf8(&self.src[..at]).map_or(at, |s| s.chars().count());
+            AbiCodecError::ParseError { position, message }
+        }
+        /// Describes the character at the current position, for error messages
+        fn current(&self) -> String {
+            std::str::from_utf8(&self.src[self.i..])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .map_or_else(
+                    || "end of input".to_string(),
+                    |c| format!("character '{c}'"),
+                )
+        }
+        fn skip_digits(&mut self, radix: u32) -> usize {
+            let start = self.i;
+            while self.peek().is_some_and(|b| (b as char).is_digit(radix)) {
+                self.i += 1;
             }
+            self.i - start
         }
         fn parse_value(&mut self) -> Result<InputValue, AbiCodecError> {
             self.skip_ws();
@@ -144,11 +245,13 @@ pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
                 Some(b'{') => self.parse_struct(),
                 Some(b't') | Some(b'f') => self.parse_bool(),
                 Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
-                _ => Err(AbiCodecError::ParseError(format!(
-                    "Unexpected character '{}' at position {}. Expected number, boolean, tuple '(' or '[', or struct '{{'",
-                    self.peek().map(|b| b as char).unwrap_or('\0'),
-                    self.i
-                ))),
+                _ => Err(self.error(
+                    self.i,
+                    format!(
+                        "Unexpected {}. Expected number, boolean, tuple '(' or '[', or struct '{{'",
+                        self.current()
+                    ),
+                )),
             }
         }
         fn parse_array(&mut self) -> Result<InputValue, AbiCodecError> {
@@ -190,10 +293,15 @@ pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
                         break;
                     }
                     _ => {
-                        return Err(AbiCodecError::ParseError(format!(
-                            "Expected ',' or '{}' at position {} in {}",
-                            close as char, self.i, context
-                        )));
+                        return Err(self.error(
+                            self.i,
+                            format!(
+                                "Expected ',' or '{}' in {}, found {}",
+                                close as char,
+                                context,
+                                self.current()
+                            ),
+                        ));
                     }
                 }
             }
@@ -217,33 +325,126 @@ pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
                 self.i += 5;
                 Ok(InputValue::Bool(false))
             } else {
-                Err(AbiCodecError::ParseError(format!(
-                    "Invalid boolean at position {}: expected 'true' or 'false'",
-                    self.i
-                )))
+                Err(self.error(
+                    self.i,
+                    "Invalid boolean: expected 'true' or 'false'".to_string(),
+                ))
             }
         }
         fn parse_number(&mut self) -> Result<InputValue, AbiCodecError> {
             let start = self.i;
-            if self.peek() == Some(b'-') {
+            let negative = self.peek() == Some(b'-');
+            if negative {
+                self.i += 1;
+            }
+
+            let magnitude =
+                if self.src[self.i..].starts_with(b"0x") || self.src[self.i..].starts_with(b"0X") {
+                    self.i += 2;
+                    self.parse_hex_digits(start)?
+                } else {
+                    self.parse_decimal(start)?
+                };
+
+            let text = std::str::from_utf8(&self.src[start..self.i]).unwrap();
+            let value = i128::try_from(magnitude)
+                .ok()
+                .map(|m| if negative { -m } else { m })
+                .and_then(|n| i64::try_from(n).ok())
+                .ok_or_else(|| {
+                    self.error(
+                        start,
+                        format!("Number '{}' does not fit in a 64-bit integer", text),
+                    )
+                })?;
+            Ok(InputValue::Number(value))
+        }
+        /// Parses the digits of a hexadecimal number, starting at byte index `start`
+        fn parse_hex_digits(&mut self, start: usize) -> Result<u128, AbiCodecError> {
+            let digits_start = self.i;
+            if self.skip_digits(16) == 0 {
+                return Err(self.error(
+                    self.i,
+                    format!("Expected hexadecimal digits, found {}", self.current()),
+                ));
+            }
+            let digits = std::str::from_utf8(&self.src[digits_start..self.i]).unwrap();
+            u128::from_str_radix(digits, 16).map_err(|_| {
+                self.error(
+                    start,
+                    format!("Number '0x{}' does not fit in a 64-bit integer", digits),
+                )
+            })
+        }
+        /// Parses a decimal number, possibly in scientific notation, starting at byte index
+        /// `start`, which must denote an integer
+        fn parse_decimal(&mut self, start: usize) -> Result<u128, AbiCodecError> {
+            let digits_start = self.i;
+            if self.skip_digits(10) == 0 {
+                return Err(
+                    self.error(self.i, format!("Expected digits, found {}", self.current()))
+                );
+            }
+            let integer = digits_start..self.i;
+
+            let mut fraction = self.i..self.i;
+            if self.peek() == Some(b'.') {
                 self.i += 1;
+                let fraction_start = self.i;
+                if self.skip_digits(10) == 0 {
+                    return Err(self.error(
+                        self.i,
+                        format!("Expected fractional digits, found {}", self.current()),
+                    ));
+                }
+                fraction = fraction_start..self.i;
             }
-            let mut seen_digit = false;
-            while let Some(b'0'..=b'9') = self.peek() {
-                seen_digit = true;
+
+            let mut exponent = 0u32;
+            if matches!(self.peek(), Some(b'e' | b'E')) {
                 self.i += 1;
+                if self.peek() == Some(b'+') {
+                    self.i += 1;
+                }
+                let exponent_start = self.i;
+                if self.skip_digits(10) == 0 {
+                    return Err(self.error(
+                        self.i,
+                        format!("Expected exponent digits, found {}", self.current()),
+                    ));
+                }
+                let digits = std::str::from_utf8(&self.src[exponent_start..self.i]).unwrap();
+                exponent = digits.parse().unwrap_or(u32::MAX);
             }
-            if !seen_digit {
-                return Err(AbiCodecError::ParseError(format!(
-                    "Invalid number starting at position {}",
-                    start
-                )));
+
+            let text = std::str::from_utf8(&self.src[start..self.i]).unwrap();
+            let out_of_range = || {
+                self.error(
+                    start,
+                    format!("Number '{}' does not fit in a 64-bit integer", text),
+                )
+            };
+
+            // The digits without the decimal point, scaled by the exponent minus the number
+            // of fractional digits
+            let mut digits = std::str::from_utf8(&self.src[integer]).unwrap().to_string();
+            digits.push_str(std::str::from_utf8(&self.src[fraction.clone()]).unwrap());
+            let fraction_len = fraction.len() as u32;
+            let mantissa: u128 = digits.parse().map_err(|_| out_of_range())?;
+
+            if mantissa == 0 {
+                Ok(0)
+            } else if exponent >= fraction_len {
+                10u128
+                    .checked_pow(exponent - fraction_len)
+                    .and_then(|scale| mantissa.checked_mul(scale))
+                    .ok_or_else(out_of_range)
+            } else {
+                match 10u128.checked_pow(fraction_len - exponent) {
+                    Some(scale) if mantissa % scale == 0 => Ok(mantissa / scale),
+                    _ => Err(self.error(start, format!("Number '{}' is not an integer", text))),
+                }
             }
-            let s = std::str::from_utf8(&self.src[start..self.i]).unwrap();
-            let n = s.parse::<i64>().map_err(|e| {
-                AbiCodecError::ParseError(format!("Failed to parse number '{}': {}", s, e))
-            })?;
-            Ok(InputValue::Number(n))
         }
     }
 
@@ -251,10 +452,7 @@ pub fn parse_cli_arg(s: &str) -> Result<InputValue, AbiCodecError> {
     let v = p.parse_value()?;
     p.skip_ws();
     if !p.eof() {
-        Err(AbiCodecError::ParseError(format!(
-            "Unexpected trailing characters at position {}",
-            p.i
-        )))
+        Err(p.error(p.i, format!("Unexpected trailing {}", p.current())))
     } else {
         Ok(v)
     }
@@ -285,6 +483,37 @@ mod tests {
             assert_eq!(m31_from_i64(-(P as i64)), M31::from(0u32));
             assert_eq!(m31_from_i64(-((P as i64) + 5)), M31::reduce((P as u64) - 5));
         }
+
+        #[test]
+        fn test_m31_to_i64_balanced() {
+            for n in [0, 1, -1, -5, (P as i64 - 1) / 2, -(P as i64 - 1) / 2] {
+                assert_eq!(m31_to_i64(m31_from_i64(n)), n);
+            }
+        }
+
+        #[test]
+        fn test_display_signed() {
+            let value = CairoMValue::Struct(vec![
+                ("x".to_string(), CairoMValue::Felt(m31_from_i64(-5))),
+                (
+                    "y".to_string(),
+                    CairoMValue::Tuple(vec![
+                        CairoMValue::Felt(M31::from(42)),
+                        CairoMValue::U32(u32::MAX),
+                        CairoMValue::Bool(true),
+                    ]),
+                ),
+                ("z".to_string(), CairoMValue::Array(vec![CairoMValue::Unit])),
+            ]);
+            assert_eq!(
+                value.to_string(),
+                "{ x: 2147483642, y: (42, 4294967295, true), z: [()] }"
+            );
+            assert_eq!(
+                value.display_signed().to_string(),
+                "{ x: -5 (0x7ffffffa), y: (42 (0x2a), 4294967295, true), z: [()] }"
+            );
+        }
     }
 
     // ==================== Parser Tests ====================
@@ -343,10 +572,46 @@ mod tests {
 
         // Error cases
         parse_test!(empty_input, "" => Err("Expected"));
-        parse_test!(incomplete_struct, "{1," => Err("Unexpected character"));
-        parse_test!(incomplete_array, "[1,2," => Err("Unexpected character"));
+        parse_test!(incomplete_struct, "{1," => Err("Unexpected end of input"));
+        parse_test!(incomplete_array, "[1,2," => Err("Unexpected end of input"));
         parse_test!(invalid_bool, "tru" => Err("Invalid boolean"));
         parse_test!(trailing_chars, "{1} extra" => Err("trailing"));
+        parse_test!(hex_without_digits, "0x" => Err("Expected hexadecimal digits"));
+        parse_test!(fractional_number, "1.5" => Err("not an integer"));
+        parse_test!(fractional_scientific, "1.25e1" => Err("not an integer"));
+        parse_test!(overflowing_scientific, "1e19" => Err("does not fit"));
+        parse_test!(overflowing_hex, "0x8000000000000000" => Err("does not fit"));
+        parse_test!(missing_exponent, "2e" => Err("Expected exponent digits"));
+
+        parse_test!(hex_number, "0x2A" => Ok(42.into()));
+        parse_test!(negative_hex_number, "-0x7fffffff" => Ok(InputValue::Number(-0x7fff_ffff)));
+        parse_test!(scientific_number, "1e6" => Ok(1_000_000.into()));
+        parse_test!(scientific_number_with_fraction, "2.5E+3" => Ok(2500.into()));
+        parse_test!(zero_scientific, "0.0e99999" => Ok(0.into()));
+        parse_test!(min_number, "-9223372036854775808" => Ok(InputValue::Number(i64::MIN)));
+
+        parse_test!(array_of_structs, "[{1, 0x2}, {-3, 4e1}, {5, (true, [])}]" => Ok(InputValue::List(vec![
+            InputValue::Struct(vec![1.into(), 2.into()]),
+            InputValue::Struct(vec![InputValue::Number(-3), 40.into()]),
+            InputValue::Struct(vec![
+                5.into(),
+                InputValue::List(vec![InputValue::Bool(true), InputValue::List(vec![])])
+            ]),
+        ])));
+
+        #[test]
+        fn error_positions_are_character_offsets() {
+            let error = |input: &str| match parse_cli_arg(input).unwrap_err() {
+                AbiCodecError::ParseError { position, .. } => position,
+                other => panic!("Expected a parse error, got {other:?}"),
+            };
+            assert_eq!(error("[1, 2; 3]"), 5);
+            assert_eq!(error("{1, [0x]}"), 7);
+            assert_eq!(error("(1, 2.5e0)"), 4);
+            // Positions count characters, not bytes
+            assert_eq!(error("[é, 1]"), 1);
+            assert_eq!(error("(\u{3000}1, 2 3)"), 7);
+        }
     }
 
     // ==================== Integration Tests ====================
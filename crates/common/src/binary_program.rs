@@ -0,0 +1,311 @@
+//! Binary encoding of compiled programs, loadable without parsing their code.
+//!
+//! The encoding is laid out as follows, all integers being little-endian:
+//!
+//! - the magic bytes [`BINARY_PROGRAM_MAGIC`] and the format version (`u32`),
+//! - the number of memory words of the program (`u32`),
+//! - the memory words, each one as its four M31 limbs (`u32`),
+//! - the entrypoints, sorted by name, and metadata of the program, as JSON, up to the end.
+//!
+//! Sorting the entrypoints makes the encoding of a program deterministic.
+//!
+//! The memory words are the flattened instructions and rodata of the program, as the VM loads
+//! them. A [`BinaryProgram`] thus only parses the entrypoints and metadata, and reads the words
+//! straight from its bytes, e.g. from a memory-mapped file, when they are loaded.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::QM31;
+
+use crate::program::{EntrypointInfo, Program, ProgramMetadata};
+
+/// Magic bytes starting the binary encoding of a program
+pub const BINARY_PROGRAM_MAGIC: [u8; 4] = *b"CMPG";
+
+/// Version of the binary encoding of programs
+pub const BINARY_PROGRAM_VERSION: u32 = 1;
+
+/// Size of the header: magic bytes, version and number of memory words
+const HEADER_SIZE: usize = BINARY_PROGRAM_MAGIC.len() + 2 * size_of::<u32>();
+
+/// Size of the encoding of a memory word
+const WORD_SIZE: usize = 4 * size_of::<u32>();
+
+/// Errors that can occur when reading a binary program
+#[derive(Debug, thiserror::Error)]
+pub enum BinaryProgramError {
+    #[error("Not a binary program: missing magic bytes")]
+    InvalidMagic,
+
+    #[error("Unsupported binary program version {0}, expected {BINARY_PROGRAM_VERSION}")]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid binary program: {words} memory words exceed the addressable size")]
+    TooLarge { words: usize },
+
+    #[error("Truncated binary program: {words} memory words need {expected} bytes, found {found}")]
+    Truncated {
+        words: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Invalid program metadata: {0}")]
+    InvalidMetadata(#[from] serde_json::Error),
+}
+
+/// Entrypoints and metadata of a binary program, as encoded
+#[derive(Serialize)]
+struct ProgramInfoRef<'a> {
+    entrypoints: BTreeMap<&'a str, &'a EntrypointInfo>,
+    metadata: &'a ProgramMetadata,
+}
+
+/// Entrypoints and metadata of a binary program, as decoded
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProgramInfo {
+    entrypoints: HashMap<String, EntrypointInfo>,
+    metadata: ProgramMetadata,
+}
+
+/// Returns whether `bytes` start with the magic bytes of a binary program
+pub fn is_binary_program(bytes: &[u8]) -> bool {
+    bytes.starts_with(&BINARY_PROGRAM_MAGIC)
+}
+
+impl Program {
+    /// Encodes the program in the binary format, see [`BinaryProgram`]
+    pub fn to_binary(&self) -> Vec<u8> {
+        let words: Vec<QM31> = self
+            .data
+            .iter()
+            .flat_map(|item| item.to_qm31_vec())
+            .collect();
+        let info = serde_json::to_vec(&ProgramInfoRef {
+            entrypoints: self
+                .entrypoints
+                .iter()
+                .map(|(name, info)| (name.as_str(), info))
+                .collect(),
+            metadata: &self.metadata,
+        })
+        .expect("program metadata serializes to JSON");
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + words.len() * WORD_SIZE + info.len());
+        bytes.extend_from_slice(&BINARY_PROGRAM_MAGIC);
+        bytes.extend_from_slice(&BINARY_PROGRAM_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(words.len() as u32).to_le_bytes());
+        for word in words {
+            for limb in word.to_m31_array() {
+                bytes.extend_from_slice(&limb.0.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&info);
+        bytes
+    }
+}
+
+/// A program in the binary format, backed by its encoded bytes
+///
+/// Only the entrypoints and metadata are decoded up front: the memory words are read from the
+/// bytes when iterated over, and their instructions are decoded by the VM when executed.
+#[derive(Debug)]
+pub struct BinaryProgram<B> {
+    bytes: B,
+    /// Number of memory words of the program
+    len: usize,
+    /// Entrypoint names mapped to their information
+    pub entrypoints: HashMap<String, EntrypointInfo>,
+    /// Program metadata
+    pub metadata: ProgramMetadata,
+}
+
+impl<B: AsRef<[u8]>> BinaryProgram<B> {
+    /// Reads a binary program from its encoded bytes
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`BinaryProgramError`] if the bytes are not a binary program of the supported
+    /// version, if they are shorter than its memory words, or if its metadata can't be decoded.
+    pub fn from_bytes(bytes: B) -> Result<Self, BinaryProgramError> {
+        let data = bytes.as_ref();
+        if !is_binary_program(data) || data.len() < HEADER_SIZE {
+            return Err(BinaryProgramError::InvalidMagic);
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes(data[offset..offset + 4].try_into().expect("4 bytes"))
+        };
+
+        let version = read_u32(BINARY_PROGRAM_MAGIC.len());
+        if version != BINARY_PROGRAM_VERSION {
+            return Err(BinaryProgramError::UnsupportedVersion(version));
+        }
+
+        let len = read_u32(BINARY_PROGRAM_MAGIC.len() + 4) as usize;
+        let code_end = len
+            .checked_mul(WORD_SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or(BinaryProgramError::TooLarge { words: len })?;
+        if data.len() < code_end {
+            return Err(BinaryProgramError::Truncated {
+                words: len,
+                expected: code_end,
+                found: data.len(),
+            });
+        }
+
+        let ProgramInfo {
+            entrypoints,
+            metadata,
+        } = serde_json::from_slice(&data[code_end..])?;
+        Ok(Self {
+            bytes,
+            len,
+            entrypoints,
+            metadata,
+        })
+    }
+
+    /// Get the full entrypoint information for a given function name
+    pub fn get_entrypoint(&self, name: &str) -> Option<&EntrypointInfo> {
+        self.entrypoints.get(name)
+    }
+
+    /// Number of memory words of the program
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the program is empty
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the memory words of the program, read from its bytes
+    pub fn memory_words(&self) -> impl ExactSizeIterator<Item = QM31> + '_ {
+        self.bytes.as_ref()[HEADER_SIZE..HEADER_SIZE + self.len * WORD_SIZE]
+            .chunks_exact(WORD_SIZE)
+            .map(|word| {
+                let limb = |i: usize| {
+                    let bytes = word[4 * i..4 * i + 4].try_into().expect("4 bytes");
+                    M31::from(u32::from_le_bytes(bytes))
+                };
+                QM31::from_m31_array([limb(0), limb(1), limb(2), limb(3)])
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{AbiSlot, AbiType, ProgramData};
+    use crate::{AbortInfo, Instruction};
+
+    fn program() -> Program {
+        let entrypoints = HashMap::from([(
+            "main".to_string(),
+            EntrypointInfo {
+                pc: 0,
+                params: vec![AbiSlot {
+                    name: "x".to_string(),
+                    ty: AbiType::Felt,
+                    default: None,
+                }],
+                returns: vec![],
+            },
+        )]);
+        let metadata = ProgramMetadata {
+            abort_messages: [(
+                1,
+                AbortInfo {
+                    message: "unreachable".to_string(),
+                    location: None,
+                },
+            )]
+            .into(),
+            ..Default::default()
+        };
+        Program::new(
+            vec![
+                ProgramData::Instruction(Instruction::StoreImm {
+                    imm: M31::from(42),
+                    dst_off: M31::from(0),
+                }),
+                ProgramData::Instruction(Instruction::Ret {}),
+                ProgramData::Value(QM31::from_u32_unchecked(1, 2, 3, 4)),
+            ],
+            entrypoints,
+            metadata,
+        )
+    }
+
+    #[test]
+    fn test_binary_program_roundtrip() {
+        let program = program();
+        let binary = BinaryProgram::from_bytes(program.to_binary()).unwrap();
+
+        let words: Vec<QM31> = program
+            .data
+            .iter()
+            .flat_map(|item| item.to_qm31_vec())
+            .collect();
+        assert_eq!(binary.len(), words.len());
+        assert_eq!(binary.memory_words().collect::<Vec<_>>(), words);
+        assert_eq!(binary.entrypoints, program.entrypoints);
+        assert_eq!(binary.metadata, program.metadata);
+    }
+
+    #[test]
+    fn test_binary_program_is_deterministic() {
+        let names = ["main", "helper", "entry", "zeta", "alpha"];
+        let with_entrypoints = |names: &[&str]| {
+            let mut program = program();
+            let main = program.entrypoints["main"].clone();
+            for name in names {
+                program.entrypoints.insert(name.to_string(), main.clone());
+            }
+            program
+        };
+        let forward = with_entrypoints(&names);
+        let backward = with_entrypoints(&names.iter().rev().copied().collect::<Vec<_>>());
+
+        assert_eq!(forward.to_binary(), backward.to_binary());
+    }
+
+    #[test]
+    fn test_binary_program_errors() {
+        let bytes = program().to_binary();
+
+        assert!(matches!(
+            BinaryProgram::from_bytes(b"{\"data\": []}".as_slice()),
+            Err(BinaryProgramError::InvalidMagic)
+        ));
+
+        let mut newer = bytes.clone();
+        newer[4..8].copy_from_slice(&(BINARY_PROGRAM_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            BinaryProgram::from_bytes(newer),
+            Err(BinaryProgramError::UnsupportedVersion(_))
+        ));
+
+        assert!(matches!(
+            BinaryProgram::from_bytes(&bytes[..HEADER_SIZE + WORD_SIZE]),
+            Err(BinaryProgramError::Truncated { .. })
+        ));
+
+        let mut oversized = bytes.clone();
+        oversized[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            BinaryProgram::from_bytes(oversized),
+            Err(BinaryProgramError::TooLarge { .. } | BinaryProgramError::Truncated { .. })
+        ));
+
+        assert!(matches!(
+            BinaryProgram::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BinaryProgramError::InvalidMetadata(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+//! # Textual CASM
+//!
+//! Assembler and disassembler for a human-readable form of Cairo-M assembly, to
+//! hand-write or patch snippets, write instruction-level tests and inspect programs.
+//!
+//! ## Syntax
+//!
+//! ```text
+//! // Computes [fp + 2] = 10 + 20
+//! main:
+//!     store_imm 10, 0        // [fp + 0] = 10
+//!     store_imm 20, 1
+//!     store_add_fp_fp 0, 1, 2
+//! loop: jmp_rel_imm loop
+//! ```
+//!
+//! - One instruction per line: a mnemonic (see [`InstructionInfo::mnemonic`], the
+//!   variant name is also accepted) followed by its operands in encoding order,
+//!   separated by commas or whitespace.
+//! - Operands are decimal or `0x`-prefixed hexadecimal integers. Negative values are
+//!   reduced modulo the M31 prime, so `-1` is `2^31 - 2`.
+//! - `name:` defines a label at the address of the next instruction. A label can be
+//!   used in place of an immediate operand: it resolves to the address of the label,
+//!   or to its offset from the current instruction for relative jumps
+//!   (`jmp_rel_imm`, `jnz_fp_imm`).
+//! - `//` starts a comment that runs to the end of the line.
+//!
+//! Addresses count QM31 words, as in the program memory layout.
+
+use std::collections::HashMap;
+
+use stwo_prover::core::fields::m31::{M31, P};
+use thiserror::Error;
+
+use crate::instruction::{InstructionInfo, OperandType};
+use crate::{Instruction, InstructionError};
+
+/// Error raised while assembling CASM text. Lines are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CasmError {
+    #[error("line {line}: unknown instruction `{mnemonic}`")]
+    UnknownMnemonic { line: usize, mnemonic: String },
+    #[error("line {line}: `{mnemonic}` takes {expected} operands, found {found}")]
+    OperandCount {
+        line: usize,
+        mnemonic: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("line {line}: invalid operand `{operand}`")]
+    InvalidOperand { line: usize, operand: String },
+    #[error("line {line}: label `{label}` used for memory operand `{operand}`")]
+    LabelAsMemoryOperand {
+        line: usize,
+        label: String,
+        operand: &'static str,
+    },
+    #[error("line {line}: undefined label `{label}`")]
+    UndefinedLabel { line: usize, label: String },
+    #[error("line {line}: label `{label}` is already defined")]
+    DuplicateLabel { line: usize, label: String },
+    #[error("line {line}: {source}")]
+    Instruction {
+        line: usize,
+        #[source]
+        source: InstructionError,
+    },
+}
+
+/// An instruction line, before label resolution.
+struct ParsedLine<'a> {
+    line: usize,
+    pc: u32,
+    info: &'static InstructionInfo,
+    operands: Vec<&'a str>,
+}
+
+/// Assembles CASM text into instructions.
+///
+/// ## Errors
+///
+/// Returns a [`CasmError`] pointing at the first invalid line.
+pub fn parse(source: &str) -> Result<Vec<Instruction>, CasmError> {
+    let mut labels = HashMap::<&str, u32>::new();
+    let mut lines = vec![];
+    let mut pc = 0;
+
+    // First pass: collect labels and instruction addresses.
+    for (index, raw_line) in source.lines().enumerate() {
+        let line = index + 1;
+        let mut text = raw_line.split("//").next().unwrap_or_default().trim();
+
+        while let Some((label, rest)) = split_label(text) {
+            if labels.insert(label, pc).is_some() {
+                return Err(CasmError::DuplicateLabel {
+                    line,
+                    label: label.to_string(),
+                });
+            }
+            text = rest;
+        }
+
+        let mut tokens = text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty());
+        let Some(mnemonic) = tokens.next() else {
+            continue;
+        };
+        let info =
+            InstructionInfo::for_mnemonic(mnemonic).ok_or_else(|| CasmError::UnknownMnemonic {
+                line,
+                mnemonic: mnemonic.to_string(),
+            })?;
+        let operands = tokens.collect::<Vec<_>>();
+        if operands.len() != info.operands.len() {
+            return Err(CasmError::OperandCount {
+                line,
+                mnemonic: mnemonic.to_string(),
+                expected: info.operands.len(),
+                found: operands.len(),
+            });
+        }
+
+        lines.push(ParsedLine {
+            line,
+            pc,
+            info,
+            operands,
+        });
+        pc += info.size_in_qm31s();
+    }
+
+    // Second pass: resolve operands.
+    lines
+        .into_iter()
+        .map(|parsed| {
+            let mut values = vec![M31::from(parsed.info.opcode)];
+            for (operand, token) in parsed.info.operands.iter().zip(&parsed.operands) {
+                values.push(resolve_operand(
+                    &parsed,
+                    operand.name,
+                    operand.kind,
+                    token,
+                    &labels,
+                )?);
+            }
+            Instruction::decode(&values).map_err(|source| CasmError::Instruction {
+                line: parsed.line,
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Splits a leading `label:` from a line.
+fn split_label(text: &str) -> Option<(&str, &str)> {
+    let (label, rest) = text.split_once(':')?;
+    let label = label.trim();
+    is_identifier(label).then(|| (label, rest.trim()))
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Resolves an operand token to its encoded value.
+fn resolve_operand(
+    parsed: &ParsedLine<'_>,
+    name: &'static str,
+    kind: OperandType,
+    token: &str,
+    labels: &HashMap<&str, u32>,
+) -> Result<M31, CasmError> {
+    if let Some(value) = parse_integer(token) {
+        return Ok(value);
+    }
+    if !is_identifier(token) {
+        return Err(CasmError::InvalidOperand {
+            line: parsed.line,
+            operand: token.to_string(),
+        });
+    }
+    if kind != OperandType::Immediate {
+        return Err(CasmError::LabelAsMemoryOperand {
+            line: parsed.line,
+            label: token.to_string(),
+            operand: name,
+        });
+    }
+
+    let &address = labels.get(token).ok_or_else(|| CasmError::UndefinedLabel {
+        line: parsed.line,
+        label: token.to_string(),
+    })?;
+    let is_relative = name == "offset" && matches!(parsed.info.name, "JmpRelImm" | "JnzFpImm");
+    if is_relative {
+        Ok(M31::from(address as i32 - parsed.pc as i32))
+    } else {
+        Ok(M31::from(address))
+    }
+}
+
+/// Parses a decimal or hexadecimal integer, reducing negative values modulo P.
+fn parse_integer(token: &str) -> Option<M31> {
+    let (negative, digits) = token
+        .strip_prefix('-')
+        .map_or((false, token), |digits| (true, digits));
+    let value = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<u32>().ok()?,
+    };
+    if value >= P {
+        return None;
+    }
+    Some(if negative {
+        -M31::from(value)
+    } else {
+        M31::from(value)
+    })
+}
+
+/// Formats an operand, printing values above `P / 2` as negative numbers.
+fn format_operand(value: M31) -> String {
+    if value.0 > P / 2 {
+        format!("-{}", P - value.0)
+    } else {
+        value.0.to_string()
+    }
+}
+
+/// Disassembles instructions into CASM text, one instruction per line.
+///
+/// Labels can't be recovered from encoded instructions: jump targets are printed as
+/// numbers. The output assembles back to the same instructions with [`parse`].
+pub fn format(instructions: &[Instruction]) -> String {
+    let mut out = String::new();
+    for instruction in instructions {
+        let info = instruction.describe();
+        out.push_str(info.mnemonic);
+        let operands = instruction
+            .encode()
+            .iter()
+            .skip(1)
+            .map(|value| format_operand(*value))
+            .collect::<Vec<_>>();
+        if !operands.is_empty() {
+            out.push(' ');
+            out.push_str(&operands.join(", "));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_integer() {
+        assert_eq!(parse_integer("42"), Some(M31::from(42)));
+        assert_eq!(parse_integer("0x10"), Some(M31::from(16)));
+        assert_eq!(parse_integer("-4"), Some(M31::from(P - 4)));
+        assert_eq!(parse_integer("2147483647"), None);
+        assert_eq!(parse_integer("foo"), None);
+    }
+
+    #[test]
+    fn test_split_label() {
+        assert_eq!(split_label("main:"), Some(("main", "")));
+        assert_eq!(split_label("loop: ret"), Some(("loop", "ret")));
+        assert_eq!(split_label("store_imm 1, 2"), None);
+    }
+}
@@ -7,11 +7,26 @@ use stwo_prover::core::fields::qm31::QM31;
 use crate::State;
 use crate::state::MemoryEntry;
 
+/// A clock update scheduled by the VM for a memory cell left untouched for too long.
+///
+/// The prover range checks the clock difference between two consecutive accesses of a
+/// memory cell. When a cell is accessed again after more than the maximum clock difference,
+/// intermediate clock updates refresh the clock of the cell without changing its value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClockUpdate {
+    /// The address of the refreshed memory cell
+    pub addr: M31,
+    /// The clock of the refresh, counted in steps from the start of the segment
+    pub clock: M31,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Segment {
     pub initial_memory: HashMap<M31, (QM31, M31, M31)>,
     pub memory_trace: RefCell<Vec<MemoryEntry>>,
     pub trace: Vec<State>,
+    /// Clock updates of the segment, in increasing clock order for each address.
+    pub clock_updates: Vec<ClockUpdate>,
 }
 
 impl Segment {
@@ -22,17 +22,141 @@ pub enum InstructionError {
     InvalidInstructionType(&'static str),
 }
 
-// User-facing marker for field kinds used in the macro input.
-// Note: This enum is only used for declarative purposes in the macro callsite;
-// it is not stored in Instruction and has no runtime impact.
-#[allow(dead_code)]
+/// Version of the instruction set, bumped on every incompatible change of the opcodes or
+/// of their operands.
+///
+/// Programs record the version they were compiled for, see
+/// [`ProgramMetadata::check_isa_version`](crate::ProgramMetadata::check_isa_version).
+pub const ISA_VERSION: u32 = 1;
+
+/// Largest exponent supported by [`Instruction::StorePowFpImm`].
+///
+/// The prover component has one column per power of the base, so the range is kept small.
+pub const MAX_POW_EXPONENT: u32 = 8;
+
+/// Number of elements of the Poseidon2 state.
+pub const POSEIDON2_STATE_SIZE: usize = 16;
+
+/// Number of felts absorbed at once by [`Instruction::Poseidon2AbsorbFpFp`].
+pub const POSEIDON2_SPONGE_RATE: usize = 8;
+
+/// Number of felts of the sponge state and digest, the first elements of the output state
+/// of a permutation.
+///
+/// The sponge state is chained between permutations in memory: it is wide enough for the
+/// sponge to keep the collision resistance of the permutation, unlike a single felt.
+pub const POSEIDON2_DIGEST_SIZE: usize = 8;
+
+/// Domain separator of the permutations of [`Instruction::Poseidon2SqueezeFpFp`].
+///
+/// The domain separator is stored in the last element of the Poseidon2 state, which is
+/// zero for the Merkle tree hashes, so that a sponge digest never collides with them.
+pub const POSEIDON2_SQUEEZE_DOMAIN: u32 = 2;
+
+/// Returns the Poseidon2 input state of [`Instruction::Poseidon2AbsorbFpFp`]:
+/// `[state[0], ..., state[DIGEST - 1], block[0], ..., block[RATE - 1]]`.
+pub fn poseidon2_absorb_input(
+    state: [M31; POSEIDON2_DIGEST_SIZE],
+    block: [M31; POSEIDON2_SPONGE_RATE],
+) -> [M31; POSEIDON2_STATE_SIZE] {
+    let mut input = [M31::from(0); POSEIDON2_STATE_SIZE];
+    input[..POSEIDON2_DIGEST_SIZE].copy_from_slice(&state);
+    input[POSEIDON2_DIGEST_SIZE..].copy_from_slice(&block);
+    input
+}
+
+/// Returns the Poseidon2 input state of [`Instruction::Poseidon2SqueezeFpFp`]:
+/// `[state[0], ..., state[DIGEST - 1], len, 0, ..., 0, POSEIDON2_SQUEEZE_DOMAIN]`.
+pub fn poseidon2_squeeze_input(
+    state: [M31; POSEIDON2_DIGEST_SIZE],
+    len: M31,
+) -> [M31; POSEIDON2_STATE_SIZE] {
+    let mut input = [M31::from(0); POSEIDON2_STATE_SIZE];
+    input[..POSEIDON2_DIGEST_SIZE].copy_from_slice(&state);
+    input[POSEIDON2_DIGEST_SIZE] = len;
+    input[POSEIDON2_STATE_SIZE - 1] = M31::from(POSEIDON2_SQUEEZE_DOMAIN);
+    input
+}
+
+/// Kind of an instruction operand.
+///
+/// Only used to describe instructions: it is not stored in [`Instruction`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[doc(hidden)]
-pub(crate) enum OperandType {
+pub enum OperandType {
+    /// Value encoded in the instruction.
     Immediate,
+    /// Offset from `fp` of a value of the given type in memory.
     Memory(DataType),
 }
 
+impl std::fmt::Display for OperandType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Immediate => write!(f, "imm"),
+            Self::Memory(DataType::Felt) => write!(f, "felt"),
+            Self::Memory(DataType::U32) => write!(f, "u32"),
+        }
+    }
+}
+
+/// Description of an explicit instruction operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperandInfo {
+    /// Field name of the operand.
+    pub name: &'static str,
+    pub kind: OperandType,
+}
+
+/// Description of an instruction of the instruction set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionInfo {
+    /// Name of the [`Instruction`] variant.
+    pub name: &'static str,
+    /// Name of the instruction in textual assembly, see [`crate::casm`].
+    pub mnemonic: &'static str,
+    pub opcode: u32,
+    /// Operands encoded after the opcode, in order.
+    pub operands: &'static [OperandInfo],
+    /// Memory operands accessed without being encoded, e.g. the saved `fp` and `pc` of `Ret`.
+    pub implicit_operands: &'static [OperandType],
+    /// Lines of the documentation of the variant, describing its semantics.
+    pub doc: &'static [&'static str],
+}
+
+impl InstructionInfo {
+    /// Returns the semantics of the instruction, as documented on the variant.
+    pub fn semantics(&self) -> String {
+        self.doc
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the size of the encoded instruction in M31 elements (including opcode).
+    pub const fn size_in_m31s(&self) -> usize {
+        1 + self.operands.len()
+    }
+
+    /// Returns the description of the instruction with the given opcode.
+    pub fn for_opcode(opcode: u32) -> Option<&'static Self> {
+        INSTRUCTION_SET.iter().find(|info| info.opcode == opcode)
+    }
+
+    /// Returns the description of the instruction with the given mnemonic or variant name.
+    /// The lookup is case-insensitive.
+    pub fn for_mnemonic(mnemonic: &str) -> Option<&'static Self> {
+        INSTRUCTION_SET.iter().find(|info| {
+            info.mnemonic.eq_ignore_ascii_case(mnemonic) || info.name.eq_ignore_ascii_case(mnemonic)
+        })
+    }
+
+    /// Returns the size of the encoded instruction in QM31 elements.
+    pub const fn size_in_qm31s(&self) -> u32 {
+        self.size_in_m31s().div_ceil(4) as u32
+    }
+}
+
 // Returns the number of limbs for a given operand kind.
 // Immediate operands do not contribute to memory accesses.
 macro_rules! __mem_accesses_for_kind {
@@ -98,6 +222,7 @@ macro_rules! __mem_accesses_for_kind {
 macro_rules! instructions {
     (
         $(
+            $(#[doc = $doc:literal])*
             $variant:ident = $opcode:literal {
                 $( $field:ident : $kind:tt ),* $(,)?
             } $(, implicit_operands: [$($implicit_kind:tt),* $(,)?])? $(;)?
@@ -107,9 +232,25 @@ macro_rules! instructions {
         /// with its required named fields
         #[derive(Clone, Copy, Debug, PartialEq, Eq)]
         pub enum Instruction {
-            $( $variant { $( $field: M31 ),* }, )*
+            $( $(#[doc = $doc])* $variant { $( $field: M31 ),* }, )*
         }
 
+        /// Description of every instruction, ordered as declared.
+        // Operand kinds are parenthesized in the macro input.
+        #[allow(unused_parens)]
+        pub const INSTRUCTION_SET: &[InstructionInfo] = &[
+            $(
+                InstructionInfo {
+                    name: stringify!($variant),
+                    mnemonic: paste! { stringify!([<$variant:snake>]) },
+                    opcode: $opcode,
+                    operands: &[ $( OperandInfo { name: stringify!($field), kind: $kind } ),* ],
+                    implicit_operands: &[ $( $( $implicit_kind ),* )? ],
+                    doc: &[ $( $doc ),* ],
+                },
+            )*
+        ];
+
         // Generate opcode constants
         paste! { $( pub const [<$variant:snake:upper>]: u32 = $opcode; )* }
 
@@ -133,6 +274,15 @@ macro_rules! instructions {
                 }
             }
 
+            /// Get the description of this instruction
+            pub const fn describe(&self) -> &'static InstructionInfo {
+                let mut i = 0;
+                while INSTRUCTION_SET[i].opcode != self.opcode_value() {
+                    i += 1;
+                }
+                &INSTRUCTION_SET[i]
+            }
+
             /// Get the size of this instruction in M31 elements (including opcode)
             pub const fn size_in_m31s(&self) -> usize {
                 match self {
@@ -313,25 +463,25 @@ macro_rules! extract_as {
 // Define all instructions with their opcodes and typed fields
 instructions! {
     // Arithmetic operations: order matters for the prover, see store_fp_fp.rs
-    // [fp + dst_off] = [fp + src0_off] + [fp + src1_off]
+    /// [fp + dst_off] = [fp + src0_off] + [fp + src1_off]
     StoreAddFpFp = 0 {
         src0_off: (OperandType::Memory(DataType::Felt)),
         src1_off: (OperandType::Memory(DataType::Felt)),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = [fp + src0_off] - [fp + src1_off]
+    /// [fp + dst_off] = [fp + src0_off] - [fp + src1_off]
     StoreSubFpFp = 1 {
         src0_off: (OperandType::Memory(DataType::Felt)),
         src1_off: (OperandType::Memory(DataType::Felt)),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = [fp + src0_off] * [fp + src1_off]
+    /// [fp + dst_off] = [fp + src0_off] * [fp + src1_off]
     StoreMulFpFp = 2 {
         src0_off: (OperandType::Memory(DataType::Felt)),
         src1_off: (OperandType::Memory(DataType::Felt)),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = [fp + src0_off] / [fp + src1_off]
+    /// [fp + dst_off] = [fp + src0_off] / [fp + src1_off]
     StoreDivFpFp = 3 {
         src0_off: (OperandType::Memory(DataType::Felt)),
         src1_off: (OperandType::Memory(DataType::Felt)),
@@ -339,13 +489,13 @@ instructions! {
     };
 
     // Arithmetic operations with immediate: order matters for the prover, see store_fp_imm.rs
-    // [fp + dst_off] = [fp + src_off] + imm
+    /// [fp + dst_off] = [fp + src_off] + imm
     StoreAddFpImm = 4 {
         src_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = [fp + src_off] * imm
+    /// [fp + dst_off] = [fp + src_off] * imm
     StoreMulFpImm = 6 {
         src_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
@@ -353,21 +503,84 @@ instructions! {
     };
 
     // Comparison operations
-    // [fp + dst_off] = [fp + src_off] <= imm
+    /// [fp + dst_off] = [fp + src_off] <= imm
     StoreLeFpImm = 48 {
         src_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
 
-    // assert [fp + src_off] == imm
+    // Exponentiation by a small immediate exponent
+    /// [fp + dst_off] = [fp + src_off] ^ imm, with 2 <= imm <= MAX_POW_EXPONENT
+    StorePowFpImm = 49 {
+        src_off: (OperandType::Memory(DataType::Felt)),
+        imm: (OperandType::Immediate),
+        dst_off: (OperandType::Memory(DataType::Felt)),
+    };
+
+    /// assert [fp + src_off] == imm
     AssertEqFpImm = 50 {
         src_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
     };
 
+    // Poseidon2 sponge: a sequence of felts is hashed by absorbing it in zero-padded blocks
+    // of POSEIDON2_SPONGE_RATE felts into a zero state of POSEIDON2_DIGEST_SIZE felts, then
+    // squeezing the state with the length of the sequence. States and digests are stored as
+    // POSEIDON2_DIGEST_SIZE consecutive felts: the implicit operands are the other felts of
+    // the states, block and digests.
+    /// ([fp + dst_off], ..., [fp + dst_off + 7]) = poseidon2_absorb(([fp + state_off], ..., [fp + state_off + 7]), ([fp + src_off], ..., [fp + src_off + 7]))
+    Poseidon2AbsorbFpFp = 51 {
+        state_off: (OperandType::Memory(DataType::Felt)),
+        src_off: (OperandType::Memory(DataType::Felt)),
+        dst_off: (OperandType::Memory(DataType::Felt)),
+    }, implicit_operands: [
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+    ];
+    /// ([fp + dst_off], ..., [fp + dst_off + 7]) = poseidon2_squeeze(([fp + state_off], ..., [fp + state_off + 7]), [fp + len_off])
+    Poseidon2SqueezeFpFp = 52 {
+        state_off: (OperandType::Memory(DataType::Felt)),
+        len_off: (OperandType::Memory(DataType::Felt)),
+        dst_off: (OperandType::Memory(DataType::Felt)),
+    }, implicit_operands: [
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+        (OperandType::Memory(DataType::Felt)),
+    ];
+
     // Memory operations
-    // [fp + dst_off] = [[fp + base_off] + imm]
+    /// [fp + dst_off] = [[fp + base_off] + imm]
     StoreDoubleDerefFp = 8 {
         base_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
@@ -375,7 +588,7 @@ instructions! {
     }, implicit_operands: [
         (OperandType::Memory(DataType::Felt)),
     ];
-    // [fp + dst_off] = [[fp + base_off] + [fp + offset_off]]
+    /// [fp + dst_off] = [[fp + base_off] + [fp + offset_off]]
     StoreDoubleDerefFpFp = 42 {
         base_off: (OperandType::Memory(DataType::Felt)),
         offset_off: (OperandType::Memory(DataType::Felt)),
@@ -383,20 +596,20 @@ instructions! {
     }, implicit_operands: [
         (OperandType::Memory(DataType::Felt)),
     ];
-    // [fp + dst_off] = imm
+    /// [fp + dst_off] = imm
     StoreImm = 9 {
         imm: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = fp + imm
+    /// [fp + dst_off] = fp + imm
     StoreFramePointer = 43 {
         imm: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
 
     // Call operations
-    // call abs imm
     // Implicitly accesses return `pc` and current `fp` as two Felt values.
+    /// call abs imm
     CallAbsImm = 10 {
         frame_off: (OperandType::Immediate),
         target: (OperandType::Immediate),
@@ -404,48 +617,48 @@ instructions! {
         (OperandType::Memory(DataType::Felt)),
         (OperandType::Memory(DataType::Felt)),
     ];
-    // ret
     // Ret is a special case: it implicitly accesses `pc` and `fp` as two Felt
     // operands. We model these as implicit memory operands so that downstream
     // components can consume the corresponding memory log entries.
+    /// ret
     Ret = 11 {}, implicit_operands: [
         (OperandType::Memory(DataType::Felt)),
         (OperandType::Memory(DataType::Felt)),
     ];
 
     // Jump operations
-    // jmp abs imm
+    /// jmp abs imm
     JmpAbsImm = 12 { target: (OperandType::Immediate) };
-    // jmp rel imm
+    /// jmp rel imm
     JmpRelImm = 13 { offset: (OperandType::Immediate) };
 
     // Conditional jumps
-    // jmp rel imm if [fp + cond_off] != 0
+    /// jmp rel imm if [fp + cond_off] != 0
     JnzFpImm = 14 {
         cond_off: (OperandType::Memory(DataType::Felt)),
         offset: (OperandType::Immediate),
     };
 
     // U32 operations with FP operands
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) + u32([fp + src1_off], [fp + src1_off + 1])
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) + u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreAddFpFp = 15 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) - u32([fp + src1_off], [fp + src1_off + 1])
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) - u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreSubFpFp = 16 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) * u32([fp + src1_off], [fp + src1_off + 1])
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) * u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreMulFpFp = 17 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) / u32([fp + src1_off], [fp + src1_off + 1])
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) / u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreDivRemFpFp = 18 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
@@ -454,21 +667,21 @@ instructions! {
     };
 
     // U32 operations with immediate
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) + u32(imm_lo, imm_hi)
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) + u32(imm_lo, imm_hi)
     U32StoreAddFpImm = 19 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) * u32(imm_lo, imm_hi)
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) * u32(imm_lo, imm_hi)
     U32StoreMulFpImm = 21 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) / u32(imm_lo, imm_hi)
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) / u32(imm_lo, imm_hi)
     U32StoreDivRemFpImm = 22 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
@@ -478,7 +691,7 @@ instructions! {
     };
 
     // U32 Memory operations
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32(imm_lo, imm_hi)
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32(imm_lo, imm_hi)
     U32StoreImm = 23 {
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
@@ -486,13 +699,13 @@ instructions! {
     };
 
     // U32 Comparison operations
-    // [fp + dst_off] = u32([fp + src0_off], [fp + src0_off + 1]) == u32([fp + src1_off], [fp + src1_off + 1])
+    /// [fp + dst_off] = u32([fp + src0_off], [fp + src0_off + 1]) == u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreEqFpFp = 24 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = u32([fp + src0_off], [fp + src0_off + 1]) < u32([fp + src1_off], [fp + src1_off + 1])
+    /// [fp + dst_off] = u32([fp + src0_off], [fp + src0_off + 1]) < u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreLtFpFp = 28 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
@@ -500,14 +713,14 @@ instructions! {
     };
 
     // U32 Comparison operations with immediate
-    // [fp + dst_off] = u32([fp + src_off], [fp + src_off + 1]) == u32(imm_lo, imm_hi)
+    /// [fp + dst_off] = u32([fp + src_off], [fp + src_off + 1]) == u32(imm_lo, imm_hi)
     U32StoreEqFpImm = 30 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::Felt)),
     };
-    // [fp + dst_off] = u32([fp + src_off], [fp + src_off + 1]) < u32(imm_lo, imm_hi)
+    /// [fp + dst_off] = u32([fp + src_off], [fp + src_off + 1]) < u32(imm_lo, imm_hi)
     U32StoreLtFpImm = 34 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
@@ -516,17 +729,19 @@ instructions! {
     };
 
     // U32 Bitwise operations
-    // u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) &/|/^ u32([fp + src1_off], [fp + src1_off + 1])
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) & u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreAndFpFp = 36 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) | u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreOrFpFp = 37 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src0_off], [fp + src0_off + 1]) ^ u32([fp + src1_off], [fp + src1_off + 1])
     U32StoreXorFpFp = 38 {
         src0_off: (OperandType::Memory(DataType::U32)),
         src1_off: (OperandType::Memory(DataType::U32)),
@@ -534,18 +749,21 @@ instructions! {
     };
 
     // U32 Bitwise operations with immediate
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) & u32(imm_lo, imm_hi)
     U32StoreAndFpImm = 39 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) | u32(imm_lo, imm_hi)
     U32StoreOrFpImm = 40 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
         imm_hi: (OperandType::Immediate),
         dst_off: (OperandType::Memory(DataType::U32)),
     };
+    /// u32([fp + dst_off], [fp + dst_off + 1]) = u32([fp + src_off], [fp + src_off + 1]) ^ u32(imm_lo, imm_hi)
     U32StoreXorFpImm = 41 {
         src_off: (OperandType::Memory(DataType::U32)),
         imm_lo: (OperandType::Immediate),
@@ -554,7 +772,7 @@ instructions! {
     };
 
     // Reverse double deref operations - store TO computed addresses
-    // [[fp + base_off] + imm] = [fp + src_off]
+    /// [[fp + base_off] + imm] = [fp + src_off]
     StoreToDoubleDerefFpImm = 44 {
         base_off: (OperandType::Memory(DataType::Felt)),
         imm: (OperandType::Immediate),
@@ -562,7 +780,7 @@ instructions! {
     }, implicit_operands: [
         (OperandType::Memory(DataType::Felt)),
     ];
-    // [[fp + base_off] + [fp + offset_off]] = [fp + src_off]
+    /// [[fp + base_off] + [fp + offset_off]] = [fp + src_off]
     StoreToDoubleDerefFpFp = 45 {
         base_off: (OperandType::Memory(DataType::Felt)),
         offset_off: (OperandType::Memory(DataType::Felt)),
@@ -572,7 +790,9 @@ instructions! {
     ];
 
     // Print operations for debugging
+    /// print [fp + offset]
     PrintM31 = 46 { offset: (OperandType::Memory(DataType::Felt)) };
+    /// print u32([fp + offset], [fp + offset + 1])
     PrintU32 = 47 { offset: (OperandType::Memory(DataType::U32)) };
 }
 
@@ -589,6 +809,56 @@ impl From<&Instruction> for SmallVec<[M31; INSTRUCTION_MAX_SIZE]> {
 }
 
 impl Instruction {
+    /// Encodes the instruction as its opcode followed by its operands.
+    pub fn encode(&self) -> SmallVec<[M31; INSTRUCTION_MAX_SIZE]> {
+        self.to_smallvec()
+    }
+
+    /// Decodes the instruction at the start of `values`.
+    ///
+    /// Trailing values are ignored: use [`Self::size_in_m31s`] on the decoded instruction
+    /// to find the start of the next one.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`InstructionError`] if the opcode is unknown or `values` is too short
+    /// to hold all the operands.
+    pub fn decode(values: &[M31]) -> Result<Self, InstructionError> {
+        let opcode = values.first().ok_or(InstructionError::SizeMismatch {
+            expected: 1,
+            found: 0,
+        })?;
+        let size = Self::size_in_m31s_for_opcode(opcode.0)
+            .ok_or(InstructionError::InvalidOpcode(*opcode))?;
+        if values.len() < size {
+            return Err(InstructionError::SizeMismatch {
+                expected: size,
+                found: values.len(),
+            });
+        }
+        Self::try_from(SmallVec::from_slice(&values[..size]))
+    }
+
+    /// Returns true for the debug print instructions.
+    ///
+    /// These instructions are proven as a plain pc increment: the VM captures their output
+    /// in a log buffer and reads the printed cells without recording the accesses in the
+    /// memory trace.
+    pub const fn is_debug_print(&self) -> bool {
+        matches!(self, Self::PrintM31 { .. } | Self::PrintU32 { .. })
+    }
+
+    /// Get the number of operand memory accesses (as limbs) recorded in the memory trace
+    ///
+    /// This is [`Self::memory_accesses`], except for debug prints whose reads are not traced.
+    pub const fn traced_memory_accesses(&self) -> usize {
+        if self.is_debug_print() {
+            0
+        } else {
+            self.memory_accesses()
+        }
+    }
+
     /// Convert instruction to QM31 values for memory storage
     /// Instructions are padded with zeros to align to QM31 boundaries
     pub fn to_qm31_vec(&self) -> Vec<QM31> {
@@ -653,3 +923,35 @@ impl<'de> Deserialize<'de> for Instruction {
         }
     }
 }
+
+/// Renders the instruction set as a Markdown table.
+///
+/// Used to generate the instruction set reference, so that it stays in sync with the code.
+pub fn isa_markdown() -> String {
+    let mut out =
+        String::from("| Opcode | Instruction | Operands | Implicit operands | Semantics |\n");
+    out.push_str("| ------ | ----------- | -------- | ----------------- | --------- |\n");
+    for info in INSTRUCTION_SET {
+        let operands = info
+            .operands
+            .iter()
+            .map(|op| format!("`{}: {}`", op.name, op.kind))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let implicit_operands = info
+            .implicit_operands
+            .iter()
+            .map(|op| format!("`{op}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} |\n",
+            info.opcode,
+            info.name,
+            operands,
+            implicit_operands,
+            info.semantics().replace('|', "\\|"),
+        ));
+    }
+    out
+}
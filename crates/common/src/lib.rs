@@ -1,11 +1,17 @@
 #![allow(clippy::option_if_let_else)]
 pub mod abi_codec;
+pub mod binary_program;
+pub mod casm;
 pub mod execution;
 pub mod instruction;
 pub mod program;
 pub mod state;
 
-pub use abi_codec::{AbiCodecError, CairoMValue, InputValue, parse_cli_arg};
+pub use abi_codec::{AbiCodecError, CairoMValue, InputValue, SignedDisplay, parse_cli_arg};
+pub use binary_program::{BinaryProgram, BinaryProgramError};
 pub use instruction::{Instruction, InstructionError};
-pub use program::{Program, ProgramData, ProgramMetadata, PublicAddressRanges};
+pub use program::{
+    AbortInfo, Builtin, LinkError, Program, ProgramData, ProgramMetadata, PublicAddressRanges,
+    PublicSegment, SegmentKind, Symbol, SymbolKind, UnsupportedIsaError,
+};
 pub use state::State;
@@ -1,10 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
 use std::ops::Range;
 
 use serde::{Deserialize, Serialize};
+use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 
-use crate::Instruction;
+use crate::instruction::ISA_VERSION;
+use crate::{InputValue, Instruction};
 
 /// ABI-visible Cairo-M type description for parameters and return values
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -73,6 +76,11 @@ pub struct AbiSlot {
     pub name: String,
     /// The Cairo-M type description for this value
     pub ty: AbiType,
+    /// Value used by the runner when the argument is omitted (parameters only)
+    ///
+    /// Not skipped when absent, as the program is also encoded with bincode.
+    #[serde(default)]
+    pub default: Option<InputValue>,
 }
 
 impl AbiSlot {
@@ -96,15 +104,56 @@ pub struct EntrypointInfo {
     pub returns: Vec<AbiSlot>,
 }
 
+/// Memory in which a public segment is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    /// Read from the initial memory, like the program and its arguments
+    Input,
+    /// Read from the final memory, like the return values
+    Output,
+}
+
+/// Named range of public addresses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicSegment {
+    /// Name of the segment, for reporting
+    pub name: String,
+    /// Memory in which the segment is read
+    pub kind: SegmentKind,
+    /// Addresses of the segment
+    pub range: Range<u32>,
+}
+
+impl PublicSegment {
+    pub fn new(name: impl Into<String>, kind: SegmentKind, range: Range<u32>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            range,
+        }
+    }
+}
+
 /// Public address ranges for structured access to program, input, and output data
+///
+/// The program, environment, input and output ranges follow the layout of the entrypoint
+/// call. Additional user segments make other ranges public, e.g. data materialized by the
+/// caller or written by the program outside of its return values.
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct PublicAddressRanges {
     /// Program addresses (instructions)
     pub program: Range<u32>,
+    /// Environment block addresses (values supplied by the runner, read with `env`)
+    #[serde(default)]
+    pub environment: Range<u32>,
     /// Input addresses (function arguments)
     pub input: Range<u32>,
     /// Output addresses (function return values)
     pub output: Range<u32>,
+    /// User segments, public in addition to the program, input and output ranges
+    #[serde(default)]
+    pub segments: Vec<PublicSegment>,
 }
 
 impl PublicAddressRanges {
@@ -116,10 +165,70 @@ impl PublicAddressRanges {
 
         Self {
             program: 0..program_end,
+            environment: program_end..program_end,
             input: program_end..input_end,
             output: input_end..output_end,
+            segments: Vec::new(),
         }
     }
+
+    /// Places an environment block of `len` values between the program and the input,
+    /// shifting the input and output ranges after it
+    pub const fn with_environment(mut self, len: u32) -> Self {
+        self.environment.end = self.environment.start + len;
+        self.input.start += len;
+        self.input.end += len;
+        self.output.start += len;
+        self.output.end += len;
+        self
+    }
+
+    /// Adds a user segment
+    pub fn with_segment(mut self, segment: PublicSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Returns all the named ranges: program, environment, input, output, then the user segments
+    pub fn named_ranges(&self) -> impl Iterator<Item = (&str, SegmentKind, Range<u32>)> + '_ {
+        [
+            ("program", SegmentKind::Input, self.program.clone()),
+            ("environment", SegmentKind::Input, self.environment.clone()),
+            ("input", SegmentKind::Input, self.input.clone()),
+            ("output", SegmentKind::Output, self.output.clone()),
+        ]
+        .into_iter()
+        .chain(
+            self.segments
+                .iter()
+                .map(|segment| (segment.name.as_str(), segment.kind, segment.range.clone())),
+        )
+    }
+
+    /// Returns the ranges read from the initial memory: program, environment, input, then the
+    /// input segments
+    pub fn initial_ranges(&self) -> impl Iterator<Item = Range<u32>> + '_ {
+        self.named_ranges()
+            .filter(|(_, kind, _)| *kind == SegmentKind::Input)
+            .map(|(_, _, range)| range)
+    }
+
+    /// Returns the ranges read from the final memory: output, then the output segments
+    pub fn final_ranges(&self) -> impl Iterator<Item = Range<u32>> + '_ {
+        self.named_ranges()
+            .filter(|(_, kind, _)| *kind == SegmentKind::Output)
+            .map(|(_, _, range)| range)
+    }
+
+    /// Returns whether an address is public in the initial memory
+    pub fn is_initial(&self, address: u32) -> bool {
+        self.initial_ranges().any(|range| range.contains(&address))
+    }
+
+    /// Returns whether an address is public in the final memory
+    pub fn is_final(&self, address: u32) -> bool {
+        self.final_ranges().any(|range| range.contains(&address))
+    }
 }
 
 /// Metadata about the compiled program
@@ -137,6 +246,168 @@ pub struct ProgramMetadata {
     /// Compiler version
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compiler_version: Option<String>,
+
+    /// Commit of the compiler sources, if it was built from a git checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+
+    /// Optimization level of the compilation, as the `--opt-level` of the compiler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opt_level: Option<u8>,
+
+    /// Version of the instruction set the program is compiled for, see [`ISA_VERSION`].
+    /// Programs without it predate versioning and use the first version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isa_version: Option<u32>,
+
+    /// Builtins used by the instructions of the program
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub builtins: BTreeSet<Builtin>,
+
+    /// Messages of the `assert` and `panic` calls, keyed by the PC of their trapping instruction
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub abort_messages: BTreeMap<u32, AbortInfo>,
+
+    /// Source locations of the divisions, as `file:line:column`, keyed by PC, reported when
+    /// their divisor is zero
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub division_locations: BTreeMap<u32, String>,
+
+    /// Source locations of the function calls, as `file:line:column`, keyed by the PC of their
+    /// call instruction, reported in backtraces
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub call_locations: BTreeMap<u32, String>,
+
+    /// PCs of the `StoreImm` instructions whose immediate is an absolute data address,
+    /// shifted when the program is linked at another address
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relocations: Vec<u32>,
+
+    /// Labels of the functions and basic blocks, sorted by PC, to resolve addresses back to
+    /// names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<Symbol>,
+
+    /// Number of values of the environment block read by the program with `env`, which the
+    /// runner must supply
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub environment_size: u32,
+
+    /// PCs of the `StoreImm` instructions whose immediate is the address of the environment
+    /// block, which follows the program data, set to the end of the program when it is linked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environment_references: Vec<u32>,
+}
+
+const fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+impl ProgramMetadata {
+    /// Returns the symbol of the given kind whose code contains `pc`: the last one starting
+    /// at or before `pc`
+    ///
+    /// Symbols only mark where code starts, so the PCs of the data following the code of the
+    /// last function also resolve to it.
+    pub fn symbol_at(&self, pc: u32, kind: SymbolKind) -> Option<&Symbol> {
+        Symbol::find(&self.symbols, pc, kind)
+    }
+
+    /// Checks that the program targets an instruction set supported by this build
+    pub const fn check_isa_version(&self) -> Result<(), UnsupportedIsaError> {
+        match self.isa_version {
+            Some(required) if required > ISA_VERSION => Err(UnsupportedIsaError {
+                required,
+                supported: ISA_VERSION,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error returned for programs compiled for a newer instruction set than the one supported
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Program requires ISA version {required}, but this build supports up to version {supported}: upgrade the runner and prover"
+)]
+pub struct UnsupportedIsaError {
+    pub required: u32,
+    pub supported: u32,
+}
+
+/// Family of specialized instructions, whose use is recorded in [`ProgramMetadata::builtins`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Builtin {
+    /// Poseidon2 sponge, [`Instruction::Poseidon2AbsorbFpFp`] and
+    /// [`Instruction::Poseidon2SqueezeFpFp`]
+    Poseidon2,
+}
+
+impl Builtin {
+    /// Returns the builtin an instruction belongs to, if any
+    pub const fn of(instruction: &Instruction) -> Option<Self> {
+        match instruction {
+            Instruction::Poseidon2AbsorbFpFp { .. } | Instruction::Poseidon2SqueezeFpFp { .. } => {
+                Some(Self::Poseidon2)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Kind of code location named by a [`Symbol`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    /// Entry of a function, or start of its outlined aborts, named after it
+    Function,
+    /// Start of a basic block, named `<function>_<block index>`
+    Block,
+}
+
+/// Code label exported in the symbol table of a program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Symbol {
+    /// Name of the label
+    pub name: String,
+    /// Kind of code location the label marks
+    pub kind: SymbolKind,
+    /// PC of the first instruction following the label
+    pub pc: u32,
+}
+
+impl Symbol {
+    /// Returns the symbol of the given kind whose code contains `pc`, in a symbol table
+    /// sorted by PC (see [`ProgramMetadata::symbol_at`])
+    pub fn find(symbols: &[Self], pc: u32, kind: SymbolKind) -> Option<&Self> {
+        symbols
+            .iter()
+            .filter(|symbol| symbol.kind == kind)
+            .take_while(|symbol| symbol.pc <= pc)
+            .last()
+    }
+}
+
+/// Message and source location reported when an `assert` or `panic` aborts execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbortInfo {
+    /// The user-provided message
+    pub message: String,
+    /// Source location of the call, as `file:line:column`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl fmt::Display for AbortInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{} at {location}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
 }
 
 /// Either an decoded instruction or a raw QM31 value
@@ -153,6 +424,21 @@ impl ProgramData {
             Self::Value(q) => vec![*q],
         }
     }
+
+    /// Number of memory cells this entry occupies
+    pub const fn size_in_qm31s(&self) -> u32 {
+        match self {
+            Self::Instruction(instruction) => instruction.size_in_qm31s(),
+            Self::Value(_) => 1,
+        }
+    }
+}
+
+/// Errors that can occur when linking programs
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LinkError {
+    #[error("Entrypoint '{0}' is defined by several programs")]
+    DuplicateEntrypoint(String),
 }
 
 /// A compiled Cairo-M program with linear data (instructions + rodata) and metadata
@@ -209,6 +495,150 @@ impl Program {
     pub const fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Link several programs into a single one
+    ///
+    /// The data of the programs is concatenated in order, and each program is relocated by the
+    /// address its data now starts at: absolute call and jump targets, the immediates listed in
+    /// [`ProgramMetadata::relocations`], entrypoints, abort messages, division and call
+    /// locations and symbols are shifted. Relative jumps need no relocation. References to the environment
+    /// block are set to the end of the linked program, where the block now starts. The linked
+    /// program requires the highest ISA version, all the builtins of the programs and the
+    /// largest environment. The remaining metadata is taken from the first program.
+    ///
+    /// ## Arguments
+    /// * `programs` - The programs to link, in the order their data is laid out
+    ///
+    /// ## Returns
+    /// * `Ok(Program)` - The linked program, exposing the entrypoints of all programs
+    /// * `Err(LinkError)` - Two programs define an entrypoint with the same name
+    pub fn link(programs: &[Self]) -> Result<Self, LinkError> {
+        let mut linked = Self {
+            data: Vec::new(),
+            entrypoints: HashMap::new(),
+            metadata: ProgramMetadata {
+                abort_messages: BTreeMap::new(),
+                division_locations: BTreeMap::new(),
+                call_locations: BTreeMap::new(),
+                relocations: Vec::new(),
+                symbols: Vec::new(),
+                isa_version: programs
+                    .iter()
+                    .filter_map(|program| program.metadata.isa_version)
+                    .max(),
+                builtins: programs
+                    .iter()
+                    .flat_map(|program| program.metadata.builtins.iter().copied())
+                    .collect(),
+                environment_size: programs
+                    .iter()
+                    .map(|program| program.metadata.environment_size)
+                    .max()
+                    .unwrap_or_default(),
+                environment_references: Vec::new(),
+                ..programs
+                    .first()
+                    .map(|program| program.metadata.clone())
+                    .unwrap_or_default()
+            },
+        };
+
+        let environment = M31::from(
+            programs
+                .iter()
+                .flat_map(|program| &program.data)
+                .map(ProgramData::size_in_qm31s)
+                .sum::<u32>(),
+        );
+        let mut base = 0u32;
+        for program in programs {
+            for (name, info) in &program.entrypoints {
+                if linked.entrypoints.contains_key(name) {
+                    return Err(LinkError::DuplicateEntrypoint(name.clone()));
+                }
+                let info = EntrypointInfo {
+                    pc: info.pc + base as usize,
+                    ..info.clone()
+                };
+                linked.entrypoints.insert(name.clone(), info);
+            }
+
+            let relocations: HashSet<u32> = program.metadata.relocations.iter().copied().collect();
+            let environment_references: HashSet<u32> = program
+                .metadata
+                .environment_references
+                .iter()
+                .copied()
+                .collect();
+            let offset = M31::from(base);
+            let mut pc = 0u32;
+            for entry in &program.data {
+                let mut entry = entry.clone();
+                if let ProgramData::Instruction(instruction) = &mut entry {
+                    match instruction {
+                        Instruction::CallAbsImm { target, .. }
+                        | Instruction::JmpAbsImm { target } => {
+                            *target += offset;
+                        }
+                        Instruction::StoreImm { imm, .. } if relocations.contains(&pc) => {
+                            *imm += offset;
+                        }
+                        Instruction::StoreImm { imm, .. }
+                            if environment_references.contains(&pc) =>
+                        {
+                            *imm = environment;
+                        }
+                        _ => {}
+                    }
+                }
+                pc += entry.size_in_qm31s();
+                linked.data.push(entry);
+            }
+
+            linked.metadata.abort_messages.extend(
+                program
+                    .metadata
+                    .abort_messages
+                    .iter()
+                    .map(|(&pc, info)| (pc + base, info.clone())),
+            );
+            linked.metadata.division_locations.extend(
+                program
+                    .metadata
+                    .division_locations
+                    .iter()
+                    .map(|(&pc, location)| (pc + base, location.clone())),
+            );
+            linked.metadata.call_locations.extend(
+                program
+                    .metadata
+                    .call_locations
+                    .iter()
+                    .map(|(&pc, location)| (pc + base, location.clone())),
+            );
+            linked
+                .metadata
+                .relocations
+                .extend(program.metadata.relocations.iter().map(|&pc| pc + base));
+            linked.metadata.environment_references.extend(
+                program
+                    .metadata
+                    .environment_references
+                    .iter()
+                    .map(|&pc| pc + base),
+            );
+            linked
+                .metadata
+                .symbols
+                .extend(program.metadata.symbols.iter().map(|symbol| Symbol {
+                    pc: symbol.pc + base,
+                    ..symbol.clone()
+                }));
+            base += pc;
+        }
+
+        Ok(linked)
+    }
 }
 
 #[cfg(test)]
@@ -264,10 +694,12 @@ mod tests {
                 params: vec![AbiSlot {
                     name: "x".to_string(),
                     ty: AbiType::Felt,
+                    default: Some(InputValue::Number(3)),
                 }],
                 returns: vec![AbiSlot {
                     name: "result".to_string(),
                     ty: AbiType::Bool,
+                    default: None,
                 }],
             },
         );
@@ -282,6 +714,27 @@ mod tests {
                 source_file: Some("test.cm".to_string()),
                 compiled_at: Some("2025-01-01".to_string()),
                 compiler_version: Some("0.1.0".to_string()),
+                git_commit: Some("0123abcd".to_string()),
+                opt_level: Some(1),
+                isa_version: Some(ISA_VERSION),
+                builtins: BTreeSet::from([Builtin::Poseidon2]),
+                abort_messages: BTreeMap::from([(
+                    1,
+                    AbortInfo {
+                        message: "x must be positive".to_string(),
+                        location: Some("test.cm:3:5".to_string()),
+                    },
+                )]),
+                division_locations: BTreeMap::from([(0, "test.cm:4:9".to_string())]),
+                call_locations: BTreeMap::from([(1, "test.cm:5:13".to_string())]),
+                relocations: vec![0],
+                symbols: vec![Symbol {
+                    name: "main".to_string(),
+                    kind: SymbolKind::Function,
+                    pc: 1,
+                }],
+                environment_size: 2,
+                environment_references: vec![0],
             },
         };
 
@@ -296,4 +749,286 @@ mod tests {
             .0;
         assert_eq!(program, dsr);
     }
+
+    fn symbol(name: &str, kind: SymbolKind, pc: u32) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind,
+            pc,
+        }
+    }
+
+    /// A program with a `main` entrypoint that calls a helper and loads the address of its
+    /// data cell.
+    fn library(main: &str, helper: &str) -> Program {
+        let data = vec![
+            ProgramData::Instruction(Instruction::StoreImm {
+                imm: M31::from(4),
+                dst_off: M31::from(0),
+            }),
+            ProgramData::Instruction(Instruction::CallAbsImm {
+                frame_off: M31::from(1),
+                target: M31::from(3),
+            }),
+            ProgramData::Instruction(Instruction::Ret {}),
+            ProgramData::Instruction(Instruction::Ret {}),
+            ProgramData::Value(QM31::from_u32_unchecked(7, 0, 0, 0)),
+        ];
+        let entrypoint = |pc| EntrypointInfo {
+            pc,
+            params: vec![],
+            returns: vec![],
+        };
+        Program {
+            data,
+            entrypoints: HashMap::from([
+                (main.to_string(), entrypoint(0)),
+                (helper.to_string(), entrypoint(3)),
+            ]),
+            metadata: ProgramMetadata {
+                abort_messages: BTreeMap::from([(
+                    2,
+                    AbortInfo {
+                        message: main.to_string(),
+                        location: None,
+                    },
+                )]),
+                division_locations: BTreeMap::from([(3, format!("{helper}.cm:2:12"))]),
+                call_locations: BTreeMap::from([(1, format!("{main}.cm:3:5"))]),
+                relocations: vec![0],
+                symbols: vec![
+                    symbol(main, SymbolKind::Function, 0),
+                    symbol(&format!("{main}_0"), SymbolKind::Block, 0),
+                    symbol(helper, SymbolKind::Function, 3),
+                    symbol(&format!("{helper}_0"), SymbolKind::Block, 3),
+                ],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_link_relocates_programs() {
+        let first = library("main_a", "helper_a");
+        let second = library("main_b", "helper_b");
+        let linked = Program::link(&[first.clone(), second.clone()]).unwrap();
+
+        let base = first
+            .data
+            .iter()
+            .map(ProgramData::size_in_qm31s)
+            .sum::<u32>();
+        assert_eq!(base, 5);
+        assert_eq!(linked.data[..5], first.data[..]);
+        assert_eq!(
+            linked.data[5..],
+            [
+                ProgramData::Instruction(Instruction::StoreImm {
+                    imm: M31::from(9),
+                    dst_off: M31::from(0),
+                }),
+                ProgramData::Instruction(Instruction::CallAbsImm {
+                    frame_off: M31::from(1),
+                    target: M31::from(8),
+                }),
+                ProgramData::Instruction(Instruction::Ret {}),
+                ProgramData::Instruction(Instruction::Ret {}),
+                ProgramData::Value(QM31::from_u32_unchecked(7, 0, 0, 0)),
+            ]
+        );
+
+        let pcs: BTreeMap<_, _> = linked
+            .entrypoints
+            .iter()
+            .map(|(name, info)| (name.as_str(), info.pc))
+            .collect();
+        assert_eq!(
+            pcs,
+            BTreeMap::from([
+                ("main_a", 0),
+                ("helper_a", 3),
+                ("main_b", 5),
+                ("helper_b", 8)
+            ])
+        );
+        assert_eq!(
+            linked
+                .metadata
+                .abort_messages
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [2, 7]
+        );
+        assert_eq!(linked.metadata.abort_messages[&7].message, "main_b");
+        assert_eq!(
+            linked.metadata.division_locations,
+            BTreeMap::from([
+                (3, "helper_a.cm:2:12".to_string()),
+                (8, "helper_b.cm:2:12".to_string())
+            ])
+        );
+        assert_eq!(
+            linked.metadata.call_locations,
+            BTreeMap::from([
+                (1, "main_a.cm:3:5".to_string()),
+                (6, "main_b.cm:3:5".to_string())
+            ])
+        );
+        assert_eq!(linked.metadata.relocations, [0, 5]);
+        assert_eq!(
+            linked
+                .metadata
+                .symbols
+                .iter()
+                .map(|symbol| (symbol.name.as_str(), symbol.pc))
+                .collect::<Vec<_>>(),
+            [
+                ("main_a", 0),
+                ("main_a_0", 0),
+                ("helper_a", 3),
+                ("helper_a_0", 3),
+                ("main_b", 5),
+                ("main_b_0", 5),
+                ("helper_b", 8),
+                ("helper_b_0", 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_points_environment_references_at_linked_end() {
+        let reader = |environment_size| Program {
+            data: vec![
+                ProgramData::Instruction(Instruction::StoreImm {
+                    imm: M31::from(2),
+                    dst_off: M31::from(0),
+                }),
+                ProgramData::Instruction(Instruction::Ret {}),
+            ],
+            entrypoints: HashMap::new(),
+            metadata: ProgramMetadata {
+                environment_size,
+                environment_references: vec![0],
+                ..Default::default()
+            },
+        };
+        let linked = Program::link(&[reader(1), reader(3)]).unwrap();
+
+        // The environment block follows the linked program, not the first one
+        for pc in [0, 2] {
+            assert_eq!(
+                linked.data[pc],
+                ProgramData::Instruction(Instruction::StoreImm {
+                    imm: M31::from(4),
+                    dst_off: M31::from(0),
+                })
+            );
+        }
+        assert_eq!(linked.metadata.environment_references, [0, 2]);
+        assert_eq!(linked.metadata.environment_size, 3);
+        assert!(linked.metadata.relocations.is_empty());
+    }
+
+    #[test]
+    fn test_symbol_at() {
+        let metadata = library("main", "helper").metadata;
+        let name = |pc, kind| {
+            metadata
+                .symbol_at(pc, kind)
+                .map(|symbol| symbol.name.as_str())
+        };
+
+        assert_eq!(name(0, SymbolKind::Function), Some("main"));
+        assert_eq!(name(2, SymbolKind::Function), Some("main"));
+        assert_eq!(name(3, SymbolKind::Function), Some("helper"));
+        assert_eq!(name(2, SymbolKind::Block), Some("main_0"));
+        assert_eq!(name(4, SymbolKind::Block), Some("helper_0"));
+        assert_eq!(
+            ProgramMetadata::default().symbol_at(0, SymbolKind::Function),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_isa_version() {
+        let metadata = |isa_version| ProgramMetadata {
+            isa_version,
+            ..Default::default()
+        };
+
+        assert_eq!(metadata(None).check_isa_version(), Ok(()));
+        assert_eq!(metadata(Some(ISA_VERSION)).check_isa_version(), Ok(()));
+        assert_eq!(
+            metadata(Some(ISA_VERSION + 1)).check_isa_version(),
+            Err(UnsupportedIsaError {
+                required: ISA_VERSION + 1,
+                supported: ISA_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn test_public_address_ranges_segments() {
+        let ranges = PublicAddressRanges::new(10, 2, 1)
+            .with_segment(PublicSegment::new("data", SegmentKind::Input, 20..24))
+            .with_segment(PublicSegment::new("log", SegmentKind::Output, 30..32));
+
+        assert_eq!(
+            ranges.initial_ranges().collect::<Vec<_>>(),
+            [0..10, 10..10, 10..12, 20..24]
+        );
+        assert_eq!(ranges.final_ranges().collect::<Vec<_>>(), [12..13, 30..32]);
+        assert!(ranges.is_initial(21) && !ranges.is_final(21));
+        assert!(ranges.is_final(31) && !ranges.is_initial(31));
+        assert!(!ranges.is_initial(13) && !ranges.is_final(13));
+        assert_eq!(
+            ranges
+                .named_ranges()
+                .map(|(name, ..)| name)
+                .collect::<Vec<_>>(),
+            ["program", "environment", "input", "output", "data", "log"]
+        );
+
+        let json = serde_json::to_string(&ranges).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PublicAddressRanges>(&json).unwrap(),
+            ranges
+        );
+        // Ranges serialized without user segments are still readable
+        let legacy = r#"{"program":{"start":0,"end":10},"input":{"start":10,"end":12},"output":{"start":12,"end":13}}"#;
+        assert_eq!(
+            serde_json::from_str::<PublicAddressRanges>(legacy).unwrap(),
+            PublicAddressRanges::new(10, 2, 1)
+        );
+    }
+
+    #[test]
+    fn test_public_address_ranges_environment() {
+        let ranges = PublicAddressRanges::new(10, 2, 1).with_environment(3);
+
+        assert_eq!(ranges.environment, 10..13);
+        assert_eq!(ranges.input, 13..15);
+        assert_eq!(ranges.output, 15..16);
+        assert!(ranges.is_initial(11) && !ranges.is_final(11));
+    }
+
+    #[test]
+    fn test_link_single_program_is_identity() {
+        let program = library("main", "helper");
+        assert_eq!(
+            Program::link(std::slice::from_ref(&program)).unwrap(),
+            program
+        );
+    }
+
+    #[test]
+    fn test_link_duplicate_entrypoint() {
+        let first = library("main_a", "helper");
+        let second = library("main_b", "helper");
+        assert_eq!(
+            Program::link(&[first, second]),
+            Err(LinkError::DuplicateEntrypoint("helper".to_string()))
+        );
+    }
 }
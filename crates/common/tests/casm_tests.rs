@@ -0,0 +1,131 @@
+use cairo_m_common::Instruction;
+use cairo_m_common::casm::{self, CasmError};
+use cairo_m_common::instruction::INSTRUCTION_SET;
+use stwo_prover::core::fields::m31::M31;
+
+#[test]
+fn test_parse_with_labels_and_comments() {
+    let source = r#"
+        // Sum two values then loop forever
+        main:
+            store_imm 10, 0      // [fp + 0] = 10
+            StoreImm 20 1
+            store_add_fp_fp 0, 1, 2
+            call_abs_imm 3, main
+        end: jmp_rel_imm end
+            jnz_fp_imm 2, main
+    "#;
+
+    let instructions = casm::parse(source).unwrap();
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::StoreImm {
+                imm: M31::from(10),
+                dst_off: M31::from(0),
+            },
+            Instruction::StoreImm {
+                imm: M31::from(20),
+                dst_off: M31::from(1),
+            },
+            Instruction::StoreAddFpFp {
+                src0_off: M31::from(0),
+                src1_off: M31::from(1),
+                dst_off: M31::from(2),
+            },
+            Instruction::CallAbsImm {
+                frame_off: M31::from(3),
+                target: M31::from(0),
+            },
+            Instruction::JmpRelImm {
+                offset: M31::from(0),
+            },
+            Instruction::JnzFpImm {
+                cond_off: M31::from(2),
+                offset: M31::from(-5),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_labels_count_qm31_words() {
+    // u32_store_div_rem_fp_imm is 6 M31s long, hence 2 QM31 words.
+    let source = "
+        u32_store_div_rem_fp_imm 0, 7, 0, 2, 4
+        target: ret
+        jmp_abs_imm target
+    ";
+    let instructions = casm::parse(source).unwrap();
+    assert_eq!(
+        instructions[2],
+        Instruction::JmpAbsImm {
+            target: M31::from(2)
+        }
+    );
+}
+
+#[test]
+fn test_format_parse_roundtrip() {
+    let instructions = INSTRUCTION_SET
+        .iter()
+        .map(|info| {
+            let mut values = vec![M31::from(info.opcode)];
+            values.extend((0..info.operands.len() as i32).map(|i| M31::from(i - 2)));
+            Instruction::decode(&values).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let text = casm::format(&instructions);
+    assert!(text.starts_with("store_add_fp_fp -2, -1, 0\n"));
+    assert_eq!(casm::parse(&text).unwrap(), instructions);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        casm::parse("nop"),
+        Err(CasmError::UnknownMnemonic {
+            line: 1,
+            mnemonic: "nop".to_string(),
+        })
+    );
+    assert_eq!(
+        casm::parse("ret\nstore_imm 1"),
+        Err(CasmError::OperandCount {
+            line: 2,
+            mnemonic: "store_imm".to_string(),
+            expected: 2,
+            found: 1,
+        })
+    );
+    assert_eq!(
+        casm::parse("jmp_abs_imm nowhere"),
+        Err(CasmError::UndefinedLabel {
+            line: 1,
+            label: "nowhere".to_string(),
+        })
+    );
+    assert_eq!(
+        casm::parse("a: ret\na: ret"),
+        Err(CasmError::DuplicateLabel {
+            line: 2,
+            label: "a".to_string(),
+        })
+    );
+    assert_eq!(
+        casm::parse("a: store_imm 1, a"),
+        Err(CasmError::LabelAsMemoryOperand {
+            line: 1,
+            label: "a".to_string(),
+            operand: "dst_off",
+        })
+    );
+    assert_eq!(
+        casm::parse("store_imm 1, 2.5"),
+        Err(CasmError::InvalidOperand {
+            line: 1,
+            operand: "2.5".to_string(),
+        })
+    );
+}
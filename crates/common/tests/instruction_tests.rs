@@ -1,7 +1,10 @@
 #![feature(variant_count)]
 use std::mem;
 
-use cairo_m_common::instruction::INSTRUCTION_MAX_SIZE;
+use cairo_m_common::instruction::{
+    INSTRUCTION_MAX_SIZE, INSTRUCTION_SET, InstructionInfo, POSEIDON2_SQUEEZE_DOMAIN, isa_markdown,
+    poseidon2_absorb_input, poseidon2_squeeze_input,
+};
 use cairo_m_common::{Instruction, InstructionError};
 use smallvec::{SmallVec, smallvec};
 use stwo_prover::core::fields::m31::M31;
@@ -289,6 +292,24 @@ fn test_memory_accesses() {
         1
     );
     assert_eq!(Instruction::Ret {}.memory_accesses(), 2);
+    assert_eq!(
+        Instruction::Poseidon2AbsorbFpFp {
+            state_off: M31::from(0),
+            src_off: M31::from(0),
+            dst_off: M31::from(0),
+        }
+        .memory_accesses(),
+        24
+    );
+    assert_eq!(
+        Instruction::Poseidon2SqueezeFpFp {
+            state_off: M31::from(0),
+            len_off: M31::from(8),
+            dst_off: M31::from(9),
+        }
+        .memory_accesses(),
+        17
+    );
 }
 
 #[allow(clippy::cognitive_complexity)]
@@ -701,6 +722,15 @@ fn test_try_from_smallvec() {
             },
             "StoreLeFpImm instruction",
         ),
+        (
+            smallvec![M31::from(49), M31::from(1), M31::from(3), M31::from(3)],
+            Instruction::StorePowFpImm {
+                src_off: M31::from(1),
+                imm: M31::from(3),
+                dst_off: M31::from(3),
+            },
+            "StorePowFpImm instruction",
+        ),
         (
             smallvec![M31::from(50), M31::from(1), M31::from(2)],
             Instruction::AssertEqFpImm {
@@ -709,6 +739,24 @@ fn test_try_from_smallvec() {
             },
             "AssertEqFpImm instruction",
         ),
+        (
+            smallvec![M31::from(51), M31::from(1), M31::from(2), M31::from(10)],
+            Instruction::Poseidon2AbsorbFpFp {
+                state_off: M31::from(1),
+                src_off: M31::from(2),
+                dst_off: M31::from(10),
+            },
+            "Poseidon2AbsorbFpFp instruction",
+        ),
+        (
+            smallvec![M31::from(52), M31::from(1), M31::from(2), M31::from(3)],
+            Instruction::Poseidon2SqueezeFpFp {
+                state_off: M31::from(1),
+                len_off: M31::from(2),
+                dst_off: M31::from(3),
+            },
+            "Poseidon2SqueezeFpFp instruction",
+        ),
     ];
 
     assert_eq!(test_cases.len(), { mem::variant_count::<Instruction>() });
@@ -945,10 +993,25 @@ fn test_roundtrip_all_instructions() {
             imm: M31::from(25),
             dst_off: M31::from(26),
         },
+        Instruction::StorePowFpImm {
+            src_off: M31::from(27),
+            imm: M31::from(5),
+            dst_off: M31::from(28),
+        },
         Instruction::AssertEqFpImm {
             src_off: M31::from(29),
             imm: M31::from(30),
         },
+        Instruction::Poseidon2AbsorbFpFp {
+            state_off: M31::from(31),
+            src_off: M31::from(32),
+            dst_off: M31::from(40),
+        },
+        Instruction::Poseidon2SqueezeFpFp {
+            state_off: M31::from(41),
+            len_off: M31::from(42),
+            dst_off: M31::from(43),
+        },
     ];
 
     for instruction in instructions {
@@ -963,3 +1026,106 @@ fn test_roundtrip_all_instructions() {
         assert_eq!(instruction, deserialized);
     }
 }
+
+#[test]
+fn test_instruction_set_covers_all_variants() {
+    assert_eq!(INSTRUCTION_SET.len(), mem::variant_count::<Instruction>());
+    for info in INSTRUCTION_SET {
+        assert_eq!(
+            Instruction::size_in_m31s_for_opcode(info.opcode),
+            Some(info.size_in_m31s())
+        );
+        assert!(
+            !info.semantics().is_empty(),
+            "{} is undocumented",
+            info.name
+        );
+    }
+}
+
+#[test]
+fn test_encode_decode_roundtrip_all_opcodes() {
+    for info in INSTRUCTION_SET {
+        let mut values = vec![M31::from(info.opcode)];
+        values.extend((0..info.operands.len() as u32).map(|i| M31::from(100 + i)));
+
+        let instruction = Instruction::decode(&values).unwrap();
+        assert_eq!(instruction.opcode_value(), info.opcode);
+        assert_eq!(instruction.encode().as_slice(), values.as_slice());
+        assert_eq!(instruction.describe(), info);
+        assert_eq!(InstructionInfo::for_opcode(info.opcode), Some(info));
+    }
+}
+
+#[test]
+fn test_decode_ignores_trailing_values() {
+    let values = [
+        M31::from(12),
+        M31::from(5),
+        M31::from(9),
+        M31::from(7),
+        M31::from(3),
+    ];
+    let instruction = Instruction::decode(&values).unwrap();
+    assert_eq!(
+        instruction,
+        Instruction::JmpAbsImm {
+            target: M31::from(5)
+        }
+    );
+
+    let next = &values[instruction.size_in_m31s()..];
+    assert_eq!(
+        Instruction::decode(next).unwrap(),
+        Instruction::StoreImm {
+            imm: M31::from(7),
+            dst_off: M31::from(3)
+        }
+    );
+}
+
+#[test]
+fn test_decode_errors() {
+    assert_eq!(
+        Instruction::decode(&[]),
+        Err(InstructionError::SizeMismatch {
+            expected: 1,
+            found: 0
+        })
+    );
+    assert_eq!(
+        Instruction::decode(&[M31::from(9), M31::from(1)]),
+        Err(InstructionError::SizeMismatch {
+            expected: 3,
+            found: 2
+        })
+    );
+    assert_eq!(
+        Instruction::decode(&[M31::from(1000)]),
+        Err(InstructionError::InvalidOpcode(M31::from(1000)))
+    );
+}
+
+#[test]
+fn test_isa_doc_is_up_to_date() {
+    let doc = include_str!("../../../docs/isa.md");
+    assert!(
+        doc.contains(&isa_markdown()),
+        "docs/isa.md is out of date: replace its table with the output of `isa_markdown()`"
+    );
+}
+
+#[test]
+fn test_poseidon2_sponge_inputs() {
+    let state = std::array::from_fn(|i| M31::from(i as u32 + 42));
+    let block = std::array::from_fn(|i| M31::from(i as u32 + 1));
+    let absorb = poseidon2_absorb_input(state, block);
+    assert_eq!(absorb[..8], state);
+    assert_eq!(absorb[8..], block);
+
+    let squeeze = poseidon2_squeeze_input(state, M31::from(3));
+    assert_eq!(squeeze[..8], state);
+    assert_eq!(squeeze[8], M31::from(3));
+    assert!(squeeze[9..15].iter().all(|v| v.0 == 0));
+    assert_eq!(squeeze[15], M31::from(POSEIDON2_SQUEEZE_DOMAIN));
+}
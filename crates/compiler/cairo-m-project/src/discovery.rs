@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use tracing::debug;
 
-use crate::{Project, ProjectId, ProjectManifest, SourceLayout, Workspace};
+use crate::{Project, ProjectId, ProjectManifest, SourceLayout, Workspace, WorkspaceManifest};
 
 /// Discovers a Cairo-M project from a given path
 ///
@@ -78,17 +78,97 @@ entry_point = "{file_stem}.cm"
 
 /// Discovers a workspace containing multiple projects
 ///
+/// If the root manifest declares a `[workspace]` table, exactly the listed members are loaded
+/// and any member that fails to load is an error. Otherwise, the directory tree is walked and
+/// every `cairom.toml` found is loaded as a project.
+///
+/// The `dependencies` of each member are then resolved against the other members to build the
+/// workspace's project graph.
+///
 /// ## Arguments
 /// * `workspace_root` - Root directory of the workspace
 ///
 /// ## Returns
 /// * `Workspace` containing all discovered projects
 pub fn discover_workspace(workspace_root: &Path) -> Result<Workspace> {
+    let root_manifest = workspace_root.join(crate::MANIFEST_FILE_NAME);
+    let workspace_manifest = if root_manifest.exists() {
+        WorkspaceManifest::from_path(&root_manifest).with_context(|| {
+            format!("Failed to parse manifest at {}", root_manifest.display())
+        })?
+    } else {
+        None
+    };
+
+    let loaded_projects = match workspace_manifest {
+        Some(manifest) => load_workspace_members(workspace_root, &manifest)?,
+        None => walk_workspace_projects(workspace_root)?,
+    };
+
     let mut projects = HashMap::new();
     let mut name_to_id = HashMap::new();
-    let mut next_id = 0;
+    for (next_id, project) in loaded_projects.into_iter().enumerate() {
+        let crate_id = ProjectId(next_id);
+        if name_to_id.insert(project.name.clone(), crate_id).is_some() {
+            anyhow::bail!("Duplicate workspace member name `{}`", project.name);
+        }
+        projects.insert(crate_id, project);
+    }
+
+    let mut dependencies = HashMap::new();
+    for (id, project) in &projects {
+        let deps = project
+            .config
+            .dependencies
+            .iter()
+            .map(|dep| {
+                name_to_id.get(dep).copied().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Project `{}` depends on `{}`, which is not a workspace member",
+                        project.name,
+                        dep
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        dependencies.insert(*id, deps);
+    }
+
+    let workspace = Workspace {
+        root_directory: workspace_root.to_owned(),
+        projects,
+        name_to_id,
+        dependencies,
+    };
+    // Reject cyclic dependency graphs early rather than at build time.
+    workspace.build_order()?;
+
+    Ok(workspace)
+}
+
+/// Load the members explicitly listed in a `[workspace]` table
+fn load_workspace_members(
+    workspace_root: &Path,
+    manifest: &WorkspaceManifest,
+) -> Result<Vec<Project>> {
+    manifest
+        .members
+        .iter()
+        .map(|member| {
+            let manifest_path = workspace_root
+                .join(member)
+                .join(crate::MANIFEST_FILE_NAME);
+            debug!("Loading workspace member from: {}", manifest_path.display());
+            load_project_from_manifest(&manifest_path)
+                .with_context(|| format!("Failed to load workspace member `{member}`"))
+        })
+        .collect()
+}
+
+/// Walk the directory tree looking for cairom.toml files
+fn walk_workspace_projects(workspace_root: &Path) -> Result<Vec<Project>> {
+    let mut projects = Vec::new();
 
-    // Walk the directory tree looking for cairom.toml files
     use ignore::WalkBuilder;
     let walker = WalkBuilder::new(workspace_root).follow_links(false).build();
 
@@ -98,13 +178,7 @@ pub fn discover_workspace(workspace_root: &Path) -> Result<Workspace> {
 
         if path.file_name() == Some(std::ffi::OsStr::new(crate::MANIFEST_FILE_NAME)) {
             match load_project_from_manifest(path) {
-                Ok(project) => {
-                    let crate_id = ProjectId(next_id);
-                    next_id += 1;
-
-                    name_to_id.insert(project.name.clone(), crate_id);
-                    projects.insert(crate_id, project);
-                }
+                Ok(project) => projects.push(project),
                 Err(e) => {
                     // Log but don't fail on individual project errors
                     debug!("Failed to load project from {}: {}", path.display(), e);
@@ -113,11 +187,28 @@ pub fn discover_workspace(workspace_root: &Path) -> Result<Workspace> {
         }
     }
 
-    Ok(Workspace {
-        root_directory: workspace_root.to_owned(),
-        projects,
-        name_to_id,
-    })
+    Ok(projects)
+}
+
+/// Find the root of the enclosing workspace, i.e. the closest ancestor directory whose
+/// manifest declares a `[workspace]` table.
+pub fn find_workspace_root(start_path: &Path) -> Result<Option<PathBuf>> {
+    let mut current = find_project_manifest(start_path)?;
+
+    while let Some(manifest_path) = current {
+        let manifest_dir = manifest_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Manifest has no parent directory"))?;
+        if WorkspaceManifest::from_path(&manifest_path)?.is_some() {
+            return Ok(Some(manifest_dir.to_owned()));
+        }
+        current = match manifest_dir.parent() {
+            Some(parent) => find_project_manifest(parent)?,
+            None => None,
+        };
+    }
+
+    Ok(None)
 }
 
 /// Find the project manifest (cairom.toml) starting from a given path
@@ -221,4 +312,87 @@ mod tests {
         let project2 = discover_project(&sub_dir).unwrap().unwrap();
         assert_eq!(project2.name, "test_project");
     }
+
+    fn write_member(root: &Path, dir: &str, manifest: &str) {
+        let member_dir = root.join(dir);
+        fs::create_dir_all(member_dir.join("src")).unwrap();
+        fs::write(member_dir.join(crate::MANIFEST_FILE_NAME), manifest).unwrap();
+        fs::write(member_dir.join("src/lib.cm"), "").unwrap();
+    }
+
+    #[test]
+    fn test_discover_workspace_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join(crate::MANIFEST_FILE_NAME),
+            r#"
+            [workspace]
+            members = ["crates/math", "crates/app"]
+        "#,
+        )
+        .unwrap();
+        write_member(
+            root,
+            "crates/math",
+            r#"
+            name = "math"
+            entry_point = "lib.cm"
+        "#,
+        );
+        write_member(
+            root,
+            "crates/app",
+            r#"
+            name = "app"
+            entry_point = "lib.cm"
+            dependencies = ["math"]
+        "#,
+        );
+        // Not listed in `members`, so it must not be picked up.
+        write_member(
+            root,
+            "scratch",
+            r#"
+            name = "scratch"
+            entry_point = "lib.cm"
+        "#,
+        );
+
+        let workspace = discover_workspace(root).unwrap();
+        assert_eq!(workspace.projects.len(), 2);
+        let (math_id, _) = workspace.project_by_name("math").unwrap();
+        let (app_id, _) = workspace.project_by_name("app").unwrap();
+        assert_eq!(workspace.dependencies_of(app_id), &[math_id]);
+        assert_eq!(workspace.build_order().unwrap(), vec![math_id, app_id]);
+
+        let member_file = root.join("crates/app/src/lib.cm");
+        assert_eq!(
+            find_workspace_root(&member_file).unwrap().unwrap(),
+            root.to_path_buf()
+        );
+    }
+
+    #[test]
+    fn test_discover_workspace_unknown_dependency() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join(crate::MANIFEST_FILE_NAME),
+            "[workspace]\nmembers = [\"app\"]\n",
+        )
+        .unwrap();
+        write_member(
+            root,
+            "app",
+            r#"
+            name = "app"
+            entry_point = "lib.cm"
+            dependencies = ["missing"]
+        "#,
+        );
+
+        let err = discover_workspace(root).unwrap_err();
+        assert!(err.to_string().contains("not a workspace member"));
+    }
 }
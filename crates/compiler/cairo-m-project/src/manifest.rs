@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,47 @@ pub struct ProjectManifest {
     pub version: String,
     /// Entry point file (relative to src/)
     pub entry_point: String,
+    /// Names of the workspace members this crate depends on
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Lint levels, keyed by diagnostic code name, or `warnings` for all lints
+    ///
+    /// ```toml
+    /// [lints]
+    /// warnings = "deny"
+    /// unused_variable = "allow"
+    /// ```
+    #[serde(default)]
+    pub lints: BTreeMap<String, LintLevel>,
+    /// Compilation settings
+    ///
+    /// ```toml
+    /// [profile]
+    /// mir_passes = "sroa,cse,dce"
+    /// ```
+    #[serde(default)]
+    pub profile: ProfileManifest,
+}
+
+/// Compilation settings of a crate, declared in its `[profile]` table
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProfileManifest {
+    /// Comma-separated MIR optimization passes to run, in order, instead of the standard
+    /// pipeline
+    #[serde(default)]
+    pub mir_passes: Option<String>,
+}
+
+/// Level of a lint, which decides how its non-error diagnostics are reported
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// The diagnostics are not reported
+    Allow,
+    /// The diagnostics are reported with their own severity
+    Warn,
+    /// The diagnostics are reported as errors, failing the compilation
+    Deny,
 }
 
 fn default_version() -> String {
@@ -25,6 +67,9 @@ impl Default for ProjectManifest {
             name: "test".to_string(),
             version: "0.1.0".to_string(),
             entry_point: "main.cm".to_string(),
+            dependencies: Vec::new(),
+            lints: BTreeMap::new(),
+            profile: ProfileManifest::default(),
         }
     }
 }
@@ -44,3 +89,118 @@ impl ProjectManifest {
         Ok(manifest)
     }
 }
+
+/// Workspace configuration, declared in a `[workspace]` table of a root manifest
+///
+/// ```toml
+/// [workspace]
+/// members = ["crates/math", "crates/app"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct WorkspaceManifest {
+    /// Member directories, relative to the workspace root
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Top-level view of a manifest file, used to extract the optional `[workspace]` table
+#[derive(Debug, Deserialize)]
+struct ManifestFile {
+    workspace: Option<WorkspaceManifest>,
+}
+
+impl WorkspaceManifest {
+    /// Load the workspace table from a manifest file, if it declares one
+    pub fn from_path(path: &Path) -> anyhow::Result<Option<Self>> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_file_content(&content)
+    }
+
+    pub fn from_file_content(content: &str) -> anyhow::Result<Option<Self>> {
+        let manifest: ManifestFile = toml::from_str(content).map_err(|e| {
+            tracing::error!("Failed to parse TOML: {}", e);
+            e
+        })?;
+        Ok(manifest.workspace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_manifest_parsing() {
+        let content = r#"
+            [workspace]
+            members = ["math", "app"]
+        "#;
+        let workspace = WorkspaceManifest::from_file_content(content)
+            .unwrap()
+            .unwrap();
+        assert_eq!(workspace.members, vec!["math", "app"]);
+
+        let project_only = r#"
+            name = "app"
+            entry_point = "main.cm"
+            dependencies = ["math"]
+        "#;
+        assert!(
+            WorkspaceManifest::from_file_content(project_only)
+                .unwrap()
+                .is_none()
+        );
+        let project = ProjectManifest::from_file_content(project_only).unwrap();
+        assert_eq!(project.dependencies, vec!["math"]);
+        assert!(project.lints.is_empty());
+    }
+
+    #[test]
+    fn test_lints_parsing() {
+        let content = r#"
+            name = "app"
+            entry_point = "main.cm"
+
+            [lints]
+            warnings = "deny"
+            unused_variable = "allow"
+        "#;
+        let project = ProjectManifest::from_file_content(content).unwrap();
+        assert_eq!(
+            project.lints,
+            BTreeMap::from([
+                ("unused_variable".to_string(), LintLevel::Allow),
+                ("warnings".to_string(), LintLevel::Deny),
+            ])
+        );
+
+        let invalid_level = r#"
+            name = "app"
+            entry_point = "main.cm"
+
+            [lints]
+            warnings = "forbid"
+        "#;
+        assert!(ProjectManifest::from_file_content(invalid_level).is_err());
+    }
+
+    #[test]
+    fn test_profile_parsing() {
+        let content = r#"
+            name = "app"
+            entry_point = "main.cm"
+
+            [profile]
+            mir_passes = "sroa,cse,dce"
+        "#;
+        let project = ProjectManifest::from_file_content(content).unwrap();
+        assert_eq!(project.profile.mir_passes.as_deref(), Some("sroa,cse,dce"));
+
+        let no_profile = r#"
+            name = "app"
+            entry_point = "main.cm"
+        "#;
+        let project = ProjectManifest::from_file_content(no_profile).unwrap();
+        assert_eq!(project.profile, ProfileManifest::default());
+    }
+}
@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use super::ProjectManifest;
 
 /// Unique identifier for a crate within a workspace
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ProjectId(pub usize);
 
 /// Represents a Cairo-M project structure
@@ -153,12 +153,154 @@ pub struct Workspace {
     pub projects: HashMap<ProjectId, Project>,
     /// Mapping from project name to project ID
     pub name_to_id: HashMap<String, ProjectId>,
+    /// Direct dependencies of each project on other workspace members
+    pub dependencies: HashMap<ProjectId, Vec<ProjectId>>,
+}
+
+/// A cycle in the dependency graph of a workspace, as the names of the members involved,
+/// the first member being repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Dependency cycle detected between workspace members: {}", .0.join(" -> "))]
+pub struct DependencyCycle(pub Vec<String>);
+
+impl Workspace {
+    /// Get a project by its name
+    pub fn project_by_name(&self, name: &str) -> Option<(ProjectId, &Project)> {
+        let id = *self.name_to_id.get(name)?;
+        self.projects.get(&id).map(|project| (id, project))
+    }
+
+    /// Enumerate all members of the workspace, ordered by project ID
+    pub fn members(&self) -> impl Iterator<Item = (ProjectId, &Project)> {
+        let mut ids = self.projects.keys().copied().collect::<Vec<_>>();
+        ids.sort();
+        ids.into_iter().map(move |id| (id, &self.projects[&id]))
+    }
+
+    /// Direct dependencies of a project on other workspace members
+    pub fn dependencies_of(&self, id: ProjectId) -> &[ProjectId] {
+        self.dependencies.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Order in which all members must be built so that every project comes after its
+    /// dependencies.
+    ///
+    /// ## Returns
+    /// The topologically sorted project IDs, or a [`DependencyCycle`] naming the members
+    /// involved in a dependency cycle.
+    pub fn build_order(&self) -> Result<Vec<ProjectId>, DependencyCycle> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        for (id, _) in self.members() {
+            self.visit_dependencies(id, &mut visited, &mut Vec::new(), &mut order)?;
+        }
+        Ok(order)
+    }
+
+    /// Transitive dependencies of a project (including itself), in build order
+    pub fn build_order_for(&self, id: ProjectId) -> Result<Vec<ProjectId>, DependencyCycle> {
+        let mut order = Vec::new();
+        self.visit_dependencies(id, &mut HashSet::new(), &mut Vec::new(), &mut order)?;
+        Ok(order)
+    }
+
+    /// Post-order DFS over the dependency graph, reporting cycles through `stack`.
+    fn visit_dependencies(
+        &self,
+        id: ProjectId,
+        visited: &mut HashSet<ProjectId>,
+        stack: &mut Vec<ProjectId>,
+        order: &mut Vec<ProjectId>,
+    ) -> Result<(), DependencyCycle> {
+        if visited.contains(&id) {
+            return Ok(());
+        }
+        if let Some(pos) = stack.iter().position(|&p| p == id) {
+            let cycle = stack[pos..]
+                .iter()
+                .chain(std::iter::once(&id))
+                .map(|p| self.projects[p].name.clone())
+                .collect();
+            return Err(DependencyCycle(cycle));
+        }
+
+        stack.push(id);
+        for &dep in self.dependencies_of(id) {
+            self.visit_dependencies(dep, visited, stack, order)?;
+        }
+        stack.pop();
+
+        visited.insert(id);
+        order.push(id);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_project(name: &str) -> Project {
+        Project {
+            config: Default::default(),
+            root_directory: PathBuf::from(format!("/ws/{name}/src/main.cm")),
+            name: name.to_string(),
+            source_layout: SourceLayout::default(),
+        }
+    }
+
+    fn test_workspace(edges: &[(usize, usize)]) -> Workspace {
+        let names = ["math", "utils", "app"];
+        let projects = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (ProjectId(i), test_project(name)))
+            .collect();
+        let name_to_id = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_string(), ProjectId(i)))
+            .collect();
+        let mut dependencies: HashMap<ProjectId, Vec<ProjectId>> = HashMap::new();
+        for &(from, to) in edges {
+            dependencies
+                .entry(ProjectId(from))
+                .or_default()
+                .push(ProjectId(to));
+        }
+        Workspace {
+            root_directory: PathBuf::from("/ws"),
+            projects,
+            name_to_id,
+            dependencies,
+        }
+    }
+
+    #[test]
+    fn test_workspace_build_order() {
+        // app -> utils -> math, app -> math
+        let workspace = test_workspace(&[(2, 1), (2, 0), (1, 0)]);
+        let order = workspace.build_order().unwrap();
+        let position = |id| order.iter().position(|&p| p == ProjectId(id)).unwrap();
+        assert_eq!(order.len(), 3);
+        assert!(position(0) < position(1));
+        assert!(position(1) < position(2));
+
+        assert_eq!(
+            workspace.build_order_for(ProjectId(1)).unwrap(),
+            vec![ProjectId(0), ProjectId(1)]
+        );
+        assert_eq!(workspace.project_by_name("app").unwrap().0, ProjectId(2));
+    }
+
+    #[test]
+    fn test_workspace_dependency_cycle() {
+        let workspace = test_workspace(&[(0, 1), (1, 0)]);
+        let err = workspace.build_order().unwrap_err();
+        assert_eq!(err.0, vec!["math", "utils", "math"]);
+        assert!(err.to_string().contains("math -> utils -> math"));
+    }
+
     #[test]
     fn test_module_name_from_path() {
         let project = Project {
@@ -0,0 +1,18 @@
+use std::process::Command;
+
+/// Exposes the commit of the sources as `CAIRO_M_GIT_COMMIT`, recorded in the metadata of the
+/// compiled programs. Builds outside of a git checkout leave it unset.
+fn main() {
+    println!("cargo:rerun-if-changed=../../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../../.git/refs");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=CAIRO_M_GIT_COMMIT={}", commit.trim());
+    }
+}
@@ -1,9 +1,11 @@
 //! Cairo Assembly (CASM) code generation
 
 use cairo_m_common::Program;
+use cairo_m_common::instruction::{DataType, OperandType};
 use cairo_m_compiler_mir::{InstructionKind, MirModule};
+use stwo_prover::core::fields::m31::{M31, P};
 
-use crate::{CodeGenerator, CodegenError};
+use crate::{CodeGenerator, CodegenError, InstructionBuilder};
 
 /// Validate that a MIR module is ready for CASM generation
 pub fn validate_for_casm(module: &MirModule) -> Result<(), CodegenError> {
@@ -39,6 +41,84 @@ pub fn validate_for_casm(module: &MirModule) -> Result<(), CodegenError> {
     Ok(())
 }
 
+/// Validate the memory accesses of the CASM instructions of a function against the prover
+///
+/// Each memory access of an instruction is constrained at the clock of the instruction, strictly
+/// after the previous access to the same cell (see `store_fp_fp.rs` in the prover components).
+/// Two reads or two writes of the same fp-relative cell in one instruction therefore produce
+/// unprovable traces, while a read followed by a write of the same cell is fine. The
+/// `deduplicate-operands` pass rewrites the double reads it knows about; this check catches the
+/// remaining ones at compile time instead of at proving time.
+///
+/// Accesses through computed addresses (double derefs) are not known statically and are not
+/// checked.
+///
+/// ## Arguments
+/// * `function_name` - Name of the function, for error reporting
+/// * `instructions` - The instructions of the function, after the codegen passes
+///
+/// ## Returns
+/// [`CodegenError::UnprovableInstruction`] with the function, the instruction and its source span
+/// for the first offending instruction
+pub fn validate_casm_accesses(
+    function_name: &str,
+    instructions: &[InstructionBuilder],
+) -> Result<(), CodegenError> {
+    for (index, instr) in instructions.iter().enumerate() {
+        let instruction = instr.inner_instr();
+        // The cells printed by debug prints are read outside of the proven trace.
+        if instruction.is_debug_print() {
+            continue;
+        }
+
+        let info = instruction.describe();
+        let mut reads: Vec<M31> = vec![];
+        let mut writes: Vec<M31> = vec![];
+        for (operand, value) in info.operands.iter().zip(&instruction.to_smallvec()[1..]) {
+            let OperandType::Memory(data_type) = operand.kind else {
+                continue;
+            };
+            let cells = match data_type {
+                DataType::Felt => 1,
+                DataType::U32 => 2,
+            };
+            let (accesses, kind) = if operand.name.starts_with("dst") {
+                (&mut writes, "writes")
+            } else {
+                (&mut reads, "reads")
+            };
+            for cell in (0..cells).map(|i| *value + M31(i)) {
+                if accesses.contains(&cell) {
+                    let location = instr
+                        .source_span()
+                        .map(|span| format!(" (source span {}..{})", span.start, span.end))
+                        .unwrap_or_default();
+                    return Err(CodegenError::UnprovableInstruction(format!(
+                        "Function '{function_name}', instruction {index} `{}`{location}: \
+                        {kind} {} twice, which the prover cannot constrain",
+                        instr
+                            .comment()
+                            .map_or_else(|| format!("{instruction:?}"), str::to_string),
+                        format_fp_cell(cell),
+                    )));
+                }
+                accesses.push(cell);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats an fp-relative cell, with negative offsets as `[fp - k]`
+fn format_fp_cell(offset: M31) -> String {
+    if offset.0 > P / 2 {
+        format!("[fp - {}]", P - offset.0)
+    } else {
+        format!("[fp + {}]", offset.0)
+    }
+}
+
 /// Generate CASM code from an optimized MIR module
 pub fn compile_module(module: &MirModule) -> Result<Program, CodegenError> {
     // Validate the module first
@@ -50,5 +130,87 @@ pub fn compile_module(module: &MirModule) -> Result<Program, CodegenError> {
     if std::env::var("DEBUG_CASM").is_ok() {
         println!("CASM: {}", generator.debug_instructions());
     }
+    for report in generator.frame_reports() {
+        tracing::info!("{report}");
+    }
     generator.compile()
 }
+
+#[cfg(test)]
+mod tests {
+    use cairo_m_common::Instruction;
+
+    use super::*;
+
+    fn validate(instruction: Instruction) -> Result<(), CodegenError> {
+        validate_casm_accesses("main", &[InstructionBuilder::from(instruction)])
+    }
+
+    #[test]
+    fn test_accepts_distinct_reads_and_read_then_write() {
+        assert!(
+            validate(Instruction::StoreAddFpFp {
+                src0_off: M31(0),
+                src1_off: M31(1),
+                dst_off: M31(0),
+            })
+            .is_ok()
+        );
+        assert!(
+            validate(Instruction::U32StoreAddFpFp {
+                src0_off: M31(0),
+                src1_off: M31(2),
+                dst_off: M31(1),
+            })
+            .is_ok()
+        );
+        assert!(validate(Instruction::Ret {}).is_ok());
+        assert!(validate(Instruction::PrintM31 { offset: M31(0) }).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_double_read() {
+        let err = validate(Instruction::StoreMulFpFp {
+            src0_off: -M31(3),
+            src1_off: -M31(3),
+            dst_off: M31(0),
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Function 'main', instruction 0 `StoreMulFpFp"),
+            "{err}"
+        );
+        assert!(err.to_string().contains("reads [fp - 3] twice"), "{err}");
+    }
+
+    #[test]
+    fn test_rejects_overlapping_u32_reads() {
+        let err = validate(Instruction::U32StoreLtFpFp {
+            src0_off: M31(4),
+            src1_off: M31(5),
+            dst_off: M31(0),
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("reads [fp + 5] twice"), "{err}");
+    }
+
+    #[test]
+    fn test_rejects_double_write_with_source_span() {
+        let instruction = InstructionBuilder::from(Instruction::U32StoreDivRemFpImm {
+            src_off: M31(0),
+            imm_lo: M31(3),
+            imm_hi: M31(0),
+            dst_off: M31(2),
+            dst_rem_off: M31(3),
+        })
+        .with_comment("u32 divrem".to_string())
+        .with_default_source_span(Some(10..25));
+        let err = validate_casm_accesses("f", &[instruction]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unprovable instruction: Function 'f', instruction 0 `u32 divrem` \
+            (source span 10..25): writes [fp + 3] twice, which the prover cannot constrain"
+        );
+    }
+}
@@ -11,6 +11,8 @@
 //! set of u32 comparison ops when called directly (e.g., unit tests), but the
 //! generator asserts that illegalized ops do not reach this stage.
 
+use std::ops::Range;
+
 use cairo_m_compiler_mir::{BinaryOp, DataLayout, Literal, MirType, Value, ValueId};
 use cairo_m_compiler_parser::parser::UnaryOp;
 use stwo_prover::core::fields::m31::M31;
@@ -19,9 +21,10 @@ use crate::{CodegenError, CodegenResult, FunctionLayout, InstructionBuilder, Lab
 
 // Centralized emission helpers for instruction/label/touch routing.
 mod aggregates;
-mod asserts;
+pub(crate) mod asserts;
 pub(crate) mod calls;
 mod ctrlflow;
+mod debug;
 mod emit;
 mod felt;
 pub(crate) mod normalize;
@@ -50,6 +53,11 @@ pub struct CasmBuilder {
     pub(super) label_counter: usize,
     /// Highest fp+ offset that has been written to (for optimization tracking)
     pub(super) max_written_offset: i32,
+    /// Source span of the MIR instruction being lowered, attached to emitted instructions
+    pub(super) source_span: Option<Range<usize>>,
+    /// Operand copies avoided by emitting operations reading the same cell twice as
+    /// single-read equivalents, instead of leaving them to the `deduplicate-operands` pass
+    pub(crate) avoided_operand_copies: usize,
 }
 
 /// Represents the type of array operation to perform
@@ -78,6 +86,8 @@ impl CasmBuilder {
             layout,
             label_counter,
             max_written_offset,
+            source_span: None,
+            avoided_operand_copies: 0,
         }
     }
 
@@ -253,6 +263,7 @@ impl CasmBuilder {
             BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
                 self.felt_arith(op, dest_off, left, right)?;
             }
+            BinaryOp::Pow => self.felt_pow(dest_off, left, right)?,
             BinaryOp::Eq => self.felt_eq(dest_off, left, right)?,
             BinaryOp::Neq => self.felt_neq(dest_off, left, right)?,
             BinaryOp::And => self.bool_and(dest_off, left, right)?,
@@ -278,6 +289,11 @@ impl CasmBuilder {
             | BinaryOp::U32BitwiseXor => {
                 self.u32_op(op, dest_off, left, right)?;
             }
+            BinaryOp::U32ShiftLeft | BinaryOp::U32ShiftRight => {
+                return Err(CodegenError::UnsupportedInstruction(format!(
+                    "u32 shift {op} should have been legalized before codegen"
+                )));
+            }
         }
 
         Ok(())
@@ -296,6 +312,7 @@ impl CasmBuilder {
             BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
                 self.felt_arith(op, dest_off, left, right)
             }
+            BinaryOp::Pow => self.felt_pow(dest_off, left, right),
             BinaryOp::Eq => self.felt_eq(dest_off, left, right),
             BinaryOp::Neq => self.felt_neq(dest_off, left, right),
             BinaryOp::And => self.bool_and(dest_off, left, right),
@@ -319,6 +336,11 @@ impl CasmBuilder {
             | BinaryOp::U32BitwiseAnd
             | BinaryOp::U32BitwiseOr
             | BinaryOp::U32BitwiseXor => self.u32_op(op, dest_off, left, right),
+            BinaryOp::U32ShiftLeft | BinaryOp::U32ShiftRight => {
+                Err(CodegenError::UnsupportedInstruction(format!(
+                    "u32 shift {op} should have been legalized before codegen"
+                )))
+            }
         }
     }
 
@@ -342,6 +364,11 @@ impl CasmBuilder {
         &mut self.labels
     }
 
+    /// Get the layout
+    pub const fn layout(&self) -> &FunctionLayout {
+        &self.layout
+    }
+
     /// Get a mutable reference to the layout
     pub const fn layout_mut(&mut self) -> &mut FunctionLayout {
         &mut self.layout
@@ -472,12 +499,187 @@ impl CasmBuilder {
 
                 Ok(())
             }
+            (MirType::Felt, MirType::U32) => self.cast_felt_to_u32(dest, source),
+            (MirType::Bool, MirType::Felt | MirType::U32) => {
+                self.cast_bool_to_numeric(dest, source, target_type)
+            }
+            (MirType::Felt | MirType::U32, MirType::Bool) => {
+                self.cast_numeric_to_bool(dest, source, source_type)
+            }
             _ => Err(CodegenError::UnsupportedInstruction(format!(
                 "Unsupported cast from {} to {}",
                 source_type, target_type
             ))),
         }
     }
+
+    /// Casts a felt to a u32. Felts are below P = 2^31 - 1, so the cast never fails.
+    ///
+    /// The limbs are computed with felt operations only: the value is decomposed bit by bit
+    /// from bit 30 down to bit 16, each comparison proving the range of the remainder. The
+    /// final remainder is the low limb, and the high limb is (value - lo) / 2^16.
+    fn cast_felt_to_u32(&mut self, dest: ValueId, source: Value) -> CodegenResult<()> {
+        const U32_LO_BITS: u32 = 16;
+        const FELT_TOP_BIT: u32 = 30;
+
+        let dest_off = self.layout.allocate_local(dest, 2)?;
+        let src_off = match source {
+            Value::Operand(id) => self.layout.get_offset(id)?,
+            Value::Literal(Literal::Integer(imm)) => {
+                self.store_u32_immediate(
+                    imm,
+                    dest_off,
+                    format!(
+                        "u32([fp + {dest_off}], [fp + {}]) = u32({imm})",
+                        dest_off + 1
+                    ),
+                );
+                return Ok(());
+            }
+            _ => {
+                return Err(CodegenError::InvalidMir(
+                    "Cast source must be an operand".to_string(),
+                ));
+            }
+        };
+
+        let bit_unset = self.layout.reserve_stack(1);
+        let shifted = self.layout.reserve_stack(1);
+        let sum = self.layout.reserve_stack(1);
+        let remainder = self.layout.reserve_stack(1);
+
+        // remainder = remainder - 2^bit if the bit is set, i.e. remainder + bit_unset * 2^bit - 2^bit
+        let mut current = src_off;
+        for bit in (U32_LO_BITS..=FELT_TOP_BIT).rev() {
+            let pow = 1i32 << bit;
+            self.felt_le_fp_imm(
+                current,
+                pow - 1,
+                bit_unset,
+                format!(
+                    "[fp + {bit_unset}] = [fp + {current}] <= {} // bit {bit} unset",
+                    pow - 1
+                ),
+            );
+            self.felt_mul_fp_imm(
+                bit_unset,
+                pow,
+                shifted,
+                format!("[fp + {shifted}] = [fp + {bit_unset}] * {pow}"),
+            );
+            self.felt_add_fp_fp(
+                current,
+                shifted,
+                sum,
+                format!("[fp + {sum}] = [fp + {current}] + [fp + {shifted}]"),
+            );
+            let next = if bit == U32_LO_BITS {
+                dest_off
+            } else {
+                remainder
+            };
+            self.felt_sub_fp_imm(sum, pow, next, String::new());
+            current = next;
+        }
+
+        // hi = (value - lo) / 2^16
+        self.felt_sub_fp_fp(
+            src_off,
+            dest_off,
+            sum,
+            format!("[fp + {sum}] = [fp + {src_off}] - [fp + {dest_off}] // value - lo"),
+        );
+        self.felt_div_fp_imm(sum, 1 << U32_LO_BITS, dest_off + 1, String::new())
+    }
+
+    /// Casts a bool to a felt or a u32, whose value is 0 or 1.
+    fn cast_bool_to_numeric(
+        &mut self,
+        dest: ValueId,
+        source: Value,
+        target_type: &MirType,
+    ) -> CodegenResult<()> {
+        let size = DataLayout::memory_size_of(target_type);
+        let dest_off = self.layout.allocate_local(dest, size)?;
+        match source {
+            Value::Operand(id) => {
+                let src_off = self.layout.get_offset(id)?;
+                self.store_copy_single(
+                    src_off,
+                    dest_off,
+                    format!("[fp + {dest_off}] = [fp + {src_off}] + 0 // Cast bool->{target_type}"),
+                );
+                if size == 2 {
+                    self.store_immediate(0, dest_off + 1, format!("[fp + {}] = 0", dest_off + 1));
+                }
+                Ok(())
+            }
+            Value::Literal(literal @ (Literal::Boolean(_) | Literal::Integer(0 | 1))) => self
+                .copy_value_to_offset(
+                    &Value::integer(literal.as_integer().unwrap_or_default()),
+                    dest_off,
+                    size,
+                ),
+            _ => Err(CodegenError::InvalidMir(
+                "Cast source must be an operand".to_string(),
+            )),
+        }
+    }
+
+    /// Casts a felt or a u32 to a bool, asserting that the value is 0 or 1.
+    fn cast_numeric_to_bool(
+        &mut self,
+        dest: ValueId,
+        source: Value,
+        source_type: &MirType,
+    ) -> CodegenResult<()> {
+        let dest_off = self.layout.allocate_local(dest, 1)?;
+        match source {
+            Value::Operand(id) => {
+                let src_off = self.layout.get_offset(id)?;
+                if matches!(source_type, MirType::U32) {
+                    self.assert_eq_fp_imm(
+                        src_off + 1,
+                        0,
+                        format!("assert [fp + {}] == 0 // hi == 0", src_off + 1),
+                    );
+                }
+
+                // value * (value - 1) == 0 iff value is 0 or 1
+                let minus_one = self.layout.reserve_stack(1);
+                self.felt_sub_fp_imm(src_off, 1, minus_one, String::new());
+                let product = self.layout.reserve_stack(1);
+                self.felt_mul_fp_fp(
+                    src_off,
+                    minus_one,
+                    product,
+                    format!("[fp + {product}] = [fp + {src_off}] * [fp + {minus_one}]"),
+                );
+                self.assert_eq_fp_imm(
+                    product,
+                    0,
+                    format!("assert [fp + {product}] == 0 // value is 0 or 1"),
+                );
+
+                self.store_copy_single(
+                    src_off,
+                    dest_off,
+                    format!("[fp + {dest_off}] = [fp + {src_off}] + 0 // Cast {source_type}->bool"),
+                );
+                Ok(())
+            }
+            Value::Literal(Literal::Integer(imm @ (0 | 1))) => {
+                self.store_immediate(imm, dest_off, format!("[fp + {dest_off}] = {imm}"));
+                Ok(())
+            }
+            Value::Literal(Literal::Integer(_)) => Err(CodegenError::InvalidMir(
+                "Cast source is a literal that is neither 0 nor 1".to_string(),
+            )),
+            _ => Err(CodegenError::InvalidMir(
+                "Cast source must be an operand".to_string(),
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
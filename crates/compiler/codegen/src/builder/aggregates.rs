@@ -370,13 +370,71 @@ impl super::CasmBuilder {
 
         Ok(())
     }
+
+    /// Copies the storage of an aggregate as a block
+    ///
+    /// Structs and tuples are copied slot by slot into the storage of `dest`. Fixed arrays
+    /// are passed by pointer: their elements are copied to freshly reserved storage, and
+    /// `dest` holds a pointer to it.
+    pub(crate) fn copy_aggregate(
+        &mut self,
+        dest: ValueId,
+        source: Value,
+        ty: &MirType,
+    ) -> CodegenResult<()> {
+        let Value::Operand(src_id) = source else {
+            return Err(CodegenError::InvalidMir(format!(
+                "CopyAggregate source must be an operand, got {:?}",
+                source
+            )));
+        };
+        let src_off = self.layout.get_offset(src_id)?;
+
+        match ty {
+            MirType::FixedArray { element_type, size } => {
+                let total_size = DataLayout::value_size_of(element_type) * size;
+                let base_offset = if total_size > 0 {
+                    self.layout.reserve_stack(total_size)
+                } else {
+                    self.layout.current_frame_usage()
+                };
+                self.copy_block_from_pointer(src_off, base_offset, total_size);
+
+                let dest_offset = self.layout.allocate_local(dest, 1)?;
+                self.store_fp_plus_imm(
+                    base_offset,
+                    dest_offset,
+                    format!("[fp + {dest_offset}] = fp + {base_offset}"),
+                );
+            }
+            MirType::Struct { .. } | MirType::Tuple(_) => {
+                let size = DataLayout::memory_size_of(ty);
+                let dest_offset = match self.layout.get_offset(dest) {
+                    Ok(offset) => offset,
+                    Err(_) => self.layout.allocate_local(dest, size)?,
+                };
+                self.copy_block(src_off, dest_offset, size);
+            }
+            _ => {
+                return Err(CodegenError::InvalidMir(format!(
+                    "CopyAggregate on non-aggregate type {:?}",
+                    ty
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use cairo_m_common::instruction::{STORE_FRAME_POINTER, STORE_IMM, U32_STORE_IMM};
+    use cairo_m_common::instruction::{
+        JNZ_FP_IMM, STORE_DOUBLE_DEREF_FP, STORE_FRAME_POINTER, STORE_IMM, U32_STORE_IMM,
+    };
     use cairo_m_compiler_mir::{MirFunction, MirType, Value, ValueId};
     use proptest::prelude::*;
+    use stwo_prover::core::fields::m31::M31;
 
     use super::*;
     use crate::builder::CasmBuilder;
@@ -837,6 +895,98 @@ mod tests {
         assert_eq!(u32_imm_stores, 2);
     }
 
+    // =========================================================================
+    // Aggregate Copy Tests
+    // =========================================================================
+
+    #[test]
+    fn test_copy_aggregate_small_tuple_is_unrolled() {
+        let mut b = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let tuple_ty = MirType::Tuple(vec![MirType::Felt, MirType::U32]);
+        let src = ValueId::from_raw(1);
+        let dest = ValueId::from_raw(2);
+        b.layout.allocate_value(src, 3).unwrap();
+
+        b.copy_aggregate(dest, Value::operand(src), &tuple_ty)
+            .unwrap();
+        assert_eq!(b.instructions.len(), 3);
+
+        let src_off = b.layout.get_offset(src).unwrap();
+        let dest_off = b.layout.get_offset(dest).unwrap();
+        let mut mem = Mem::new(64);
+        for i in 0..3 {
+            mem.set(src_off + i, M31::from(10 + i as u32));
+        }
+        exec(&mut mem, &b.instructions).unwrap();
+        for i in 0..3 {
+            assert_eq!(mem.get(dest_off + i), M31::from(10 + i as u32));
+        }
+    }
+
+    #[test]
+    fn test_copy_aggregate_large_struct_uses_loop() {
+        let mut b = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let fields = (0..20).map(|i| (format!("f{i}"), MirType::Felt)).collect();
+        let struct_ty = MirType::Struct {
+            name: "Big".to_string(),
+            fields,
+        };
+        let src = ValueId::from_raw(1);
+        let dest = ValueId::from_raw(2);
+        b.layout.allocate_value(src, 20).unwrap();
+
+        b.copy_aggregate(dest, Value::operand(src), &struct_ty)
+            .unwrap();
+
+        // Source and destination pointers, counter, then a four-instruction loop body
+        assert_eq!(b.instructions.len(), 7);
+        assert_eq!(
+            b.instructions.last().unwrap().inner_instr().opcode_value(),
+            JNZ_FP_IMM
+        );
+        assert!(b.layout.value_layouts.contains_key(&dest));
+    }
+
+    #[test]
+    fn test_copy_aggregate_fixed_array_copies_elements() {
+        let mut b = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let array_ty = MirType::FixedArray {
+            element_type: Box::new(MirType::Felt),
+            size: 3,
+        };
+        let src = ValueId::from_raw(1);
+        let dest = ValueId::from_raw(2);
+        b.layout.allocate_value(src, 1).unwrap();
+
+        b.copy_aggregate(dest, Value::operand(src), &array_ty)
+            .unwrap();
+
+        // One load per element, then the pointer to the copy
+        let loads = b
+            .instructions
+            .iter()
+            .filter(|i| i.inner_instr().opcode_value() == STORE_DOUBLE_DEREF_FP)
+            .count();
+        assert_eq!(loads, 3);
+        assert_eq!(
+            b.instructions.last().unwrap().inner_instr().opcode_value(),
+            STORE_FRAME_POINTER
+        );
+        assert!(b.layout.value_layouts.contains_key(&dest));
+    }
+
+    #[test]
+    fn test_copy_aggregate_rejects_scalars() {
+        let mut b = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let src = ValueId::from_raw(1);
+        b.layout.allocate_value(src, 1).unwrap();
+
+        assert!(
+            b.copy_aggregate(ValueId::from_raw(2), Value::operand(src), &MirType::Felt)
+                .is_err()
+        );
+    }
+
     // =========================================================================
     // Property Tests
     // =========================================================================
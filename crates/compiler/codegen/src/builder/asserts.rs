@@ -1,4 +1,4 @@
-use cairo_m_common::Instruction as CasmInstr;
+use cairo_m_common::{AbortInfo, Instruction as CasmInstr};
 use stwo_prover::core::fields::m31::M31;
 
 use crate::InstructionBuilder;
@@ -12,6 +12,35 @@ impl super::CasmBuilder {
         .with_comment(comment);
         self.emit_push(instr);
     }
+
+    /// Emits an assertion that always fails, tagged with the message the runner reports.
+    ///
+    /// Stores 0 in a fresh slot and asserts that it equals 1, so no valid trace can go
+    /// past this instruction.
+    pub(crate) fn abort(&mut self, info: AbortInfo) {
+        let slot = self.layout.reserve_stack(1);
+        for instr in abort_instructions(slot, info) {
+            self.emit_push(instr);
+        }
+    }
+}
+
+/// Instructions of an abort: a store of 0 to `[fp + slot]`, then an assertion that it
+/// equals 1, tagged with the message the runner reports.
+pub(crate) fn abort_instructions(slot: i32, info: AbortInfo) -> [InstructionBuilder; 2] {
+    let store = InstructionBuilder::from(CasmInstr::StoreImm {
+        imm: M31::from(0),
+        dst_off: M31::from(slot),
+    })
+    .with_comment(format!("[fp + {slot}] = 0"));
+    let comment = format!("abort: {info}");
+    let assert = InstructionBuilder::from(CasmInstr::AssertEqFpImm {
+        src_off: M31::from(slot),
+        imm: M31::from(1),
+    })
+    .with_comment(comment)
+    .with_abort(info);
+    [store, assert]
 }
 
 #[cfg(test)]
@@ -32,4 +61,24 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_abort() {
+        let mut builder = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let info = AbortInfo {
+            message: "boom".to_string(),
+            location: Some("main.cm:1:1".to_string()),
+        };
+        builder.abort(info.clone());
+        assert_eq!(builder.instructions.len(), 2);
+        assert!(builder.instructions[0].abort().is_none());
+        assert_eq!(
+            builder.instructions[1].inner_instr(),
+            &CasmInstr::AssertEqFpImm {
+                src_off: M31::from(0),
+                imm: M31::from(1),
+            }
+        );
+        assert_eq!(builder.instructions[1].abort(), Some(&info));
+    }
 }
@@ -0,0 +1,96 @@
+use cairo_m_common::Instruction as CasmInstr;
+use cairo_m_compiler_mir::{Literal, MirType, Value};
+use stwo_prover::core::fields::m31::M31;
+
+use crate::{CodegenError, CodegenResult, InstructionBuilder};
+
+impl super::CasmBuilder {
+    /// Emit a debug print of a scalar value.
+    ///
+    /// Operands are printed in place. Literals (e.g. after constant propagation)
+    /// are first materialized in a scratch stack slot.
+    pub(crate) fn debug_print(&mut self, value: &Value, ty: &MirType) -> CodegenResult<()> {
+        let is_u32 = match ty {
+            MirType::Felt | MirType::Bool => false,
+            MirType::U32 => true,
+            other => {
+                return Err(CodegenError::UnsupportedInstruction(format!(
+                    "debug_print is not supported for type {other}"
+                )));
+            }
+        };
+
+        let offset = match value {
+            Value::Operand(id) => self.layout_mut().get_offset(*id)?,
+            Value::Literal(Literal::Integer(imm)) => {
+                let slots = if is_u32 { 2 } else { 1 };
+                let off = self.layout_mut().reserve_stack(slots);
+                if is_u32 {
+                    self.store_u32_immediate(
+                        *imm,
+                        off,
+                        format!("u32([fp + {off}], [fp + {}]) = u32({imm})", off + 1),
+                    );
+                } else {
+                    self.store_immediate(*imm, off, format!("[fp + {off}] = {imm}"));
+                }
+                off
+            }
+            Value::Literal(Literal::Boolean(b)) => {
+                let off = self.layout_mut().reserve_stack(1);
+                self.store_immediate(*b as u32, off, format!("[fp + {off}] = {b}"));
+                off
+            }
+            other => {
+                return Err(CodegenError::InvalidMir(format!(
+                    "Invalid value for debug_print: {other:?}"
+                )));
+            }
+        };
+
+        let instr = if is_u32 {
+            InstructionBuilder::from(CasmInstr::PrintU32 {
+                offset: M31::from(offset),
+            })
+            .with_comment(format!(
+                "debug_print u32([fp + {offset}], [fp + {}])",
+                offset + 1
+            ))
+        } else {
+            InstructionBuilder::from(CasmInstr::PrintM31 {
+                offset: M31::from(offset),
+            })
+            .with_comment(format!("debug_print [fp + {offset}]"))
+        };
+        self.emit_push(instr);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CasmBuilder, FunctionLayout};
+
+    #[test]
+    fn test_debug_print_literal_u32() {
+        let mut builder = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        builder
+            .debug_print(&Value::integer(70000), &MirType::U32)
+            .unwrap();
+        assert_eq!(builder.instructions.len(), 2);
+        assert_eq!(
+            builder.instructions[1].inner_instr(),
+            &CasmInstr::PrintU32 {
+                offset: M31::from(0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_debug_print_rejects_aggregates() {
+        let mut builder = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        let ty = MirType::Tuple(vec![MirType::Felt, MirType::Felt]);
+        assert!(builder.debug_print(&Value::integer(1), &ty).is_err());
+    }
+}
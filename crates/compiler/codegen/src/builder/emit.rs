@@ -4,13 +4,46 @@
 //! instructions, label creation, and frame write tracking. The goal is to
 //! make instruction emission uniform and easy to audit.
 
+use std::ops::Range;
+
+use cairo_m_common::Instruction as CasmInstr;
+
 use crate::{InstructionBuilder, Label};
 
 /// Emission helpers wired onto the `CasmBuilder` facade.
 impl super::CasmBuilder {
-    /// Push an instruction into the program.
+    /// Push an instruction into the program, tagged with the current source span.
     pub(crate) fn emit_push(&mut self, instr: InstructionBuilder) {
-        self.instructions.push(instr);
+        self.instructions
+            .push(instr.with_default_source_span(self.source_span.clone()));
+    }
+
+    /// Set the source span attached to the instructions emitted from now on.
+    pub(crate) fn set_source_span(&mut self, span: Option<Range<usize>>) {
+        self.source_span = span;
+    }
+
+    /// Attach `location` to the division instructions emitted since index `start`.
+    pub(crate) fn tag_divisions(&mut self, start: usize, location: &str) {
+        for instr in &mut self.instructions[start..] {
+            if matches!(
+                instr.inner_instr(),
+                CasmInstr::StoreDivFpFp { .. }
+                    | CasmInstr::U32StoreDivRemFpFp { .. }
+                    | CasmInstr::U32StoreDivRemFpImm { .. }
+            ) {
+                instr.set_division_location(location.to_string());
+            }
+        }
+    }
+
+    /// Attach `location` to the call instructions emitted since index `start`.
+    pub(crate) fn tag_calls(&mut self, start: usize, location: &str) {
+        for instr in &mut self.instructions[start..] {
+            if matches!(instr.inner_instr(), CasmInstr::CallAbsImm { .. }) {
+                instr.set_call_location(location.to_string());
+            }
+        }
     }
 
     /// Generate a fresh label name using the builder's counter.
@@ -2,7 +2,9 @@
 //! selection to `opcodes` and uses `emit` to push instructions.
 
 use cairo_m_common::Instruction as CasmInstr;
+use cairo_m_common::instruction::MAX_POW_EXPONENT;
 use cairo_m_compiler_mir::{BinaryOp, Literal, Value};
+use stwo_prover::core::fields::FieldExpOps;
 use stwo_prover::core::fields::m31::M31;
 
 use crate::{CodegenError, CodegenResult, InstructionBuilder};
@@ -127,6 +129,48 @@ impl super::CasmBuilder {
         Ok(())
     }
 
+    /// Computes `base ** exponent`, where the exponent must be an integer literal in
+    /// `2..=MAX_POW_EXPONENT`.
+    pub(super) fn felt_pow(
+        &mut self,
+        dest_off: i32,
+        base: Value,
+        exponent: Value,
+    ) -> CodegenResult<()> {
+        let Value::Literal(Literal::Integer(exponent)) = exponent else {
+            return Err(CodegenError::InvalidMir(format!(
+                "Felt exponent must be an integer literal, got {exponent:?}"
+            )));
+        };
+        if !(2..=MAX_POW_EXPONENT).contains(&exponent) {
+            return Err(CodegenError::InvalidMir(format!(
+                "Felt exponent {exponent} out of range 2..={MAX_POW_EXPONENT}"
+            )));
+        }
+
+        match base {
+            Value::Operand(id) => {
+                let src_off = self.layout.get_offset(id)?;
+                self.felt_pow_fp_imm(
+                    src_off,
+                    exponent as i32,
+                    dest_off,
+                    format!("[fp + {dest_off}] = [fp + {src_off}] ** {exponent}"),
+                );
+            }
+            Value::Literal(lit) => {
+                let res = M31::from(Self::literal_to_i32(&lit) as u32)
+                    .pow(exponent as u128)
+                    .0;
+                self.store_immediate(res, dest_off, format!("[fp + {dest_off}] = {res}"));
+            }
+            Value::Error => {
+                return Err(CodegenError::InvalidMir("Invalid felt pow base".into()));
+            }
+        }
+        Ok(())
+    }
+
     pub(super) fn felt_eq(
         &mut self,
         dest_off: i32,
@@ -187,6 +231,11 @@ impl super::CasmBuilder {
         src1_off: i32,
         dst_off: i32,
     ) -> CodegenResult<()> {
+        if src0_off == src1_off && self.felt_same_operand_op(op, src0_off, dst_off) {
+            self.avoided_operand_copies += 1;
+            return Ok(());
+        }
+
         let comment = format!("[fp + {dst_off}] = [fp + {src0_off}] op [fp + {src1_off}]");
         match op {
             BinaryOp::Add => self.felt_add_fp_fp(src0_off, src1_off, dst_off, comment),
@@ -202,6 +251,33 @@ impl super::CasmBuilder {
         Ok(())
     }
 
+    /// Emits `[fp + src_off] op [fp + src_off]` reading the operand once, as two reads of the
+    /// same cell in one instruction are invalid
+    ///
+    /// Returns false if `op` has no single-read equivalent: the operand then needs a copy.
+    fn felt_same_operand_op(&mut self, op: BinaryOp, src_off: i32, dst_off: i32) -> bool {
+        match op {
+            BinaryOp::Add => self.felt_mul_fp_imm(
+                src_off,
+                2,
+                dst_off,
+                format!("[fp + {dst_off}] = [fp + {src_off}] * 2 // x + x"),
+            ),
+            BinaryOp::Sub => {
+                self.store_immediate(0, dst_off, format!("[fp + {dst_off}] = 0 // x - x"))
+            }
+            BinaryOp::Mul => self.felt_pow_fp_imm(
+                src_off,
+                2,
+                dst_off,
+                format!("[fp + {dst_off}] = [fp + {src_off}] ^ 2 // x * x"),
+            ),
+            // x / x faults when x is 0, which must be preserved
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn felt_fp_imm_op(
         &mut self,
         op: BinaryOp,
@@ -232,6 +308,7 @@ impl super::CasmBuilder {
     felt_fp_imm_op!(felt_mul_fp_imm, StoreMulFpImm);
     felt_fp_imm_op!(felt_add_fp_imm, StoreAddFpImm);
     felt_fp_imm_op!(felt_le_fp_imm, StoreLeFpImm);
+    felt_fp_imm_op!(felt_pow_fp_imm, StorePowFpImm);
 
     pub(crate) fn felt_sub_fp_imm(
         &mut self,
@@ -453,6 +530,31 @@ mod tests {
         assert_eq!(mem.get(5), M31::from(150));
     }
 
+    #[test]
+    fn test_felt_same_operand_reads_it_once() {
+        for (op, expected) in [(BinaryOp::Add, 14), (BinaryOp::Sub, 0), (BinaryOp::Mul, 49)] {
+            let (mut b, x) = mk_builder_with_value(7);
+            b.felt_arith(op, 5, Value::operand(x), Value::operand(x))
+                .unwrap();
+
+            assert_eq!(b.instructions.len(), 2);
+            assert!(
+                !matches!(
+                    b.instructions[1].inner_instr(),
+                    CasmInstr::StoreAddFpFp { .. }
+                        | CasmInstr::StoreSubFpFp { .. }
+                        | CasmInstr::StoreMulFpFp { .. }
+                ),
+                "{op} should not read the operand twice"
+            );
+            assert_eq!(b.avoided_operand_copies, 1);
+
+            let mut mem = Mem::new(10);
+            exec(&mut mem, &b.instructions).unwrap();
+            assert_eq!(mem.get(5), M31::from(expected));
+        }
+    }
+
     #[test]
     fn test_felt_mul_imm_left_normalized() {
         let (mut b, right) = mk_builder_with_value(7);
@@ -6,7 +6,10 @@ use cairo_m_common::Instruction as CasmInstr;
 use cairo_m_compiler_mir::{Literal, Value};
 use stwo_prover::core::fields::m31::M31;
 
-use crate::{CodegenError, CodegenResult, InstructionBuilder};
+use crate::{CodegenError, CodegenResult, InstructionBuilder, Label};
+
+/// Largest block copy emitted as one store per slot; larger blocks are copied by a loop
+const BLOCK_COPY_UNROLL_LIMIT: usize = 8;
 
 impl super::CasmBuilder {
     /// Store copy of an M31 from src to dest with an exact comment string.
@@ -117,6 +120,71 @@ impl super::CasmBuilder {
         }
     }
 
+    /// Copy a block of `slots` consecutive words from `src_off` to `dest_off`, unrolled up
+    /// to `BLOCK_COPY_UNROLL_LIMIT` slots and with a loop above.
+    pub(super) fn copy_block(&mut self, src_off: i32, dest_off: i32, slots: usize) {
+        if slots <= BLOCK_COPY_UNROLL_LIMIT {
+            self.copy_slots(src_off, dest_off, slots, "Block copy");
+            return;
+        }
+        let src_ptr = self.layout.reserve_stack(1);
+        self.store_fp_plus_imm(
+            src_off,
+            src_ptr,
+            format!("[fp + {src_ptr}] = fp + {src_off}"),
+        );
+        self.copy_block_loop(src_ptr, dest_off, slots);
+    }
+
+    /// Copy a block of `slots` words from the address stored at `[fp + ptr_off]` to
+    /// `dest_off`, unrolled up to `BLOCK_COPY_UNROLL_LIMIT` slots and with a loop above.
+    pub(super) fn copy_block_from_pointer(&mut self, ptr_off: i32, dest_off: i32, slots: usize) {
+        if slots <= BLOCK_COPY_UNROLL_LIMIT {
+            for i in 0..slots {
+                let d = dest_off + i as i32;
+                self.store_from_double_deref_fp_imm(
+                    ptr_off,
+                    i as i32,
+                    d,
+                    format!("Block copy slot {i}: [fp + {d}] = [[fp + {ptr_off}] + {i}]"),
+                );
+            }
+            return;
+        }
+        self.copy_block_loop(ptr_off, dest_off, slots);
+    }
+
+    /// Copy loop from the last slot down to the first: the counter holds both the number of
+    /// slots left to copy and, once decremented, the index of the next one.
+    fn copy_block_loop(&mut self, src_ptr: i32, dest_off: i32, slots: usize) {
+        let dest_ptr = self.layout.reserve_stack(1);
+        let counter = self.layout.reserve_stack(1);
+        let temp = self.layout.reserve_stack(1);
+        self.store_fp_plus_imm(
+            dest_off,
+            dest_ptr,
+            format!("[fp + {dest_ptr}] = fp + {dest_off}"),
+        );
+        self.store_immediate(slots as u32, counter, format!("[fp + {counter}] = {slots}"));
+
+        let loop_label = self.emit_new_label_name("block_copy");
+        self.emit_add_label(Label::new(loop_label.clone()));
+        self.felt_sub_fp_imm(counter, 1, counter, String::new());
+        self.store_from_double_deref_fp_fp(
+            src_ptr,
+            counter,
+            temp,
+            format!("[fp + {temp}] = [[fp + {src_ptr}] + [fp + {counter}]]"),
+        );
+        self.store_to_double_deref_fp_fp(
+            dest_ptr,
+            counter,
+            temp,
+            format!("[[fp + {dest_ptr}] + [fp + {counter}]] = [fp + {temp}]"),
+        );
+        self.jnz_offset(counter, &loop_label);
+    }
+
     pub(crate) fn store_to_double_deref_fp_imm(
         &mut self,
         src_off: i32,
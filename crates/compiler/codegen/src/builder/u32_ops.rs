@@ -216,6 +216,24 @@ impl super::CasmBuilder {
         src1_off: i32,
         dest_off: i32,
     ) -> CodegenResult<()> {
+        if src0_off == src1_off {
+            // x == x and x < x are known without reading x
+            let result = match op {
+                BinaryOp::U32Eq => Some(1),
+                BinaryOp::U32Less => Some(0),
+                _ => None,
+            };
+            if let Some(result) = result {
+                self.store_immediate(
+                    result,
+                    dest_off,
+                    format!("[fp + {dest_off}] = {result} // x {op} x"),
+                );
+                self.avoided_operand_copies += 1;
+                return Ok(());
+            }
+        }
+
         let comment = format!(
             "[fp + {dest_off}] = u32([fp + {src0_off}], [fp + {}]) {op} u32([fp + {src1_off}], [fp + {}])",
             src0_off + 1,
@@ -250,6 +268,11 @@ impl super::CasmBuilder {
         src1_off: i32,
         dest_off: i32,
     ) -> CodegenResult<()> {
+        if src0_off == src1_off && self.u32_same_operand_op(op, src0_off, dest_off) {
+            self.avoided_operand_copies += 1;
+            return Ok(());
+        }
+
         let comment = format!(
             "u32([fp + {dest_off}], [fp + {}]) = u32([fp + {src0_off}], [fp + {}]) {op} u32([fp + {src1_off}], [fp + {}])",
             dest_off + 1,
@@ -274,6 +297,36 @@ impl super::CasmBuilder {
         Ok(())
     }
 
+    /// Emits `u32([fp + src_off], ..) op u32([fp + src_off], ..)` reading the operand once, as
+    /// two reads of the same cells in one instruction are invalid
+    ///
+    /// Returns false if `op` has no single-read equivalent: the operand then needs a copy.
+    fn u32_same_operand_op(&mut self, op: BinaryOp, src_off: i32, dest_off: i32) -> bool {
+        match op {
+            BinaryOp::U32Add => self.u32_mul_fp_imm(
+                src_off,
+                2,
+                dest_off,
+                format!(
+                    "u32([fp + {dest_off}], [fp + {}]) = u32([fp + {src_off}], [fp + {}]) * u32(2, 0) // x + x",
+                    dest_off + 1,
+                    src_off + 1
+                ),
+            ),
+            BinaryOp::U32Sub => self.store_u32_immediate(
+                0,
+                dest_off,
+                format!(
+                    "[fp + {dest_off}], [fp + {}] = u32(0) // x - x",
+                    dest_off + 1
+                ),
+            ),
+            // x / x and x % x fault when x is 0, which must be preserved
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn u32_fp_imm_op(
         &mut self,
         op: BinaryOp,
@@ -3,8 +3,8 @@
 use std::sync::Arc;
 
 use cairo_m_common::Program;
-use cairo_m_compiler_mir::MirDb;
-use cairo_m_compiler_mir::pipeline::PipelineConfig;
+use cairo_m_compiler_mir::pipeline::{OptimizationLevel, PipelineConfig};
+use cairo_m_compiler_mir::{MirDb, MirModule, MirPass};
 use cairo_m_compiler_parser::Upcast;
 use cairo_m_compiler_semantic::db::Crate;
 
@@ -33,23 +33,53 @@ pub fn compile_project_with_config(
     crate_id: Crate,
     pipeline: PipelineConfig,
 ) -> Result<Arc<Program>, CodegenError> {
+    compile_project_with_passes(db, crate_id, pipeline, &mut [])
+}
+
+/// Compile a crate to a compiled program, running custom passes in the MIR pipeline.
+pub fn compile_project_with_passes(
+    db: &dyn CodegenDb,
+    crate_id: Crate,
+    pipeline: PipelineConfig,
+    extra_passes: &mut [Box<dyn MirPass>],
+) -> Result<Arc<Program>, CodegenError> {
+    compile_project_with_mir(db, crate_id, pipeline, extra_passes).map(|(program, _)| program)
+}
+
+/// Compile a crate to a compiled program, also returning the optimized MIR it was generated
+/// from, e.g. to visualize it.
+pub fn compile_project_with_mir(
+    db: &dyn CodegenDb,
+    crate_id: Crate,
+    pipeline: PipelineConfig,
+    extra_passes: &mut [Box<dyn MirPass>],
+) -> Result<(Arc<Program>, Arc<MirModule>), CodegenError> {
+    let opt_level = match pipeline.optimization_level {
+        OptimizationLevel::None => 0,
+        OptimizationLevel::Standard => 1,
+    };
+
     // Get the MIR module using provided pipeline config
-    let mir_module =
-        cairo_m_compiler_mir::generate_mir_with_config(db.upcast(), crate_id, pipeline).map_err(
-            |err| {
-                CodegenError::InvalidMir(
-                    err.iter()
-                        .map(|diag| diag.to_string())
-                        .collect::<Vec<_>>()
-                        .join("\n"),
-                )
-            },
-        )?;
+    let mir_module = cairo_m_compiler_mir::generate_mir_with_passes(
+        db.upcast(),
+        crate_id,
+        pipeline,
+        extra_passes,
+    )
+    .map_err(|err| {
+        CodegenError::InvalidMir(
+            err.iter()
+                .map(|diag| diag.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    })?;
 
     // Use the existing compile_module logic
-    let compiled = crate::compile_module(&mir_module)?;
+    let mut compiled = crate::compile_module(&mir_module)?;
+    compiled.metadata.opt_level = Some(opt_level);
 
-    Ok(Arc::new(compiled))
+    Ok((Arc::new(compiled), mir_module))
 }
 
 #[cfg(test)]
@@ -4,9 +4,11 @@
 
 use std::collections::HashMap;
 
-use cairo_m_common::instruction::Instruction as CasmInstr;
+use cairo_m_common::instruction::{ISA_VERSION, Instruction as CasmInstr};
 use cairo_m_common::program::{AbiSlot, AbiType, EntrypointInfo};
-use cairo_m_common::{Program, ProgramData, ProgramMetadata};
+use cairo_m_common::{
+    AbortInfo, Builtin, InputValue, Program, ProgramData, ProgramMetadata, Symbol, SymbolKind,
+};
 use cairo_m_compiler_mir::{
     BasicBlockId, BinaryOp, DataLayout, Instruction, InstructionKind, Literal, MirFunction,
     MirModule, MirType, Projection, Terminator, Value, ValueId,
@@ -14,14 +16,21 @@ use cairo_m_compiler_mir::{
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 
+use crate::backend::validate_casm_accesses;
+use crate::builder::asserts::abort_instructions;
+use crate::mir_passes::fuse_pow::fuse_pow_module;
 use crate::mir_passes::legalize::legalize_module_for_vm;
 use crate::{
-    CasmBuilder, CodegenError, CodegenResult, FunctionLayout, InstructionBuilder, Label, passes,
+    CasmBuilder, CodegenError, CodegenResult, FrameReport, FunctionLayout, InstructionBuilder,
+    Label, passes,
 };
 
 // Mirror runner's memory model: MAX_ADDRESS = 2^28 - 1
 const MAX_ADDRESS: i32 = (1 << 28) - 1;
 
+/// Label of the address of the environment block, which the runner writes after the program
+const ENVIRONMENT_LABEL: &str = "ENVIRONMENT";
+
 /// Main code generator that orchestrates MIR to CASM translation
 #[derive(Debug)]
 pub struct CodeGenerator {
@@ -50,6 +59,21 @@ pub struct CodeGenerator {
     data_blobs: Vec<Vec<QM31>>,
     /// Label -> mutable data blob index
     data_label_to_blob: std::collections::HashMap<String, usize>,
+    /// Physical PCs of the `StoreImm` instructions whose immediate is a data address
+    relocations: Vec<u32>,
+    /// Number of environment values read by the program
+    environment_size: u32,
+    /// Physical PCs of the `StoreImm` instructions whose immediate is the environment address
+    environment_references: Vec<u32>,
+    /// Frame-size report of each generated function
+    frame_reports: Vec<FrameReport>,
+    /// Kinds of the function and block labels, exported in the symbol table
+    symbol_kinds: HashMap<String, SymbolKind>,
+    /// Symbol table, filled during label resolution
+    symbols: Vec<Symbol>,
+    /// Starts of the aborts of each function in the cold region, as function names and
+    /// logical PCs, exported as function symbols so that backtraces name the aborting function
+    cold_symbols: Vec<(String, usize)>,
 }
 
 impl CodeGenerator {
@@ -68,6 +92,13 @@ impl CodeGenerator {
             rodata_blob_to_label: std::collections::HashMap::new(),
             data_blobs: Vec::new(),
             data_label_to_blob: std::collections::HashMap::new(),
+            relocations: Vec::new(),
+            environment_size: 0,
+            environment_references: Vec::new(),
+            frame_reports: Vec::new(),
+            symbol_kinds: HashMap::new(),
+            symbols: Vec::new(),
+            cold_symbols: Vec::new(),
         }
     }
 
@@ -188,17 +219,54 @@ impl CodeGenerator {
 
         Ok(())
     }
+
+    /// Lower a LoadEnv MIR instruction into a load from the environment block.
+    ///
+    /// The block is written by the runner right after the program, so its address is only
+    /// known once the program is laid out: it is materialized with a `StoreImm` labelled with
+    /// [`ENVIRONMENT_LABEL`], resolved to the program length and recorded in the metadata so
+    /// that linking can update it.
+    fn lower_load_env(
+        &mut self,
+        dest: ValueId,
+        index: u32,
+        builder: &mut CasmBuilder,
+    ) -> CodegenResult<()> {
+        self.environment_size = self.environment_size.max(index + 1);
+
+        let env_addr_off = builder.layout_mut().reserve_stack(1);
+        let ib = InstructionBuilder::from(CasmInstr::StoreImm {
+            imm: M31::from(0),
+            dst_off: M31::from(env_addr_off),
+        })
+        .with_comment(format!("[fp + {env_addr_off}] = <{ENVIRONMENT_LABEL}>"))
+        .with_label(ENVIRONMENT_LABEL.to_string());
+        builder.emit_push(ib);
+
+        let dest_off = builder.layout_mut().allocate_local(dest, 1)?;
+        builder.store_from_double_deref_fp_imm(
+            env_addr_off,
+            index as i32,
+            dest_off,
+            format!("[fp + {dest_off}] = [[fp + {env_addr_off}] + {index}] (env {index})"),
+        );
+
+        Ok(())
+    }
+
     /// Generate CASM code for an entire MIR module
     pub fn generate_module(&mut self, module: &MirModule) -> CodegenResult<()> {
-        // Clone MIR and run target-specific legalization so builder can assume invariants.
+        // Clone MIR and run target-specific legalization and fusion so builder can assume invariants.
         let mut legalized = module.clone();
         legalize_module_for_vm(&mut legalized);
+        fuse_pow_module(&mut legalized);
 
         // Step 1: Calculate layouts for all functions (post-legalization)
         self.calculate_all_layouts(&legalized)?;
 
-        // Step 2: Generate code for all functions (first pass)
+        // Step 2: Generate code for all functions (first pass), followed by the cold region
         self.generate_all_functions(&legalized)?;
+        self.generate_cold_region(&legalized)?;
 
         // Step 3: Calculate memory layout for variable-sized instructions
         self.calculate_memory_layout()?;
@@ -211,11 +279,42 @@ impl CodeGenerator {
 
     /// Compile the generated code into a CompiledProgram.
     pub(crate) fn compile(self) -> CodegenResult<Program> {
+        // Messages of aborting instructions, keyed by physical PC
+        let abort_messages = self
+            .instructions
+            .iter()
+            .zip(&self.memory_layout)
+            .filter_map(|(instr, &pc)| instr.abort().map(|info| (pc, info.clone())))
+            .collect();
+        // Source locations of divisions, keyed by physical PC
+        let division_locations = self
+            .instructions
+            .iter()
+            .zip(&self.memory_layout)
+            .filter_map(|(instr, &pc)| {
+                instr
+                    .division_location()
+                    .map(|location| (pc, location.to_string()))
+            })
+            .collect();
+        // Source locations of call sites, keyed by physical PC
+        let call_locations = self
+            .instructions
+            .iter()
+            .zip(&self.memory_layout)
+            .filter_map(|(instr, &pc)| {
+                instr
+                    .call_location()
+                    .map(|location| (pc, location.to_string()))
+            })
+            .collect();
+
         let instructions: Vec<cairo_m_common::Instruction> = self
             .instructions
             .iter()
             .map(|instr| instr.build())
             .collect::<CodegenResult<_>>()?;
+        let builtins = instructions.iter().filter_map(Builtin::of).collect();
 
         // Build linear program data: instructions then rodata
         let mut data: Vec<ProgramData> = instructions
@@ -239,7 +338,19 @@ impl CodeGenerator {
             metadata: ProgramMetadata {
                 compiler_version: Some(env!("CARGO_PKG_VERSION").to_string()),
                 compiled_at: Some(chrono::Utc::now().to_rfc3339()),
+                git_commit: option_env!("CAIRO_M_GIT_COMMIT").map(str::to_string),
+                // Set by the pipeline, which knows the optimization level
+                opt_level: None,
+                isa_version: Some(ISA_VERSION),
+                builtins,
                 source_file: None,
+                abort_messages,
+                division_locations,
+                call_locations,
+                relocations: self.relocations,
+                environment_size: self.environment_size,
+                environment_references: self.environment_references,
+                symbols: self.symbols,
             },
             entrypoints: self.function_entrypoints,
             data,
@@ -352,6 +463,47 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Generate the cold region of the module, after its functions
+    ///
+    /// Each outlined abort is reached by a jump from the aborting function, so it runs in its
+    /// frame. It stores to a slot above the frames of all the functions, which leaves the
+    /// locals of the aborting function intact.
+    fn generate_cold_region(&mut self, module: &MirModule) -> CodegenResult<()> {
+        let slot = self
+            .frame_reports
+            .iter()
+            .map(|report| report.frame_size)
+            .max()
+            .unwrap_or(0) as i32;
+
+        let mut current_function = None;
+        for (abort_id, abort) in module.cold_aborts() {
+            if current_function != Some(abort.function) {
+                current_function = Some(abort.function);
+                let function = module.get_function(abort.function).ok_or_else(|| {
+                    CodegenError::MissingTarget(format!(
+                        "Function {:?} of cold abort {abort_id:?}",
+                        abort.function
+                    ))
+                })?;
+                self.cold_symbols
+                    .push((function.name.clone(), self.instructions.len()));
+            }
+
+            let label = Label::for_cold_abort(abort_id);
+            self.labels.push(Label {
+                address: Some(self.instructions.len()),
+                ..label
+            });
+            let info = AbortInfo {
+                message: abort.message.clone(),
+                location: abort.location.clone(),
+            };
+            self.instructions.extend(abort_instructions(slot, info));
+        }
+        Ok(())
+    }
+
     /// Generate code for a single function
     fn generate_function(
         &mut self,
@@ -380,9 +532,19 @@ impl CodeGenerator {
             .enumerate()
             .map(|(i, &value_id)| {
                 let ty = &function.value_types[&value_id];
+                let default =
+                    function
+                        .parameter_defaults
+                        .get(&value_id)
+                        .map(|literal| match literal {
+                            Literal::Integer(n) => InputValue::Number(*n as i64),
+                            Literal::Boolean(b) => InputValue::Bool(*b),
+                            Literal::Unit => InputValue::Unit,
+                        });
                 Ok(AbiSlot {
                     name: format!("arg{}", i), // TODO: Get proper names from semantic info
                     ty: abi_type_from_mir(ty)?,
+                    default,
                 })
             })
             .collect::<CodegenResult<_>>()?;
@@ -395,6 +557,7 @@ impl CodeGenerator {
                 Ok(AbiSlot {
                     name: format!("ret{}", i),
                     ty: abi_type_from_mir(&function.value_types[&value_id])?,
+                    default: None,
                 })
             })
             .collect::<CodegenResult<_>>()?;
@@ -407,6 +570,8 @@ impl CodeGenerator {
         self.function_entrypoints
             .insert(function.name.clone(), entrypoint_info);
 
+        self.symbol_kinds
+            .insert(func_label.name.clone(), SymbolKind::Function);
         builder.emit_add_label(func_label);
 
         self.generate_basic_blocks(function, module, &mut builder)?;
@@ -414,7 +579,13 @@ impl CodeGenerator {
         self.label_counter += builder.label_counter();
 
         // Run post-builder passes (deduplication, peephole opts, etc.)
-        passes::run_all(&mut builder)?;
+        let pass_spills = passes::run_all(&mut builder)?;
+        self.frame_reports.push(FrameReport::new(
+            &function.name,
+            builder.layout(),
+            pass_spills,
+        ));
+        validate_casm_accesses(&function.name, builder.instructions())?;
 
         // Fix label addresses to be relative to the global instruction stream
         let instruction_offset = self.instructions.len();
@@ -445,9 +616,12 @@ impl CodeGenerator {
         for (block_id, block) in function.basic_blocks.iter_enumerated() {
             // Add block label
             let block_label = Label::for_block(&function.name, block_id);
+            self.symbol_kinds
+                .insert(block_label.name.clone(), SymbolKind::Block);
             builder.emit_add_label(block_label);
 
             for (idx, instruction) in block.instructions.iter().enumerate() {
+                builder.set_source_span(instruction.source_span.map(|span| span.start..span.end));
                 match &instruction.kind {
                     InstructionKind::MakeFixedArray {
                         dest,
@@ -506,6 +680,9 @@ impl CodeGenerator {
                     InstructionKind::HeapAllocCells { dest, cells } => {
                         self.lower_heap_alloc_cells(*dest, cells, builder)?;
                     }
+                    InstructionKind::LoadEnv { dest, index } => {
+                        self.lower_load_env(*dest, *index, builder)?;
+                    }
                     _ => {
                         self.generate_instruction(
                             instruction,
@@ -528,6 +705,7 @@ impl CodeGenerator {
             };
 
             // Generate terminator with fall-through optimization
+            builder.set_source_span(None);
             self.generate_terminator(
                 &block.terminator,
                 &function.name,
@@ -690,7 +868,8 @@ impl CodeGenerator {
                 left,
                 right,
             } => {
-                // Post-legalization invariant: only U32Eq/U32Less remain among u32 comparisons.
+                // Post-legalization invariant: only U32Eq/U32Less remain among u32 comparisons,
+                // and shifts are expanded.
                 debug_assert!(
                     !matches!(
                         op,
@@ -698,8 +877,10 @@ impl CodeGenerator {
                             | BinaryOp::U32Greater
                             | BinaryOp::U32LessEqual
                             | BinaryOp::U32GreaterEqual
+                            | BinaryOp::U32ShiftLeft
+                            | BinaryOp::U32ShiftRight
                     ),
-                    "Illegalized u32 comparison {:?} should not reach codegen (expected legalizer to rewrite)",
+                    "Illegalized u32 operation {:?} should not reach codegen (expected legalizer to rewrite)",
                     op
                 );
                 // Direct Argument Placement Optimization
@@ -725,7 +906,11 @@ impl CodeGenerator {
                     target_offset = self.get_target_offset_for_dest(*dest, terminator, function);
                 }
 
+                let start = builder.instructions().len();
                 builder.binary_op(*op, *dest, *left, *right, target_offset)?;
+                if let Some(location) = function.division_locations.get(dest) {
+                    builder.tag_divisions(start, location);
+                }
             }
 
             InstructionKind::Call {
@@ -738,7 +923,11 @@ impl CodeGenerator {
                 let callee_function = module.get_function(*callee).ok_or_else(|| {
                     CodegenError::MissingTarget(format!("No function found for callee {callee:?}"))
                 })?;
+                let start = builder.instructions().len();
                 builder.lower_call(&callee_function.name, args, signature, dests)?;
+                if let Some(location) = &instruction.location {
+                    builder.tag_calls(start, location);
+                }
             }
             InstructionKind::Cast {
                 dest,
@@ -753,6 +942,10 @@ impl CodeGenerator {
                 todo!("Debug is not implemented yet");
             }
 
+            InstructionKind::DebugPrint { value, ty } => {
+                builder.debug_print(value, ty)?;
+            }
+
             InstructionKind::Phi { .. } => {
                 // Phi nodes are a compile-time construct for SSA form
                 // They should be eliminated before code generation through
@@ -834,6 +1027,10 @@ impl CodeGenerator {
                 builder.make_fixed_array(*dest, elements, element_ty)?;
             }
 
+            InstructionKind::CopyAggregate { dest, source, ty } => {
+                builder.copy_aggregate(*dest, *source, ty)?;
+            }
+
             // New memory ops over places
             InstructionKind::Load { dest, place, ty } => {
                 // Generalized place projection handling: Index + Field + Tuple
@@ -1324,7 +1521,7 @@ impl CodeGenerator {
                     }
                 }
             }
-            InstructionKind::HeapAllocCells { .. } => {
+            InstructionKind::HeapAllocCells { .. } | InstructionKind::LoadEnv { .. } => {
                 // Handled at the basic-block level to enable label and data layout decisions.
             }
         }
@@ -1431,9 +1628,11 @@ impl CodeGenerator {
                 let else_label = format!("{function_name}_{else_target:?}");
 
                 // Because CASM has only JNZ, we need to jump to the then_label if the condition is true.
-                // We can't optimize a fallthrough.
+                // Fallthrough to the `else` block if the `jnz` was not taken.
                 builder.jnz(*condition, &then_label)?;
-                builder.jump(&else_label);
+                if next_block_id != Some(*else_target) {
+                    builder.jump(&else_label);
+                }
             }
 
             Terminator::BranchCmp {
@@ -1531,6 +1730,56 @@ impl CodeGenerator {
                 }
             }
 
+            Terminator::Switch {
+                op,
+                value,
+                cases,
+                default,
+            } => {
+                // CASM has no multi-way jump: the cases are tested in order, all sharing a
+                // temporary slot for the comparison result.
+                let temp_slot_offset = builder.layout_mut().reserve_stack(1);
+
+                for (case, target) in cases {
+                    let target_label = format!("{function_name}_{target:?}");
+                    match op {
+                        BinaryOp::Eq => {
+                            // `value - case` is zero on a match: skip the jump to the case otherwise.
+                            let next_case = builder.emit_new_label_name("next_case");
+                            builder.compute_into_offset(
+                                BinaryOp::Sub,
+                                temp_slot_offset,
+                                *value,
+                                *case,
+                            )?;
+                            builder.jnz_offset(temp_slot_offset, &next_case);
+                            builder.jump(&target_label);
+                            builder.emit_add_label(Label::new(next_case));
+                        }
+                        BinaryOp::U32Eq => {
+                            // U32Eq returns 1 on a match: jump to the case if non-zero.
+                            builder.compute_into_offset(
+                                BinaryOp::U32Eq,
+                                temp_slot_offset,
+                                *value,
+                                *case,
+                            )?;
+                            builder.jnz_offset(temp_slot_offset, &target_label);
+                        }
+                        _ => {
+                            return Err(CodegenError::UnsupportedInstruction(format!(
+                                "Unsupported comparison op in Switch: {op:?}"
+                            )));
+                        }
+                    }
+                }
+
+                // No case matched: fall through to the default block if it is next.
+                if next_block_id != Some(*default) {
+                    builder.jump(&format!("{function_name}_{default:?}"));
+                }
+            }
+
             Terminator::Return { values } => {
                 // Get the return types from the function
                 let return_types: Vec<MirType> = function
@@ -1548,6 +1797,17 @@ impl CodeGenerator {
                 builder.return_values(values, &return_types)?;
             }
 
+            Terminator::Abort { message, location } => {
+                builder.abort(AbortInfo {
+                    message: message.clone(),
+                    location: location.clone(),
+                });
+            }
+
+            Terminator::ColdAbort { abort } => {
+                builder.jump(&Label::for_cold_abort(*abort).name);
+            }
+
             Terminator::Unreachable => {
                 // Unreachable code - could add a debug trap or just ignore
             }
@@ -1580,6 +1840,13 @@ impl CodeGenerator {
             if let Some(logical_addr) = label.address {
                 if let Some(&physical_addr) = self.memory_layout.get(logical_addr) {
                     label_map.insert(label.name.clone(), physical_addr as usize);
+                    if let Some(&kind) = self.symbol_kinds.get(&label.name) {
+                        self.symbols.push(Symbol {
+                            name: label.name.clone(),
+                            kind,
+                            pc: physical_addr,
+                        });
+                    }
                 } else {
                     return Err(CodegenError::UnresolvedLabel(format!(
                         "Label {} has invalid address {}",
@@ -1589,6 +1856,23 @@ impl CodeGenerator {
             }
         }
 
+        // The aborts of a function in the cold region are named after it
+        for (name, logical_pc) in &self.cold_symbols {
+            let &physical_pc = self.memory_layout.get(*logical_pc).ok_or_else(|| {
+                CodegenError::UnresolvedLabel(format!(
+                    "Cold aborts of {name} have invalid PC {logical_pc}"
+                ))
+            })?;
+            self.symbols.push(Symbol {
+                name: name.clone(),
+                kind: SymbolKind::Function,
+                pc: physical_pc,
+            });
+        }
+
+        // Sort the symbol table by PC, a function before its entry block
+        self.symbols.sort_by_key(|symbol| (symbol.pc, symbol.kind));
+
         // Before resolving instruction labels, add rodata and data labels to the map
         // Compute total code length in QM31 words
         let mut code_len_qm31: u32 = 0;
@@ -1645,6 +1929,8 @@ impl CodeGenerator {
                 .ok_or_else(|| CodegenError::InternalError("Invalid data blob index".into()))?;
             label_map.insert(lbl.clone(), addr as usize);
         }
+        // The environment block is written right after the program data
+        label_map.insert(ENVIRONMENT_LABEL.to_string(), total as usize);
 
         // Resolve label references in instructions (typed API)
         for (logical_pc, instruction) in self.instructions.iter_mut().enumerate() {
@@ -1687,6 +1973,11 @@ impl CodeGenerator {
                         .ok_or_else(|| CodegenError::UnresolvedLabel(label_name.clone()))?;
                     *imm = M31::from(target_addr as i32);
                     instruction.label = None;
+                    if label_name == ENVIRONMENT_LABEL {
+                        self.environment_references.push(physical_pc);
+                    } else {
+                        self.relocations.push(physical_pc);
+                    }
                 }
                 _ => {
                     return Err(CodegenError::UnresolvedLabel(format!(
@@ -1700,6 +1991,11 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Frame-size reports of the generated functions, in generation order
+    pub fn frame_reports(&self) -> &[FrameReport] {
+        &self.frame_reports
+    }
+
     /// Generate a debug representation of instructions with resolved labels
     /// This is primarily used for snapshot testing
     pub fn debug_instructions(&self) -> String {
@@ -1860,6 +2156,7 @@ mod tests_asserts {
                 },
                 source_span: None,
                 source_expr_id: None,
+                location: None,
                 comment: None,
             });
 
@@ -1894,6 +2191,7 @@ mod tests_asserts {
                 },
                 source_span: None,
                 source_expr_id: None,
+                location: None,
                 comment: None,
             });
 
@@ -1925,6 +2223,66 @@ mod tests_asserts {
     }
 }
 
+#[cfg(test)]
+mod tests_cold_region {
+    use cairo_m_compiler_mir::{ColdAbort, ColdAbortId, MirModule, MirType, Terminator, Value};
+
+    use super::*;
+
+    /// Creates a function which jumps to `abort` when its parameter is 0
+    fn function_with_cold_abort(name: &str, abort: ColdAbortId) -> MirFunction {
+        let mut function = MirFunction::new(name.to_string());
+        let x = function.new_typed_value_id(MirType::Felt);
+        function.parameters.push(x);
+        let abort_block = function.add_basic_block();
+        let return_block = function.add_basic_block();
+        function.basic_blocks[function.entry_block].set_terminator(Terminator::branch(
+            Value::operand(x),
+            return_block,
+            abort_block,
+        ));
+        function.basic_blocks[abort_block].set_terminator(Terminator::cold_abort(abort));
+        function.basic_blocks[return_block].set_terminator(Terminator::return_void());
+        function
+    }
+
+    #[test]
+    fn cold_aborts_are_emitted_after_the_functions() {
+        let mut module = MirModule::new();
+        for (index, (name, message)) in [("f", "zero"), ("g", "also zero")].into_iter().enumerate()
+        {
+            let function_id =
+                module.add_function(function_with_cold_abort(name, ColdAbortId::new(index)));
+            module.add_cold_abort(ColdAbort {
+                function: function_id,
+                message: message.to_string(),
+                location: None,
+            });
+        }
+
+        let mut codegen = CodeGenerator::new();
+        codegen.generate_module(&module).unwrap();
+        let program = codegen.compile().unwrap();
+
+        // The aborts follow the code of both functions, and are named after them
+        let entry_g = program.entrypoints["g"].pc as u32;
+        let aborts: Vec<_> = program
+            .metadata
+            .abort_messages
+            .iter()
+            .map(|(&pc, info)| {
+                assert!(pc > entry_g);
+                let function = program
+                    .metadata
+                    .symbol_at(pc, SymbolKind::Function)
+                    .unwrap();
+                (function.name.as_str(), info.message.as_str())
+            })
+            .collect();
+        assert_eq!(aborts, [("f", "zero"), ("g", "also zero")]);
+    }
+}
+
 #[cfg(test)]
 mod tests_heap_alloc {
     use cairo_m_compiler_mir::{BasicBlock, MirFunction, MirModule, MirType, Terminator, Value};
@@ -2096,6 +2454,7 @@ mod tests_rodata {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         });
 
@@ -2110,6 +2469,7 @@ mod tests_rodata {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         });
 
@@ -2426,6 +2786,43 @@ mod tests {
         assert!(generator.function_entrypoints.contains_key("main"));
     }
 
+    #[test]
+    fn test_symbol_table() {
+        let identity = |name: &str| {
+            let mut function = MirFunction::new(name.to_string());
+            let value_id = function.new_typed_value_id(MirType::Felt);
+            function.parameters.push(value_id);
+            function.return_values.push(value_id);
+            function.basic_blocks[function.entry_block]
+                .set_terminator(Terminator::return_value(Value::Operand(value_id)));
+            function
+        };
+        let mut module = MirModule::new();
+        module.add_function(identity("main"));
+        module.add_function(identity("helper"));
+
+        let mut generator = CodeGenerator::new();
+        generator.generate_module(&module).unwrap();
+        let helper_pc = generator.function_entrypoints["helper"].pc as u32;
+        let program = generator.compile().unwrap();
+
+        let symbols: Vec<_> = program
+            .metadata
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.pc))
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                ("main", SymbolKind::Function, 0),
+                ("main_0", SymbolKind::Block, 0),
+                ("helper", SymbolKind::Function, helper_pc),
+                ("helper_0", SymbolKind::Block, helper_pc),
+            ]
+        );
+    }
+
     #[test]
     fn test_immediate_compilation() {
         // Create a simple module with a store immediate instruction
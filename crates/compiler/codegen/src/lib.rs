@@ -15,8 +15,10 @@
 #![feature(let_chains)]
 #![allow(clippy::option_if_let_else)]
 
-use cairo_m_common::{Instruction, InstructionError};
-use cairo_m_compiler_mir::BasicBlockId;
+use std::ops::Range;
+
+use cairo_m_common::{AbortInfo, Instruction, InstructionError};
+use cairo_m_compiler_mir::{BasicBlockId, ColdAbortId};
 use thiserror::Error;
 
 pub mod backend;
@@ -26,6 +28,7 @@ pub mod generator;
 pub mod layout;
 pub mod mir_passes;
 pub mod passes;
+pub mod report;
 
 // Test support utilities (only compiled for tests)
 #[cfg(test)]
@@ -37,6 +40,7 @@ pub use builder::CasmBuilder;
 pub use db::{CodegenDb, compile_project as db_compile_project};
 pub use generator::CodeGenerator;
 pub use layout::FunctionLayout;
+pub use report::FrameReport;
 
 /// Represents an instruction being built during code generation.
 ///
@@ -51,6 +55,14 @@ pub struct InstructionBuilder {
     label: Option<String>,
     /// Human-readable comment for debugging
     comment: Option<String>,
+    /// Message reported by the runner if this instruction fails
+    abort: Option<AbortInfo>,
+    /// Source location reported by the runner if this division has a zero divisor
+    division_location: Option<String>,
+    /// Source location of the call site reported in backtraces, if this instruction is a call
+    call_location: Option<String>,
+    /// Byte range in the source of the MIR instruction this instruction was generated from
+    source_span: Option<Range<usize>>,
 }
 
 impl InstructionBuilder {
@@ -90,6 +102,50 @@ impl InstructionBuilder {
         self
     }
 
+    pub(crate) fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Attach the message reported when this instruction fails
+    pub(crate) fn with_abort(mut self, abort: AbortInfo) -> Self {
+        self.abort = Some(abort);
+        self
+    }
+
+    pub(crate) const fn abort(&self) -> Option<&AbortInfo> {
+        self.abort.as_ref()
+    }
+
+    /// Attach the source location reported when this division has a zero divisor
+    pub(crate) fn set_division_location(&mut self, location: String) {
+        self.division_location = Some(location);
+    }
+
+    pub(crate) fn division_location(&self) -> Option<&str> {
+        self.division_location.as_deref()
+    }
+
+    /// Attach the source location of the call site of this call
+    pub(crate) fn set_call_location(&mut self, location: String) {
+        self.call_location = Some(location);
+    }
+
+    pub(crate) fn call_location(&self) -> Option<&str> {
+        self.call_location.as_deref()
+    }
+
+    /// Set the source span, unless the instruction already has one
+    pub(crate) fn with_default_source_span(mut self, span: Option<Range<usize>>) -> Self {
+        if self.source_span.is_none() {
+            self.source_span = span;
+        }
+        self
+    }
+
+    pub(crate) const fn source_span(&self) -> Option<&Range<usize>> {
+        self.source_span.as_ref()
+    }
+
     pub(crate) const fn inner_instr(&self) -> &Instruction {
         &self.inner
     }
@@ -126,8 +182,17 @@ impl Label {
     pub(crate) fn for_function(function_name: &str) -> Self {
         Self::new(function_name.to_string())
     }
+
+    /// Create a label for an abort of the cold region
+    pub(crate) fn for_cold_abort(abort_id: ColdAbortId) -> Self {
+        Self::new(format!("{COLD_REGION}_{abort_id:?}"))
+    }
 }
 
+/// Prefix of the labels of the cold region emitted after the functions, which holds their
+/// outlined aborts
+const COLD_REGION: &str = "<cold>";
+
 /// Errors that can occur during code generation
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum CodegenError {
@@ -152,6 +217,9 @@ pub enum CodegenError {
     /// Instruction error
     #[error("Instruction error: {0}")]
     Instruction(#[from] InstructionError),
+    /// Instruction whose memory accesses the prover cannot constrain
+    #[error("Unprovable instruction: {0}")]
+    UnprovableInstruction(String),
 }
 
 /// Result type for codegen operations
@@ -187,6 +255,10 @@ impl From<Instruction> for InstructionBuilder {
         Self {
             label: None,
             comment: None,
+            abort: None,
+            division_location: None,
+            call_location: None,
+            source_span: None,
             inner: instr,
         }
     }
@@ -0,0 +1,212 @@
+//! Exponentiation fusion: rewrite chains of felt multiplications of a same base into a
+//! single `BinaryOp::Pow`, lowered to the `StorePowFpImm` opcode.
+//!
+//! For example `x * x * x` lowers to `t = x * x; y = t * x` and is rewritten into
+//! `y = x ** 3`, which saves one instruction and the memory accesses of `t`.
+//!
+//! Only intermediate powers used once are fused away, so that no computed value is lost,
+//! and the fused exponent never exceeds `MAX_POW_EXPONENT`. A lone square `x * x` is kept
+//! as a multiplication, which is cheaper to prove. The pass works inside each basic block.
+
+use std::collections::HashMap;
+
+use cairo_m_common::instruction::MAX_POW_EXPONENT;
+use cairo_m_compiler_mir::instruction::InstructionKind;
+use cairo_m_compiler_mir::{BinaryOp, Instruction, MirFunction, MirModule, Value, ValueId};
+
+/// Apply exponentiation fusion to a whole module (in place).
+pub fn fuse_pow_module(module: &mut MirModule) {
+    for func in module.functions_mut() {
+        fuse_pow_function(func);
+    }
+}
+
+/// Apply exponentiation fusion to a single function (in place).
+pub fn fuse_pow_function(function: &mut MirFunction) {
+    let use_counts = function.get_value_use_counts();
+
+    for block in function.basic_blocks.iter_mut() {
+        let old_instrs = std::mem::take(&mut block.instructions);
+        let mut new_instrs: Vec<Option<Instruction>> = Vec::with_capacity(old_instrs.len());
+        // Values of the block known to be a power of a base: value -> (base, exponent, index)
+        let mut powers: HashMap<ValueId, (ValueId, u32, usize)> = HashMap::new();
+
+        // A value used once as a factor can be fused into its user: it is seen as
+        // `base ** exponent`. Any other value is its own base, with exponent 1.
+        let as_power =
+            |powers: &HashMap<ValueId, (ValueId, u32, usize)>, value: ValueId| match powers
+                .get(&value)
+            {
+                Some(&(base, exponent, index)) if use_counts.get(&value) == Some(&1) => {
+                    (base, exponent, Some(index))
+                }
+                _ => (value, 1, None),
+            };
+
+        for instr in old_instrs {
+            let InstructionKind::BinaryOp {
+                op: BinaryOp::Mul,
+                dest,
+                left: Value::Operand(left),
+                right: Value::Operand(right),
+            } = instr.kind
+            else {
+                new_instrs.push(Some(instr));
+                continue;
+            };
+
+            let (left_base, left_exp, left_def) = as_power(&powers, left);
+            let (right_base, right_exp, right_def) = as_power(&powers, right);
+            let exponent = left_exp + right_exp;
+            if left_base != right_base || exponent > MAX_POW_EXPONENT {
+                new_instrs.push(Some(instr));
+                continue;
+            }
+
+            powers.insert(dest, (left_base, exponent, new_instrs.len()));
+            if left_def.is_none() && right_def.is_none() {
+                // Nothing to fuse yet: a square stays a multiplication.
+                new_instrs.push(Some(instr));
+                continue;
+            }
+
+            // The fused factors are only used here: drop their definitions.
+            for index in [left_def, right_def].into_iter().flatten() {
+                new_instrs[index] = None;
+            }
+
+            let mut fused = Instruction::binary_op(
+                BinaryOp::Pow,
+                dest,
+                Value::operand(left_base),
+                Value::integer(exponent),
+            );
+            fused.source_span = instr.source_span;
+            fused.source_expr_id = instr.source_expr_id;
+            fused.comment = instr.comment;
+            new_instrs.push(Some(fused));
+        }
+
+        block.instructions = new_instrs.into_iter().flatten().collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_m_compiler_mir::{MirType, Terminator};
+
+    use super::*;
+
+    fn mul(dest: ValueId, left: ValueId, right: ValueId) -> Instruction {
+        Instruction::binary_op(
+            BinaryOp::Mul,
+            dest,
+            Value::operand(left),
+            Value::operand(right),
+        )
+    }
+
+    /// Builds a single-block function over `n_values` felt values and fuses it.
+    fn fused(
+        n_values: usize,
+        build: impl FnOnce(&[ValueId]) -> (Vec<Instruction>, ValueId),
+    ) -> (Vec<Instruction>, Vec<ValueId>) {
+        let mut f = MirFunction::new("pow".to_string());
+        let values = (0..n_values)
+            .map(|_| f.new_typed_value_id(MirType::felt()))
+            .collect::<Vec<_>>();
+        let (instructions, ret) = build(&values);
+        let entry = f.entry_block;
+        let block = f.get_basic_block_mut(entry).unwrap();
+        block.instructions = instructions;
+        block.terminator = Terminator::return_value(Value::operand(ret));
+        fuse_pow_function(&mut f);
+        (f.basic_blocks[entry].instructions.clone(), values)
+    }
+
+    fn assert_pow(instr: &Instruction, expected_dest: ValueId, base: ValueId, exponent: u32) {
+        match &instr.kind {
+            InstructionKind::BinaryOp {
+                op: BinaryOp::Pow,
+                dest,
+                left,
+                right,
+            } => {
+                assert_eq!(*dest, expected_dest);
+                assert_eq!(left.as_operand(), Some(base));
+                assert_eq!(*right, Value::integer(exponent));
+            }
+            other => panic!("expected a pow, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fuses_chain_of_multiplications() {
+        // t0 = x * x; t1 = t0 * x; t2 = x * t1  ==>  t2 = x ** 4
+        let (insts, v) = fused(4, |v| {
+            let insts = vec![
+                mul(v[1], v[0], v[0]),
+                mul(v[2], v[1], v[0]),
+                mul(v[3], v[0], v[2]),
+            ];
+            (insts, v[3])
+        });
+        assert_eq!(insts.len(), 1);
+        assert_pow(&insts[0], v[3], v[0], 4);
+    }
+
+    #[test]
+    fn keeps_squares_as_multiplications() {
+        // t0 = x * x; t1 = t0 * t0  ==>  t0 is used twice, nothing is fused
+        let (insts, _) = fused(3, |v| {
+            (vec![mul(v[1], v[0], v[0]), mul(v[2], v[1], v[1])], v[2])
+        });
+        assert_eq!(insts.len(), 2);
+        assert!(insts.iter().all(|instr| matches!(
+            instr.kind,
+            InstructionKind::BinaryOp {
+                op: BinaryOp::Mul,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn keeps_intermediate_powers_used_elsewhere() {
+        // t0 = x * x; t1 = t0 * x; t2 = t1 + t0  ==>  t0 is kept for the addition
+        let (insts, v) = fused(4, |v| {
+            let add = Instruction::binary_op(
+                BinaryOp::Add,
+                v[3],
+                Value::operand(v[2]),
+                Value::operand(v[1]),
+            );
+            (
+                vec![mul(v[1], v[0], v[0]), mul(v[2], v[1], v[0]), add],
+                v[3],
+            )
+        });
+        assert_eq!(insts.len(), 3);
+        assert!(insts[..2].iter().all(|instr| matches!(
+            instr.kind,
+            InstructionKind::BinaryOp {
+                op: BinaryOp::Mul,
+                ..
+            }
+        )));
+        assert_eq!(insts[0].destinations(), vec![v[1]]);
+    }
+
+    #[test]
+    fn caps_exponent() {
+        // x ** (MAX_POW_EXPONENT + 1) is fused up to MAX_POW_EXPONENT, then multiplied by x
+        let n_muls = MAX_POW_EXPONENT as usize;
+        let (insts, v) = fused(n_muls + 1, |v| {
+            let mut insts = vec![mul(v[1], v[0], v[0])];
+            insts.extend((2..=n_muls).map(|i| mul(v[i], v[i - 1], v[0])));
+            (insts, v[n_muls])
+        });
+        assert_eq!(insts.len(), 2);
+        assert_pow(&insts[0], v[n_muls - 1], v[0], MAX_POW_EXPONENT);
+    }
+}
@@ -4,6 +4,8 @@
 //! - Normalize u32 comparisons to a canonical set supported by the VM: `U32Eq` and strict `U32Less`.
 //! - Expand unsupported comparisons using `UnaryOp::Not` and/or operand swaps.
 //! - (Optionally) canonicalize commutative u32 ops to put immediates on the right.
+//! - Expand u32 shifts, which have no dedicated opcode, into a multiplication or division
+//!   by `2^(n % 32)`.
 //!
 //! This pass runs inside `codegen` so MIR remains target-agnostic.
 //!
@@ -12,7 +14,9 @@
 //! to reduce the amount of columns in the prover.
 
 use cairo_m_compiler_mir::instruction::InstructionKind;
-use cairo_m_compiler_mir::{BinaryOp, MirFunction, MirModule, MirType, Value};
+use cairo_m_compiler_mir::{
+    BinaryOp, Instruction, Literal, MirFunction, MirModule, MirType, Value,
+};
 use cairo_m_compiler_parser::parser::UnaryOp;
 
 use crate::builder::normalize::is_commutative_u32;
@@ -111,6 +115,17 @@ pub fn legalize_function_for_vm(function: &mut MirFunction) {
                             ));
                         }
 
+                        // x << n  ==>  x * 2^(n % 32);  x >> n  ==>  x / 2^(n % 32)
+                        BinaryOp::U32ShiftLeft | BinaryOp::U32ShiftRight => {
+                            let scale_op = if op == BinaryOp::U32ShiftLeft {
+                                BinaryOp::U32Mul
+                            } else {
+                                BinaryOp::U32Div
+                            };
+                            let factor = pow2_of_shift_amount(function, right, &mut new_instrs);
+                            new_instrs.push(Instruction::binary_op(scale_op, dest, left, factor));
+                        }
+
                         // Felt comparisons and others: keep as-is (codegen will reject unsupported felt cmps)
                         _ => {
                             new_instrs.push(instr);
@@ -127,6 +142,40 @@ pub fn legalize_function_for_vm(function: &mut MirFunction) {
     }
 }
 
+/// Emits the computation of `2^(amount % 32)` and returns its value.
+///
+/// Constant amounts fold to an immediate. Otherwise, for each bit `b_i` of the 5 low bits
+/// of the amount, the factor `1 + b_i * (2^(2^i) - 1)` is either `1` or `2^(2^i)`, and
+/// their product is the power of two.
+fn pow2_of_shift_amount(
+    function: &mut MirFunction,
+    amount: Value,
+    instrs: &mut Vec<Instruction>,
+) -> Value {
+    if let Value::Literal(Literal::Integer(amount)) = amount {
+        return Value::integer(1u32.wrapping_shl(amount));
+    }
+
+    let mut emit = |op: BinaryOp, left: Value, right: Value| -> Value {
+        let dest = function.new_typed_value_id(MirType::u32());
+        instrs.push(Instruction::binary_op(op, dest, left, right));
+        Value::operand(dest)
+    };
+
+    // Bit 0: factor = (n & 1) + 1
+    let bit = emit(BinaryOp::U32BitwiseAnd, amount, Value::integer(1));
+    let mut pow = emit(BinaryOp::U32Add, bit, Value::integer(1));
+    for i in 1..5 {
+        let mask = 1u32 << i;
+        let masked = emit(BinaryOp::U32BitwiseAnd, amount, Value::integer(mask));
+        let bit = emit(BinaryOp::U32Div, masked, Value::integer(mask));
+        let scaled = emit(BinaryOp::U32Mul, bit, Value::integer((1u32 << mask) - 1));
+        let factor = emit(BinaryOp::U32Add, scaled, Value::integer(1));
+        pow = emit(BinaryOp::U32Mul, pow, factor);
+    }
+    pow
+}
+
 const fn canonicalize_commutative_u32(
     op: BinaryOp,
     left: Value,
@@ -143,8 +192,6 @@ const fn canonicalize_commutative_u32(
 
 #[cfg(test)]
 mod tests {
-    use cairo_m_compiler_mir::instruction::Instruction;
-
     use super::*;
 
     fn mk_simple_fn() -> MirFunction {
@@ -266,4 +313,78 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn rewrites_constant_shifts_to_scaling() {
+        let mut f = MirFunction::new("shl".to_string());
+        let bid = f.entry_block;
+        let a = f.new_typed_value_id(MirType::u32());
+        let d0 = f.new_typed_value_id(MirType::u32());
+        let d1 = f.new_typed_value_id(MirType::u32());
+        f.get_basic_block_mut(bid).unwrap().instructions = vec![
+            Instruction::binary_op(
+                BinaryOp::U32ShiftLeft,
+                d0,
+                Value::operand(a),
+                Value::integer(3),
+            ),
+            Instruction::binary_op(
+                BinaryOp::U32ShiftRight,
+                d1,
+                Value::operand(a),
+                Value::integer(33),
+            ),
+        ];
+        legalize_function_for_vm(&mut f);
+        let insts = &f.basic_blocks[bid].instructions;
+        assert_eq!(insts.len(), 2);
+        match &insts[0].kind {
+            InstructionKind::BinaryOp { op, right, .. } => {
+                assert!(matches!(op, BinaryOp::U32Mul));
+                assert_eq!(*right, Value::integer(8));
+            }
+            _ => panic!(),
+        }
+        match &insts[1].kind {
+            InstructionKind::BinaryOp { op, right, .. } => {
+                assert!(matches!(op, BinaryOp::U32Div));
+                assert_eq!(*right, Value::integer(2));
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn expands_variable_shift() {
+        let mut f = MirFunction::new("shr".to_string());
+        let bid = f.entry_block;
+        let a = f.new_typed_value_id(MirType::u32());
+        let n = f.new_typed_value_id(MirType::u32());
+        let d = f.new_typed_value_id(MirType::u32());
+        f.get_basic_block_mut(bid).unwrap().instructions = vec![Instruction::binary_op(
+            BinaryOp::U32ShiftRight,
+            d,
+            Value::operand(a),
+            Value::operand(n),
+        )];
+        legalize_function_for_vm(&mut f);
+        let insts = &f.basic_blocks[bid].instructions;
+        // 2 instructions for bit 0, 5 for each of the 4 other bits, then the division.
+        assert_eq!(insts.len(), 23);
+        assert!(insts.iter().all(|instr| !matches!(
+            instr.kind,
+            InstructionKind::BinaryOp {
+                op: BinaryOp::U32ShiftLeft | BinaryOp::U32ShiftRight,
+                ..
+            }
+        )));
+        match &insts[22].kind {
+            InstructionKind::BinaryOp { op, dest, left, .. } => {
+                assert!(matches!(op, BinaryOp::U32Div));
+                assert_eq!(*dest, d);
+                assert_eq!(left.as_operand(), Some(a));
+            }
+            _ => panic!(),
+        }
+    }
 }
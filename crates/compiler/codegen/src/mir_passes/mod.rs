@@ -3,4 +3,5 @@
 //! Passes in this module are intended to keep the generic MIR clean and
 //! move backend quirks or instruction-set constraints into codegen.
 
+pub mod fuse_pow;
 pub mod legalize;
@@ -17,6 +17,10 @@
 //! The `DeduplicateOperandsPass` rewrites offending instructions by inserting
 //! temporary copies for one of the sources when both sources alias (or overlap
 //! for u32). Destination aliasing is not rewritten since read-then-write is OK.
+//! Most of these fixups are avoided upstream: the builder emits operations on the
+//! same operand with single-read equivalents (`x + x` as `x * 2`, `x - x` as `0`,
+//! ...), and the pass reuses a previous copy of an operand while neither the
+//! operand nor the copy has been overwritten since.
 //!
 //! We also include tiny peephole canonicalizations (e.g., `* 1` -> `+ 0`,
 //! `* 0` -> `StoreImm(0)`/`U32StoreImm(0)`) that reduce unnecessary reads while
@@ -24,14 +28,21 @@
 //! opcode.
 
 use cairo_m_common::Instruction as CasmInstr;
+use cairo_m_common::instruction::{DataType, OperandType};
 use stwo_prover::core::fields::m31::M31;
 
+use crate::report::PassSpills;
 use crate::{CasmBuilder, CodegenError, CodegenResult, InstructionBuilder, Label};
 
 /// A transformation pass that runs over a function’s instruction list.
 pub trait CodegenPass {
     fn name(&self) -> &str;
     fn run(&self, builder: &mut CasmBuilder) -> CodegenResult<()>;
+
+    /// Number of fixups of this pass avoided in the function, by the builder or the pass
+    fn avoided_fixups(&self, _builder: &CasmBuilder) -> usize {
+        0
+    }
 }
 
 /// Pass 1: Ensure no single instruction reuses the same memory cell twice.
@@ -48,10 +59,29 @@ impl CodegenPass for DeduplicateOperandsPass {
         let mut new_instrs: Vec<InstructionBuilder> = Vec::with_capacity(old_instrs.len());
         let mut index_mapping: Vec<Option<std::ops::Range<usize>>> =
             Vec::with_capacity(old_instrs.len());
-
-        for instr in old_instrs.iter() {
+        let labeled: std::collections::HashSet<usize> = builder
+            .labels()
+            .iter()
+            .filter_map(|label| label.address)
+            .collect();
+        let mut copies = OperandCopies::default();
+
+        for (idx, instr) in old_instrs.iter().enumerate() {
+            // Copies can't be reused across a jump target
+            if labeled.contains(&idx) {
+                copies.live.clear();
+            }
             let start = new_instrs.len();
-            let repl = rewrite_instruction(builder, instr)?;
+            let repl = rewrite_instruction(builder, instr, &mut copies)?
+                .into_iter()
+                .map(|new| new.with_default_source_span(instr.source_span().cloned()))
+                .collect::<Vec<_>>();
+            for new in &repl {
+                copies.forget_clobbered(new.inner_instr());
+            }
+            if let Some(op) = repl.last() {
+                copies.commit(op.inner_instr());
+            }
             if repl.is_empty() {
                 index_mapping.push(None);
             } else {
@@ -80,8 +110,13 @@ impl CodegenPass for DeduplicateOperandsPass {
         }
 
         *builder.instructions_mut() = new_instrs;
+        builder.avoided_operand_copies += copies.reused;
         Ok(())
     }
+
+    fn avoided_fixups(&self, builder: &CasmBuilder) -> usize {
+        builder.avoided_operand_copies
+    }
 }
 
 /// Pass 2: Canonicalize a few immediate arithmetic patterns.
@@ -181,19 +216,124 @@ impl CodegenPass for CanonicalizeImmediateOpsPass {
 }
 
 /// Run the default pass pipeline on a single function’s CASM.
-pub fn run_all(builder: &mut CasmBuilder) -> CodegenResult<()> {
+///
+/// Returns the spills introduced by each pass, measured as the instructions and stack slots
+/// it added.
+pub fn run_all(builder: &mut CasmBuilder) -> CodegenResult<Vec<PassSpills>> {
     let passes: [&dyn CodegenPass; 2] = [&DeduplicateOperandsPass, &CanonicalizeImmediateOpsPass];
+    let mut pass_spills = Vec::with_capacity(passes.len());
     for p in passes.into_iter() {
+        let instructions_before = builder.instructions().len();
+        let frame_size_before = builder.layout().frame_size;
         p.run(builder)?;
+        pass_spills.push(PassSpills {
+            pass: p.name().to_string(),
+            spills: builder
+                .instructions()
+                .len()
+                .saturating_sub(instructions_before),
+            slots: builder.layout().frame_size - frame_size_before,
+            avoided: p.avoided_fixups(builder),
+        });
     }
-    Ok(())
+    Ok(pass_spills)
 }
 
 // ===== Helpers for DeduplicateOperandsPass =====
 
+/// A copy of an operand made by the pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OperandCopy {
+    /// Offset of the copied operand
+    src: u32,
+    /// Offset of the copy
+    copy: u32,
+    /// Size of the operand in slots
+    size: u32,
+}
+
+/// Copies of operands made by the pass, reusable while neither the operand nor the copy is
+/// overwritten
+#[derive(Debug, Default)]
+struct OperandCopies {
+    live: Vec<OperandCopy>,
+    /// Copy made for the instruction being rewritten, live once that instruction is emitted
+    pending: Option<OperandCopy>,
+    /// Number of copies reused instead of made again
+    reused: usize,
+}
+
+impl OperandCopies {
+    /// Returns the offset of a live copy of the operand at `src`, counting the reuse
+    fn reuse(&mut self, src: u32, size: u32) -> Option<u32> {
+        let copy = self
+            .live
+            .iter()
+            .find(|copy| copy.src == src && copy.size == size)?
+            .copy;
+        self.reused += 1;
+        Some(copy)
+    }
+
+    /// Forgets the copies invalidated by an emitted instruction
+    fn forget_clobbered(&mut self, instr: &CasmInstr) {
+        match written_cells(instr) {
+            Some(cells) => self
+                .live
+                .retain(|copy| !cells.iter().any(|&cell| copy.overlaps(cell))),
+            None => self.live.clear(),
+        }
+    }
+
+    /// Makes the pending copy live, unless the instruction it was made for overwrote the
+    /// operand
+    fn commit(&mut self, op: &CasmInstr) {
+        let Some(copy) = self.pending.take() else {
+            return;
+        };
+        let clobbered =
+            written_cells(op).is_none_or(|cells| cells.iter().any(|&c| copy.overlaps(c)));
+        if !clobbered {
+            self.live.push(copy);
+        }
+    }
+}
+
+impl OperandCopy {
+    /// Returns true if `cell` is the operand or its copy
+    const fn overlaps(&self, cell: u32) -> bool {
+        (self.src <= cell && cell < self.src + self.size)
+            || (self.copy <= cell && cell < self.copy + self.size)
+    }
+}
+
+/// Cells written by an instruction, or `None` if it may write other cells than its
+/// destination operands, through pointers, implicit operands or calls
+fn written_cells(instr: &CasmInstr) -> Option<Vec<u32>> {
+    let info = instr.describe();
+    if !info.implicit_operands.is_empty() {
+        return None;
+    }
+    let values = instr.to_smallvec();
+    let mut cells = Vec::new();
+    // The first value is the opcode
+    for (operand, value) in info.operands.iter().zip(values.iter().skip(1)) {
+        if !operand.name.starts_with("dst") {
+            continue;
+        }
+        match operand.kind {
+            OperandType::Memory(DataType::Felt) => cells.push(value.0),
+            OperandType::Memory(DataType::U32) => cells.extend([value.0, value.0 + 1]),
+            OperandType::Immediate => {}
+        }
+    }
+    Some(cells)
+}
+
 fn rewrite_instruction(
     builder: &mut CasmBuilder,
     instr: &InstructionBuilder,
+    copies: &mut OperandCopies,
 ) -> CodegenResult<Vec<InstructionBuilder>> {
     match instr.inner_instr() {
         // felt fp+fp
@@ -216,7 +356,7 @@ fn rewrite_instruction(
             src0_off,
             src1_off,
             dst_off,
-        } => rewrite_felt_fp_fp(builder, instr, *src0_off, *src1_off, *dst_off),
+        } => rewrite_felt_fp_fp(builder, copies, instr, *src0_off, *src1_off, *dst_off),
 
         // u32 fp+fp arithmetic
         CasmInstr::U32StoreAddFpFp {
@@ -233,7 +373,9 @@ fn rewrite_instruction(
             src0_off,
             src1_off,
             dst_off,
-        } => rewrite_u32_fp_fp(builder, instr, *src0_off, *src1_off, *dst_off, None, false),
+        } => rewrite_u32_fp_fp(
+            builder, copies, instr, *src0_off, *src1_off, *dst_off, None, false,
+        ),
         CasmInstr::U32StoreDivRemFpFp {
             src0_off,
             src1_off,
@@ -241,6 +383,7 @@ fn rewrite_instruction(
             dst_rem_off,
         } => rewrite_u32_fp_fp(
             builder,
+            copies,
             instr,
             *src0_off,
             *src1_off,
@@ -259,7 +402,9 @@ fn rewrite_instruction(
             src0_off,
             src1_off,
             dst_off,
-        } => rewrite_u32_fp_fp(builder, instr, *src0_off, *src1_off, *dst_off, None, true),
+        } => rewrite_u32_fp_fp(
+            builder, copies, instr, *src0_off, *src1_off, *dst_off, None, true,
+        ),
 
         _ => Ok(vec![instr.clone()]),
     }
@@ -356,6 +501,7 @@ const fn u32_fp_fp_op_name(orig: &CasmInstr) -> Option<&'static str> {
 
 fn rewrite_felt_fp_fp(
     builder: &mut CasmBuilder,
+    copies: &mut OperandCopies,
     orig: &InstructionBuilder,
     src0: M31,
     src1: M31,
@@ -364,14 +510,28 @@ fn rewrite_felt_fp_fp(
     let o0 = src0.0;
     let o1 = src1.0;
     if o0 == o1 {
-        // Two reads from the same cell: copy one source to a temp.
-        let t0 = builder.layout_mut().reserve_stack(1);
-        let copy = InstructionBuilder::from(CasmInstr::StoreAddFpImm {
-            src_off: src0,
-            imm: M31::from(0),
-            dst_off: M31::from(t0),
-        })
-        .with_comment(format!("[fp + {t0}] = [fp + {o0}] + 0"));
+        // Two reads from the same cell: read one source from a copy, made unless a previous
+        // one is still valid.
+        let mut out = Vec::with_capacity(2);
+        let t0 = if let Some(t0) = copies.reuse(o0, 1) {
+            t0
+        } else {
+            let t0 = builder.layout_mut().reserve_stack(1);
+            out.push(
+                InstructionBuilder::from(CasmInstr::StoreAddFpImm {
+                    src_off: src0,
+                    imm: M31::from(0),
+                    dst_off: M31::from(t0),
+                })
+                .with_comment(format!("[fp + {t0}] = [fp + {o0}] + 0")),
+            );
+            copies.pending = Some(OperandCopy {
+                src: o0,
+                copy: t0 as u32,
+                size: 1,
+            });
+            t0 as u32
+        };
         let op = InstructionBuilder::from(rebuild_felt_fp_fp(
             orig.inner_instr(),
             M31::from(t0),
@@ -382,7 +542,8 @@ fn rewrite_felt_fp_fp(
             "[fp + {}] = [fp + {}] op [fp + {}]",
             dst.0, t0, src1.0
         ));
-        Ok(vec![copy, op])
+        out.push(op);
+        Ok(out)
     } else {
         Ok(vec![orig.clone()])
     }
@@ -399,6 +560,7 @@ const fn u32_overlap(a: u32, b: u32) -> bool {
 
 fn rewrite_u32_fp_fp(
     builder: &mut CasmBuilder,
+    copies: &mut OperandCopies,
     orig: &InstructionBuilder,
     src0: M31,
     src1: M31,
@@ -409,23 +571,38 @@ fn rewrite_u32_fp_fp(
     let o0 = src0.0;
     let o1 = src1.0;
     if u32_overlap(o0, o1) {
-        let t0 = builder.layout_mut().reserve_stack(2);
-        let copy = InstructionBuilder::from(CasmInstr::U32StoreAddFpImm {
-            src_off: src0,
-            imm_lo: M31::from(0),
-            imm_hi: M31::from(0),
-            dst_off: M31::from(t0),
-        })
-        .with_comment(format!(
-            "u32([fp + {t0}], [fp + {}]) = u32([fp + {o0}], [fp + {}]) + u32(0, 0)",
-            t0 + 1,
-            o0 + 1
-        ));
-        let op_instr = rebuild_u32_fp_fp(orig.inner_instr(), M31::from(t0), src1, dst, dst_2)?;
+        // Read either source from a previous copy if one is still valid, else copy the first
+        let mut out = Vec::with_capacity(2);
+        let (s0, s1) = if let Some(t0) = copies.reuse(o0, 2) {
+            (t0, o1)
+        } else if let Some(t1) = copies.reuse(o1, 2) {
+            (o0, t1)
+        } else {
+            let t0 = builder.layout_mut().reserve_stack(2);
+            out.push(
+                InstructionBuilder::from(CasmInstr::U32StoreAddFpImm {
+                    src_off: src0,
+                    imm_lo: M31::from(0),
+                    imm_hi: M31::from(0),
+                    dst_off: M31::from(t0),
+                })
+                .with_comment(format!(
+                    "u32([fp + {t0}], [fp + {}]) = u32([fp + {o0}], [fp + {}]) + u32(0, 0)",
+                    t0 + 1,
+                    o0 + 1
+                )),
+            );
+            copies.pending = Some(OperandCopy {
+                src: o0,
+                copy: t0 as u32,
+                size: 2,
+            });
+            (t0 as u32, o1)
+        };
+        let op_instr =
+            rebuild_u32_fp_fp(orig.inner_instr(), M31::from(s0), M31::from(s1), dst, dst_2)?;
         let op_name = u32_fp_fp_op_name(orig.inner_instr()).unwrap_or("op");
         let op = InstructionBuilder::from(op_instr).with_comment({
-            let s0 = t0;
-            let s1 = src1.0;
             let d = dst.0;
             // For comparisons (felt result) vs arithmetic (u32 result)
             let is_cmp = matches!(orig.inner_instr(), CasmInstr::U32StoreEqFpFp { .. } | CasmInstr::U32StoreLtFpFp { .. });
@@ -444,7 +621,8 @@ fn rewrite_u32_fp_fp(
                 )
             }
         });
-        Ok(vec![copy, op])
+        out.push(op);
+        Ok(out)
     } else {
         Ok(vec![orig.clone()])
     }
@@ -637,6 +815,48 @@ mod tests {
         assert_eq!(out[0].inner_instr(), instr.inner_instr());
     }
 
+    fn felt_div(src: u32, dst: u32) -> InstructionBuilder {
+        InstructionBuilder::from(CasmInstr::StoreDivFpFp {
+            src0_off: M31::from(src),
+            src1_off: M31::from(src),
+            dst_off: M31::from(dst),
+        })
+    }
+
+    #[test]
+    fn felt_fp_fp_reuses_previous_copy() {
+        let (b, out) = run_dedup(vec![felt_div(5, 6), felt_div(5, 7)]);
+        // One copy shared by both divisions
+        assert_eq!(out.len(), 3);
+        let copy = match out[0].inner_instr() {
+            CasmInstr::StoreAddFpImm { dst_off, .. } => *dst_off,
+            other => panic!("expected copy, got {other:?}"),
+        };
+        for op in &out[1..] {
+            match op.inner_instr() {
+                CasmInstr::StoreDivFpFp { src0_off, .. } => assert_eq!(*src0_off, copy),
+                other => panic!("expected division, got {other:?}"),
+            }
+        }
+        assert_eq!(b.avoided_operand_copies, 1);
+    }
+
+    #[test]
+    fn felt_fp_fp_copy_not_reused_after_write() {
+        // The first division overwrites its operand
+        let (_b, out) = run_dedup(vec![felt_div(5, 5), felt_div(5, 7)]);
+        assert_eq!(out.len(), 4);
+
+        // A jump target may be reached with other values
+        let mut b = CasmBuilder::new(FunctionLayout::new_for_test(), 0);
+        b.emit_push(felt_div(5, 6));
+        b.emit_add_label(Label::new("L_loop".to_string()));
+        b.emit_push(felt_div(5, 7));
+        DeduplicateOperandsPass.run(&mut b).unwrap();
+        assert_eq!(b.instructions().len(), 4);
+        assert_eq!(b.avoided_operand_copies, 0);
+    }
+
     #[test]
     fn labels_remapped_on_expansion() {
         // One harmless instr, then label, then an expanding instr
@@ -0,0 +1,180 @@
+//! Frame-size reports
+//!
+//! After code generation, each function gets a report of its frame size, of the stack slots
+//! spilled by the post-builder passes (see [`crate::passes`]), and of the MIR values taking
+//! the most slots. Reports are logged by [`crate::compile_module`] at the info level, which
+//! the compiler CLI enables with `--verbose`, so that users can restructure hot functions.
+
+use std::fmt;
+
+use cairo_m_compiler_mir::ValueId;
+
+use crate::layout::{FunctionLayout, ValueLayout};
+
+/// Number of MIR values listed as the largest contributors to a frame
+const LARGEST_VALUES: usize = 3;
+
+/// Spills introduced by a post-builder pass in a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassSpills {
+    /// Name of the pass
+    pub pass: String,
+    /// Number of instructions inserted to spill an operand to a temporary
+    pub spills: usize,
+    /// Number of stack slots reserved for the temporaries
+    pub slots: usize,
+    /// Number of spills the pass would have needed, avoided by emitting or reusing operands
+    /// that don't need one
+    pub avoided: usize,
+}
+
+/// Frame usage of a generated function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameReport {
+    /// Name of the function
+    pub function: String,
+    /// Frame size in slots, including temporaries and call areas
+    pub frame_size: usize,
+    /// Spills introduced or avoided by each pass that introduced or avoided any
+    pub pass_spills: Vec<PassSpills>,
+    /// Values with the largest stack allocations, with their size in slots
+    pub largest_values: Vec<(ValueId, usize)>,
+}
+
+impl FrameReport {
+    /// Builds the report of a function from its final layout
+    ///
+    /// # Arguments
+    /// * `function` - Name of the function
+    /// * `layout` - Layout of the function after the post-builder passes
+    /// * `pass_spills` - Spills introduced by each post-builder pass
+    pub fn new(function: &str, layout: &FunctionLayout, pass_spills: Vec<PassSpills>) -> Self {
+        // Parameters live in the caller's frame, at negative offsets
+        let mut largest_values: Vec<(ValueId, usize)> = layout
+            .value_layouts
+            .iter()
+            .filter_map(|(&value_id, value_layout)| match value_layout {
+                ValueLayout::Slot { offset } if *offset >= 0 => Some((value_id, 1)),
+                ValueLayout::MultiSlot { offset, size } if *offset >= 0 => Some((value_id, *size)),
+                _ => None,
+            })
+            .collect();
+        largest_values.sort_by(|(a_id, a_size), (b_id, b_size)| {
+            b_size.cmp(a_size).then(a_id.index().cmp(&b_id.index()))
+        });
+        largest_values.truncate(LARGEST_VALUES);
+
+        Self {
+            function: function.to_string(),
+            frame_size: layout.frame_size,
+            pass_spills: pass_spills
+                .into_iter()
+                .filter(|spills| spills.spills > 0 || spills.avoided > 0)
+                .collect(),
+            largest_values,
+        }
+    }
+}
+
+/// Formats a number of slots, e.g. `1 slot` or `3 slots`
+fn slots(count: usize) -> String {
+    if count == 1 {
+        "1 slot".to_string()
+    } else {
+        format!("{count} slots")
+    }
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "frame report for `{}`: {}",
+            self.function,
+            slots(self.frame_size)
+        )?;
+
+        let spills = if self.pass_spills.is_empty() {
+            "none".to_string()
+        } else {
+            self.pass_spills
+                .iter()
+                .map(|spills| {
+                    let avoided = if spills.avoided > 0 {
+                        format!(", {} avoided", spills.avoided)
+                    } else {
+                        String::new()
+                    };
+                    format!(
+                        "{} {} ({}{avoided})",
+                        spills.spills,
+                        spills.pass,
+                        slots(spills.slots)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f, "  spills: {spills}")?;
+
+        let values = if self.largest_values.is_empty() {
+            "none".to_string()
+        } else {
+            self.largest_values
+                .iter()
+                .map(|(value_id, size)| format!("%{} ({})", value_id.index(), slots(*size)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(f, "  largest values: {values}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_report() {
+        let mut layout = FunctionLayout::new_for_test();
+        for (id, size) in [(0, 1), (1, 2), (2, 1), (3, 2)] {
+            layout.allocate_value(ValueId::from_raw(id), size).unwrap();
+        }
+        layout.reserve_stack(1);
+
+        let report = FrameReport::new(
+            "main",
+            &layout,
+            vec![
+                PassSpills {
+                    pass: "deduplicate-operands".to_string(),
+                    spills: 1,
+                    slots: 1,
+                    avoided: 2,
+                },
+                PassSpills {
+                    pass: "canonicalize-imm-ops".to_string(),
+                    spills: 0,
+                    slots: 0,
+                    avoided: 0,
+                },
+            ],
+        );
+
+        assert_eq!(report.frame_size, 7);
+        assert_eq!(
+            report.largest_values,
+            vec![
+                (ValueId::from_raw(1), 2),
+                (ValueId::from_raw(3), 2),
+                (ValueId::from_raw(0), 1)
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "frame report for `main`: 7 slots\n  \
+             spills: 1 deduplicate-operands (1 slot, 2 avoided)\n  \
+             largest values: %1 (2 slots), %3 (2 slots), %0 (1 slot)"
+        );
+    }
+}
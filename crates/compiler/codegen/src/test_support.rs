@@ -122,6 +122,18 @@ pub fn exec(mem: &mut Mem, instrs: &[InstructionBuilder]) -> Result<(), Executio
                 let val = a <= M31::from(imm.0);
                 mem.set(dst_off.0 as i32, M31::from(val as u32));
             }
+            Instruction::StorePowFpImm {
+                src_off,
+                imm,
+                dst_off,
+            } => {
+                if !(2..=MAX_POW_EXPONENT).contains(&imm.0) {
+                    return Err(ExecutionError::InvalidOperands);
+                }
+                let a = mem.get(src_off.0 as i32);
+                let val = (1..imm.0).fold(a, |acc, _| acc * a);
+                mem.set(dst_off.0 as i32, val);
+            }
             Instruction::AssertEqFpImm { src_off, imm } => {
                 let a = mem.get(src_off.0 as i32);
                 let val = a == M31::from(imm.0);
@@ -0,0 +1,108 @@
+//! MIR and CASM snapshot tests of the test_data fixtures at each optimization level.
+//! The fixtures are also executed at each level with identical inputs, so that optimizer
+//! regressions show up either as a failed equivalence check or as a reviewable snapshot diff.
+
+mod common;
+
+use cairo_m_common::program::AbiType;
+use cairo_m_common::{InputValue, Program};
+use cairo_m_compiler_codegen::{CodeGenerator, compile_module};
+use cairo_m_compiler_mir::{
+    OptimizationLevel, PipelineConfig, PrettyPrint, generate_mir_with_config,
+};
+use cairo_m_compiler_semantic::db::project_validate_semantics;
+use cairo_m_runner::{RunnerOptions, run_cairo_program};
+use cairo_m_test_utils::discover_all_fixtures;
+use cairo_m_test_utils::opt_levels::{LevelArtifacts, OptLevelRunner};
+use common::{TestDatabase, create_test_crate};
+
+/// Executes every entrypoint of a program whose parameters are all scalars
+///
+/// Each scalar argument is set to 3, which keeps the recursive fixtures short.
+fn execute_entrypoints(program: &Program) -> Vec<(String, String)> {
+    let mut entrypoints = program.entrypoints.iter().collect::<Vec<_>>();
+    entrypoints.sort_by_key(|(name, _)| *name);
+
+    entrypoints
+        .into_iter()
+        .filter_map(|(name, info)| {
+            let args = info
+                .params
+                .iter()
+                .map(|param| match param.ty {
+                    AbiType::Felt | AbiType::U32 => Some(InputValue::Number(3)),
+                    AbiType::Bool => Some(InputValue::Bool(true)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let result = match run_cairo_program(program, name, &args, RunnerOptions::default()) {
+                Ok(output) => format!("{:?}", output.return_values),
+                Err(_) => "execution error".to_string(),
+            };
+            Some((name.clone(), result))
+        })
+        .collect()
+}
+
+#[test]
+fn test_opt_level_snapshots() {
+    use insta::{assert_snapshot, with_settings};
+
+    let db = TestDatabase::default();
+
+    let runner = OptLevelRunner::new(
+        vec![
+            ("none", OptimizationLevel::None),
+            ("standard", OptimizationLevel::Standard),
+        ],
+        |source, name, optimization_level| {
+            let crate_id = create_test_crate(&db, source, name, "fixtures");
+
+            // validate semantics
+            let diagnostics = project_validate_semantics(&db, crate_id);
+            if diagnostics.has_errors() {
+                let formatted_diags = diagnostics.display_without_color(source);
+                return Err(format!(
+                    "Semantic validation failed with diagnostics:\n{formatted_diags}",
+                ));
+            }
+
+            let pipeline = PipelineConfig {
+                optimization_level,
+                debug: false,
+                verbose_passes: false,
+                passes: None,
+                entrypoints: None,
+            };
+            let mir_module = generate_mir_with_config(&db, crate_id, pipeline)
+                .map_err(|diagnostics| format!("MIR generation failed: {:#?}", diagnostics))?;
+
+            let mut generator = CodeGenerator::new();
+            generator
+                .generate_module(&mir_module)
+                .map_err(|e| format!("Code generation failed: {:#?}", e))?;
+            let program = compile_module(&mir_module)
+                .map_err(|e| format!("Code generation failed: {:#?}", e))?;
+
+            Ok(LevelArtifacts {
+                mir: mir_module.pretty_print(0),
+                casm: generator.debug_instructions(),
+                executions: execute_entrypoints(&program),
+            })
+        },
+    );
+
+    for (fixture, _) in discover_all_fixtures() {
+        for snapshot in runner.run_fixture(&fixture) {
+            with_settings!({
+                description => format!("MIR and CASM snapshot for fixture: {}", snapshot.name).as_str(),
+                omit_expression => true,
+                snapshot_suffix => snapshot.suffix,
+                prepend_module_to_snapshot => false,
+            }, {
+                assert_snapshot!(snapshot.content);
+            });
+        }
+    }
+}
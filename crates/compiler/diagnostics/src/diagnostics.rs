@@ -22,6 +22,10 @@ pub struct Diagnostic {
 
     /// Optional related spans for additional context
     pub related_spans: Vec<(SimpleSpan<usize>, String)>,
+
+    /// Path of the module the diagnostic belongs to, prefixed with its crate name (e.g.
+    /// `math::lib`), set when several crates are compiled together
+    pub module_path: Option<String>,
 }
 
 impl fmt::Display for Diagnostic {
@@ -82,7 +86,7 @@ impl fmt::Display for DiagnosticSeverity {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DiagnosticCode {
     // Parse-related errors (0-999)
     LexicalError,
@@ -122,6 +126,20 @@ pub enum DiagnosticCode {
     TypeInferenceError,
     /// Passing or embedding a const array by pointer (disallowed); user must copy first
     ConstArrayByPointer,
+    /// Discarding the non-unit result of a function call
+    UnusedResult,
+    /// Unknown attribute on an item
+    UnknownAttribute,
+    /// Parameter default value that is not a literal of the parameter type, or that is
+    /// followed by a parameter without default
+    InvalidParameterDefault,
+    /// Constant whose value depends on itself, directly or through other constants
+    CyclicConstDefinition,
+    /// Array size that is not a constant with a non-negative integer value
+    InvalidArraySize,
+    /// `impl` block of a type that is not a struct of its module, or method without a `self`
+    /// receiver
+    InvalidImpl,
     // TODO: Add more type-related diagnostic codes:
     // - InvalidTypeAnnotation
     // - TypeArgumentMismatch
@@ -134,8 +152,12 @@ pub enum DiagnosticCode {
     MissingReturn,
     BreakOutsideLoop,
     ContinueOutsideLoop,
-    // TODO: Add more control flow diagnostic codes:
-    // - DeadCode
+    /// Branch of an `if` whose condition is a compile-time constant
+    DeadBranch,
+    /// Recursive call made again with the same arguments
+    UnboundedRecursion,
+    /// Loop whose body neither changes any state nor exits
+    NonTerminatingLoop,
 
     // Internal errors (9000-9999)
     InternalError,
@@ -148,6 +170,115 @@ pub enum DiagnosticCode {
     // - Security warnings (7000-7999)
 }
 
+impl DiagnosticCode {
+    /// Every diagnostic code
+    pub const ALL: &'static [Self] = &[
+        Self::LexicalError,
+        Self::SyntaxError,
+        Self::UnexpectedToken,
+        Self::UnexpectedEndOfFile,
+        Self::InvalidCharacter,
+        Self::UndeclaredVariable,
+        Self::UnusedVariable,
+        Self::DuplicateDefinition,
+        Self::UseBeforeDefinition,
+        Self::UnresolvedImport,
+        Self::DuplicateParameter,
+        Self::DuplicateStructField,
+        Self::DuplicatePatternIdentifier,
+        Self::UndeclaredType,
+        Self::UnresolvedModule,
+        Self::TypeMismatch,
+        Self::InvalidFieldAccess,
+        Self::InvalidIndexAccess,
+        Self::InvalidIndexType,
+        Self::InvalidStructLiteral,
+        Self::InvalidFunctionCall,
+        Self::InvalidAssignment,
+        Self::InvalidReturnType,
+        Self::InvalidTypeDefinition,
+        Self::InvalidAssignmentTarget,
+        Self::MissingReturnValue,
+        Self::TupleIndexOutOfBounds,
+        Self::InvalidTupleIndexAccess,
+        Self::AssignmentToConst,
+        Self::IndexOutOfBounds,
+        Self::TypeInferenceError,
+        Self::ConstArrayByPointer,
+        Self::UnusedResult,
+        Self::UnknownAttribute,
+        Self::InvalidParameterDefault,
+        Self::CyclicConstDefinition,
+        Self::InvalidArraySize,
+        Self::InvalidImpl,
+        Self::UnreachableCode,
+        Self::MissingReturn,
+        Self::BreakOutsideLoop,
+        Self::ContinueOutsideLoop,
+        Self::DeadBranch,
+        Self::UnboundedRecursion,
+        Self::NonTerminatingLoop,
+        Self::InternalError,
+    ];
+
+    /// Name of the code in snake case, as used in the `[lints]` table of a manifest
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::LexicalError => "lexical_error",
+            Self::SyntaxError => "syntax_error",
+            Self::UnexpectedToken => "unexpected_token",
+            Self::UnexpectedEndOfFile => "unexpected_end_of_file",
+            Self::InvalidCharacter => "invalid_character",
+            Self::UndeclaredVariable => "undeclared_variable",
+            Self::UnusedVariable => "unused_variable",
+            Self::DuplicateDefinition => "duplicate_definition",
+            Self::UseBeforeDefinition => "use_before_definition",
+            Self::UnresolvedImport => "unresolved_import",
+            Self::DuplicateParameter => "duplicate_parameter",
+            Self::DuplicateStructField => "duplicate_struct_field",
+            Self::DuplicatePatternIdentifier => "duplicate_pattern_identifier",
+            Self::UndeclaredType => "undeclared_type",
+            Self::UnresolvedModule => "unresolved_module",
+            Self::TypeMismatch => "type_mismatch",
+            Self::InvalidFieldAccess => "invalid_field_access",
+            Self::InvalidIndexAccess => "invalid_index_access",
+            Self::InvalidIndexType => "invalid_index_type",
+            Self::InvalidStructLiteral => "invalid_struct_literal",
+            Self::InvalidFunctionCall => "invalid_function_call",
+            Self::InvalidAssignment => "invalid_assignment",
+            Self::InvalidReturnType => "invalid_return_type",
+            Self::InvalidTypeDefinition => "invalid_type_definition",
+            Self::InvalidAssignmentTarget => "invalid_assignment_target",
+            Self::MissingReturnValue => "missing_return_value",
+            Self::TupleIndexOutOfBounds => "tuple_index_out_of_bounds",
+            Self::InvalidTupleIndexAccess => "invalid_tuple_index_access",
+            Self::AssignmentToConst => "assignment_to_const",
+            Self::IndexOutOfBounds => "index_out_of_bounds",
+            Self::TypeInferenceError => "type_inference_error",
+            Self::ConstArrayByPointer => "const_array_by_pointer",
+            Self::UnusedResult => "unused_result",
+            Self::UnknownAttribute => "unknown_attribute",
+            Self::InvalidParameterDefault => "invalid_parameter_default",
+            Self::CyclicConstDefinition => "cyclic_const_definition",
+            Self::InvalidArraySize => "invalid_array_size",
+            Self::InvalidImpl => "invalid_impl",
+            Self::UnreachableCode => "unreachable_code",
+            Self::MissingReturn => "missing_return",
+            Self::BreakOutsideLoop => "break_outside_loop",
+            Self::ContinueOutsideLoop => "continue_outside_loop",
+            Self::DeadBranch => "dead_branch",
+            Self::UnboundedRecursion => "unbounded_recursion",
+            Self::NonTerminatingLoop => "non_terminating_loop",
+            Self::InternalError => "internal_error",
+        }
+    }
+
+    /// Finds a diagnostic code from its snake case name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|code| code.name() == name)
+    }
+}
+
 impl From<DiagnosticCode> for u32 {
     fn from(code: DiagnosticCode) -> Self {
         match code {
@@ -179,6 +310,9 @@ impl From<DiagnosticCode> for u32 {
             DiagnosticCode::MissingReturn => 3002,
             DiagnosticCode::BreakOutsideLoop => 3003,
             DiagnosticCode::ContinueOutsideLoop => 3004,
+            DiagnosticCode::DeadBranch => 3005,
+            DiagnosticCode::UnboundedRecursion => 3006,
+            DiagnosticCode::NonTerminatingLoop => 3007,
             DiagnosticCode::InvalidAssignmentTarget => 2010,
             DiagnosticCode::MissingReturnValue => 2011,
             DiagnosticCode::TupleIndexOutOfBounds => 2012,
@@ -187,6 +321,12 @@ impl From<DiagnosticCode> for u32 {
             DiagnosticCode::IndexOutOfBounds => 2015,
             DiagnosticCode::TypeInferenceError => 2016,
             DiagnosticCode::ConstArrayByPointer => 2017,
+            DiagnosticCode::UnusedResult => 2018,
+            DiagnosticCode::UnknownAttribute => 2019,
+            DiagnosticCode::InvalidParameterDefault => 2020,
+            DiagnosticCode::CyclicConstDefinition => 2021,
+            DiagnosticCode::InvalidArraySize => 2022,
+            DiagnosticCode::InvalidImpl => 2023,
             DiagnosticCode::InternalError => 9001,
         }
     }
@@ -203,6 +343,7 @@ impl Diagnostic {
             file_path: "".to_string(),
             span: SimpleSpan::from(0..0),
             related_spans: Vec::new(),
+            module_path: None,
         }
     }
 
@@ -215,6 +356,7 @@ impl Diagnostic {
             file_path: "".to_string(),
             span: SimpleSpan::from(0..0),
             related_spans: Vec::new(),
+            module_path: None,
         }
     }
 
@@ -227,6 +369,7 @@ impl Diagnostic {
             file_path: "".to_string(),
             span: SimpleSpan::from(0..0),
             related_spans: Vec::new(),
+            module_path: None,
         }
     }
 
@@ -237,6 +380,12 @@ impl Diagnostic {
         self
     }
 
+    /// Set the crate-qualified path of the module this diagnostic belongs to
+    pub fn with_module_path(mut self, module_path: String) -> Self {
+        self.module_path = Some(module_path);
+        self
+    }
+
     /// Add a related span with context message
     pub fn with_related_span(
         mut self,
@@ -306,6 +455,39 @@ impl Diagnostic {
         .with_location(file_path, span)
     }
 
+    /// Convenience method for dead branch warning
+    pub fn dead_branch(file_path: String, condition: bool, span: SimpleSpan<usize>) -> Self {
+        Self::warning(
+            DiagnosticCode::DeadBranch,
+            format!("Dead branch: the condition is always {condition}"),
+        )
+        .with_location(file_path, span)
+    }
+
+    /// Convenience method for unbounded recursion warning
+    pub fn unbounded_recursion(
+        file_path: String,
+        function_name: &str,
+        span: SimpleSpan<usize>,
+    ) -> Self {
+        Self::warning(
+            DiagnosticCode::UnboundedRecursion,
+            format!(
+                "Unbounded recursion: '{function_name}' is called again with unchanged arguments"
+            ),
+        )
+        .with_location(file_path, span)
+    }
+
+    /// Convenience method for non-terminating loop warning
+    pub fn non_terminating_loop(file_path: String, span: SimpleSpan<usize>) -> Self {
+        Self::warning(
+            DiagnosticCode::NonTerminatingLoop,
+            "Non-terminating loop: the loop body changes no state and never exits".to_string(),
+        )
+        .with_location(file_path, span)
+    }
+
     /// Convenience method for missing return warning
     pub fn missing_return(file_path: String, function_name: &str, span: SimpleSpan<usize>) -> Self {
         Self::error(
@@ -591,4 +773,13 @@ mod tests {
         assert!(display.contains("test"));
         assert!(display.contains("5:10"));
     }
+
+    #[test]
+    fn test_diagnostic_code_names() {
+        for &code in DiagnosticCode::ALL {
+            assert_eq!(DiagnosticCode::from_name(code.name()), Some(code));
+        }
+        assert_eq!(DiagnosticCode::UnusedVariable.name(), "unused_variable");
+        assert_eq!(DiagnosticCode::from_name("unknown_lint"), None);
+    }
 }
@@ -33,7 +33,11 @@ pub fn build_diagnostic_message(
                 .with_color(with_color),
         )
         .with_code(code_u32)
-        .with_message(&diagnostic.message)
+        .with_message(match &diagnostic.module_path {
+            // Files of different crates may share a name, the module path tells them apart
+            Some(module_path) => format!("{module_path}: {}", diagnostic.message),
+            None => diagnostic.message.clone(),
+        })
         .with_label(Label::new(report_span).with_message(&diagnostic.message));
 
     // Add related spans as notes
@@ -102,6 +102,13 @@ fn collect_item_spans(item: &TopLevelItem, spans: &mut Vec<SimpleSpan<usize>>) {
         TopLevelItem::Use(u) => {
             spans.push(u.span());
         }
+        TopLevelItem::Impl(i) => {
+            spans.push(i.span());
+            for method in &i.value().methods {
+                spans.push(method.span());
+                collect_function_spans(method.value(), spans);
+            }
+        }
     }
 }
 
@@ -154,7 +161,7 @@ fn collect_statement_spans(
         Statement::Let { value, .. } => {
             collect_expression_spans(value, spans);
         }
-        Statement::Assignment { lhs, rhs } => {
+        Statement::Assignment { lhs, rhs } | Statement::CompoundAssignment { lhs, rhs, .. } => {
             collect_expression_spans(lhs, spans);
             collect_expression_spans(rhs, spans);
         }
@@ -205,6 +212,21 @@ fn collect_expression_spans(
         Expression::Parenthesized(inner) => {
             collect_expression_spans(inner, spans);
         }
+        Expression::Block { statements, value } => {
+            for stmt in statements {
+                collect_statement_spans(stmt, spans);
+            }
+            collect_expression_spans(value, spans);
+        }
+        Expression::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            collect_expression_spans(condition, spans);
+            collect_expression_spans(then_branch, spans);
+            collect_expression_spans(else_branch, spans);
+        }
         Expression::StructLiteral { fields, .. } => {
             for (_, value) in fields {
                 collect_expression_spans(value, spans);
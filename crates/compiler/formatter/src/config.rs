@@ -8,6 +8,12 @@ pub struct FormatterConfig {
     pub trailing_comma: bool,
     /// Line ending style
     pub newline_style: NewlineStyle,
+    /// Whether to dedupe, sort and group `use` imports at the top of the module
+    pub organize_imports: bool,
+    /// Modules of the current project, whose imports are grouped last
+    ///
+    /// Paths starting with `crate`, `self` or `super` are always project imports.
+    pub project_modules: Vec<String>,
 }
 
 impl Default for FormatterConfig {
@@ -17,6 +23,8 @@ impl Default for FormatterConfig {
             indent_width: 4,
             trailing_comma: false,
             newline_style: NewlineStyle::Auto,
+            organize_imports: false,
+            project_modules: Vec::new(),
         }
     }
 }
@@ -9,12 +9,17 @@ pub enum Doc {
     Line,
     /// Soft line break (space or newline)
     SoftLine,
+    /// Soft break (nothing or newline)
+    SoftBreak,
     /// Group - tries to fit on one line
     Group(Box<Doc>),
     /// Indented block
     Indent(u32, Box<Doc>),
     /// Concatenation of multiple docs
     Concat(Vec<Doc>),
+    /// Fill - alternating contents and separators, packing as many contents per line as
+    /// fit: a separator only breaks if the content following it does not fit
+    Fill(Vec<Doc>),
 }
 
 impl Doc {
@@ -34,6 +39,10 @@ impl Doc {
         Self::SoftLine
     }
 
+    pub const fn softbreak() -> Self {
+        Self::SoftBreak
+    }
+
     pub fn group(doc: Self) -> Self {
         Self::Group(Box::new(doc))
     }
@@ -46,6 +55,11 @@ impl Doc {
         Self::Concat(docs)
     }
 
+    /// Create a fill from alternating contents and separators, starting with a content
+    pub const fn fill(parts: Vec<Self>) -> Self {
+        Self::Fill(parts)
+    }
+
     /// Join documents with a separator
     pub fn join(sep: Self, docs: Vec<Self>) -> Self {
         if docs.is_empty() {
@@ -174,14 +188,22 @@ impl Renderer {
                     self.pos = 0;
                 }
             },
+            Doc::SoftBreak => {
+                if let Mode::Break = mode {
+                    self.output.push('\n');
+                    self.pos = 0;
+                }
+            }
             Doc::Group(inner) => {
                 let fits = self.fits(inner, self.max_width.saturating_sub(self.pos));
                 let inner_mode = if fits { Mode::Flat } else { Mode::Break };
                 self.render_doc(inner, inner_mode);
             }
             Doc::Indent(width, inner) => {
-                // Render inner content to a separate string first
-                let mut inner_renderer = Self::new(self.max_width);
+                // Render inner content to a separate string first, in the coordinates of
+                // the indented lines so that groups are measured against the actual width
+                let mut inner_renderer = Self::new(self.max_width.saturating_sub(*width as usize));
+                inner_renderer.pos = self.pos.saturating_sub(*width as usize);
                 inner_renderer.render_doc(inner, mode);
 
                 // Apply indentation to the rendered content
@@ -209,9 +231,44 @@ impl Renderer {
                     self.render_doc(doc, mode);
                 }
             }
+            Doc::Fill(parts) => {
+                if self.fits(doc, self.max_width.saturating_sub(self.pos)) {
+                    for part in parts {
+                        self.render_doc(part, Mode::Flat);
+                    }
+                    return;
+                }
+
+                let mut parts = parts.iter();
+                if let Some(first) = parts.next() {
+                    self.render_content(first);
+                }
+                while let Some(separator) = parts.next() {
+                    let content = parts.next().unwrap_or(&Doc::Nil);
+                    let pair = Doc::concat(vec![separator.clone(), content.clone()]);
+                    let separator_mode =
+                        if self.fits(&pair, self.max_width.saturating_sub(self.pos)) {
+                            Mode::Flat
+                        } else {
+                            Mode::Break
+                        };
+                    self.render_doc(separator, separator_mode);
+                    self.render_content(content);
+                }
+            }
         }
     }
 
+    /// Renders a content of a fill, flat if it fits on the rest of the line
+    fn render_content(&mut self, doc: &Doc) {
+        let mode = if self.fits(doc, self.max_width.saturating_sub(self.pos)) {
+            Mode::Flat
+        } else {
+            Mode::Break
+        };
+        self.render_doc(doc, mode);
+    }
+
     fn fits(&self, doc: &Doc, width: usize) -> bool {
         Self::measure(doc, width, Mode::Flat).is_some()
     }
@@ -231,9 +288,13 @@ impl Renderer {
                 Mode::Flat => width.checked_sub(1),
                 Mode::Break => None,
             },
+            Doc::SoftBreak => match mode {
+                Mode::Flat => Some(width),
+                Mode::Break => None,
+            },
             Doc::Group(inner) => Self::measure(inner, width, Mode::Flat),
             Doc::Indent(_, inner) => Self::measure(inner, width, mode),
-            Doc::Concat(docs) => {
+            Doc::Concat(docs) | Doc::Fill(docs) => {
                 for doc in docs {
                     width = Self::measure(doc, width, mode)?;
                 }
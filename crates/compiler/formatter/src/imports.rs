@@ -0,0 +1,139 @@
+//! Import organization
+//!
+//! When [`FormatterConfig::organize_imports`](crate::FormatterConfig::organize_imports) is
+//! set, the `use` statements of a module are hoisted to its top, deduplicated, sorted by
+//! path, and grouped: standard library imports first, then third-party imports, then
+//! imports of the current project. Groups are separated by a blank line.
+
+use std::collections::HashSet;
+
+use cairo_m_compiler_parser::parser::{Spanned, UseItem, UseItems, UseStmt};
+
+/// Path roots of standard library imports
+const STD_ROOTS: &[&str] = &["std", "core"];
+
+/// Path roots always referring to the current project
+const PROJECT_ROOTS: &[&str] = &["crate", "self", "super"];
+
+/// Group of an import, in output order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ImportGroup {
+    /// `std` and `core` imports
+    Std,
+    /// Imports of other projects
+    ThirdParty,
+    /// Imports of the current project
+    Project,
+}
+
+/// Module path of a use statement, e.g. `["std", "math"]`
+fn path_segments(use_stmt: &UseStmt) -> Vec<&str> {
+    use_stmt.path.iter().map(|s| s.value().as_str()).collect()
+}
+
+/// Classifies an import by the first segment of its path
+pub fn import_group(use_stmt: &UseStmt, project_modules: &[String]) -> ImportGroup {
+    let root = use_stmt
+        .path
+        .first()
+        .map(|s| s.value().as_str())
+        .unwrap_or_default();
+
+    if STD_ROOTS.contains(&root) {
+        ImportGroup::Std
+    } else if PROJECT_ROOTS.contains(&root) || project_modules.iter().any(|m| m == root) {
+        ImportGroup::Project
+    } else {
+        ImportGroup::ThirdParty
+    }
+}
+
+/// Removes the items already imported by an earlier statement, and sorts item lists
+///
+/// Returns `None` if every item of the statement was already imported.
+fn dedupe_items(
+    use_stmt: &Spanned<UseStmt>,
+    imported: &mut HashSet<(Vec<String>, String, String)>,
+) -> Option<Spanned<UseStmt>> {
+    let path: Vec<String> = path_segments(use_stmt.value())
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut is_new = |item: &UseItem| {
+        imported.insert((
+            path.clone(),
+            item.name.value().clone(),
+            item.local_name().value().clone(),
+        ))
+    };
+
+    let items = match &use_stmt.value().items {
+        UseItems::Single(item) => {
+            if !is_new(item) {
+                return None;
+            }
+            UseItems::Single(item.clone())
+        }
+        UseItems::List(items) => {
+            let mut items: Vec<_> = items.iter().filter(|item| is_new(item)).cloned().collect();
+            if items.is_empty() {
+                return None;
+            }
+            items.sort_by(|a, b| a.name.value().cmp(b.name.value()));
+            UseItems::List(items)
+        }
+    };
+
+    Some(Spanned::new(
+        UseStmt {
+            path: use_stmt.value().path.clone(),
+            items,
+            is_pub: use_stmt.value().is_pub,
+        },
+        use_stmt.span(),
+    ))
+}
+
+/// Sort key of a use statement: its path, then its first item
+fn sort_key(use_stmt: &UseStmt) -> (Vec<&str>, &str) {
+    let first_item = match &use_stmt.items {
+        UseItems::Single(item) => item.name.value().as_str(),
+        UseItems::List(items) => items
+            .first()
+            .map(|item| item.name.value().as_str())
+            .unwrap_or_default(),
+    };
+    (path_segments(use_stmt), first_item)
+}
+
+/// Dedupes, sorts and groups use statements
+///
+/// Statements keep their span so that their comments stay attached. Empty groups are
+/// omitted.
+pub fn organize_imports(
+    imports: &[Spanned<UseStmt>],
+    project_modules: &[String],
+) -> Vec<Vec<Spanned<UseStmt>>> {
+    let mut imported = HashSet::new();
+    let mut imports: Vec<Spanned<UseStmt>> = imports
+        .iter()
+        .filter_map(|use_stmt| dedupe_items(use_stmt, &mut imported))
+        .collect();
+    imports.sort_by(|a, b| {
+        let group_a = import_group(a.value(), project_modules);
+        let group_b = import_group(b.value(), project_modules);
+        group_a
+            .cmp(&group_b)
+            .then_with(|| sort_key(a.value()).cmp(&sort_key(b.value())))
+    });
+
+    let mut groups: Vec<(ImportGroup, Vec<Spanned<UseStmt>>)> = Vec::new();
+    for use_stmt in imports {
+        let group = import_group(use_stmt.value(), project_modules);
+        match groups.last_mut() {
+            Some((last, stmts)) if *last == group => stmts.push(use_stmt),
+            _ => groups.push((group, vec![use_stmt])),
+        }
+    }
+    groups.into_iter().map(|(_, stmts)| stmts).collect()
+}
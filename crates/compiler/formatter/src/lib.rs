@@ -4,6 +4,7 @@ pub mod comment_preserver;
 pub mod config;
 pub mod context;
 pub mod doc;
+pub mod imports;
 pub mod rules;
 pub mod simple_comment_preserver;
 pub mod trivia;
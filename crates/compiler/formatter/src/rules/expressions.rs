@@ -1,4 +1,5 @@
-use cairo_m_compiler_parser::parser::{BinaryOp, Expression, UnaryOp};
+use cairo_m_compiler_parser::parser::{BinaryOp, Expression, Spanned, UnaryOp};
+use chumsky::span::SimpleSpan;
 
 use crate::Format;
 use crate::context::FormatterCtx;
@@ -7,6 +8,10 @@ use crate::utils::*;
 
 impl Format for Expression {
     fn format(&self, ctx: &mut FormatterCtx) -> Doc {
+        if let Some(doc) = format_call_chain(self, ctx) {
+            return doc;
+        }
+
         match self {
             Self::Literal(n, suffix) => {
                 let mut text = n.to_string();
@@ -16,28 +21,16 @@ impl Format for Expression {
                 Doc::text(text)
             }
             Self::BooleanLiteral(b) => Doc::text(if *b { "true" } else { "false" }),
+            Self::StringLiteral(s) => Doc::text(format!("\"{s}\"")),
             Self::Identifier(id) => Doc::text(id.value()),
             Self::UnaryOp { op, expr } => {
                 Doc::concat(vec![op.format(ctx), expr.value().format(ctx)])
             }
-            Self::BinaryOp { op, left, right } => Doc::group(Doc::concat(vec![
-                left.value().format(ctx),
-                Doc::softline(),
-                op.format(ctx),
-                Doc::softline(),
-                right.value().format(ctx),
-            ])),
-            Self::FunctionCall { callee, args } => {
-                let arg_docs = args
-                    .iter()
-                    .map(|a| a.value().format(ctx))
-                    .collect::<Vec<_>>();
-
-                Doc::concat(vec![
-                    callee.value().format(ctx),
-                    parens(comma_separated(arg_docs)),
-                ])
-            }
+            Self::BinaryOp { op, left, right } => format_binary_chain(op, left, right, ctx),
+            Self::FunctionCall { callee, args } => Doc::concat(vec![
+                callee.value().format(ctx),
+                format_call_args(args, ctx),
+            ]),
             Self::IndexAccess { array, index } => Doc::concat(vec![
                 array.value().format(ctx),
                 Doc::text("["),
@@ -109,11 +102,294 @@ impl Format for Expression {
                 count.value().format(ctx),
                 Doc::text("]"),
             ]),
+            Self::Block { statements, value } => {
+                let mut lines = statements
+                    .iter()
+                    .map(|s| Doc::concat(vec![Doc::line(), s.value().format(ctx)]))
+                    .collect::<Vec<_>>();
+                lines.push(Doc::concat(vec![Doc::line(), value.value().format(ctx)]));
+
+                Doc::concat(vec![
+                    Doc::text("{"),
+                    Doc::indent(ctx.cfg.indent_width, Doc::concat(lines)),
+                    Doc::line(),
+                    Doc::text("}"),
+                ])
+            }
+            Self::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => Doc::concat(vec![
+                Doc::text("if"),
+                Doc::text(" "),
+                condition.value().format(ctx),
+                Doc::text(" "),
+                then_branch.value().format(ctx),
+                Doc::text(" "),
+                Doc::text("else"),
+                Doc::text(" "),
+                else_branch.value().format(ctx),
+            ]),
             Self::Parenthesized(inner) => parens(inner.value().format(ctx)),
         }
     }
 }
 
+/// Precedence tier of a binary operator
+///
+/// Binary operators are left-associative, so operands of a same tier chain without
+/// parentheses.
+const fn precedence(op: &BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Or => 0,
+        BinaryOp::And => 1,
+        BinaryOp::Eq
+        | BinaryOp::Neq
+        | BinaryOp::Less
+        | BinaryOp::Greater
+        | BinaryOp::LessEqual
+        | BinaryOp::GreaterEqual => 2,
+        BinaryOp::BitwiseOr => 3,
+        BinaryOp::BitwiseXor => 4,
+        BinaryOp::BitwiseAnd => 5,
+        BinaryOp::ShiftLeft | BinaryOp::ShiftRight => 6,
+        BinaryOp::Add | BinaryOp::Sub => 7,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 8,
+    }
+}
+
+/// Texts of the comments trailing a node
+fn trailing_comments(ctx: &FormatterCtx, span: SimpleSpan<usize>) -> Vec<String> {
+    ctx.get_trailing_comments(span)
+        .map(|comments| comments.iter().map(|c| c.text.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// An operand of a binary operator chain, with the operator preceding it
+struct ChainOperand<'a> {
+    op: Option<&'a BinaryOp>,
+    expr: &'a Spanned<Expression>,
+    /// Comments trailing the operand, or trailing the sub-chain it ends
+    comments: Vec<String>,
+}
+
+/// Flattens a left-associative chain of operators of the given tier, e.g. `a + b - c`
+fn collect_chain_operands<'a>(
+    expr: &'a Spanned<Expression>,
+    op: Option<&'a BinaryOp>,
+    tier: u8,
+    ctx: &FormatterCtx,
+    operands: &mut Vec<ChainOperand<'a>>,
+) {
+    if let Expression::BinaryOp {
+        op: inner_op,
+        left,
+        right,
+    } = expr.value()
+    {
+        if precedence(inner_op) == tier {
+            collect_chain_operands(left, op, tier, ctx, operands);
+            // The sub-chain ends with its right operand, which owns its comments
+            let mut comments = trailing_comments(ctx, right.span());
+            comments.extend(trailing_comments(ctx, expr.span()));
+            operands.push(ChainOperand {
+                op: Some(inner_op),
+                expr: right,
+                comments,
+            });
+            return;
+        }
+    }
+
+    operands.push(ChainOperand {
+        op,
+        expr,
+        comments: trailing_comments(ctx, expr.span()),
+    });
+}
+
+/// Formats a binary operator chain as a fill
+///
+/// Operands are packed on as few lines as possible, continuation lines starting with the
+/// operator. A comment trailing an operand stays on its line, forcing a break after it.
+fn format_binary_chain(
+    op: &BinaryOp,
+    left: &Spanned<Expression>,
+    right: &Spanned<Expression>,
+    ctx: &mut FormatterCtx,
+) -> Doc {
+    let mut operands = vec![];
+    collect_chain_operands(left, None, precedence(op), ctx, &mut operands);
+    operands.push(ChainOperand {
+        op: Some(op),
+        expr: right,
+        // The last operand is followed by the enclosing node, which handles its comments
+        comments: vec![],
+    });
+
+    let mut parts = vec![];
+    let mut previous_has_comments = false;
+    for operand in operands {
+        if !parts.is_empty() {
+            parts.push(if previous_has_comments {
+                Doc::line()
+            } else {
+                Doc::softline()
+            });
+        }
+
+        let mut content = vec![];
+        if let Some(op) = operand.op {
+            content.push(op.format(ctx));
+            content.push(Doc::text(" "));
+        }
+        content.push(operand.expr.value().format(ctx));
+
+        previous_has_comments = !operand.comments.is_empty();
+        parts.push(Doc::with_trailing_comments(
+            Doc::concat(content),
+            operand.comments,
+        ));
+    }
+
+    Doc::indent(ctx.cfg.indent_width, Doc::fill(parts))
+}
+
+/// A link of a member access chain, following its root or a previous link
+enum ChainLink<'a> {
+    Field(&'a Spanned<String>),
+    Call(&'a [Spanned<Expression>]),
+    Index(&'a Spanned<Expression>),
+    TupleIndex(usize),
+}
+
+/// Flattens a chain of field accesses, method calls and indexing, e.g. `a.b.c(x)[0].d(y)`,
+/// into its root expression and its links in source order
+fn collect_chain_links<'a>(
+    mut expr: &'a Expression,
+    links: &mut Vec<ChainLink<'a>>,
+) -> &'a Expression {
+    loop {
+        expr = match expr {
+            Expression::MemberAccess { object, field } => {
+                links.push(ChainLink::Field(field));
+                object.value()
+            }
+            Expression::FunctionCall { callee, args }
+                if matches!(callee.value(), Expression::MemberAccess { .. }) =>
+            {
+                links.push(ChainLink::Call(args));
+                callee.value()
+            }
+            Expression::IndexAccess { array, index } => {
+                links.push(ChainLink::Index(index));
+                array.value()
+            }
+            Expression::TupleIndex { tuple, index } => {
+                links.push(ChainLink::TupleIndex(*index));
+                tuple.value()
+            }
+            _ => break,
+        };
+    }
+    links.reverse();
+    expr
+}
+
+/// Formats a chain of at least two method calls as a group, either on one line or with one
+/// call per line
+///
+/// The root of the chain and its field accesses preceding the first method call stay on the
+/// first line, e.g. `a.b`, the following links breaking before each `.` with a hanging indent:
+///
+/// ```text
+/// a.b
+///     .c(x)
+///     .d(y)
+/// ```
+///
+/// Returns `None` for other expressions, formatted as usual.
+fn format_call_chain(expr: &Expression, ctx: &mut FormatterCtx) -> Option<Doc> {
+    let mut links = vec![];
+    let root = collect_chain_links(expr, &mut links);
+
+    let first_call = links
+        .windows(2)
+        .position(|pair| matches!(pair, [ChainLink::Field(_), ChainLink::Call(_)]))?;
+    let calls = links[first_call..]
+        .iter()
+        .filter(|link| matches!(link, ChainLink::Call(_)))
+        .count();
+    if calls < 2 {
+        return None;
+    }
+
+    let mut head = vec![root.format(ctx)];
+    let mut segments = vec![];
+    for (i, link) in links.iter().enumerate() {
+        let doc = match link {
+            ChainLink::Field(field) => Doc::text(format!(".{}", field.value())),
+            ChainLink::Call(args) => format_call_args(args, ctx),
+            ChainLink::Index(index) => brackets(index.value().format(ctx)),
+            ChainLink::TupleIndex(index) => Doc::text(format!(".{index}")),
+        };
+        if i < first_call {
+            head.push(doc);
+        } else {
+            // Each segment starts at a field access and runs up to the next one
+            if matches!(link, ChainLink::Field(_)) {
+                segments.push(Doc::softbreak());
+            }
+            segments.push(doc);
+        }
+    }
+
+    head.push(Doc::indent(ctx.cfg.indent_width, Doc::concat(segments)));
+    Some(Doc::group(Doc::concat(head)))
+}
+
+/// Formats call arguments as a group, either on one line or one argument per line
+///
+/// A comment trailing an argument stays after it, breaking the group.
+fn format_call_args(args: &[Spanned<Expression>], ctx: &mut FormatterCtx) -> Doc {
+    if args.is_empty() {
+        return Doc::text("()");
+    }
+
+    let mut parts = vec![Doc::softbreak()];
+    let mut closing_break = Doc::softbreak();
+    for (i, arg) in args.iter().enumerate() {
+        let is_last = i + 1 == args.len();
+        let mut doc = arg.value().format(ctx);
+        if !is_last {
+            doc = Doc::concat(vec![doc, Doc::text(",")]);
+        }
+
+        let comments = trailing_comments(ctx, arg.span());
+        let separator = if !comments.is_empty() {
+            Doc::line()
+        } else if is_last {
+            Doc::softbreak()
+        } else {
+            Doc::softline()
+        };
+        parts.push(Doc::with_trailing_comments(doc, comments));
+        if is_last {
+            closing_break = separator;
+        } else {
+            parts.push(separator);
+        }
+    }
+
+    Doc::group(Doc::concat(vec![
+        Doc::text("("),
+        Doc::indent(ctx.cfg.indent_width, Doc::concat(parts)),
+        closing_break,
+        Doc::text(")"),
+    ]))
+}
+
 impl Format for BinaryOp {
     fn format(&self, _ctx: &mut FormatterCtx) -> Doc {
         let op_text = match self {
@@ -133,6 +409,8 @@ impl Format for BinaryOp {
             Self::BitwiseAnd => "&",
             Self::BitwiseOr => "|",
             Self::BitwiseXor => "^",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
         };
         Doc::text(op_text)
     }
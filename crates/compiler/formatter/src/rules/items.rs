@@ -1,27 +1,60 @@
 use cairo_m_compiler_parser::parser::{
-    ConstDef, FunctionDef, Parameter, ParsedModule, StructDef, TopLevelItem, UseItems, UseStmt,
+    ConstDef, FunctionDef, ImplDef, Parameter, ParsedModule, Spanned, StructDef, TopLevelItem,
+    UseItem, UseItems, UseStmt,
 };
 
 use crate::Format;
 use crate::context::FormatterCtx;
 use crate::doc::Doc;
+use crate::imports::organize_imports;
 use crate::utils::*;
 
 impl Format for ParsedModule {
     fn format(&self, ctx: &mut FormatterCtx) -> Doc {
-        let mut docs = vec![];
+        let mut sections = vec![];
+
+        if ctx.cfg.organize_imports {
+            let imports: Vec<_> = self
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    TopLevelItem::Use(use_stmt) => Some(use_stmt.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            // Imports of a group are on consecutive lines
+            for group in organize_imports(&imports, &ctx.cfg.project_modules) {
+                let docs = group
+                    .into_iter()
+                    .map(|use_stmt| TopLevelItem::Use(use_stmt).format(ctx))
+                    .collect();
+                sections.push(Doc::join(Doc::line(), docs));
+            }
+            for item in &self.items {
+                if !matches!(item, TopLevelItem::Use(_)) {
+                    sections.push(item.format(ctx));
+                }
+            }
+        } else {
+            for item in &self.items {
+                sections.push(item.format(ctx));
+            }
+        }
 
-        for (i, item) in self.items.iter().enumerate() {
+        let mut docs = vec![];
+        let has_items = !sections.is_empty();
+        for (i, section) in sections.into_iter().enumerate() {
             if i > 0 {
                 // Add blank line between top-level items
                 docs.push(Doc::line());
                 docs.push(Doc::line());
             }
-            docs.push(item.format(ctx));
+            docs.push(section);
         }
 
         // Add final newline
-        if !self.items.is_empty() {
+        if has_items {
             docs.push(Doc::line());
         }
 
@@ -36,6 +69,7 @@ impl Format for TopLevelItem {
             Self::Struct(s) => (s.span(), s.value().format(ctx)),
             Self::Const(c) => (c.span(), c.value().format(ctx)),
             Self::Use(u) => (u.span(), u.value().format(ctx)),
+            Self::Impl(i) => (i.span(), i.value().format(ctx)),
         };
 
         // Add leading comments
@@ -57,11 +91,17 @@ impl Format for TopLevelItem {
 
 impl Format for FunctionDef {
     fn format(&self, ctx: &mut FormatterCtx) -> Doc {
-        let mut parts = vec![
+        // Attributes, one per line
+        let mut parts = self
+            .attributes
+            .iter()
+            .flat_map(|attr| [Doc::text(format!("#[{}]", attr.value())), Doc::line()])
+            .collect::<Vec<_>>();
+        parts.extend([
             Doc::text("fn"),
             Doc::text(" "),
             Doc::text(self.name.value()),
-        ];
+        ]);
 
         // Parameters
         let params = self
@@ -101,9 +141,17 @@ impl Format for FunctionDef {
 
 impl Format for Parameter {
     fn format(&self, _ctx: &mut FormatterCtx) -> Doc {
+        // Receivers take the type of their impl block
+        if self.is_receiver() {
+            return Doc::text(self.name.value());
+        }
         let mut parts = vec![Doc::text(self.name.value())];
         parts.push(Doc::text(": "));
         parts.push(self.type_expr.value().format(_ctx));
+        if let Some(default) = &self.default {
+            parts.push(Doc::text(" = "));
+            parts.push(default.value().format(_ctx));
+        }
         Doc::concat(parts)
     }
 }
@@ -143,6 +191,59 @@ impl Format for StructDef {
     }
 }
 
+impl Format for ImplDef {
+    fn format(&self, ctx: &mut FormatterCtx) -> Doc {
+        let mut parts = vec![
+            Doc::text("impl"),
+            Doc::text(" "),
+            Doc::text(self.type_name.value()),
+        ];
+
+        parts.push(Doc::text(" {"));
+
+        if !self.methods.is_empty() {
+            let mut methods = vec![];
+            for (i, method) in self.methods.iter().enumerate() {
+                if i > 0 {
+                    // Add blank line between methods
+                    methods.push(Doc::line());
+                }
+                methods.push(Doc::line());
+                methods.push(method.format(ctx));
+            }
+
+            parts.push(Doc::indent(ctx.cfg.indent_width, Doc::concat(methods)));
+            parts.push(Doc::line());
+        }
+
+        parts.push(Doc::text("}"));
+
+        Doc::concat(parts)
+    }
+}
+
+// Implement Format for Spanned<FunctionDef> to handle the comments of methods
+impl Format for Spanned<FunctionDef> {
+    fn format(&self, ctx: &mut FormatterCtx) -> Doc {
+        let span = self.span();
+        let mut doc = self.value().format(ctx);
+
+        // Add leading comments
+        if let Some(leading) = ctx.get_leading_comments(span) {
+            let comments: Vec<String> = leading.iter().map(|c| c.text.clone()).collect();
+            doc = Doc::with_leading_comments(comments, doc);
+        }
+
+        // Add trailing comments
+        if let Some(trailing) = ctx.get_trailing_comments(span) {
+            let comments: Vec<String> = trailing.iter().map(|c| c.text.clone()).collect();
+            doc = Doc::with_trailing_comments(doc, comments);
+        }
+
+        doc
+    }
+}
+
 impl Format for ConstDef {
     fn format(&self, ctx: &mut FormatterCtx) -> Doc {
         Doc::concat(vec![
@@ -158,7 +259,11 @@ impl Format for ConstDef {
 
 impl Format for UseStmt {
     fn format(&self, _ctx: &mut FormatterCtx) -> Doc {
-        let mut parts = vec![Doc::text("use"), Doc::text(" ")];
+        let mut parts = vec![];
+        if self.is_pub {
+            parts.push(Doc::text("pub "));
+        }
+        parts.extend([Doc::text("use"), Doc::text(" ")]);
 
         // Format the path
         let path_str = self
@@ -173,13 +278,10 @@ impl Format for UseStmt {
         // Format the items
         match &self.items {
             UseItems::Single(item) => {
-                parts.push(Doc::text(item.value()));
+                parts.push(format_use_item(item));
             }
             UseItems::List(items) => {
-                let item_docs = items
-                    .iter()
-                    .map(|i| Doc::text(i.value()))
-                    .collect::<Vec<_>>();
+                let item_docs = items.iter().map(format_use_item).collect::<Vec<_>>();
                 parts.push(braces(comma_separated(item_docs)));
             }
         }
@@ -188,3 +290,11 @@ impl Format for UseStmt {
         Doc::concat(parts)
     }
 }
+
+/// Formats an imported item, with its alias if renamed
+fn format_use_item(item: &UseItem) -> Doc {
+    match &item.alias {
+        Some(alias) => Doc::text(format!("{} as {}", item.name.value(), alias.value())),
+        None => Doc::text(item.name.value()),
+    }
+}
@@ -56,6 +56,14 @@ impl Format for Statement {
                 rhs.value().format(ctx),
                 Doc::text(";"),
             ]),
+            Self::CompoundAssignment { lhs, op, rhs } => Doc::concat(vec![
+                lhs.value().format(ctx),
+                Doc::text(" "),
+                op.format(ctx),
+                Doc::text("= "),
+                rhs.value().format(ctx),
+                Doc::text(";"),
+            ]),
             Self::Return { value } => {
                 let mut parts = vec![Doc::text("return")];
                 if let Some(expr) = value {
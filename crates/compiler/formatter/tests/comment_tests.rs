@@ -88,6 +88,46 @@ struct Point {
     let y = x * 2;
     return y;
 }
+"#;
+
+        let formatted = format_code(input);
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_preserve_comments_in_broken_call_args() {
+        let input = r#"fn test() -> felt {
+    let x = compute(first, // the first
+        second);
+    return x;
+}"#;
+
+        let expected = r#"fn test() -> felt {
+    let x = compute(
+        first, // the first
+        second
+    );
+    return x;
+}
+"#;
+
+        let formatted = format_code(input);
+        assert_eq!(formatted, expected);
+    }
+
+    #[test]
+    fn test_preserve_comments_in_broken_binary_chain() {
+        let input = r#"fn test() -> felt {
+    let x = a // first operand
+        + b + c;
+    return x;
+}"#;
+
+        let expected = r#"fn test() -> felt {
+    let x = a // first operand
+        + b + c;
+    return x;
+}
 "#;
 
         let formatted = format_code(input);
@@ -2,10 +2,21 @@ use cairo_m_compiler_parser::{ParserDatabaseImpl, SourceFile};
 use cairo_m_formatter::{FormatterConfig, format_source_file};
 
 fn format_code(source: &str) -> String {
+    format_code_with_config(source, &FormatterConfig::default())
+}
+
+fn format_code_with_config(source: &str, config: &FormatterConfig) -> String {
     let db = ParserDatabaseImpl::default();
     let file = SourceFile::new(&db, source.to_string(), "test.cm".to_string());
-    let config = FormatterConfig::default();
-    format_source_file(&db, file, &config)
+    format_source_file(&db, file, config)
+}
+
+fn organize_imports_config() -> FormatterConfig {
+    FormatterConfig {
+        organize_imports: true,
+        project_modules: vec!["math".to_string()],
+        ..Default::default()
+    }
 }
 
 // Parentheses preservation and precedence-sensitive formatting
@@ -125,3 +136,163 @@ fn test() { return; }
 "#;
     assert_eq!(format_code(input), expected);
 }
+
+#[test]
+fn test_function_attributes() {
+    let input = r#"#[must_use]   fn test()->felt{return 1;}"#;
+    let expected = "#[must_use]\nfn test() -> felt {\n    return 1;\n}\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_impl_block() {
+    let input = r#"impl Point { fn norm(self)->felt{return self.x*self.x;} fn shift(self,dx:felt)->felt{return self.x+dx;} }"#;
+    let expected = "impl Point {\n    fn norm(self) -> felt {\n        return self.x * self.x;\n    }\n\n    fn shift(self, dx: felt) -> felt {\n        return self.x + dx;\n    }\n}\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_organize_imports_sorts_and_groups() {
+    let input = r#"use math::sub;
+fn main() -> felt { return 0; }
+use utils::{max, min};
+use std::math::sqrt;
+use math::add;
+"#;
+    let expected = "use std::math::sqrt;\n\nuse utils::{max, min};\n\nuse math::add;\nuse math::sub;\n\nfn main() -> felt {\n    return 0;\n}\n";
+    let config = organize_imports_config();
+    assert_eq!(format_code_with_config(input, &config), expected);
+}
+
+#[test]
+fn test_organize_imports_dedupes() {
+    let input = r#"use math::{sub, add};
+use math::add;
+use math::{sub, mul};
+"#;
+    let expected = "use math::{add, sub};\nuse math::{mul};\n";
+    let config = organize_imports_config();
+    assert_eq!(format_code_with_config(input, &config), expected);
+}
+
+#[test]
+fn test_organize_imports_disabled_by_default() {
+    let input = "use math::sub;\nuse math::add;\n";
+    let expected = "use math::sub;\n\nuse math::add;\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_aliased_and_pub_imports() {
+    let input = "pub  use math::add  as plus;\nuse math::{sub as minus,mul};\n";
+    let expected = "pub use math::add as plus;\n\nuse math::{sub as minus, mul};\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_organize_imports_idempotence() {
+    let input = r#"use utils::min;
+use core::ops::{mul, add};
+use math::add;
+"#;
+    let config = organize_imports_config();
+    let formatted_once = format_code_with_config(input, &config);
+    let formatted_twice = format_code_with_config(&formatted_once, &config);
+    assert_eq!(formatted_once, formatted_twice);
+}
+
+#[test]
+fn test_long_binary_chain_fills_lines() {
+    let input = r#"fn test() -> felt { let total = alpha + beta + gamma + delta + epsilon + zeta; return total; }"#;
+    let expected = "fn test() -> felt {\n    let total = alpha + beta + gamma\n        + delta + epsilon + zeta;\n    return total;\n}\n";
+    let config = FormatterConfig {
+        max_width: 40,
+        ..Default::default()
+    };
+    let formatted = format_code_with_config(input, &config);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code_with_config(&formatted, &config), expected);
+}
+
+#[test]
+fn test_long_call_args_break_one_per_line() {
+    let input =
+        r#"fn test() -> felt { let x = compute(first_argument, second_argument); return x; }"#;
+    let expected = "fn test() -> felt {\n    let x = compute(\n        first_argument,\n        second_argument\n    );\n    return x;\n}\n";
+    let config = FormatterConfig {
+        max_width: 40,
+        ..Default::default()
+    };
+    let formatted = format_code_with_config(input, &config);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code_with_config(&formatted, &config), expected);
+}
+
+#[test]
+fn test_long_call_chain_breaks_one_call_per_line() {
+    let input = r#"fn test() -> felt { let x = point.inner.scale(factor).offset(delta).norm(); return x; }"#;
+    let expected = "fn test() -> felt {\n    let x = point.inner\n        .scale(factor)\n        .offset(delta)\n        .norm();\n    return x;\n}\n";
+    let config = FormatterConfig {
+        max_width: 40,
+        ..Default::default()
+    };
+    let formatted = format_code_with_config(input, &config);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code_with_config(&formatted, &config), expected);
+}
+
+#[test]
+fn test_short_call_chain_stays_on_one_line() {
+    let input = r#"fn test() -> felt { let x = p.pair.scale(2).values[0].norm(); return x.0; }"#;
+    let expected =
+        "fn test() -> felt {\n    let x = p.pair.scale(2).values[0].norm();\n    return x.0;\n}\n";
+    let formatted = format_code(input);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code(&formatted), expected);
+}
+
+#[test]
+fn test_single_method_call_is_not_a_chain() {
+    let input =
+        r#"fn test() -> felt { let x = point.inner.compute(first_argument, second); return x; }"#;
+    let expected = "fn test() -> felt {\n    let x = point.inner.compute(\n        first_argument,\n        second\n    );\n    return x;\n}\n";
+    let config = FormatterConfig {
+        max_width: 40,
+        ..Default::default()
+    };
+    let formatted = format_code_with_config(input, &config);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code_with_config(&formatted, &config), expected);
+}
+
+#[test]
+fn test_parameter_default_value() {
+    let input = r#"fn test(x:felt,y:felt=3){}"#;
+    let expected = "fn test(x: felt, y: felt = 3) {}\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_const_array_size() {
+    let input = r#"const N=2*SIZE;fn test(x:[felt;N]){let y=[0;N];}"#;
+    let expected = "const N = 2 * SIZE;\n\nfn test(x: [felt; N]) {\n    let y = [0; N];\n}\n";
+    assert_eq!(format_code(input), expected);
+}
+
+#[test]
+fn test_if_and_block_expressions() {
+    let input = r#"fn test(c:bool)->felt{let x=if c {1} else if !c {2} else {3};let y={let t=x*2;t+1};return y;}"#;
+    let expected = "fn test(c: bool) -> felt {\n    let x = if c {\n        1\n    } else if !c {\n        2\n    } else {\n        3\n    };\n    let y = {\n        let t = x * 2;\n        t + 1\n    };\n    return y;\n}\n";
+    let formatted = format_code(input);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code(&formatted), expected);
+}
+
+#[test]
+fn test_compound_assignments() {
+    let input = r#"fn test(){let x=1;x+=2;x-=1;x*=3;for(let i=0;i<3;i+=1){x+=i;}return;}"#;
+    let expected = "fn test() {\n    let x = 1;\n    x += 2;\n    x -= 1;\n    x *= 3;\n    for (let i = 0; i < 3; i += 1) {\n        x += i;\n    }\n    return;\n}\n";
+    let formatted = format_code(input);
+    assert_eq!(formatted, expected);
+    assert_eq!(format_code(&formatted), expected);
+}
@@ -4,7 +4,7 @@
 //! including critical edge detection and splitting, predecessor/successor queries,
 //! and other CFG transformations.
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{BasicBlock, BasicBlockId, MirFunction, Terminator};
 
@@ -129,6 +129,93 @@ pub(crate) fn split_all_critical_edges(
     edge_splits
 }
 
+/// Get the blocks reachable from the entry block, in reverse postorder
+///
+/// In reverse postorder, every block comes before its successors, back edges excepted.
+pub(crate) fn reverse_postorder(function: &MirFunction) -> Vec<BasicBlockId> {
+    let mut visited = FxHashSet::default();
+    let mut postorder = Vec::new();
+    // Each entry is a block and the index of its next successor to visit
+    let mut stack = vec![(function.entry_block, 0)];
+    visited.insert(function.entry_block);
+
+    while let Some((block_id, succ_idx)) = stack.pop() {
+        if let Some(&succ) = get_successors(function, block_id).get(succ_idx) {
+            stack.push((block_id, succ_idx + 1));
+            if visited.insert(succ) {
+                stack.push((succ, 0));
+            }
+        } else {
+            postorder.push(block_id);
+        }
+    }
+
+    postorder.reverse();
+    postorder
+}
+
+/// Get the predecessors of the blocks reachable from the entry block
+///
+/// Unlike [`get_predecessors`], the predecessors are derived from the terminators, and
+/// unreachable predecessors are ignored.
+pub(crate) fn reachable_predecessors(
+    function: &MirFunction,
+) -> FxHashMap<BasicBlockId, Vec<BasicBlockId>> {
+    let mut preds: FxHashMap<BasicBlockId, Vec<BasicBlockId>> = FxHashMap::default();
+    for block_id in reverse_postorder(function) {
+        for succ in get_successors(function, block_id) {
+            preds.entry(succ).or_default().push(block_id);
+        }
+    }
+    preds
+}
+
+/// Compute the dominators of the blocks reachable from the entry block
+///
+/// A block `d` dominates a block `b` if every path from the entry block to `b` goes
+/// through `d`; every block dominates itself. Unreachable blocks are absent from the map.
+pub(crate) fn compute_dominators(
+    function: &MirFunction,
+) -> FxHashMap<BasicBlockId, FxHashSet<BasicBlockId>> {
+    let order = reverse_postorder(function);
+    let preds = reachable_predecessors(function);
+    let all_blocks: FxHashSet<_> = order.iter().copied().collect();
+
+    let mut dominators: FxHashMap<_, _> = order
+        .iter()
+        .map(|&block_id| (block_id, all_blocks.clone()))
+        .collect();
+    dominators.insert(
+        function.entry_block,
+        std::iter::once(function.entry_block).collect(),
+    );
+
+    // Iterate to a fixpoint: dom(b) = {b} ∪ ⋂ dom(p) for p in preds(b)
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &block_id in order.iter().skip(1) {
+            let mut new_doms: Option<FxHashSet<BasicBlockId>> = None;
+            for pred in preds.get(&block_id).into_iter().flatten() {
+                let pred_doms = &dominators[pred];
+                new_doms = Some(match new_doms {
+                    None => pred_doms.clone(),
+                    Some(doms) => doms.intersection(pred_doms).copied().collect(),
+                });
+            }
+            let mut new_doms = new_doms.unwrap_or_default();
+            new_doms.insert(block_id);
+
+            if new_doms != dominators[&block_id] {
+                dominators.insert(block_id, new_doms);
+                changed = true;
+            }
+        }
+    }
+
+    dominators
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,4 +375,20 @@ mod tests {
         assert!(entry_succs.contains(&edge_block));
         assert!(!entry_succs.contains(&merge));
     }
+
+    #[test]
+    fn test_dominators() {
+        let function = create_diamond_cfg();
+        let entry = BasicBlockId::from_raw(0);
+        let left = BasicBlockId::from_raw(1);
+        let merge = BasicBlockId::from_raw(3);
+
+        assert_eq!(reverse_postorder(&function).first(), Some(&entry));
+
+        let dominators = compute_dominators(&function);
+        assert_eq!(dominators.len(), 4);
+        assert_eq!(dominators[&left], FxHashSet::from_iter([entry, left]));
+        // Neither branch dominates the merge block
+        assert_eq!(dominators[&merge], FxHashSet::from_iter([entry, merge]));
+    }
 }
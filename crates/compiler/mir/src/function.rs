@@ -9,8 +9,8 @@ use index_vec::IndexVec;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    BasicBlock, BasicBlockId, Instruction, MirType, PrettyPrint, Terminator, Value, ValueId,
-    indent_str,
+    BasicBlock, BasicBlockId, FunctionId, Instruction, InstructionKind, Literal, MirType,
+    PrettyPrint, Terminator, Value, ValueId, indent_str,
 };
 
 /// A simple definition identifier for MIR that doesn't depend on Salsa lifetimes
@@ -56,6 +56,10 @@ pub struct MirFunction {
     /// The order matches the function signature
     pub parameters: Vec<ValueId>,
 
+    /// Default values of the trailing parameters, keyed by their parameter value
+    /// Recorded in the ABI so that the runner can omit these arguments
+    pub parameter_defaults: FxHashMap<ValueId, Literal>,
+
     /// The return values of the function
     /// Empty for void functions, contains one or more values for functions with returns
     pub return_values: Vec<ValueId>,
@@ -68,6 +72,10 @@ pub struct MirFunction {
     /// Maps ValueId to its MirType for type checking and optimization
     pub value_types: FxHashMap<ValueId, MirType>,
 
+    /// Source location of each division, as `file:line:column`, keyed by its destination
+    /// Reported by the runner when the divisor is zero
+    pub division_locations: FxHashMap<ValueId, String>,
+
     /// Track which ValueIds have been used as destinations
     /// Used to enforce SSA - each ValueId can only be defined once
     pub(crate) defined_values: FxHashSet<ValueId>,
@@ -99,9 +107,11 @@ impl MirFunction {
             basic_blocks,
             entry_block,
             parameters: Vec::new(),
+            parameter_defaults: FxHashMap::default(),
             return_values: Vec::new(),
             next_value_id: 0,
             value_types: FxHashMap::default(),
+            division_locations: FxHashMap::default(),
             defined_values: FxHashSet::default(),
             // Initialize SSA state
             current_def: FxHashMap::default(),
@@ -177,9 +187,20 @@ impl MirFunction {
         self.basic_blocks.len()
     }
 
+    /// Returns an iterator over the functions called by this function
+    pub(crate) fn callees(&self) -> impl Iterator<Item = FunctionId> + '_ {
+        self.basic_blocks
+            .iter()
+            .flat_map(|block| &block.instructions)
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::Call { callee, .. } => Some(*callee),
+                _ => None,
+            })
+    }
+
     /// Returns a map from each ValueId to its usage count in the function.
     /// This is useful for optimization passes like dead code elimination or instruction fusion.
-    pub(crate) fn get_value_use_counts(&self) -> FxHashMap<ValueId, usize> {
+    pub fn get_value_use_counts(&self) -> FxHashMap<ValueId, usize> {
         let mut counts = FxHashMap::default();
         for (_id, block) in self.basic_blocks() {
             for instruction in &block.instructions {
@@ -131,6 +131,7 @@ impl crate::InstructionKind {
             kind: self,
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -23,6 +23,9 @@ pub enum BinaryOp {
     Sub,
     Mul,
     Div,
+    /// Felt exponentiation by a literal exponent. Not produced from source: codegen fuses
+    /// repeated multiplications into it.
+    Pow,
 
     // Felt comparison operators
     Eq,
@@ -55,6 +58,10 @@ pub enum BinaryOp {
     U32BitwiseAnd,
     U32BitwiseOr,
     U32BitwiseXor,
+
+    // U32 shift operators, the shift amount is taken modulo 32
+    U32ShiftLeft,
+    U32ShiftRight,
 }
 
 impl std::fmt::Display for BinaryOp {
@@ -64,6 +71,7 @@ impl std::fmt::Display for BinaryOp {
             Self::Sub => write!(f, "-"),
             Self::Mul => write!(f, "*"),
             Self::Div => write!(f, "/"),
+            Self::Pow => write!(f, "**"),
             Self::Eq => write!(f, "=="),
             Self::Neq => write!(f, "!="),
             Self::Less => write!(f, "<"),
@@ -86,6 +94,8 @@ impl std::fmt::Display for BinaryOp {
             Self::U32BitwiseAnd => write!(f, "& (u32)"),
             Self::U32BitwiseOr => write!(f, "| (u32)"),
             Self::U32BitwiseXor => write!(f, "^ (u32)"),
+            Self::U32ShiftLeft => write!(f, "<< (u32)"),
+            Self::U32ShiftRight => write!(f, ">> (u32)"),
         }
     }
 }
@@ -129,6 +139,8 @@ impl BinaryOp {
             (P::BitwiseAnd, T::U32) => Self::U32BitwiseAnd,
             (P::BitwiseOr, T::U32) => Self::U32BitwiseOr,
             (P::BitwiseXor, T::U32) => Self::U32BitwiseXor,
+            (P::ShiftLeft, T::U32) => Self::U32ShiftLeft,
+            (P::ShiftRight, T::U32) => Self::U32ShiftRight,
 
             // Bool operations
             (P::Eq, T::Bool) => Self::Eq,
@@ -151,13 +163,17 @@ impl BinaryOp {
     pub const fn result_type(&self) -> crate::MirType {
         match self {
             // Arithmetic ops return same type
-            Self::Add | Self::Sub | Self::Mul | Self::Div => crate::MirType::felt(),
+            Self::Add | Self::Sub | Self::Mul | Self::Div | Self::Pow => crate::MirType::felt(),
             Self::U32Add | Self::U32Sub | Self::U32Mul | Self::U32Div | Self::U32Rem => {
                 crate::MirType::u32()
             }
 
-            // U32 bitwise ops return u32
-            Self::U32BitwiseAnd | Self::U32BitwiseOr | Self::U32BitwiseXor => crate::MirType::u32(),
+            // U32 bitwise and shift ops return u32
+            Self::U32BitwiseAnd
+            | Self::U32BitwiseOr
+            | Self::U32BitwiseXor
+            | Self::U32ShiftLeft
+            | Self::U32ShiftRight => crate::MirType::u32(),
 
             // Comparison ops return bool
             Self::Eq
@@ -226,6 +242,10 @@ pub struct Instruction {
     /// Used for type queries and cross-referencing
     pub source_expr_id: Option<MirExpressionId>,
 
+    /// Source location as `file:line:column`, recorded for calls so that the runner can
+    /// report call sites in backtraces
+    pub location: Option<String>,
+
     /// Optional comment for debugging
     pub comment: Option<String>,
 }
@@ -364,15 +384,34 @@ pub enum InstructionKind {
         is_const: bool,
     },
 
+    /// Copy the storage of an aggregate: `dest = copy_aggregate(source)`
+    /// Structs and tuples copy their slots into `dest`. Fixed arrays are passed by pointer:
+    /// their elements are copied to fresh storage, and `dest` points to the copy.
+    CopyAggregate {
+        dest: ValueId,
+        source: Value,
+        ty: MirType,
+    },
+
     /// Assert equality between two values.
     AssertEq { left: Value, right: Value },
 
+    /// Print a scalar value to the runner's debug log.
+    /// Emitted for the `debug_print` builtin. The print is proven as a step that only advances
+    /// the pc: the printed value is read outside of the memory trace and is not bound by the proof.
+    DebugPrint { value: Value, ty: MirType },
+
     /// Allocate `cells` QM31 cells on the heap and return a pointer
     /// to the first cell in `dest` (felt pointer). Advances the global
     /// heap cursor by `cells`.
     ///
     /// Side effects: mutates the global heap register.
     HeapAllocCells { dest: ValueId, cells: Value },
+
+    /// Load the felt at `index` in the environment block into `dest`.
+    /// Emitted for the `env` builtin: the block holds values supplied by the runner, which
+    /// never change during the execution.
+    LoadEnv { dest: ValueId, index: u32 },
 }
 
 impl Instruction {
@@ -382,6 +421,7 @@ impl Instruction {
             kind: InstructionKind::Assign { dest, source, ty },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -392,6 +432,7 @@ impl Instruction {
             kind: InstructionKind::UnaryOp { op, dest, source },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -407,6 +448,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -435,6 +477,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -455,6 +498,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -465,6 +509,7 @@ impl Instruction {
             kind: InstructionKind::Load { dest, place, ty },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -475,6 +520,7 @@ impl Instruction {
             kind: InstructionKind::Store { place, value, ty },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -485,6 +531,18 @@ impl Instruction {
             kind: InstructionKind::Debug { message, values },
             source_span: None,
             source_expr_id: None,
+            location: None,
+            comment: None,
+        }
+    }
+
+    /// Creates a new debug print instruction
+    pub const fn debug_print(value: Value, ty: MirType) -> Self {
+        Self {
+            kind: InstructionKind::DebugPrint { value, ty },
+            source_span: None,
+            source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -495,6 +553,7 @@ impl Instruction {
             kind: InstructionKind::MakeTuple { dest, elements },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -515,6 +574,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -533,6 +593,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -553,6 +614,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -575,6 +637,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -597,6 +660,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -606,6 +670,7 @@ impl Instruction {
             kind: InstructionKind::Nop,
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -625,6 +690,29 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
+            comment: None,
+        }
+    }
+
+    /// Creates a new aggregate copy instruction
+    pub const fn copy_aggregate(dest: ValueId, source: Value, ty: MirType) -> Self {
+        Self {
+            kind: InstructionKind::CopyAggregate { dest, source, ty },
+            source_span: None,
+            source_expr_id: None,
+            location: None,
+            comment: None,
+        }
+    }
+
+    /// Creates a new instruction loading the environment value at `index` into `dest`
+    pub const fn load_env(dest: ValueId, index: u32) -> Self {
+        Self {
+            kind: InstructionKind::LoadEnv { dest, index },
+            source_span: None,
+            source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -636,6 +724,7 @@ impl Instruction {
             kind: InstructionKind::HeapAllocCells { dest, cells },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -655,6 +744,7 @@ impl Instruction {
             },
             source_span: None,
             source_expr_id: None,
+            location: None,
             comment: None,
         }
     }
@@ -665,6 +755,12 @@ impl Instruction {
         self
     }
 
+    /// Sets the source location reported for this instruction
+    pub fn with_location(mut self, location: String) -> Self {
+        self.location = Some(location);
+        self
+    }
+
     /// Sets the source expression ID for this instruction
     pub const fn with_expr_id(mut self, expr_id: MirExpressionId) -> Self {
         self.source_expr_id = Some(expr_id);
@@ -687,14 +783,17 @@ impl Instruction {
             | InstructionKind::InsertField { dest, .. }
             | InstructionKind::InsertTuple { dest, .. }
             | InstructionKind::MakeFixedArray { dest, .. }
-            | InstructionKind::HeapAllocCells { dest, .. } => vec![*dest],
+            | InstructionKind::CopyAggregate { dest, .. }
+            | InstructionKind::HeapAllocCells { dest, .. }
+            | InstructionKind::LoadEnv { dest, .. } => vec![*dest],
 
             InstructionKind::Call { dests, .. } => dests.clone(),
 
             InstructionKind::Debug { .. }
             | InstructionKind::Nop
             | InstructionKind::Store { .. }
-            | InstructionKind::AssertEq { .. } => vec![],
+            | InstructionKind::AssertEq { .. }
+            | InstructionKind::DebugPrint { .. } => vec![],
         }
     }
 
@@ -836,6 +935,11 @@ impl Instruction {
                     used.insert(id);
                 });
             }
+            InstructionKind::CopyAggregate { source, .. } => {
+                visit_value(source, |id| {
+                    used.insert(id);
+                });
+            }
             InstructionKind::HeapAllocCells { cells, .. } => {
                 visit_value(cells, |id| {
                     used.insert(id);
@@ -850,6 +954,14 @@ impl Instruction {
                     used.insert(id);
                 });
             }
+
+            InstructionKind::DebugPrint { value, .. } => {
+                visit_value(value, |id| {
+                    used.insert(id);
+                });
+            }
+
+            InstructionKind::LoadEnv { .. } => {}
         }
 
         used
@@ -931,6 +1043,9 @@ impl Instruction {
             InstructionKind::MakeFixedArray { elements, .. } => {
                 replace_value_ids(elements, from, to);
             }
+            InstructionKind::CopyAggregate { source, .. } => {
+                replace_value_id(source, from, to);
+            }
             InstructionKind::HeapAllocCells { cells, .. } => {
                 replace_value_id(cells, from, to);
             }
@@ -938,6 +1053,10 @@ impl Instruction {
                 replace_value_id(left, from, to);
                 replace_value_id(right, from, to);
             }
+            InstructionKind::DebugPrint { value, .. } => {
+                replace_value_id(value, from, to);
+            }
+            InstructionKind::LoadEnv { .. } => {}
         }
     }
 
@@ -961,8 +1080,11 @@ impl Instruction {
             InstructionKind::InsertField { .. } => Ok(()),
             InstructionKind::InsertTuple { .. } => Ok(()),
             InstructionKind::MakeFixedArray { .. } => Ok(()),
+            InstructionKind::CopyAggregate { .. } => Ok(()),
             InstructionKind::HeapAllocCells { .. } => Ok(()),
             InstructionKind::AssertEq { .. } => Ok(()),
+            InstructionKind::DebugPrint { .. } => Ok(()),
+            InstructionKind::LoadEnv { .. } => Ok(()),
         }
     }
 
@@ -974,6 +1096,7 @@ impl Instruction {
             self.kind,
             InstructionKind::Call { .. }
                 | InstructionKind::Debug { .. }
+                | InstructionKind::DebugPrint { .. }
                 | InstructionKind::Store { .. }
                 | InstructionKind::HeapAllocCells { .. }
         )
@@ -991,6 +1114,7 @@ impl Instruction {
             comment: None,
             source_span: None,
             source_expr_id: None,
+            location: None,
         }
     }
 
@@ -1261,6 +1385,13 @@ impl PrettyPrint for Instruction {
                     elements_str
                 ));
             }
+            InstructionKind::CopyAggregate { dest, source, .. } => {
+                result.push_str(&format!(
+                    "{} = copyaggregate {}",
+                    dest.pretty_print(0),
+                    source.pretty_print(0)
+                ));
+            }
             InstructionKind::HeapAllocCells { dest, cells } => {
                 result.push_str(&format!(
                     "{} = heapalloccells {}",
@@ -1276,6 +1407,14 @@ impl PrettyPrint for Instruction {
                     right.pretty_print(0)
                 ));
             }
+
+            InstructionKind::DebugPrint { value, ty } => {
+                result.push_str(&format!("debug_print {} ({ty})", value.pretty_print(0)));
+            }
+
+            InstructionKind::LoadEnv { dest, index } => {
+                result.push_str(&format!("{} = env {index}", dest.pretty_print(0)));
+            }
         }
 
         result
@@ -54,17 +54,25 @@ pub use function::{MirDefinitionId, MirFunction};
 pub use instruction::{BinaryOp, Instruction, InstructionKind, MirExpressionId};
 pub use layout::DataLayout;
 pub use mir_types::MirType;
-pub use module::MirModule;
+pub use module::{ColdAbort, MirModule};
+pub use passes::abort_outlining::outline_aborts;
 pub use passes::arithmetic_simplify::ArithmeticSimplify;
+pub use passes::array_dematerialization::ArrayDematerialization;
+pub use passes::block_layout::BlockLayout;
 pub use passes::constant_folding::ConstantFolding;
+pub use passes::constant_pooling::ConstantPooling;
 pub use passes::constant_propagation::ConstantPropagation;
 pub use passes::copy_propagation::CopyPropagation;
 pub use passes::dead_code_elimination::DeadCodeElimination;
 pub use passes::fuse_cmp::FuseCmpBranch;
+pub use passes::jump_threading::JumpThreading;
+pub use passes::load_store_forwarding::LoadStoreForwarding;
 pub use passes::local_cse::LocalCSE;
+pub use passes::range_check_hoisting::RangeCheckHoisting;
 pub use passes::simplify_branches::SimplifyBranches;
 pub use passes::sroa::ScalarReplacementOfAggregates;
-pub use passes::{MirPass, PassManager};
+pub use passes::statistics::{PassStatistics, PassTotals};
+pub use passes::{MirPass, PassKind, PassManager};
 pub use pipeline::{OptimizationLevel, PipelineConfig};
 pub use terminator::Terminator;
 pub use value::{Literal, Place, Projection, Value};
@@ -84,9 +92,10 @@ pub mod pipeline;
 pub mod terminator;
 pub mod value;
 pub mod value_visitor;
+pub mod visualization;
 
 pub use db::MirDb;
-pub use lowering::{generate_mir, generate_mir_with_config};
+pub use lowering::{generate_mir, generate_mir_with_config, generate_mir_with_passes};
 
 #[cfg(test)]
 pub mod testing;
@@ -123,6 +132,11 @@ index_vec::define_index_type! {
     pub struct ValueId = usize;
 }
 
+index_vec::define_index_type! {
+    /// Unique identifier for an abort of the cold region of a module
+    pub struct ColdAbortId = usize;
+}
+
 // --- Pretty Printing Support ---
 
 /// Trait for pretty-printing MIR constructs
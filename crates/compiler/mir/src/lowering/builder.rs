@@ -6,20 +6,22 @@
 
 use std::cell::RefCell;
 
-use cairo_m_compiler_parser::parser::{Expression, Spanned};
+use cairo_m_compiler_parser::parser::{BinaryOp, Expression, Spanned};
 use cairo_m_compiler_semantic::db::Crate;
 use cairo_m_compiler_semantic::definition::{Definition, DefinitionKind};
-use cairo_m_compiler_semantic::semantic_index::{DefinitionId, ExpressionId, SemanticIndex};
+use cairo_m_compiler_semantic::semantic_index::{
+    DefinitionId, ExpressionId, SemanticIndex, resolve_imported_item,
+};
 use cairo_m_compiler_semantic::type_resolution::{
-    definition_semantic_type, expression_semantic_type, resolve_ast_type,
+    definition_semantic_type, expression_semantic_type, operator_method, resolve_ast_type,
 };
-use cairo_m_compiler_semantic::types::TypeData;
-use cairo_m_compiler_semantic::{File, SemanticDb, module_semantic_index};
+use cairo_m_compiler_semantic::types::{TypeData, TypeId};
+use cairo_m_compiler_semantic::{File, SemanticDb};
 use rustc_hash::FxHashMap;
 
 use crate::{
     BasicBlockId, CfgBuilder, FunctionId, InstrBuilder, Instruction, MirDefinitionId, MirFunction,
-    MirType, Value, ValueId,
+    MirType, Terminator, Value, ValueId,
 };
 // Removed SSABuilder import - SSA is now integrated directly into MirFunction
 
@@ -190,42 +192,26 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
 
     /// Resolves an imported function to its FunctionId in the crate
     ///
-    /// Follows the import chain: module_name.function_name -> FunctionId
+    /// Follows the import chain: module_name.function_name -> FunctionId, through the
+    /// re-exports (`pub use`) of the imported module.
     pub(crate) fn resolve_imported_function(
         &self,
         imported_module_name: &str,
         function_name: &str,
     ) -> Option<FunctionId> {
-        // Get the crate's semantic index
-        let imported_index = module_semantic_index(
+        // Resolve the actual function definition, wherever it is re-exported from
+        let (imported_def_idx, imported_def, imported_file) = resolve_imported_item(
             self.ctx.db,
             self.ctx.crate_id,
-            imported_module_name.to_string(),
-        )
-        .ok()?;
-
-        // Get imported module's root scope
-        let imported_root = imported_index.root_scope()?;
-
-        // Resolve the actual function definition in the imported module using DefinitionIndex helper
-        let imported_def_idx =
-            imported_index.latest_definition_index_by_name(imported_root, function_name)?;
-        let imported_def = imported_index
-            .definition(imported_def_idx)
-            .expect("Definition should exist for imported function");
+            imported_module_name,
+            function_name,
+        )?;
 
         // Verify it's actually a function
         if !matches!(imported_def.kind, DefinitionKind::Function(_)) {
             return None;
         }
 
-        // Get the imported file
-        let imported_file = *self
-            .ctx
-            .crate_id
-            .modules(self.ctx.db)
-            .get(imported_module_name)?;
-
         // Create the correct DefinitionId for the imported function
         let func_def_id = DefinitionId::new(self.ctx.db, imported_file, imported_def_idx);
 
@@ -277,12 +263,48 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         self.state.is_terminated = state.is_terminated;
     }
 
+    /// Terminates the current block with a multi-way branch
+    pub(crate) fn terminate_with_switch(
+        &mut self,
+        op: crate::BinaryOp,
+        value: Value,
+        cases: Vec<(Value, BasicBlockId)>,
+        default: BasicBlockId,
+    ) {
+        let state = self
+            .cfg()
+            .terminate(Terminator::switch(op, value, cases, default));
+        self.state.is_terminated = state.is_terminated;
+    }
+
     /// Terminates the current block with a return
     pub(crate) fn terminate_with_return(&mut self, values: Vec<Value>) {
         let state = self.cfg().terminate_with_return(values);
         self.state.is_terminated = state.is_terminated;
     }
 
+    /// Terminates the current block with an abort, recording the source location of `span`
+    pub(crate) fn terminate_with_abort(
+        &mut self,
+        message: String,
+        span: chumsky::prelude::SimpleSpan,
+    ) {
+        let location = self.source_location(span);
+        let state = self
+            .cfg()
+            .terminate(Terminator::abort(message, Some(location)));
+        self.state.is_terminated = state.is_terminated;
+    }
+
+    /// Formats the start of `span` as `file:line:column`, both 1-based
+    pub(crate) fn source_location(&self, span: chumsky::prelude::SimpleSpan) -> String {
+        let text = self.ctx.file.text(self.ctx.db);
+        let prefix = &text[..span.start.min(text.len())];
+        let line = prefix.matches('\n').count() + 1;
+        let column = prefix.len() - prefix.rfind('\n').map_or(0, |i| i + 1) + 1;
+        format!("{}:{line}:{column}", self.ctx.file.file_path(self.ctx.db))
+    }
+
     /// Creates blocks for a loop
     pub(crate) fn create_loop_blocks(&mut self) -> (BasicBlockId, BasicBlockId, BasicBlockId) {
         self.cfg().create_loop_blocks()
@@ -447,9 +469,10 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                         }
                         DefinitionKind::Use(use_ref) => {
                             // Imported function
+                            // Looked up by its name in the imported module, not its alias
                             self.resolve_imported_function(
                                 use_ref.imported_module.value(),
-                                func_name.value(),
+                                use_ref.item.value(),
                             )
                             .ok_or_else(|| {
                                 format!(
@@ -465,6 +488,10 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                 }
             }
             Expression::MemberAccess { object, field } => {
+                // Handle object.method pattern
+                if let Some(func_id) = self.resolve_method(callee) {
+                    return Ok(func_id);
+                }
                 // Handle module.function pattern
                 if let Expression::Identifier(module_name) = object.value() {
                     // This could be an imported module function
@@ -484,6 +511,48 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         }
     }
 
+    /// Resolves a method callee (`object.method`) to the FunctionId of the method
+    ///
+    /// Returns `None` if the callee is not a method of a struct.
+    pub(crate) fn resolve_method(&self, callee: &Spanned<Expression>) -> Option<FunctionId> {
+        if !matches!(callee.value(), Expression::MemberAccess { .. }) {
+            return None;
+        }
+        let callee_expr_id = self
+            .ctx
+            .semantic_index
+            .expression_id_by_span(callee.span())?;
+        let callee_type = expression_semantic_type(
+            self.ctx.db,
+            self.ctx.crate_id,
+            self.ctx.file,
+            callee_expr_id,
+            None,
+        );
+        let TypeData::Function(signature_id) = callee_type.data(self.ctx.db) else {
+            return None;
+        };
+        self.ctx
+            .function_mapping
+            .get(&signature_id.definition_id(self.ctx.db))
+            .map(|(_, func_id)| *func_id)
+    }
+
+    /// Resolves the struct method overloading `op` for a left operand of type `left_type`
+    ///
+    /// Returns `None` if the operator is not overloaded, and is lowered as a binary operation.
+    pub(crate) fn resolve_operator_method(
+        &self,
+        op: BinaryOp,
+        left_type: TypeId<'db>,
+    ) -> Option<FunctionId> {
+        let signature_id = operator_method(self.ctx.db, self.ctx.crate_id, &op, left_type)?;
+        self.ctx
+            .function_mapping
+            .get(&signature_id.definition_id(self.ctx.db))
+            .map(|(_, func_id)| *func_id)
+    }
+
     // ================================================================================
     // Value-Based Aggregate Operations
     // ================================================================================
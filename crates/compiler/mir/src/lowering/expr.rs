@@ -3,17 +3,20 @@
 //! This module contains the trait and implementations for lowering expressions
 //! from the AST to MIR values.
 
-use cairo_m_compiler_parser::parser::{BinaryOp, Expression, Spanned, UnaryOp};
+use cairo_m_compiler_parser::const_eval::eval_const_condition;
+use cairo_m_compiler_parser::parser::{BinaryOp, Expression, Spanned, Statement, UnaryOp};
 use cairo_m_compiler_semantic::builtins::{BuiltinFn, is_builtin_function_name};
+use cairo_m_compiler_semantic::const_eval::{ConstValue, const_value};
 use cairo_m_compiler_semantic::definition::DefinitionKind;
 use cairo_m_compiler_semantic::place::FileScopeId;
 use cairo_m_compiler_semantic::semantic_index::{DefinitionId, ExpressionId};
 use cairo_m_compiler_semantic::type_resolution::expression_semantic_type;
-use cairo_m_compiler_semantic::types::TypeData;
+use cairo_m_compiler_semantic::types::{TypeData, TypeId};
 
 use super::builder::{CallResult, MirBuilder};
+use super::stmt::LowerStmt;
 use crate::instruction::CalleeSignature;
-use crate::{Instruction, MirType, Place, Value};
+use crate::{FunctionId, Instruction, MirType, Place, Terminator, Value};
 
 /// Trait for lowering expressions to MIR values
 pub trait LowerExpr<'a> {
@@ -78,6 +81,12 @@ impl<'a, 'db> LowerExpr<'a> for MirBuilder<'a, 'db> {
         match &expr_info.ast_node {
             Expression::Literal(n, _) => Ok(LoweredExpr::new(Value::integer(*n as u32))),
             Expression::BooleanLiteral(b) => Ok(LoweredExpr::new(Value::boolean(*b))),
+            Expression::StringLiteral(_) => {
+                Err(
+                    "string literals are only allowed as assert and panic messages and include_bytes paths"
+                        .to_string(),
+                )
+            }
             Expression::New { elem_type, count } => {
                 // Compute cells = count * elem_slots, where elem_slots depends on T
                 let sem_elem_type = cairo_m_compiler_semantic::type_resolution::resolve_ast_type(
@@ -155,19 +164,105 @@ impl<'a, 'db> LowerExpr<'a> for MirBuilder<'a, 'db> {
             Expression::Tuple(elements) => self.lower_tuple_literal(elements, expr_id),
             Expression::TupleIndex { tuple, index } => self.lower_tuple_index(tuple, *index),
             Expression::ArrayLiteral(elements) => self.lower_array_literal(elements, expr_id),
-            Expression::ArrayRepeat { element, count } => {
-                self.lower_array_repeat(element, *count.value() as usize, expr_id)
+            Expression::ArrayRepeat { element, count: _ } => {
+                self.lower_array_repeat(element, expr_id)
             }
             Expression::Cast {
                 expr,
                 target_type: _,
             } => self.lower_cast(expr, expr_id),
+            Expression::Block { statements, value } => {
+                self.lower_block_expression(statements, value)
+            }
+            Expression::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.lower_if_expression(condition, then_branch, else_branch, expr_id),
         }
     }
 }
 
 // Individual expression lowering methods
 impl<'a, 'db> MirBuilder<'a, 'db> {
+    /// Lowers the statements of a block expression, then its final expression.
+    fn lower_block_expression(
+        &mut self,
+        statements: &[Spanned<Statement>],
+        value: &Spanned<Expression>,
+    ) -> Result<LoweredExpr, String> {
+        for stmt in statements {
+            self.lower_statement(stmt)?;
+
+            // A statement like `return` or `break` leaves the value unreachable
+            if self.is_current_block_terminated() {
+                return Ok(LoweredExpr::new(Value::error()));
+            }
+        }
+        self.lower_expression(value)
+    }
+
+    /// Lowers an `if` expression. The value of each branch reaching the merge block is an
+    /// operand of a phi, which is the value of the expression.
+    fn lower_if_expression(
+        &mut self,
+        condition: &Spanned<Expression>,
+        then_branch: &Spanned<Expression>,
+        else_branch: &Spanned<Expression>,
+        expr_id: ExpressionId,
+    ) -> Result<LoweredExpr, String> {
+        // A constant condition selects a single branch: the dead one is not lowered.
+        if let Some(value) = eval_const_condition(condition.value()) {
+            return self.lower_expression(if value { then_branch } else { else_branch });
+        }
+
+        let condition_value = self.lower_expression(condition)?.into_value();
+
+        let then_block_id = self.create_block();
+        let else_block_id = self.create_block();
+        self.terminate_with_branch(condition_value, then_block_id, else_block_id);
+        self.seal_block(then_block_id);
+        self.seal_block(else_block_id);
+
+        // Value of each branch that does not terminate, with the block it ends in
+        let mut sources = Vec::new();
+        for (block_id, branch) in [(then_block_id, then_branch), (else_block_id, else_branch)] {
+            self.switch_to_block(block_id);
+            let value = self.lower_expression(branch)?.into_value();
+            self.mark_block_filled(block_id);
+
+            if !self.is_current_block_terminated() {
+                sources.push((self.state.current_block_id, value));
+            }
+        }
+
+        if sources.is_empty() {
+            // Both branches ended in a terminator.
+            self.state.is_terminated = true;
+            return Ok(LoweredExpr::new(Value::error()));
+        }
+
+        let merge_block_id = self.create_block();
+        for (block_id, _) in &sources {
+            let mut cfg = self.cfg();
+            cfg.set_block_terminator(*block_id, Terminator::jump(merge_block_id));
+        }
+        self.seal_block(merge_block_id);
+        self.switch_to_block(merge_block_id);
+
+        if let [(_, value)] = sources.as_slice() {
+            return Ok(LoweredExpr::new(*value));
+        }
+
+        let ty = self.ctx.get_expr_type(expr_id);
+        let dest = self.state.mir_function.new_typed_value_id(ty.clone());
+        self.state.mir_function.mark_as_defined(dest)?;
+        if let Some(block) = self.state.mir_function.get_basic_block_mut(merge_block_id) {
+            block.push_phi_front(Instruction::phi(dest, ty, sources));
+        }
+        Ok(LoweredExpr::new(Value::operand(dest)))
+    }
+
     /// Resolves an identifier by looking up its definition in the semantic index.
     ///
     /// With the value-first approach, most variables are bound to values directly.
@@ -188,7 +283,15 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
 
             // Check if this is a constant definition
             if let DefinitionKind::Const(const_ref) = &def.kind {
-                // Constants need to be evaluated to their values
+                // Constants folded during semantic analysis are used as literals
+                if let Some(value) = const_value(self.ctx.db, self.ctx.crate_id, def_id) {
+                    let value = match value {
+                        ConstValue::Felt(value) | ConstValue::U32(value) => Value::integer(value),
+                        ConstValue::Bool(value) => Value::boolean(value),
+                    };
+                    return Ok(LoweredExpr::new(value));
+                }
+                // Other constants need to be evaluated to their values
                 if let Some(value_expr_id) = const_ref.value_expr_id {
                     // Get the constant's value expression from the semantic index
                     if let Some(expr_info) = self.ctx.semantic_index.expression(value_expr_id) {
@@ -253,12 +356,6 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         let lhs_value = self.lower_expression(left)?.into_value();
         let rhs_value = self.lower_expression(right)?.into_value();
 
-        // Query semantic type system for result type based on this expression
-        let result_type = self.ctx.get_expr_type(expr_id);
-        let dest = self.state.mir_function.new_typed_value_id(result_type);
-
-        // Register binary op result as a Value
-
         // Get the type of the left operand to determine the correct binary operation
         let left_expr_id = self.expr_id(left.span())?;
 
@@ -269,9 +366,38 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             left_expr_id,
             None,
         );
+
+        // Operators overloaded by a struct are calls to its method
+        if let Some(func_id) = self.resolve_operator_method(op, left_type) {
+            let return_type = expression_semantic_type(
+                self.ctx.db,
+                self.ctx.crate_id,
+                self.ctx.file,
+                expr_id,
+                None,
+            );
+            let value =
+                self.lower_operator_call(func_id, lhs_value, rhs_value, return_type, left.span())?;
+            return Ok(LoweredExpr::new(value));
+        }
+
+        // Query semantic type system for result type based on this expression
+        let result_type = self.ctx.get_expr_type(expr_id);
+        let dest = self.state.mir_function.new_typed_value_id(result_type);
+
         let left_type_data = left_type.data(self.ctx.db);
 
         let typed_op = crate::BinaryOp::from_parser(op, &left_type_data)?;
+        if matches!(
+            typed_op,
+            crate::BinaryOp::Div | crate::BinaryOp::U32Div | crate::BinaryOp::U32Rem
+        ) {
+            let location = self.source_location(left.span());
+            self.state
+                .mir_function
+                .division_locations
+                .insert(dest, location);
+        }
         self.instr()
             .binary_op_to(typed_op, dest, lhs_value, rhs_value);
         Ok(LoweredExpr::new(Value::operand(dest)))
@@ -283,21 +409,41 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         args: &[Spanned<Expression>],
         expr_id: ExpressionId,
     ) -> Result<LoweredExpr, String> {
-        // Handle built-in assert(...) in expression position as well.
+        // Handle built-in assert(...), panic(...) and debug_print(...) in expression position as well.
         // Emit the same MIR as in statement position, then return unit.
-        if let Expression::Identifier(name) = callee.value() {
-            if is_builtin_function_name(name.value()) == Some(BuiltinFn::Assert) {
-                // Retrieve the call span fom the current expression id
-                let call_span = self
-                    .ctx
-                    .semantic_index
-                    .expression(expr_id)
-                    .map(|info| info.ast_span)
-                    .ok_or_else(|| format!("MIR: No ExpressionInfo for call ID {expr_id:?}"))?;
-
-                self.lower_assert_call(args, call_span)?;
-                return Ok(LoweredExpr::new(Value::unit()));
+        // The u32 intrinsics lower to the existing u32 operations and return their result.
+        // include_bytes(...) lowers to a const felt array of the included bytes.
+        if let Expression::Identifier(name) = callee.value()
+            && let Some(builtin) = is_builtin_function_name(name.value())
+        {
+            // Retrieve the call span fom the current expression id
+            let call_span = self
+                .ctx
+                .semantic_index
+                .expression(expr_id)
+                .map(|info| info.ast_span)
+                .ok_or_else(|| format!("MIR: No ExpressionInfo for call ID {expr_id:?}"))?;
+
+            match builtin {
+                BuiltinFn::Assert => self.lower_assert_call(args, call_span)?,
+                BuiltinFn::Panic => self.lower_panic_call(args, call_span)?,
+                BuiltinFn::DebugPrint => self.lower_debug_print_call(args, call_span)?,
+                BuiltinFn::U32OverflowingAdd
+                | BuiltinFn::U32CheckedSub
+                | BuiltinFn::U32WrappingMul => {
+                    let value = self.lower_u32_intrinsic_call(builtin, args, expr_id)?;
+                    return Ok(LoweredExpr::new(value));
+                }
+                BuiltinFn::IncludeBytes => {
+                    let value = self.lower_include_bytes_call(args)?;
+                    return Ok(LoweredExpr::new(value));
+                }
+                BuiltinFn::Env => {
+                    let value = self.lower_env_call(args)?;
+                    return Ok(LoweredExpr::new(value));
+                }
             }
+            return Ok(LoweredExpr::new(Value::unit()));
         }
 
         match self.lower_function_call(callee, args, expr_id)? {
@@ -314,6 +460,105 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         }
     }
 
+    /// Lower `include_bytes(path)` to a felt array of the bytes of the included file
+    ///
+    /// The call is only valid as the value of a const, so it is lowered under const context and
+    /// the array is emitted as a read-only data blob.
+    fn lower_include_bytes_call(&mut self, args: &[Spanned<Expression>]) -> Result<Value, String> {
+        let [path] = args else {
+            return Err("include_bytes expects exactly one argument".to_string());
+        };
+        let Expression::StringLiteral(path) = path.value() else {
+            return Err("include_bytes path must be a string literal".to_string());
+        };
+        let bytes = cairo_m_compiler_semantic::builtins::read_included_bytes(
+            self.ctx.db,
+            self.ctx.file,
+            path,
+        )
+        .map_err(|e| format!("Failed to include `{path}`: {e}"))?;
+
+        let elements = bytes
+            .into_iter()
+            .map(|byte| Value::integer(u32::from(byte)))
+            .collect();
+        let array_dest = self.make_fixed_array(elements, MirType::Felt);
+        Ok(Value::operand(array_dest))
+    }
+
+    /// Lower `env(index)` to a read of the environment block supplied at run time
+    ///
+    /// The index is checked to be an integer literal by semantic validation, so that the size
+    /// of the environment block the program needs is known at compile time.
+    fn lower_env_call(&mut self, args: &[Spanned<Expression>]) -> Result<Value, String> {
+        let [index] = args else {
+            return Err("env expects exactly one argument".to_string());
+        };
+        let Expression::Literal(index, _) = index.value() else {
+            return Err("env index must be an integer literal".to_string());
+        };
+        let index =
+            u32::try_from(*index).map_err(|_| format!("env index {index} is out of range"))?;
+
+        let dest = self.state.mir_function.new_typed_value_id(MirType::Felt);
+        self.instr()
+            .add_instruction(Instruction::load_env(dest, index));
+        Ok(Value::operand(dest))
+    }
+
+    /// Lower a call to a u32 intrinsic onto the existing u32 operations:
+    /// - `u32_overflowing_add(a, b)`: `(a + b, a + b < a)`
+    /// - `u32_checked_sub(a, b)`: `(a - b, a < b)`
+    /// - `u32_wrapping_mul(a, b)`: `a * b`
+    ///
+    /// u32 arithmetic wraps, so the carry or borrow is recovered with a comparison.
+    fn lower_u32_intrinsic_call(
+        &mut self,
+        builtin: BuiltinFn,
+        args: &[Spanned<Expression>],
+        expr_id: ExpressionId,
+    ) -> Result<Value, String> {
+        let [lhs, rhs] = args else {
+            return Err(format!("{builtin:?} expects exactly two arguments"));
+        };
+        let lhs = self.lower_expression(lhs)?.into_value();
+        let rhs = self.lower_expression(rhs)?.into_value();
+
+        let (result, flag) = match builtin {
+            BuiltinFn::U32OverflowingAdd => {
+                let sum = self.emit_binary_op(crate::BinaryOp::U32Add, lhs, rhs, MirType::U32);
+                let carry = self.emit_binary_op(crate::BinaryOp::U32Less, sum, lhs, MirType::Bool);
+                (sum, carry)
+            }
+            BuiltinFn::U32CheckedSub => {
+                let diff = self.emit_binary_op(crate::BinaryOp::U32Sub, lhs, rhs, MirType::U32);
+                let borrow = self.emit_binary_op(crate::BinaryOp::U32Less, lhs, rhs, MirType::Bool);
+                (diff, borrow)
+            }
+            BuiltinFn::U32WrappingMul => {
+                return Ok(self.emit_binary_op(crate::BinaryOp::U32Mul, lhs, rhs, MirType::U32));
+            }
+            _ => return Err(format!("{builtin:?} is not a u32 intrinsic")),
+        };
+
+        let tuple_type = self.ctx.get_expr_type(expr_id);
+        let tuple_value = self.make_tuple(vec![result, flag], tuple_type);
+        Ok(Value::operand(tuple_value))
+    }
+
+    /// Emit a binary operation into a fresh value of type `result_type`.
+    fn emit_binary_op(
+        &mut self,
+        op: crate::BinaryOp,
+        left: Value,
+        right: Value,
+        result_type: MirType,
+    ) -> Value {
+        let dest = self.state.mir_function.new_typed_value_id(result_type);
+        self.instr().binary_op_to(op, dest, left, right);
+        Value::operand(dest)
+    }
+
     fn lower_member_access(
         &mut self,
         object: &Spanned<Expression>,
@@ -362,18 +607,28 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         let func_id = self.resolve_callee_expression(callee)?;
 
         // Lower the arguments
-        let mut arg_values = Vec::new();
-        for arg in args {
-            arg_values.push(self.lower_expression(arg)?.into_value());
-        }
-
-        // Get the callee's signature by looking up the function definition
-        let (param_types, return_types) = self.get_function_signature(func_id)?;
+        let arg_values = self.lower_call_args(callee, args)?;
 
         // Get the return type of the function
         let semantic_type =
             expression_semantic_type(self.ctx.db, self.ctx.crate_id, self.ctx.file, expr_id, None);
 
+        self.emit_call(func_id, arg_values, semantic_type, callee.span())
+    }
+
+    /// Emits a call to `func_id` returning a value of type `semantic_type`, located at the
+    /// start of `span`
+    fn emit_call(
+        &mut self,
+        func_id: FunctionId,
+        arg_values: Vec<Value>,
+        semantic_type: TypeId,
+        span: chumsky::prelude::SimpleSpan,
+    ) -> Result<CallResult, String> {
+        // Get the callee's signature by looking up the function definition
+        let (param_types, return_types) = self.get_function_signature(func_id)?;
+        let location = self.source_location(span);
+
         // Check if the return type is a tuple
         match semantic_type.data(self.ctx.db) {
             TypeData::Tuple(element_types) => {
@@ -393,7 +648,8 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                 };
 
                 // Create the call instruction with the signature
-                let call_instr = Instruction::call(dests.clone(), func_id, arg_values, signature);
+                let call_instr = Instruction::call(dests.clone(), func_id, arg_values, signature)
+                    .with_location(location);
                 self.instr().add_instruction(call_instr);
 
                 // Return the tuple values directly
@@ -414,7 +670,8 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                 };
 
                 // Create the call instruction with the signature
-                let call_instr = Instruction::call(vec![dest], func_id, arg_values, signature);
+                let call_instr = Instruction::call(vec![dest], func_id, arg_values, signature)
+                    .with_location(location);
                 self.instr().add_instruction(call_instr);
 
                 Ok(CallResult::Single(Value::operand(dest)))
@@ -422,6 +679,43 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         }
     }
 
+    /// Lowers `lhs op rhs` for an operator overloaded by a struct to a call of its method,
+    /// returning a value of type `return_type`
+    pub(super) fn lower_operator_call(
+        &mut self,
+        func_id: FunctionId,
+        lhs: Value,
+        rhs: Value,
+        return_type: TypeId,
+        span: chumsky::prelude::SimpleSpan,
+    ) -> Result<Value, String> {
+        match self.emit_call(func_id, vec![lhs, rhs], return_type, span)? {
+            CallResult::Single(value) => Ok(value),
+            CallResult::Tuple(values) => {
+                let tuple_type = MirType::from_semantic_type(self.ctx.db, return_type);
+                Ok(Value::operand(self.make_tuple(values, tuple_type)))
+            }
+        }
+    }
+
+    /// Lowers the arguments of a call, method calls passing their receiver first
+    pub(super) fn lower_call_args(
+        &mut self,
+        callee: &Spanned<Expression>,
+        args: &[Spanned<Expression>],
+    ) -> Result<Vec<Value>, String> {
+        let mut arg_values = Vec::with_capacity(args.len() + 1);
+        if let Expression::MemberAccess { object, .. } = callee.value()
+            && self.resolve_method(callee).is_some()
+        {
+            arg_values.push(self.lower_expression(object)?.into_value());
+        }
+        for arg in args {
+            arg_values.push(self.lower_expression(arg)?.into_value());
+        }
+        Ok(arg_values)
+    }
+
     fn lower_struct_literal(
         &mut self,
         fields: &[(Spanned<String>, Spanned<Expression>)],
@@ -541,16 +835,16 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
     fn lower_array_repeat(
         &mut self,
         element: &Spanned<Expression>,
-        count: usize,
         expr_id: ExpressionId,
     ) -> Result<LoweredExpr, String> {
         // Lower the element expression once
         let elem_value = self.lower_expression(element)?.into_value();
 
-        // Query semantic array type to obtain element MIR type
+        // Query semantic array type to obtain element MIR type and the count, which may be
+        // given by a constant
         let array_type = self.ctx.get_expr_type(expr_id);
-        let element_mir_type = match &array_type {
-            MirType::FixedArray { element_type, .. } => (**element_type).clone(),
+        let (element_mir_type, count) = match &array_type {
+            MirType::FixedArray { element_type, size } => ((**element_type).clone(), *size),
             _ => return Err("ArrayRepeat does not have array type".to_string()),
         };
 
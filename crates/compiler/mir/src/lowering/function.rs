@@ -9,19 +9,21 @@ use std::sync::Arc;
 
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSeverity};
 use cairo_m_compiler_parser::parse_file;
-use cairo_m_compiler_parser::parser::{FunctionDef, Parameter, Spanned, Statement, TopLevelItem};
+use cairo_m_compiler_parser::parser::{Expression, FunctionDef, Parameter, Spanned, Statement};
 use cairo_m_compiler_semantic::FileScopeId;
 use cairo_m_compiler_semantic::db::Crate;
 use cairo_m_compiler_semantic::definition::{Definition, DefinitionKind};
 use cairo_m_compiler_semantic::semantic_index::DefinitionId;
 use cairo_m_compiler_semantic::type_resolution::definition_semantic_type;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use super::builder::MirBuilder;
 use super::stmt::LowerStmt;
 use crate::db::MirDb;
-use crate::pipeline::{PipelineConfig, optimize_module};
-use crate::{MirFunction, MirModule, MirType, PrettyPrint, Value, ValueId};
+use crate::pipeline::{PipelineConfig, optimize_module_with_passes};
+use crate::{
+    FunctionId, Literal, MirFunction, MirModule, MirPass, MirType, PrettyPrint, Value, ValueId,
+};
 
 /// The main entry point for MIR generation.
 ///
@@ -49,6 +51,19 @@ pub fn generate_mir_with_config(
     db: &dyn MirDb,
     crate_id: Crate,
     pipeline_config: PipelineConfig,
+) -> Result<Arc<MirModule>, Vec<Diagnostic>> {
+    generate_mir_with_passes(db, crate_id, pipeline_config, &mut [])
+}
+
+/// Entry point for MIR generation with custom passes.
+///
+/// This mirrors `generate_mir_with_config`, and also runs `extra_passes` on each function
+/// (see [`optimize_module_with_passes`]).
+pub fn generate_mir_with_passes(
+    db: &dyn MirDb,
+    crate_id: Crate,
+    pipeline_config: PipelineConfig,
+    extra_passes: &mut [Box<dyn MirPass>],
 ) -> Result<Arc<MirModule>, Vec<Diagnostic>> {
     // Get semantic index for the entire crate
     let crate_semantic_index =
@@ -96,7 +111,7 @@ pub fn generate_mir_with_config(
         for (def_idx, def) in semantic_index.all_definitions() {
             if let DefinitionKind::Function(_) = &def.kind {
                 // Find the corresponding AST node
-                if let Some(_func_ast) = find_function_ast(&parsed_module.items, &def.name) {
+                if let Some(_func_ast) = parsed_module.function(&def.name) {
                     let def_id = DefinitionId::new(db, file, def_idx);
 
                     // Create a placeholder function that will be filled in during lowering
@@ -115,77 +130,113 @@ pub fn generate_mir_with_config(
         }
     }
 
-    // Second pass: Now lower all function bodies with the complete function mapping
-    for (module_name, semantic_index) in crate_semantic_index.modules() {
-        let file = *modules_map
-            .get(module_name)
-            .expect("Module file should exist");
-
-        let position = crate_id
-            .modules(db)
-            .iter()
-            .position(|(name, _)| name == module_name)
-            .expect("Module should exist in crate");
-
-        let mut hasher = DefaultHasher::new();
-        crate_id.name(db).hash(&mut hasher);
-        position.hash(&mut hasher);
-        let file_id: u64 = hasher.finish();
-
-        // Get the parsed module for this file
-        let (_, parsed_module) = parsed_modules
-            .get(module_name)
-            .expect("Module should have been parsed");
-
-        // Process all functions in the module
-        for (def_idx, def) in semantic_index.all_definitions() {
-            if let DefinitionKind::Function(_) = &def.kind {
-                let func_def_id = DefinitionId::new(db, file, def_idx);
-
-                // Find the corresponding AST node
-                if let Some(func_ast) = find_function_ast(&parsed_module.items, &def.name) {
-                    // Get the assigned FunctionId from the mapping
-                    let func_id = function_mapping
-                        .get(&func_def_id)
-                        .map(|(_, id)| *id)
-                        .expect("Function should have been registered");
-
-                    // Create a builder for this function
-                    let builder = MirBuilder::new(
-                        db,
-                        file,
-                        semantic_index,
-                        &function_mapping,
-                        file_id,
-                        crate_id,
-                    );
-
-                    // Lower the function
-                    match lower_function(builder, func_def_id, def, func_ast) {
-                        Ok(mir_function) => {
-                            // Use direct indexing to replace the placeholder function
-                            mir_module.functions[func_id] = mir_function;
+    // Functions to lower: every function, or the transitive closure of the selected
+    // entrypoints. Names that are not functions of the crate are ignored.
+    let mut selected: Option<FxHashSet<FunctionId>> =
+        pipeline_config.entrypoints.as_ref().map(|names| {
+            names
+                .iter()
+                .filter_map(|name| mir_module.lookup_function(name))
+                .collect()
+        });
+    let mut lowered = FxHashSet::default();
+
+    // Second pass: Now lower the function bodies with the complete function mapping. When
+    // entrypoints are selected, the modules are scanned until every callee has been lowered.
+    loop {
+        for (module_name, semantic_index) in crate_semantic_index.modules() {
+            let file = *modules_map
+                .get(module_name)
+                .expect("Module file should exist");
+
+            let position = crate_id
+                .modules(db)
+                .iter()
+                .position(|(name, _)| name == module_name)
+                .expect("Module should exist in crate");
+
+            let mut hasher = DefaultHasher::new();
+            crate_id.name(db).hash(&mut hasher);
+            position.hash(&mut hasher);
+            let file_id: u64 = hasher.finish();
+
+            // Get the parsed module for this file
+            let (_, parsed_module) = parsed_modules
+                .get(module_name)
+                .expect("Module should have been parsed");
+
+            // Process all functions in the module
+            for (def_idx, def) in semantic_index.all_definitions() {
+                if let DefinitionKind::Function(_) = &def.kind {
+                    let func_def_id = DefinitionId::new(db, file, def_idx);
+
+                    // Find the corresponding AST node
+                    if let Some(func_ast) = parsed_module.function(&def.name) {
+                        // Get the assigned FunctionId from the mapping
+                        let func_id = function_mapping
+                            .get(&func_def_id)
+                            .map(|(_, id)| *id)
+                            .expect("Function should have been registered");
+                        if lowered.contains(&func_id)
+                            || selected
+                                .as_ref()
+                                .is_some_and(|selected| !selected.contains(&func_id))
+                        {
+                            continue;
                         }
-                        Err(e) => {
-                            // Collect the error instead of just logging
-                            lowering_errors.push(Diagnostic {
-                                code: DiagnosticCode::InternalError,
-                                file_path: file.file_path(db).to_string(),
-                                related_spans: vec![],
-                                severity: DiagnosticSeverity::Error,
-                                message: format!("Failed to lower function '{}': {}", def.name, e),
-                                span: func_ast.value().name.span(),
-                            });
+                        lowered.insert(func_id);
+
+                        // Create a builder for this function
+                        let builder = MirBuilder::new(
+                            db,
+                            file,
+                            semantic_index,
+                            &function_mapping,
+                            file_id,
+                            crate_id,
+                        );
+
+                        // Lower the function
+                        match lower_function(builder, func_def_id, def, func_ast) {
+                            Ok(mir_function) => {
+                                if let Some(selected) = &mut selected {
+                                    selected.extend(mir_function.callees());
+                                }
+                                // Use direct indexing to replace the placeholder function
+                                mir_module.functions[func_id] = mir_function;
+                            }
+                            Err(e) => {
+                                // Collect the error instead of just logging
+                                lowering_errors.push(Diagnostic {
+                                    code: DiagnosticCode::InternalError,
+                                    file_path: file.file_path(db).to_string(),
+                                    related_spans: vec![],
+                                    module_path: None,
+                                    severity: DiagnosticSeverity::Error,
+                                    message: format!(
+                                        "Failed to lower function '{}': {}",
+                                        def.name, e
+                                    ),
+                                    span: func_ast.value().name.span(),
+                                });
+                            }
                         }
+                    } else {
+                        log::warn!(
+                            "Function '{}' found in semantic index but not in AST",
+                            def.name
+                        );
                     }
-                } else {
-                    log::warn!(
-                        "Function '{}' found in semantic index but not in AST",
-                        def.name
-                    );
                 }
             }
         }
+
+        if selected
+            .as_ref()
+            .is_none_or(|selected| selected.is_subset(&lowered))
+        {
+            break;
+        }
     }
 
     // Check if we have any lowering errors
@@ -193,8 +244,13 @@ pub fn generate_mir_with_config(
         return Err(lowering_errors);
     }
 
+    // Only emit the selected functions
+    if selected.is_some() {
+        mir_module.retain_functions(&lowered);
+    }
+
     // Run optimization pipeline on the entire module
-    optimize_module(&mut mir_module, &pipeline_config);
+    optimize_module_with_passes(&mut mir_module, &pipeline_config, extra_passes);
 
     if std::env::var("DEBUG_MIR").is_ok() {
         println!("{}", mir_module.pretty_print(0));
@@ -203,20 +259,6 @@ pub fn generate_mir_with_config(
     Ok(Arc::new(mir_module))
 }
 
-fn find_function_ast<'a>(
-    items: &'a [TopLevelItem],
-    func_name: &str,
-) -> Option<&'a Spanned<FunctionDef>> {
-    for item in items {
-        if let TopLevelItem::Function(func) = item
-            && func.value().name.value() == func_name
-        {
-            return Some(func);
-        }
-    }
-    None
-}
-
 /// Lowers a single function from the AST into a `MirFunction`
 pub(super) fn lower_function<'a, 'db>(
     mut builder: MirBuilder<'a, 'db>,
@@ -301,6 +343,25 @@ fn lower_parameter<'a, 'db>(
         .parameters
         .push(incoming_param_val);
 
+    // Defaults are validated as literals of the parameter type by the semantic analysis
+    if let Some(default) = &param_ast.default {
+        let literal = match default.value() {
+            Expression::Literal(value, _) => Literal::Integer(*value as u32),
+            Expression::BooleanLiteral(value) => Literal::Boolean(*value),
+            _ => {
+                return Err(format!(
+                    "Internal Compiler Error: Default value of parameter '{}' is not a literal",
+                    param_ast.name.value()
+                ));
+            }
+        };
+        builder
+            .state
+            .mir_function
+            .parameter_defaults
+            .insert(incoming_param_val, literal);
+    }
+
     // 2. Bind the parameter using SSA
     builder.bind_variable_def(def_id, Value::operand(incoming_param_val))?;
     Ok(())
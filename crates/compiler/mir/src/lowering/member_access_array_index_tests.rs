@@ -239,4 +239,38 @@ mod member_access_array_index_lowering_tests {
             "did not expect insertfield in place-based update"
         );
     }
+
+    #[test]
+    fn test_compound_assignment_evaluates_target_once() {
+        let source = r#"
+        struct Point { x: felt, y: felt }
+
+        fn next() -> felt {
+            return 1;
+        }
+
+        fn test() -> felt {
+            let arr: [Point; 2] = [Point { x: 1, y: 0 }, Point { x: 2, y: 0 }];
+            arr[next()].x += 5;
+            return arr[1].x;
+        }
+        "#;
+
+        let db = TestDatabase::default();
+        let crate_id = create_test_crate(&db, source);
+        let module = generate_mir(&db, crate_id).expect("MIR generation failed");
+        let mir_text = module.pretty_print(0);
+
+        // The index is computed once, and used to both load and store the element field
+        assert_eq!(
+            mir_text.matches("= call ").count(),
+            1,
+            "expected a single evaluation of the index in:\n{mir_text}"
+        );
+        assert!(mir_text.contains(" + "), "expected the addition");
+        assert!(
+            mir_text.contains("store "),
+            "expected store back to arr[i].x"
+        );
+    }
 }
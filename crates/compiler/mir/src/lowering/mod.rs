@@ -22,4 +22,4 @@ mod member_access_array_index_tests;
 // Re-export the main entry points
 // Re-export commonly used items
 pub use builder::MirBuilder;
-pub use function::{generate_mir, generate_mir_with_config};
+pub use function::{generate_mir, generate_mir_with_config, generate_mir_with_passes};
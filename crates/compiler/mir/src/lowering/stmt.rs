@@ -3,16 +3,84 @@
 //! This module contains the trait and implementations for lowering statements
 //! from the AST to MIR instructions.
 
-use cairo_m_compiler_parser::parser::{Expression, Pattern, Spanned, Statement};
+use cairo_m_compiler_parser::const_eval::eval_const_condition;
+use cairo_m_compiler_parser::parser::{BinaryOp, Expression, Pattern, Spanned, Statement};
 use cairo_m_compiler_semantic::place::FileScopeId;
 use cairo_m_compiler_semantic::semantic_index::DefinitionId;
 use cairo_m_compiler_semantic::type_resolution::expression_semantic_type;
 use cairo_m_compiler_semantic::types::TypeData;
 
 use super::builder::MirBuilder;
-use super::expr::{LowerExpr, LoweredExpr};
+use super::expr::LowerExpr;
 use crate::{Instruction, MirType, Terminator, Value};
 
+/// A case of an `if`/`else if` ladder: the literal compared to the scrutinee, and the body
+type SwitchCase<'a> = (&'a Spanned<Expression>, &'a Spanned<Statement>);
+
+/// Splits a `x == literal` (or `literal == x`) condition into the variable and the literal
+fn switch_case_condition(
+    condition: &Spanned<Expression>,
+) -> Option<(&Spanned<Expression>, &Spanned<Expression>)> {
+    match condition.value() {
+        Expression::Parenthesized(inner) => switch_case_condition(inner),
+        Expression::BinaryOp {
+            op: BinaryOp::Eq,
+            left,
+            right,
+        } => match (left.value(), right.value()) {
+            (Expression::Identifier(_), Expression::Literal(..)) => Some((left, right)),
+            (Expression::Literal(..), Expression::Identifier(_)) => Some((right, left)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collects the cases of an `if x == a {..} else if x == b {..} else {..}` ladder, as long
+/// as each condition compares the same variable to a literal.
+///
+/// # Returns
+/// The scrutinee, the cases in order and the final `else` statement, or `None` if the
+/// ladder has less than two cases
+fn switch_ladder<'a>(
+    condition: &'a Spanned<Expression>,
+    then_block: &'a Spanned<Statement>,
+    else_block: Option<&'a Spanned<Statement>>,
+) -> Option<(
+    &'a Spanned<Expression>,
+    Vec<SwitchCase<'a>>,
+    Option<&'a Spanned<Statement>>,
+)> {
+    let (scrutinee, literal) = switch_case_condition(condition)?;
+    let Expression::Identifier(name) = scrutinee.value() else {
+        return None;
+    };
+
+    let mut cases = vec![(literal, then_block)];
+    let mut default = else_block;
+    while let Some(Statement::If {
+        condition,
+        then_block,
+        else_block,
+    }) = default.map(Spanned::value)
+    {
+        let Some((other, literal)) = switch_case_condition(condition) else {
+            break;
+        };
+        let same_variable = matches!(
+            other.value(),
+            Expression::Identifier(other) if other.value() == name.value()
+        );
+        if !same_variable {
+            break;
+        }
+        cases.push((literal, then_block));
+        default = else_block.as_deref();
+    }
+
+    (cases.len() >= 2).then_some((scrutinee, cases, default))
+}
+
 /// Internal representation of an lvalue access step on the LHS.
 /// Helps avoid re-evaluating AST while rebuilding nested assignments.
 #[derive(Clone)]
@@ -41,6 +109,17 @@ enum LhsStep {
     },
 }
 
+/// Value written to the target of an assignment.
+enum AssignedValue<'e> {
+    /// Lowered right-hand side of `lhs = rhs`
+    Value(Value),
+    /// `lhs op= rhs`, computed from the current value of the target
+    Compound {
+        op: BinaryOp,
+        rhs: &'e Spanned<Expression>,
+    },
+}
+
 /// Trait for lowering statements to MIR
 pub trait LowerStmt<'a> {
     fn lower_statement(&mut self, stmt: &Spanned<Statement>) -> Result<(), String>;
@@ -54,6 +133,9 @@ impl<'a, 'db> LowerStmt<'a> for MirBuilder<'a, 'db> {
             Statement::Let { pattern, value, .. } => self.lower_let_statement(pattern, value),
             Statement::Return { value } => self.lower_return_statement(value),
             Statement::Assignment { lhs, rhs } => self.lower_assignment_statement(lhs, rhs),
+            Statement::CompoundAssignment { lhs, op, rhs } => {
+                self.lower_compound_assignment_statement(lhs, *op, rhs)
+            }
             Statement::Expression(expr) => self.lower_expression_statement(expr),
             Statement::If {
                 condition,
@@ -197,6 +279,74 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         &mut self,
         lhs: &Spanned<Expression>,
         rhs: &Spanned<Expression>,
+    ) -> Result<(), String> {
+        self.lower_assignment(lhs, rhs, None)
+    }
+
+    /// Lowers `lhs op= rhs` as `lhs = lhs op rhs`, evaluating the target only once: the
+    /// indices of `arr[f()] += 1` are computed a single time, and the element is loaded from
+    /// and stored to the same place.
+    pub(super) fn lower_compound_assignment_statement(
+        &mut self,
+        lhs: &Spanned<Expression>,
+        op: BinaryOp,
+        rhs: &Spanned<Expression>,
+    ) -> Result<(), String> {
+        self.lower_assignment(lhs, rhs, Some(op))
+    }
+
+    /// Returns the value to write to the target of an assignment.
+    ///
+    /// `current` provides the value of the target, and is only called for compound
+    /// assignments.
+    fn lower_assigned_value(
+        &mut self,
+        lhs: &Spanned<Expression>,
+        assigned: AssignedValue<'_>,
+        current: impl FnOnce(&mut Self) -> Result<Value, String>,
+    ) -> Result<Value, String> {
+        let (op, rhs) = match assigned {
+            AssignedValue::Value(value) => return Ok(value),
+            AssignedValue::Compound { op, rhs } => (op, rhs),
+        };
+        let current_value = current(self)?;
+        let rhs_value = self.lower_expression(rhs)?.into_value();
+
+        let lhs_expr_id = self.expr_id(lhs.span())?;
+        let lhs_type = expression_semantic_type(
+            self.ctx.db,
+            self.ctx.crate_id,
+            self.ctx.file,
+            lhs_expr_id,
+            None,
+        );
+        // Operators overloaded by a struct are calls to its method, which returns the type
+        // of the target
+        if let Some(func_id) = self.resolve_operator_method(op, lhs_type) {
+            return self.lower_operator_call(
+                func_id,
+                current_value,
+                rhs_value,
+                lhs_type,
+                lhs.span(),
+            );
+        }
+        let typed_op = crate::BinaryOp::from_parser(op, &lhs_type.data(self.ctx.db))?;
+        let dest = self
+            .state
+            .mir_function
+            .new_typed_value_id(MirType::from_semantic_type(self.ctx.db, lhs_type));
+        self.instr()
+            .binary_op_to(typed_op, dest, current_value, rhs_value);
+        Ok(Value::operand(dest))
+    }
+
+    /// Lowers an assignment, compound if `op` is set.
+    fn lower_assignment(
+        &mut self,
+        lhs: &Spanned<Expression>,
+        rhs: &Spanned<Expression>,
+        op: Option<BinaryOp>,
     ) -> Result<(), String> {
         // Resolve LHS info once
         let lhs_expr_id = self.expr_id(lhs.span())?;
@@ -206,9 +356,18 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             .expression(lhs_expr_id)
             .ok_or_else(|| format!("MIR: No ExpressionInfo for LHS ID {lhs_expr_id:?}"))?;
 
+        // Lower RHS once. The RHS of a compound assignment is lowered after the target, whose
+        // current value it is combined with.
+        let assigned = match op {
+            Some(op) => AssignedValue::Compound { op, rhs },
+            None => AssignedValue::Value(self.lower_expression(rhs)?.into_value()),
+        };
+
         // Simple identifier assignment
         if let Expression::Identifier(_name) = &lhs_expr_info.ast_node {
-            let rhs_value = self.lower_expression(rhs)?.into_value();
+            let value = self.lower_assigned_value(lhs, assigned, |builder| {
+                Ok(builder.lower_expression(lhs)?.into_value())
+            })?;
             let (def_idx, _def) = self
                 .ctx
                 .semantic_index
@@ -220,15 +379,12 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                     )
                 })?;
             let def_id = DefinitionId::new(self.ctx.db, self.ctx.file, def_idx);
-            return self.bind_variable_def(def_id, rhs_value);
+            return self.bind_variable_def(def_id, value);
         }
 
         // General path: decompose LHS once, reuse lowered results
         let (base_expr, steps) = self.decompose_lhs_path(lhs);
 
-        // Lower RHS once
-        let mut updated_val: Value = self.lower_expression(rhs)?.into_value();
-
         // Lower base once (get both value and potential place)
         let base_lowered = self.lower_expression(&base_expr)?;
         let base_val = *base_lowered.value();
@@ -236,6 +392,7 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
 
         // No steps: either array element or identifier/temporary
         if steps.is_empty() {
+            let updated_val = self.lower_assigned_value(lhs, assigned, |_| Ok(base_val))?;
             if let Some(place) = base_place {
                 let element_ty = self.ctx.get_expr_type(self.expr_id(base_expr.span())?);
                 self.instr()
@@ -269,6 +426,18 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             }
 
             let lhs_ty = self.expr_mir_type(lhs.span())?;
+            let updated_val = self.lower_assigned_value(lhs, assigned, |builder| {
+                let current = builder
+                    .state
+                    .mir_function
+                    .new_typed_value_id(lhs_ty.clone());
+                builder.instr().add_instruction(Instruction::load(
+                    current,
+                    place.clone(),
+                    lhs_ty.clone(),
+                ));
+                Ok(Value::operand(current))
+            })?;
             self.instr()
                 .add_instruction(Instruction::store(place, updated_val, lhs_ty));
             return Ok(());
@@ -307,6 +476,9 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             }
         }
 
+        let target_val = current_container_val;
+        let mut updated_val = self.lower_assigned_value(lhs, assigned, |_| Ok(target_val))?;
+
         // Rebuild outward using inserts from deepest -> base
         for (i, step) in steps.iter().enumerate().rev() {
             let container_expr_span = match step {
@@ -361,13 +533,24 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
     ) -> Result<(), String> {
         // For statement expressions, check if it's a function call that should be void
         if let Expression::FunctionCall { callee, args } = expr.value() {
-            // Handle built-in assert(...)
-            if let Expression::Identifier(name) = callee.value()
-                && cairo_m_compiler_semantic::builtins::is_builtin_function_name(name.value())
-                    == Some(cairo_m_compiler_semantic::builtins::BuiltinFn::Assert)
-            {
-                self.lower_assert_call(args, expr.span())?;
-                return Ok(());
+            // Handle built-in assert(...), panic(...) and debug_print(...)
+            if let Expression::Identifier(name) = callee.value() {
+                match cairo_m_compiler_semantic::builtins::is_builtin_function_name(name.value()) {
+                    Some(cairo_m_compiler_semantic::builtins::BuiltinFn::Assert) => {
+                        self.lower_assert_call(args, expr.span())?;
+                        return Ok(());
+                    }
+                    Some(cairo_m_compiler_semantic::builtins::BuiltinFn::Panic) => {
+                        self.lower_panic_call(args, expr.span())?;
+                        return Ok(());
+                    }
+                    Some(cairo_m_compiler_semantic::builtins::BuiltinFn::DebugPrint) => {
+                        self.lower_debug_print_call(args, expr.span())?;
+                        return Ok(());
+                    }
+                    // Intrinsics returning a value are lowered as expressions below.
+                    Some(_) | None => {}
+                }
             }
             // Handle function calls as statements (void calls)
             let expr_id = self.expr_id(expr.span())?;
@@ -375,13 +558,10 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             // Try to resolve the function using our helper
             if let Ok(func_id) = self.resolve_callee_expression(callee) {
                 // Lower arguments
-                let arg_values = args
-                    .iter()
-                    .map(|arg| self.lower_expression(arg).map(LoweredExpr::into_value))
-                    .collect::<Result<Vec<_>, _>>()?;
+                let arg_values = self.lower_call_args(callee, args)?;
 
                 // Use our helper to emit the call and discard results
-                self.emit_call_and_discard_result(func_id, arg_values, expr_id)?;
+                self.emit_call_and_discard_result(func_id, arg_values, expr_id, expr.span())?;
                 return Ok(());
             }
         }
@@ -393,6 +573,9 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
 
     /// Lower a built-in assert(...) call.
     /// Evaluate the condition expression to a boolean value and assert it equals true.
+    ///
+    /// With a message, `assert(cond, "msg")` branches to a block that aborts with the message
+    /// when the condition is false, so that the runner can report it.
     pub(crate) fn lower_assert_call(
         &mut self,
         args: &[Spanned<Expression>],
@@ -405,6 +588,22 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         // Lower the first argument as the condition; semantic layer ensures it's a bool.
         let cond_val = self.lower_expression(&args[0])?.into_value();
 
+        if let Some(message_arg) = args.get(1) {
+            let message = Self::abort_message(message_arg)?;
+            let continue_block = self.create_block();
+            let abort_block = self.create_block();
+            self.terminate_with_branch(cond_val, continue_block, abort_block);
+            self.seal_block(continue_block);
+            self.seal_block(abort_block);
+
+            self.switch_to_block(abort_block);
+            self.terminate_with_abort(message, call_span);
+            self.mark_block_filled(abort_block);
+
+            self.switch_to_block(continue_block);
+            return Ok(());
+        }
+
         // Assert the boolean condition equals true (1)
         self.instr().add_instruction(crate::Instruction {
             kind: crate::InstructionKind::AssertEq {
@@ -413,18 +612,82 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
             },
             source_span: Some(call_span),
             source_expr_id: None,
+            location: None,
             comment: None,
         });
 
         Ok(())
     }
 
+    /// Lower a built-in panic(...) call.
+    /// Terminates the current block with an abort carrying the optional message.
+    pub(crate) fn lower_panic_call(
+        &mut self,
+        args: &[Spanned<Expression>],
+        call_span: chumsky::prelude::SimpleSpan,
+    ) -> Result<(), String> {
+        let message = match args {
+            [] => "explicit panic".to_string(),
+            [message_arg] => Self::abort_message(message_arg)?,
+            _ => return Err("panic expects at most one argument".to_string()),
+        };
+        self.terminate_with_abort(message, call_span);
+        Ok(())
+    }
+
+    /// Extracts the message of an assert(...) or panic(...) call.
+    fn abort_message(arg: &Spanned<Expression>) -> Result<String, String> {
+        match arg.value() {
+            Expression::StringLiteral(message) => Ok(message.clone()),
+            _ => Err("abort message must be a string literal".to_string()),
+        }
+    }
+
+    /// Lower a built-in debug_print(...) call.
+    /// Evaluate the argument and emit a typed `DebugPrint` instruction.
+    pub(crate) fn lower_debug_print_call(
+        &mut self,
+        args: &[Spanned<Expression>],
+        call_span: chumsky::prelude::SimpleSpan,
+    ) -> Result<(), String> {
+        let [arg] = args else {
+            return Err("debug_print expects exactly one argument".to_string());
+        };
+
+        // The semantic layer ensures the argument is a felt, u32 or bool.
+        let arg_expr_id = self.expr_id(arg.span())?;
+        let ty = self.ctx.get_expr_type(arg_expr_id);
+        let value = self.lower_expression(arg)?.into_value();
+
+        self.instr()
+            .add_instruction(crate::Instruction::debug_print(value, ty).with_span(call_span));
+
+        Ok(())
+    }
+
     pub(super) fn lower_if_statement(
         &mut self,
         condition: &Spanned<Expression>,
         then_block: &Spanned<Statement>,
         else_block: Option<&Spanned<Statement>>,
     ) -> Result<(), String> {
+        // A constant condition selects a single branch: the dead one is not lowered.
+        if let Some(value) = eval_const_condition(condition.value()) {
+            return match (value, else_block) {
+                (true, _) => self.lower_statement(then_block),
+                (false, Some(else_stmt)) => self.lower_statement(else_stmt),
+                (false, None) => Ok(()),
+            };
+        }
+
+        // A ladder of equality tests on the same variable becomes a single switch
+        if let Some((scrutinee, cases, default)) = switch_ladder(condition, then_block, else_block)
+        {
+            if self.lower_switch_ladder(scrutinee, &cases, default)? {
+                return Ok(());
+            }
+        }
+
         // Lower the condition expression
         let condition_value = self.lower_expression(condition)?.into_value();
 
@@ -532,6 +795,90 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         Ok(())
     }
 
+    /// Lowers an `if`/`else if` ladder collected by `switch_ladder` to a `Switch` terminator
+    ///
+    /// # Returns
+    /// `false` if the scrutinee is neither a felt nor a u32, in which case nothing is lowered
+    fn lower_switch_ladder(
+        &mut self,
+        scrutinee: &Spanned<Expression>,
+        cases: &[SwitchCase<'_>],
+        default: Option<&Spanned<Statement>>,
+    ) -> Result<bool, String> {
+        let scrutinee_type = expression_semantic_type(
+            self.ctx.db,
+            self.ctx.crate_id,
+            self.ctx.file,
+            self.expr_id(scrutinee.span())?,
+            None,
+        );
+        let op = match crate::BinaryOp::from_parser(BinaryOp::Eq, &scrutinee_type.data(self.ctx.db))
+        {
+            Ok(op @ (crate::BinaryOp::Eq | crate::BinaryOp::U32Eq)) => op,
+            _ => return Ok(false),
+        };
+
+        let value = self.lower_expression(scrutinee)?.into_value();
+        let mut switch_cases = Vec::with_capacity(cases.len());
+        for (literal, _) in cases {
+            let case_value = self.lower_expression(literal)?.into_value();
+            switch_cases.push((case_value, self.create_block()));
+        }
+
+        // Without a final `else`, unmatched values go directly to the merge block
+        let default_block_id = self.create_block();
+        let case_blocks: Vec<_> = switch_cases.iter().map(|(_, block_id)| *block_id).collect();
+        self.terminate_with_switch(op, value, switch_cases, default_block_id);
+
+        // Seal case and default blocks since their predecessor sets are now final
+        for &block_id in &case_blocks {
+            self.seal_block(block_id);
+        }
+        self.seal_block(default_block_id);
+
+        // Keep track of the final blocks from each case that need to be connected to the merge block
+        let mut final_blocks = Vec::new();
+        for ((_, body), &block_id) in cases.iter().zip(&case_blocks) {
+            self.switch_to_block(block_id);
+            self.lower_statement(body)?;
+            self.mark_block_filled(block_id);
+            if !self.is_current_block_terminated() {
+                final_blocks.push(self.state.current_block_id);
+            }
+        }
+
+        let Some(else_stmt) = default else {
+            self.switch_to_block(default_block_id);
+            for block_id in final_blocks {
+                let mut cfg = self.cfg();
+                cfg.set_block_terminator(block_id, Terminator::jump(default_block_id));
+            }
+            return Ok(true);
+        };
+
+        self.switch_to_block(default_block_id);
+        self.lower_statement(else_stmt)?;
+        self.mark_block_filled(default_block_id);
+        if !self.is_current_block_terminated() {
+            final_blocks.push(self.state.current_block_id);
+        }
+
+        if final_blocks.is_empty() {
+            // All cases ended in a terminator
+            self.state.is_terminated = true;
+        } else {
+            let merge_block_id = self.create_block();
+            for block_id in final_blocks {
+                let mut cfg = self.cfg();
+                cfg.set_block_terminator(block_id, Terminator::jump(merge_block_id));
+            }
+            self.seal_block(merge_block_id);
+            self.switch_to_block(merge_block_id);
+        }
+
+        Ok(true)
+    }
+
     pub(super) fn lower_block_statement(
         &mut self,
         statements: &[Spanned<Statement>],
@@ -5,6 +5,7 @@
 
 use cairo_m_compiler_semantic::type_resolution::expression_semantic_type;
 use cairo_m_compiler_semantic::types::TypeData;
+use chumsky::prelude::SimpleSpan;
 
 use super::builder::MirBuilder;
 use crate::instruction::CalleeSignature;
@@ -21,12 +22,13 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
     /// Emits a call instruction with destinations and proper signature
     ///
     /// This helper centralizes the logic for emitting function calls with
-    /// proper signatures and destination handling.
+    /// proper signatures and destination handling. The call is located at the start of `span`.
     pub(crate) fn emit_call_with_destinations(
         &mut self,
         func_id: FunctionId,
         args: Vec<Value>,
         dests: Vec<ValueId>,
+        span: SimpleSpan,
     ) -> Result<(), String> {
         // Get the function signature
         let (param_types, return_types) = self.get_function_signature(func_id)?;
@@ -38,7 +40,8 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         };
 
         // Create the call instruction with the signature
-        let call_instr = Instruction::call(dests, func_id, args, signature);
+        let call_instr = Instruction::call(dests, func_id, args, signature)
+            .with_location(self.source_location(span));
         self.instr().add_instruction(call_instr);
         Ok(())
     }
@@ -53,6 +56,7 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
         func_id: FunctionId,
         args: Vec<Value>,
         expr_id: cairo_m_compiler_semantic::semantic_index::ExpressionId,
+        span: SimpleSpan,
     ) -> Result<(), String> {
         // Check the function's return type
         let func_expr_semantic_type =
@@ -66,13 +70,13 @@ impl<'a, 'db> MirBuilder<'a, 'db> {
                     let mir_type = MirType::from_semantic_type(self.ctx.db, elem_type);
                     dests.push(self.state.mir_function.new_typed_value_id(mir_type));
                 }
-                self.emit_call_with_destinations(func_id, args, dests)?;
+                self.emit_call_with_destinations(func_id, args, dests, span)?;
             }
             _ => {
                 // Function returns a single value - create a destination but don't use it
                 let return_type = MirType::from_semantic_type(self.ctx.db, func_expr_semantic_type);
                 let dest = self.state.mir_function.new_typed_value_id(return_type);
-                self.emit_call_with_destinations(func_id, args, vec![dest])?;
+                self.emit_call_with_destinations(func_id, args, vec![dest], span)?;
             }
         }
         Ok(())
@@ -4,9 +4,9 @@
 //! compilation unit (typically a source file).
 
 use index_vec::IndexVec;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::{FunctionId, MirFunction, PrettyPrint, indent_str};
+use crate::{ColdAbortId, FunctionId, InstructionKind, MirFunction, PrettyPrint, indent_str};
 
 /// The MIR for an entire program module (compilation unit)
 ///
@@ -26,6 +26,22 @@ pub struct MirModule {
     /// Mapping from function names to their IDs for lookup
     /// This enables efficient name-based function resolution
     pub(crate) function_names: FxHashMap<String, FunctionId>,
+
+    /// Aborts outlined from the functions, which jump to them with
+    /// [`Terminator::ColdAbort`](crate::Terminator::ColdAbort)
+    pub(crate) cold_aborts: IndexVec<ColdAbortId, ColdAbort>,
+}
+
+/// An abort of the cold region of a module, shared by the blocks of a function aborting
+/// with the same message at the same location
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColdAbort {
+    /// The function jumping to this abort, which runs in its frame
+    pub function: FunctionId,
+    /// The message reported by the runner
+    pub message: String,
+    /// Source location of the `assert` or `panic`
+    pub location: Option<String>,
 }
 
 impl MirModule {
@@ -34,6 +50,7 @@ impl MirModule {
         Self {
             functions: IndexVec::new(),
             function_names: FxHashMap::default(),
+            cold_aborts: IndexVec::new(),
         }
     }
 
@@ -65,11 +82,62 @@ impl MirModule {
         self.functions.iter_mut()
     }
 
+    /// Adds an abort to the cold region, unless it already contains it, and returns its ID
+    pub fn add_cold_abort(&mut self, abort: ColdAbort) -> ColdAbortId {
+        match self
+            .cold_aborts
+            .iter_enumerated()
+            .find(|(_, existing)| **existing == abort)
+        {
+            Some((id, _)) => id,
+            None => self.cold_aborts.push(abort),
+        }
+    }
+
+    /// Returns an iterator over the aborts of the cold region
+    pub fn cold_aborts(&self) -> impl Iterator<Item = (ColdAbortId, &ColdAbort)> {
+        self.cold_aborts.iter_enumerated()
+    }
+
     /// Returns the number of functions in this module
     pub fn function_count(&self) -> usize {
         self.functions.len()
     }
 
+    /// Removes the functions that are not in `keep`
+    ///
+    /// The remaining functions are renumbered, and the callees of their calls updated
+    /// accordingly. Every function called by a kept function must be kept.
+    pub(crate) fn retain_functions(&mut self, keep: &FxHashSet<FunctionId>) {
+        debug_assert!(
+            self.cold_aborts.is_empty(),
+            "functions are retained before their aborts are outlined"
+        );
+        let mut new_ids = FxHashMap::default();
+        let mut functions = IndexVec::new();
+        for (id, function) in std::mem::take(&mut self.functions).into_iter_enumerated() {
+            if keep.contains(&id) {
+                new_ids.insert(id, functions.push(function));
+            }
+        }
+
+        for function in functions.iter_mut() {
+            for block in function.basic_blocks.iter_mut() {
+                for instruction in &mut block.instructions {
+                    if let InstructionKind::Call { callee, .. } = &mut instruction.kind {
+                        *callee = new_ids[&*callee];
+                    }
+                }
+            }
+        }
+
+        self.function_names = functions
+            .iter_enumerated()
+            .map(|(id, function)| (function.name.clone(), id))
+            .collect();
+        self.functions = functions;
+    }
+
     /// Validates the module structure
     ///
     /// Checks:
@@ -132,6 +200,13 @@ impl PrettyPrint for MirModule {
             result.push('\n');
         }
 
+        for (id, abort) in self.cold_aborts() {
+            result.push_str(&format!(
+                "{base_indent}  cold_abort_{id:?}: abort {:?}\n",
+                abort.message
+            ));
+        }
+
         result.push_str(&format!("{base_indent}}}\n"));
         result
     }
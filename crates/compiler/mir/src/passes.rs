@@ -3,9 +3,12 @@
 //! This module implements various optimization passes that can be applied to MIR functions
 //! to improve code quality and remove dead code.
 
-use crate::MirFunction;
+use crate::{MirFunction, PrettyPrint};
 
+pub mod abort_outlining;
 pub mod const_eval;
+pub mod statistics;
+use statistics::{FunctionSize, PassStatistics};
 
 /// A trait for MIR optimization passes
 pub trait MirPass {
@@ -37,6 +40,9 @@ use simplify_branches::SimplifyBranches;
 pub mod fuse_cmp;
 use fuse_cmp::FuseCmpBranch;
 
+pub mod jump_threading;
+use jump_threading::JumpThreading;
+
 pub mod dead_code_elimination;
 use dead_code_elimination::DeadCodeElimination;
 
@@ -46,16 +52,146 @@ use sroa::ScalarReplacementOfAggregates;
 pub mod phi_elimination;
 use phi_elimination::PhiElimination;
 
+pub mod range_check_hoisting;
+use range_check_hoisting::RangeCheckHoisting;
+
+pub mod array_dematerialization;
+use array_dematerialization::ArrayDematerialization;
+
+pub mod load_store_forwarding;
+use load_store_forwarding::LoadStoreForwarding;
+
+pub mod constant_pooling;
+use constant_pooling::ConstantPooling;
+
+pub mod block_layout;
+use block_layout::BlockLayout;
+
+/// An optimization pass that can be selected by name to build a custom pipeline
+///
+/// Phi elimination is not selectable: it always runs last, to convert functions out of SSA
+/// form for codegen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PassKind {
+    ArrayDematerialization,
+    Sroa,
+    LoadStoreForwarding,
+    ArithmeticSimplify,
+    ConstantPropagation,
+    ConstantFolding,
+    CopyPropagation,
+    Cse,
+    ConstantPooling,
+    RangeCheckHoisting,
+    SimplifyBranches,
+    FuseCmp,
+    JumpThreading,
+    Dce,
+    BlockLayout,
+}
+
+impl PassKind {
+    /// All selectable passes, in the order of the standard pipeline
+    pub const ALL: &[Self] = &[
+        Self::ArrayDematerialization,
+        Self::Sroa,
+        Self::LoadStoreForwarding,
+        Self::ArithmeticSimplify,
+        Self::ConstantPropagation,
+        Self::ConstantFolding,
+        Self::CopyPropagation,
+        Self::Cse,
+        Self::ConstantPooling,
+        Self::RangeCheckHoisting,
+        Self::SimplifyBranches,
+        Self::FuseCmp,
+        Self::JumpThreading,
+        Self::Dce,
+        Self::BlockLayout,
+    ];
+
+    /// Name of the pass in pipeline specifications
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::ArrayDematerialization => "array_dematerialization",
+            Self::Sroa => "sroa",
+            Self::LoadStoreForwarding => "load_store_forwarding",
+            Self::ArithmeticSimplify => "arithmetic_simplify",
+            Self::ConstantPropagation => "constant_propagation",
+            Self::ConstantFolding => "constant_folding",
+            Self::CopyPropagation => "copy_propagation",
+            Self::Cse => "cse",
+            Self::ConstantPooling => "constant_pooling",
+            Self::RangeCheckHoisting => "range_check_hoisting",
+            Self::SimplifyBranches => "simplify_branches",
+            Self::FuseCmp => "fuse_cmp",
+            Self::JumpThreading => "jump_threading",
+            Self::Dce => "dce",
+            Self::BlockLayout => "block_layout",
+        }
+    }
+
+    /// Looks up a pass by its [name](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// Parses a comma-separated list of pass names, such as `"sroa,cse,dce"`
+    ///
+    /// ## Errors
+    /// Returns a message naming the first unknown pass and listing the known ones
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                Self::from_name(name).ok_or_else(|| {
+                    let known: Vec<_> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                    format!(
+                        "Unknown MIR pass '{name}', expected one of: {}",
+                        known.join(", ")
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Creates a new instance of the pass
+    pub fn create(self) -> Box<dyn MirPass> {
+        match self {
+            Self::ArrayDematerialization => Box::new(ArrayDematerialization::new()),
+            Self::Sroa => Box::new(ScalarReplacementOfAggregates::new()),
+            Self::LoadStoreForwarding => Box::new(LoadStoreForwarding::new()),
+            Self::ArithmeticSimplify => Box::new(ArithmeticSimplify::new()),
+            Self::ConstantPropagation => Box::new(ConstantPropagation::new()),
+            Self::ConstantFolding => Box::new(ConstantFolding::new()),
+            Self::CopyPropagation => Box::new(CopyPropagation::new()),
+            Self::Cse => Box::new(LocalCSE::new()),
+            Self::ConstantPooling => Box::new(ConstantPooling::new()),
+            Self::RangeCheckHoisting => Box::new(RangeCheckHoisting::new()),
+            Self::SimplifyBranches => Box::new(SimplifyBranches::new()),
+            Self::FuseCmp => Box::new(FuseCmpBranch::new()),
+            Self::JumpThreading => Box::new(JumpThreading::new()),
+            Self::Dce => Box::new(DeadCodeElimination::new()),
+            Self::BlockLayout => Box::new(BlockLayout::new()),
+        }
+    }
+}
+
 /// A pass manager that can run multiple passes in sequence
 #[derive(Default)]
 pub struct PassManager {
     passes: Vec<Box<dyn MirPass>>,
+    /// Statistics of every pass run, recorded once enabled with [`Self::with_statistics`]
+    statistics: Option<Vec<PassStatistics>>,
+    /// Whether the statistics include the MIR diff of the passes modifying their function
+    diffs: bool,
 }
 
 impl PassManager {
     /// Create a new pass manager
     pub fn new() -> Self {
-        Self { passes: Vec::new() }
+        Self::default()
     }
 
     /// Add a pass to the manager
@@ -64,21 +200,79 @@ impl PassManager {
         self
     }
 
+    /// Create a pass manager running the given passes, in order
+    pub fn from_kinds(kinds: &[PassKind]) -> Self {
+        Self {
+            passes: kinds.iter().map(|kind| kind.create()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Record the statistics of every pass run, see [`Self::statistics`]
+    pub fn with_statistics(mut self) -> Self {
+        self.statistics.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Record the statistics of every pass run, along with the MIR diff of the passes that
+    /// modify their function
+    ///
+    /// Diffing pretty-prints the function around every pass, which slows down the pipeline.
+    pub fn with_diffs(mut self) -> Self {
+        self.diffs = true;
+        self.with_statistics()
+    }
+
+    /// Statistics of the passes run so far, in run order, empty unless enabled
+    pub fn statistics(&self) -> &[PassStatistics] {
+        self.statistics.as_deref().unwrap_or_default()
+    }
+
+    /// Takes the statistics of the passes run so far, leaving them empty
+    pub fn take_statistics(&mut self) -> Vec<PassStatistics> {
+        self.statistics
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     /// Run all passes on the function
     /// Returns true if any pass modified the function
     pub fn run(&mut self, function: &mut MirFunction) -> bool {
         let mut modified = false;
 
         for pass in &mut self.passes {
-            if pass.run(function) {
-                modified = true;
-            }
+            let Some(records) = &mut self.statistics else {
+                modified |= pass.run(function);
+                continue;
+            };
+
+            let before = FunctionSize::of(function);
+            let text_before = self.diffs.then(|| function.pretty_print(0));
+            let pass_modified = pass.run(function);
+            let diff = text_before
+                .filter(|_| pass_modified)
+                .map(|text_before| statistics::line_diff(&text_before, &function.pretty_print(0)));
+            records.push(PassStatistics {
+                pass: pass.name(),
+                function: function.name.clone(),
+                modified: pass_modified,
+                before,
+                after: FunctionSize::of(function),
+                diff,
+            });
+            modified |= pass_modified;
         }
 
         modified
     }
 
     pub fn no_opt_pipeline() -> Self {
+        Self::ssa_destruction_pipeline()
+    }
+
+    /// Create the pipeline converting functions out of SSA form, run after all other passes
+    pub fn ssa_destruction_pipeline() -> Self {
         Self::new().add_pass(PhiElimination::new())
     }
 
@@ -91,17 +285,27 @@ impl PassManager {
     /// This allows optimizations like constant folding to work on value-based aggregates
     /// while still ensuring all aggregates are memory-based for CASM generation.
     pub fn standard_pipeline() -> Self {
+        Self::standard_optimizations().add_pass(PhiElimination::new())
+    }
+
+    /// Create the optimizations of the standard pipeline, which run on functions in SSA form
+    pub fn standard_optimizations() -> Self {
         Self::new()
+            .add_pass(ArrayDematerialization::new()) // Reload arrays after dynamic accesses for SROA
             .add_pass(ScalarReplacementOfAggregates::new()) // Run SROA early to expose scalars
+            .add_pass(LoadStoreForwarding::new()) // Forward stores to loads of arrays left in memory
             .add_pass(ArithmeticSimplify::new())
             .add_pass(ConstantPropagation::new())
             .add_pass(ConstantFolding::new())
             .add_pass(CopyPropagation::new())
             .add_pass(LocalCSE::new())
+            .add_pass(ConstantPooling::new()) // Pool constant arrays in read-only data
+            .add_pass(RangeCheckHoisting::new())
             .add_pass(SimplifyBranches::new())
             .add_pass(FuseCmpBranch::new())
+            .add_pass(JumpThreading::new())
             .add_pass(DeadCodeElimination::new())
-            .add_pass(PhiElimination::new()) // Convert from SSA to non-SSA form
+            .add_pass(BlockLayout::new()) // Order blocks so that branches fall through
     }
 }
 
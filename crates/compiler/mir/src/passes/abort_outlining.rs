@@ -0,0 +1,137 @@
+//! # Abort Outlining Pass
+//!
+//! Codegen inlines an abort as a store followed by a failing assertion, in a frame slot
+//! reserved for it. Aborts are rarely executed, yet each `assert` and `panic` of a function
+//! grows its code and its frame. This pass moves the aborts of a module to its cold region,
+//! where each distinct abort of a function is emitted once, and replaces them in the
+//! functions with a jump to the shared abort.
+
+use crate::{ColdAbort, MirModule, Terminator};
+
+/// Outlines the aborts of all the functions of a module to its cold region
+///
+/// Aborts of a function with the same message and source location share a single cold
+/// abort. The instructions of an aborting block stay in place, only its terminator is
+/// replaced.
+///
+/// ### Before:
+/// ```mir
+/// block_1:
+///   abort "division by zero"
+/// ```
+///
+/// ### After:
+/// ```mir
+/// block_1:
+///   jump cold_abort_0
+///
+/// cold_abort_0: abort "division by zero"
+/// ```
+///
+/// Returns true if any abort was outlined.
+pub fn outline_aborts(module: &mut MirModule) -> bool {
+    let mut modified = false;
+    for function_id in module.functions.indices() {
+        for block_id in module.functions[function_id].basic_blocks.indices() {
+            let terminator = &mut module.functions[function_id].basic_blocks[block_id].terminator;
+            let Terminator::Abort { message, location } = terminator else {
+                continue;
+            };
+            let abort = ColdAbort {
+                function: function_id,
+                message: std::mem::take(message),
+                location: location.take(),
+            };
+            let abort_id = module.add_cold_abort(abort);
+            module.functions[function_id].basic_blocks[block_id].terminator =
+                Terminator::cold_abort(abort_id);
+            modified = true;
+        }
+    }
+    modified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BasicBlockId, ColdAbortId, MirFunction, MirType, PrettyPrint, Value};
+
+    /// Creates a function whose entry block aborts with `message` when its parameter is 0,
+    /// and with `message` again when it is 1
+    fn aborting_function(name: &str, message: &str) -> MirFunction {
+        let mut function = MirFunction::new(name.to_string());
+        let x = function.new_typed_value_id(MirType::felt());
+        function.parameters.push(x);
+        let check_one = function.add_basic_block();
+        let abort_zero = function.add_basic_block();
+        let abort_one = function.add_basic_block();
+        let return_block = function.add_basic_block();
+        function.set_terminator_with_edges(
+            function.entry_block,
+            Terminator::branch(Value::operand(x), check_one, abort_zero),
+        );
+        function.set_terminator_with_edges(
+            check_one,
+            Terminator::branch_cmp(
+                crate::BinaryOp::Eq,
+                Value::operand(x),
+                Value::integer(1),
+                abort_one,
+                return_block,
+            ),
+        );
+        for block in [abort_zero, abort_one] {
+            function.set_terminator_with_edges(
+                block,
+                Terminator::abort(message.to_string(), Some("test.cm:1:1".to_string())),
+            );
+        }
+        function
+            .set_terminator_with_edges(return_block, Terminator::return_value(Value::operand(x)));
+        function
+    }
+
+    #[test]
+    fn test_identical_aborts_of_a_function_are_shared() {
+        let mut module = MirModule::new();
+        let f = module.add_function(aborting_function("f", "zero"));
+        let g = module.add_function(aborting_function("g", "zero"));
+
+        assert!(outline_aborts(&mut module));
+
+        let cold_aborts: Vec<_> = module.cold_aborts().map(|(_, abort)| abort).collect();
+        let cold_abort = |function| ColdAbort {
+            function,
+            message: "zero".to_string(),
+            location: Some("test.cm:1:1".to_string()),
+        };
+        assert_eq!(cold_aborts, [&cold_abort(f), &cold_abort(g)]);
+
+        let terminator = |function_id, block| {
+            module.get_function(function_id).unwrap().basic_blocks[BasicBlockId::new(block)]
+                .terminator
+                .clone()
+        };
+        assert_eq!(
+            terminator(f, 2),
+            Terminator::cold_abort(ColdAbortId::new(0))
+        );
+        assert_eq!(
+            terminator(f, 3),
+            Terminator::cold_abort(ColdAbortId::new(0))
+        );
+        assert_eq!(
+            terminator(g, 2),
+            Terminator::cold_abort(ColdAbortId::new(1))
+        );
+        assert!(module.validate().is_ok());
+        assert!(
+            module
+                .pretty_print(0)
+                .contains("cold_abort_1: abort \"zero\"")
+        );
+
+        // Nothing is left to outline
+        assert!(!outline_aborts(&mut module));
+    }
+}
@@ -0,0 +1,439 @@
+//! # Array Dematerialization Pass
+//!
+//! SROA only scalarizes arrays whose indices are all compile-time constants: a single
+//! dynamic access keeps the whole array in memory, along with every constant-index access
+//! that follows it. This pass gives those accesses back to SROA by reloading the elements
+//! into a fresh SSA aggregate once no dynamic access can happen anymore.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::MirPass;
+use crate::cfg::reachable_predecessors;
+use crate::{
+    BasicBlockId, Instruction, InstructionKind, MirFunction, MirType, Place, Projection, Value,
+    ValueId,
+};
+
+/// Largest array reloaded by the pass, matching the default SROA size limit
+const MAX_ARRAY_SIZE: usize = 8;
+
+/// Array Dematerialization Pass
+///
+/// An array built by `MakeFixedArray` is a candidate if its address never escapes: it is
+/// only used as the base of `load`/`store` instructions indexing one of its elements.
+/// Copies, calls, returns and uses as an aggregate element all count as escapes, as they
+/// may share the memory of the array or need it as a whole.
+///
+/// For a candidate with dynamic accesses, every block that cannot reach a dynamic access
+/// reloads the elements right before its first access that follows the dynamic ones, and
+/// rewrites the later accesses of the block to use the reloaded array. Since that array
+/// is only indexed by constants within a single block, SROA then replaces its loads with
+/// the element values, and dead code elimination drops the unused reloads.
+///
+/// Stores to the reloaded array are not written back to memory: a block is only rewritten
+/// if it has no stores, or if it cannot reach another access to the array.
+///
+/// ### Before:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 3]
+/// store 5 -> %0[%i]
+/// store %x -> %0[0]
+/// %1 = load %0[0]
+/// ```
+///
+/// ### After:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 3]
+/// store 5 -> %0[%i]
+/// %2 = load %0[0]
+/// %3 = load %0[1]
+/// %4 = load %0[2]
+/// %5 = makefixedarray [%2, %3, %4]
+/// store %x -> %5[0]
+/// %1 = load %5[0]
+/// ```
+#[derive(Debug, Default)]
+pub struct ArrayDematerialization;
+
+impl ArrayDematerialization {
+    /// Create a new array dematerialization pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns the index of an instruction accessing an element of `array`, or `None` if
+    /// the instruction does not access it this way
+    pub(super) fn element_index(instruction: &Instruction, array: ValueId) -> Option<&Value> {
+        let (place, stored) = match &instruction.kind {
+            InstructionKind::Load { place, .. } => (place, None),
+            InstructionKind::Store { place, value, .. } => (place, Some(value)),
+            _ => return None,
+        };
+        let [Projection::Index(index)] = place.projections.as_slice() else {
+            return None;
+        };
+        let is_array = |value: &Value| value.as_operand() == Some(array);
+        (place.base == array && !is_array(index) && !stored.is_some_and(is_array)).then_some(index)
+    }
+
+    const fn is_dynamic(index: &Value) -> bool {
+        index.as_const_integer().is_none()
+    }
+
+    /// Returns true if `array` is used other than through element accesses
+    pub(super) fn escapes(function: &MirFunction, array: ValueId) -> bool {
+        function.basic_blocks().any(|(_, block)| {
+            block.instructions.iter().any(|instruction| {
+                instruction.used_values().contains(&array)
+                    && Self::element_index(instruction, array).is_none()
+            }) || block.terminator.used_values().contains(&array)
+        })
+    }
+
+    /// Blocks with a path of at least one edge to one of `targets`
+    fn blocks_reaching<'a>(
+        preds: &FxHashMap<BasicBlockId, Vec<BasicBlockId>>,
+        targets: impl IntoIterator<Item = &'a BasicBlockId>,
+    ) -> FxHashSet<BasicBlockId> {
+        let mut reaching = FxHashSet::default();
+        let mut worklist: Vec<BasicBlockId> = targets
+            .into_iter()
+            .flat_map(|target| preds.get(target).into_iter().flatten())
+            .copied()
+            .collect();
+        while let Some(block_id) = worklist.pop() {
+            if reaching.insert(block_id) {
+                worklist.extend(preds.get(&block_id).into_iter().flatten());
+            }
+        }
+        reaching
+    }
+
+    /// Reloads `array` after its dynamic accesses, in every block where it is safe
+    fn dematerialize(
+        function: &mut MirFunction,
+        array: ValueId,
+        element_ty: &MirType,
+        size: usize,
+    ) -> bool {
+        if Self::escapes(function, array) {
+            return false;
+        }
+
+        // Blocks accessing the array, and the position after their last dynamic access
+        let mut users = Vec::new();
+        let mut after_dynamic: FxHashMap<BasicBlockId, usize> = FxHashMap::default();
+        for (block_id, block) in function.basic_blocks() {
+            for (i, instruction) in block.instructions.iter().enumerate() {
+                let Some(index) = Self::element_index(instruction, array) else {
+                    continue;
+                };
+                if users.last() != Some(&block_id) {
+                    users.push(block_id);
+                }
+                if Self::is_dynamic(index) {
+                    after_dynamic.insert(block_id, i + 1);
+                }
+            }
+        }
+        if after_dynamic.is_empty() {
+            return false;
+        }
+
+        let preds = reachable_predecessors(function);
+        let reaching_dynamic = Self::blocks_reaching(&preds, after_dynamic.keys());
+        let reaching_users = Self::blocks_reaching(&preds, &users);
+        let Some(array_ty) = function.get_value_type(array).cloned() else {
+            return false;
+        };
+
+        let mut modified = false;
+        for block_id in users {
+            if reaching_dynamic.contains(&block_id) {
+                continue;
+            }
+            let start = after_dynamic.get(&block_id).copied().unwrap_or(0);
+            let instructions = &function.basic_blocks[block_id].instructions;
+            let accesses: Vec<usize> = (start..instructions.len())
+                .filter(|&i| Self::element_index(&instructions[i], array).is_some())
+                .collect();
+            let Some(&first) = accesses.first() else {
+                continue;
+            };
+            let has_store = accesses
+                .iter()
+                .any(|&i| matches!(instructions[i].kind, InstructionKind::Store { .. }));
+            if has_store && reaching_users.contains(&block_id) {
+                continue;
+            }
+
+            let mut reload = Vec::with_capacity(size + 1);
+            let mut elements = Vec::with_capacity(size);
+            for index in 0..size {
+                let element = function.new_typed_value_id(element_ty.clone());
+                let place = Place::new(array).with_index(Value::integer(index as u32));
+                reload.push(Instruction::load(element, place, element_ty.clone()));
+                elements.push(Value::operand(element));
+            }
+            let reloaded = function.new_typed_value_id(array_ty.clone());
+            reload.push(Instruction::make_fixed_array(
+                reloaded,
+                elements,
+                element_ty.clone(),
+            ));
+
+            let block = &mut function.basic_blocks[block_id];
+            for &i in &accesses {
+                if let InstructionKind::Load { place, .. } | InstructionKind::Store { place, .. } =
+                    &mut block.instructions[i].kind
+                {
+                    place.base = reloaded;
+                }
+            }
+            block.instructions.splice(first..first, reload);
+            modified = true;
+        }
+        modified
+    }
+}
+
+impl MirPass for ArrayDematerialization {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        let arrays: Vec<(ValueId, MirType, usize)> = function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::MakeFixedArray {
+                    dest,
+                    elements,
+                    element_ty,
+                    ..
+                } if elements.len() <= MAX_ARRAY_SIZE => {
+                    Some((*dest, element_ty.clone(), elements.len()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut modified = false;
+        for (array, element_ty, size) in arrays {
+            modified |= Self::dematerialize(function, array, &element_ty, size);
+        }
+        modified
+    }
+
+    fn name(&self) -> &'static str {
+        "ArrayDematerialization"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinaryOp, Terminator};
+
+    fn array_ty() -> MirType {
+        MirType::FixedArray {
+            element_type: Box::new(MirType::felt()),
+            size: 3,
+        }
+    }
+
+    /// Builds `[1, 2, 3]` in the entry block, with `%i` as a parameter
+    fn function_with_array() -> (MirFunction, ValueId, ValueId) {
+        let mut function = MirFunction::new("test".to_string());
+        let i = function.new_typed_value_id(MirType::felt());
+        function.parameters = vec![i];
+        let array = function.new_typed_value_id(array_ty());
+        let entry = function.entry_block;
+        function.basic_blocks[entry].push_instruction(Instruction::make_fixed_array(
+            array,
+            vec![Value::integer(1), Value::integer(2), Value::integer(3)],
+            MirType::felt(),
+        ));
+        (function, array, i)
+    }
+
+    fn element(array: ValueId, index: Value) -> Place {
+        Place::new(array).with_index(index)
+    }
+
+    /// Bases of the loads and stores of a block
+    fn access_bases(function: &MirFunction, block_id: BasicBlockId) -> Vec<ValueId> {
+        function.basic_blocks[block_id]
+            .instructions
+            .iter()
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::Load { place, .. } | InstructionKind::Store { place, .. } => {
+                    Some(place.base)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reloads_after_dynamic_store() {
+        let (mut function, array, i) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::store(
+            element(array, Value::operand(i)),
+            Value::integer(5),
+            MirType::felt(),
+        ));
+        block.push_instruction(Instruction::load(
+            x,
+            element(array, Value::integer(0)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(ArrayDematerialization::new().run(&mut function));
+
+        let instructions = &function.basic_blocks[entry].instructions;
+        let InstructionKind::MakeFixedArray { dest: reloaded, .. } = instructions[5].kind else {
+            panic!("expected the reloaded array, got {:?}", instructions[5]);
+        };
+        // The dynamic store and the reloads use the memory array, the last load the reloaded one
+        assert_eq!(
+            access_bases(&function, entry),
+            vec![array, array, array, array, reloaded]
+        );
+    }
+
+    #[test]
+    fn test_escaping_array_is_kept() {
+        let (mut function, array, i) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::store(
+            element(array, Value::operand(i)),
+            Value::integer(5),
+            MirType::felt(),
+        ));
+        block.push_instruction(Instruction::load(
+            x,
+            element(array, Value::integer(0)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_values(vec![
+            Value::operand(x),
+            Value::operand(array),
+        ]));
+
+        assert!(!ArrayDematerialization::new().run(&mut function));
+    }
+
+    #[test]
+    fn test_constant_accesses_are_left_to_sroa() {
+        let (mut function, array, _) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::load(
+            x,
+            element(array, Value::integer(0)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(!ArrayDematerialization::new().run(&mut function));
+    }
+
+    #[test]
+    fn test_reloads_after_loop() {
+        // entry: array = [1, 2, 3]; jump header
+        // header: if %c then jump body else jump exit
+        // body: %d = load array[%i]; store array[%i], %d + 1; jump header
+        // exit: %x = load array[0]; return %x
+        let (mut function, array, i) = function_with_array();
+        let c = function.new_typed_value_id(MirType::bool());
+        let d = function.new_typed_value_id(MirType::felt());
+        let e = function.new_typed_value_id(MirType::felt());
+        let x = function.new_typed_value_id(MirType::felt());
+        function.parameters.push(c);
+        let entry = function.entry_block;
+        let header = function.add_basic_block();
+        let body = function.add_basic_block();
+        let exit = function.add_basic_block();
+
+        function.basic_blocks[entry].set_terminator(Terminator::jump(header));
+        function.basic_blocks[header].set_terminator(Terminator::branch(
+            Value::operand(c),
+            body,
+            exit,
+        ));
+        let block = &mut function.basic_blocks[body];
+        block.push_instruction(Instruction::load(
+            d,
+            element(array, Value::operand(i)),
+            MirType::felt(),
+        ));
+        block.push_instruction(Instruction::binary_op(
+            BinaryOp::Add,
+            e,
+            Value::operand(d),
+            Value::integer(1),
+        ));
+        block.push_instruction(Instruction::store(
+            element(array, Value::operand(i)),
+            Value::operand(e),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::jump(header));
+        let block = &mut function.basic_blocks[exit];
+        block.push_instruction(Instruction::load(
+            x,
+            element(array, Value::integer(0)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(ArrayDematerialization::new().run(&mut function));
+
+        // The loop body still accesses memory, the exit block the reloaded array
+        assert_eq!(access_bases(&function, body), vec![array, array]);
+        let bases = access_bases(&function, exit);
+        assert_eq!(bases.len(), 4);
+        assert_ne!(bases[3], array);
+    }
+
+    #[test]
+    fn test_stores_reaching_other_accesses_are_kept() {
+        // entry: array = [1, 2, 3]; store array[%i], 5; store array[0], 7; jump next
+        // next: %x = load array[0]; return %x
+        let (mut function, array, i) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let next = function.add_basic_block();
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::store(
+            element(array, Value::operand(i)),
+            Value::integer(5),
+            MirType::felt(),
+        ));
+        block.push_instruction(Instruction::store(
+            element(array, Value::integer(0)),
+            Value::integer(7),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::jump(next));
+        let block = &mut function.basic_blocks[next];
+        block.push_instruction(Instruction::load(
+            x,
+            element(array, Value::integer(0)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(ArrayDematerialization::new().run(&mut function));
+
+        // The store in the entry block must reach memory; the load of the next block
+        // reloads the array after it
+        assert_eq!(access_bases(&function, entry), vec![array, array]);
+        assert_ne!(access_bases(&function, next)[3], array);
+    }
+}
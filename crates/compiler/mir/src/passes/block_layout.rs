@@ -0,0 +1,337 @@
+//! # Block Layout Pass
+//!
+//! Codegen emits the basic blocks in index order, and elides the jump of a terminator to
+//! the block emitted right after it. This pass reorders the blocks so that each block is
+//! followed by the successor it can fall through to, which removes executed jumps, each of
+//! them costing a row of the execution trace.
+
+use index_vec::IndexVec;
+
+use super::MirPass;
+use crate::{BasicBlockId, BinaryOp, Instruction, MirFunction, Terminator};
+
+/// Block Layout Pass
+///
+/// Blocks are laid out in chains: starting from the entry block, each block is followed by
+/// its fallthrough successor while it has not been placed yet. CASM only has a `jnz`
+/// instruction, which jumps when its operand is non-zero, so the fallthrough successor of a
+/// branch is the one reached when the jump is not taken:
+/// - `jump` falls through to its target,
+/// - `if` and `switch` fall through to their `else` and default targets,
+/// - `BranchCmp` falls through to the target taken when the compared difference is zero,
+///   i.e. `then` for `==` and `else` for `!=`.
+///
+/// Cold blocks, which can only end in an abort, are kept out of the chains of the other
+/// blocks and laid out last, so that the paths that return get the fallthroughs.
+///
+/// ### Before:
+/// ```mir
+/// block_0:
+///   if %c then jump block_1 else jump block_2
+/// block_1:
+///   jump block_3
+/// block_2:
+///   jump block_3
+/// block_3:
+///   return %x
+/// ```
+///
+/// ### After:
+/// ```mir
+/// block_0:
+///   if %c then jump block_3 else jump block_1
+/// block_1:
+///   jump block_2
+/// block_2:
+///   return %x
+/// block_3:
+///   jump block_2
+/// ```
+#[derive(Debug, Default)]
+pub struct BlockLayout;
+
+impl BlockLayout {
+    /// Create a new block layout pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns the successor of a terminator that codegen reaches without a jump
+    const fn fallthrough(terminator: &Terminator) -> Option<BasicBlockId> {
+        match terminator {
+            Terminator::Jump { target } => Some(*target),
+            Terminator::If { else_target, .. } => Some(*else_target),
+            Terminator::BranchCmp {
+                op: BinaryOp::Eq | BinaryOp::U32Neq,
+                then_target,
+                ..
+            } => Some(*then_target),
+            Terminator::BranchCmp { else_target, .. } => Some(*else_target),
+            Terminator::Switch { default, .. } => Some(*default),
+            Terminator::Return { .. }
+            | Terminator::Abort { .. }
+            | Terminator::ColdAbort { .. }
+            | Terminator::Unreachable => None,
+        }
+    }
+
+    /// Returns which blocks are cold: blocks from which no path reaches a return
+    fn cold_blocks(function: &MirFunction) -> IndexVec<BasicBlockId, bool> {
+        let mut cold: IndexVec<BasicBlockId, bool> = function
+            .basic_blocks
+            .iter()
+            .map(|block| !matches!(block.terminator, Terminator::Return { .. }))
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (block_id, block) in function.basic_blocks.iter_enumerated() {
+                if cold[block_id]
+                    && block
+                        .terminator
+                        .target_blocks()
+                        .into_iter()
+                        .any(|target| !cold[target])
+                {
+                    cold[block_id] = false;
+                    changed = true;
+                }
+            }
+        }
+        cold
+    }
+
+    /// Computes the layout of the blocks, starting with the entry block
+    fn layout(function: &MirFunction) -> Vec<BasicBlockId> {
+        let cold = Self::cold_blocks(function);
+        let mut placed: IndexVec<BasicBlockId, bool> =
+            IndexVec::from_vec(vec![false; function.basic_blocks.len()]);
+        let mut order = Vec::with_capacity(function.basic_blocks.len());
+
+        // Chains start at the entry block, then at the remaining blocks in index order,
+        // which follows the source order.
+        let hot_starts = function
+            .basic_blocks
+            .indices()
+            .filter(|&block_id| !cold[block_id] && block_id != function.entry_block);
+        let cold_starts = function
+            .basic_blocks
+            .indices()
+            .filter(|&block_id| cold[block_id] && block_id != function.entry_block);
+        let starts = std::iter::once(function.entry_block)
+            .chain(hot_starts)
+            .chain(cold_starts);
+
+        for start in starts {
+            let mut current = start;
+            while !placed[current] {
+                placed[current] = true;
+                order.push(current);
+                match Self::fallthrough(&function.basic_blocks[current].terminator) {
+                    Some(next) if cold[current] || !cold[next] => current = next,
+                    _ => break,
+                }
+            }
+        }
+        order
+    }
+
+    /// Renames the block targets of a terminator
+    fn remap_terminator(terminator: &mut Terminator, map: &IndexVec<BasicBlockId, BasicBlockId>) {
+        match terminator {
+            Terminator::Jump { target } => *target = map[*target],
+            Terminator::If {
+                then_target,
+                else_target,
+                ..
+            }
+            | Terminator::BranchCmp {
+                then_target,
+                else_target,
+                ..
+            } => {
+                *then_target = map[*then_target];
+                *else_target = map[*else_target];
+            }
+            Terminator::Switch { cases, default, .. } => {
+                for (_, target) in cases {
+                    *target = map[*target];
+                }
+                *default = map[*default];
+            }
+            Terminator::Return { .. }
+            | Terminator::Abort { .. }
+            | Terminator::ColdAbort { .. }
+            | Terminator::Unreachable => {}
+        }
+    }
+}
+
+impl MirPass for BlockLayout {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        if function.basic_blocks.is_empty() {
+            return false;
+        }
+
+        let order = Self::layout(function);
+        if order.iter().copied().eq(function.basic_blocks.indices()) {
+            return false;
+        }
+
+        // Old block ID -> new block ID
+        let mut map: IndexVec<BasicBlockId, BasicBlockId> =
+            IndexVec::from_vec(vec![BasicBlockId::new(0); function.basic_blocks.len()]);
+        for (new_index, &old_id) in order.iter().enumerate() {
+            map[old_id] = BasicBlockId::new(new_index);
+        }
+
+        let mut old_blocks: IndexVec<BasicBlockId, _> = std::mem::take(&mut function.basic_blocks)
+            .into_iter()
+            .map(Some)
+            .collect();
+        for old_id in order {
+            let mut block = old_blocks[old_id].take().expect("blocks are laid out once");
+            Self::remap_terminator(&mut block.terminator, &map);
+            for pred in &mut block.preds {
+                *pred = map[*pred];
+            }
+            for sources in block
+                .instructions
+                .iter_mut()
+                .filter_map(Instruction::phi_operands_mut)
+            {
+                for (pred, _) in sources {
+                    *pred = map[*pred];
+                }
+            }
+            function.basic_blocks.push(block);
+        }
+        function.entry_block = map[function.entry_block];
+
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "BlockLayout"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InstructionKind, MirType, Value};
+
+    /// Creates a function with `count` blocks, the first one being the entry
+    fn function_with_blocks(count: usize) -> (MirFunction, Vec<BasicBlockId>) {
+        let mut function = MirFunction::new("test".to_string());
+        let mut blocks = vec![function.entry_block];
+        for _ in 1..count {
+            blocks.push(function.add_basic_block());
+        }
+        (function, blocks)
+    }
+
+    #[test]
+    fn test_if_falls_through_to_else() {
+        let (mut function, b) = function_with_blocks(4);
+        let cond = function.new_typed_value_id(MirType::bool());
+        let x = function.new_typed_value_id(MirType::felt());
+
+        function
+            .set_terminator_with_edges(b[0], Terminator::branch(Value::operand(cond), b[1], b[2]));
+        function.set_terminator_with_edges(b[1], Terminator::jump(b[3]));
+        function.set_terminator_with_edges(b[2], Terminator::jump(b[3]));
+        function.set_terminator_with_edges(b[3], Terminator::return_value(Value::operand(x)));
+
+        assert!(BlockLayout::new().run(&mut function));
+
+        // Layout: b0, b2, b3, b1
+        let blocks = &function.basic_blocks;
+        assert_eq!(
+            blocks[b[0]].terminator,
+            Terminator::branch(Value::operand(cond), b[3], b[1])
+        );
+        assert_eq!(blocks[b[1]].terminator, Terminator::jump(b[2]));
+        assert_eq!(
+            blocks[b[2]].terminator,
+            Terminator::return_value(Value::operand(x))
+        );
+        assert_eq!(blocks[b[3]].terminator, Terminator::jump(b[2]));
+        assert_eq!(blocks[b[2]].preds.len(), 2);
+        assert!(blocks[b[2]].preds.contains(&b[1]) && blocks[b[2]].preds.contains(&b[3]));
+        assert!(function.validate().is_ok());
+
+        // The layout is already optimal
+        assert!(!BlockLayout::new().run(&mut function));
+    }
+
+    #[test]
+    fn test_cold_blocks_laid_out_last() {
+        let (mut function, b) = function_with_blocks(3);
+        let x = function.new_typed_value_id(MirType::felt());
+
+        // b0: if x == 0 then abort (b1) else return (b2)
+        function.set_terminator_with_edges(
+            b[0],
+            Terminator::branch_cmp(
+                BinaryOp::Eq,
+                Value::operand(x),
+                Value::integer(0),
+                b[1],
+                b[2],
+            ),
+        );
+        function.set_terminator_with_edges(b[1], Terminator::abort("zero".to_string(), None));
+        function.set_terminator_with_edges(b[2], Terminator::return_value(Value::operand(x)));
+
+        assert!(BlockLayout::new().run(&mut function));
+
+        // Layout: b0, b2, b1
+        assert_eq!(
+            function.basic_blocks[b[0]].terminator,
+            Terminator::branch_cmp(
+                BinaryOp::Eq,
+                Value::operand(x),
+                Value::integer(0),
+                b[2],
+                b[1]
+            )
+        );
+        assert!(matches!(
+            function.basic_blocks[b[2]].terminator,
+            Terminator::Abort { .. }
+        ));
+    }
+
+    #[test]
+    fn test_remap_phi_sources() {
+        let (mut function, b) = function_with_blocks(4);
+        let cond = function.new_typed_value_id(MirType::bool());
+        let phi = function.new_typed_value_id(MirType::felt());
+
+        function
+            .set_terminator_with_edges(b[0], Terminator::branch(Value::operand(cond), b[1], b[2]));
+        function.set_terminator_with_edges(b[1], Terminator::jump(b[3]));
+        function.set_terminator_with_edges(b[2], Terminator::jump(b[3]));
+        function.basic_blocks[b[3]].push_instruction(Instruction::phi(
+            phi,
+            MirType::felt(),
+            vec![(b[1], Value::integer(1)), (b[2], Value::integer(2))],
+        ));
+        function.set_terminator_with_edges(b[3], Terminator::return_value(Value::operand(phi)));
+
+        assert!(BlockLayout::new().run(&mut function));
+
+        // Layout: b0, b2, b3, b1
+        let InstructionKind::Phi { sources, .. } =
+            &function.basic_blocks[b[2]].instructions[0].kind
+        else {
+            panic!("expected a phi");
+        };
+        assert_eq!(
+            sources,
+            &vec![(b[3], Value::integer(1)), (b[1], Value::integer(2))]
+        );
+    }
+}
@@ -3,6 +3,7 @@
 /// This module provides a single source of truth for constant evaluation
 /// to ensure consistent semantics across all optimization passes.
 use cairo_m_compiler_parser::parser::UnaryOp;
+use stwo_prover::core::fields::FieldExpOps;
 use stwo_prover::core::fields::m31::M31;
 
 use crate::{BinaryOp, Literal, Value};
@@ -54,6 +55,11 @@ impl ConstEvaluator {
                 Some(Literal::Integer(result.0))
             }
 
+            (BinaryOp::Pow, Literal::Integer(a), Literal::Integer(b)) => {
+                let result = M31::from(a).pow(b as u128);
+                Some(Literal::Integer(result.0))
+            }
+
             // Felt comparisons - compare as field elements
             (BinaryOp::Eq, Literal::Integer(a), Literal::Integer(b)) => {
                 Some(Literal::Boolean(a == b))
@@ -125,6 +131,14 @@ impl ConstEvaluator {
                 Some(Literal::Integer(result))
             }
 
+            // U32 shifts - the shift amount is taken modulo 32
+            (BinaryOp::U32ShiftLeft, Literal::Integer(a), Literal::Integer(b)) => {
+                Some(Literal::Integer(a.wrapping_shl(b)))
+            }
+            (BinaryOp::U32ShiftRight, Literal::Integer(a), Literal::Integer(b)) => {
+                Some(Literal::Integer(a.wrapping_shr(b)))
+            }
+
             // Boolean operations
             (BinaryOp::And, Literal::Boolean(a), Literal::Boolean(b)) => {
                 Some(Literal::Boolean(a && b))
@@ -233,6 +247,15 @@ mod tests {
             assert_eq!(result, Some(Literal::Integer(expected.0)));
         }
 
+        #[test]
+        fn test_felt_pow(a in 0..P, exponent in 2u32..=8) {
+            let evaluator = ConstEvaluator::new();
+
+            let result = evaluator.eval_binary_op(BinaryOp::Pow, Literal::Integer(a), Literal::Integer(exponent));
+            let expected = (1..exponent).fold(M31::from(a), |acc, _| acc * M31::from(a));
+            assert_eq!(result, Some(Literal::Integer(expected.0)));
+        }
+
         #[test]
         fn test_felt_division_modular_inverse(a in 1..P, b in 1..P) {
             let evaluator = ConstEvaluator::new();
@@ -380,6 +403,17 @@ mod tests {
             assert_eq!(result, Some(Literal::Integer(a ^ b)));
         }
 
+        #[test]
+        fn test_u32_shift_operations(a in any::<u32>(), b in any::<u32>()) {
+            let evaluator = ConstEvaluator::new();
+
+            let result = evaluator.eval_binary_op(BinaryOp::U32ShiftLeft, Literal::Integer(a), Literal::Integer(b));
+            assert_eq!(result, Some(Literal::Integer(a << (b % 32))));
+
+            let result = evaluator.eval_binary_op(BinaryOp::U32ShiftRight, Literal::Integer(a), Literal::Integer(b));
+            assert_eq!(result, Some(Literal::Integer(a >> (b % 32))));
+        }
+
         #[test]
         fn test_u32_bitwise_associativity(a in any::<u32>(), b in any::<u32>(), c in any::<u32>()) {
             let evaluator = ConstEvaluator::new();
@@ -0,0 +1,188 @@
+//! # Constant Pooling Pass
+//!
+//! Array literals are built element by element on the stack each time they are evaluated,
+//! even when all their elements are known at compile time and the array is only read. This
+//! pass marks such arrays as constant, so that codegen pools their data in the read-only
+//! data section of the program, shared by all identical arrays, and only stores a pointer to
+//! it instead of building the array.
+
+use super::MirPass;
+use super::array_dematerialization::ArrayDematerialization;
+use crate::{InstructionKind, MirFunction, MirType, ValueId};
+
+/// Constant Pooling Pass
+///
+/// An array built by a non-const `MakeFixedArray` is pooled if:
+/// - its elements are all literals of a scalar type, which codegen can lay out as data;
+/// - its address never escapes, see [`ArrayDematerialization::escapes`];
+/// - it is never stored to, as pooled data is read-only and shared.
+///
+/// Arrays only accessed at constant indices are usually scalarized by SROA beforehand, so the
+/// pass mostly applies to lookup tables indexed dynamically. Struct and tuple literals are not
+/// pooled: SROA replaces their fields by scalars, and constant fields by their values.
+///
+/// ### Before:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 4, 8]
+/// %1 = load %0[%i]
+/// ```
+///
+/// ### After:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 4, 8] // is_const: emitted as read-only data
+/// %1 = load %0[%i]
+/// ```
+#[derive(Debug, Default)]
+pub struct ConstantPooling;
+
+impl ConstantPooling {
+    /// Create a new constant pooling pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns true if `array` is only read, through element loads
+    fn is_read_only(function: &MirFunction, array: ValueId) -> bool {
+        if ArrayDematerialization::escapes(function, array) {
+            return false;
+        }
+        !function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .any(|instruction| {
+                matches!(instruction.kind, InstructionKind::Store { .. })
+                    && ArrayDematerialization::element_index(instruction, array).is_some()
+            })
+    }
+}
+
+impl MirPass for ConstantPooling {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        let candidates: Vec<ValueId> = function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::MakeFixedArray {
+                    dest,
+                    elements,
+                    element_ty,
+                    is_const: false,
+                } if matches!(element_ty, MirType::Felt | MirType::Bool | MirType::U32)
+                    && elements.iter().all(|element| element.is_literal()) =>
+                {
+                    Some(*dest)
+                }
+                _ => None,
+            })
+            .filter(|&array| Self::is_read_only(function, array))
+            .collect();
+        if candidates.is_empty() {
+            return false;
+        }
+
+        for block in function.basic_blocks.iter_mut() {
+            for instruction in &mut block.instructions {
+                if let InstructionKind::MakeFixedArray { dest, is_const, .. } =
+                    &mut instruction.kind
+                {
+                    if candidates.contains(dest) {
+                        *is_const = true;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn name(&self) -> &'static str {
+        "ConstantPooling"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Instruction, Place, Terminator, Value};
+
+    /// Builds a felt array of `elements`, with `%i` as a parameter, and loads `%array[%i]`
+    fn function_with_lookup(elements: Vec<Value>) -> (MirFunction, ValueId, ValueId) {
+        let mut function = MirFunction::new("test".to_string());
+        let i = function.new_typed_value_id(MirType::felt());
+        function.parameters = vec![i];
+        let array = function.new_typed_value_id(MirType::FixedArray {
+            element_type: Box::new(MirType::felt()),
+            size: elements.len(),
+        });
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::make_fixed_array(
+            array,
+            elements,
+            MirType::felt(),
+        ));
+        block.push_instruction(Instruction::load(
+            x,
+            Place::new(array).with_index(Value::operand(i)),
+            MirType::felt(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+        (function, array, i)
+    }
+
+    fn is_pooled(function: &MirFunction) -> bool {
+        function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .any(|instruction| {
+                matches!(
+                    instruction.kind,
+                    InstructionKind::MakeFixedArray { is_const: true, .. }
+                )
+            })
+    }
+
+    #[test]
+    fn test_pools_constant_lookup_table() {
+        let elements = (0..16).map(|n| Value::integer(1 << n)).collect();
+        let (mut function, _, _) = function_with_lookup(elements);
+
+        assert!(ConstantPooling::new().run(&mut function));
+        assert!(is_pooled(&function));
+
+        // Already pooled arrays are left as is
+        assert!(!ConstantPooling::new().run(&mut function));
+    }
+
+    #[test]
+    fn test_non_literal_elements_are_not_pooled() {
+        let (mut function, _, i) = function_with_lookup(vec![Value::integer(1), Value::integer(2)]);
+        let entry = function.entry_block;
+        if let InstructionKind::MakeFixedArray { elements, .. } =
+            &mut function.basic_blocks[entry].instructions[0].kind
+        {
+            elements[1] = Value::operand(i);
+        }
+
+        assert!(!ConstantPooling::new().run(&mut function));
+        assert!(!is_pooled(&function));
+    }
+
+    #[test]
+    fn test_stored_array_is_not_pooled() {
+        let (mut function, array, i) =
+            function_with_lookup(vec![Value::integer(1), Value::integer(2)]);
+        let entry = function.entry_block;
+        function.basic_blocks[entry].instructions.insert(
+            1,
+            Instruction::store(
+                Place::new(array).with_index(Value::operand(i)),
+                Value::integer(3),
+                MirType::felt(),
+            ),
+        );
+
+        assert!(!ConstantPooling::new().run(&mut function));
+        assert!(!is_pooled(&function));
+    }
+}
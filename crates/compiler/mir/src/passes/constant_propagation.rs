@@ -149,12 +149,14 @@ impl ConstantPropagation {
             | K::InsertField { .. }
             | K::InsertTuple { .. }
             | K::MakeFixedArray { .. }
+            | K::CopyAggregate { .. }
             | K::HeapAllocCells { .. }
             | K::Cast { .. }
             | K::Call { .. }
             | K::Debug { .. }
             | K::Nop
-            | K::AssertEq { .. } => NonConst,
+            | K::AssertEq { .. }
+            | K::DebugPrint { .. } => NonConst,
         };
 
         Some((dest, lattice))
@@ -270,6 +272,9 @@ impl ConstantPropagation {
                             replace_value(e, state, &mut modified);
                         }
                     }
+                    InstructionKind::CopyAggregate { source, .. } => {
+                        replace_value(source, state, &mut modified);
+                    }
                     InstructionKind::HeapAllocCells { cells, .. } => {
                         replace_value(cells, state, &mut modified);
                     }
@@ -277,7 +282,10 @@ impl ConstantPropagation {
                         replace_value(left, state, &mut modified);
                         replace_value(right, state, &mut modified);
                     }
-                    InstructionKind::Nop => {}
+                    InstructionKind::DebugPrint { value, .. } => {
+                        replace_value(value, state, &mut modified);
+                    }
+                    InstructionKind::LoadEnv { .. } | InstructionKind::Nop => {}
                 }
             }
 
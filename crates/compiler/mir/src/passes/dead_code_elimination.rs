@@ -63,6 +63,31 @@ impl DeadCodeElimination {
                     else_target,
                 }
             }
+            Terminator::Switch {
+                op,
+                value,
+                cases,
+                default,
+            } => {
+                let cases = cases
+                    .into_iter()
+                    .map(|(case, target)| {
+                        let target = *map
+                            .get(&target)
+                            .expect("case target should be reachable after DCE");
+                        (case, target)
+                    })
+                    .collect();
+                let default = *map
+                    .get(&default)
+                    .expect("default target should be reachable after DCE");
+                Terminator::Switch {
+                    op,
+                    value,
+                    cases,
+                    default,
+                }
+            }
             // Remap an unconditional jump.
             // Note: `Terminator::jump(new_target)` is the canonical constructor.
             Terminator::Jump { target } => {
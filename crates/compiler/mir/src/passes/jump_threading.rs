@@ -0,0 +1,271 @@
+//! # Jump Threading Pass
+//!
+//! This pass collapses branch-to-branch chains: an edge to an empty block that only jumps
+//! elsewhere is redirected to the final destination of the chain.
+
+use std::collections::HashSet;
+
+use super::MirPass;
+use crate::{BasicBlockId, MirFunction, Terminator};
+
+/// Jump Threading Pass
+///
+/// Lowering and branch simplification leave behind empty blocks whose only purpose is to
+/// forward control flow. This pass threads every edge through such blocks, which then
+/// become unreachable and are removed by dead code elimination.
+///
+/// ### Before:
+/// ```mir
+/// block_0:
+///   if %c then jump block_1 else jump block_2
+/// block_1:
+///   jump block_3
+/// ```
+///
+/// ### After:
+/// ```mir
+/// block_0:
+///   if %c then jump block_3 else jump block_2
+/// ```
+///
+/// Edges into blocks starting with phi nodes are not threaded, as phi sources are keyed by
+/// predecessor. A conditional branch whose targets all end up being the same block is
+/// replaced by a jump.
+#[derive(Debug, Default)]
+pub struct JumpThreading;
+
+impl JumpThreading {
+    /// Create a new jump threading pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns true if `block_id` starts with phi nodes
+    fn has_phis(function: &MirFunction, block_id: BasicBlockId) -> bool {
+        function
+            .get_basic_block(block_id)
+            .is_some_and(|block| block.phi_instructions().next().is_some())
+    }
+
+    /// Returns the target of `block_id` if it is an empty block ending in a jump that can be
+    /// threaded through
+    fn forwarding_target(function: &MirFunction, block_id: BasicBlockId) -> Option<BasicBlockId> {
+        let block = function.get_basic_block(block_id)?;
+        if !block.instructions.is_empty() {
+            return None;
+        }
+        match block.terminator {
+            Terminator::Jump { target } if !Self::has_phis(function, target) => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Follows the chain of forwarding blocks starting at `target`
+    ///
+    /// Chains forming a cycle (an empty infinite loop) stop at the first repeated block.
+    fn thread_target(function: &MirFunction, target: BasicBlockId) -> BasicBlockId {
+        let mut visited = HashSet::new();
+        let mut current = target;
+        while visited.insert(current) {
+            match Self::forwarding_target(function, current) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        current
+    }
+}
+
+impl MirPass for JumpThreading {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        let mut modified = false;
+
+        // Collect block IDs to avoid borrowing issues
+        let block_ids: Vec<BasicBlockId> = function.basic_blocks.indices().collect();
+
+        for block_id in block_ids {
+            let old_terminator = function.basic_blocks[block_id].terminator.clone();
+            let mut new_terminator = old_terminator.clone();
+
+            for target in old_terminator.target_blocks() {
+                let threaded = Self::thread_target(function, target);
+                if threaded != target {
+                    new_terminator.replace_target(target, threaded);
+                }
+            }
+
+            // A conditional branch to a single block no longer needs its condition
+            if new_terminator.is_conditional() {
+                let targets = new_terminator.target_blocks();
+                let first = targets[0];
+                if targets.iter().all(|&target| target == first) && !Self::has_phis(function, first)
+                {
+                    new_terminator = Terminator::jump(first);
+                }
+            }
+
+            if new_terminator != old_terminator {
+                function.set_terminator_with_edges(block_id, new_terminator);
+                modified = true;
+            }
+        }
+
+        modified
+    }
+
+    fn name(&self) -> &'static str {
+        "JumpThreading"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Instruction, MirType, Value};
+
+    /// Creates a function with `count` blocks, the first one being the entry
+    fn function_with_blocks(count: usize) -> (MirFunction, Vec<BasicBlockId>) {
+        let mut function = MirFunction::new("test".to_string());
+        let mut blocks = vec![function.entry_block];
+        for _ in 1..count {
+            blocks.push(function.add_basic_block());
+        }
+        (function, blocks)
+    }
+
+    fn set_terminator(function: &mut MirFunction, block_id: BasicBlockId, terminator: Terminator) {
+        function.set_terminator_with_edges(block_id, terminator);
+    }
+
+    #[test]
+    fn test_thread_jump_chain() {
+        let (mut function, b) = function_with_blocks(5);
+        let cond = function.new_typed_value_id(MirType::bool());
+
+        // b0: if %c then jump b1 else jump b2; b1: jump b3; b3: jump b4
+        set_terminator(
+            &mut function,
+            b[0],
+            Terminator::branch(Value::operand(cond), b[1], b[2]),
+        );
+        set_terminator(&mut function, b[1], Terminator::jump(b[3]));
+        set_terminator(&mut function, b[2], Terminator::return_void());
+        set_terminator(&mut function, b[3], Terminator::jump(b[4]));
+        set_terminator(&mut function, b[4], Terminator::return_void());
+
+        let mut pass = JumpThreading::new();
+        assert!(pass.run(&mut function));
+
+        assert_eq!(
+            function.basic_blocks[b[0]].terminator,
+            Terminator::branch(Value::operand(cond), b[4], b[2])
+        );
+        assert!(function.basic_blocks[b[4]].preds.contains(&b[0]));
+        assert!(!function.basic_blocks[b[1]].preds.contains(&b[0]));
+    }
+
+    #[test]
+    fn test_branch_to_single_target_becomes_jump() {
+        let (mut function, b) = function_with_blocks(4);
+        let cond = function.new_typed_value_id(MirType::bool());
+
+        // b0: if %c then jump b1 else jump b2; b1: jump b3; b2: jump b3
+        set_terminator(
+            &mut function,
+            b[0],
+            Terminator::branch(Value::operand(cond), b[1], b[2]),
+        );
+        set_terminator(&mut function, b[1], Terminator::jump(b[3]));
+        set_terminator(&mut function, b[2], Terminator::jump(b[3]));
+        set_terminator(&mut function, b[3], Terminator::return_void());
+
+        let mut pass = JumpThreading::new();
+        assert!(pass.run(&mut function));
+
+        assert_eq!(
+            function.basic_blocks[b[0]].terminator,
+            Terminator::jump(b[3])
+        );
+    }
+
+    #[test]
+    fn test_switch_targets_are_threaded() {
+        let (mut function, b) = function_with_blocks(4);
+        let value = function.new_typed_value_id(MirType::felt());
+
+        // b0: switch %v == [1 => jump b1] else jump b2; b1: jump b3
+        set_terminator(
+            &mut function,
+            b[0],
+            Terminator::switch(
+                crate::BinaryOp::Eq,
+                Value::operand(value),
+                vec![(Value::integer(1), b[1])],
+                b[2],
+            ),
+        );
+        set_terminator(&mut function, b[1], Terminator::jump(b[3]));
+        set_terminator(&mut function, b[2], Terminator::return_void());
+        set_terminator(&mut function, b[3], Terminator::return_void());
+
+        let mut pass = JumpThreading::new();
+        assert!(pass.run(&mut function));
+
+        assert_eq!(
+            function.basic_blocks[b[0]].terminator,
+            Terminator::switch(
+                crate::BinaryOp::Eq,
+                Value::operand(value),
+                vec![(Value::integer(1), b[3])],
+                b[2],
+            )
+        );
+    }
+
+    #[test]
+    fn test_no_threading_into_phi_block() {
+        let (mut function, b) = function_with_blocks(4);
+        let cond = function.new_typed_value_id(MirType::bool());
+        let dest = function.new_typed_value_id(MirType::felt());
+
+        // b0: if %c then jump b1 else jump b2; b1: jump b3; b2: jump b3; b3: phi
+        set_terminator(
+            &mut function,
+            b[0],
+            Terminator::branch(Value::operand(cond), b[1], b[2]),
+        );
+        set_terminator(&mut function, b[1], Terminator::jump(b[3]));
+        set_terminator(&mut function, b[2], Terminator::jump(b[3]));
+        function.basic_blocks[b[3]].push_instruction(Instruction::phi(
+            dest,
+            MirType::felt(),
+            vec![(b[1], Value::integer(1)), (b[2], Value::integer(2))],
+        ));
+        set_terminator(
+            &mut function,
+            b[3],
+            Terminator::return_value(Value::operand(dest)),
+        );
+
+        let mut pass = JumpThreading::new();
+        assert!(!pass.run(&mut function));
+    }
+
+    #[test]
+    fn test_empty_loop_terminates() {
+        let (mut function, b) = function_with_blocks(3);
+
+        // b0: jump b1; b1: jump b2; b2: jump b1
+        set_terminator(&mut function, b[0], Terminator::jump(b[1]));
+        set_terminator(&mut function, b[1], Terminator::jump(b[2]));
+        set_terminator(&mut function, b[2], Terminator::jump(b[1]));
+
+        let mut pass = JumpThreading::new();
+        pass.run(&mut function);
+
+        assert!(matches!(
+            function.basic_blocks[b[0]].terminator,
+            Terminator::Jump { .. }
+        ));
+    }
+}
@@ -0,0 +1,464 @@
+//! # Load/Store Forwarding Pass
+//!
+//! Arrays that SROA cannot scalarize stay in memory, where each access is a memory row of
+//! the trace. Many of those accesses are redundant: a load following a store to the same
+//! element reads back the stored value. This pass tracks the contents of the arrays along
+//! the CFG and removes the accesses whose outcome is already known.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::MirPass;
+use super::array_dematerialization::ArrayDematerialization;
+use crate::cfg::{reachable_predecessors, reverse_postorder};
+use crate::{BasicBlockId, Instruction, InstructionKind, MirFunction, Value, ValueId};
+
+/// Known contents of the arrays at a program point: the value held by an element,
+/// identified by its array and its index
+type MemoryState = FxHashMap<(ValueId, Value), Value>;
+
+/// An access whose outcome is known
+enum KnownAccess {
+    /// A load of an element holding the given value
+    Load(Value),
+    /// A store of the value the element already holds
+    Store,
+}
+
+/// Load/Store Forwarding Pass
+///
+/// Only the arrays built by `MakeFixedArray` whose address never escapes are tracked (see
+/// [`ArrayDematerialization`]), so that no call or copy can access their memory.
+///
+/// Each store defines a new version of the memory of its array, in the manner of memory
+/// SSA: the element it writes holds the stored value, and the elements it may overwrite
+/// are forgotten. Two indices refer to distinct elements only if they are distinct
+/// constants. At control flow joins, only the contents known on every incoming edge are
+/// kept. Given these contents:
+/// - a load of an element with a known value is replaced by that value,
+/// - a store of the value an element already holds is removed,
+/// - the stores to an array that is never loaded from are removed.
+///
+/// The contents mentioning a value are forgotten when that value is defined again, which
+/// keeps them valid across loop iterations.
+///
+/// ### Before:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 3]
+/// store %x -> %0[%i]
+/// %1 = load %0[%i]
+/// %2 = load %0[1]
+/// ```
+///
+/// ### After:
+/// ```mir
+/// %0 = makefixedarray [1, 2, 3]
+/// store %x -> %0[%i]
+/// %1 = %x
+/// %2 = load %0[1]
+/// ```
+#[derive(Debug, Default)]
+pub struct LoadStoreForwarding;
+
+impl LoadStoreForwarding {
+    /// Create a new load/store forwarding pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns `true` if the indices `a` and `b` may refer to the same element
+    const fn may_alias(a: &Value, b: &Value) -> bool {
+        match (a.as_const_integer(), b.as_const_integer()) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+
+    /// Returns the tracked array accessed by a load or a store, with the index of the
+    /// accessed element
+    fn tracked_access(
+        instruction: &Instruction,
+        arrays: &FxHashSet<ValueId>,
+    ) -> Option<(ValueId, Value)> {
+        let (InstructionKind::Load { place, .. } | InstructionKind::Store { place, .. }) =
+            &instruction.kind
+        else {
+            return None;
+        };
+        if !arrays.contains(&place.base) {
+            return None;
+        }
+        ArrayDematerialization::element_index(instruction, place.base)
+            .map(|index| (place.base, *index))
+    }
+
+    /// Updates `state` with the effect of `instruction`, and returns the access performed
+    /// by the instruction if its outcome is known
+    fn transfer(
+        instruction: &Instruction,
+        arrays: &FxHashSet<ValueId>,
+        state: &mut MemoryState,
+    ) -> Option<KnownAccess> {
+        for dest in instruction.destinations() {
+            let defined = Value::operand(dest);
+            state.retain(|(array, index), value| {
+                *array != dest && *index != defined && *value != defined
+            });
+        }
+
+        match &instruction.kind {
+            InstructionKind::MakeFixedArray { dest, elements, .. } if arrays.contains(dest) => {
+                for (index, element) in elements.iter().enumerate() {
+                    state.insert((*dest, Value::integer(index as u32)), *element);
+                }
+                None
+            }
+            InstructionKind::Load { dest, .. } => {
+                let element = Self::tracked_access(instruction, arrays)?;
+                if let Some(value) = state.get(&element) {
+                    return Some(KnownAccess::Load(*value));
+                }
+                state.insert(element, Value::operand(*dest));
+                None
+            }
+            InstructionKind::Store { value, .. } => {
+                let (array, index) = Self::tracked_access(instruction, arrays)?;
+                if state.get(&(array, index)) == Some(value) {
+                    return Some(KnownAccess::Store);
+                }
+                state.retain(|(other_array, other_index), _| {
+                    *other_array != array || !Self::may_alias(other_index, &index)
+                });
+                state.insert((array, index), *value);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Keeps the contents known at the end of every predecessor that was already analyzed
+    fn merge(
+        preds: &[BasicBlockId],
+        exit_states: &FxHashMap<BasicBlockId, MemoryState>,
+    ) -> MemoryState {
+        let mut states = preds.iter().filter_map(|pred| exit_states.get(pred));
+        let Some(first) = states.next() else {
+            return MemoryState::default();
+        };
+        let mut merged = first.clone();
+        for state in states {
+            merged.retain(|element, value| state.get(element) == Some(value));
+        }
+        merged
+    }
+
+    /// Computes the contents known at the start of each reachable block
+    fn analyze(
+        function: &MirFunction,
+        arrays: &FxHashSet<ValueId>,
+    ) -> FxHashMap<BasicBlockId, MemoryState> {
+        let order = reverse_postorder(function);
+        let preds = reachable_predecessors(function);
+        let mut entry_states: FxHashMap<BasicBlockId, MemoryState> = FxHashMap::default();
+        let mut exit_states: FxHashMap<BasicBlockId, MemoryState> = FxHashMap::default();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block_id in &order {
+                let mut state = if block_id == function.entry_block {
+                    MemoryState::default()
+                } else {
+                    Self::merge(
+                        preds.get(&block_id).map_or(&[], Vec::as_slice),
+                        &exit_states,
+                    )
+                };
+                entry_states.insert(block_id, state.clone());
+                for instruction in &function.basic_blocks[block_id].instructions {
+                    Self::transfer(instruction, arrays, &mut state);
+                }
+                if exit_states.get(&block_id) != Some(&state) {
+                    exit_states.insert(block_id, state);
+                    changed = true;
+                }
+            }
+        }
+        entry_states
+    }
+
+    /// Replaces the loads of known elements by their value, and removes the stores of the
+    /// values elements already hold
+    fn forward(
+        function: &mut MirFunction,
+        arrays: &FxHashSet<ValueId>,
+        entry_states: FxHashMap<BasicBlockId, MemoryState>,
+    ) -> bool {
+        let mut modified = false;
+        for (block_id, mut state) in entry_states {
+            let block = &mut function.basic_blocks[block_id];
+            let mut redundant_stores = Vec::new();
+            for (i, instruction) in block.instructions.iter_mut().enumerate() {
+                match Self::transfer(instruction, arrays, &mut state) {
+                    Some(KnownAccess::Load(value)) => {
+                        if let InstructionKind::Load { dest, ty, .. } = &instruction.kind {
+                            instruction.kind = InstructionKind::Assign {
+                                dest: *dest,
+                                source: value,
+                                ty: ty.clone(),
+                            };
+                            modified = true;
+                        }
+                    }
+                    Some(KnownAccess::Store) => redundant_stores.push(i),
+                    None => {}
+                }
+            }
+            for &i in redundant_stores.iter().rev() {
+                block.instructions.remove(i);
+                modified = true;
+            }
+        }
+        modified
+    }
+
+    /// Removes the stores to the arrays that are never loaded from
+    fn remove_unread_stores(function: &mut MirFunction, arrays: &FxHashSet<ValueId>) -> bool {
+        let loaded: FxHashSet<ValueId> = function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::Load { place, .. } => Some(place.base),
+                _ => None,
+            })
+            .collect();
+
+        let mut modified = false;
+        for block in function.basic_blocks.iter_mut() {
+            let len = block.instructions.len();
+            block.instructions.retain(|instruction| {
+                !matches!(
+                    &instruction.kind,
+                    InstructionKind::Store { place, .. }
+                        if arrays.contains(&place.base) && !loaded.contains(&place.base)
+                )
+            });
+            modified |= block.instructions.len() != len;
+        }
+        modified
+    }
+}
+
+impl MirPass for LoadStoreForwarding {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        let candidates: Vec<ValueId> = function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .filter_map(|instruction| match &instruction.kind {
+                InstructionKind::MakeFixedArray { dest, .. } => Some(*dest),
+                _ => None,
+            })
+            .collect();
+        let arrays: FxHashSet<ValueId> = candidates
+            .into_iter()
+            .filter(|&array| !ArrayDematerialization::escapes(function, array))
+            .collect();
+        if arrays.is_empty() {
+            return false;
+        }
+
+        let entry_states = Self::analyze(function, &arrays);
+        let mut modified = Self::forward(function, &arrays, entry_states);
+        modified |= Self::remove_unread_stores(function, &arrays);
+        modified
+    }
+
+    fn name(&self) -> &'static str {
+        "LoadStoreForwarding"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinaryOp, MirType, Place, Terminator};
+
+    fn array_ty() -> MirType {
+        MirType::FixedArray {
+            element_type: Box::new(MirType::felt()),
+            size: 3,
+        }
+    }
+
+    /// Builds `[1, 2, 3]` in the entry block, with `%i` and `%j` as parameters
+    fn function_with_array() -> (MirFunction, ValueId, ValueId, ValueId) {
+        let mut function = MirFunction::new("test".to_string());
+        let i = function.new_typed_value_id(MirType::felt());
+        let j = function.new_typed_value_id(MirType::felt());
+        function.parameters = vec![i, j];
+        let array = function.new_typed_value_id(array_ty());
+        let entry = function.entry_block;
+        function.basic_blocks[entry].push_instruction(Instruction::make_fixed_array(
+            array,
+            vec![Value::integer(1), Value::integer(2), Value::integer(3)],
+            MirType::felt(),
+        ));
+        (function, array, i, j)
+    }
+
+    fn element(array: ValueId, index: Value) -> Place {
+        Place::new(array).with_index(index)
+    }
+
+    fn store(array: ValueId, index: Value, value: Value) -> Instruction {
+        Instruction::store(element(array, index), value, MirType::felt())
+    }
+
+    fn load(dest: ValueId, array: ValueId, index: Value) -> Instruction {
+        Instruction::load(dest, element(array, index), MirType::felt())
+    }
+
+    /// The value assigned to `dest`, if its load was forwarded
+    fn forwarded_value(function: &MirFunction, dest: ValueId) -> Option<Value> {
+        function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .find_map(|instruction| match &instruction.kind {
+                InstructionKind::Assign {
+                    dest: assigned,
+                    source,
+                    ..
+                } if *assigned == dest => Some(*source),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_forwards_stored_value() {
+        let (mut function, array, i, _) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let y = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(store(array, Value::operand(i), Value::integer(5)));
+        block.push_instruction(load(x, array, Value::operand(i)));
+        block.push_instruction(load(y, array, Value::integer(1)));
+        block.set_terminator(Terminator::return_values(vec![
+            Value::operand(x),
+            Value::operand(y),
+        ]));
+
+        assert!(LoadStoreForwarding::new().run(&mut function));
+
+        assert_eq!(forwarded_value(&function, x), Some(Value::integer(5)));
+        // The dynamic store may have overwritten the element 1
+        assert_eq!(forwarded_value(&function, y), None);
+    }
+
+    #[test]
+    fn test_interfering_store_prevents_forwarding() {
+        let (mut function, array, i, j) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let y = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(store(array, Value::operand(i), Value::integer(5)));
+        block.push_instruction(store(array, Value::integer(0), Value::integer(6)));
+        block.push_instruction(store(array, Value::operand(j), Value::integer(7)));
+        block.push_instruction(store(array, Value::integer(1), Value::integer(8)));
+        block.push_instruction(load(x, array, Value::operand(i)));
+        block.push_instruction(load(y, array, Value::integer(1)));
+        block.set_terminator(Terminator::return_values(vec![
+            Value::operand(x),
+            Value::operand(y),
+        ]));
+
+        assert!(LoadStoreForwarding::new().run(&mut function));
+
+        // `%j` may be equal to `%i`, and the element 1 was stored last
+        assert_eq!(forwarded_value(&function, x), None);
+        assert_eq!(forwarded_value(&function, y), Some(Value::integer(8)));
+    }
+
+    #[test]
+    fn test_removes_redundant_and_unread_stores() {
+        let (mut function, array, i, _) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        // Element 0 already holds 1
+        block.push_instruction(store(array, Value::integer(0), Value::integer(1)));
+        block.push_instruction(load(x, array, Value::operand(i)));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(LoadStoreForwarding::new().run(&mut function));
+        assert_eq!(function.basic_blocks[entry].instructions.len(), 2);
+
+        // Once its loads are forwarded, an array is only written to
+        let (mut function, array, i, _) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(store(array, Value::operand(i), Value::integer(5)));
+        block.push_instruction(load(x, array, Value::operand(i)));
+        block.set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(LoadStoreForwarding::new().run(&mut function));
+        let instructions = &function.basic_blocks[entry].instructions;
+        assert!(
+            !instructions
+                .iter()
+                .any(|instruction| matches!(instruction.kind, InstructionKind::Store { .. })),
+            "expected the stores to be removed, got {instructions:?}"
+        );
+    }
+
+    #[test]
+    fn test_loop_store_is_not_forwarded_to_header() {
+        // entry: array = [1, 2, 3]; jump header
+        // header: %x = load array[0]; if %c then jump body else jump exit
+        // body: %d = %x + 1; store %d -> array[0]; jump header
+        // exit: return %x
+        let (mut function, array, _, _) = function_with_array();
+        let c = function.new_typed_value_id(MirType::bool());
+        let x = function.new_typed_value_id(MirType::felt());
+        let d = function.new_typed_value_id(MirType::felt());
+        function.parameters.push(c);
+        let entry = function.entry_block;
+        let header = function.add_basic_block();
+        let body = function.add_basic_block();
+        let exit = function.add_basic_block();
+
+        function.basic_blocks[entry].set_terminator(Terminator::jump(header));
+        let block = &mut function.basic_blocks[header];
+        block.push_instruction(load(x, array, Value::integer(0)));
+        block.set_terminator(Terminator::branch(Value::operand(c), body, exit));
+        let block = &mut function.basic_blocks[body];
+        block.push_instruction(Instruction::binary_op(
+            BinaryOp::Add,
+            d,
+            Value::operand(x),
+            Value::integer(1),
+        ));
+        block.push_instruction(store(array, Value::integer(0), Value::operand(d)));
+        block.set_terminator(Terminator::jump(header));
+        function.basic_blocks[exit].set_terminator(Terminator::return_value(Value::operand(x)));
+
+        assert!(!LoadStoreForwarding::new().run(&mut function));
+        assert_eq!(forwarded_value(&function, x), None);
+    }
+
+    #[test]
+    fn test_escaping_array_is_kept() {
+        let (mut function, array, i, _) = function_with_array();
+        let x = function.new_typed_value_id(MirType::felt());
+        let entry = function.entry_block;
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(store(array, Value::operand(i), Value::integer(5)));
+        block.push_instruction(load(x, array, Value::operand(i)));
+        block.set_terminator(Terminator::return_values(vec![
+            Value::operand(x),
+            Value::operand(array),
+        ]));
+
+        assert!(!LoadStoreForwarding::new().run(&mut function));
+    }
+}
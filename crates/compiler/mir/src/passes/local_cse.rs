@@ -170,7 +170,10 @@ impl PureExpressionKey {
             | InstructionKind::Phi { .. }
             | InstructionKind::Nop
             | InstructionKind::HeapAllocCells { .. }
-            | InstructionKind::AssertEq { .. } => None,
+            | InstructionKind::LoadEnv { .. }
+            | InstructionKind::CopyAggregate { .. }
+            | InstructionKind::AssertEq { .. }
+            | InstructionKind::DebugPrint { .. } => None,
 
             // Aggregate modification operations - skip for conservatism
             InstructionKind::InsertField { .. } | InstructionKind::InsertTuple { .. } => None,
@@ -9,6 +9,7 @@
 //! 1. **Critical Edge Splitting**: Ensures unambiguous placement of copy instructions
 //! 2. **Phi Decomposition**: Replaces phi-nodes with explicit copy instructions
 //! 3. **Copy Sequencing**: Handles circular dependencies (the "lost copy problem")
+//! 4. **Aggregate Copies**: Emits struct and tuple copies as `CopyAggregate` block copies
 //!
 //! ## References
 //!
@@ -20,7 +21,7 @@ use std::collections::{HashSet, VecDeque};
 use rustc_hash::FxHashMap;
 
 use crate::{
-    BasicBlockId, Instruction, InstructionKind, MirFunction, MirPass, Value, ValueId, cfg,
+    BasicBlockId, Instruction, InstructionKind, MirFunction, MirPass, MirType, Value, ValueId, cfg,
 };
 
 /// Statistics for phi elimination
@@ -397,6 +398,19 @@ impl PhiElimination {
         sorted
     }
 
+    /// Turns a copy of a struct or tuple operand into a `CopyAggregate`, so that codegen
+    /// copies its slots as a block
+    fn lower_aggregate_copy(copy: Instruction) -> Instruction {
+        match copy.kind {
+            InstructionKind::Assign {
+                dest,
+                source: source @ Value::Operand(_),
+                ty: ty @ (MirType::Struct { .. } | MirType::Tuple(_)),
+            } => Instruction::copy_aggregate(dest, source, ty),
+            kind => Instruction { kind, ..copy },
+        }
+    }
+
     /// Insert copy instructions into predecessor blocks
     fn insert_copies(
         &mut self,
@@ -426,6 +440,7 @@ impl PhiElimination {
 
                 // Insert all copy instructions
                 for instr in sequenced {
+                    let instr = Self::lower_aggregate_copy(instr);
                     if self.debug {
                         eprintln!("  Inserting: {:?}", instr.kind);
                     }
@@ -518,7 +533,7 @@ impl MirPass for PhiElimination {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Literal, MirType, Terminator};
+    use crate::{Literal, Terminator};
 
     /// Create a simple diamond CFG with a phi node
     fn create_diamond_cfg_with_phi() -> MirFunction {
@@ -749,4 +764,42 @@ mod tests {
         // We expect more copies than phi nodes due to the temporary
         assert!(pass.stats.copies_inserted >= pass.stats.phis_eliminated);
     }
+
+    #[test]
+    fn test_aggregate_phi_copies_are_block_copies() {
+        let mut function = MirFunction::new("test_aggregate".to_string());
+        let entry = function.add_basic_block();
+        let merge = function.add_basic_block();
+        function.entry_block = entry;
+
+        let pair_ty = MirType::Tuple(vec![MirType::Felt, MirType::Felt]);
+        let pair = function.new_typed_value_id(pair_ty.clone());
+        function.basic_blocks[entry]
+            .instructions
+            .push(Instruction::make_tuple(
+                pair,
+                vec![Value::integer(1), Value::integer(2)],
+            ));
+        function.basic_blocks[entry].terminator = Terminator::Jump { target: merge };
+        function.connect(entry, merge);
+
+        let merged = function.new_typed_value_id(pair_ty.clone());
+        function.basic_blocks[merge]
+            .instructions
+            .push(Instruction::phi(
+                merged,
+                pair_ty,
+                vec![(entry, Value::Operand(pair))],
+            ));
+        function.basic_blocks[merge].terminator = Terminator::Return {
+            values: vec![Value::Operand(merged)],
+        };
+
+        assert!(PhiElimination::new().run(&mut function));
+        assert!(matches!(
+            function.basic_blocks[entry].instructions.last().unwrap().kind,
+            InstructionKind::CopyAggregate { dest, source: Value::Operand(source), .. }
+                if dest == merged && source == pair
+        ));
+    }
 }
@@ -0,0 +1,392 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::MirPass;
+use crate::cfg::{compute_dominators, get_successors, reachable_predecessors, reverse_postorder};
+use crate::{BasicBlockId, InstructionKind, MirFunction, MirType, Value, ValueId};
+
+type Dominators = FxHashMap<BasicBlockId, FxHashSet<BasicBlockId>>;
+
+/// A cast of a value, identified by its source and its source and target types
+type CastKey = (ValueId, MirType, MirType);
+
+/// A natural loop: the header and every block that reaches a back edge to it
+struct NaturalLoop {
+    header: BasicBlockId,
+    blocks: FxHashSet<BasicBlockId>,
+}
+
+/// Range Check Hoisting Pass
+///
+/// Casts range-check their source: `u32 -> felt` checks that the value fits in a felt,
+/// casts to `bool` check that the value is 0 or 1, and `felt -> u32` decomposes the value
+/// bit by bit. This pass reduces the number of checks executed by:
+/// - Hoisting casts of loop-invariant values into the loop preheader
+/// - Coalescing casts of the same value, when one dominates the other
+///
+/// A cast that can fail is only hoisted if it runs on every path leaving the loop, so that
+/// hoisting it cannot abort an execution that would never have reached it.
+///
+/// ### Examples:
+/// - `loop { %2 = cast %x as felt; ... }` → `%2 = cast %x as felt; loop { ... }`
+/// - `%2 = cast %x as felt; ...; %5 = cast %x as felt` → uses of `%5` replaced by `%2`
+#[derive(Debug, Default)]
+pub struct RangeCheckHoisting;
+
+impl RangeCheckHoisting {
+    /// Create a new range check hoisting pass
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Returns the cast key of an instruction, if it is a cast of an operand
+    fn cast_key(kind: &InstructionKind) -> Option<(ValueId, CastKey)> {
+        match kind {
+            InstructionKind::Cast {
+                dest,
+                source: Value::Operand(source),
+                source_type,
+                target_type,
+            } => Some((*dest, (*source, source_type.clone(), target_type.clone()))),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the cast aborts for some values of its source
+    const fn cast_can_fail(source_type: &MirType, target_type: &MirType) -> bool {
+        matches!(
+            (source_type, target_type),
+            (MirType::U32, MirType::Felt) | (_, MirType::Bool)
+        )
+    }
+
+    /// Find the natural loops of the function, innermost loops first
+    fn find_loops(
+        function: &MirFunction,
+        dominators: &Dominators,
+        preds: &FxHashMap<BasicBlockId, Vec<BasicBlockId>>,
+    ) -> Vec<NaturalLoop> {
+        let mut loops: FxHashMap<BasicBlockId, FxHashSet<BasicBlockId>> = FxHashMap::default();
+
+        for (&block_id, block_doms) in dominators {
+            for header in get_successors(function, block_id) {
+                // An edge to a dominator is a back edge
+                if !block_doms.contains(&header) {
+                    continue;
+                }
+                let blocks = loops
+                    .entry(header)
+                    .or_insert_with(|| std::iter::once(header).collect());
+                let mut worklist = vec![block_id];
+                while let Some(current) = worklist.pop() {
+                    if blocks.insert(current) {
+                        worklist.extend(preds.get(&current).into_iter().flatten());
+                    }
+                }
+            }
+        }
+
+        let mut loops: Vec<_> = loops
+            .into_iter()
+            .map(|(header, blocks)| NaturalLoop { header, blocks })
+            .collect();
+        loops.sort_by_key(|l| (l.blocks.len(), l.header));
+        loops
+    }
+
+    /// Returns the unique block entering the loop, if it only jumps to the loop header
+    fn find_preheader(
+        function: &MirFunction,
+        preds: &FxHashMap<BasicBlockId, Vec<BasicBlockId>>,
+        natural_loop: &NaturalLoop,
+    ) -> Option<BasicBlockId> {
+        let mut entering = preds
+            .get(&natural_loop.header)
+            .into_iter()
+            .flatten()
+            .filter(|pred| !natural_loop.blocks.contains(pred));
+        let preheader = *entering.next()?;
+        if entering.next().is_some() || get_successors(function, preheader) != [natural_loop.header]
+        {
+            return None;
+        }
+        Some(preheader)
+    }
+
+    /// Hoist the casts of loop-invariant values out of every loop with a preheader
+    fn hoist_invariant_casts(
+        function: &mut MirFunction,
+        order: &[BasicBlockId],
+        dominators: &Dominators,
+    ) -> bool {
+        let preds = reachable_predecessors(function);
+        let loops = Self::find_loops(function, dominators, &preds);
+        if loops.is_empty() {
+            return false;
+        }
+
+        // Block defining each value; parameters are defined before any block
+        let mut def_blocks: FxHashMap<ValueId, BasicBlockId> = FxHashMap::default();
+        for (block_id, block) in function.basic_blocks() {
+            for instr in &block.instructions {
+                for dest in instr.destinations() {
+                    def_blocks.insert(dest, block_id);
+                }
+            }
+        }
+
+        let mut modified = false;
+        for natural_loop in &loops {
+            let Some(preheader) = Self::find_preheader(function, &preds, natural_loop) else {
+                continue;
+            };
+            let exiting_blocks: Vec<_> = natural_loop
+                .blocks
+                .iter()
+                .copied()
+                .filter(|&block_id| {
+                    let succs = get_successors(function, block_id);
+                    succs.is_empty() || succs.iter().any(|s| !natural_loop.blocks.contains(s))
+                })
+                .collect();
+
+            for &block_id in order
+                .iter()
+                .filter(|block_id| natural_loop.blocks.contains(block_id))
+            {
+                let runs_on_exit = exiting_blocks
+                    .iter()
+                    .all(|exiting| dominators[exiting].contains(&block_id));
+
+                let mut idx = 0;
+                while idx < function.basic_blocks[block_id].instructions.len() {
+                    let instr = &function.basic_blocks[block_id].instructions[idx];
+                    let hoistable = Self::cast_key(&instr.kind).is_some_and(
+                        |(_, (source, source_type, target_type))| {
+                            let invariant = def_blocks
+                                .get(&source)
+                                .is_none_or(|def| !natural_loop.blocks.contains(def));
+                            invariant
+                                && (runs_on_exit
+                                    || !Self::cast_can_fail(&source_type, &target_type))
+                        },
+                    );
+
+                    if hoistable {
+                        let instr = function.basic_blocks[block_id].instructions.remove(idx);
+                        for dest in instr.destinations() {
+                            def_blocks.insert(dest, preheader);
+                        }
+                        function.basic_blocks[preheader].instructions.push(instr);
+                        modified = true;
+                    } else {
+                        idx += 1;
+                    }
+                }
+            }
+        }
+
+        modified
+    }
+
+    /// Replace each cast by an identical cast dominating it
+    fn coalesce_casts(
+        function: &mut MirFunction,
+        order: &[BasicBlockId],
+        dominators: &Dominators,
+    ) -> bool {
+        let mut available: FxHashMap<CastKey, Vec<(BasicBlockId, ValueId)>> = FxHashMap::default();
+        let mut replacements = Vec::new();
+
+        // Visiting blocks in reverse postorder visits each cast after the casts dominating it
+        for &block_id in order {
+            for instr in &function.basic_blocks[block_id].instructions {
+                let Some((dest, key)) = Self::cast_key(&instr.kind) else {
+                    continue;
+                };
+                let casts = available.entry(key).or_default();
+                if let Some(&(_, existing)) = casts
+                    .iter()
+                    .find(|(cast_block, _)| dominators[&block_id].contains(cast_block))
+                {
+                    replacements.push((dest, existing));
+                } else {
+                    casts.push((block_id, dest));
+                }
+            }
+        }
+
+        if replacements.is_empty() {
+            return false;
+        }
+
+        let redundant: FxHashSet<ValueId> = replacements.iter().map(|&(dest, _)| dest).collect();
+        for block in function.basic_blocks.iter_mut() {
+            block.instructions.retain(|instr| {
+                !matches!(&instr.kind, InstructionKind::Cast { dest, .. } if redundant.contains(dest))
+            });
+        }
+        for (dest, existing) in replacements {
+            function.replace_all_uses(dest, existing);
+        }
+
+        true
+    }
+}
+
+impl MirPass for RangeCheckHoisting {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        // Hoisting does not change the CFG, so the analyses remain valid for coalescing
+        let order = reverse_postorder(function);
+        let dominators = compute_dominators(function);
+
+        let hoisted = Self::hoist_invariant_casts(function, &order, &dominators);
+        let coalesced = Self::coalesce_casts(function, &order, &dominators);
+        hoisted || coalesced
+    }
+
+    fn name(&self) -> &'static str {
+        "RangeCheckHoisting"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BinaryOp, Instruction, Terminator};
+
+    fn casts_in(function: &MirFunction, block_id: BasicBlockId) -> usize {
+        function.basic_blocks[block_id]
+            .instructions
+            .iter()
+            .filter(|instr| matches!(instr.kind, InstructionKind::Cast { .. }))
+            .count()
+    }
+
+    /// entry -> header; header: if %cond then body else exit; body -> header; exit: return
+    ///
+    /// The body casts the parameter `%x` from `source_type` to `target_type`.
+    fn create_loop(
+        source_type: MirType,
+        target_type: MirType,
+    ) -> (MirFunction, [BasicBlockId; 4], ValueId) {
+        let mut function = MirFunction::new("test_loop".to_string());
+        let entry = function.entry_block;
+        let header = function.add_basic_block();
+        let body = function.add_basic_block();
+        let exit = function.add_basic_block();
+
+        let x = function.new_typed_value_id(source_type.clone());
+        let cond = function.new_typed_value_id(MirType::bool());
+        let cast = function.new_typed_value_id(target_type.clone());
+        let sum = function.new_typed_value_id(target_type.clone());
+        function.parameters = vec![x, cond];
+
+        function.basic_blocks[entry].set_terminator(Terminator::jump(header));
+        function.basic_blocks[header].set_terminator(Terminator::branch(
+            Value::operand(cond),
+            body,
+            exit,
+        ));
+        function.basic_blocks[body].push_instruction(Instruction::cast(
+            cast,
+            Value::operand(x),
+            source_type,
+            target_type,
+        ));
+        function.basic_blocks[body].push_instruction(Instruction::binary_op(
+            BinaryOp::Add,
+            sum,
+            Value::operand(cast),
+            Value::operand(cast),
+        ));
+        function.basic_blocks[body].set_terminator(Terminator::jump(header));
+        function.basic_blocks[exit].set_terminator(Terminator::return_value(Value::integer(0)));
+
+        function.connect(entry, header);
+        function.connect(header, body);
+        function.connect(header, exit);
+        function.connect(body, header);
+
+        (function, [entry, header, body, exit], cast)
+    }
+
+    #[test]
+    fn test_hoist_infallible_cast() {
+        let (mut function, [entry, _, body, _], _) = create_loop(MirType::felt(), MirType::u32());
+
+        assert!(RangeCheckHoisting::new().run(&mut function));
+        assert_eq!(casts_in(&function, entry), 1);
+        assert_eq!(casts_in(&function, body), 0);
+    }
+
+    #[test]
+    fn test_fallible_cast_not_hoisted_from_conditional_block() {
+        // The loop may exit without running the body, where the cast could abort
+        let (mut function, [entry, _, body, _], _) = create_loop(MirType::u32(), MirType::felt());
+
+        assert!(!RangeCheckHoisting::new().run(&mut function));
+        assert_eq!(casts_in(&function, entry), 0);
+        assert_eq!(casts_in(&function, body), 1);
+    }
+
+    #[test]
+    fn test_fallible_cast_hoisted_from_header() {
+        let (mut function, [entry, header, body, _], _) =
+            create_loop(MirType::u32(), MirType::felt());
+        let instructions = std::mem::take(&mut function.basic_blocks[body].instructions);
+        function.basic_blocks[header].instructions = instructions;
+
+        assert!(RangeCheckHoisting::new().run(&mut function));
+        assert_eq!(casts_in(&function, entry), 1);
+        assert_eq!(casts_in(&function, header), 0);
+    }
+
+    #[test]
+    fn test_coalesce_dominated_casts() {
+        let (mut function, [entry, _, body, _], cast) =
+            create_loop(MirType::u32(), MirType::felt());
+        let x = function.parameters[0];
+        let first = function.new_typed_value_id(MirType::felt());
+        function.basic_blocks[entry].push_instruction(Instruction::cast(
+            first,
+            Value::operand(x),
+            MirType::u32(),
+            MirType::felt(),
+        ));
+
+        assert!(RangeCheckHoisting::new().run(&mut function));
+        assert_eq!(casts_in(&function, entry), 1);
+        assert_eq!(casts_in(&function, body), 0);
+        let use_counts = function.get_value_use_counts();
+        assert_eq!(use_counts.get(&cast).copied().unwrap_or(0), 0);
+        assert_eq!(use_counts.get(&first).copied(), Some(2));
+    }
+
+    #[test]
+    fn test_casts_to_different_types_not_coalesced() {
+        let mut function = MirFunction::new("test".to_string());
+        let entry = function.entry_block;
+        let x = function.new_typed_value_id(MirType::felt());
+        let as_u32 = function.new_typed_value_id(MirType::u32());
+        let as_bool = function.new_typed_value_id(MirType::bool());
+        function.parameters = vec![x];
+
+        let block = &mut function.basic_blocks[entry];
+        block.push_instruction(Instruction::cast(
+            as_u32,
+            Value::operand(x),
+            MirType::felt(),
+            MirType::u32(),
+        ));
+        block.push_instruction(Instruction::cast(
+            as_bool,
+            Value::operand(x),
+            MirType::felt(),
+            MirType::bool(),
+        ));
+        block.set_terminator(Terminator::return_value(Value::operand(as_bool)));
+
+        assert!(!RangeCheckHoisting::new().run(&mut function));
+        assert_eq!(casts_in(&function, entry), 2);
+    }
+}
@@ -18,6 +18,7 @@ use crate::{BasicBlockId, Literal, MirFunction, Terminator, Value};
 /// - `if 0 then jump A else jump B` → `jump B` (0 is false)
 /// - `if 42 then jump A else jump B` → `jump A` (non-zero is true)
 /// - `if 5 == 3 then jump A else jump B` → `jump B` (constant comparison)
+/// - `switch 2 == [1 => jump A, 2 => jump B] else jump C` → `jump B` (constant scrutinee)
 #[derive(Debug, Default)]
 pub struct SimplifyBranches {
     evaluator: ConstEvaluator,
@@ -87,6 +88,32 @@ impl SimplifyBranches {
             None
         }
     }
+
+    /// Try to simplify a switch on a constant scrutinee with constant cases
+    fn simplify_switch(&self, terminator: &Terminator) -> Option<Terminator> {
+        let Terminator::Switch {
+            op,
+            value: Value::Literal(value),
+            cases,
+            default,
+        } = terminator
+        else {
+            return None;
+        };
+
+        // Cases are tested in order: stop at the first one that matches
+        for (case, target) in cases {
+            let Value::Literal(case) = case else {
+                return None;
+            };
+            match self.evaluator.eval_binary_op(*op, *value, *case)? {
+                Literal::Boolean(true) => return Some(Terminator::jump(*target)),
+                Literal::Boolean(false) => {}
+                _ => return None,
+            }
+        }
+        Some(Terminator::jump(*default))
+    }
 }
 
 impl MirPass for SimplifyBranches {
@@ -103,7 +130,8 @@ impl MirPass for SimplifyBranches {
                 // Try to simplify the terminator
                 let new_terminator = self
                     .simplify_if_terminator(&current_terminator)
-                    .or_else(|| self.simplify_branch_cmp(&current_terminator));
+                    .or_else(|| self.simplify_branch_cmp(&current_terminator))
+                    .or_else(|| self.simplify_switch(&current_terminator));
 
                 if let Some(new_term) = new_terminator {
                     // Use the new utility function to update edges properly
@@ -258,6 +286,31 @@ mod tests {
         assert_eq!(block.terminator, Terminator::jump(else_block));
     }
 
+    #[test]
+    fn test_simplify_constant_switch() {
+        let mut function = MirFunction::new("test".to_string());
+        let entry = function.add_basic_block();
+        let first = function.add_basic_block();
+        let second = function.add_basic_block();
+        let default = function.add_basic_block();
+        function.entry_block = entry;
+
+        // Create: switch 2 == [1 => jump first, 2 => jump second] else jump default
+        let block = function.get_basic_block_mut(entry).unwrap();
+        block.set_terminator(Terminator::switch(
+            BinaryOp::Eq,
+            Value::integer(2),
+            vec![(Value::integer(1), first), (Value::integer(2), second)],
+            default,
+        ));
+
+        let mut pass = SimplifyBranches::new();
+        assert!(pass.run(&mut function));
+
+        let block = function.get_basic_block(entry).unwrap();
+        assert_eq!(block.terminator, Terminator::jump(second));
+    }
+
     #[test]
     fn test_no_simplification_for_variable() {
         let mut function = MirFunction::new("test".to_string());
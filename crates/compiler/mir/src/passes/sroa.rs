@@ -76,7 +76,8 @@
 //! ## Limitations (Phase 1)
 //!
 //! - No scalarization across basic blocks (requires phi node handling)
-//! - Arrays are not scalarized if any dynamic indexing occurs
+//! - Arrays are not scalarized if any dynamic indexing occurs (the accesses following the
+//!   dynamic ones are reloaded into a fresh array by `ArrayDematerialization`)
 //! - Recursive aggregates not supported
 //! - Maximum aggregate size limit (configurable, default 8 fields)
 
@@ -710,11 +711,22 @@ impl ScalarReplacementOfAggregates {
                     callback(*id);
                 }
             }
+            InstructionKind::DebugPrint { value, .. } => {
+                if let Value::Operand(id) = value {
+                    callback(*id);
+                }
+            }
             InstructionKind::HeapAllocCells { cells, .. } => {
                 if let Value::Operand(id) = cells {
                     callback(*id);
                 }
             }
+            InstructionKind::CopyAggregate { source, .. } => {
+                if let Value::Operand(id) = source {
+                    callback(*id);
+                }
+            }
+            InstructionKind::LoadEnv { .. } => {}
         }
     }
 
@@ -746,7 +758,17 @@ impl ScalarReplacementOfAggregates {
                     callback(*id);
                 }
             }
-            Terminator::Unreachable => {}
+            Terminator::Switch { value, cases, .. } => {
+                if let Value::Operand(id) = value {
+                    callback(*id);
+                }
+                for (case, _) in cases {
+                    if let Value::Operand(id) = case {
+                        callback(*id);
+                    }
+                }
+            }
+            Terminator::Abort { .. } | Terminator::ColdAbort { .. } | Terminator::Unreachable => {}
         }
     }
 }
@@ -0,0 +1,255 @@
+//! # Pass Statistics
+//!
+//! Instrumentation of the [`PassManager`](super::PassManager): the size of a function is
+//! measured before and after each pass, and its MIR can be diffed, to see what each pass
+//! contributes when tuning the optimizer or bisecting a miscompilation.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::MirFunction;
+
+/// Size of a function, in the units tracked by the pass statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FunctionSize {
+    /// Number of instructions, over all blocks
+    pub instructions: usize,
+    /// Number of basic blocks
+    pub blocks: usize,
+    /// Number of values defined by instructions
+    pub values: usize,
+}
+
+impl FunctionSize {
+    /// Measures a function
+    pub fn of(function: &MirFunction) -> Self {
+        let instructions = || {
+            function
+                .basic_blocks()
+                .flat_map(|(_, block)| &block.instructions)
+        };
+        Self {
+            instructions: instructions().count(),
+            blocks: function.basic_blocks.len(),
+            values: instructions()
+                .map(|instruction| instruction.destinations().len())
+                .sum(),
+        }
+    }
+}
+
+/// Effect of a single run of a pass on a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassStatistics {
+    /// Name of the pass
+    pub pass: &'static str,
+    /// Name of the function the pass ran on
+    pub function: String,
+    /// Whether the pass reported modifying the function
+    pub modified: bool,
+    /// Size of the function before the pass
+    pub before: FunctionSize,
+    /// Size of the function after the pass
+    pub after: FunctionSize,
+    /// Line diff of the MIR of the function, if diffs are enabled and the pass modified it
+    pub diff: Option<String>,
+}
+
+impl PassStatistics {
+    /// Net number of instructions removed, negative if the pass added instructions
+    pub const fn instructions_removed(&self) -> isize {
+        self.before.instructions as isize - self.after.instructions as isize
+    }
+
+    /// Net number of basic blocks removed, negative if the pass added blocks
+    pub const fn blocks_removed(&self) -> isize {
+        self.before.blocks as isize - self.after.blocks as isize
+    }
+
+    /// Net number of values eliminated, negative if the pass defined new values
+    pub const fn values_eliminated(&self) -> isize {
+        self.before.values as isize - self.after.values as isize
+    }
+}
+
+impl fmt::Display for PassStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {}: instructions {} -> {}, blocks {} -> {}, values {} -> {}",
+            self.pass,
+            self.function,
+            self.before.instructions,
+            self.after.instructions,
+            self.before.blocks,
+            self.after.blocks,
+            self.before.values,
+            self.after.values,
+        )
+    }
+}
+
+/// Statistics of a pass summed over all its runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PassTotals {
+    /// Number of runs of the pass
+    pub runs: usize,
+    /// Number of runs that modified their function
+    pub modified: usize,
+    /// Net number of instructions removed
+    pub instructions_removed: isize,
+    /// Net number of basic blocks removed
+    pub blocks_removed: isize,
+    /// Net number of values eliminated
+    pub values_eliminated: isize,
+}
+
+/// Sums the statistics of each pass over all its runs, in order of first run
+pub fn pass_totals(statistics: &[PassStatistics]) -> Vec<(&'static str, PassTotals)> {
+    let mut order = Vec::new();
+    let mut totals: BTreeMap<&'static str, PassTotals> = BTreeMap::new();
+    for stats in statistics {
+        let totals = totals.entry(stats.pass).or_insert_with(|| {
+            order.push(stats.pass);
+            PassTotals::default()
+        });
+        totals.runs += 1;
+        totals.modified += usize::from(stats.modified);
+        totals.instructions_removed += stats.instructions_removed();
+        totals.blocks_removed += stats.blocks_removed();
+        totals.values_eliminated += stats.values_eliminated();
+    }
+    order.into_iter().map(|pass| (pass, totals[pass])).collect()
+}
+
+/// Formats the totals of each pass as a table, see [`pass_totals`]
+///
+/// The instruction, block and value columns are net changes, negative when the pass shrinks
+/// the functions.
+pub fn format_pass_totals(statistics: &[PassStatistics]) -> String {
+    let totals = pass_totals(statistics);
+    let width = totals
+        .iter()
+        .map(|(pass, _)| pass.len())
+        .chain(["pass".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "{:<width$}  {:>6}  {:>8}  {:>12}  {:>6}  {:>6}\n",
+        "pass", "runs", "modified", "instructions", "blocks", "values"
+    );
+    for (pass, totals) in totals {
+        table.push_str(&format!(
+            "{:<width$}  {:>6}  {:>8}  {:>12}  {:>6}  {:>6}\n",
+            pass,
+            totals.runs,
+            totals.modified,
+            -totals.instructions_removed,
+            -totals.blocks_removed,
+            -totals.values_eliminated,
+        ));
+    }
+    table
+}
+
+/// Line diff between two texts, with removed lines prefixed by `-`, added ones by `+`, and
+/// unchanged ones omitted
+pub fn line_diff(before: &str, after: &str) -> String {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+
+    // Longest common subsequence lengths of the suffixes
+    let mut lcs = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lcs[i][j] = if before[i] == after[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if i < before.len() && (j == after.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", before[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", after[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn size(instructions: usize, blocks: usize, values: usize) -> FunctionSize {
+        FunctionSize {
+            instructions,
+            blocks,
+            values,
+        }
+    }
+
+    fn stats(pass: &'static str, before: FunctionSize, after: FunctionSize) -> PassStatistics {
+        PassStatistics {
+            pass,
+            function: "f".to_string(),
+            modified: before != after,
+            before,
+            after,
+            diff: None,
+        }
+    }
+
+    #[test]
+    fn test_pass_totals() {
+        let statistics = vec![
+            stats("DeadCodeElimination", size(10, 3, 8), size(6, 2, 5)),
+            stats("CopyPropagation", size(6, 2, 5), size(6, 2, 5)),
+            stats("DeadCodeElimination", size(4, 1, 4), size(3, 1, 3)),
+        ];
+
+        assert_eq!(
+            pass_totals(&statistics),
+            vec![
+                (
+                    "DeadCodeElimination",
+                    PassTotals {
+                        runs: 2,
+                        modified: 2,
+                        instructions_removed: 5,
+                        blocks_removed: 1,
+                        values_eliminated: 4,
+                    }
+                ),
+                (
+                    "CopyPropagation",
+                    PassTotals {
+                        runs: 1,
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+
+        let table = format_pass_totals(&statistics);
+        let row: Vec<&str> = table.lines().nth(1).unwrap().split_whitespace().collect();
+        assert_eq!(row, vec!["DeadCodeElimination", "2", "2", "-5", "-1", "-4"]);
+    }
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(line_diff("a\nb\nc\n", "a\nc\nd\n"), "- b\n+ d\n");
+        assert!(line_diff("a\nb\n", "a\nb\n").is_empty());
+    }
+}
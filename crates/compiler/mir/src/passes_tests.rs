@@ -91,3 +91,105 @@ fn test_pass_manager() {
     // The function should now only have the entry block
     assert_eq!(function.basic_blocks.len(), 1);
 }
+
+#[test]
+fn test_pass_manager_statistics() {
+    let mut function = MirFunction::new("test_function".to_string());
+    let entry_block = function.entry_block;
+    let unreachable_block = function.add_basic_block();
+    function
+        .get_basic_block_mut(entry_block)
+        .unwrap()
+        .set_terminator(Terminator::return_void());
+    function
+        .get_basic_block_mut(unreachable_block)
+        .unwrap()
+        .push_instruction(Instruction::debug("Unreachable".to_string(), vec![]));
+    function
+        .get_basic_block_mut(unreachable_block)
+        .unwrap()
+        .set_terminator(Terminator::return_void());
+
+    // Statistics are only recorded when enabled
+    let mut pass_manager = PassManager::standard_pipeline();
+    pass_manager.run(&mut function.clone());
+    assert!(pass_manager.statistics().is_empty());
+
+    let mut pass_manager = PassManager::standard_pipeline().with_diffs();
+    pass_manager.run(&mut function);
+
+    let statistics = pass_manager.statistics();
+    assert!(
+        statistics
+            .iter()
+            .all(|stats| stats.function == "test_function")
+    );
+    // The unreachable block is removed by one of the passes
+    let removal = statistics
+        .iter()
+        .find(|stats| stats.blocks_removed() == 1)
+        .unwrap();
+    assert!(removal.modified);
+    assert_eq!(removal.instructions_removed(), 1);
+    assert!(removal.diff.as_ref().unwrap().contains("- "));
+    assert_eq!(
+        statistics
+            .iter()
+            .map(|stats| stats.blocks_removed())
+            .sum::<isize>(),
+        1
+    );
+    assert!(
+        statistics
+            .iter()
+            .filter(|stats| !stats.modified)
+            .all(|stats| stats.diff.is_none())
+    );
+
+    let taken = pass_manager.take_statistics();
+    assert!(!taken.is_empty());
+    assert!(pass_manager.statistics().is_empty());
+}
+
+#[test]
+fn test_pass_kind_names() {
+    for &kind in PassKind::ALL {
+        assert_eq!(PassKind::from_name(kind.name()), Some(kind));
+    }
+    assert_eq!(
+        PassKind::parse_list("sroa, cse,dce,").unwrap(),
+        vec![PassKind::Sroa, PassKind::Cse, PassKind::Dce]
+    );
+    assert!(PassKind::parse_list("").unwrap().is_empty());
+
+    let error = PassKind::parse_list("sroa,inline").unwrap_err();
+    assert!(error.contains("'inline'"));
+    assert!(error.contains("jump_threading"));
+}
+
+#[test]
+fn test_pass_manager_from_kinds() {
+    let mut function = MirFunction::new("test_function".to_string());
+    let entry_block = function.entry_block;
+    let unreachable_block = function.add_basic_block();
+    function
+        .get_basic_block_mut(entry_block)
+        .unwrap()
+        .set_terminator(Terminator::return_void());
+    function
+        .get_basic_block_mut(unreachable_block)
+        .unwrap()
+        .set_terminator(Terminator::return_void());
+
+    let mut pass_manager =
+        PassManager::from_kinds(&[PassKind::Cse, PassKind::Dce]).with_statistics();
+    assert!(pass_manager.run(&mut function));
+    assert_eq!(function.basic_blocks.len(), 1);
+
+    let passes: Vec<_> = pass_manager
+        .statistics()
+        .iter()
+        .map(|stats| stats.pass)
+        .collect();
+    assert_eq!(passes, vec!["LocalCSE", "DeadCodeElimination"]);
+}
@@ -1,6 +1,7 @@
 //! Simplified MIR optimization pipeline configuration
 
-use crate::{MirModule, PassManager};
+use crate::passes::statistics::{PassStatistics, format_pass_totals};
+use crate::{MirModule, MirPass, PassKind, PassManager, outline_aborts};
 
 /// Optimization level for the MIR pipeline
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,6 +25,15 @@ pub struct PipelineConfig {
     pub optimization_level: OptimizationLevel,
     /// Enable debug output (verbose MIR dumps)
     pub debug: bool,
+    /// Print the MIR diff of every pass that modifies a function, and the statistics of each
+    /// pass over the module, to stderr
+    pub verbose_passes: bool,
+    /// Optimization passes to run, in order, replacing those of the optimization level.
+    /// The passes of the optimization level run when `None`.
+    pub passes: Option<Vec<PassKind>>,
+    /// Functions to lower, along with the functions they transitively call.
+    /// All functions are lowered when `None`.
+    pub entrypoints: Option<Vec<String>>,
 }
 
 impl Default for PipelineConfig {
@@ -31,6 +41,9 @@ impl Default for PipelineConfig {
         Self {
             optimization_level: OptimizationLevel::Standard,
             debug: false,
+            verbose_passes: false,
+            passes: None,
+            entrypoints: None,
         }
     }
 }
@@ -41,6 +54,9 @@ impl PipelineConfig {
         Self {
             optimization_level: OptimizationLevel::None,
             debug: false,
+            verbose_passes: false,
+            passes: None,
+            entrypoints: None,
         }
     }
 
@@ -49,16 +65,38 @@ impl PipelineConfig {
         Self {
             optimization_level: OptimizationLevel::Standard,
             debug: true,
+            verbose_passes: false,
+            passes: None,
+            entrypoints: None,
         }
     }
 }
 
 /// Run the optimization pipeline on a MIR module
 pub fn optimize_module(module: &mut MirModule, config: &PipelineConfig) {
-    let mut pass_manager = match config.optimization_level {
-        OptimizationLevel::None => PassManager::no_opt_pipeline(),
-        OptimizationLevel::Standard => PassManager::standard_pipeline(),
+    optimize_module_with_passes(module, config, &mut []);
+}
+
+/// Run the optimization pipeline on a MIR module, along with custom passes
+///
+/// The custom passes run in order on each function, after the built-in optimizations and
+/// before the conversion out of SSA form, so they see the same MIR as the built-in passes.
+pub fn optimize_module_with_passes(
+    module: &mut MirModule,
+    config: &PipelineConfig,
+    extra_passes: &mut [Box<dyn MirPass>],
+) {
+    let mut optimizations = match (&config.passes, config.optimization_level) {
+        (Some(passes), _) => PassManager::from_kinds(passes),
+        (None, OptimizationLevel::None) => PassManager::new(),
+        (None, OptimizationLevel::Standard) => PassManager::standard_optimizations(),
     };
+    let mut ssa_destruction = PassManager::ssa_destruction_pipeline();
+    if config.verbose_passes {
+        optimizations = optimizations.with_diffs();
+        ssa_destruction = ssa_destruction.with_diffs();
+    }
+    let mut statistics = Vec::new();
 
     // Apply passes to each function
     for function in module.functions_mut() {
@@ -66,15 +104,40 @@ pub fn optimize_module(module: &mut MirModule, config: &PipelineConfig) {
         let _ = function.validate();
 
         // Run optimization passes
-        pass_manager.run(function);
+        optimizations.run(function);
+        for pass in extra_passes.iter_mut() {
+            pass.run(function);
+        }
+        ssa_destruction.run(function);
 
         // Validate after optimization
         let _ = function.validate();
 
-        // Debug output removed - no logging
+        statistics.extend(optimizations.take_statistics());
+        statistics.extend(ssa_destruction.take_statistics());
+    }
+
+    if config.verbose_passes {
+        report_pass_statistics(&statistics);
+    }
+
+    // Move the aborts of all functions to the cold region of the module
+    if config.optimization_level == OptimizationLevel::Standard {
+        outline_aborts(module);
     }
 }
 
+/// Prints the diff of each pass that modified a function, then the totals of each pass
+fn report_pass_statistics(statistics: &[PassStatistics]) {
+    for stats in statistics.iter().filter(|stats| stats.modified) {
+        eprintln!("=== {stats}");
+        if let Some(diff) = &stats.diff {
+            eprint!("{diff}");
+        }
+    }
+    eprint!("{}", format_pass_totals(statistics));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,4 +170,40 @@ mod tests {
         // Module should still be valid after optimization
         assert!(module.validate().is_ok());
     }
+
+    /// Records the names of the functions it runs on
+    struct RecordingPass {
+        seen: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    }
+
+    impl MirPass for RecordingPass {
+        fn run(&mut self, function: &mut MirFunction) -> bool {
+            self.seen.borrow_mut().push(function.name.clone());
+            false
+        }
+
+        fn name(&self) -> &'static str {
+            "RecordingPass"
+        }
+    }
+
+    #[test]
+    fn test_optimize_module_with_passes() {
+        let mut module = MirModule::new();
+        for name in ["foo", "bar"] {
+            let mut func = MirFunction::new(name.to_string());
+            let entry = func.add_basic_block();
+            func.entry_block = entry;
+            module.add_function(func);
+        }
+
+        let seen = std::rc::Rc::default();
+        let mut extra_passes: Vec<Box<dyn MirPass>> = vec![Box::new(RecordingPass {
+            seen: std::rc::Rc::clone(&seen),
+        })];
+        optimize_module_with_passes(&mut module, &PipelineConfig::no_opt(), &mut extra_passes);
+
+        assert_eq!(*seen.borrow(), vec!["foo".to_string(), "bar".to_string()]);
+        assert!(module.validate().is_ok());
+    }
 }
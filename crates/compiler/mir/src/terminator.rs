@@ -6,7 +6,7 @@
 use std::collections::HashSet;
 
 use crate::value_visitor::{visit_value, visit_values};
-use crate::{BasicBlockId, BinaryOp, PrettyPrint, Value};
+use crate::{BasicBlockId, BinaryOp, ColdAbortId, PrettyPrint, Value};
 
 /// A terminator ends a basic block and transfers control
 ///
@@ -18,6 +18,8 @@ use crate::{BasicBlockId, BinaryOp, PrettyPrint, Value};
 /// - Each terminator specifies its target blocks explicitly
 /// - Conditional branches specify both targets (taken/not taken)
 /// - Return terminators end function execution
+/// - Abort terminators end program execution, with a message reported by the runner
+/// - Cold abort terminators jump to an abort shared in the cold region of the module
 /// - Unreachable terminators indicate impossible code paths
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Terminator {
@@ -43,10 +45,33 @@ pub enum Terminator {
         else_target: BasicBlockId,
     },
 
+    /// Multi-way branch: `switch value [case => jump target, ...] else jump default`
+    /// Compares `value` to each case in order with `op` (`Eq` or `U32Eq`), and transfers
+    /// control to the target of the first equal case, or to `default` if none matches.
+    Switch {
+        op: BinaryOp,
+        value: Value,
+        cases: Vec<(Value, BasicBlockId)>,
+        default: BasicBlockId,
+    },
+
     /// Function return: `return values`
     /// Ends function execution and returns zero or more values
     Return { values: Vec<Value> },
 
+    /// Abort: `abort "message"`
+    /// Stops program execution; emitted for `panic` and for failing `assert` with a message.
+    /// The message and source location are reported by the runner, not proven.
+    Abort {
+        message: String,
+        location: Option<String>,
+    },
+
+    /// Outlined abort: `jump cold_abort_id`
+    /// Jumps to an abort of the cold region of the module, which ends program execution
+    /// like the `Abort` it replaces without inlining its code in the function.
+    ColdAbort { abort: ColdAbortId },
+
     /// Unreachable code: indicates this point should never be reached
     /// Used as a placeholder during construction and for optimization
     /// Also used for functions that never return (infinite loops, panics)
@@ -89,6 +114,21 @@ impl Terminator {
         }
     }
 
+    /// Creates a new multi-way branch terminator
+    pub const fn switch(
+        op: BinaryOp,
+        value: Value,
+        cases: Vec<(Value, BasicBlockId)>,
+        default: BasicBlockId,
+    ) -> Self {
+        Self::Switch {
+            op,
+            value,
+            cases,
+            default,
+        }
+    }
+
     /// Creates a new return terminator with a single value
     pub fn return_value(value: Value) -> Self {
         Self::Return {
@@ -106,6 +146,16 @@ impl Terminator {
         Self::Return { values: Vec::new() }
     }
 
+    /// Creates an abort terminator
+    pub const fn abort(message: String, location: Option<String>) -> Self {
+        Self::Abort { message, location }
+    }
+
+    /// Creates a jump to an abort of the cold region of the module
+    pub const fn cold_abort(abort: ColdAbortId) -> Self {
+        Self::ColdAbort { abort }
+    }
+
     /// Creates an unreachable terminator
     pub const fn unreachable() -> Self {
         Self::Unreachable
@@ -127,7 +177,13 @@ impl Terminator {
                 else_target,
                 ..
             } => vec![*then_target, *else_target],
+            Self::Switch { cases, default, .. } => cases
+                .iter()
+                .map(|(_, target)| *target)
+                .chain(std::iter::once(*default))
+                .collect(),
             Self::Return { .. } => vec![], // Returns don't target blocks
+            Self::Abort { .. } | Self::ColdAbort { .. } => vec![], // Aborts end the program
             Self::Unreachable => vec![],   // Unreachable code has no targets
         }
     }
@@ -156,13 +212,24 @@ impl Terminator {
                 });
             }
 
+            Self::Switch { value, cases, .. } => {
+                visit_value(value, |id| {
+                    used.insert(id);
+                });
+                for (case, _) in cases {
+                    visit_value(case, |id| {
+                        used.insert(id);
+                    });
+                }
+            }
+
             Self::Return { values } => {
                 visit_values(values, |id| {
                     used.insert(id);
                 });
             }
 
-            Self::Unreachable => {
+            Self::Abort { .. } | Self::ColdAbort { .. } | Self::Unreachable => {
                 // No values used
             }
         }
@@ -189,10 +256,16 @@ impl Terminator {
                 replace_value_id(left, from, to);
                 replace_value_id(right, from, to);
             }
+            Self::Switch { value, cases, .. } => {
+                replace_value_id(value, from, to);
+                for (case, _) in cases {
+                    replace_value_id(case, from, to);
+                }
+            }
             Self::Return { values } => {
                 replace_value_ids(values, from, to);
             }
-            Self::Unreachable => {
+            Self::Abort { .. } | Self::ColdAbort { .. } | Self::Unreachable => {
                 // No values used - nothing to replace
             }
         }
@@ -207,12 +280,18 @@ impl Terminator {
 
     /// Returns true if this terminator ends the function
     pub const fn ends_function(&self) -> bool {
-        matches!(self, Self::Return { .. } | Self::Unreachable)
+        matches!(
+            self,
+            Self::Return { .. } | Self::Abort { .. } | Self::ColdAbort { .. } | Self::Unreachable
+        )
     }
 
     /// Returns true if this is a conditional branch
     pub const fn is_conditional(&self) -> bool {
-        matches!(self, Self::If { .. } | Self::BranchCmp { .. })
+        matches!(
+            self,
+            Self::If { .. } | Self::BranchCmp { .. } | Self::Switch { .. }
+        )
     }
 
     /// Returns true if this is an unconditional branch (not counting returns)
@@ -226,7 +305,10 @@ impl Terminator {
             Self::Jump { .. } => Ok(()),
             Self::If { .. } => Ok(()),
             Self::BranchCmp { .. } => Ok(()),
+            Self::Switch { .. } => Ok(()),
             Self::Return { .. } => Ok(()),
+            Self::Abort { .. } => Ok(()),
+            Self::ColdAbort { .. } => Ok(()),
             Self::Unreachable => Ok(()),
         }
     }
@@ -236,7 +318,11 @@ impl Terminator {
         match self {
             Self::Jump { .. } => 1,
             Self::If { .. } | Self::BranchCmp { .. } => 2,
-            Self::Return { .. } | Self::Unreachable => 0,
+            Self::Switch { cases, .. } => cases.len() + 1,
+            Self::Return { .. }
+            | Self::Abort { .. }
+            | Self::ColdAbort { .. }
+            | Self::Unreachable => 0,
         }
     }
 
@@ -277,7 +363,21 @@ impl Terminator {
                 }
             }
 
-            Self::Return { .. } | Self::Unreachable => {
+            Self::Switch { cases, default, .. } => {
+                for (_, target) in cases.iter_mut() {
+                    if *target == old_block {
+                        *target = new_block;
+                    }
+                }
+                if *default == old_block {
+                    *default = new_block;
+                }
+            }
+
+            Self::Return { .. }
+            | Self::Abort { .. }
+            | Self::ColdAbort { .. }
+            | Self::Unreachable => {
                 // No targets to replace
             }
         }
@@ -317,6 +417,23 @@ impl PrettyPrint for Terminator {
                 )
             }
 
+            Self::Switch {
+                op,
+                value,
+                cases,
+                default,
+            } => {
+                let cases_str = cases
+                    .iter()
+                    .map(|(case, target)| format!("{} => jump {target:?}", case.pretty_print(0)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "switch {} {op} [{cases_str}] else jump {default:?}",
+                    value.pretty_print(0)
+                )
+            }
+
             Self::Return { values } => {
                 if values.is_empty() {
                     "return".to_string()
@@ -332,6 +449,10 @@ impl PrettyPrint for Terminator {
                 }
             }
 
+            Self::Abort { message, .. } => format!("abort {message:?}"),
+
+            Self::ColdAbort { abort } => format!("jump cold_abort_{abort:?}"),
+
             Self::Unreachable => "unreachable".to_string(),
         }
     }
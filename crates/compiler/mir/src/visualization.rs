@@ -0,0 +1,289 @@
+//! # MIR Visualization
+//!
+//! Renders the control flow graph of a function, or the call graph of a module, as a
+//! Graphviz (DOT) or Mermaid diagram. Blocks and functions are annotated with their
+//! instruction counts, to spot where code size goes when reviewing the optimizer output.
+
+use std::collections::BTreeMap;
+
+use crate::{InstructionKind, MirFunction, MirModule, PrettyPrint, Terminator};
+
+/// Output format of a graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT, rendered with e.g. `dot -Tsvg`
+    Dot,
+    /// Mermaid flowchart, rendered by Markdown viewers supporting it
+    Mermaid,
+}
+
+impl GraphFormat {
+    /// Conventional file extension of the format
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Mermaid => "mmd",
+        }
+    }
+}
+
+/// A graph, independent of its output format
+struct Graph {
+    name: String,
+    /// Node identifiers and labels, the entry node being highlighted
+    nodes: Vec<(String, String, bool)>,
+    /// Source, target and optional label of each edge
+    edges: Vec<(String, String, Option<String>)>,
+}
+
+impl Graph {
+    fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.render_dot(),
+            GraphFormat::Mermaid => self.render_mermaid(),
+        }
+    }
+
+    fn render_dot(&self) -> String {
+        let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut out = format!("digraph \"{}\" {{\n", escape(&self.name));
+        out.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+        for (id, label, entry) in &self.nodes {
+            let style = if *entry { ", penwidth=2" } else { "" };
+            out.push_str(&format!(
+                "  {id} [label=\"{}\"{style}];\n",
+                escape(label).replace('\n', "\\n")
+            ));
+        }
+        for (from, to, label) in &self.edges {
+            match label {
+                Some(label) => out.push_str(&format!(
+                    "  {from} -> {to} [label=\"{}\"];\n",
+                    escape(label)
+                )),
+                None => out.push_str(&format!("  {from} -> {to};\n")),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn render_mermaid(&self) -> String {
+        let escape = |text: &str| text.replace('"', "#quot;");
+        let mut out = format!("---\ntitle: {}\n---\nflowchart TD\n", self.name);
+        for (id, label, entry) in &self.nodes {
+            let label = escape(label).replace('\n', "<br/>");
+            if *entry {
+                out.push_str(&format!("  {id}[[\"{label}\"]]\n"));
+            } else {
+                out.push_str(&format!("  {id}[\"{label}\"]\n"));
+            }
+        }
+        for (from, to, label) in &self.edges {
+            match label {
+                Some(label) => out.push_str(&format!("  {from} -->|\"{}\"| {to}\n", escape(label))),
+                None => out.push_str(&format!("  {from} --> {to}\n")),
+            }
+        }
+        out
+    }
+}
+
+/// Renders the control flow graph of a function
+///
+/// Each block is labelled with its name, its number of instructions and its terminator,
+/// and the entry block is highlighted. Branch edges are labelled with the branch taken.
+pub fn function_cfg(function: &MirFunction, format: GraphFormat) -> String {
+    let node = |block_id: crate::BasicBlockId| format!("bb{}", block_id.index());
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    for (block_id, block) in function.basic_blocks() {
+        let header = match &block.name {
+            Some(name) => format!("{} ({name})", block_id.index()),
+            None => block_id.index().to_string(),
+        };
+        let label = format!(
+            "{header}\n{} instructions\n{}",
+            block.instructions.len(),
+            terminator_summary(&block.terminator)
+        );
+        nodes.push((node(block_id), label, block_id == function.entry_block));
+
+        let targets: Vec<_> = match &block.terminator {
+            Terminator::If {
+                then_target,
+                else_target,
+                ..
+            }
+            | Terminator::BranchCmp {
+                then_target,
+                else_target,
+                ..
+            } => vec![
+                (*then_target, Some("then".to_string())),
+                (*else_target, Some("else".to_string())),
+            ],
+            Terminator::Switch { cases, default, .. } => cases
+                .iter()
+                .map(|(value, target)| (*target, Some(value.pretty_print(0))))
+                .chain([(*default, Some("default".to_string()))])
+                .collect(),
+            terminator => terminator
+                .target_blocks()
+                .into_iter()
+                .map(|target| (target, None))
+                .collect(),
+        };
+        for (target, label) in targets {
+            edges.push((node(block_id), node(target), label));
+        }
+    }
+
+    Graph {
+        name: function.name.clone(),
+        nodes,
+        edges,
+    }
+    .render(format)
+}
+
+/// Renders the call graph of a module
+///
+/// Each function is labelled with its number of instructions, and each edge with the number
+/// of calls from the caller to the callee when there are several.
+pub fn call_graph(module: &MirModule, format: GraphFormat) -> String {
+    let node = |function_id: crate::FunctionId| format!("f{}", function_id.index());
+
+    let mut nodes = Vec::new();
+    let mut calls: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for (function_id, function) in module.functions() {
+        let instructions: Vec<_> = function
+            .basic_blocks()
+            .flat_map(|(_, block)| &block.instructions)
+            .collect();
+        nodes.push((
+            node(function_id),
+            format!("{}\n{} instructions", function.name, instructions.len()),
+            false,
+        ));
+        for instruction in instructions {
+            if let InstructionKind::Call { callee, .. } = &instruction.kind {
+                *calls
+                    .entry((function_id.index(), callee.index()))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let edges = calls
+        .into_iter()
+        .map(|((caller, callee), count)| {
+            (
+                node(caller.into()),
+                node(callee.into()),
+                (count > 1).then(|| format!("{count} calls")),
+            )
+        })
+        .collect();
+
+    Graph {
+        name: "call graph".to_string(),
+        nodes,
+        edges,
+    }
+    .render(format)
+}
+
+/// Kind of a terminator, with its condition for branches
+fn terminator_summary(terminator: &Terminator) -> String {
+    match terminator {
+        Terminator::Jump { .. } => "jump".to_string(),
+        Terminator::If { condition, .. } => format!("if {}", condition.pretty_print(0)),
+        Terminator::BranchCmp {
+            op, left, right, ..
+        } => format!("if {} {op} {}", left.pretty_print(0), right.pretty_print(0)),
+        Terminator::Switch { value, .. } => format!("switch {}", value.pretty_print(0)),
+        Terminator::Return { .. } => "return".to_string(),
+        Terminator::Abort { .. } | Terminator::ColdAbort { .. } => "abort".to_string(),
+        Terminator::Unreachable => "unreachable".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::CalleeSignature;
+    use crate::{Instruction, MirType, Value};
+
+    /// `main` branches on its parameter, calling `helper` twice on one branch
+    fn module() -> MirModule {
+        let mut module = MirModule::new();
+
+        let mut helper = MirFunction::new("helper".to_string());
+        let entry = helper.entry_block;
+        helper.basic_blocks[entry].set_terminator(Terminator::return_void());
+        let helper_id = module.add_function(helper);
+
+        let mut main = MirFunction::new("main".to_string());
+        let x = main.new_typed_value_id(MirType::Bool);
+        main.parameters = vec![x];
+        let entry = main.entry_block;
+        let then_block = main.add_basic_block();
+        let else_block = main.add_basic_block();
+        main.basic_blocks[entry].set_terminator(Terminator::branch(
+            Value::operand(x),
+            then_block,
+            else_block,
+        ));
+        for _ in 0..2 {
+            main.basic_blocks[then_block].push_instruction(Instruction::call(
+                vec![],
+                helper_id,
+                vec![],
+                CalleeSignature {
+                    param_types: vec![],
+                    return_types: vec![],
+                },
+            ));
+        }
+        main.basic_blocks[then_block].set_terminator(Terminator::return_void());
+        main.basic_blocks[else_block].set_terminator(Terminator::return_void());
+        module.add_function(main);
+        module
+    }
+
+    #[test]
+    fn test_function_cfg_dot() {
+        let module = module();
+        let main = module.get_function(1.into()).unwrap();
+        let dot = function_cfg(main, GraphFormat::Dot);
+
+        assert!(dot.starts_with("digraph \"main\" {\n"));
+        assert!(dot.contains("  bb0 [label=\"0\\n0 instructions\\nif %0\", penwidth=2];\n"));
+        assert!(dot.contains("  bb1 [label=\"1\\n2 instructions\\nreturn\"];\n"));
+        assert!(dot.contains("  bb0 -> bb1 [label=\"then\"];\n"));
+        assert!(dot.contains("  bb0 -> bb2 [label=\"else\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_function_cfg_mermaid() {
+        let module = module();
+        let main = module.get_function(1.into()).unwrap();
+        let mermaid = function_cfg(main, GraphFormat::Mermaid);
+
+        assert!(mermaid.contains("flowchart TD\n"));
+        assert!(mermaid.contains("  bb0[[\"0<br/>0 instructions<br/>if %0\"]]\n"));
+        assert!(mermaid.contains("  bb0 -->|\"then\"| bb1\n"));
+    }
+
+    #[test]
+    fn test_call_graph() {
+        let dot = call_graph(&module(), GraphFormat::Dot);
+
+        assert!(dot.contains("  f0 [label=\"helper\\n0 instructions\"];\n"));
+        assert!(dot.contains("  f1 [label=\"main\\n2 instructions\"];\n"));
+        assert!(dot.contains("  f1 -> f0 [label=\"2 calls\"];\n"));
+    }
+}
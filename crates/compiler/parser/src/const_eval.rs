@@ -0,0 +1,185 @@
+//! # Constant Conditions
+//!
+//! Evaluation of boolean expressions whose value is known from the AST alone, such as
+//! `true`, `!false` or `1 == 2`. Used to detect and drop dead `if` branches.
+//!
+//! Only expressions without side effects are evaluated: a condition like `false && f()`
+//! is not constant, as `f` is still called.
+
+use crate::parser::{BinaryOp, Expression, UnaryOp};
+
+/// The M31 field modulus, used to compare felt literals.
+const P: u64 = (1 << 31) - 1;
+
+/// A constant operand of a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConstValue {
+    Bool(bool),
+    /// Integer literal and its suffix, if any
+    Integer(u64, Option<IntSuffix>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntSuffix {
+    Felt,
+    U32,
+}
+
+/// Evaluates a condition whose value is a compile-time constant.
+///
+/// # Returns
+/// * `Some(value)` - The condition always evaluates to `value`
+/// * `None` - The condition depends on runtime values, or cannot be evaluated
+pub fn eval_const_condition(expr: &Expression) -> Option<bool> {
+    match eval(expr)? {
+        ConstValue::Bool(value) => Some(value),
+        ConstValue::Integer(..) => None,
+    }
+}
+
+fn eval(expr: &Expression) -> Option<ConstValue> {
+    match expr {
+        Expression::BooleanLiteral(value) => Some(ConstValue::Bool(*value)),
+        Expression::Literal(value, suffix) => {
+            let suffix = match suffix.as_deref() {
+                None => None,
+                Some("felt") => Some(IntSuffix::Felt),
+                Some("u32") => Some(IntSuffix::U32),
+                Some(_) => return None,
+            };
+            Some(ConstValue::Integer(*value, suffix))
+        }
+        Expression::Parenthesized(inner) => eval(inner.value()),
+        Expression::UnaryOp {
+            op: UnaryOp::Not,
+            expr,
+        } => match eval(expr.value())? {
+            ConstValue::Bool(value) => Some(ConstValue::Bool(!value)),
+            ConstValue::Integer(..) => None,
+        },
+        Expression::BinaryOp { op, left, right } => {
+            let left = eval(left.value())?;
+            let right = eval(right.value())?;
+            let value = match (op, left, right) {
+                (BinaryOp::And, ConstValue::Bool(a), ConstValue::Bool(b)) => a && b,
+                (BinaryOp::Or, ConstValue::Bool(a), ConstValue::Bool(b)) => a || b,
+                (BinaryOp::Eq | BinaryOp::Neq, _, _) => {
+                    let equal = const_eq(left, right)?;
+                    if *op == BinaryOp::Eq { equal } else { !equal }
+                }
+                _ => return None,
+            };
+            Some(ConstValue::Bool(value))
+        }
+        _ => None,
+    }
+}
+
+/// Compares two constants, following the type of the literals: felt literals are compared
+/// modulo P, while u32 literals are compared as integers. An unsuffixed literal takes the
+/// type of the other operand.
+fn const_eq(left: ConstValue, right: ConstValue) -> Option<bool> {
+    match (left, right) {
+        (ConstValue::Bool(a), ConstValue::Bool(b)) => Some(a == b),
+        (ConstValue::Integer(a, a_suffix), ConstValue::Integer(b, b_suffix)) => {
+            match (a_suffix, b_suffix) {
+                (Some(a_suffix), Some(b_suffix)) if a_suffix != b_suffix => None,
+                (Some(IntSuffix::U32), _) | (_, Some(IntSuffix::U32)) => {
+                    (a <= u32::MAX as u64 && b <= u32::MAX as u64).then_some(a == b)
+                }
+                _ => Some(a % P == b % P),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::span::SimpleSpan;
+
+    use super::*;
+    use crate::parser::Spanned;
+
+    fn spanned(expr: Expression) -> Box<Spanned<Expression>> {
+        Box::new(Spanned::new(expr, SimpleSpan::from(0..0)))
+    }
+
+    fn binary(op: BinaryOp, left: Expression, right: Expression) -> Expression {
+        Expression::BinaryOp {
+            op,
+            left: spanned(left),
+            right: spanned(right),
+        }
+    }
+
+    fn int(value: u64, suffix: Option<&str>) -> Expression {
+        Expression::Literal(value, suffix.map(str::to_string))
+    }
+
+    #[test]
+    fn test_const_conditions() {
+        let t = || Expression::BooleanLiteral(true);
+        let f = || Expression::BooleanLiteral(false);
+
+        assert_eq!(eval_const_condition(&t()), Some(true));
+        assert_eq!(
+            eval_const_condition(&Expression::UnaryOp {
+                op: UnaryOp::Not,
+                expr: spanned(t()),
+            }),
+            Some(false)
+        );
+        assert_eq!(
+            eval_const_condition(&Expression::Parenthesized(spanned(f()))),
+            Some(false)
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::Or, f(), t())),
+            Some(true)
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::And, t(), f())),
+            Some(false)
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::Eq, int(1, None), int(2, None))),
+            Some(false)
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::Neq, int(3, Some("u32")), int(3, None))),
+            Some(false)
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::Eq, int(P, None), int(0, None))),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_non_const_conditions() {
+        let x = || Expression::Identifier(Spanned::new("x".to_string(), SimpleSpan::from(0..1)));
+        assert_eq!(eval_const_condition(&x()), None);
+        assert_eq!(eval_const_condition(&int(1, None)), None);
+        assert_eq!(
+            eval_const_condition(&binary(
+                BinaryOp::And,
+                Expression::BooleanLiteral(false),
+                x()
+            )),
+            None
+        );
+        assert_eq!(
+            eval_const_condition(&binary(BinaryOp::Less, int(1, None), int(2, None))),
+            None
+        );
+        assert_eq!(
+            eval_const_condition(&binary(
+                BinaryOp::Eq,
+                int(1, Some("felt")),
+                int(1, Some("u32"))
+            )),
+            None
+        );
+    }
+}
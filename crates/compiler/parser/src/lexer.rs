@@ -164,6 +164,12 @@ pub enum TokenType<'a> {
         parse_number_literal
     )]
     LiteralNumber(NumberLiteral<'a>),
+    /// String literal, without its quotes. Escapes are not supported.
+    #[regex(r#""[^"\\\n]*""#, |lex| {
+        let slice = lex.slice();
+        &slice[1..slice.len() - 1]
+    })]
+    LiteralString(&'a str),
     // Keywords
     #[token("as")]
     As,
@@ -177,10 +183,14 @@ pub enum TokenType<'a> {
     Function,
     #[token("if")]
     If,
+    #[token("impl")]
+    Impl,
     #[token("let")]
     Let,
     #[token("new")]
     New,
+    #[token("pub")]
+    Pub,
     #[token("return")]
     Return,
     #[token("struct")]
@@ -217,6 +227,10 @@ pub enum TokenType<'a> {
     BitwiseOr,
     #[token("^")]
     BitwiseXor,
+    #[token("<<")]
+    ShiftLeft,
+    #[token(">>")]
+    ShiftRight,
     #[token("==")]
     EqEq,
     #[token("!=")]
@@ -241,6 +255,12 @@ pub enum TokenType<'a> {
     Mod,
     #[token("=")]
     Eq,
+    #[token("+=")]
+    PlusEq,
+    #[token("-=")]
+    MinusEq,
+    #[token("*=")]
+    MulEq,
     // Punctuation
     #[token("->")]
     Arrow,
@@ -266,6 +286,8 @@ pub enum TokenType<'a> {
     ColonColon,
     #[token(".")]
     Dot,
+    #[token("#")]
+    Pound,
 }
 
 impl<'a> fmt::Display for TokenType<'a> {
@@ -278,6 +300,7 @@ impl<'a> fmt::Display for TokenType<'a> {
                     write!(f, "{}", literal.value)
                 }
             }
+            TokenType::LiteralString(s) => write!(f, "\"{s}\""),
             TokenType::Identifier(s) => write!(f, "{s}"),
             TokenType::As => write!(f, "as"),
             TokenType::Const => write!(f, "const"),
@@ -302,6 +325,8 @@ impl<'a> fmt::Display for TokenType<'a> {
             TokenType::BitwiseAnd => write!(f, "&"),
             TokenType::BitwiseOr => write!(f, "|"),
             TokenType::BitwiseXor => write!(f, "^"),
+            TokenType::ShiftLeft => write!(f, "<<"),
+            TokenType::ShiftRight => write!(f, ">>"),
             TokenType::EqEq => write!(f, "=="),
             TokenType::Neq => write!(f, "!="),
             TokenType::LessEqual => write!(f, "<="),
@@ -314,6 +339,9 @@ impl<'a> fmt::Display for TokenType<'a> {
             TokenType::Div => write!(f, "/"),
             TokenType::Mod => write!(f, "%"),
             TokenType::Eq => write!(f, "="),
+            TokenType::PlusEq => write!(f, "+="),
+            TokenType::MinusEq => write!(f, "-="),
+            TokenType::MulEq => write!(f, "*="),
             TokenType::Arrow => write!(f, "->"),
             TokenType::LParen => write!(f, "("),
             TokenType::RParen => write!(f, ")"),
@@ -326,7 +354,10 @@ impl<'a> fmt::Display for TokenType<'a> {
             TokenType::Colon => write!(f, ":"),
             TokenType::ColonColon => write!(f, "::"),
             TokenType::Dot => write!(f, "."),
+            TokenType::Pound => write!(f, "#"),
+            TokenType::Pub => write!(f, "pub"),
             TokenType::Use => write!(f, "use"),
+            TokenType::Impl => write!(f, "impl"),
         }
     }
 }
@@ -593,6 +624,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_shift_operators() {
+        // Test that shifts are distinct from comparisons
+        let input = "a << b >> c < d >= e";
+        let lexer = TokenType::lexer(input);
+        let tokens: Vec<_> = lexer.spanned().map(|(t, _)| t).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Ok(TokenType::Identifier("a")),
+                Ok(TokenType::ShiftLeft),
+                Ok(TokenType::Identifier("b")),
+                Ok(TokenType::ShiftRight),
+                Ok(TokenType::Identifier("c")),
+                Ok(TokenType::Less),
+                Ok(TokenType::Identifier("d")),
+                Ok(TokenType::GreaterEqual),
+                Ok(TokenType::Identifier("e")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_operators() {
+        // Test that compound assignments are distinct from their operator and `=`
+        let input = "a += b -= c *= d + = e";
+        let lexer = TokenType::lexer(input);
+        let tokens: Vec<_> = lexer.spanned().map(|(t, _)| t).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Ok(TokenType::Identifier("a")),
+                Ok(TokenType::PlusEq),
+                Ok(TokenType::Identifier("b")),
+                Ok(TokenType::MinusEq),
+                Ok(TokenType::Identifier("c")),
+                Ok(TokenType::MulEq),
+                Ok(TokenType::Identifier("d")),
+                Ok(TokenType::Plus),
+                Ok(TokenType::Eq),
+                Ok(TokenType::Identifier("e")),
+            ]
+        );
+    }
+
     #[test]
     fn test_logical_vs_bitwise_operators() {
         // Test that logical operators are still recognized correctly and distinct from bitwise
@@ -640,4 +718,25 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_string_literals() {
+        let input = r#"assert(x, "x must be positive") "" "a // b""#;
+        let lexer = TokenType::lexer(input);
+        let tokens: Vec<_> = lexer.spanned().map(|(t, _)| t).collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Ok(TokenType::Identifier("assert")),
+                Ok(TokenType::LParen),
+                Ok(TokenType::Identifier("x")),
+                Ok(TokenType::Comma),
+                Ok(TokenType::LiteralString("x must be positive")),
+                Ok(TokenType::RParen),
+                Ok(TokenType::LiteralString("")),
+                Ok(TokenType::LiteralString("a // b")),
+            ]
+        );
+    }
 }
@@ -1,4 +1,5 @@
 #![allow(clippy::option_if_let_else)]
+pub mod const_eval;
 pub mod db;
 pub mod lexer;
 pub mod parser;
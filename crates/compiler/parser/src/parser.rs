@@ -46,12 +46,14 @@
 //! Member      ::= "." IDENT
 //! TupleIndex  ::= "." UNSUFFIXED_INT
 //! Index       ::= "[" Expr "]"
-//! Primary     ::= Literal | Bool | IDENT | StructLiteral | ArrayLiteral | TupleOrParen
+//! Primary     ::= Literal | Bool | IDENT | IfExpr | BlockExpr | StructLiteral | ArrayLiteral | TupleOrParen
 //! TupleOrParen ::= "(" Expr ( "," Expr )* ","? ")" | "()"   // see “Parens” note
+//! BlockExpr   ::= "{" Statement* Expr "}"
+//! IfExpr      ::= "if" Expr BlockExpr "else" ( BlockExpr | IfExpr )
 //!
 //! Type        ::= PostType ("*")*                           // right-assoc pointers via fold
 //! PostType    ::= NamedType | ArrayType | TupleType
-//! ArrayType   ::= "[" Type ";" UNSUFFIXED_INT "]"
+//! ArrayType   ::= "[" Type ";" ( UNSUFFIXED_INT | IDENT ) "]"
 //! TupleType   ::= "(" Type ( "," Type )+ ","? ")"
 //! ```
 //!
@@ -123,6 +125,8 @@
 //! touching the top-level `parser()` builder; AST node types remain plain Rust structs/enums.
 //!
 
+use std::collections::BTreeMap;
+
 use cairo_m_compiler_diagnostics::Diagnostic;
 use chumsky::input::ValueInput;
 use chumsky::prelude::*;
@@ -162,13 +166,43 @@ pub enum TypeExpr {
     Pointer(Box<Spanned<TypeExpr>>),
     /// A tuple type (e.g., `(felt, felt)`, `(Vector, felt, bool)`)
     Tuple(Vec<Spanned<TypeExpr>>),
-    /// A fixed-size array type (e.g., `[u32; 8]`, `[felt; 100]`)
+    /// A fixed-size array type (e.g., `[u32; 8]`, `[felt; SIZE]`)
     FixedArray {
         element_type: Box<Spanned<TypeExpr>>,
-        size: Spanned<u64>,
+        size: Spanned<ArraySize>,
     },
 }
 
+/// The size of a fixed-size array type.
+///
+/// Named sizes refer to constants, whose values are resolved during semantic analysis.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ArraySize {
+    /// An integer literal (e.g., `[felt; 8]`)
+    Literal(u64),
+    /// A named constant (e.g., `[felt; SIZE]`)
+    Const(String),
+}
+
+impl ArraySize {
+    /// Returns the size if it is given as a literal
+    pub const fn literal(&self) -> Option<u64> {
+        match self {
+            Self::Literal(size) => Some(*size),
+            Self::Const(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ArraySize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Literal(size) => write!(f, "{size}"),
+            Self::Const(name) => write!(f, "{name}"),
+        }
+    }
+}
+
 /// Unary operators supported in expressions.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum UnaryOp {
@@ -225,6 +259,10 @@ pub enum BinaryOp {
     BitwiseOr,
     /// Bitwise XOR operator `^`
     BitwiseXor,
+    /// Left shift operator `<<`
+    ShiftLeft,
+    /// Right shift operator `>>`
+    ShiftRight,
 }
 
 impl std::fmt::Display for BinaryOp {
@@ -246,6 +284,8 @@ impl std::fmt::Display for BinaryOp {
             Self::BitwiseAnd => write!(f, "&"),
             Self::BitwiseOr => write!(f, "|"),
             Self::BitwiseXor => write!(f, "^"),
+            Self::ShiftLeft => write!(f, "<<"),
+            Self::ShiftRight => write!(f, ">>"),
         }
     }
 }
@@ -261,6 +301,8 @@ pub enum Expression {
     Literal(u64, Option<String>),
     /// Boolean literal (e.g., `true`, `false`)
     BooleanLiteral(bool),
+    /// String literal (e.g., `"x must be positive"`), only valid as an `assert` or `panic` message
+    StringLiteral(String),
     /// Variable identifier (e.g., `x`, `my_var`, `result`)
     Identifier(Spanned<String>),
     /// Unary operation (e.g., `!x`, `-y`)
@@ -303,10 +345,10 @@ pub enum Expression {
     },
     /// Array literal (e.g., `[1, 2, 3]`)
     ArrayLiteral(Vec<Spanned<Expression>>),
-    /// Array repetition literal (e.g., `[0; 10]`, `[1u32; 4]`)
+    /// Array repetition literal (e.g., `[0; 10]`, `[1u32; 4]`, `[0; SIZE]`)
     ArrayRepeat {
         element: Box<Spanned<Expression>>,
-        count: Spanned<u64>,
+        count: Spanned<ArraySize>,
     },
     /// Type cast expression (e.g., `x as felt`, `42u32 as felt`)
     Cast {
@@ -318,6 +360,20 @@ pub enum Expression {
         elem_type: Spanned<TypeExpr>,
         count: Box<Spanned<Expression>>,
     },
+    /// Block expression (e.g., `{ let t = f(); t + 1 }`), evaluating to its final expression
+    Block {
+        statements: Vec<Spanned<Statement>>,
+        value: Box<Spanned<Expression>>,
+    },
+    /// Conditional expression (e.g., `if c { a } else { b }`)
+    ///
+    /// The `else` branch is required. Each branch is a [`Expression::Block`], or another
+    /// [`Expression::If`] for `else if` chains.
+    If {
+        condition: Box<Spanned<Expression>>,
+        then_branch: Box<Spanned<Expression>>,
+        else_branch: Box<Spanned<Expression>>,
+    },
     /// Parenthesized expression (e.g., `(a + b)`)
     ///
     /// This node preserves source parentheses for the formatter while being a no-op
@@ -325,7 +381,7 @@ pub enum Expression {
     Parenthesized(Box<Spanned<Expression>>),
 }
 
-/// Represents a function parameter with its name and type.
+/// Represents a function parameter with its name, type and optional default value.
 ///
 /// Used in function definitions to specify the expected arguments.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -334,6 +390,19 @@ pub struct Parameter {
     pub name: Spanned<String>,
     /// The parameter's type
     pub type_expr: Spanned<TypeExpr>,
+    /// The default value (e.g., `= 10`), used when an entrypoint is run without this argument
+    pub default: Option<Spanned<Expression>>,
+}
+
+/// Name of the receiver parameter of a method.
+pub const SELF_PARAM: &str = "self";
+
+impl Parameter {
+    /// Returns whether the parameter is a `self` receiver written without a type, whose
+    /// type is the one of its `impl` block.
+    pub fn is_receiver(&self) -> bool {
+        self.name.value() == SELF_PARAM && self.type_expr.span() == self.name.span()
+    }
 }
 
 /// Represents a pattern in let bindings.
@@ -366,6 +435,14 @@ pub enum Statement {
         lhs: Spanned<Expression>,
         rhs: Spanned<Expression>,
     },
+    /// Compound assignment to an existing variable (e.g., `x += 1;`)
+    ///
+    /// Equivalent to `lhs = lhs op rhs;`, with `lhs` evaluated only once.
+    CompoundAssignment {
+        lhs: Spanned<Expression>,
+        op: BinaryOp,
+        rhs: Spanned<Expression>,
+    },
     /// Return statement (e.g., `return x;`, `return;`)
     Return { value: Option<Spanned<Expression>> },
     /// Conditional statement (e.g., `if (condition) { ... } else { ... }`)
@@ -416,6 +493,8 @@ pub enum TopLevelItem {
     Const(Spanned<ConstDef>),
     /// Use statement
     Use(Spanned<UseStmt>),
+    /// Methods of a struct
+    Impl(Spanned<ImplDef>),
 }
 
 /// Represents a constant definition.
@@ -460,6 +539,8 @@ impl<T> Spanned<T> {
 /// Represents a function definition.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FunctionDef {
+    /// The function's attributes (e.g., `must_use` for `#[must_use]`)
+    pub attributes: Vec<Spanned<String>>,
     /// The function's name
     pub name: Spanned<String>,
     /// The function's parameters
@@ -479,30 +560,73 @@ pub struct StructDef {
     pub fields: Vec<(Spanned<String>, Spanned<TypeExpr>)>,
 }
 
+/// Represents an `impl` block, defining methods of a struct.
+///
+/// Methods are plain functions whose receiver, if any, is their first parameter:
+/// `p.norm()` calls the function `Point::norm` with `p` as first argument.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImplDef {
+    /// The name of the struct
+    pub type_name: Spanned<String>,
+    /// The methods of the struct
+    pub methods: Vec<Spanned<FunctionDef>>,
+}
+
+impl ImplDef {
+    /// Returns the name of a method in its module, e.g. `Point::norm`.
+    pub fn method_name(type_name: &str, method: &str) -> String {
+        format!("{type_name}::{method}")
+    }
+}
+
+/// Represents an item imported by a use statement, optionally renamed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UseItem {
+    /// The name of the item in the imported module.
+    pub name: Spanned<String>,
+    /// The name the item is imported as (e.g., `baz` for `bar as baz`).
+    pub alias: Option<Spanned<String>>,
+}
+
+impl UseItem {
+    /// Returns the name the item is bound to in the importing module.
+    pub fn local_name(&self) -> &Spanned<String> {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
+}
+
 /// Represents items in a use statement.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum UseItems {
     /// A single item import.
-    Single(Spanned<String>),
+    Single(UseItem),
     /// A list of items in braces.
-    List(Vec<Spanned<String>>),
+    List(Vec<UseItem>),
 }
 
 impl UseItems {
-    /// Returns the list of names of imported items.
-    pub fn names(&self) -> Vec<&str> {
+    /// Returns the imported items.
+    pub fn items(&self) -> &[UseItem] {
         match self {
-            Self::Single(name) => vec![name.value().as_str()],
-            Self::List(names) => names.iter().map(|name| name.value().as_str()).collect(),
+            Self::Single(item) => std::slice::from_ref(item),
+            Self::List(items) => items,
         }
     }
 
-    /// Returns the list of spans of imported items.
+    /// Returns the list of names the imported items are bound to.
+    pub fn names(&self) -> Vec<&str> {
+        self.items()
+            .iter()
+            .map(|item| item.local_name().value().as_str())
+            .collect()
+    }
+
+    /// Returns the list of spans of the names the imported items are bound to.
     pub fn spans(&self) -> Vec<SimpleSpan<usize>> {
-        match self {
-            Self::Single(name) => vec![name.span()],
-            Self::List(names) => names.iter().map(|name| name.span()).collect(),
-        }
+        self.items()
+            .iter()
+            .map(|item| item.local_name().span())
+            .collect()
     }
 }
 
@@ -513,6 +637,8 @@ pub struct UseStmt {
     pub path: Vec<Spanned<String>>,
     /// The imported items.
     pub items: UseItems,
+    /// Whether the items are re-exported to the importers of the module (`pub use`).
+    pub is_pub: bool,
 }
 
 // Deterministic a deterministic paren-or-tuple parser.
@@ -526,6 +652,110 @@ enum ParenTail {
     Tuple(Vec<Spanned<Expression>>), // (e, rest..., [trailing ,]) )
 }
 
+/// Documentation comments of a module, attached to the items they document.
+///
+/// Comments are skipped by the lexer, so they are collected from the source once the items
+/// are parsed: `///` lines right before a function, method, struct or constant (or before its
+/// attributes) document that item, and the `//!` lines at the top of the file document the
+/// module. The comment markers, and the space following them, are stripped so that the text
+/// can be rendered as markdown.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DocComments {
+    /// Documentation of the module, from its leading `//!` comments
+    pub module: Option<String>,
+    /// Documentation of the items, keyed by the start of the span of their name
+    pub items: BTreeMap<usize, String>,
+}
+
+impl DocComments {
+    pub const fn new() -> Self {
+        Self {
+            module: None,
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// Collects the documentation comments of `items` from the `source` they were parsed from
+    pub fn collect(source: &str, items: &[TopLevelItem]) -> Self {
+        let mut documented = Vec::new();
+        for item in items {
+            match item {
+                TopLevelItem::Function(func) => {
+                    documented.push((func.span(), func.value().name.span()));
+                }
+                TopLevelItem::Struct(struct_def) => {
+                    documented.push((struct_def.span(), struct_def.value().name.span()));
+                }
+                TopLevelItem::Const(const_def) => {
+                    documented.push((const_def.span(), const_def.value().name.span()));
+                }
+                TopLevelItem::Impl(impl_def) => documented.extend(
+                    impl_def
+                        .value()
+                        .methods
+                        .iter()
+                        .map(|method| (method.span(), method.value().name.span())),
+                ),
+                TopLevelItem::Use(_) => {}
+            }
+        }
+
+        Self {
+            module: Self::module_doc(source),
+            items: documented
+                .into_iter()
+                .filter_map(|(item_span, name_span)| {
+                    Self::item_doc(source, item_span.start).map(|doc| (name_span.start, doc))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the documentation of the item whose name is at `name_span`
+    pub fn item(&self, name_span: SimpleSpan<usize>) -> Option<&str> {
+        self.items.get(&name_span.start).map(String::as_str)
+    }
+
+    /// Reads the `//!` lines at the top of the file, after blank lines
+    fn module_doc(source: &str) -> Option<String> {
+        let lines = source
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| line.is_empty())
+            .map_while(|line| line.strip_prefix("//!"));
+        Self::join(lines)
+    }
+
+    /// Reads the `///` lines preceding the item starting at `item_start`, skipping attributes
+    fn item_doc(source: &str, item_start: usize) -> Option<String> {
+        let (preceding, line_prefix) = source[..item_start]
+            .rsplit_once('\n')
+            .unwrap_or(("", &source[..item_start]));
+        if !line_prefix.trim().is_empty() {
+            // The item doesn't start its line
+            return None;
+        }
+
+        let mut lines: Vec<&str> = preceding
+            .lines()
+            .rev()
+            .map(str::trim)
+            .filter(|line| !line.starts_with("#["))
+            .map_while(|line| line.strip_prefix("///").filter(|doc| !doc.starts_with('/')))
+            .collect();
+        lines.reverse();
+        Self::join(lines.into_iter())
+    }
+
+    /// Joins doc lines, without the space following their marker
+    fn join<'a>(lines: impl Iterator<Item = &'a str>) -> Option<String> {
+        let lines: Vec<&str> = lines
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
 /// Wrapper for the parsed AST result.
 ///
 /// This follows the Salsa best practice of caching the entire parse result
@@ -534,16 +764,61 @@ enum ParenTail {
 pub struct ParsedModule {
     /// The top-level items in the module
     pub items: Vec<TopLevelItem>,
+    /// The documentation comments of the module and its items
+    pub docs: DocComments,
 }
 
 impl ParsedModule {
     pub const fn new(items: Vec<TopLevelItem>) -> Self {
-        Self { items }
+        Self {
+            items,
+            docs: DocComments::new(),
+        }
+    }
+
+    /// Attaches the documentation comments collected from the source of the module
+    pub fn with_docs(mut self, docs: DocComments) -> Self {
+        self.docs = docs;
+        self
     }
 
     pub fn items(&self) -> &[TopLevelItem] {
         &self.items
     }
+
+    pub const fn docs(&self) -> &DocComments {
+        &self.docs
+    }
+
+    /// Returns the functions of the module along with their names, including the methods
+    /// of `impl` blocks, named as in [`ImplDef::method_name`].
+    pub fn functions(&self) -> impl Iterator<Item = (String, &Spanned<FunctionDef>)> {
+        self.items.iter().flat_map(|item| match item {
+            TopLevelItem::Function(func) => vec![(func.value().name.value().clone(), func)],
+            TopLevelItem::Impl(impl_def) => {
+                let type_name = impl_def.value().type_name.value();
+                impl_def
+                    .value()
+                    .methods
+                    .iter()
+                    .map(|method| {
+                        (
+                            ImplDef::method_name(type_name, method.value().name.value()),
+                            method,
+                        )
+                    })
+                    .collect()
+            }
+            TopLevelItem::Struct(_) | TopLevelItem::Const(_) | TopLevelItem::Use(_) => vec![],
+        })
+    }
+
+    /// Returns the function or method named `name`, see [`Self::functions`].
+    pub fn function(&self, name: &str) -> Option<&Spanned<FunctionDef>> {
+        self.functions()
+            .find(|(function_name, _)| function_name == name)
+            .map(|(_, func)| func)
+    }
 }
 
 /// Output from the parsing process, including both AST and diagnostics
@@ -606,7 +881,10 @@ pub fn parse_file(db: &dyn crate::Db, source: SourceFile) -> ParseOutput {
         .parse(token_stream)
         .into_result()
     {
-        Ok(items) => ParseOutput::new(ParsedModule::new(items), diagnostics),
+        Ok(items) => {
+            let docs = DocComments::collect(input, &items);
+            ParseOutput::new(ParsedModule::new(items).with_docs(docs), diagnostics)
+        }
         Err(parse_errors) => {
             // Convert parser errors to diagnostics with better messages
             for error in parse_errors {
@@ -709,22 +987,30 @@ where
                 }
             });
 
-        // Fixed-size array types: [u32; 8], [felt; 100], etc.
+        // Fixed-size array types: [u32; 8], [felt; SIZE], etc.
+        // The size is parsed from the tokens directly: the expression parser depends on the
+        // statement parser, which depends on this one.
+        let array_size = select! {
+            TokenType::LiteralNumber(lit) if lit.suffix.is_none() => ArraySize::Literal(lit.value),
+            TokenType::Identifier(name) => ArraySize::Const(name.to_string()),
+        }
+        .map_with(|size, extra| Spanned::new(size, extra.span()))
+        .then_ignore(just(TokenType::RBrack).rewind())
+        .or(any()
+            .and_is(just(TokenType::RBrack).not())
+            .repeated()
+            .at_least(1)
+            .map_with(|_, extra| extra.span())
+            .try_map(|_, span| {
+                Err::<Spanned<ArraySize>, _>(Rich::custom(
+                    span,
+                    "Fixed size arrays must have a size known at compile-time",
+                ))
+            }));
         let array_type = just(TokenType::LBrack)
             .ignore_then(type_expr.clone())
             .then_ignore(just(TokenType::Semicolon))
-            .then(
-                // Parse any expression, then require it be a plain integer literal
-                expression_parser().try_map_with(|expr, _extra| match expr.value() {
-                    Expression::Literal(value, suffix) if suffix.is_none() => {
-                        Ok(Spanned::new(*value, expr.span()))
-                    }
-                    _ => Err(Rich::custom(
-                        expr.span(),
-                        "Fixed size arrays must have a size known at compile-time",
-                    )),
-                }),
-            )
+            .then(array_size)
             .then_ignore(just(TokenType::RBrack))
             .map_with(|(element_type, size), extra| {
                 let span = extra.span();
@@ -791,6 +1077,12 @@ where
         }
         .map_with(|lit, extra| Spanned::new(lit, extra.span()));
 
+        // String literals (e.g., "x must be positive")
+        let string_literal = select! {
+            TokenType::LiteralString(s) => Expression::StringLiteral(s.to_string()),
+        }
+        .map_with(|lit, extra| Spanned::new(lit, extra.span()));
+
         // Variable identifiers (e.g., x, my_var, result)
         let ident_expr = spanned_ident
             .clone()
@@ -929,30 +1221,38 @@ where
             )
             .map_with(|expr, extra| Spanned::new(expr, extra.span()));
 
-        // Array repetition expressions: "[expr; UNSUFFIXED_INT]"
+        // Array repetition expressions: "[expr; UNSUFFIXED_INT]" or "[expr; CONST_NAME]"
+        let array_repeat_count = select! {
+            TokenType::LiteralNumber(lit) => Ok(lit),
+            TokenType::Identifier(name) => Err(name.to_string()),
+        }
+        .map_with(|count, extra| Spanned::new(count, extra.span()));
         let array_repeat = just(TokenType::LBrack)
             .ignore_then(
-                expr.clone().then_ignore(just(TokenType::Semicolon)).then(
-                    select! { TokenType::LiteralNumber(lit) => lit }
-                        .map_with(|lit, extra| Spanned::new(lit, extra.span())),
-                ),
+                expr.clone()
+                    .then_ignore(just(TokenType::Semicolon))
+                    .then(array_repeat_count),
             )
             .then_ignore(just(TokenType::RBrack))
-            .try_map_with(|(element, lit_spanned), extra| {
-                if lit_spanned.value().suffix.is_some() {
-                    Err(Rich::custom(
-                        lit_spanned.span(),
-                        "array length must be an unsuffixed integer",
-                    ))
-                } else {
-                    Ok(Spanned::new(
-                        Expression::ArrayRepeat {
-                            element: Box::new(element),
-                            count: Spanned::new(lit_spanned.value().value, lit_spanned.span()),
-                        },
-                        extra.span(),
-                    ))
-                }
+            .try_map_with(|(element, count), extra| {
+                let count_span = count.span();
+                let count = match count.value() {
+                    Ok(lit) if lit.suffix.is_some() => {
+                        return Err(Rich::custom(
+                            count_span,
+                            "array length must be an unsuffixed integer",
+                        ));
+                    }
+                    Ok(lit) => ArraySize::Literal(lit.value),
+                    Err(name) => ArraySize::Const(name.clone()),
+                };
+                Ok(Spanned::new(
+                    Expression::ArrayRepeat {
+                        element: Box::new(element),
+                        count: Spanned::new(count, count_span),
+                    },
+                    extra.span(),
+                ))
             });
 
         // Array literal expressions: "[elem1, elem2, elem3]"
@@ -965,10 +1265,47 @@ where
             .map(Expression::ArrayLiteral)
             .map_with(|expr, extra| Spanned::new(expr, extra.span()));
 
+        // Block expressions: "{ stmt1; stmt2; value }"
+        let statement = statement_parser_with(expr.clone());
+        let block_expr = statement
+            .repeated()
+            .collect::<Vec<Spanned<Statement>>>()
+            .then(expr.clone())
+            .delimited_by(just(TokenType::LBrace), just(TokenType::RBrace))
+            .map(|(statements, value)| Expression::Block {
+                statements,
+                value: Box::new(value),
+            })
+            .map_with(|expr, extra| Spanned::new(expr, extra.span()));
+
+        // If expressions: "if cond { a } else { b }", with `else if` chains
+        let if_expr = recursive(|if_expr| {
+            let if_condition = choice((
+                expr.clone()
+                    .delimited_by(just(TokenType::LParen), just(TokenType::RParen)), // condition in parens
+                expr.clone(), // bare condition
+            ));
+
+            just(TokenType::If)
+                .ignore_then(if_condition)
+                .then(block_expr.clone())
+                .then_ignore(just(TokenType::Else))
+                .then(block_expr.clone().or(if_expr))
+                .map(|((condition, then_branch), else_branch)| Expression::If {
+                    condition: Box::new(condition),
+                    then_branch: Box::new(then_branch),
+                    else_branch: Box::new(else_branch),
+                })
+                .map_with(|expr, extra| Spanned::new(expr, extra.span()))
+        });
+
         // Basic atomic expressions - try each alternative in order
         let atom = literal
             .or(boolean_literal)
+            .or(string_literal)
             .or(new_expr)
+            .or(if_expr)
+            .or(block_expr)
             .or(struct_literal)
             .or(array_repeat.clone())
             .or(array_literal)
@@ -1168,8 +1505,31 @@ where
             },
         );
 
+        // Shift operators: <<, >> (left-associative)
+        let shift = add.clone().foldl(
+            choice((
+                op(TokenType::ShiftLeft, BinaryOp::ShiftLeft),
+                op(TokenType::ShiftRight, BinaryOp::ShiftRight),
+            ))
+            .then(add.clone())
+            .repeated(),
+            |lhs, (op, rhs)| {
+                let span_lhs = lhs.span();
+                let span_rhs = rhs.span();
+                let span = SimpleSpan::from(span_lhs.start..span_rhs.end);
+                Spanned::new(
+                    Expression::BinaryOp {
+                        op,
+                        left: Box::new(lhs),
+                        right: Box::new(rhs),
+                    },
+                    span,
+                )
+            },
+        );
+
         // Comparison operators: ==, !=, <, >, <=, >= (left-associative)
-        let cmp = add.clone().foldl(
+        let cmp = shift.clone().foldl(
             choice((
                 op(TokenType::EqEq, BinaryOp::Eq),
                 op(TokenType::Neq, BinaryOp::Neq),
@@ -1178,7 +1538,7 @@ where
                 op(TokenType::LessEqual, BinaryOp::LessEqual),
                 op(TokenType::GreaterEqual, BinaryOp::GreaterEqual),
             ))
-            .then(add.clone())
+            .then(shift.clone())
             .repeated(),
             |lhs, (op, rhs)| {
                 let span_lhs = lhs.span();
@@ -1252,12 +1612,33 @@ where
 {
     let spanned_ident = spanned_ident_parser();
     let type_expr = type_expr_parser();
+    let expr = expression_parser();
 
-    // Function parameter: name: type
-    spanned_ident
+    // Function parameter: name: type [= default]
+    let typed = spanned_ident
         .then_ignore(just(TokenType::Colon)) // parameter name, ignore ':'
         .then(type_expr) // parameter type
-        .map(|(name, type_expr)| Parameter { name, type_expr })
+        .then(just(TokenType::Eq).ignore_then(expr).or_not()) // optional default value
+        .map(|((name, type_expr), default)| Parameter {
+            name,
+            type_expr,
+            default,
+        });
+
+    // Method receiver: self, typed `Self` until its `impl` block gives it the struct type
+    let receiver = select! { TokenType::Identifier(SELF_PARAM) => () }.map_with(|_, extra| {
+        let span = extra.span();
+        Parameter {
+            name: Spanned::new(SELF_PARAM.to_string(), span),
+            type_expr: Spanned::new(
+                TypeExpr::Named(Spanned::new(NamedType::Custom("Self".to_string()), span)),
+                span,
+            ),
+            default: None,
+        }
+    });
+
+    typed.or(receiver)
 }
 
 /// Creates a parser for statements
@@ -1265,9 +1646,24 @@ fn statement_parser<'tokens, 'src: 'tokens, I>()
 -> impl Parser<'tokens, I, Spanned<Statement>, extra::Err<Rich<'tokens, TokenType<'src>>>> + Clone
 where
     I: ValueInput<'tokens, Token = TokenType<'src>, Span = SimpleSpan>,
+{
+    statement_parser_with(expression_parser())
+}
+
+/// Creates a parser for statements, using `expr` for the expressions they contain.
+///
+/// Block expressions contain statements, so the expression parser builds its statement parser
+/// from its own recursive handle.
+fn statement_parser_with<'tokens, 'src: 'tokens, I, E>(
+    expr: E,
+) -> impl Parser<'tokens, I, Spanned<Statement>, extra::Err<Rich<'tokens, TokenType<'src>>>> + Clone
+where
+    I: ValueInput<'tokens, Token = TokenType<'src>, Span = SimpleSpan>,
+    E: Parser<'tokens, I, Spanned<Expression>, extra::Err<Rich<'tokens, TokenType<'src>>>>
+        + Clone
+        + 'tokens,
 {
     let spanned_ident = spanned_ident_parser();
-    let expr = expression_parser();
     let type_expr = type_expr_parser();
 
     // Pattern parser for destructuring
@@ -1383,6 +1779,15 @@ where
             })
             .map_with(|stmt, extra| Spanned::new(stmt, extra.span()));
 
+        // Assignment operator and right-hand side: "= rhs", "+= rhs", "-= rhs" or "*= rhs"
+        let assignment = choice((
+            just(TokenType::Eq).to(None),
+            just(TokenType::PlusEq).to(Some(BinaryOp::Add)),
+            just(TokenType::MinusEq).to(Some(BinaryOp::Sub)),
+            just(TokenType::MulEq).to(Some(BinaryOp::Mul)),
+        ))
+        .then(expr.clone());
+
         // --- Helpers for C-style `for` loop parts ---
         // init: either a `let` declaration or an assignment/expression, and MUST end with ';'
         let for_init = choice((
@@ -1405,12 +1810,9 @@ where
                 .map_with(|stmt, extra| Spanned::new(stmt, extra.span())),
             // assignment or expression followed by ';'
             expr.clone()
-                .then(just(TokenType::Eq).ignore_then(expr.clone()).or_not())
+                .then(assignment.clone().or_not())
                 .then_ignore(just(TokenType::Semicolon))
-                .map(|(lhs, rhs)| match rhs {
-                    Some(rhs) => Statement::Assignment { lhs, rhs },
-                    None => Statement::Expression(lhs),
-                })
+                .map(|(lhs, assignment)| assignment_statement(lhs, assignment))
                 .map_with(|stmt, extra| Spanned::new(stmt, extra.span())),
         ));
 
@@ -1420,11 +1822,8 @@ where
         // step: assignment or expression WITHOUT a trailing ';'
         let for_step = expr
             .clone()
-            .then(just(TokenType::Eq).ignore_then(expr.clone()).or_not())
-            .map(|(lhs, rhs)| match rhs {
-                Some(rhs) => Statement::Assignment { lhs, rhs },
-                None => Statement::Expression(lhs),
-            })
+            .then(assignment.clone().or_not())
+            .map(|(lhs, assignment)| assignment_statement(lhs, assignment))
             .map_with(|stmt, extra| Spanned::new(stmt, extra.span()));
 
         // For statement (C-style): for (init; condition; step) body
@@ -1456,15 +1855,12 @@ where
             .to(Statement::Continue)
             .map_with(|stmt, extra| Spanned::new(stmt, extra.span()));
 
-        // Assignment or expression statement: lhs = rhs; or expr;
+        // Assignment or expression statement: lhs = rhs;, lhs += rhs; or expr;
         let assignment_or_expr = expr
             .clone()
-            .then(just(TokenType::Eq).ignore_then(expr.clone()).or_not()) // optional assignment
+            .then(assignment.or_not()) // optional assignment
             .then_ignore(just(TokenType::Semicolon)) // ignore ';'
-            .map(|(lhs, rhs)| match rhs {
-                Some(rhs) => Statement::Assignment { lhs, rhs },
-                None => Statement::Expression(lhs),
-            })
+            .map(|(lhs, assignment)| assignment_statement(lhs, assignment))
             .map_with(|stmt, extra| Spanned::new(stmt, extra.span()));
 
         // Try statement alternatives in order
@@ -1482,6 +1878,20 @@ where
     })
 }
 
+/// Builds the statement for `lhs`, followed by an optional assignment operator and value.
+///
+/// `None` is a plain expression statement, and `Some((None, rhs))` a plain assignment.
+fn assignment_statement(
+    lhs: Spanned<Expression>,
+    assignment: Option<(Option<BinaryOp>, Spanned<Expression>)>,
+) -> Statement {
+    match assignment {
+        Some((None, rhs)) => Statement::Assignment { lhs, rhs },
+        Some((Some(op), rhs)) => Statement::CompoundAssignment { lhs, op, rhs },
+        None => Statement::Expression(lhs),
+    }
+}
+
 /// Creates a parser for function definitions
 fn function_def_parser<'tokens, 'src: 'tokens, I>()
 -> impl Parser<'tokens, I, Spanned<FunctionDef>, extra::Err<Rich<'tokens, TokenType<'src>>>> + Clone
@@ -1493,9 +1903,19 @@ where
     let type_expr = type_expr_parser();
     let statement = statement_parser();
 
-    // Function definition: fn name(param1: type1, param2: type2) -> return_type { body }
-    just(TokenType::Function)
-        .ignore_then(spanned_ident) // function name
+    // Attribute: #[name]
+    let attribute = just(TokenType::Pound).ignore_then(
+        spanned_ident
+            .clone()
+            .delimited_by(just(TokenType::LBrack), just(TokenType::RBrack)),
+    );
+
+    // Function definition: #[attribute] fn name(param1: type1, param2: type2) -> return_type { body }
+    attribute
+        .repeated()
+        .collect::<Vec<_>>()
+        .then_ignore(just(TokenType::Function))
+        .then(spanned_ident) // function name
         .then(
             param
                 .separated_by(just(TokenType::Comma)) // parameters separated by commas
@@ -1514,22 +1934,25 @@ where
                 .collect::<Vec<Spanned<Statement>>>()
                 .delimited_by(just(TokenType::LBrace), just(TokenType::RBrace)), // body in {}
         )
-        .map_with(|(((name, params), return_type), body), extra| {
-            // If no return type is specified, default to unit type ()
-            let return_type = return_type.unwrap_or_else(|| {
-                let span = SimpleSpan::from(0..0); // Default span for unit type
-                Spanned::new(TypeExpr::Tuple(vec![]), span)
-            });
-            Spanned(
-                FunctionDef {
-                    name,
-                    params,
-                    return_type,
-                    body,
-                },
-                extra.span(),
-            )
-        })
+        .map_with(
+            |((((attributes, name), params), return_type), body), extra| {
+                // If no return type is specified, default to unit type ()
+                let return_type = return_type.unwrap_or_else(|| {
+                    let span = SimpleSpan::from(0..0); // Default span for unit type
+                    Spanned::new(TypeExpr::Tuple(vec![]), span)
+                });
+                Spanned(
+                    FunctionDef {
+                        attributes,
+                        name,
+                        params,
+                        return_type,
+                        body,
+                    },
+                    extra.span(),
+                )
+            },
+        )
 }
 
 /// Creates a parser for struct definitions
@@ -1560,6 +1983,42 @@ where
         .map_with(|(name, fields), extra| Spanned(StructDef { name, fields }, extra.span()))
 }
 
+/// Creates a parser for `impl` blocks
+fn impl_def_parser<'tokens, 'src: 'tokens, I>()
+-> impl Parser<'tokens, I, Spanned<ImplDef>, extra::Err<Rich<'tokens, TokenType<'src>>>> + Clone
+where
+    I: ValueInput<'tokens, Token = TokenType<'src>, Span = SimpleSpan>,
+{
+    let spanned_ident = spanned_ident_parser();
+
+    // Impl block: impl Name { fn method(self, param: type) -> return_type { body } ... }
+    just(TokenType::Impl)
+        .ignore_then(spanned_ident) // struct name
+        .then(
+            function_def_parser()
+                .repeated()
+                .collect::<Vec<_>>()
+                .delimited_by(just(TokenType::LBrace), just(TokenType::RBrace)), // wrapped in {}
+        )
+        .map_with(|(type_name, mut methods), extra| {
+            // Receivers take the type of the struct
+            for method in &mut methods {
+                for param in &mut method.0.params {
+                    if param.is_receiver() {
+                        param.type_expr = Spanned::new(
+                            TypeExpr::Named(Spanned::new(
+                                NamedType::Custom(type_name.value().clone()),
+                                param.name.span(),
+                            )),
+                            param.name.span(),
+                        );
+                    }
+                }
+            }
+            Spanned(ImplDef { type_name, methods }, extra.span())
+        })
+}
+
 /// Creates a parser for use statements (Rust-like imports).
 fn use_stmt_parser<'tokens, 'src: 'tokens, I>()
 -> impl Parser<'tokens, I, Spanned<UseStmt>, extra::Err<Rich<'tokens, TokenType<'src>>>> + Clone
@@ -1574,29 +2033,38 @@ where
         .at_least(1) // Ensure at least one segment
         .collect::<Vec<_>>();
 
+    // Optional renaming: `item as alias`
+    let alias = just(TokenType::As)
+        .ignore_then(spanned_ident.clone())
+        .or_not();
+
     // Single item: use path::to::module::item;
     let single = module_path
         .clone()
-        .map(|mut path| {
+        .then(alias.clone())
+        .map(|(mut path, alias)| {
             // Safe unwrap: at_least(1) ensures path has at least one segment
             // But we use safe pattern matching for robustness
-            let item = path.pop().unwrap_or_else(|| {
+            let name = path.pop().unwrap_or_else(|| {
                 // This should never happen due to at_least(1) constraint
                 Spanned(String::from(""), SimpleSpan::new((), 0..0))
             });
             UseStmt {
                 path,
-                items: UseItems::Single(item),
+                items: UseItems::Single(UseItem { name, alias }),
+                is_pub: false,
             }
         })
         .then_ignore(just(TokenType::Semicolon));
 
-    // List: use path::to::module::{item1, item2};
+    // List: use path::to::module::{item1, item2 as alias};
     let list = module_path
         .then_ignore(just(TokenType::ColonColon))
         .then(
             spanned_ident
                 .clone()
+                .then(alias)
+                .map(|(name, alias)| UseItem { name, alias })
                 .separated_by(just(TokenType::Comma))
                 .allow_trailing()
                 .collect::<Vec<_>>()
@@ -1605,12 +2073,23 @@ where
         .map(|(path, items)| UseStmt {
             path,
             items: UseItems::List(items),
+            is_pub: false,
         })
         .then_ignore(just(TokenType::Semicolon));
 
-    just(TokenType::Use)
-        .ignore_then(single.or(list))
-        .map_with(|stmt, extra| Spanned::new(stmt, extra.span()))
+    just(TokenType::Pub)
+        .or_not()
+        .then_ignore(just(TokenType::Use))
+        .then(single.or(list))
+        .map_with(|(is_pub, stmt), extra| {
+            Spanned::new(
+                UseStmt {
+                    is_pub: is_pub.is_some(),
+                    ..stmt
+                },
+                extra.span(),
+            )
+        })
 }
 
 /// Creates a parser for constant definitions
@@ -1644,9 +2123,14 @@ where
         let struct_def = struct_def_parser().map(TopLevelItem::Struct);
         let const_def = const_def_parser().map(TopLevelItem::Const);
         let use_stmt = use_stmt_parser().map(TopLevelItem::Use);
+        let impl_def = impl_def_parser().map(TopLevelItem::Impl);
 
         // Try top-level item alternatives in order
-        func_def.or(struct_def).or(const_def).or(use_stmt)
+        func_def
+            .or(struct_def)
+            .or(const_def)
+            .or(use_stmt)
+            .or(impl_def)
     })
 }
 
@@ -1662,7 +2146,7 @@ where
 /// 1. **Expressions**: Built from atoms (literals, identifiers) up through binary operators
 /// 2. **Types**: Handle named types, pointers, and tuples
 /// 3. **Statements**: Control flow, declarations, and expression statements
-/// 4. **Top-level items**: Functions, structs, imports, and constants
+/// 4. **Top-level items**: Functions, structs, imports, constants and impl blocks
 ///
 /// ## Operator Precedence (lowest to highest)
 ///
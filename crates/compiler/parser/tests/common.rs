@@ -24,7 +24,7 @@ impl Upcast<dyn ParserDb> for TestDb {
     }
 }
 
-fn test_db() -> TestDb {
+pub fn test_db() -> TestDb {
     TestDb::default()
 }
 
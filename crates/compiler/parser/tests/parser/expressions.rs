@@ -331,3 +331,22 @@ fn heap_allocation_new_parameterized() {
         ]
     }
 }
+
+// ===================
+// Block and If Expressions
+// ===================
+
+#[test]
+fn if_and_block_expressions_parameterized() {
+    assert_parses_parameterized! {
+        ok: [
+            in_function("let x = if c { 1 } else { 2 };"),
+            in_function("let y = { let t = 2; t + 1 };"),
+            in_function("let z = if a { 1 } else if b { 2 } else { 3 };"),
+        ],
+        err: [
+            // An if expression must have an `else` branch
+            in_function("let x = if c { 1 };"),
+        ]
+    }
+}
@@ -40,6 +40,7 @@ fn array_creation_parameterized() {
             in_function("let arr: [felt; 3] = [1, 2, 3];"),
             in_function("let arr: [u32; 3] = [1, 2, 3];"),
             in_function("let arr: [MyStruct; 3] = [MyStruct { x: 1, y: 2 }, MyStruct { x: 3, y: 4 }, MyStruct { x: 5, y: 6 }];"),
+            in_function("let grid = [[1, 2], [3, 4]];"),
         ],
         err: [
             in_function("let arr: [felt] = [1, 2, 3];"),
@@ -55,6 +56,8 @@ fn test_array_repeat_syntax() {
             in_function("let arr = [1; 3];"),
             in_function("let arr: [felt; 3] = [1; 3];"),
             in_function("let arr: [u32; 4] = [1u32; 4];"),
+            in_function("let arr = [0; SIZE];"),
+            in_function("let grid = [[0; 4]; 2];"),
        ]
     }
 }
@@ -66,6 +69,9 @@ fn assignment_statements_parameterized() {
             in_function("x = 5;"),
             in_function("obj.field = value;"),
             in_function("arr[0] = item;"),
+            in_function("x += 5;"),
+            in_function("obj.field -= value;"),
+            in_function("arr[0] *= item;"),
         ],
         err: [
             in_function("= 5;"),
@@ -1,3 +1,8 @@
+use cairo_m_compiler_parser::parser::{NamedType, TopLevelItem, TypeExpr};
+use cairo_m_compiler_parser::{SourceFile, parse_file};
+use chumsky::span::SimpleSpan;
+
+use crate::common::test_db;
 use crate::{assert_parses_err, assert_parses_ok, assert_parses_parameterized};
 
 #[test]
@@ -49,6 +54,8 @@ fn use_statements_parameterized() {
         ok: [
             "use std::math::add;",
             "use std::math::{add, sub};",
+            "pub use std::math::add as plus;",
+            "use std::math::{add as plus, sub};",
         ],
         err: [
             "use std::math::add",
@@ -134,6 +141,11 @@ fn imports_and_functions() {
     );
 }
 
+#[test]
+fn parameter_default_value() {
+    assert_parses_ok!("fn test(x: felt, y: felt = 3) { }");
+}
+
 #[test]
 fn empty_program() {
     assert_parses_ok!("");
@@ -195,3 +207,98 @@ fn function_with_loops() {
     "#
     );
 }
+
+#[test]
+fn impl_block_with_receiver() {
+    let db = test_db();
+    let code = "impl Point { fn norm(self, k: felt) -> felt { return self.x * k; } }";
+    let source = SourceFile::new(&db, code.to_string(), "test.cairo".to_string());
+    let result = parse_file(&db, source);
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+
+    let [TopLevelItem::Impl(impl_def)] = result.module.items() else {
+        panic!("expected a single impl block");
+    };
+    let impl_def = impl_def.value();
+    assert_eq!(impl_def.type_name.value(), "Point");
+    assert_eq!(impl_def.methods.len(), 1);
+
+    let params = &impl_def.methods[0].value().params;
+    assert!(params[0].is_receiver());
+    assert!(!params[1].is_receiver());
+    let TypeExpr::Named(receiver_type) = params[0].type_expr.value() else {
+        panic!("expected a named receiver type");
+    };
+    assert_eq!(
+        receiver_type.value(),
+        &NamedType::Custom("Point".to_string())
+    );
+
+    let names: Vec<_> = result.module.functions().map(|(name, _)| name).collect();
+    assert_eq!(names, ["Point::norm"]);
+}
+
+#[test]
+fn impl_block_errors() {
+    let db = test_db();
+    for code in ["impl { fn f(self) { } }", "impl Point fn f(self) { }"] {
+        let source = SourceFile::new(&db, code.to_string(), "test.cairo".to_string());
+        assert!(!parse_file(&db, source).diagnostics.is_empty(), "{code}");
+    }
+}
+
+#[test]
+fn doc_comments() {
+    let db = test_db();
+    let code = r#"
+//! Geometry helpers.
+//!
+//! Points and distances.
+
+/// The origin.
+const ORIGIN: felt = 0;
+
+/// A point in the plane.
+struct Point { x: felt, y: felt }
+
+impl Point {
+    /// Squared norm of the point.
+    ///
+    /// Avoids the square root.
+    fn norm(self) -> felt { return self.x * self.x + self.y * self.y; }
+}
+
+/// Checks the origin.
+#[must_use]
+fn is_origin(p: Point) -> bool { return p.norm() == ORIGIN; }
+
+// Not a doc comment.
+fn helper() { }
+
+//// Not a doc comment either.
+fn other() { }
+"#;
+    let source = SourceFile::new(&db, code.to_string(), "test.cairo".to_string());
+    let result = parse_file(&db, source);
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+
+    let docs = result.module.docs();
+    assert_eq!(
+        docs.module.as_deref(),
+        Some("Geometry helpers.\n\nPoints and distances.")
+    );
+
+    let doc = |name: &str| {
+        let start = code.find(&format!(" {name}")).unwrap() + 1;
+        docs.item(SimpleSpan::from(start..start + name.len()))
+    };
+    assert_eq!(doc("ORIGIN"), Some("The origin."));
+    assert_eq!(doc("Point"), Some("A point in the plane."));
+    assert_eq!(
+        doc("norm"),
+        Some("Squared norm of the point.\n\nAvoids the square root.")
+    );
+    assert_eq!(doc("is_origin"), Some("Checks the origin."));
+    assert_eq!(doc("helper"), None);
+    assert_eq!(doc("other"), None);
+}
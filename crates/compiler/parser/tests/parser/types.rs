@@ -59,6 +59,11 @@ fn fixed_size_array_type_custom_type() {
     assert_parses_ok!(&with_param("[MyStruct; 3]"));
 }
 
+#[test]
+fn fixed_size_array_type_named_size() {
+    assert_parses_ok!(&with_param("[felt; SIZE]"));
+}
+
 // ===================
 // Tuple Types
 // ===================
@@ -1,14 +1,83 @@
 //! Built-in functions registry and helpers.
 
+use crate::File;
+use crate::db::SemanticDb;
+use crate::types::{TypeData, TypeId};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BuiltinFn {
     Assert,
+    Panic,
+    DebugPrint,
+    /// `u32_overflowing_add(lhs, rhs) -> (u32, bool)`: wrapping sum and carry.
+    U32OverflowingAdd,
+    /// `u32_checked_sub(lhs, rhs) -> (u32, bool)`: wrapping difference and borrow.
+    U32CheckedSub,
+    /// `u32_wrapping_mul(lhs, rhs) -> u32`: product modulo 2^32.
+    U32WrappingMul,
+    /// `include_bytes(path) -> [felt; N]`: bytes of a file, one felt per byte, embedded at
+    /// compile time. Only valid as the value of a `const`.
+    IncludeBytes,
+    /// `env(index) -> felt`: value at `index` in the environment block, supplied by the runner
+    /// at run time. The index must be an integer literal.
+    Env,
+}
+
+impl BuiltinFn {
+    /// Returns the parameters and return type of built-ins that are typed like regular
+    /// functions.
+    ///
+    /// `assert`, `panic` and `debug_print` accept variable arguments, and the return type of
+    /// `include_bytes` depends on the included file: they are validated separately and have no
+    /// signature.
+    pub fn signature(self, db: &dyn SemanticDb) -> Option<(Vec<(&'static str, TypeId)>, TypeId)> {
+        let u32_type = TypeId::new(db, TypeData::U32);
+        let bool_type = TypeId::new(db, TypeData::Bool);
+        let u32_params = vec![("lhs", u32_type), ("rhs", u32_type)];
+        match self {
+            Self::Assert | Self::Panic | Self::DebugPrint | Self::IncludeBytes => None,
+            Self::Env => {
+                let felt_type = TypeId::new(db, TypeData::Felt);
+                Some((vec![("index", felt_type)], felt_type))
+            }
+            Self::U32OverflowingAdd | Self::U32CheckedSub => Some((
+                u32_params,
+                TypeId::new(db, TypeData::Tuple(vec![u32_type, bool_type])),
+            )),
+            Self::U32WrappingMul => Some((u32_params, u32_type)),
+        }
+    }
 }
 
 /// Return true if the given identifier is a recognized built-in function name.
 pub fn is_builtin_function_name(name: &str) -> Option<BuiltinFn> {
     match name {
         "assert" => Some(BuiltinFn::Assert),
+        "panic" => Some(BuiltinFn::Panic),
+        "debug_print" => Some(BuiltinFn::DebugPrint),
+        "u32_overflowing_add" => Some(BuiltinFn::U32OverflowingAdd),
+        "u32_checked_sub" => Some(BuiltinFn::U32CheckedSub),
+        "u32_wrapping_mul" => Some(BuiltinFn::U32WrappingMul),
+        "include_bytes" => Some(BuiltinFn::IncludeBytes),
+        "env" => Some(BuiltinFn::Env),
         _ => None,
     }
 }
+
+/// Reads the file included by an `include_bytes` call made from `file`
+///
+/// Relative paths are resolved from the directory of `file`.
+///
+/// ## Errors
+/// Returns the I/O error if the file can't be read.
+pub fn read_included_bytes(
+    db: &dyn SemanticDb,
+    file: File,
+    path: &str,
+) -> std::io::Result<Vec<u8>> {
+    let source_path = std::path::Path::new(file.file_path(db));
+    let path = source_path
+        .parent()
+        .map_or_else(|| std::path::PathBuf::from(path), |dir| dir.join(path));
+    std::fs::read(path)
+}
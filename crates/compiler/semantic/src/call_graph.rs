@@ -0,0 +1,247 @@
+//! # Call Graph
+//!
+//! Crate-wide graph of the calls between functions, built from the semantic index of every
+//! module. Calls are resolved like names are: through the scopes of the caller and its imports
+//! for plain calls, and through the type of the receiver for method calls. Calls to builtins,
+//! or to names that do not resolve to a function, are not part of the graph.
+
+use cairo_m_compiler_parser::parser::Expression;
+use chumsky::span::SimpleSpan;
+
+use crate::db::{Crate, SemanticDb, module_name_for_file, module_semantic_index};
+use crate::type_resolution::{expression_semantic_type, struct_method};
+use crate::{DefinitionKind, File, SemanticIndex, TypeData};
+
+/// A function of a crate, identified by its module and its name in that module
+///
+/// Methods are named as in [`ImplDef::method_name`](cairo_m_compiler_parser::parser::ImplDef::method_name).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FunctionRef {
+    pub module: String,
+    pub name: String,
+}
+
+/// A call from a function to another one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallSite {
+    /// The function making the call
+    pub caller: FunctionRef,
+    /// The function being called
+    pub callee: FunctionRef,
+    /// Span of the callee name at the call, in the module of the caller
+    pub span: SimpleSpan<usize>,
+}
+
+/// The calls between the functions of a crate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    /// All calls, ordered by caller module and position in that module
+    calls: Vec<CallSite>,
+}
+
+impl CallGraph {
+    /// All calls of the crate
+    pub fn calls(&self) -> &[CallSite] {
+        &self.calls
+    }
+
+    /// Calls made by `function`, in source order
+    pub fn outgoing_calls<'a>(
+        &'a self,
+        function: &'a FunctionRef,
+    ) -> impl Iterator<Item = &'a CallSite> + 'a {
+        self.calls
+            .iter()
+            .filter(move |call| &call.caller == function)
+    }
+
+    /// Calls made to `function`, grouped by caller module
+    pub fn incoming_calls<'a>(
+        &'a self,
+        function: &'a FunctionRef,
+    ) -> impl Iterator<Item = &'a CallSite> + 'a {
+        self.calls
+            .iter()
+            .filter(move |call| &call.callee == function)
+    }
+}
+
+/// Builds the call graph of a crate
+///
+/// Modules whose semantic index can't be built are skipped.
+#[salsa::tracked]
+pub fn crate_call_graph(db: &dyn SemanticDb, crate_id: Crate) -> CallGraph {
+    let mut modules: Vec<_> = crate_id.modules(db).iter().collect();
+    modules.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut calls = Vec::new();
+    for (module_name, file) in modules {
+        let Ok(index) = module_semantic_index(db, crate_id, module_name.clone()) else {
+            continue;
+        };
+        calls.extend(module_calls(db, crate_id, *file, module_name, &index));
+    }
+    CallGraph { calls }
+}
+
+/// Resolves the calls made by the functions of a module
+fn module_calls(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    module_name: &str,
+    index: &SemanticIndex,
+) -> Vec<CallSite> {
+    let functions: Vec<_> = index
+        .all_definitions()
+        .filter(|(_, def)| matches!(def.kind, DefinitionKind::Function(_)))
+        .map(|(_, def)| (def.name.clone(), def.full_span))
+        .collect();
+
+    let mut calls = Vec::new();
+    for (_, expr) in index.all_expressions() {
+        let Expression::FunctionCall { callee, .. } = &expr.ast_node else {
+            continue;
+        };
+        let Some(caller) = functions
+            .iter()
+            .filter(|(_, span)| span.start <= expr.ast_span.start && expr.ast_span.end <= span.end)
+            .min_by_key(|(_, span)| span.end - span.start)
+        else {
+            continue;
+        };
+
+        let resolved = match callee.value() {
+            Expression::Identifier(name) => index
+                .resolve_name_with_imports_at_position(
+                    db,
+                    crate_id,
+                    file,
+                    name.value(),
+                    expr.scope_id,
+                    name.span(),
+                )
+                .filter(|(_, def, _)| matches!(def.kind, DefinitionKind::Function(_)))
+                .map(|(_, def, def_file)| (def.name, def_file, name.span())),
+            Expression::MemberAccess { object, field } => {
+                let Some(object_id) = index.expression_id_by_span(object.span()) else {
+                    continue;
+                };
+                let TypeData::Struct(struct_id) =
+                    expression_semantic_type(db, crate_id, file, object_id, None).data(db)
+                else {
+                    continue;
+                };
+                struct_method(db, crate_id, struct_id, field.value()).and_then(|method_id| {
+                    let method_file = method_id.file(db);
+                    let method_module = module_name_for_file(db, crate_id, method_file)?;
+                    let method_index = module_semantic_index(db, crate_id, method_module).ok()?;
+                    let def = method_index.definition(method_id.id_in_file(db))?;
+                    Some((def.name.clone(), method_file, field.span()))
+                })
+            }
+            _ => None,
+        };
+        let Some((callee_name, callee_file, span)) = resolved else {
+            continue;
+        };
+        let Some(callee_module) = module_name_for_file(db, crate_id, callee_file) else {
+            continue;
+        };
+
+        calls.push(CallSite {
+            caller: FunctionRef {
+                module: module_name.to_string(),
+                name: caller.0.clone(),
+            },
+            callee: FunctionRef {
+                module: callee_module,
+                name: callee_name,
+            },
+            span,
+        });
+    }
+    calls.sort_by_key(|call| (call.span.start, call.span.end));
+    calls
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::db::tests::test_db;
+
+    fn function(module: &str, name: &str) -> FunctionRef {
+        FunctionRef {
+            module: module.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_call_graph_across_modules() {
+        let db = test_db();
+        let main = "use math::double;\n\
+                    fn main() -> felt { let p = Point { x: 1 }; return helper(p.get()) + double(2); }\n\
+                    fn helper(x: felt) -> felt { return double(x); }\n\
+                    struct Point { x: felt }\n\
+                    impl Point { fn get(self) -> felt { return self.x; } }";
+        let math = "fn double(x: felt) -> felt { return x + x; }\nfn unused() { return; }";
+        let modules = HashMap::from([
+            (
+                "main".to_string(),
+                File::new(&db, main.to_string(), "main.cm".to_string()),
+            ),
+            (
+                "math".to_string(),
+                File::new(&db, math.to_string(), "math.cm".to_string()),
+            ),
+        ]);
+        let crate_id = Crate::new(
+            &db,
+            modules,
+            "main".to_string(),
+            PathBuf::from("."),
+            "crate_test".to_string(),
+        );
+
+        let graph = crate_call_graph(&db, crate_id);
+        let callees = |caller: &FunctionRef| {
+            graph
+                .outgoing_calls(caller)
+                .map(|call| call.callee.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            callees(&function("main", "main")),
+            vec![
+                function("main", "helper"),
+                function("main", "Point::get"),
+                function("math", "double"),
+            ]
+        );
+        assert_eq!(
+            callees(&function("main", "helper")),
+            vec![function("math", "double")]
+        );
+
+        let callers: Vec<_> = graph
+            .incoming_calls(&function("math", "double"))
+            .map(|call| call.caller.clone())
+            .collect();
+        assert_eq!(
+            callers,
+            vec![function("main", "main"), function("main", "helper")]
+        );
+        assert_eq!(graph.incoming_calls(&function("math", "unused")).count(), 0);
+
+        // Spans point at the callee name in the caller's module
+        let call = graph
+            .outgoing_calls(&function("main", "helper"))
+            .next()
+            .unwrap();
+        assert_eq!(&main[call.span.start..call.span.end], "double");
+    }
+}
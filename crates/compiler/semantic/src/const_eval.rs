@@ -0,0 +1,475 @@
+//! # Constant Evaluation
+//!
+//! Folds the values of `const` items, so that constants can be defined from other constants
+//! (`const B = A * 2 + SIZE;`) and used as array sizes (`[felt; SIZE]`).
+//!
+//! Top-level constants can refer to constants declared later in the module, so constants are
+//! evaluated on demand, in dependency order. The dependencies are checked for cycles first:
+//! the constants of a cycle have no value, and the type of an unannotated one is an error
+//! rather than a query cycle.
+//!
+//! Only felt, u32 and bool values built from literals, other constants and operators are
+//! folded. Other constants, such as arrays, are still lowered from their value expression.
+
+use cairo_m_compiler_parser::parser::{
+    BinaryOp, Expression, NamedType, Spanned, TypeExpr, UnaryOp,
+};
+use chumsky::span::SimpleSpan;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::File;
+use crate::db::{Crate, SemanticDb, module_name_for_file, module_semantic_index};
+use crate::definition::{DefinitionKind, UseDefRef};
+use crate::semantic_index::{DefinitionId, DefinitionIndex, SemanticIndex, resolve_imported_item};
+
+/// The M31 field modulus.
+const P: u64 = (1 << 31) - 1;
+
+/// Folded value of a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstValue {
+    Felt(u32),
+    U32(u32),
+    Bool(bool),
+}
+
+impl ConstValue {
+    /// Returns the value as an array size, if it is an integer.
+    pub const fn as_array_size(self) -> Option<usize> {
+        match self {
+            Self::Felt(value) | Self::U32(value) => Some(value as usize),
+            Self::Bool(_) => None,
+        }
+    }
+
+    const fn kind(self) -> ConstKind {
+        match self {
+            Self::Felt(_) => ConstKind::Felt,
+            Self::U32(_) => ConstKind::U32,
+            Self::Bool(_) => ConstKind::Bool,
+        }
+    }
+}
+
+/// Folded values of the constants of a module.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleConstValues {
+    /// Values of the constants, and of the imports of constants.
+    values: FxHashMap<DefinitionIndex, ConstValue>,
+    /// Constants depending on themselves, each cycle starting from the constant first reached.
+    cycles: Vec<Vec<DefinitionIndex>>,
+}
+
+impl ModuleConstValues {
+    /// Returns the folded value of a constant, or of an imported constant.
+    pub fn value(&self, def_idx: DefinitionIndex) -> Option<ConstValue> {
+        self.values.get(&def_idx).copied()
+    }
+
+    /// Returns the dependency cycles between the constants of the module.
+    pub fn cycles(&self) -> &[Vec<DefinitionIndex>] {
+        &self.cycles
+    }
+
+    /// Checks whether a constant belongs to a dependency cycle.
+    pub fn is_cyclic(&self, def_idx: DefinitionIndex) -> bool {
+        self.cycles.iter().any(|cycle| cycle.contains(&def_idx))
+    }
+}
+
+/// Folds the constants of a module.
+#[salsa::tracked]
+pub fn module_const_values(db: &dyn SemanticDb, crate_id: Crate, file: File) -> ModuleConstValues {
+    let Some(module_name) = module_name_for_file(db, crate_id, file) else {
+        return ModuleConstValues::default();
+    };
+    let Ok(index) = module_semantic_index(db, crate_id, module_name) else {
+        return ModuleConstValues::default();
+    };
+
+    let cycles = find_cycles(&index);
+    let mut evaluator = ConstEvaluator {
+        db,
+        crate_id,
+        index: &index,
+        cyclic: cycles.iter().flatten().copied().collect(),
+        values: FxHashMap::default(),
+    };
+    let mut values = FxHashMap::default();
+    for (def_idx, def) in index.all_definitions() {
+        let value = match &def.kind {
+            DefinitionKind::Const(_) => evaluator.eval_const(def_idx),
+            DefinitionKind::Use(use_ref) => imported_const_value(db, crate_id, use_ref),
+            _ => None,
+        };
+        if let Some(value) = value {
+            values.insert(def_idx, value);
+        }
+    }
+
+    ModuleConstValues { values, cycles }
+}
+
+/// Returns the folded value of a constant, or of an imported constant.
+pub fn const_value<'db>(
+    db: &'db dyn SemanticDb,
+    crate_id: Crate,
+    def_id: DefinitionId<'db>,
+) -> Option<ConstValue> {
+    module_const_values(db, crate_id, def_id.file(db)).value(def_id.id_in_file(db))
+}
+
+fn imported_const_value(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    use_ref: &UseDefRef,
+) -> Option<ConstValue> {
+    let (def_idx, _, imported_file) = resolve_imported_item(
+        db,
+        crate_id,
+        use_ref.imported_module.value(),
+        use_ref.item.value(),
+    )?;
+    module_const_values(db, crate_id, imported_file).value(def_idx)
+}
+
+/// Finds the cycles in the dependencies between constants with a depth-first search.
+///
+/// Every cycle contains at least one of the reported ones, so stopping at the constants of the
+/// reported cycles is enough to break any recursion through constant values.
+fn find_cycles(index: &SemanticIndex) -> Vec<Vec<DefinitionIndex>> {
+    fn visit(
+        index: &SemanticIndex,
+        def_idx: DefinitionIndex,
+        path: &mut Vec<DefinitionIndex>,
+        visited: &mut FxHashSet<DefinitionIndex>,
+        cycles: &mut Vec<Vec<DefinitionIndex>>,
+    ) {
+        if let Some(position) = path.iter().position(|&idx| idx == def_idx) {
+            cycles.push(path[position..].to_vec());
+            return;
+        }
+        if !visited.insert(def_idx) {
+            return;
+        }
+        path.push(def_idx);
+        for dependency in const_dependencies(index, def_idx) {
+            visit(index, dependency, path, visited, cycles);
+        }
+        path.pop();
+    }
+
+    let mut visited = FxHashSet::default();
+    let mut cycles = Vec::new();
+    for (def_idx, def) in index.all_definitions() {
+        if matches!(def.kind, DefinitionKind::Const(_)) {
+            visit(index, def_idx, &mut Vec::new(), &mut visited, &mut cycles);
+        }
+    }
+    cycles
+}
+
+/// Returns the constants of the module referenced by the value of a constant.
+fn const_dependencies(index: &SemanticIndex, def_idx: DefinitionIndex) -> Vec<DefinitionIndex> {
+    let Some(DefinitionKind::Const(const_ref)) = index.definition(def_idx).map(|def| &def.kind)
+    else {
+        return Vec::new();
+    };
+    let Some(expr_info) = const_ref
+        .value_expr_id
+        .and_then(|expr_id| index.expression(expr_id))
+    else {
+        return Vec::new();
+    };
+
+    let mut dependencies = Vec::new();
+    let value = Spanned::new(expr_info.ast_node.clone(), expr_info.ast_span);
+    for_each_identifier(&value, &mut |span| {
+        if let Some((dep_idx, dep)) = index
+            .expression_id_by_span(span)
+            .and_then(|expr_id| index.definition_for_identifier_expr(expr_id))
+            && matches!(dep.kind, DefinitionKind::Const(_))
+        {
+            dependencies.push(dep_idx);
+        }
+    });
+    dependencies
+}
+
+/// Calls `f` with the span of every identifier expression of `expr`.
+fn for_each_identifier(expr: &Spanned<Expression>, f: &mut impl FnMut(SimpleSpan<usize>)) {
+    match expr.value() {
+        Expression::Identifier(_) => f(expr.span()),
+        Expression::Literal(..) | Expression::BooleanLiteral(_) | Expression::StringLiteral(_) => {}
+        Expression::UnaryOp { expr: inner, .. }
+        | Expression::Parenthesized(inner)
+        | Expression::MemberAccess { object: inner, .. }
+        | Expression::TupleIndex { tuple: inner, .. }
+        | Expression::ArrayRepeat { element: inner, .. }
+        | Expression::Cast { expr: inner, .. }
+        | Expression::New { count: inner, .. }
+        | Expression::Block { value: inner, .. } => for_each_identifier(inner, f),
+        Expression::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            for_each_identifier(condition, f);
+            for_each_identifier(then_branch, f);
+            for_each_identifier(else_branch, f);
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            for_each_identifier(left, f);
+            for_each_identifier(right, f);
+        }
+        Expression::IndexAccess { array, index } => {
+            for_each_identifier(array, f);
+            for_each_identifier(index, f);
+        }
+        Expression::FunctionCall { callee, args } => {
+            for_each_identifier(callee, f);
+            for arg in args {
+                for_each_identifier(arg, f);
+            }
+        }
+        Expression::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                for_each_identifier(value, f);
+            }
+        }
+        Expression::Tuple(elements) | Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                for_each_identifier(element, f);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConstKind {
+    Felt,
+    U32,
+    Bool,
+}
+
+impl ConstKind {
+    fn from_type_expr(type_expr: &TypeExpr) -> Option<Self> {
+        match type_expr {
+            TypeExpr::Named(named) => match named.value() {
+                NamedType::Felt => Some(Self::Felt),
+                NamedType::U32 => Some(Self::U32),
+                NamedType::Bool => Some(Self::Bool),
+                NamedType::Custom(_) => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A partially folded expression: integer literals without suffix take the type of the
+/// operation they appear in, as they do during type inference.
+#[derive(Debug, Clone, Copy)]
+enum Folded {
+    Value(ConstValue),
+    Int(u64),
+}
+
+impl Folded {
+    const fn kind(self) -> Option<ConstKind> {
+        match self {
+            Self::Value(value) => Some(value.kind()),
+            Self::Int(_) => None,
+        }
+    }
+
+    fn coerce(self, kind: ConstKind) -> Option<ConstValue> {
+        match (self, kind) {
+            (Self::Value(value), _) => (value.kind() == kind).then_some(value),
+            (Self::Int(value), ConstKind::Felt) => {
+                (value < P).then(|| ConstValue::Felt(value as u32))
+            }
+            (Self::Int(value), ConstKind::U32) => u32::try_from(value).ok().map(ConstValue::U32),
+            (Self::Int(_), ConstKind::Bool) => None,
+        }
+    }
+}
+
+struct ConstEvaluator<'a, 'db> {
+    db: &'db dyn SemanticDb,
+    crate_id: Crate,
+    index: &'a SemanticIndex,
+    /// Constants of the dependency cycles, which are not evaluated.
+    cyclic: FxHashSet<DefinitionIndex>,
+    /// Memoized values of the constants evaluated so far.
+    values: FxHashMap<DefinitionIndex, Option<ConstValue>>,
+}
+
+impl ConstEvaluator<'_, '_> {
+    fn eval_const(&mut self, def_idx: DefinitionIndex) -> Option<ConstValue> {
+        if self.cyclic.contains(&def_idx) {
+            return None;
+        }
+        if let Some(value) = self.values.get(&def_idx) {
+            return *value;
+        }
+        let value = self.fold_const(def_idx);
+        self.values.insert(def_idx, value);
+        value
+    }
+
+    fn fold_const(&mut self, def_idx: DefinitionIndex) -> Option<ConstValue> {
+        let index = self.index;
+        let DefinitionKind::Const(const_ref) = &index.definition(def_idx)?.kind else {
+            return None;
+        };
+        let kind = match &const_ref.type_ast {
+            Some(type_ast) => Some(ConstKind::from_type_expr(type_ast.value())?),
+            None => None,
+        };
+        let expr_info = index.expression(const_ref.value_expr_id?)?;
+        let value = Spanned::new(expr_info.ast_node.clone(), expr_info.ast_span);
+        self.fold_typed(&value, kind)
+    }
+
+    /// Folds an expression, giving integer literals the expected type, or felt by default.
+    fn fold_typed(
+        &mut self,
+        expr: &Spanned<Expression>,
+        expected: Option<ConstKind>,
+    ) -> Option<ConstValue> {
+        let folded = self.fold(expr, expected)?;
+        folded.coerce(expected.or(folded.kind()).unwrap_or(ConstKind::Felt))
+    }
+
+    fn fold(&mut self, expr: &Spanned<Expression>, expected: Option<ConstKind>) -> Option<Folded> {
+        match expr.value() {
+            Expression::Literal(value, None) => Some(Folded::Int(*value)),
+            Expression::Literal(value, Some(suffix)) => {
+                let kind = match suffix.as_str() {
+                    "felt" => ConstKind::Felt,
+                    "u32" => ConstKind::U32,
+                    _ => return None,
+                };
+                Folded::Int(*value).coerce(kind).map(Folded::Value)
+            }
+            Expression::BooleanLiteral(value) => Some(Folded::Value(ConstValue::Bool(*value))),
+            Expression::Parenthesized(inner) => self.fold(inner, expected),
+            Expression::Identifier(_) => self.fold_identifier(expr.span()).map(Folded::Value),
+            Expression::UnaryOp { op, expr: operand } => {
+                let value = match op {
+                    UnaryOp::Not => match self.fold_typed(operand, Some(ConstKind::Bool))? {
+                        ConstValue::Bool(value) => ConstValue::Bool(!value),
+                        _ => return None,
+                    },
+                    UnaryOp::Neg => match self.fold_typed(operand, expected)? {
+                        ConstValue::Felt(value) => {
+                            ConstValue::Felt(((P - value as u64) % P) as u32)
+                        }
+                        _ => return None,
+                    },
+                };
+                Some(Folded::Value(value))
+            }
+            Expression::BinaryOp { op, left, right } => self
+                .fold_binary_op(*op, left, right, expected)
+                .map(Folded::Value),
+            _ => None,
+        }
+    }
+
+    /// Folds a reference to a constant of this module, or to an imported constant.
+    fn fold_identifier(&mut self, span: SimpleSpan<usize>) -> Option<ConstValue> {
+        let index = self.index;
+        let expr_id = index.expression_id_by_span(span)?;
+        let (def_idx, def) = index.definition_for_identifier_expr(expr_id)?;
+        match &def.kind {
+            DefinitionKind::Const(_) => self.eval_const(def_idx),
+            DefinitionKind::Use(use_ref) => imported_const_value(self.db, self.crate_id, use_ref),
+            _ => None,
+        }
+    }
+
+    fn fold_binary_op(
+        &mut self,
+        op: BinaryOp,
+        left: &Spanned<Expression>,
+        right: &Spanned<Expression>,
+        expected: Option<ConstKind>,
+    ) -> Option<ConstValue> {
+        use ConstValue::{Bool, Felt, U32};
+
+        if matches!(op, BinaryOp::And | BinaryOp::Or) {
+            let (Bool(a), Bool(b)) = (
+                self.fold_typed(left, Some(ConstKind::Bool))?,
+                self.fold_typed(right, Some(ConstKind::Bool))?,
+            ) else {
+                return None;
+            };
+            return Some(Bool(if op == BinaryOp::And { a && b } else { a || b }));
+        }
+
+        // Operands of comparisons do not take the type of the result.
+        let is_comparison = matches!(
+            op,
+            BinaryOp::Eq
+                | BinaryOp::Neq
+                | BinaryOp::Less
+                | BinaryOp::Greater
+                | BinaryOp::LessEqual
+                | BinaryOp::GreaterEqual
+        );
+        let operand_expected = if is_comparison { None } else { expected };
+        let left = self.fold(left, operand_expected)?;
+        let right = self.fold(right, operand_expected)?;
+        let kind = left
+            .kind()
+            .or(right.kind())
+            .or(operand_expected)
+            .unwrap_or(ConstKind::Felt);
+
+        let value = match (op, left.coerce(kind)?, right.coerce(kind)?) {
+            (BinaryOp::Eq, a, b) => Bool(a == b),
+            (BinaryOp::Neq, a, b) => Bool(a != b),
+
+            (BinaryOp::Add, Felt(a), Felt(b)) => Felt(((a as u64 + b as u64) % P) as u32),
+            (BinaryOp::Sub, Felt(a), Felt(b)) => Felt(((a as u64 + P - b as u64) % P) as u32),
+            (BinaryOp::Mul, Felt(a), Felt(b)) => Felt(((a as u64 * b as u64) % P) as u32),
+            (BinaryOp::Div, Felt(a), Felt(b)) if b != 0 => {
+                Felt(((a as u64 * felt_inverse(b as u64)) % P) as u32)
+            }
+
+            (BinaryOp::Add, U32(a), U32(b)) => U32(a.wrapping_add(b)),
+            (BinaryOp::Sub, U32(a), U32(b)) => U32(a.wrapping_sub(b)),
+            (BinaryOp::Mul, U32(a), U32(b)) => U32(a.wrapping_mul(b)),
+            (BinaryOp::Div, U32(a), U32(b)) if b != 0 => U32(a / b),
+            (BinaryOp::Mod, U32(a), U32(b)) if b != 0 => U32(a % b),
+            (BinaryOp::BitwiseAnd, U32(a), U32(b)) => U32(a & b),
+            (BinaryOp::BitwiseOr, U32(a), U32(b)) => U32(a | b),
+            (BinaryOp::BitwiseXor, U32(a), U32(b)) => U32(a ^ b),
+            (BinaryOp::ShiftLeft, U32(a), U32(b)) => U32(a.wrapping_shl(b)),
+            (BinaryOp::ShiftRight, U32(a), U32(b)) => U32(a.wrapping_shr(b)),
+            (BinaryOp::Less, U32(a), U32(b)) => Bool(a < b),
+            (BinaryOp::Greater, U32(a), U32(b)) => Bool(a > b),
+            (BinaryOp::LessEqual, U32(a), U32(b)) => Bool(a <= b),
+            (BinaryOp::GreaterEqual, U32(a), U32(b)) => Bool(a >= b),
+
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Computes the inverse of a non-zero felt, as `x^(P - 2)`.
+const fn felt_inverse(x: u64) -> u64 {
+    let mut result = 1;
+    let mut base = x % P;
+    let mut exponent = P - 2;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % P;
+        }
+        base = base * base % P;
+        exponent >>= 1;
+    }
+    result
+}
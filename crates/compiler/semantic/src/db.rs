@@ -138,6 +138,66 @@ pub fn crate_from_project(
     let mut modules = HashMap::new();
     let mut diagnostics = DiagnosticCollection::default();
 
+    collect_project_modules(db, &project, None, &mut modules, &mut diagnostics);
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    Ok(new_crate_for_project(db, project, modules))
+}
+
+/// Create a semantic crate for a workspace member, including the modules of all the
+/// members it transitively depends on.
+///
+/// Modules of a dependency are namespaced under the dependency's name, so that
+/// `dep::utils` refers to the `utils` module of the `dep` member. Modules of the member
+/// itself keep their unqualified names.
+pub fn crate_from_workspace_member(
+    db: &dyn SemanticDb,
+    workspace: &cairo_m_project::Workspace,
+    member: cairo_m_project::ProjectId,
+) -> Result<Crate, DiagnosticCollection> {
+    let mut modules = HashMap::new();
+    let mut diagnostics = DiagnosticCollection::default();
+
+    let build_order = match workspace.build_order_for(member) {
+        Ok(order) => order,
+        Err(e) => {
+            diagnostics.add(Diagnostic::error(
+                DiagnosticCode::InternalError,
+                format!("Failed to resolve workspace dependencies: {}", e),
+            ));
+            return Err(diagnostics);
+        }
+    };
+
+    for id in build_order {
+        let project = &workspace.projects[&id];
+        let prefix = (id != member).then_some(project.name.as_str());
+        collect_project_modules(db, project, prefix, &mut modules, &mut diagnostics);
+    }
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    Ok(new_crate_for_project(
+        db,
+        workspace.projects[&member].clone(),
+        modules,
+    ))
+}
+
+/// Read all source files of `project` into `modules`, optionally namespacing the module
+/// names under `module_prefix`.
+fn collect_project_modules(
+    db: &dyn SemanticDb,
+    project: &cairo_m_project::Project,
+    module_prefix: Option<&str>,
+    modules: &mut HashMap<String, File>,
+    diagnostics: &mut DiagnosticCollection,
+) {
     // Get all source files from the project
     let source_files = match project.source_files() {
         Ok(files) => files,
@@ -146,7 +206,7 @@ pub fn crate_from_project(
                 DiagnosticCode::InternalError,
                 format!("Failed to discover source files: {}", e),
             ));
-            return Err(diagnostics);
+            return;
         }
     };
 
@@ -166,6 +226,10 @@ pub fn crate_from_project(
                 continue;
             }
         };
+        let module_name = match module_prefix {
+            Some(prefix) => format!("{prefix}::{module_name}"),
+            None => module_name,
+        };
 
         // Read the file content
         let content = match std::fs::read_to_string(&file_path) {
@@ -183,11 +247,13 @@ pub fn crate_from_project(
         let file = File::new(db, content, file_path.to_string_lossy().to_string());
         modules.insert(module_name, file);
     }
+}
 
-    if !diagnostics.is_empty() {
-        return Err(diagnostics);
-    }
-
+fn new_crate_for_project(
+    db: &dyn SemanticDb,
+    project: cairo_m_project::Project,
+    modules: HashMap<String, File>,
+) -> Crate {
     // Determine entry point module name from the entry file
     let entry_point = project
         .root_directory
@@ -196,13 +262,13 @@ pub fn crate_from_project(
         .unwrap_or("main")
         .to_string();
 
-    Ok(Crate::new(
+    Crate::new(
         db,
         modules,
         entry_point,
         project.root_directory.clone(),
         project.name,
-    ))
+    )
 }
 
 #[salsa::tracked]
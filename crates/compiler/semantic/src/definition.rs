@@ -80,6 +80,9 @@ impl fmt::Display for DefinitionKind {
     }
 }
 
+/// Attribute marking a function whose result must be used: `#[must_use]`
+pub const MUST_USE_ATTRIBUTE: &str = "must_use";
+
 /// Reference to a function definition in the AST
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FunctionDefRef {
@@ -88,6 +91,8 @@ pub struct FunctionDefRef {
     pub params_ast: Vec<(String, Spanned<TypeExpr>)>,
     /// Return type AST expression (defaults to unit type)
     pub return_type_ast: Spanned<TypeExpr>,
+    /// Whether the function is marked `#[must_use]`
+    pub must_use: bool,
 }
 
 impl FunctionDefRef {
@@ -101,6 +106,11 @@ impl FunctionDefRef {
                 .map(|param| (param.name.value().clone(), param.type_expr.clone()))
                 .collect(),
             return_type_ast: func.value().return_type.clone(),
+            must_use: func
+                .value()
+                .attributes
+                .iter()
+                .any(|attr| attr.value() == MUST_USE_ATTRIBUTE),
         }
     }
 }
@@ -212,7 +222,19 @@ impl ParameterDefRef {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct UseDefRef {
     pub imported_module: Spanned<String>,
+    /// Name of the item in the imported module
     pub item: Spanned<String>,
+    /// Name the item is imported as, for `use foo::bar as baz;`
+    pub alias: Option<Spanned<String>>,
+    /// Whether the item is re-exported to the importers of the module, for `pub use`
+    pub is_pub: bool,
+}
+
+impl UseDefRef {
+    /// Name the item is bound to in the importing module
+    pub fn local_name(&self) -> &Spanned<String> {
+        self.alias.as_ref().unwrap_or(&self.item)
+    }
 }
 
 /// Reference to a loop variable definition
@@ -264,6 +286,7 @@ mod tests {
 
         // Test various definition kind constructors
         let func_def = FunctionDef {
+            attributes: vec![],
             name: Spanned::new("test_func".to_string(), SimpleSpan::from(0..5)),
             params: vec![],
             return_type: named_type(NamedType::Felt),
@@ -274,6 +297,7 @@ mod tests {
         assert_eq!(func_ref.name, "test_func");
         assert_eq!(func_ref.return_type_ast, named_type(NamedType::Felt));
         assert_eq!(func_ref.params_ast.len(), 0);
+        assert!(!func_ref.must_use);
 
         let struct_def = StructDef {
             name: Spanned::new("Point".to_string(), SimpleSpan::from(0..5)),
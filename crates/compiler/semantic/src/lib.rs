@@ -25,6 +25,8 @@ use cairo_m_compiler_parser as parser;
 pub use parser::{ParsedModule, SourceFile, parse_file};
 
 pub mod builtins;
+pub mod call_graph;
+pub mod const_eval;
 pub mod definition;
 pub mod place;
 pub mod semantic_index;
@@ -1,5 +1,5 @@
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode};
-use cairo_m_compiler_parser::parser::TopLevelItem;
+use cairo_m_compiler_parser::parser::{ImplDef, TopLevelItem};
 use rustc_hash::{FxBuildHasher, FxHashSet};
 
 pub trait SemanticSyntaxContext {
@@ -37,10 +37,25 @@ impl SemanticSyntaxChecker {
         let mut all_item_names = FxHashSet::with_capacity_and_hasher(items.len(), FxBuildHasher);
         for item in items {
             let names = match item {
-                TopLevelItem::Function(func) => vec![func.value().name.value().as_str()],
-                TopLevelItem::Struct(struct_def) => vec![struct_def.value().name.value().as_str()],
-                TopLevelItem::Const(const_def) => vec![const_def.value().name.value().as_str()],
-                TopLevelItem::Use(use_stmt) => use_stmt.value().items.names(),
+                TopLevelItem::Function(func) => vec![func.value().name.value().clone()],
+                TopLevelItem::Struct(struct_def) => vec![struct_def.value().name.value().clone()],
+                TopLevelItem::Const(const_def) => vec![const_def.value().name.value().clone()],
+                TopLevelItem::Use(use_stmt) => use_stmt
+                    .value()
+                    .items
+                    .names()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+                TopLevelItem::Impl(impl_def) => {
+                    let type_name = impl_def.value().type_name.value();
+                    impl_def
+                        .value()
+                        .methods
+                        .iter()
+                        .map(|method| ImplDef::method_name(type_name, method.value().name.value()))
+                        .collect()
+                }
             };
 
             let spans = match item {
@@ -48,10 +63,16 @@ impl SemanticSyntaxChecker {
                 TopLevelItem::Struct(struct_def) => vec![struct_def.value().name.span()],
                 TopLevelItem::Const(const_def) => vec![const_def.value().name.span()],
                 TopLevelItem::Use(use_stmt) => use_stmt.value().items.spans(),
+                TopLevelItem::Impl(impl_def) => impl_def
+                    .value()
+                    .methods
+                    .iter()
+                    .map(|method| method.value().name.span())
+                    .collect(),
             };
 
-            for (item_name, span) in names.iter().zip(spans) {
-                if !all_item_names.insert(*item_name) {
+            for (item_name, span) in names.into_iter().zip(spans) {
+                if !all_item_names.insert(item_name.clone()) {
                     Self::add_error(
                         ctx,
                         Diagnostic {
@@ -61,6 +82,7 @@ impl SemanticSyntaxChecker {
                             file_path: ctx.path().to_string(),
                             span,
                             related_spans: vec![],
+                            module_path: None,
                         },
                     );
                 }
@@ -52,8 +52,8 @@ use cairo_m_compiler_diagnostics::{
 };
 use cairo_m_compiler_parser::ParsedModule;
 use cairo_m_compiler_parser::parser::{
-    ConstDef, Expression, FunctionDef, NamedType, Parameter, Pattern, Spanned, Statement,
-    StructDef, TopLevelItem, TypeExpr, UseItems, UseStmt,
+    ArraySize, ConstDef, Expression, FunctionDef, ImplDef, NamedType, Parameter, Pattern, Spanned,
+    Statement, StructDef, TopLevelItem, TypeExpr, UseStmt,
 };
 use chumsky::span::SimpleSpan;
 use index_vec::IndexVec;
@@ -146,6 +146,8 @@ pub enum Origin {
     ReturnExpr,
     /// Expression is a condition in a control-flow statement.
     Condition { kind: ConditionKind },
+    /// Expression is an operand of a unary or binary operator, or is parenthesized.
+    Operand { parent: ExpressionId },
 }
 
 /// Information about an expression node in the AST
@@ -252,6 +254,12 @@ pub struct SemanticIndex {
     /// **Indexed by**: Vector index (used as key in `uses` map)
     identifier_usages: Vec<IdentifierUsage>,
 
+    /// Identifier usages naming the size of a fixed-size array type, e.g. `N` in `[felt; N]`.
+    ///
+    /// **Used by**: Array size validation
+    /// **Values**: Indices into `identifier_usages`
+    array_size_usages: Vec<usize>,
+
     /// **Type usage tracking**: All type usage sites with location and scope context.
     ///
     /// Records every place a type is used (not defined), with its exact source
@@ -309,6 +317,15 @@ pub struct SemanticIndex {
     /// **Key**: Scope where the use statement appears, **Value**: The imported item info
     pub(crate) imports: Vec<(FileScopeId, crate::definition::UseDefRef)>,
 
+    /// **Documentation**: The `///` doc comments of the documented top-level definitions.
+    ///
+    /// **Used by**: Hover and completion documentation
+    /// **Key**: The documented definition, **Value**: Its doc comment, as markdown
+    docs: FxHashMap<DefinitionIndex, String>,
+
+    /// The `//!` doc comment of the module, as markdown.
+    module_doc: Option<String>,
+
     /// **Semantic errors**: All semantic errors collected while building the index.
     pub semantic_syntax_errors: DiagnosticCollection,
 }
@@ -321,6 +338,7 @@ impl SemanticIndex {
             definitions: IndexVec::new(),
             uses: FxHashMap::default(),
             identifier_usages: Vec::new(),
+            array_size_usages: Vec::new(),
             type_usages: Vec::new(),
             type_usage_to_definition: FxHashMap::default(),
             expressions: IndexVec::new(),
@@ -329,6 +347,8 @@ impl SemanticIndex {
             used_definitions: FxHashSet::default(),
             identifier_expr_to_usage: FxHashMap::default(),
             imports: Vec::new(),
+            docs: FxHashMap::default(),
+            module_doc: None,
             semantic_syntax_errors: Default::default(),
         }
     }
@@ -430,13 +450,16 @@ impl SemanticIndex {
                 for &def_idx in def_indices.iter().rev() {
                     if let Some(def) = self.definition(def_idx) {
                         // Skip if declared after position for local (non-top-level) definitions only.
-                        // Forward references are allowed for top-level Function/Struct/Use.
-                        let is_top_level_allowed = matches!(
-                            def.kind,
+                        // Forward references are allowed for top-level Function/Struct/Use/Const.
+                        let is_top_level_allowed = match def.kind {
                             crate::definition::DefinitionKind::Function(_)
-                                | crate::definition::DefinitionKind::Struct(_)
-                                | crate::definition::DefinitionKind::Use(_)
-                        );
+                            | crate::definition::DefinitionKind::Struct(_)
+                            | crate::definition::DefinitionKind::Use(_) => true,
+                            crate::definition::DefinitionKind::Const(_) => {
+                                self.root_scope() == Some(def.scope_id)
+                            }
+                            _ => false,
+                        };
                         if !is_top_level_allowed && def.full_span.start > position.start {
                             continue;
                         }
@@ -479,7 +502,8 @@ impl SemanticIndex {
     ///
     /// Note: imported-module resolution is intended for top-level items only
     /// (Function/Struct/Use). It is not position-aware within the imported file
-    /// and allows forward references for those top-level kinds.
+    /// and allows forward references for those top-level kinds. Aliases are resolved to the
+    /// item they rename, and re-exports to the item's definition, see [`resolve_imported_item`].
     pub fn resolve_name_with_imports_at_position(
         &self,
         db: &dyn SemanticDb,
@@ -500,33 +524,17 @@ impl SemanticIndex {
 
         // Else, check imports visible from this scope
         let imports = self.get_imports_in_scope(starting_scope);
-        for use_def_ref in imports {
-            if use_def_ref.item.value() == name {
-                let imported_module_index = module_semantic_index(
+        imports
+            .into_iter()
+            .find(|use_def_ref| use_def_ref.local_name().value() == name)
+            .and_then(|use_def_ref| {
+                resolve_imported_item(
                     db,
                     crate_id,
-                    use_def_ref.imported_module.value().clone(),
+                    use_def_ref.imported_module.value(),
+                    use_def_ref.item.value(),
                 )
-                .ok()?;
-                if let Some(imported_root) = imported_module_index.root_scope() {
-                    if let Some(imported_def_idx) =
-                        imported_module_index.latest_definition_index_by_name(imported_root, name)
-                    {
-                        let imported_def = imported_module_index
-                            .definition(imported_def_idx)
-                            .expect("Definition should exist in imported module");
-                        if let Some(imported_file) = crate_id
-                            .modules(db)
-                            .get(use_def_ref.imported_module.value())
-                        {
-                            return Some((imported_def_idx, imported_def.clone(), *imported_file));
-                        }
-                    }
-                }
-            }
-        }
-
-        None
+            })
     }
 
     /// Get imports visible from a specific scope
@@ -555,6 +563,13 @@ impl SemanticIndex {
         self.definitions.push(definition)
     }
 
+    /// Attach its value expression to a forward-declared constant
+    pub(crate) fn set_const_value_expr(&mut self, def_idx: DefinitionIndex, expr_id: ExpressionId) {
+        if let DefinitionKind::Const(const_ref) = &mut self.definitions[def_idx].kind {
+            const_ref.value_expr_id = Some(expr_id);
+        }
+    }
+
     /// Get all definitions in a specific scope
     pub fn definitions_in_scope(
         &self,
@@ -566,6 +581,16 @@ impl SemanticIndex {
     }
 
     /// Get all definitions in the file
+    /// Get the doc comment of a definition, as markdown
+    pub fn definition_doc(&self, id: DefinitionIndex) -> Option<&str> {
+        self.docs.get(&id).map(String::as_str)
+    }
+
+    /// Get the doc comment of the module, as markdown
+    pub fn module_doc(&self) -> Option<&str> {
+        self.module_doc.as_deref()
+    }
+
     pub fn all_definitions(&self) -> impl Iterator<Item = (DefinitionIndex, &Definition)> + '_ {
         self.definitions.iter_enumerated()
     }
@@ -679,6 +704,13 @@ impl SemanticIndex {
         &self.type_usages
     }
 
+    /// Get the identifier usages naming array sizes
+    pub(crate) fn array_size_usages(&self) -> impl Iterator<Item = &IdentifierUsage> + '_ {
+        self.array_size_usages
+            .iter()
+            .map(|&usage_index| &self.identifier_usages[usage_index])
+    }
+
     /// Check if an identifier usage has a corresponding definition
     pub fn is_usage_resolved(&self, usage_index: usize) -> bool {
         self.uses.contains_key(&usage_index)
@@ -741,6 +773,66 @@ impl Default for SemanticIndex {
     }
 }
 
+/// Resolves the item `name` of the module `module_name`, as seen by the importers of the module
+///
+/// Re-exports (`pub use`) are followed to the definition they refer to, so that importing an
+/// item from a facade module links to its actual definition. Private imports of the module are
+/// not visible to its importers and don't resolve.
+///
+/// # Returns
+/// The definition with its index and file, or `None` if the module or the item doesn't exist,
+/// is a private import, or the re-exports form a cycle
+pub fn resolve_imported_item(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    module_name: &str,
+    name: &str,
+) -> Option<(DefinitionIndex, Definition, File)> {
+    let mut module_name = module_name.to_string();
+    let mut name = name.to_string();
+    let mut visited = FxHashSet::default();
+
+    while visited.insert((module_name.clone(), name.clone())) {
+        let file = *crate_id.modules(db).get(&module_name)?;
+        let index = module_semantic_index(db, crate_id, module_name.clone()).ok()?;
+        let def_idx = index.latest_definition_index_by_name(index.root_scope()?, &name)?;
+        let def = index.definition(def_idx)?;
+        match &def.kind {
+            DefinitionKind::Use(use_ref) if use_ref.is_pub => {
+                module_name = use_ref.imported_module.value().clone();
+                name = use_ref.item.value().clone();
+            }
+            DefinitionKind::Use(_) => return None,
+            _ => return Some((def_idx, def.clone(), file)),
+        }
+    }
+    None
+}
+
+/// Returns the doc comment of the definition `def_idx` of `file`
+///
+/// Imports are followed to the item they refer to, so that the doc of an imported item is
+/// available in the importing module.
+pub fn resolve_definition_doc(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    def_idx: DefinitionIndex,
+) -> Option<String> {
+    let module_name = crate::db::module_name_for_file(db, crate_id, file)?;
+    let index = module_semantic_index(db, crate_id, module_name).ok()?;
+    if let DefinitionKind::Use(use_ref) = &index.definition(def_idx)?.kind {
+        let (def_idx, _, file) = resolve_imported_item(
+            db,
+            crate_id,
+            use_ref.imported_module.value(),
+            use_ref.item.value(),
+        )?;
+        return resolve_definition_doc(db, crate_id, file, def_idx);
+    }
+    index.definition_doc(def_idx).map(str::to_string)
+}
+
 /// Build semantic index from an already-parsed module
 ///
 /// This function is useful when you already have a parsed module and want to
@@ -791,6 +883,8 @@ pub(crate) struct SemanticIndexBuilder<'db, 'sink> {
     loop_depth: usize,
     /// Current expected type hint for expression inference
     expected_type_hint: Option<Spanned<TypeExpr>>,
+    /// Top-level constants declared in pass 1, by span of their item
+    declared_consts: FxHashMap<SimpleSpan<usize>, DefinitionIndex>,
 
     /// Sink for collecting diagnostics while building the semantic index.
     diagnostic_sink: &'sink dyn DiagnosticSink,
@@ -823,6 +917,7 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
             scope_stack: Vec::new(),
             loop_depth: 0,
             expected_type_hint: None,
+            declared_consts: FxHashMap::default(),
             diagnostic_sink,
             semantic_syntax_checker: SemanticSyntaxChecker::default(),
         };
@@ -838,16 +933,17 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
     /// Build the semantic index from the module.
     /// Processes recursively all items from the root scope.
     pub(crate) fn build(mut self) -> SemanticIndex {
-        // Pass 1: Declare functions and structs for forward references
-        // Only functions and structs need forward declarations
+        // Pass 1: Declare top-level items for forward references
         {
             for item in self.module.items() {
                 match item {
-                    TopLevelItem::Function(func) => self.declare_function(func),
+                    TopLevelItem::Function(func) => {
+                        self.declare_function(func.value().name.value(), func)
+                    }
                     TopLevelItem::Struct(struct_def) => self.declare_struct(struct_def),
                     TopLevelItem::Use(use_stmt) => self.declare_use(use_stmt),
-                    // Structs, use statements, and consts will be handled in pass 2
-                    _ => {}
+                    TopLevelItem::Const(const_def) => self.declare_const(const_def),
+                    TopLevelItem::Impl(impl_def) => self.declare_impl(impl_def),
                 }
             }
         }
@@ -858,6 +954,23 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
         // Pop the root scope
         self.scope_stack.pop();
 
+        // Attach doc comments to the definitions whose name they precede
+        let docs = &self.module.docs;
+        self.index.module_doc = docs.module.clone();
+        self.index.docs = self
+            .index
+            .all_definitions()
+            .filter(|(_, def)| {
+                matches!(
+                    def.kind,
+                    DefinitionKind::Function(_)
+                        | DefinitionKind::Struct(_)
+                        | DefinitionKind::Const(_)
+                )
+            })
+            .filter_map(|(def_idx, def)| Some((def_idx, docs.item(def.name_span)?.to_string())))
+            .collect();
+
         self.index
     }
 
@@ -935,19 +1048,27 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
     }
 
     /// Declare a function without processing its body (for forward references)
-    fn declare_function(&mut self, func: &Spanned<FunctionDef>) {
+    fn declare_function(&mut self, name: &str, func: &Spanned<FunctionDef>) {
         use crate::definition::{DefinitionKind, FunctionDefRef};
         let func_def = func.value();
         let func_span = func.span();
 
         // Define the function in the current scope
-        let def_kind = DefinitionKind::Function(FunctionDefRef::from_ast(func));
-        self.add_place_with_definition(
-            func_def.name.value(),
-            def_kind,
-            func_def.name.span(),
-            func_span,
-        );
+        let mut func_ref = FunctionDefRef::from_ast(func);
+        func_ref.name = name.to_string();
+        let def_kind = DefinitionKind::Function(func_ref);
+        self.add_place_with_definition(name, def_kind, func_def.name.span(), func_span);
+    }
+
+    /// Declare the methods of an impl block as functions named `Type::method`, which can't
+    /// be referred to by an identifier: they are only reachable through method calls.
+    fn declare_impl(&mut self, impl_def: &Spanned<ImplDef>) {
+        let impl_def = impl_def.value();
+        for method in &impl_def.methods {
+            let name =
+                ImplDef::method_name(impl_def.type_name.value(), method.value().name.value());
+            self.declare_function(&name, method);
+        }
     }
 
     fn declare_struct(&mut self, struct_def: &Spanned<StructDef>) {
@@ -965,6 +1086,44 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
         );
     }
 
+    /// Declare a constant without its value, which is attached in pass 2, so that constants
+    /// can refer to constants declared later in the module.
+    fn declare_const(&mut self, const_def: &Spanned<ConstDef>) {
+        use crate::definition::{ConstDefRef, DefinitionKind};
+        let const_def_inner = const_def.value();
+
+        let def_kind = DefinitionKind::Const(ConstDefRef::from_ast(const_def, None));
+        let def_idx = self.add_place_with_definition(
+            const_def_inner.name.value(),
+            def_kind,
+            const_def_inner.name.span(),
+            const_def.span(),
+        );
+        self.declared_consts.insert(const_def.span(), def_idx);
+    }
+
+    /// Record the use of the constant naming an array size, e.g. `N` in `[felt; N]` or `[0; N]`
+    fn visit_array_size(&mut self, size: &Spanned<ArraySize>) {
+        let ArraySize::Const(name) = size.value() else {
+            return;
+        };
+        let current_scope = self.current_scope();
+        let usage = IdentifierUsage {
+            name: name.clone(),
+            span: size.span(),
+            scope_id: current_scope,
+        };
+        let usage_index = self.index.add_identifier_usage(usage);
+        self.index.array_size_usages.push(usage_index);
+        if let Some(def_idx) = self
+            .index
+            .latest_definition_index_by_name_in_chain(current_scope, name)
+        {
+            self.index.mark_definition_used(def_idx);
+            self.index.add_use(usage_index, def_idx);
+        }
+    }
+
     fn declare_use(&mut self, use_stmt: &Spanned<UseStmt>) {
         use crate::definition::{DefinitionKind, UseDefRef};
         let use_inner = use_stmt.value();
@@ -986,39 +1145,26 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
             use_span,
         );
 
-        // Process the imported items
-        match &use_inner.items {
-            UseItems::Single(item_spanned) => {
-                let item = item_spanned.value().clone();
-                let item_span = item_spanned.span();
-
-                let use_def_ref = UseDefRef {
-                    imported_module,
-                    item: item_spanned.clone(),
-                };
-                let def_kind = DefinitionKind::Use(use_def_ref.clone());
-                let current_scope = self.current_scope();
-                self.add_place_with_definition(&item, def_kind, item_span, use_span);
-
-                // Store the import for cross-module resolution
-                self.index.imports.push((current_scope, use_def_ref));
-            }
-            UseItems::List(items) => {
-                for item_spanned in items {
-                    let item = item_spanned.value().clone();
-                    let item_span = item_spanned.span();
-
-                    let use_def_ref = UseDefRef {
-                        imported_module: imported_module.clone(),
-                        item: item_spanned.clone(),
-                    };
-                    let def_kind = DefinitionKind::Use(use_def_ref.clone());
-                    let current_scope = self.current_scope();
-                    self.add_place_with_definition(&item, def_kind, item_span, use_span);
-
-                    self.index.imports.push((current_scope, use_def_ref));
-                }
-            }
+        // Process the imported items, bound to their alias if renamed
+        for item in use_inner.items.items() {
+            let local_name = item.local_name();
+            let use_def_ref = UseDefRef {
+                imported_module: imported_module.clone(),
+                item: item.name.clone(),
+                alias: item.alias.clone(),
+                is_pub: use_inner.is_pub,
+            };
+            let def_kind = DefinitionKind::Use(use_def_ref.clone());
+            let current_scope = self.current_scope();
+            self.add_place_with_definition(
+                local_name.value(),
+                def_kind,
+                local_name.span(),
+                use_span,
+            );
+
+            // Store the import for cross-module resolution
+            self.index.imports.push((current_scope, use_def_ref));
         }
     }
 
@@ -1083,14 +1229,14 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
                 // Note: Unresolved symbols will be detected in the validation pass
             }
             Expression::BinaryOp { left, right, .. } => {
-                self.visit_expr(left);
-                self.visit_expr(right);
+                self.visit_expr_with_origin(left, Origin::Operand { parent: expr_id });
+                self.visit_expr_with_origin(right, Origin::Operand { parent: expr_id });
             }
             Expression::UnaryOp { expr, .. } => {
-                self.visit_expr(expr);
+                self.visit_expr_with_origin(expr, Origin::Operand { parent: expr_id });
             }
             Expression::Parenthesized(inner) => {
-                self.visit_expr(inner);
+                self.visit_expr_with_origin(inner, Origin::Operand { parent: expr_id });
             }
             Expression::FunctionCall { callee, args } => {
                 self.visit_expr(callee);
@@ -1265,7 +1411,10 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
                         .as_ref()
                         .and_then(|hint| match hint.value() {
                             TypeExpr::FixedArray { element_type, size }
-                                if *size.value() == elements.len() as u64 =>
+                                if size
+                                    .value()
+                                    .literal()
+                                    .is_none_or(|size| size == elements.len() as u64) =>
                             {
                                 Some(element_type.as_ref().clone())
                             }
@@ -1301,7 +1450,9 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
                         .as_ref()
                         .and_then(|hint| match hint.value() {
                             TypeExpr::FixedArray { element_type, size }
-                                if *size.value() as usize == *count.value() as usize =>
+                                if size.value().literal().is_none_or(|size| {
+                                    count.value().literal().is_none_or(|count| size == count)
+                                }) =>
                             {
                                 Some(element_type.as_ref().clone())
                             }
@@ -1320,6 +1471,7 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
                 } else {
                     self.visit_expr_with_origin(element, elem_origin);
                 }
+                self.visit_array_size(count);
             }
             Expression::New { elem_type, count } => {
                 // Leverage existing TypeExpr visitor to record type usages (and nested types)
@@ -1334,7 +1486,39 @@ impl<'db, 'sink> SemanticIndexBuilder<'db, 'sink> {
                 // Visit the expression being cast
                 self.visit_expr(expr);
             }
-            Expression::Literal(_, _) | Expression::BooleanLiteral(_) => {
+            Expression::Block { statements, value } => {
+                self.with_new_scope(crate::place::ScopeKind::Block, |builder| {
+                    let current_scope = builder.current_scope();
+                    builder.index.set_scope_for_span(expr.span(), current_scope);
+
+                    // Only the final expression gives the value of the block
+                    builder.with_expected_type(None, |builder| {
+                        for stmt in statements {
+                            builder.visit_stmt(stmt);
+                        }
+                    });
+                    builder.visit_expr_with_origin(value, Origin::Operand { parent: expr_id });
+                });
+            }
+            Expression::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.with_expected_type(None, |builder| {
+                    builder.visit_expr_with_origin(
+                        condition,
+                        Origin::Condition {
+                            kind: ConditionKind::If,
+                        },
+                    );
+                });
+                self.visit_expr_with_origin(then_branch, Origin::Operand { parent: expr_id });
+                self.visit_expr_with_origin(else_branch, Origin::Operand { parent: expr_id });
+            }
+            Expression::Literal(_, _)
+            | Expression::BooleanLiteral(_)
+            | Expression::StringLiteral(_) => {
                 // Leaf nodes - no sub-expressions
             }
         }
@@ -1564,6 +1748,7 @@ where
 
                 // Process the value expression with optional type annotation
                 if let Some(ref ty) = const_def.ty {
+                    self.visit_type_expr(ty);
                     self.with_expected_type(Some(ty.clone()), |builder| {
                         builder.visit_expr(&const_def.value);
                     });
@@ -1588,7 +1773,7 @@ where
                     stmt.span(),
                 );
             }
-            Statement::Assignment { lhs, rhs } => {
+            Statement::Assignment { lhs, rhs } | Statement::CompoundAssignment { lhs, rhs, .. } => {
                 self.visit_expr(lhs);
                 // Get the lhs expression ID to provide context for the RHS
                 if let Some(lhs_expr_id) = self.index.expression_id_by_span(lhs.span()) {
@@ -1671,8 +1856,6 @@ where
     fn visit_use(&mut self, _use_stmt: &'ast Spanned<UseStmt>) {}
 
     fn visit_const(&mut self, const_def: &'ast Spanned<ConstDef>) {
-        use crate::definition::{ConstDefRef, DefinitionKind};
-
         let const_def_inner = const_def.value();
         let const_span = const_def.span();
 
@@ -1682,6 +1865,7 @@ where
 
         // Visit the value expression with optional type annotation
         if let Some(ref ty) = const_def_inner.ty {
+            self.visit_type_expr(ty);
             self.with_expected_type(Some(ty.clone()), |builder| {
                 builder.visit_expr(&const_def_inner.value);
             });
@@ -1693,14 +1877,9 @@ where
             .expression_id_by_span(const_def_inner.value.span())
             .expect("expression should have been registered");
 
-        // Define the constant in the current scope
-        let def_kind = DefinitionKind::Const(ConstDefRef::from_ast(const_def, Some(value_expr_id)));
-        self.add_place_with_definition(
-            const_def_inner.name.value(),
-            def_kind,
-            const_def_inner.name.span(),
-            const_span,
-        );
+        // The constant was declared in pass 1
+        let def_idx = self.declared_consts[&const_span];
+        self.index.set_const_value_expr(def_idx, value_expr_id);
     }
 
     fn visit_type_expr(&mut self, type_expr: &'ast Spanned<TypeExpr>) {
@@ -1727,6 +1906,10 @@ where
                     }
                 }
             }
+            TypeExpr::FixedArray { element_type, size } => {
+                self.visit_array_size(size);
+                self.visit_type_expr(element_type);
+            }
             _ => walk_type_expr(self, type_expr), // Default traversal for Pointer/Tuple
         }
     }
@@ -356,3 +356,109 @@ fn test_definition_expression_ids_are_captured() {
         }
     }
 }
+
+#[test]
+fn test_doc_comments_are_attached_to_definitions() {
+    let TestCase { db, source } = test_case(
+        "//! Shapes.\n\
+         /// A point.\n\
+         struct Point { x: felt }\n\
+         impl Point {\n\
+             /// The abscissa.\n\
+             fn get(self) -> felt { return self.x; }\n\
+         }\n\
+         /// Builds a point.\n\
+         /// With `x` as abscissa.\n\
+         fn make(x: felt) -> Point { let p = Point { x: x }; return p; }",
+    );
+    let crate_id = single_file_crate(&db, source);
+    let index = module_semantic_index(&db, crate_id, "main".to_string()).unwrap();
+    let root = index.root_scope().unwrap();
+    let doc = |name: &str| {
+        let def_idx = index.latest_definition_index_by_name(root, name).unwrap();
+        index.definition_doc(def_idx)
+    };
+
+    assert_eq!(index.module_doc(), Some("Shapes."));
+    assert_eq!(doc("Point"), Some("A point."));
+    assert_eq!(doc("Point::get"), Some("The abscissa."));
+    assert_eq!(doc("make"), Some("Builds a point.\nWith `x` as abscissa."));
+
+    // Locals and parameters are not documented
+    let (p_idx, _) = index
+        .all_definitions()
+        .find(|(_, def)| def.name == "p")
+        .unwrap();
+    assert_eq!(index.definition_doc(p_idx), None);
+}
+
+#[test]
+fn test_imports_follow_aliases_and_reexports() {
+    let db = test_db();
+    let utils = "fn add(a: felt, b: felt) -> felt { return a + b; }";
+    let facade = "pub use utils::add as sum;\n\
+                  use utils::add;\n\
+                  fn twice(x: felt) -> felt { return add(x, x); }";
+    let main = "use facade::sum as plus;\n\
+                use facade::add;\n\
+                fn main() -> felt { return plus(1, 2) + add(3, 4); }";
+    let utils_file = File::new(&db, utils.to_string(), "utils.cm".to_string());
+    let modules = HashMap::from([
+        (
+            "main".to_string(),
+            File::new(&db, main.to_string(), "main.cm".to_string()),
+        ),
+        (
+            "facade".to_string(),
+            File::new(&db, facade.to_string(), "facade.cm".to_string()),
+        ),
+        ("utils".to_string(), utils_file),
+    ]);
+    let crate_id = Crate::new(
+        &db,
+        modules,
+        "main".to_string(),
+        PathBuf::from("."),
+        "crate_test".to_string(),
+    );
+
+    // Re-exports resolve to the definition they refer to, private imports don't resolve
+    let (_, def, file) = resolve_imported_item(&db, crate_id, "facade", "sum").unwrap();
+    assert_eq!(def.name, "add");
+    assert_eq!(file, utils_file);
+    assert!(resolve_imported_item(&db, crate_id, "facade", "add").is_none());
+
+    // Aliased names resolve through the re-export chain
+    let index = module_semantic_index(&db, crate_id, "main".to_string()).unwrap();
+    let usage = index
+        .identifier_usages()
+        .iter()
+        .find(|usage| usage.name == "plus")
+        .unwrap();
+    let main_file = *crate_id.modules(&db).get("main").unwrap();
+    let (_, def, file) = index
+        .resolve_name_with_imports_at_position(
+            &db,
+            crate_id,
+            main_file,
+            "plus",
+            usage.scope_id,
+            usage.span,
+        )
+        .unwrap();
+    assert_eq!(def.name, "add");
+    assert_eq!(file, utils_file);
+
+    let diagnostics = crate::db::project_validate_semantics(&db, crate_id);
+    let import_errors: Vec<_> = diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code == DiagnosticCode::UnresolvedImport)
+        .collect();
+    assert_eq!(import_errors.len(), 1);
+    assert!(
+        import_errors[0]
+            .message
+            .contains("private import of module `facade`")
+    );
+}
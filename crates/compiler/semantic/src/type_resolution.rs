@@ -10,18 +10,23 @@
 //! - `expression_semantic_type`: Infers the type of an expression
 //! - `struct_semantic_data`: Resolves struct type information
 //! - `function_semantic_signature`: Resolves function signature information
+//! - `struct_method`: Resolves the methods of a struct
+//! - `operator_method`: Resolves the struct methods overloading `+`, `-` and `*`
 //! - `are_types_compatible`: Checks type compatibility
 
 use cairo_m_compiler_parser::parser::{
-    BinaryOp, Expression, NamedType, Spanned, TypeExpr as AstTypeExpr, UnaryOp,
+    ArraySize, BinaryOp, Expression, ImplDef, NamedType, Spanned, TypeExpr as AstTypeExpr, UnaryOp,
 };
 
 use crate::File;
-use crate::builtins::{self, BuiltinFn};
+use crate::builtins;
+use crate::const_eval::{ConstValue, const_value, module_const_values};
 use crate::db::{Crate, SemanticDb, module_name_for_file, module_semantic_index};
 use crate::definition::{DefinitionKind, FunctionDefRef, ParameterDefRef, StructDefRef};
 use crate::place::FileScopeId;
-use crate::semantic_index::{DefinitionId, ExpressionId, Origin};
+use crate::semantic_index::{
+    DefinitionId, ExpressionId, ExpressionInfo, Origin, SemanticIndex, resolve_imported_item,
+};
 use crate::types::{FunctionSignatureId, StructTypeId, TypeData, TypeId};
 
 /// Resolves an AST type expression to a `TypeId`
@@ -103,11 +108,22 @@ pub fn resolve_ast_type<'db>(
                 return TypeId::new(db, TypeData::Error);
             }
 
+            let Some(size) = resolve_array_size(
+                db,
+                crate_id,
+                file,
+                &semantic_index,
+                context_scope_id,
+                size.value(),
+            ) else {
+                return TypeId::new(db, TypeData::Error);
+            };
+
             TypeId::new(
                 db,
                 TypeData::FixedArray {
                     element_type: element_type_id,
-                    size: *size.value() as usize,
+                    size,
                 },
             )
         }
@@ -221,6 +237,9 @@ pub fn definition_semantic_type<'db>(
             // Check if there's an explicit type annotation
             if let Some(type_ast) = &const_ref.type_ast {
                 resolve_ast_type(db, crate_id, file, type_ast.clone(), definition.scope_id)
+            } else if module_const_values(db, crate_id, file).is_cyclic(def_index) {
+                // The type of a constant depending on itself cannot be inferred
+                TypeId::new(db, TypeData::Error)
             } else if let Some(value_expr_id) = const_ref.value_expr_id {
                 // Infer from the value expression if no explicit type
                 expression_semantic_type(db, crate_id, file, value_expr_id, None)
@@ -230,33 +249,18 @@ pub fn definition_semantic_type<'db>(
             }
         }
         DefinitionKind::Use(use_ref) => {
-            // Check if the imported module exists in the project
-            if !crate_id
-                .modules(db)
-                .contains_key(use_ref.imported_module.value())
-            {
-                return TypeId::new(db, TypeData::Error);
-            }
-
-            let imported_module = use_ref.imported_module.clone();
-            let imported_index =
-                module_semantic_index(db, crate_id, imported_module.value().clone())
-                    .expect("Failed to resolve index for imported module");
-            let imported_root = imported_index
-                .root_scope()
-                .expect("Imported module should have root scope");
-
-            if let Some(imported_def_idx) =
-                imported_index.latest_definition_index_by_name(imported_root, use_ref.item.value())
-            {
-                let imported_file = *crate_id
-                    .modules(db)
-                    .get(use_ref.imported_module.value())
-                    .expect("Imported file should exist");
-                let imported_def_id = DefinitionId::new(db, imported_file, imported_def_idx);
-                definition_semantic_type(db, crate_id, imported_def_id)
-            } else {
-                TypeId::new(db, TypeData::Error)
+            // Follow aliases and re-exports to the definition of the imported item
+            match resolve_imported_item(
+                db,
+                crate_id,
+                use_ref.imported_module.value(),
+                use_ref.item.value(),
+            ) {
+                Some((imported_def_idx, _, imported_file)) => {
+                    let imported_def_id = DefinitionId::new(db, imported_file, imported_def_idx);
+                    definition_semantic_type(db, crate_id, imported_def_id)
+                }
+                None => TypeId::new(db, TypeData::Error),
             }
         }
         DefinitionKind::LoopVariable(_) => {
@@ -267,59 +271,101 @@ pub fn definition_semantic_type<'db>(
     }
 }
 
-/// Infers the semantic type of an expression
+/// Derives the type expected for an expression from its origin, when the caller provides none.
+///
+/// Be careful to avoid cycles: this never queries the type of an ancestor of the expression,
+/// which is why context is not derived from parent container types for array/tuple elements.
 #[allow(clippy::cognitive_complexity)]
-#[salsa::tracked]
-pub fn expression_semantic_type<'db>(
+fn origin_context<'db>(
     db: &'db dyn SemanticDb,
     crate_id: Crate,
     file: File,
-    expression_id: ExpressionId,
-    context_expected: Option<TypeId<'db>>,
-) -> TypeId<'db> {
-    let module_name = match module_name_for_file(db, crate_id, file) {
-        Some(name) => name,
-        None => {
-            // File not found in project - this can happen during rapid edits
-            // Return error type instead of panicking
-            tracing::debug!(
-                "Could not find module name for file: {:?}",
-                file.file_path(db)
-            );
-            return TypeId::new(db, TypeData::Error);
+    semantic_index: &SemanticIndex,
+    expr_info: &ExpressionInfo,
+) -> Option<TypeId<'db>> {
+    match &expr_info.origin {
+        Origin::AssignmentRhs { lhs } => {
+            // Only provide assignment context for expressions whose type depends on it, i.e.
+            // non suffixed literals and arithmetic on them, as other expressions infer their
+            // own type
+            if is_untyped_numeric(&expr_info.ast_node) {
+                Some(expression_semantic_type(db, crate_id, file, *lhs, None))
+            } else {
+                None
+            }
         }
-    };
-    let semantic_index = module_semantic_index(db, crate_id, module_name)
-        .expect("Failed to resolve index for module");
-
-    let Some(expr_info) = semantic_index.expression(expression_id) else {
-        return TypeId::new(db, TypeData::Error);
-    };
-
-    // If context_expected is None, try to derive it from origin.
-    // Be careful to avoid cycles: do not derive context from parent container types for
-    // array/tuple elements here, as that can cause recursive calls back into this node.
-    let context_expected = if context_expected.is_none() {
-        match &expr_info.origin {
-            Origin::AssignmentRhs { lhs } => {
-                // Only provide assignment context for direct literals, not complex expressions
-                // This avoids interfering with binary operations' internal type inference
-                match &expr_info.ast_node {
-                    Expression::Literal(_, None) => {
-                        // Only for non suffixed literals - provide LHS type as context
-                        Some(expression_semantic_type(db, crate_id, file, *lhs, None))
-                    }
-                    _ => {
-                        // For complex expressions like binary ops, let them handle their own inference
+        Origin::Operand { parent } => {
+            // An operand whose type depends on its context takes the type of its sibling
+            // operand, or else the context of its parent, so that `3 - x` types `3` like `x`
+            // whichever side of the expression the type comes from.
+            if !is_untyped_numeric(&expr_info.ast_node) {
+                return None;
+            }
+            let parent_info = semantic_index.expression(*parent)?;
+            match &parent_info.ast_node {
+                Expression::BinaryOp { left, op, right } => {
+                    let sibling = if left.span() == expr_info.ast_span {
+                        right
+                    } else {
+                        left
+                    };
+                    if !is_untyped_numeric(sibling.value()) {
+                        let sibling_id = semantic_index.expression_id_by_span(sibling.span())?;
+                        let sibling_type =
+                            expression_semantic_type(db, crate_id, file, sibling_id, None);
+                        // The right operand of an overloaded operator is an argument of its
+                        // method
+                        Some(
+                            operator_method(db, crate_id, op, sibling_type)
+                                .filter(|_| sibling.span() == left.span())
+                                .map_or(sibling_type, |signature| signature.params(db)[1].1),
+                        )
+                    } else if preserves_operand_type(op) {
+                        origin_context(db, crate_id, file, semantic_index, parent_info)
+                    } else {
                         None
                     }
                 }
+                Expression::If {
+                    then_branch,
+                    else_branch,
+                    ..
+                } => {
+                    // A branch takes the type of the other branch, like an operand
+                    let sibling = if then_branch.span() == expr_info.ast_span {
+                        else_branch
+                    } else {
+                        then_branch
+                    };
+                    if !is_untyped_numeric(sibling.value()) {
+                        let sibling_id = semantic_index.expression_id_by_span(sibling.span())?;
+                        Some(expression_semantic_type(
+                            db, crate_id, file, sibling_id, None,
+                        ))
+                    } else {
+                        origin_context(db, crate_id, file, semantic_index, parent_info)
+                    }
+                }
+                Expression::UnaryOp { .. }
+                | Expression::Parenthesized(_)
+                | Expression::Block { .. } => {
+                    origin_context(db, crate_id, file, semantic_index, parent_info)
+                }
+                _ => None,
             }
-            Origin::Arg { callee, index } => {
-                semantic_index.expression(*callee).and_then(|callee_info| {
-                    match &callee_info.ast_node {
-                        // Use builder-recorded mapping for the callee identifier
-                        Expression::Identifier(_name) => semantic_index
+        }
+        Origin::Arg { callee, index } => {
+            semantic_index.expression(*callee).and_then(|callee_info| {
+                match &callee_info.ast_node {
+                    // Built-ins with a signature provide their parameter types, other
+                    // callees use the builder-recorded mapping for the identifier
+                    Expression::Identifier(name) => {
+                        if let Some(builtin) = builtins::is_builtin_function_name(name.value()) {
+                            return builtin.signature(db).and_then(|(params, _)| {
+                                params.get(*index).map(|(_, param_type)| *param_type)
+                            });
+                        }
+                        semantic_index
                             .definition_for_identifier_expr(*callee)
                             .and_then(|(def_idx, _)| {
                                 let def_id = DefinitionId::new(db, file, def_idx);
@@ -329,103 +375,183 @@ pub fn expression_semantic_type<'db>(
                                         params.get(*index).map(|(_, param_type)| *param_type)
                                     },
                                 )
-                            }),
-                        _ => None,
+                            })
                     }
-                })
-            }
-            Origin::Condition { .. } => Some(TypeId::new(db, TypeData::Bool)),
-            Origin::ReturnExpr => {
-                // Get the function's return type as context for return expressions
-                // We need to find the containing function to get its return type
-
-                // Walk up the scope hierarchy to find a function scope
-                let expr_scope = expr_info.scope_id;
-                let mut current_scope = Some(expr_scope);
-
-                let mut result = None;
-                while let Some(scope_id) = current_scope {
-                    // Check if this scope has a function definition
-                    for (def_idx, def) in semantic_index.all_definitions() {
-                        if let DefinitionKind::Function(_func_def) = &def.kind {
-                            // Check if this function's scope contains our expression
-                            // Functions create a new scope, so we need to check if our expression
-                            // is within the function's body scope
-                            if def.scope_id == scope_id
-                                || semantic_index
-                                    .scope(expr_scope)
-                                    .and_then(|s| {
-                                        let mut parent = s.parent;
-                                        while let Some(p) = parent {
-                                            if p == def.scope_id {
-                                                return Some(true);
-                                            }
-                                            parent =
-                                                semantic_index.scope(p).and_then(|ps| ps.parent);
+                    // Method calls pass the receiver as first argument
+                    Expression::MemberAccess { .. } => {
+                        match expression_semantic_type(db, crate_id, file, *callee, None).data(db) {
+                            TypeData::Function(signature_id) => signature_id
+                                .params(db)
+                                .get(*index + 1)
+                                .map(|(_, param_type)| *param_type),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            })
+        }
+        Origin::Condition { .. } => Some(TypeId::new(db, TypeData::Bool)),
+        Origin::ReturnExpr => {
+            // Get the function's return type as context for return expressions
+            // We need to find the containing function to get its return type
+
+            // Walk up the scope hierarchy to find a function scope
+            let expr_scope = expr_info.scope_id;
+            let mut current_scope = Some(expr_scope);
+
+            let mut result = None;
+            while let Some(scope_id) = current_scope {
+                // Check if this scope has a function definition
+                for (def_idx, def) in semantic_index.all_definitions() {
+                    if let DefinitionKind::Function(_func_def) = &def.kind {
+                        // Check if this function's scope contains our expression
+                        // Functions create a new scope, so we need to check if our expression
+                        // is within the function's body scope
+                        if def.scope_id == scope_id
+                            || semantic_index
+                                .scope(expr_scope)
+                                .and_then(|s| {
+                                    let mut parent = s.parent;
+                                    while let Some(p) = parent {
+                                        if p == def.scope_id {
+                                            return Some(true);
                                         }
-                                        None
-                                    })
-                                    .unwrap_or(false)
+                                        parent = semantic_index.scope(p).and_then(|ps| ps.parent);
+                                    }
+                                    None
+                                })
+                                .unwrap_or(false)
+                        {
+                            // Found the containing function, get its signature
+                            let def_id = DefinitionId::new(db, file, def_idx);
+                            if let Some(signature) =
+                                function_semantic_signature(db, crate_id, def_id)
                             {
-                                // Found the containing function, get its signature
-                                let def_id = DefinitionId::new(db, file, def_idx);
-                                if let Some(signature) =
-                                    function_semantic_signature(db, crate_id, def_id)
-                                {
-                                    result = Some(signature.return_type(db));
-                                    break;
-                                }
+                                result = Some(signature.return_type(db));
+                                break;
                             }
                         }
                     }
+                }
 
-                    if result.is_some() {
-                        break;
-                    }
-
-                    // Move to parent scope
-                    current_scope = semantic_index.scope(scope_id).and_then(|s| s.parent);
+                if result.is_some() {
+                    break;
                 }
 
-                result
-            }
-            Origin::StructField { parent, field, .. } => {
-                semantic_index.expression(*parent).and_then(|parent_info| {
-                    if let Expression::StructLiteral { name, .. } = &parent_info.ast_node {
-                        semantic_index
-                            .resolve_name_at_position(
-                                name.value(),
-                                parent_info.scope_id,
-                                name.span(),
-                            )
-                            .and_then(|(_def_idx, definition)| match &definition.kind {
-                                DefinitionKind::Struct(struct_def) => struct_def
-                                    .fields_ast
-                                    .iter()
-                                    .find(|(field_name, _)| field_name == field)
-                                    .map(|(_, field_type)| {
-                                        resolve_ast_type(
-                                            db,
-                                            crate_id,
-                                            file,
-                                            field_type.clone(),
-                                            parent_info.scope_id,
-                                        )
-                                    }),
-                                _ => None,
-                            })
-                    } else {
-                        None
-                    }
-                })
+                // Move to parent scope
+                current_scope = semantic_index.scope(scope_id).and_then(|s| s.parent);
             }
-            // Avoid deriving from TupleElem/ArrayElem origins to prevent cycles.
-            _ => None,
+
+            result
         }
-    } else {
-        context_expected
+        Origin::StructField { parent, field, .. } => {
+            semantic_index.expression(*parent).and_then(|parent_info| {
+                if let Expression::StructLiteral { name, .. } = &parent_info.ast_node {
+                    semantic_index
+                        .resolve_name_at_position(name.value(), parent_info.scope_id, name.span())
+                        .and_then(|(_def_idx, definition)| match &definition.kind {
+                            DefinitionKind::Struct(struct_def) => struct_def
+                                .fields_ast
+                                .iter()
+                                .find(|(field_name, _)| field_name == field)
+                                .map(|(_, field_type)| {
+                                    resolve_ast_type(
+                                        db,
+                                        crate_id,
+                                        file,
+                                        field_type.clone(),
+                                        parent_info.scope_id,
+                                    )
+                                }),
+                            _ => None,
+                        })
+                } else {
+                    None
+                }
+            })
+        }
+        // Avoid deriving from TupleElem/ArrayElem origins to prevent cycles.
+        _ => None,
+    }
+}
+
+/// Returns whether the type of an expression depends on its context: non suffixed literals,
+/// and arithmetic on them.
+fn is_untyped_numeric(expr: &Expression) -> bool {
+    match expr {
+        Expression::Literal(_, None) => true,
+        Expression::Parenthesized(inner) | Expression::Block { value: inner, .. } => {
+            is_untyped_numeric(inner.value())
+        }
+        Expression::If {
+            then_branch,
+            else_branch,
+            ..
+        } => is_untyped_numeric(then_branch.value()) && is_untyped_numeric(else_branch.value()),
+        Expression::UnaryOp {
+            op: UnaryOp::Neg,
+            expr,
+        } => is_untyped_numeric(expr.value()),
+        Expression::BinaryOp { left, op, right } => {
+            preserves_operand_type(op)
+                && is_untyped_numeric(left.value())
+                && is_untyped_numeric(right.value())
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether a binary operator yields the type of its operands.
+const fn preserves_operand_type(op: &BinaryOp) -> bool {
+    matches!(
+        op,
+        BinaryOp::Add
+            | BinaryOp::Sub
+            | BinaryOp::Mul
+            | BinaryOp::Div
+            | BinaryOp::Mod
+            | BinaryOp::BitwiseAnd
+            | BinaryOp::BitwiseOr
+            | BinaryOp::BitwiseXor
+            | BinaryOp::ShiftLeft
+            | BinaryOp::ShiftRight
+    )
+}
+
+/// Infers the semantic type of an expression
+#[allow(clippy::cognitive_complexity)]
+#[salsa::tracked]
+pub fn expression_semantic_type<'db>(
+    db: &'db dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    expression_id: ExpressionId,
+    context_expected: Option<TypeId<'db>>,
+) -> TypeId<'db> {
+    let module_name = match module_name_for_file(db, crate_id, file) {
+        Some(name) => name,
+        None => {
+            // File not found in project - this can happen during rapid edits
+            // Return error type instead of panicking
+            tracing::debug!(
+                "Could not find module name for file: {:?}",
+                file.file_path(db)
+            );
+            return TypeId::new(db, TypeData::Error);
+        }
+    };
+    let semantic_index = module_semantic_index(db, crate_id, module_name)
+        .expect("Failed to resolve index for module");
+
+    let Some(expr_info) = semantic_index.expression(expression_id) else {
+        return TypeId::new(db, TypeData::Error);
     };
 
+    // If context_expected is None, try to derive it from origin.
+    let context_expected =
+        context_expected.or_else(|| origin_context(db, crate_id, file, &semantic_index, expr_info));
+
     // Access the AST node directly from ExpressionInfo - no lookup needed!
     match &expr_info.ast_node {
         Expression::Literal(_value, literal_suffix) => {
@@ -438,7 +564,18 @@ pub fn expression_semantic_type<'db>(
                 }
             }
 
-            // Priority 2: Check for expected type from AST (e.g., let x: u32 = 42)
+            // Priority 2: Check for context from propagated type (e.g., in x + 1 where x is u32)
+            if let Some(context_type) = context_expected {
+                // If the context expects a numeric primitive, use it
+                match context_type.data(db) {
+                    TypeData::U32 | TypeData::Felt => {
+                        return context_type;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Priority 3: Check for expected type from AST (e.g., let x: u32 = 42)
             if let Some(type_ast) = expr_info.expected_type_ast.clone() {
                 // We found an explicit type annotation. Resolve it.
                 let expected_type =
@@ -452,21 +589,13 @@ pub fn expression_semantic_type<'db>(
                 }
             }
 
-            // Priority 3: Check for context from propagated type (e.g., in x + 1 where x is u32)
-            if let Some(context_type) = context_expected {
-                // If the context expects a numeric primitive, use it
-                match context_type.data(db) {
-                    TypeData::U32 | TypeData::Felt => {
-                        return context_type;
-                    }
-                    _ => {}
-                }
-            }
-
             // Default: If no specific context is found, default to `felt`.
             TypeId::new(db, TypeData::Felt)
         }
         Expression::BooleanLiteral(_) => TypeId::new(db, TypeData::Bool),
+        // Strings are not values: they are only valid as `assert` and `panic` messages,
+        // which the type validator checks.
+        Expression::StringLiteral(_) => TypeId::new(db, TypeData::Error),
         Expression::Parenthesized(inner) => {
             // Parentheses are semantically transparent; propagate expected type
             if let Some(inner_id) = semantic_index.expression_id_by_span(inner.span()) {
@@ -543,45 +672,32 @@ pub fn expression_semantic_type<'db>(
             let left_id = semantic_index.expression_id_by_span(left.span()).unwrap();
             let right_id = semantic_index.expression_id_by_span(right.span()).unwrap();
 
-            // For commutative operators, we need to be smarter about type inference
-            // First, check if either operand is a literal - if so, we want to infer its type from the other operand
-            let is_commutative = matches!(
-                op,
-                BinaryOp::Add | BinaryOp::Mul | BinaryOp::Eq | BinaryOp::Neq
-            );
-
-            let (left_type, right_type) = if is_commutative {
-                // Check if left is a literal and right has a concrete type
-                let left_expr = &semantic_index.expression(left_id).unwrap().ast_node;
-                let right_expr = &semantic_index.expression(right_id).unwrap().ast_node;
-
-                let left_is_unsuffixed_literal = matches!(left_expr, Expression::Literal(_, None));
-                let right_is_unsuffixed_literal =
-                    matches!(right_expr, Expression::Literal(_, None));
-
-                if left_is_unsuffixed_literal && !right_is_unsuffixed_literal {
-                    // Infer right first, then use it as context for left
+            // An operand whose type depends on its context takes the type of the other
+            // operand, so infer the other one first. Operators yielding the type of their
+            // operands also propagate the expected type of their result.
+            let operand_context = if preserves_operand_type(op) {
+                context_expected
+            } else {
+                None
+            };
+            let (left_type, right_type) =
+                if is_untyped_numeric(left.value()) && !is_untyped_numeric(right.value()) {
                     let right_type =
-                        expression_semantic_type(db, crate_id, file, right_id, context_expected);
+                        expression_semantic_type(db, crate_id, file, right_id, operand_context);
                     let left_type =
                         expression_semantic_type(db, crate_id, file, left_id, Some(right_type));
                     (left_type, right_type)
                 } else {
-                    // Default behavior: left first, then right with left as context
                     let left_type =
-                        expression_semantic_type(db, crate_id, file, left_id, context_expected);
+                        expression_semantic_type(db, crate_id, file, left_id, operand_context);
+                    // Operators overloaded by a struct call its method, which types the result
+                    if let Some(signature) = operator_method(db, crate_id, op, left_type) {
+                        return signature.return_type(db);
+                    }
                     let right_type =
                         expression_semantic_type(db, crate_id, file, right_id, Some(left_type));
                     (left_type, right_type)
-                }
-            } else {
-                // Non-commutative operators: always left first, then right
-                let left_type =
-                    expression_semantic_type(db, crate_id, file, left_id, context_expected);
-                let right_type =
-                    expression_semantic_type(db, crate_id, file, right_id, Some(left_type));
-                (left_type, right_type)
-            };
+                };
 
             for signature in get_binary_op_signatures(db) {
                 if signature.op == *op
@@ -605,10 +721,13 @@ pub fn expression_semantic_type<'db>(
 
             match object_type.data(db) {
                 TypeData::Struct(struct_id) => {
-                    // Direct struct field access
-                    struct_id
-                        .field_type(db, field.value())
-                        .unwrap_or_else(|| TypeId::new(db, TypeData::Error))
+                    // Direct struct field access, or the callee of a method call
+                    struct_id.field_type(db, field.value()).unwrap_or_else(|| {
+                        struct_method(db, crate_id, struct_id, field.value()).map_or_else(
+                            || TypeId::new(db, TypeData::Error),
+                            |method_id| definition_semantic_type(db, crate_id, method_id),
+                        )
+                    })
                 }
                 _ => {
                     // Field access on non-struct, non-pointer type
@@ -617,11 +736,40 @@ pub fn expression_semantic_type<'db>(
             }
         }
         Expression::FunctionCall { callee, args } => {
-            // Built-in function handling: assert(...)
+            // Built-in function handling: assert(...), panic(...), debug_print(...) return no
+            // value, include_bytes(...) returns a felt array of the size of the included file,
+            // the u32 intrinsics return the type of their signature.
             if let Expression::Identifier(name) = callee.value() {
-                if builtins::is_builtin_function_name(name.value()) == Some(BuiltinFn::Assert) {
-                    // No returned value.
-                    return TypeId::new(db, TypeData::Tuple(vec![]));
+                if let Some(builtin) = builtins::is_builtin_function_name(name.value()) {
+                    if builtin == builtins::BuiltinFn::IncludeBytes {
+                        let size = match args.as_slice() {
+                            [arg] => match arg.value() {
+                                Expression::StringLiteral(path) => {
+                                    builtins::read_included_bytes(db, file, path)
+                                        .ok()
+                                        .map(|bytes| bytes.len())
+                                }
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        return size.map_or_else(
+                            || TypeId::new(db, TypeData::Error),
+                            |size| {
+                                TypeId::new(
+                                    db,
+                                    TypeData::FixedArray {
+                                        element_type: TypeId::new(db, TypeData::Felt),
+                                        size,
+                                    },
+                                )
+                            },
+                        );
+                    }
+                    return builtin.signature(db).map_or_else(
+                        || TypeId::new(db, TypeData::Tuple(vec![])),
+                        |(_, return_type)| return_type,
+                    );
                 }
             }
             // Get ExpressionId for the callee
@@ -632,13 +780,16 @@ pub fn expression_semantic_type<'db>(
                 // If it's a function type, infer arguments with parameter types and return the return type
                 match callee_type.data(db) {
                     TypeData::Function(signature_id) => {
-                        // Infer each argument with its corresponding parameter type
+                        // Infer each argument with its corresponding parameter type, the
+                        // receiver of a method call being its first argument
                         let params = signature_id.params(db);
+                        let receivers =
+                            usize::from(matches!(callee.value(), Expression::MemberAccess { .. }));
                         for (index, arg) in args.iter().enumerate() {
                             if let Some(arg_expr_id) =
                                 semantic_index.expression_id_by_span(arg.span())
                             {
-                                if let Some((_, param_type)) = params.get(index) {
+                                if let Some((_, param_type)) = params.get(index + receivers) {
                                     // Infer the argument type with the parameter type as context
                                     let _ = expression_semantic_type(
                                         db,
@@ -878,14 +1029,71 @@ pub fn expression_semantic_type<'db>(
             let inferred_element_type =
                 expression_semantic_type(db, crate_id, file, element_id, element_hint);
 
+            let Some(size) = resolve_array_size(
+                db,
+                crate_id,
+                file,
+                &semantic_index,
+                expr_info.scope_id,
+                count.value(),
+            ) else {
+                return TypeId::new(db, TypeData::Error);
+            };
+
             TypeId::new(
                 db,
                 TypeData::FixedArray {
                     element_type: inferred_element_type,
-                    size: *count.value() as usize,
+                    size,
                 },
             )
         }
+        Expression::Block { value, .. } => {
+            // A block evaluates to its final expression
+            if let Some(value_id) = semantic_index.expression_id_by_span(value.span()) {
+                return expression_semantic_type(db, crate_id, file, value_id, context_expected);
+            }
+            TypeId::new(db, TypeData::Error)
+        }
+        Expression::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            let (Some(then_id), Some(else_id)) = (
+                semantic_index.expression_id_by_span(then_branch.span()),
+                semantic_index.expression_id_by_span(else_branch.span()),
+            ) else {
+                return TypeId::new(db, TypeData::Error);
+            };
+            // Without context, an untyped branch takes the type of the other branch through
+            // its origin. Mismatching branches are reported by the type validator.
+            let then_type = expression_semantic_type(db, crate_id, file, then_id, context_expected);
+            if matches!(then_type.data(db), TypeData::Error) {
+                return expression_semantic_type(db, crate_id, file, else_id, context_expected);
+            }
+            then_type
+        }
+    }
+}
+
+/// Resolves the size of a fixed-size array, given as a literal or as the name of a constant
+///
+/// Returns `None` if the name does not refer to a constant with an integer value.
+pub(crate) fn resolve_array_size(
+    db: &dyn SemanticDb,
+    crate_id: Crate,
+    file: File,
+    semantic_index: &SemanticIndex,
+    scope_id: FileScopeId,
+    size: &ArraySize,
+) -> Option<usize> {
+    match size {
+        ArraySize::Literal(size) => Some(*size as usize),
+        ArraySize::Const(name) => semantic_index
+            .latest_definition_index_by_name_in_chain(scope_id, name)
+            .and_then(|def_idx| const_value(db, crate_id, DefinitionId::new(db, file, def_idx)))
+            .and_then(ConstValue::as_array_size),
     }
 }
 
@@ -978,6 +1186,63 @@ pub fn function_semantic_signature<'db>(
     }
 }
 
+/// Resolves the method `name` of a struct to its function definition
+///
+/// Methods are declared by the `impl` blocks of the module defining the struct, under the
+/// name given by [`ImplDef::method_name`].
+pub fn struct_method<'db>(
+    db: &'db dyn SemanticDb,
+    crate_id: Crate,
+    struct_type: StructTypeId<'db>,
+    name: &str,
+) -> Option<DefinitionId<'db>> {
+    let file = struct_type.definition_id(db).file(db);
+    let module_name = module_name_for_file(db, crate_id, file)?;
+    let semantic_index = module_semantic_index(db, crate_id, module_name).ok()?;
+    let root_scope = semantic_index.root_scope()?;
+
+    let method_name = ImplDef::method_name(&struct_type.name(db), name);
+    let def_idx = semantic_index.latest_definition_index_by_name(root_scope, &method_name)?;
+    matches!(
+        semantic_index.definition(def_idx)?.kind,
+        DefinitionKind::Function(_)
+    )
+    .then(|| DefinitionId::new(db, file, def_idx))
+}
+
+/// Names of the struct methods overloading binary operators
+pub const OPERATOR_METHOD_NAMES: &[&str] = &["add", "sub", "mul"];
+
+/// Returns the name of the struct method overloading a binary operator, if it can be
+/// overloaded.
+pub const fn operator_method_name(op: &BinaryOp) -> Option<&'static str> {
+    match op {
+        BinaryOp::Add => Some("add"),
+        BinaryOp::Sub => Some("sub"),
+        BinaryOp::Mul => Some("mul"),
+        _ => None,
+    }
+}
+
+/// Resolves the method overloading `op` when the left operand is of type `left_type`
+///
+/// A struct overloads `+`, `-` and `*` by defining the `add`, `sub` and `mul` methods, taking
+/// the right operand as their only argument: `a + b` calls `add(a, b)`.
+pub fn operator_method<'db>(
+    db: &'db dyn SemanticDb,
+    crate_id: Crate,
+    op: &BinaryOp,
+    left_type: TypeId<'db>,
+) -> Option<FunctionSignatureId<'db>> {
+    let name = operator_method_name(op)?;
+    let TypeData::Struct(struct_type) = left_type.data(db) else {
+        return None;
+    };
+    let method_id = struct_method(db, crate_id, struct_type, name)?;
+    function_semantic_signature(db, crate_id, method_id)
+        .filter(|signature| signature.params(db).len() == 2)
+}
+
 /// Checks if two types are compatible
 #[salsa::tracked]
 pub fn are_types_compatible<'db>(
@@ -1251,6 +1516,21 @@ pub(crate) fn get_binary_op_signatures<'db>(
             right: u32,
             result: u32,
         },
+        // Shifts (u32 only, the shift amount is a u32 too)
+        // ShiftLeft
+        OperatorSignature {
+            op: BinaryOp::ShiftLeft,
+            left: u32,
+            right: u32,
+            result: u32,
+        },
+        // ShiftRight
+        OperatorSignature {
+            op: BinaryOp::ShiftRight,
+            left: u32,
+            right: u32,
+            result: u32,
+        },
     ]
 }
 
@@ -216,6 +216,7 @@ fn test_expression_type_coverage() {
         let variant_name = match &expr_info.ast_node {
             Expression::Literal(_, _) => "Literal",
             Expression::BooleanLiteral(_) => "BooleanLiteral",
+            Expression::StringLiteral(_) => "StringLiteral",
             Expression::Identifier(_) => "Identifier",
             Expression::UnaryOp { .. } => "UnaryOp",
             Expression::BinaryOp { .. } => "BinaryOp",
@@ -230,6 +231,8 @@ fn test_expression_type_coverage() {
             Expression::TupleIndex { .. } => "TupleIndex",
             Expression::Cast { .. } => "Cast",
             Expression::New { .. } => "New",
+            Expression::Block { .. } => "Block",
+            Expression::If { .. } => "If",
         };
         expression_types_found.insert(variant_name);
 
@@ -399,3 +402,111 @@ fn test_typed_const_resolution() {
         panic!("POW2 should be a FixedArray type");
     }
 }
+
+/// Asserts the type of each literal of the program, resolved without context as MIR lowering
+/// does. Literals are identified by their value.
+fn assert_literal_types(program: &str, expected: &[(u64, &str)]) {
+    let db = test_db();
+    let crate_id = crate_from_program(&db, program);
+    let file = *crate_id.modules(&db).values().next().unwrap();
+    let semantic_index = module_semantic_index(&db, crate_id, "main".to_string()).unwrap();
+
+    for (expected_value, expected_type) in expected {
+        let (expr_id, _) = semantic_index
+            .all_expressions()
+            .find(|(_, info)| matches!(info.ast_node, Expression::Literal(value, _) if value == *expected_value))
+            .unwrap_or_else(|| panic!("Literal {expected_value} not found"));
+        let literal_type = expression_semantic_type(&db, crate_id, file, expr_id, None);
+        assert_eq!(
+            literal_type.data(&db).display_name(&db),
+            *expected_type,
+            "Unexpected type for literal {expected_value}"
+        );
+    }
+}
+
+#[test]
+fn test_literal_operand_inference() {
+    let program = r#"
+        fn id(x: u32) -> u32 {
+            return x;
+        }
+
+        fn test(y: u32) -> u32 {
+            let a = 3 - y;
+            let b: u32 = 0;
+            b = 4 + 5;
+            let c = id(6 * 7);
+            let d = 8 < y;
+            let e = 12 + 13;
+            let f = -(14 + y);
+            return (9 + 10) * 11;
+        }
+    "#;
+
+    assert_literal_types(
+        program,
+        &[
+            // From the sibling operand, whichever side it is on
+            (3, "u32"),
+            (8, "u32"),
+            (14, "u32"),
+            // From the context of the whole arithmetic expression
+            (4, "u32"),
+            (5, "u32"),
+            (6, "u32"),
+            (7, "u32"),
+            (9, "u32"),
+            (10, "u32"),
+            (11, "u32"),
+            // Without context, literals default to felt
+            (12, "felt"),
+            (13, "felt"),
+        ],
+    );
+}
+
+#[test]
+fn test_literal_context_overrides_let_annotation() {
+    let program = r#"
+        fn test(y: u32) {
+            let a: u32 = 3 + y;
+            let b: felt = 4 + 5;
+            let c: felt = y + 6;
+            return;
+        }
+    "#;
+
+    assert_literal_types(program, &[(3, "u32"), (4, "felt"), (5, "felt"), (6, "u32")]);
+}
+
+#[test]
+fn test_mismatched_literal_operand_diagnostic() {
+    let db = test_db();
+    let program = "fn test(x: felt) -> felt { let a = 3u32 - x; return x * 4felt; }";
+    let crate_id = crate_from_program(&db, program);
+    let diagnostics = crate::db::project_validate_semantics(&db, crate_id);
+
+    let mismatches: Vec<_> = diagnostics
+        .errors()
+        .into_iter()
+        .filter(|diag| diag.message.starts_with("Mismatched literal operand"))
+        .collect();
+    assert_eq!(mismatches.len(), 1, "{diagnostics:?}");
+
+    // The error points at the suffixed literal, even on the left of a non commutative operator
+    let literal_start = program.find("3u32").unwrap();
+    let mismatch = mismatches[0];
+    assert_eq!(
+        mismatch.message,
+        "Mismatched literal operand for operator `-`. Expected `felt`, found `u32`"
+    );
+    assert_eq!(
+        mismatch.span,
+        SimpleSpan::from(literal_start..literal_start + 4)
+    );
+    assert_eq!(
+        mismatch.related_spans[0].1,
+        "Remove the `u32` suffix, or cast the literal with `3u32 as felt`"
+    );
+}
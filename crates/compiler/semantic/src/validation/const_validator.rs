@@ -0,0 +1,82 @@
+//! # Const Validator
+//!
+//! This validator checks constant expressions:
+//! - Constants whose value depends on themselves
+//! - Array sizes naming something other than an integer constant
+
+use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
+
+use crate::const_eval::{ConstValue, const_value, module_const_values};
+use crate::db::{Crate, SemanticDb};
+use crate::semantic_index::DefinitionId;
+use crate::validation::Validator;
+use crate::{File, SemanticIndex};
+
+/// Validator for constant expressions
+///
+/// Constants are folded by [`module_const_values`]; this validator reports the constants
+/// that cannot be folded where a compile-time value is required.
+#[derive(Debug, Default)]
+pub struct ConstValidator;
+
+impl Validator for ConstValidator {
+    fn validate(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let file_path = file.file_path(db);
+        let const_values = module_const_values(db, crate_id, file);
+
+        for cycle in const_values.cycles() {
+            let Some(first) = index.definition(cycle[0]) else {
+                continue;
+            };
+            let path = cycle
+                .iter()
+                .chain(std::iter::once(&cycle[0]))
+                .filter_map(|&def_idx| index.definition(def_idx))
+                .map(|def| def.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::CyclicConstDefinition,
+                    format!("Cyclic constant definition: {path}"),
+                )
+                .with_location(file_path.to_string(), first.name_span),
+            );
+        }
+
+        for usage in index.array_size_usages() {
+            // Unresolved names are reported by the scope validator
+            let Some(def_idx) =
+                index.latest_definition_index_by_name_in_chain(usage.scope_id, &usage.name)
+            else {
+                continue;
+            };
+            // Cycles are reported above
+            if const_values.is_cyclic(def_idx) {
+                continue;
+            }
+            let size = const_value(db, crate_id, DefinitionId::new(db, file, def_idx))
+                .and_then(ConstValue::as_array_size);
+            if size.is_none() {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidArraySize,
+                        format!("Array size '{}' is not an integer constant", usage.name),
+                    )
+                    .with_location(file_path.to_string(), usage.span),
+                );
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "ConstValidator"
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! This module implements control-flow validation rules for Cairo-M:
 //! - **Unreachable code detection**: Identifies statements that appear after statements that
-//!   unconditionally terminate a block, like `return` or an `if-else` where both branches
-//!   terminate.
+//!   unconditionally terminate a block, like `return`, `panic(...)` or an `if-else` where
+//!   both branches terminate.
 //! - **Missing return detection**: Ensures that every execution path of a function that
 //!   is expected to return a value *does* return a value.
+//! - **Dead branch detection**: Warns about the branch of an `if` that is never taken
+//!   because its condition is a compile-time constant, like `if false { ... }`.
 //!
 //! # Implementation Notes
 //!
@@ -18,17 +20,19 @@
 //!     all paths are covered, a `MissingReturn` diagnostic is emitted.
 //!
 use cairo_m_compiler_diagnostics::Diagnostic;
-use cairo_m_compiler_parser::parser::{FunctionDef, Spanned, Statement, TopLevelItem, parse_file};
+use cairo_m_compiler_parser::const_eval::eval_const_condition;
+use cairo_m_compiler_parser::parser::{Expression, FunctionDef, Spanned, Statement, parse_file};
 
+use crate::builtins::{BuiltinFn, is_builtin_function_name};
 use crate::db::{Crate, SemanticDb};
 use crate::definition::DefinitionKind;
-use crate::validation::Validator;
+use crate::validation::{Validator, shared};
 use crate::{File, SemanticIndex};
 
 /// Validator for control-flow–related semantic rules.
 ///
-/// This validator currently catches unreachable code and functions that do not
-/// return on all paths when a return value is required.
+/// This validator currently catches unreachable code, dead branches, and functions that do
+/// not return on all paths when a return value is required.
 pub struct ControlFlowValidator;
 
 impl Validator for ControlFlowValidator {
@@ -104,14 +108,7 @@ impl ControlFlowValidator {
         parsed_module: &'a cairo_m_compiler_parser::parser::ParsedModule,
         function_name: &str,
     ) -> Option<&'a FunctionDef> {
-        for item in parsed_module.items() {
-            if let TopLevelItem::Function(func_spanned) = item {
-                if func_spanned.value().name.value() == function_name {
-                    return Some(func_spanned.value());
-                }
-            }
-        }
-        None
+        parsed_module.function(function_name).map(Spanned::value)
     }
 
     // ---------------------------------------------------------------------
@@ -162,16 +159,37 @@ impl ControlFlowValidator {
         loop_depth: usize,
         sink: &dyn cairo_m_compiler_diagnostics::DiagnosticSink,
     ) -> bool {
+        // Block expressions may contain any statement, including `break` and `continue`
+        for block in shared::expression_blocks(stmt.value()) {
+            Self::analyze_for_unreachable_code_in_sequence(db, file, block, loop_depth, sink);
+        }
+
         match stmt.value() {
             Statement::Return { .. } => true,
+            Statement::Expression(expr) if Self::is_panic_call(expr) => true,
             Statement::Block(body) => {
                 Self::analyze_for_unreachable_code_in_sequence(db, file, body, loop_depth, sink)
             }
             Statement::If {
+                condition,
                 then_block,
                 else_block,
-                ..
             } => {
+                // A constant condition makes one of the branches dead.
+                if let Some(value) = eval_const_condition(condition.value()) {
+                    let dead_branch = if value {
+                        else_block.as_deref()
+                    } else {
+                        Some(&**then_block)
+                    };
+                    if let Some(dead_branch) = dead_branch {
+                        sink.push(Diagnostic::dead_branch(
+                            file.file_path(db).to_string(),
+                            value,
+                            dead_branch.span(),
+                        ));
+                    }
+                }
                 let then_terminates = Self::analyze_for_unreachable_code_in_statement(
                     db, file, then_block, loop_depth, sink,
                 );
@@ -280,6 +298,8 @@ impl ControlFlowValidator {
     fn statement_provides_return_value(stmt: &Spanned<Statement>) -> bool {
         match stmt.value() {
             Statement::Return { .. } => true,
+            // A path that panics never needs to produce a value.
+            Statement::Expression(expr) if Self::is_panic_call(expr) => true,
             Statement::Block(body) => Self::body_returns_on_all_paths(body),
             Statement::If {
                 then_block,
@@ -315,6 +335,7 @@ impl ControlFlowValidator {
     fn statement_guarantees_hard_return(stmt: &Spanned<Statement>) -> bool {
         match stmt.value() {
             Statement::Return { .. } => true,
+            Statement::Expression(expr) if Self::is_panic_call(expr) => true,
             Statement::Block(body) => {
                 // A block has a hard return if any of its statements has one.
                 body.iter().any(Self::statement_guarantees_hard_return)
@@ -342,8 +363,26 @@ impl ControlFlowValidator {
         }
     }
 
+    /// Returns `true` if the expression is a call to the `panic` builtin.
+    fn is_panic_call(expr: &Spanned<Expression>) -> bool {
+        match expr.value() {
+            Expression::FunctionCall { callee, .. } => matches!(
+                callee.value(),
+                Expression::Identifier(ident)
+                    if is_builtin_function_name(ident.value()) == Some(BuiltinFn::Panic)
+            ),
+            _ => false,
+        }
+    }
+
     /// Check if a statement contains any break statements
     fn contains_break(stmt: &Spanned<Statement>) -> bool {
+        if shared::expression_blocks(stmt.value())
+            .into_iter()
+            .any(|block| block.iter().any(Self::contains_break))
+        {
+            return true;
+        }
         match stmt.value() {
             Statement::Break => true,
             Statement::Block(statements) => statements.iter().any(Self::contains_break),
@@ -373,6 +412,7 @@ impl ControlFlowValidator {
             Statement::Let { .. } => "variable declaration",
             Statement::Const(_) => "constant declaration",
             Statement::Assignment { .. } => "assignment",
+            Statement::CompoundAssignment { .. } => "compound assignment",
             Statement::Return { .. } => "return statement",
             Statement::If { .. } => "if statement",
             Statement::Expression(_) => "expression statement",
@@ -22,7 +22,7 @@ use crate::{DefinitionKind, File, SemanticIndex};
 pub struct LiteralValidator;
 
 /// Maximum value for felt type (2^31 - 1)
-const FELT_MAX: u64 = (1u64 << 31) - 1;
+pub(crate) const FELT_MAX: u64 = (1u64 << 31) - 1;
 
 impl Validator for LiteralValidator {
     fn validate(
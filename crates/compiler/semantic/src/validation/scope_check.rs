@@ -23,9 +23,11 @@
 use std::collections::HashSet;
 
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
+use cairo_m_compiler_parser::parser::SELF_PARAM;
 
 use crate::builtins::is_builtin_function_name;
 use crate::db::{Crate, SemanticDb};
+use crate::semantic_index::resolve_imported_item;
 use crate::validation::Validator;
 use crate::{File, SemanticIndex};
 
@@ -105,17 +107,21 @@ impl ScopeValidator {
         sink: &dyn DiagnosticSink,
     ) {
         for (def_idx, def) in index.definitions_in_scope(scope_id) {
-            // Only warn for non-function/struct items
+            // Only warn for non-function/struct items, re-exports are used by importers
             let is_func_or_struct = matches!(
                 def.kind,
                 crate::definition::DefinitionKind::Function(_)
                     | crate::definition::DefinitionKind::Struct(_)
             );
-            if is_func_or_struct {
+            let is_reexport = matches!(
+                &def.kind,
+                crate::definition::DefinitionKind::Use(use_def) if use_def.is_pub
+            );
+            if is_func_or_struct || is_reexport {
                 continue;
             }
-            // Ignore underscore-prefixed names
-            if def.name.starts_with('_') {
+            // Ignore underscore-prefixed names and method receivers
+            if def.name.starts_with('_') || def.name == SELF_PARAM {
                 continue;
             }
             // Skip if used
@@ -239,12 +245,41 @@ impl ScopeValidator {
 
             // Check if the target module exists
             if let Some(imported_module_index) = modules.get(imported_module_name.value()) {
-                // Check if the imported item exists in the target module
+                // Check if the imported item exists in the target module, and is not a private
+                // import of it
                 if let Some(imported_root) = imported_module_index.root_scope() {
-                    if imported_module_index
-                        .latest_definition_index_by_name(imported_root, imported_item.value())
-                        .is_none()
+                    if resolve_imported_item(
+                        db,
+                        crate_id,
+                        imported_module_name.value(),
+                        imported_item.value(),
+                    )
+                    .is_none()
                     {
+                        let is_private_import = imported_module_index
+                            .latest_definition_index_by_name(imported_root, imported_item.value())
+                            .and_then(|def_idx| imported_module_index.definition(def_idx))
+                            .is_some_and(|def| {
+                                matches!(
+                                    &def.kind,
+                                    crate::definition::DefinitionKind::Use(use_def)
+                                        if !use_def.is_pub
+                                )
+                            });
+                        let message = if is_private_import {
+                            format!(
+                                "`{}` is a private import of module `{}`, re-export it with `pub use`",
+                                imported_item.value(),
+                                imported_module_name.value()
+                            )
+                        } else {
+                            format!(
+                                "unresolved import `{}` from module `{}`",
+                                imported_item.value(),
+                                imported_module_name.value()
+                            )
+                        };
+
                         // The imported item doesn't exist in the target module
                         // We need to find the span for this specific import item to create a proper diagnostic
 
@@ -257,11 +292,7 @@ impl ScopeValidator {
                                     sink.push(
                                         Diagnostic::error(
                                             DiagnosticCode::UnresolvedImport,
-                                            format!(
-                                                "unresolved import `{}` from module `{}`",
-                                                imported_item.value(),
-                                                imported_module_name.value()
-                                            ),
+                                            message,
                                         )
                                         .with_location(
                                             file.file_path(db).to_string(),
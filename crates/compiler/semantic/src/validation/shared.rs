@@ -5,7 +5,9 @@
 //! patterns that apply across different validation passes.
 
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
-use cairo_m_compiler_parser::parser::{Parameter, Pattern, Spanned, StructDef};
+use cairo_m_compiler_parser::parser::{
+    Expression, Parameter, Pattern, Spanned, Statement, StructDef,
+};
 use rustc_hash::{FxBuildHasher, FxHashSet};
 
 /// Check for duplicate parameter names in a function or method
@@ -99,3 +101,87 @@ pub(crate) fn check_duplicate_struct_fields(
         }
     }
 }
+
+/// Returns the statements of the block expressions found in the expressions of a statement,
+/// e.g. `let t = f();` for `let x = { let t = f(); t + 1 };`.
+///
+/// The statements nested in the returned ones are not walked: the callers recurse on the
+/// returned statements.
+pub(crate) fn expression_blocks(stmt: &Statement) -> Vec<&[Spanned<Statement>]> {
+    let mut blocks = Vec::new();
+    let expressions: Vec<&Spanned<Expression>> = match stmt {
+        Statement::Let { value, .. } => vec![value],
+        Statement::Const(const_def) => vec![&const_def.value],
+        Statement::Assignment { lhs, rhs } | Statement::CompoundAssignment { lhs, rhs, .. } => {
+            vec![lhs, rhs]
+        }
+        Statement::Return { value } => value.iter().collect(),
+        Statement::Expression(expr) => vec![expr],
+        Statement::If { condition, .. }
+        | Statement::While { condition, .. }
+        | Statement::For { condition, .. } => vec![condition],
+        Statement::Block(_) | Statement::Loop { .. } | Statement::Break | Statement::Continue => {
+            vec![]
+        }
+    };
+    for expr in expressions {
+        collect_expression_blocks(expr, &mut blocks);
+    }
+    blocks
+}
+
+fn collect_expression_blocks<'a>(
+    expr: &'a Spanned<Expression>,
+    blocks: &mut Vec<&'a [Spanned<Statement>]>,
+) {
+    match expr.value() {
+        Expression::Block { statements, value } => {
+            blocks.push(statements);
+            collect_expression_blocks(value, blocks);
+        }
+        Expression::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            collect_expression_blocks(condition, blocks);
+            collect_expression_blocks(then_branch, blocks);
+            collect_expression_blocks(else_branch, blocks);
+        }
+        Expression::UnaryOp { expr: inner, .. }
+        | Expression::Cast { expr: inner, .. }
+        | Expression::Parenthesized(inner)
+        | Expression::MemberAccess { object: inner, .. }
+        | Expression::TupleIndex { tuple: inner, .. }
+        | Expression::ArrayRepeat { element: inner, .. }
+        | Expression::New { count: inner, .. } => collect_expression_blocks(inner, blocks),
+        Expression::BinaryOp { left, right, .. } => {
+            collect_expression_blocks(left, blocks);
+            collect_expression_blocks(right, blocks);
+        }
+        Expression::IndexAccess { array, index } => {
+            collect_expression_blocks(array, blocks);
+            collect_expression_blocks(index, blocks);
+        }
+        Expression::FunctionCall { callee, args } => {
+            collect_expression_blocks(callee, blocks);
+            for arg in args {
+                collect_expression_blocks(arg, blocks);
+            }
+        }
+        Expression::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                collect_expression_blocks(value, blocks);
+            }
+        }
+        Expression::Tuple(elements) | Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                collect_expression_blocks(element, blocks);
+            }
+        }
+        Expression::Literal(..)
+        | Expression::BooleanLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::Identifier(_) => {}
+    }
+}
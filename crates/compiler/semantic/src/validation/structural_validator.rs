@@ -5,16 +5,19 @@
 //! - Duplicate parameter names in functions
 //! - Duplicate field names in struct definitions
 //! - Duplicate identifiers in pattern destructuring
+//! - `impl` blocks of unknown types, methods without receiver and operator methods not
+//!   taking a single right operand
 //! - Type cohesion between expressions and type annotations
 
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
 use cairo_m_compiler_parser::parser::{
-    Expression, FunctionDef, NamedType, Spanned, Statement, StructDef, TopLevelItem, TypeExpr,
-    parse_file,
+    Expression, FunctionDef, ImplDef, NamedType, SELF_PARAM, Spanned, Statement, StructDef,
+    TopLevelItem, TypeExpr, parse_file,
 };
 
 use crate::db::{Crate, SemanticDb};
 use crate::definition::DefinitionKind;
+use crate::type_resolution::OPERATOR_METHOD_NAMES;
 use crate::validation::{Validator, shared};
 use crate::{File, SemanticIndex};
 
@@ -64,6 +67,13 @@ impl Validator for StructuralValidator {
             }
         }
 
+        // Check the targets and receivers of impl blocks
+        for item in parsed_module.items() {
+            if let TopLevelItem::Impl(impl_def) = item {
+                self.check_impl(impl_def.value(), index, file_path, sink);
+            }
+        }
+
         // Check patterns in let statements and function bodies
         self.check_patterns_in_module(&parsed_module, file_path, sink);
 
@@ -82,10 +92,7 @@ impl StructuralValidator {
         module: &'a cairo_m_compiler_parser::parser::ParsedModule,
         name: &str,
     ) -> Option<&'a FunctionDef> {
-        module.items().iter().find_map(|item| match item {
-            TopLevelItem::Function(func) if func.value().name.value() == name => Some(func.value()),
-            _ => None,
-        })
+        module.function(name).map(Spanned::value)
     }
 
     fn find_struct_in_module(
@@ -101,16 +108,92 @@ impl StructuralValidator {
         })
     }
 
+    /// Checks that an impl block defines methods of a struct of the module, each taking a
+    /// `self` receiver of the struct type as first parameter.
+    fn check_impl(
+        &self,
+        impl_def: &ImplDef,
+        index: &SemanticIndex,
+        file_path: &str,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let type_name = &impl_def.type_name;
+        let definition = index.root_scope().and_then(|root| {
+            index
+                .latest_definition_index_by_name(root, type_name.value())
+                .and_then(|def_idx| index.definition(def_idx))
+        });
+        match definition.map(|definition| &definition.kind) {
+            Some(DefinitionKind::Struct(_)) => {}
+            None => sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::UndeclaredType,
+                    format!("Cannot find struct `{}`", type_name.value()),
+                )
+                .with_location(file_path.to_string(), type_name.span()),
+            ),
+            Some(DefinitionKind::Use(_)) => sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidImpl,
+                    format!(
+                        "Methods of `{}` must be defined in the module of the struct",
+                        type_name.value()
+                    ),
+                )
+                .with_location(file_path.to_string(), type_name.span()),
+            ),
+            Some(_) => sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidImpl,
+                    format!("`{}` is not a struct", type_name.value()),
+                )
+                .with_location(file_path.to_string(), type_name.span()),
+            ),
+        }
+
+        let self_type = NamedType::Custom(type_name.value().clone());
+        for method in &impl_def.methods {
+            let method = method.value();
+            let has_receiver = method.params.first().is_some_and(|param| {
+                param.name.value() == SELF_PARAM
+                    && matches!(param.type_expr.value(), TypeExpr::Named(ty) if *ty.value() == self_type)
+            });
+            if !has_receiver {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidImpl,
+                        format!(
+                            "Method `{}` must take `self` as first parameter",
+                            method.name.value()
+                        ),
+                    )
+                    .with_location(file_path.to_string(), method.name.span()),
+                );
+            } else if OPERATOR_METHOD_NAMES.contains(&method.name.value().as_str())
+                && method.params.len() != 2
+            {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidImpl,
+                        format!(
+                            "Operator method `{}` must take `self` and the right operand as parameters",
+                            method.name.value()
+                        ),
+                    )
+                    .with_location(file_path.to_string(), method.name.span()),
+                );
+            }
+        }
+    }
+
     fn check_patterns_in_module(
         &self,
         module: &cairo_m_compiler_parser::parser::ParsedModule,
         file_path: &str,
         sink: &dyn DiagnosticSink,
     ) {
-        for item in module.items() {
-            if let TopLevelItem::Function(func) = item {
-                self.check_patterns_in_statements(&func.value().body, file_path, sink);
-            }
+        for (_, func) in module.functions() {
+            self.check_patterns_in_statements(&func.value().body, file_path, sink);
         }
     }
 
@@ -122,6 +205,9 @@ impl StructuralValidator {
         sink: &dyn DiagnosticSink,
     ) {
         for stmt in statements {
+            for block in shared::expression_blocks(stmt.value()) {
+                self.check_patterns_in_statements(block, file_path, sink);
+            }
             match stmt.value() {
                 Statement::Let { pattern, .. } => {
                     shared::check_duplicate_pattern_identifiers(pattern, file_path, sink);
@@ -157,10 +243,8 @@ impl StructuralValidator {
         file_path: &str,
         sink: &dyn DiagnosticSink,
     ) {
-        for item in module.items() {
-            if let TopLevelItem::Function(func) = item {
-                self.check_type_cohesion_in_statements(&func.value().body, file_path, sink);
-            }
+        for (_, func) in module.functions() {
+            self.check_type_cohesion_in_statements(&func.value().body, file_path, sink);
         }
     }
 
@@ -171,6 +255,9 @@ impl StructuralValidator {
         sink: &dyn DiagnosticSink,
     ) {
         for stmt in statements {
+            for block in shared::expression_blocks(stmt.value()) {
+                self.check_type_cohesion_in_statements(block, file_path, sink);
+            }
             match stmt.value() {
                 Statement::Let {
                     value,
@@ -233,6 +320,7 @@ impl StructuralValidator {
                     file_path: file_path.to_string(),
                     span: type_ast.span(),
                     related_spans: vec![],
+                    module_path: None,
                 });
                 return;
             }
@@ -276,6 +364,7 @@ impl StructuralValidator {
                             actual_type, typed_name
                         ),
                     )],
+                    module_path: None,
                 });
             }
         }
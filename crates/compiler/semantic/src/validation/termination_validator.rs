@@ -0,0 +1,436 @@
+//! # Termination Lints
+//!
+//! This module implements warnings for executions that never terminate. Such executions do not
+//! fail at compile time nor at runtime: they burn the step limit of the runner, which makes them
+//! hard to diagnose.
+//! - **Unbounded recursion**: Builds the call graph of the module, keeping only the calls whose
+//!   arguments are all parameters of the caller left unchanged. A call on a cycle of such calls
+//!   is made again with the same arguments, forever.
+//! - **Non-terminating loops**: Warns about `loop` and `while` loops whose body changes no
+//!   state (no assignment, no function call) and never exits (no `break`, no `return`).
+//!
+//! # Implementation Notes
+//!
+//! Both lints are heuristics working on the AST: they only report cases that are
+//! certain to loop, and miss the ones depending on the values being computed.
+//! - A parameter counts as changed when it is assigned (including through a field or an
+//!   index), shadowed by a `let`, or is passed by pointer (pointers and fixed-size arrays), as
+//!   the memory it points to can change.
+//! - Only the calls to functions of the same module are part of the call graph.
+
+use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticSink};
+use cairo_m_compiler_parser::const_eval::eval_const_condition;
+use cairo_m_compiler_parser::parser::{
+    Expression, FunctionDef, Pattern, Spanned, Statement, TypeExpr, parse_file,
+};
+use chumsky::span::SimpleSpan;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::db::{Crate, SemanticDb};
+use crate::validation::{Validator, shared};
+use crate::{File, SemanticIndex};
+
+/// Validator for unbounded recursion and non-terminating loops.
+pub struct TerminationValidator;
+
+/// A call to a function of the module whose arguments are unchanged parameters of the caller.
+struct StationaryCall {
+    caller: usize,
+    callee: usize,
+    span: SimpleSpan<usize>,
+}
+
+impl Validator for TerminationValidator {
+    fn validate(
+        &self,
+        db: &dyn SemanticDb,
+        _crate_id: Crate,
+        file: File,
+        _index: &SemanticIndex,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let parsed_program = parse_file(db, file);
+        if !parsed_program.diagnostics.is_empty() {
+            panic!("Got unexpected parse errors");
+        }
+        let parsed_module = parsed_program.module;
+        let file_path = file.file_path(db);
+
+        let functions: Vec<&FunctionDef> = parsed_module
+            .functions()
+            .map(|(_, func)| func.value())
+            .collect();
+
+        for function in &functions {
+            for stmt in &function.body {
+                for_each_statement(stmt, &mut |stmt| {
+                    if Self::is_non_terminating_loop(stmt) {
+                        sink.push(Diagnostic::non_terminating_loop(
+                            file_path.to_string(),
+                            stmt.span(),
+                        ));
+                    }
+                });
+            }
+        }
+
+        let calls = Self::stationary_calls(&functions);
+        for call in &calls {
+            if Self::reaches(&calls, call.callee, call.caller) {
+                sink.push(Diagnostic::unbounded_recursion(
+                    file_path.to_string(),
+                    functions[call.callee].name.value(),
+                    call.span,
+                ));
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "TerminationValidator"
+    }
+}
+
+impl TerminationValidator {
+    // ---------------------------------------------------------------------
+    // Unbounded recursion
+    // ---------------------------------------------------------------------
+
+    /// Collects the calls between functions of the module that pass unchanged parameters of
+    /// the caller as arguments. A call of a function to itself must also keep each parameter
+    /// at its position.
+    fn stationary_calls(functions: &[&FunctionDef]) -> Vec<StationaryCall> {
+        let function_indices: FxHashMap<&str, usize> = functions
+            .iter()
+            .enumerate()
+            .map(|(i, function)| (function.name.value().as_str(), i))
+            .collect();
+
+        let mut calls = Vec::new();
+        for (caller, function) in functions.iter().enumerate() {
+            let changed = Self::changed_parameters(function);
+            // Position of each unchanged parameter
+            let unchanged: FxHashMap<&str, usize> = function
+                .params
+                .iter()
+                .enumerate()
+                .filter(|(_, param)| !changed.contains(param.name.value().as_str()))
+                .map(|(i, param)| (param.name.value().as_str(), i))
+                .collect();
+
+            for stmt in &function.body {
+                for_each_expression(stmt, &mut |expr| {
+                    let Expression::FunctionCall { callee, args } = expr.value() else {
+                        return;
+                    };
+                    let Expression::Identifier(name) = callee.value() else {
+                        return;
+                    };
+                    let Some(&callee) = function_indices.get(name.value().as_str()) else {
+                        return;
+                    };
+                    if args.len() != functions[callee].params.len() {
+                        return;
+                    }
+                    let is_stationary = args.iter().enumerate().all(|(position, arg)| {
+                        let Expression::Identifier(arg) = strip_parentheses(arg) else {
+                            return false;
+                        };
+                        unchanged
+                            .get(arg.value().as_str())
+                            .is_some_and(|&param| callee != caller || param == position)
+                    });
+                    if is_stationary {
+                        calls.push(StationaryCall {
+                            caller,
+                            callee,
+                            span: expr.span(),
+                        });
+                    }
+                });
+            }
+        }
+        calls
+    }
+
+    /// Returns the parameters of a function that may hold another value than the one it was
+    /// called with.
+    fn changed_parameters(function: &FunctionDef) -> FxHashSet<&str> {
+        let mut changed: FxHashSet<&str> = function
+            .params
+            .iter()
+            .filter(|param| {
+                matches!(
+                    param.type_expr.value(),
+                    TypeExpr::Pointer(_) | TypeExpr::FixedArray { .. }
+                )
+            })
+            .map(|param| param.name.value().as_str())
+            .collect();
+        for stmt in &function.body {
+            for_each_statement(stmt, &mut |stmt| match stmt.value() {
+                Statement::Let { pattern, .. } => collect_pattern_names(pattern, &mut changed),
+                Statement::Assignment { lhs, .. } | Statement::CompoundAssignment { lhs, .. } => {
+                    if let Some(root) = assigned_variable(lhs) {
+                        changed.insert(root);
+                    }
+                }
+                _ => {}
+            });
+        }
+        changed
+    }
+
+    /// Returns `true` if `to` can be reached from `from` through the calls.
+    fn reaches(calls: &[StationaryCall], from: usize, to: usize) -> bool {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![from];
+        while let Some(function) = stack.pop() {
+            if function == to {
+                return true;
+            }
+            if visited.insert(function) {
+                stack.extend(
+                    calls
+                        .iter()
+                        .filter(|call| call.caller == function)
+                        .map(|call| call.callee),
+                );
+            }
+        }
+        false
+    }
+
+    // ---------------------------------------------------------------------
+    // Non-terminating loops
+    // ---------------------------------------------------------------------
+
+    /// Returns `true` if the statement is a loop that is entered and that neither changes any
+    /// state nor exits.
+    fn is_non_terminating_loop(stmt: &Spanned<Statement>) -> bool {
+        let body = match stmt.value() {
+            Statement::Loop { body } => body,
+            Statement::While { condition, body } => {
+                if eval_const_condition(condition.value()) == Some(false)
+                    || Self::contains_call(condition)
+                {
+                    return false;
+                }
+                body
+            }
+            _ => return false,
+        };
+        !Self::exits_loop(body) && !Self::changes_state(body)
+    }
+
+    /// Returns `true` if the statement contains a `return`, or a `break` of the enclosing loop.
+    fn exits_loop(stmt: &Spanned<Statement>) -> bool {
+        if shared::expression_blocks(stmt.value())
+            .into_iter()
+            .any(|block| block.iter().any(Self::exits_loop))
+        {
+            return true;
+        }
+        match stmt.value() {
+            Statement::Break | Statement::Return { .. } => true,
+            Statement::Block(statements) => statements.iter().any(Self::exits_loop),
+            Statement::If {
+                then_block,
+                else_block,
+                ..
+            } => {
+                Self::exits_loop(then_block)
+                    || else_block.as_ref().is_some_and(|eb| Self::exits_loop(eb))
+            }
+            // The breaks of nested loops exit these loops only
+            Statement::Loop { body }
+            | Statement::While { body, .. }
+            | Statement::For { body, .. } => Self::contains_return(body),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the statement contains a `return`.
+    fn contains_return(stmt: &Spanned<Statement>) -> bool {
+        let mut found = false;
+        for_each_statement(stmt, &mut |stmt| {
+            found |= matches!(stmt.value(), Statement::Return { .. });
+        });
+        found
+    }
+
+    /// Returns `true` if the statement contains an assignment or a function call.
+    fn changes_state(stmt: &Spanned<Statement>) -> bool {
+        let mut found = false;
+        for_each_statement(stmt, &mut |stmt| {
+            found |= matches!(
+                stmt.value(),
+                Statement::Assignment { .. } | Statement::CompoundAssignment { .. }
+            );
+        });
+        for_each_expression(stmt, &mut |expr| {
+            found |= matches!(expr.value(), Expression::FunctionCall { .. });
+        });
+        found
+    }
+
+    /// Returns `true` if the expression contains a function call.
+    fn contains_call(expr: &Spanned<Expression>) -> bool {
+        let mut found = false;
+        walk_expression(expr, &mut |expr| {
+            found |= matches!(expr.value(), Expression::FunctionCall { .. });
+        });
+        found
+    }
+}
+
+/// Calls `f` on the statement and on all the statements nested in it, including the ones of
+/// block expressions.
+fn for_each_statement<'a>(
+    stmt: &'a Spanned<Statement>,
+    f: &mut impl FnMut(&'a Spanned<Statement>),
+) {
+    f(stmt);
+    for block in shared::expression_blocks(stmt.value()) {
+        for stmt in block {
+            for_each_statement(stmt, f);
+        }
+    }
+    match stmt.value() {
+        Statement::Block(statements) => {
+            for stmt in statements {
+                for_each_statement(stmt, f);
+            }
+        }
+        Statement::If {
+            then_block,
+            else_block,
+            ..
+        } => {
+            for_each_statement(then_block, f);
+            if let Some(else_block) = else_block {
+                for_each_statement(else_block, f);
+            }
+        }
+        Statement::Loop { body } | Statement::While { body, .. } => for_each_statement(body, f),
+        Statement::For {
+            init, step, body, ..
+        } => {
+            for_each_statement(init, f);
+            for_each_statement(step, f);
+            for_each_statement(body, f);
+        }
+        _ => {}
+    }
+}
+
+/// Calls `f` on all the expressions of the statement, nested ones included.
+fn for_each_expression<'a>(
+    stmt: &'a Spanned<Statement>,
+    f: &mut impl FnMut(&'a Spanned<Expression>),
+) {
+    for_each_statement(stmt, &mut |stmt| match stmt.value() {
+        Statement::Let { value, .. } => walk_expression(value, f),
+        Statement::Const(const_def) => walk_expression(&const_def.value, f),
+        Statement::Assignment { lhs, rhs } | Statement::CompoundAssignment { lhs, rhs, .. } => {
+            walk_expression(lhs, f);
+            walk_expression(rhs, f);
+        }
+        Statement::Return { value } => {
+            if let Some(value) = value {
+                walk_expression(value, f);
+            }
+        }
+        Statement::Expression(expr) => walk_expression(expr, f),
+        Statement::If { condition, .. }
+        | Statement::While { condition, .. }
+        | Statement::For { condition, .. } => walk_expression(condition, f),
+        Statement::Block(_) | Statement::Loop { .. } | Statement::Break | Statement::Continue => {}
+    });
+}
+
+/// Calls `f` on the expression and on all its sub-expressions.
+///
+/// The statements of block expressions are walked by [`for_each_statement`].
+fn walk_expression<'a>(expr: &'a Spanned<Expression>, f: &mut impl FnMut(&'a Spanned<Expression>)) {
+    f(expr);
+    match expr.value() {
+        Expression::Literal(..)
+        | Expression::BooleanLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::Identifier(_) => {}
+        Expression::UnaryOp { expr, .. }
+        | Expression::Cast { expr, .. }
+        | Expression::Parenthesized(expr) => walk_expression(expr, f),
+        Expression::BinaryOp { left, right, .. } => {
+            walk_expression(left, f);
+            walk_expression(right, f);
+        }
+        Expression::FunctionCall { callee, args } => {
+            walk_expression(callee, f);
+            for arg in args {
+                walk_expression(arg, f);
+            }
+        }
+        Expression::MemberAccess { object, .. } => walk_expression(object, f),
+        Expression::IndexAccess { array, index } => {
+            walk_expression(array, f);
+            walk_expression(index, f);
+        }
+        Expression::StructLiteral { fields, .. } => {
+            for (_, value) in fields {
+                walk_expression(value, f);
+            }
+        }
+        Expression::Tuple(elements) | Expression::ArrayLiteral(elements) => {
+            for element in elements {
+                walk_expression(element, f);
+            }
+        }
+        Expression::TupleIndex { tuple, .. } => walk_expression(tuple, f),
+        Expression::ArrayRepeat { element, .. } => walk_expression(element, f),
+        Expression::New { count, .. } => walk_expression(count, f),
+        Expression::Block { value, .. } => walk_expression(value, f),
+        Expression::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            walk_expression(condition, f);
+            walk_expression(then_branch, f);
+            walk_expression(else_branch, f);
+        }
+    }
+}
+
+fn strip_parentheses(expr: &Spanned<Expression>) -> &Expression {
+    match expr.value() {
+        Expression::Parenthesized(inner) => strip_parentheses(inner),
+        expr => expr,
+    }
+}
+
+/// Returns the variable written by an assignment, e.g. `p` for `p.x[0] = 1`.
+fn assigned_variable(lhs: &Spanned<Expression>) -> Option<&str> {
+    match lhs.value() {
+        Expression::Identifier(name) => Some(name.value().as_str()),
+        Expression::MemberAccess { object: inner, .. }
+        | Expression::IndexAccess { array: inner, .. }
+        | Expression::TupleIndex { tuple: inner, .. }
+        | Expression::Parenthesized(inner) => assigned_variable(inner),
+        _ => None,
+    }
+}
+
+fn collect_pattern_names<'a>(pattern: &'a Pattern, names: &mut FxHashSet<&'a str>) {
+    match pattern {
+        Pattern::Identifier(name) => {
+            names.insert(name.value().as_str());
+        }
+        Pattern::Tuple(patterns) => {
+            for pattern in patterns {
+                collect_pattern_names(pattern, names);
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@
 //! - Assignment type compatibility
 //! - Return type matching
 //! - Conditional expression types
+//! - Unused function call results and function attributes
 
 #![allow(clippy::too_many_arguments)]
 
@@ -16,20 +17,23 @@ use std::collections::HashSet;
 use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode, DiagnosticSink};
 use cairo_m_compiler_parser::ParsedModule;
 use cairo_m_compiler_parser::parser::{
-    BinaryOp, Expression, FunctionDef, Pattern, Spanned, Statement, TopLevelItem, TypeExpr,
+    BinaryOp, Expression, FunctionDef, NamedType, Parameter, Pattern, Spanned, Statement, TypeExpr,
     UnaryOp, parse_file,
 };
 use chumsky::span::SimpleSpan;
 
 use crate::builtins::{BuiltinFn, is_builtin_function_name};
-use crate::db::{Crate, SemanticDb};
+use crate::db::{Crate, SemanticDb, module_name_for_file, module_semantic_index};
+use crate::definition::MUST_USE_ATTRIBUTE;
 use crate::semantic_index::{DefinitionId as SemDefinitionId, ExpressionInfo};
 use crate::type_resolution::{
     are_types_compatible, definition_semantic_type as sem_definition_type,
-    expression_semantic_type, get_binary_op_signatures, get_unary_op_signatures, resolve_ast_type,
+    expression_semantic_type, get_binary_op_signatures, get_unary_op_signatures, operator_method,
+    operator_method_name, resolve_ast_type, struct_method,
 };
 use crate::types::{TypeData, TypeId};
-use crate::validation::Validator;
+use crate::validation::literal_validator::FELT_MAX;
+use crate::validation::{Validator, shared};
 use crate::{DefinitionKind, ExpressionId, File, SemanticIndex};
 
 /// Unified validator for all type-related semantic checks
@@ -68,15 +72,29 @@ impl Validator for TypeValidator {
         }
 
         // Check all function definitions for nested arrays in their signatures
-        for item in parsed_module.items() {
-            if let TopLevelItem::Function(func_spanned) = item {
-                let func_def = func_spanned.value();
-                // Check return type for nested arrays
-                Self::check_for_nested_arrays(db, file, &func_def.return_type, sink);
-
-                // Check parameter types for nested arrays
-                for param in &func_def.params {
-                    Self::check_for_nested_arrays(db, file, &param.type_expr, sink);
+        for (_, func_spanned) in parsed_module.functions() {
+            let func_def = func_spanned.value();
+            // Check return type for nested arrays
+            Self::check_for_nested_arrays(db, file, &func_def.return_type, sink);
+
+            // Check parameter types for nested arrays
+            for param in &func_def.params {
+                Self::check_for_nested_arrays(db, file, &param.type_expr, sink);
+            }
+
+            // Check parameter default values
+            Self::check_parameter_defaults(db, file, &func_def.params, sink);
+
+            // Check that attributes are known
+            for attr in &func_def.attributes {
+                if attr.value() != MUST_USE_ATTRIBUTE {
+                    sink.push(
+                        Diagnostic::error(
+                            DiagnosticCode::UnknownAttribute,
+                            format!("Unknown attribute `{}`", attr.value()),
+                        )
+                        .with_location(file.file_path(db).to_string(), attr.span()),
+                    );
                 }
             }
         }
@@ -162,10 +180,28 @@ impl TypeValidator {
         crate_id: Crate,
         file: File,
         index: &SemanticIndex,
-        _callee: &Spanned<Expression>,
+        callee: &Spanned<Expression>,
         args: &[Spanned<Expression>],
         sink: &dyn DiagnosticSink,
     ) {
+        // assert takes a condition and an optional message.
+        if args.len() > 2 {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidFunctionCall,
+                    format!(
+                        "`assert` expects a condition and an optional message, but {} arguments were provided",
+                        args.len()
+                    ),
+                )
+                .with_location(file.file_path(db).to_string(), callee.span()),
+            );
+            return;
+        }
+        if let Some(message) = args.get(1) {
+            Self::check_abort_message(db, file, "assert", message, sink);
+        }
+
         // Only validate that the inner expression (if provided) evaluates to a boolean.
         // Delegate all other typing rules to the general validators.
         if let Some(cond) = args.first() {
@@ -190,6 +226,252 @@ impl TypeValidator {
         }
     }
 
+    fn check_builtin_panic(
+        &self,
+        db: &dyn SemanticDb,
+        file: File,
+        callee: &Spanned<Expression>,
+        args: &[Spanned<Expression>],
+        sink: &dyn DiagnosticSink,
+    ) {
+        // panic takes an optional message.
+        match args {
+            [] => {}
+            [message] => Self::check_abort_message(db, file, "panic", message, sink),
+            _ => {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::InvalidFunctionCall,
+                        format!(
+                            "`panic` expects an optional message, but {} arguments were provided",
+                            args.len()
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), callee.span()),
+                );
+            }
+        }
+    }
+
+    /// Check a call to a built-in intrinsic against its signature.
+    fn check_builtin_intrinsic_call(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        name: &str,
+        callee: &Spanned<Expression>,
+        args: &[Spanned<Expression>],
+        sink: &dyn DiagnosticSink,
+    ) {
+        let Some((params, _)) = is_builtin_function_name(name).and_then(|b| b.signature(db)) else {
+            return;
+        };
+
+        if args.len() != params.len() {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidFunctionCall,
+                    format!(
+                        "Function expects {} argument(s), but {} were provided",
+                        params.len(),
+                        args.len()
+                    ),
+                )
+                .with_location(file.file_path(db).to_string(), callee.span()),
+            );
+            return;
+        }
+
+        for (arg, (param_name, param_type)) in args.iter().zip(params) {
+            let Some(arg_expr_id) = index.expression_id_by_span(arg.span()) else {
+                continue;
+            };
+            let arg_type =
+                expression_semantic_type(db, crate_id, file, arg_expr_id, Some(param_type));
+            if !are_types_compatible(db, arg_type, param_type) {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "argument type mismatch for parameter `{}` of `{}`: expected `{}`, got `{}`",
+                            param_name,
+                            name,
+                            param_type.data(db).display_name(db),
+                            arg_type.data(db).display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), arg.span()),
+                );
+            }
+        }
+    }
+
+    /// Checks that an `assert` or `panic` message is a string literal.
+    fn check_abort_message(
+        db: &dyn SemanticDb,
+        file: File,
+        builtin: &str,
+        message: &Spanned<Expression>,
+        sink: &dyn DiagnosticSink,
+    ) {
+        if !matches!(message.value(), Expression::StringLiteral(_)) {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::TypeMismatch,
+                    format!("`{builtin}` message must be a string literal"),
+                )
+                .with_location(file.file_path(db).to_string(), message.span()),
+            );
+        }
+    }
+
+    /// Returns whether a string literal with this origin is an `assert` or `panic` message, or an
+    /// `include_bytes` path.
+    fn is_abort_message(index: &SemanticIndex, origin: &crate::semantic_index::Origin) -> bool {
+        use crate::semantic_index::Origin;
+
+        let Origin::Arg { callee, index: arg } = origin else {
+            return false;
+        };
+        let Some(Expression::Identifier(ident)) =
+            index.expression(*callee).map(|info| &info.ast_node)
+        else {
+            return false;
+        };
+        matches!(
+            (is_builtin_function_name(ident.value()), arg),
+            (Some(BuiltinFn::Assert), 1)
+                | (Some(BuiltinFn::Panic), 0)
+                | (Some(BuiltinFn::IncludeBytes), 0)
+        )
+    }
+
+    fn check_builtin_include_bytes(
+        &self,
+        db: &dyn SemanticDb,
+        file: File,
+        index: &SemanticIndex,
+        expr_id: ExpressionId,
+        callee: &Spanned<Expression>,
+        args: &[Spanned<Expression>],
+        sink: &dyn DiagnosticSink,
+    ) {
+        let error = |message: String, span: SimpleSpan<usize>| {
+            sink.push(
+                Diagnostic::error(DiagnosticCode::InvalidFunctionCall, message)
+                    .with_location(file.file_path(db).to_string(), span),
+            );
+        };
+
+        // The included data is embedded once in program memory, as the value of a const
+        let is_const_value = index.all_definitions().any(|(_, def)| match &def.kind {
+            DefinitionKind::Const(const_ref) => const_ref.value_expr_id == Some(expr_id),
+            _ => false,
+        });
+        if !is_const_value {
+            error(
+                "`include_bytes` can only be used as the value of a `const`".to_string(),
+                callee.span(),
+            );
+        }
+
+        let [path] = args else {
+            error(
+                format!(
+                    "`include_bytes` expects 1 argument, but {} were provided",
+                    args.len()
+                ),
+                callee.span(),
+            );
+            return;
+        };
+        let Expression::StringLiteral(path_value) = path.value() else {
+            error(
+                "`include_bytes` path must be a string literal".to_string(),
+                path.span(),
+            );
+            return;
+        };
+        if let Err(e) = crate::builtins::read_included_bytes(db, file, path_value) {
+            error(
+                format!("Failed to include `{path_value}`: {e}"),
+                path.span(),
+            );
+        }
+    }
+
+    /// Checks that the index of an `env` call is an integer literal, so that the size of the
+    /// environment block read by the program is known at compile time.
+    fn check_env_index(
+        db: &dyn SemanticDb,
+        file: File,
+        args: &[Spanned<Expression>],
+        sink: &dyn DiagnosticSink,
+    ) {
+        if let [index] = args
+            && !matches!(index.value(), Expression::Literal(_, None))
+        {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidFunctionCall,
+                    "`env` index must be an integer literal".to_string(),
+                )
+                .with_location(file.file_path(db).to_string(), index.span()),
+            );
+        }
+    }
+
+    fn check_builtin_debug_print(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        callee: &Spanned<Expression>,
+        args: &[Spanned<Expression>],
+        sink: &dyn DiagnosticSink,
+    ) {
+        // debug_print takes exactly one scalar value: felt, u32 or bool.
+        let [value] = args else {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::InvalidFunctionCall,
+                    format!(
+                        "Function expects 1 argument(s), but {} were provided",
+                        args.len()
+                    ),
+                )
+                .with_location(file.file_path(db).to_string(), callee.span()),
+            );
+            return;
+        };
+
+        let Some(value_expr_id) = index.expression_id_by_span(value.span()) else {
+            return;
+        };
+        let value_type = expression_semantic_type(db, crate_id, file, value_expr_id, None);
+        match value_type.data(db) {
+            TypeData::Felt | TypeData::U32 | TypeData::Bool => {}
+            TypeData::Error | TypeData::Unknown => {
+                // Avoid cascading diagnostics for already-invalid expressions
+            }
+            other => {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "`debug_print` expects a felt, u32 or bool, found `{}`",
+                            other.display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), value.span()),
+                );
+            }
+        }
+    }
+
     /// Check if a type expression contains nested arrays
     fn check_for_nested_arrays(
         db: &dyn SemanticDb,
@@ -220,6 +502,80 @@ impl TypeValidator {
         }
     }
 
+    /// Check that parameter default values are literals of the parameter type, and that
+    /// they are only given to trailing parameters.
+    ///
+    /// Defaults are recorded in the ABI and used by the runner, so they must be known
+    /// without evaluating any expression.
+    fn check_parameter_defaults(
+        db: &dyn SemanticDb,
+        file: File,
+        params: &[Parameter],
+        sink: &dyn DiagnosticSink,
+    ) {
+        let file_path = file.file_path(db);
+        let mut first_default: Option<&Parameter> = None;
+
+        for param in params {
+            let Some(default) = &param.default else {
+                if let Some(defaulted) = first_default {
+                    sink.push(
+                        Diagnostic::error(
+                            DiagnosticCode::InvalidParameterDefault,
+                            format!(
+                                "Parameter '{}' without default value follows parameter '{}' with a default value",
+                                param.name.value(),
+                                defaulted.name.value()
+                            ),
+                        )
+                        .with_location(file_path.to_string(), param.name.span())
+                        .with_related_span(
+                            file_path.to_string(),
+                            defaulted.name.span(),
+                            "parameter with a default value".to_string(),
+                        ),
+                    );
+                }
+                continue;
+            };
+            first_default.get_or_insert(param);
+
+            let error = match (param.type_expr.value(), default.value()) {
+                (TypeExpr::Named(named), Expression::Literal(value, suffix)) => {
+                    match (named.value(), suffix.as_deref()) {
+                        (NamedType::Felt, None | Some("felt")) if *value > FELT_MAX => Some(
+                            format!("Default value {value} is out of range for type felt"),
+                        ),
+                        (NamedType::U32, None | Some("u32")) if *value > u32::MAX as u64 => Some(
+                            format!("Default value {value} is out of range for type u32"),
+                        ),
+                        (NamedType::Felt, None | Some("felt"))
+                        | (NamedType::U32, None | Some("u32")) => None,
+                        (ty, _) => Some(format!(
+                            "Default value of parameter '{}' is not a literal of type {ty}",
+                            param.name.value()
+                        )),
+                    }
+                }
+                (TypeExpr::Named(named), Expression::BooleanLiteral(_))
+                    if matches!(named.value(), NamedType::Bool) =>
+                {
+                    None
+                }
+                _ => Some(format!(
+                    "Default value of parameter '{}' must be a felt, u32 or bool literal of the parameter type",
+                    param.name.value()
+                )),
+            };
+            if let Some(message) = error {
+                sink.push(
+                    Diagnostic::error(DiagnosticCode::InvalidParameterDefault, message)
+                        .with_location(file_path.to_string(), default.span()),
+                );
+            }
+        }
+    }
+
     /// Suggest possible type conversions or fixes for type mismatches
     fn suggest_type_conversion(
         &self,
@@ -274,7 +630,7 @@ impl TypeValidator {
         crate_id: Crate,
         file: File,
         index: &SemanticIndex,
-        _expr_id: ExpressionId,
+        expr_id: ExpressionId,
         expr_info: &ExpressionInfo,
         sink: &dyn DiagnosticSink,
     ) {
@@ -310,12 +666,64 @@ impl TypeValidator {
                 self.check_binary_op_types(db, crate_id, file, index, left, op, right, sink);
             }
             Expression::FunctionCall { callee, args } => {
-                // Handle built-in assert() semantics
-                if let Expression::Identifier(ident) = callee.value()
-                    && is_builtin_function_name(ident.value()) == Some(BuiltinFn::Assert)
-                {
-                    self.check_builtin_assert(db, crate_id, file, index, callee, args, sink);
-                    return;
+                // Handle built-in assert() / panic() / debug_print() semantics and typed intrinsics
+                if let Expression::Identifier(ident) = callee.value() {
+                    match is_builtin_function_name(ident.value()) {
+                        Some(BuiltinFn::Assert) => {
+                            self.check_builtin_assert(
+                                db, crate_id, file, index, callee, args, sink,
+                            );
+                            return;
+                        }
+                        Some(BuiltinFn::Panic) => {
+                            self.check_builtin_panic(db, file, callee, args, sink);
+                            return;
+                        }
+                        Some(BuiltinFn::DebugPrint) => {
+                            self.check_builtin_debug_print(
+                                db, crate_id, file, index, callee, args, sink,
+                            );
+                            return;
+                        }
+                        Some(BuiltinFn::IncludeBytes) => {
+                            self.check_builtin_include_bytes(
+                                db, file, index, expr_id, callee, args, sink,
+                            );
+                            return;
+                        }
+                        Some(BuiltinFn::Env) => {
+                            self.check_builtin_intrinsic_call(
+                                db,
+                                crate_id,
+                                file,
+                                index,
+                                ident.value(),
+                                callee,
+                                args,
+                                sink,
+                            );
+                            Self::check_env_index(db, file, args, sink);
+                            return;
+                        }
+                        Some(
+                            BuiltinFn::U32OverflowingAdd
+                            | BuiltinFn::U32CheckedSub
+                            | BuiltinFn::U32WrappingMul,
+                        ) => {
+                            self.check_builtin_intrinsic_call(
+                                db,
+                                crate_id,
+                                file,
+                                index,
+                                ident.value(),
+                                callee,
+                                args,
+                                sink,
+                            );
+                            return;
+                        }
+                        None => {}
+                    }
                 }
                 self.check_function_call_types(db, crate_id, file, index, callee, args, sink);
             }
@@ -390,6 +798,35 @@ impl TypeValidator {
                     }
                 }
             }
+            Expression::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.check_if_expression_types(
+                    db,
+                    crate_id,
+                    file,
+                    index,
+                    expr_id,
+                    condition,
+                    then_branch,
+                    else_branch,
+                    sink,
+                );
+            }
+            Expression::StringLiteral(_) => {
+                if !Self::is_abort_message(index, &expr_info.origin) {
+                    sink.push(
+                        Diagnostic::error(
+                            DiagnosticCode::TypeMismatch,
+                            "string literals are only allowed as `assert` and `panic` messages and `include_bytes` paths"
+                                .to_string(),
+                        )
+                        .with_location(file.file_path(db).to_string(), expr_info.ast_span),
+                    );
+                }
+            }
             // Literals, identifiers, and tuples don't need additional type validation
             // beyond what's already done in type_resolution.rs
             _ => {}
@@ -416,52 +853,75 @@ impl TypeValidator {
             return;
         };
 
-        // For commutative operators with literal operands, we need special handling
-        let is_commutative = matches!(
-            op,
-            BinaryOp::Add | BinaryOp::Mul | BinaryOp::Eq | BinaryOp::Neq
-        );
-
-        let (left_type, right_type) = if is_commutative {
-            // Check if operands are literals
-            let left_expr = &index.expression(left_id).unwrap().ast_node;
-            let right_expr = &index.expression(right_id).unwrap().ast_node;
-
-            let left_is_unsuffixed_literal = matches!(left_expr, Expression::Literal(_, None));
-            let right_is_unsuffixed_literal = matches!(right_expr, Expression::Literal(_, None));
-
-            if left_is_unsuffixed_literal && !right_is_unsuffixed_literal {
-                // Infer right first, then use it as context for left
-                let right_type = expression_semantic_type(db, crate_id, file, right_id, None);
-                let left_type =
-                    expression_semantic_type(db, crate_id, file, left_id, Some(right_type));
-                (left_type, right_type)
-            } else {
-                // Default: left first, then right with left as context
-                let left_type = expression_semantic_type(db, crate_id, file, left_id, None);
-                let right_type =
-                    expression_semantic_type(db, crate_id, file, right_id, Some(left_type));
-                (left_type, right_type)
-            }
-        } else {
-            // Non-commutative operators
-            let left_type = expression_semantic_type(db, crate_id, file, left_id, None);
-            let right_type =
-                expression_semantic_type(db, crate_id, file, right_id, Some(left_type));
-            (left_type, right_type)
-        };
+        // Operands whose type depends on their context are inferred from the other operand
+        // through their origin, in the same way as during type resolution.
+        let left_type = expression_semantic_type(db, crate_id, file, left_id, None);
+        let right_type = expression_semantic_type(db, crate_id, file, right_id, None);
 
         // Any un-resolved type must not trigger a type mismatch error here.
         if left_type.data(db) == TypeData::Error || right_type.data(db) == TypeData::Error {
             return;
         }
 
+        // Operators overloaded by a struct take the right operand as argument of its method
+        if let Some(signature) = operator_method(db, crate_id, op, left_type) {
+            let param_type = signature.params(db)[1].1;
+            if !are_types_compatible(db, right_type, param_type) {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "Invalid right operand for operator `{}`. Expected `{}`, found `{}`",
+                            op,
+                            param_type.data(db).display_name(db),
+                            right_type.data(db).display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), right.span()),
+                );
+            }
+            return;
+        }
+
+        // Mixing felt and u32 because of a literal suffix is reported on the literal, which
+        // is what needs fixing.
+        if left_type != right_type
+            && left_type.data(db).is_numeric()
+            && right_type.data(db).is_numeric()
+        {
+            let literal = match (left.value(), right.value()) {
+                (Expression::Literal(_, Some(_)), Expression::Literal(_, Some(_))) => None,
+                (Expression::Literal(_, Some(_)), _) => Some((left, left_type, right_type)),
+                (_, Expression::Literal(_, Some(_))) => Some((right, right_type, left_type)),
+                _ => None,
+            };
+            if let Some((literal, literal_type, other_type)) = literal {
+                self.report_mismatched_literal_operand(
+                    db,
+                    file,
+                    op,
+                    literal,
+                    literal_type,
+                    other_type,
+                    sink,
+                );
+                return;
+            }
+        }
+
         let op_signatures = get_binary_op_signatures(db);
         let mut binary_op_on_left_type = op_signatures
             .iter()
             .filter(|op_signature| op_signature.op == *op && op_signature.left == left_type);
         if binary_op_on_left_type.clone().count() == 0 {
-            let suggestion = self.suggest_type_conversion(db, left_type, right_type);
+            let suggestion = match (left_type.data(db), operator_method_name(op)) {
+                (TypeData::Struct(struct_type), Some(method)) => Some(format!(
+                    "Define `fn {method}(self, other: {right}) -> {name}` in `impl {name}` to overload `{op}`",
+                    name = struct_type.name(db),
+                    right = right_type.data(db).display_name(db),
+                )),
+                _ => self.suggest_type_conversion(db, left_type, right_type),
+            };
             let mut diag = Diagnostic::error(
                 DiagnosticCode::TypeMismatch,
                 format!(
@@ -501,6 +961,47 @@ impl TypeValidator {
         }
     }
 
+    /// Report a suffixed literal operand whose type differs from the other operand
+    #[allow(clippy::too_many_arguments)]
+    fn report_mismatched_literal_operand(
+        &self,
+        db: &dyn SemanticDb,
+        file: File,
+        op: &BinaryOp,
+        literal: &Spanned<Expression>,
+        literal_type: TypeId,
+        other_type: TypeId,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let Expression::Literal(value, Some(suffix)) = literal.value() else {
+            return;
+        };
+        let other_name = other_type.data(db).display_name(db);
+        let suggestion = match (literal_type.data(db), other_type.data(db)) {
+            (TypeData::U32, TypeData::Felt) => format!(
+                "Remove the `{suffix}` suffix, or cast the literal with `{value}{suffix} as felt`"
+            ),
+            _ => format!("Remove the `{suffix}` suffix, or write `{value}{other_name}`"),
+        };
+        sink.push(
+            Diagnostic::error(
+                DiagnosticCode::TypeMismatch,
+                format!(
+                    "Mismatched literal operand for operator `{}`. Expected `{}`, found `{}`",
+                    op,
+                    other_name,
+                    literal_type.data(db).display_name(db)
+                ),
+            )
+            .with_location(file.file_path(db).to_string(), literal.span())
+            .with_related_span(
+                file.file_path(db).to_string(),
+                literal.span(),
+                suggestion,
+            ),
+        );
+    }
+
     /// Validate unary operation type compatibility
     fn check_unary_op_types(
         &self,
@@ -561,7 +1062,10 @@ impl TypeValidator {
 
         match callee_type.data(db) {
             TypeData::Function(signature_id) => {
-                let params = signature_id.params(db);
+                // The receiver of a method call is its first argument
+                let receivers =
+                    usize::from(matches!(callee.value(), Expression::MemberAccess { .. }));
+                let params = signature_id.params(db).get(receivers..).unwrap_or_default();
 
                 // Check arity
                 if args.len() != params.len() {
@@ -599,7 +1103,10 @@ impl TypeValidator {
                             let func_def = index.definition(func_def_id.id_in_file(db)).unwrap();
                             let param_type_span =
                                 if let DefinitionKind::Function(func_ref) = &func_def.kind {
-                                    func_ref.params_ast.get(arg_idx).map(|p| p.1.span())
+                                    func_ref
+                                        .params_ast
+                                        .get(arg_idx + receivers)
+                                        .map(|p| p.1.span())
                                 } else {
                                     None
                                 };
@@ -685,6 +1192,66 @@ impl TypeValidator {
         }
     }
 
+    /// Validate that the result of a function call used as a statement is not discarded
+    ///
+    /// Discarding a non-unit result is a warning, or an error if the called function is
+    /// marked `#[must_use]`.
+    fn check_unused_result(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        expr: &Spanned<Expression>,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let Expression::FunctionCall { callee, .. } = expr.value() else {
+            return;
+        };
+        let Some(callee_expr_id) = index.expression_id_by_span(callee.span()) else {
+            return;
+        };
+        let TypeData::Function(signature_id) =
+            expression_semantic_type(db, crate_id, file, callee_expr_id, None).data(db)
+        else {
+            return;
+        };
+
+        let return_type = signature_id.return_type(db);
+        match return_type.data(db) {
+            TypeData::Tuple(types) if types.is_empty() => return,
+            TypeData::Error => return,
+            _ => {}
+        }
+
+        // The function may be defined in another module: look it up in its own index
+        let func_def_id = signature_id.definition_id(db);
+        let Some(func_def) = module_name_for_file(db, crate_id, func_def_id.file(db))
+            .and_then(|module_name| module_semantic_index(db, crate_id, module_name).ok())
+            .and_then(|func_index| func_index.definition(func_def_id.id_in_file(db)).cloned())
+        else {
+            return;
+        };
+        let must_use =
+            matches!(&func_def.kind, DefinitionKind::Function(func_ref) if func_ref.must_use);
+
+        let message = format!(
+            "Unused result of type `{}` returned by `{}`",
+            return_type.data(db).display_name(db),
+            func_def.name
+        );
+        let diag = if must_use {
+            Diagnostic::error(DiagnosticCode::UnusedResult, message).with_related_span(
+                func_def.file.file_path(db).to_string(),
+                func_def.name_span,
+                format!("`{}` is marked `#[must_use]`", func_def.name),
+            )
+        } else {
+            Diagnostic::warning(DiagnosticCode::UnusedResult, message)
+        };
+        sink.push(diag.with_location(file.file_path(db).to_string(), expr.span()));
+    }
+
     /// Validate member access types
     fn check_member_access_types(
         &self,
@@ -705,7 +1272,9 @@ impl TypeValidator {
         match object_type {
             TypeData::Struct(struct_type) => {
                 let fields = struct_type.fields(db);
-                if !fields.iter().any(|(name, _)| name == field.value()) {
+                if !fields.iter().any(|(name, _)| name == field.value())
+                    && struct_method(db, crate_id, struct_type, field.value()).is_none()
+                {
                     sink.push(
                         Diagnostic::error(
                             DiagnosticCode::InvalidFieldAccess,
@@ -1200,30 +1769,33 @@ impl TypeValidator {
             let target_type_id =
                 resolve_ast_type(db, crate_id, file, target_type.clone(), expr_info.scope_id);
 
-            // Check if the cast is valid
-            let is_valid = match (source_type.data(db), target_type_id.data(db)) {
-                // Allow u32 to felt casting
-                (TypeData::U32, TypeData::Felt) => true,
-                // All other casts are invalid
-                _ => false,
+            let source_data = source_type.data(db);
+            let target_data = target_type_id.data(db);
+            let is_primitive =
+                |data: &TypeData| matches!(data, TypeData::Felt | TypeData::U32 | TypeData::Bool);
+
+            // Casts are allowed between distinct primitive types: u32 to felt checks that the
+            // value fits in a felt, felt to u32 never fails, and casts to bool check that the
+            // value is 0 or 1.
+            let message = if source_data == target_data && is_primitive(&source_data) {
+                format!(
+                    "Unnecessary cast: expression is already of type '{}'",
+                    source_data.display_name(db)
+                )
+            } else if is_primitive(&source_data) && is_primitive(&target_data) {
+                return;
+            } else {
+                format!(
+                    "Invalid cast from '{}' to '{}'. Only casts between felt, u32 and bool are supported.",
+                    source_data.display_name(db),
+                    target_data.display_name(db)
+                )
             };
 
-            if !is_valid {
-                let source_name = source_type.data(db).display_name(db);
-                let target_name = target_type_id.data(db).display_name(db);
-
-                sink.push(
-                    Diagnostic::error(
-                        DiagnosticCode::TypeMismatch,
-                        format!(
-                            "Invalid cast from '{}' to '{}'. Only u32 to felt casting is currently supported.",
-                            source_name,
-                            target_name
-                        ),
-                    )
+            sink.push(
+                Diagnostic::error(DiagnosticCode::TypeMismatch, message)
                     .with_location(file.file_path(db).to_string(), expr.span()),
-                );
-            }
+            );
         }
     }
 
@@ -1295,6 +1867,10 @@ impl TypeValidator {
                 // Return type mismatches are handled by check_return_types
                 // with more detailed context, so we can skip here
             }
+            Origin::Operand { .. } => {
+                // Operand type mismatches are handled by check_binary_op_types and
+                // check_unary_op_types, so we can skip here
+            }
             Origin::Condition { kind } => {
                 sink.push(
                     Diagnostic::error(
@@ -1354,6 +1930,12 @@ impl TypeValidator {
         stmt: &Spanned<Statement>,
         sink: &dyn DiagnosticSink,
     ) {
+        for block in shared::expression_blocks(stmt.value()) {
+            for stmt in block {
+                self.check_statement_type(db, crate_id, file, index, function_def, stmt, sink);
+            }
+        }
+
         match stmt.value() {
             Statement::Let {
                 pattern,
@@ -1374,6 +1956,9 @@ impl TypeValidator {
             Statement::Assignment { lhs, rhs } => {
                 self.check_assignment_types(db, crate_id, file, index, lhs, rhs, sink);
             }
+            Statement::CompoundAssignment { lhs, op, rhs } => {
+                self.check_compound_assignment_types(db, crate_id, file, index, lhs, op, rhs, sink);
+            }
             Statement::Return { value } => {
                 self.check_return_types(
                     db,
@@ -1410,8 +1995,9 @@ impl TypeValidator {
                 }
             }
             Statement::Expression(expr) => {
-                // Expression statements are handled by check_expression_types
-                let _expr_id = index.expression_id_by_span(expr.span());
+                // Expression types are checked by check_expression_types, only the
+                // discarded value is checked here
+                self.check_unused_result(db, crate_id, file, index, expr, sink);
             }
             Statement::Const(const_def) => {
                 // Validate that the const value type matches the declared type
@@ -1601,55 +2187,15 @@ impl TypeValidator {
                 // Tuple pattern - check that RHS is a tuple with matching structure
                 match value_type.data(db) {
                     TypeData::Tuple(element_types) => {
-                        if element_types.len() != patterns.len() {
-                            sink.push(
-                                Diagnostic::error(
-                                    DiagnosticCode::TypeMismatch,
-                                    format!(
-                                        "Tuple pattern has {} elements but value has {} elements",
-                                        patterns.len(),
-                                        element_types.len()
-                                    ),
-                                )
-                                .with_location(file.file_path(db).to_string(), value.span()),
-                            );
-                        }
-
-                        // Recursively check nested patterns
-                        for (pattern, elem_type) in patterns.iter().zip(element_types.iter()) {
-                            if let Pattern::Tuple(nested_patterns) = pattern {
-                                // Check nested tuple pattern matches nested tuple type
-                                match elem_type.data(db) {
-                                    TypeData::Tuple(nested_types) => {
-                                        if nested_types.len() != nested_patterns.len() {
-                                            sink.push(
-                                                Diagnostic::error(
-                                                    DiagnosticCode::TypeMismatch,
-                                                    format!(
-                                                        "Nested tuple pattern has {} elements but value has {} elements",
-                                                        nested_patterns.len(),
-                                                        nested_types.len()
-                                                    ),
-                                                )
-                                                .with_location(file.file_path(db).to_string(), value.span()),
-                                            );
-                                        }
-                                    }
-                                    _ => {
-                                        sink.push(
-                                            Diagnostic::error(
-                                                DiagnosticCode::TypeMismatch,
-                                                format!(
-                                                    "Expected tuple type for nested tuple pattern, found `{}`",
-                                                    elem_type.data(db).display_name(db)
-                                                ),
-                                            )
-                                            .with_location(file.file_path(db).to_string(), value.span()),
-                                        );
-                                    }
-                                }
-                            }
-                        }
+                        Self::check_tuple_pattern_structure(
+                            db,
+                            file,
+                            patterns,
+                            &element_types,
+                            false,
+                            value,
+                            sink,
+                        );
 
                         // If a type annotation is provided, it should be a tuple type
                         if let Some(ty) = statement_type {
@@ -1691,6 +2237,66 @@ impl TypeValidator {
         }
     }
 
+    /// Check that a tuple pattern has the structure of the destructured tuple type, down to its
+    /// most nested patterns
+    fn check_tuple_pattern_structure(
+        db: &dyn SemanticDb,
+        file: File,
+        patterns: &[Pattern],
+        element_types: &[TypeId],
+        is_nested: bool,
+        value: &Spanned<Expression>,
+        sink: &dyn DiagnosticSink,
+    ) {
+        if element_types.len() != patterns.len() {
+            sink.push(
+                Diagnostic::error(
+                    DiagnosticCode::TypeMismatch,
+                    format!(
+                        "{} has {} elements but value has {} elements",
+                        if is_nested {
+                            "Nested tuple pattern"
+                        } else {
+                            "Tuple pattern"
+                        },
+                        patterns.len(),
+                        element_types.len()
+                    ),
+                )
+                .with_location(file.file_path(db).to_string(), value.span()),
+            );
+        }
+
+        for (pattern, elem_type) in patterns.iter().zip(element_types.iter()) {
+            let Pattern::Tuple(nested_patterns) = pattern else {
+                continue;
+            };
+            match elem_type.data(db) {
+                TypeData::Tuple(nested_types) => Self::check_tuple_pattern_structure(
+                    db,
+                    file,
+                    nested_patterns,
+                    &nested_types,
+                    true,
+                    value,
+                    sink,
+                ),
+                _ => {
+                    sink.push(
+                        Diagnostic::error(
+                            DiagnosticCode::TypeMismatch,
+                            format!(
+                                "Expected tuple type for nested tuple pattern, found `{}`",
+                                elem_type.data(db).display_name(db)
+                            ),
+                        )
+                        .with_location(file.file_path(db).to_string(), value.span()),
+                    );
+                }
+            }
+        }
+    }
+
     /// Check types for assignment statements
     fn check_assignment_types(
         &self,
@@ -1713,6 +2319,99 @@ impl TypeValidator {
         // Pass LHS type as context for RHS to support literal type inference
         let rhs_type = expression_semantic_type(db, crate_id, file, rhs_expr_id, Some(lhs_type));
 
+        if !self.check_assignment_target(db, crate_id, file, index, lhs, lhs_expr_id, sink) {
+            return;
+        }
+
+        // Check type compatibility
+        if !are_types_compatible(db, lhs_type, rhs_type) {
+            let error_message = format!(
+                "type mismatch in assignment: expected `{}`, got `{}`",
+                lhs_type.data(db).display_name(db),
+                rhs_type.data(db).display_name(db)
+            );
+
+            let mut diag = Diagnostic::error(DiagnosticCode::TypeMismatch, error_message)
+                .with_location(file.file_path(db).to_string(), rhs.span());
+
+            if let Some(suggestion) = self.suggest_type_conversion(db, rhs_type, lhs_type) {
+                diag =
+                    diag.with_related_span(file.file_path(db).to_string(), rhs.span(), suggestion);
+            }
+
+            diag = diag.with_related_span(
+                file.file_path(db).to_string(),
+                lhs.span(),
+                format!(
+                    "variable declared with type `{}`",
+                    lhs_type.data(db).display_name(db)
+                ),
+            );
+
+            sink.push(diag);
+        }
+    }
+
+    /// Check types for compound assignment statements (e.g. `x += 1;`)
+    ///
+    /// The operation must be defined on the target type, and its result is assigned back to
+    /// the target.
+    #[allow(clippy::too_many_arguments)]
+    fn check_compound_assignment_types(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        lhs: &Spanned<Expression>,
+        op: &BinaryOp,
+        rhs: &Spanned<Expression>,
+        sink: &dyn DiagnosticSink,
+    ) {
+        let Some(lhs_expr_id) = index.expression_id_by_span(lhs.span()) else {
+            return;
+        };
+        if !self.check_assignment_target(db, crate_id, file, index, lhs, lhs_expr_id, sink) {
+            return;
+        }
+        // `+`, `-` and `*` yield the type of their operands, which is the type of the target
+        self.check_binary_op_types(db, crate_id, file, index, lhs, op, rhs, sink);
+
+        // An overloaded operator must also return the type of the target
+        let lhs_type = expression_semantic_type(db, crate_id, file, lhs_expr_id, None);
+        if let Some(signature) = operator_method(db, crate_id, op, lhs_type) {
+            let return_type = signature.return_type(db);
+            if !are_types_compatible(db, return_type, lhs_type) {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "Operator `{}` returns `{}`, which cannot be assigned to `{}`",
+                            op,
+                            return_type.data(db).display_name(db),
+                            lhs_type.data(db).display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), lhs.span()),
+                );
+            }
+        }
+    }
+
+    /// Check that the left-hand side of an assignment can be assigned to.
+    ///
+    /// Returns `false` if a diagnostic was reported.
+    #[allow(clippy::too_many_arguments)]
+    fn check_assignment_target(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        lhs: &Spanned<Expression>,
+        lhs_expr_id: ExpressionId,
+        sink: &dyn DiagnosticSink,
+    ) -> bool {
         // Helper: detect if an lvalue ultimately refers to a const definition
         fn lvalue_resolves_to_const(
             db: &dyn SemanticDb,
@@ -1835,7 +2534,7 @@ impl TypeValidator {
                                     "const variable defined here".to_string(),
                                 ),
                             );
-                            return;
+                            return false;
                         }
                     }
                     // Try via imports as well, to catch imported consts
@@ -1862,7 +2561,7 @@ impl TypeValidator {
                                     "const variable defined here".to_string(),
                                 ),
                             );
-                            return;
+                            return false;
                         }
                     }
                 }
@@ -1886,7 +2585,7 @@ impl TypeValidator {
                             "const variable defined here".to_string(),
                         ),
                     );
-                    return;
+                    return false;
                 }
             }
             Expression::TupleIndex { .. } => {
@@ -1905,7 +2604,7 @@ impl TypeValidator {
                             "const variable defined here".to_string(),
                         ),
                     );
-                    return;
+                    return false;
                 }
             }
             Expression::IndexAccess { array: _, index: _ } => {
@@ -1924,7 +2623,7 @@ impl TypeValidator {
                             "const variable defined here".to_string(),
                         ),
                     );
-                    return;
+                    return false;
                 }
             }
             _ => {
@@ -1936,37 +2635,10 @@ impl TypeValidator {
                     )
                     .with_location(file.file_path(db).to_string(), lhs.span()),
                 );
-                return;
-            }
-        }
-
-        // Check type compatibility
-        if !are_types_compatible(db, lhs_type, rhs_type) {
-            let error_message = format!(
-                "type mismatch in assignment: expected `{}`, got `{}`",
-                lhs_type.data(db).display_name(db),
-                rhs_type.data(db).display_name(db)
-            );
-
-            let mut diag = Diagnostic::error(DiagnosticCode::TypeMismatch, error_message)
-                .with_location(file.file_path(db).to_string(), rhs.span());
-
-            if let Some(suggestion) = self.suggest_type_conversion(db, rhs_type, lhs_type) {
-                diag =
-                    diag.with_related_span(file.file_path(db).to_string(), rhs.span(), suggestion);
+                return false;
             }
-
-            diag = diag.with_related_span(
-                file.file_path(db).to_string(),
-                lhs.span(),
-                format!(
-                    "variable declared with type `{}`",
-                    lhs_type.data(db).display_name(db)
-                ),
-            );
-
-            sink.push(diag);
         }
+        true
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -2123,20 +2795,69 @@ impl TypeValidator {
         }
     }
 
+    /// Check types for if expressions: the condition is a bool, and both branches have the
+    /// type of the expression.
+    #[allow(clippy::too_many_arguments)]
+    fn check_if_expression_types(
+        &self,
+        db: &dyn SemanticDb,
+        crate_id: Crate,
+        file: File,
+        index: &SemanticIndex,
+        expr_id: ExpressionId,
+        condition: &Spanned<Expression>,
+        then_branch: &Spanned<Expression>,
+        else_branch: &Spanned<Expression>,
+        sink: &dyn DiagnosticSink,
+    ) {
+        if let Some(condition_expr_id) = index.expression_id_by_span(condition.span()) {
+            let bool_type = TypeId::new(db, TypeData::Bool);
+            let condition_type =
+                expression_semantic_type(db, crate_id, file, condition_expr_id, Some(bool_type));
+            if !are_types_compatible(db, condition_type, bool_type) {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "If condition must be of type 'bool', found `{}`",
+                            condition_type.data(db).display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), condition.span()),
+                );
+            }
+        }
+
+        let if_type = expression_semantic_type(db, crate_id, file, expr_id, None);
+        for branch in [then_branch, else_branch] {
+            let Some(branch_expr_id) = index.expression_id_by_span(branch.span()) else {
+                continue;
+            };
+            let branch_type =
+                expression_semantic_type(db, crate_id, file, branch_expr_id, Some(if_type));
+            if !are_types_compatible(db, branch_type, if_type) {
+                sink.push(
+                    Diagnostic::error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "`if` and `else` have incompatible types: expected `{}`, found `{}`",
+                            if_type.data(db).display_name(db),
+                            branch_type.data(db).display_name(db)
+                        ),
+                    )
+                    .with_location(file.file_path(db).to_string(), branch.span()),
+                );
+            }
+        }
+    }
+
     /// Locate a function definition by name in the parsed module.
     fn find_function_in_module<'a>(
         &self,
         parsed_module: &'a ParsedModule,
         function_name: &str,
     ) -> Option<&'a FunctionDef> {
-        for item in parsed_module.items() {
-            if let TopLevelItem::Function(func_spanned) = item {
-                if func_spanned.value().name.value() == function_name {
-                    return Some(func_spanned.value());
-                }
-            }
-        }
-        None
+        parsed_module.function(function_name).map(Spanned::value)
     }
 }
 
@@ -2145,7 +2866,7 @@ mod tests {
     use std::collections::HashMap;
     use std::path::PathBuf;
 
-    use cairo_m_compiler_diagnostics::DiagnosticCollection;
+    use cairo_m_compiler_diagnostics::{DiagnosticCollection, DiagnosticSeverity};
 
     use super::*;
     use crate::db::tests::test_db;
@@ -2535,4 +3256,86 @@ mod tests {
             "Should have 2 invalid target errors"
         );
     }
+
+    #[test]
+    fn test_unused_result_validation() {
+        let db = test_db();
+        let program = r#"
+            fn get() -> felt { return 1; }
+            #[must_use]
+            fn must_get() -> felt { return 2; }
+            fn nothing() { return; }
+            #[inline]
+            fn test() {
+                nothing();                  // OK: unit result
+                let x = get();              // OK: result is used
+                get();                      // Warning: unused result
+                if x == 1 {
+                    must_get();             // Error: unused must_use result
+                }
+                return;
+            }
+        "#;
+        let file = crate::File::new(&db, program.to_string(), "test.cm".to_string());
+        let crate_id = single_file_crate(&db, file);
+        let semantic_index = get_main_semantic_index(&db, crate_id);
+
+        let validator = TypeValidator;
+        let sink = cairo_m_compiler_diagnostics::VecSink::new();
+        validator.validate(&db, crate_id, file, &semantic_index, &sink);
+        let diagnostics = DiagnosticCollection::new(sink.into_diagnostics());
+
+        let unused_results: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::UnusedResult)
+            .collect();
+        assert_eq!(unused_results.len(), 2, "Should have 2 unused results");
+        assert_eq!(unused_results[0].severity, DiagnosticSeverity::Warning);
+        assert!(unused_results[0].message.contains("`get`"));
+        assert_eq!(unused_results[1].severity, DiagnosticSeverity::Error);
+        assert!(unused_results[1].message.contains("`must_get`"));
+
+        let unknown_attributes: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::UnknownAttribute)
+            .collect();
+        assert_eq!(
+            unknown_attributes.len(),
+            1,
+            "Should have 1 unknown attribute"
+        );
+        assert!(unknown_attributes[0].message.contains("`inline`"));
+    }
+
+    #[test]
+    fn test_parameter_default_validation() {
+        let db = test_db();
+        let program = r#"
+            fn ok(a: felt, b: felt = 3, c: u32 = 7u32, d: bool = true) { return; }
+            fn not_trailing(a: felt = 1, b: felt) { return; }
+            fn wrong_type(a: bool = 1, b: felt = false) { return; }
+            fn not_literal(a: felt = 1 + 2) { return; }
+            fn out_of_range(a: felt = 2147483648) { return; }
+        "#;
+        let file = crate::File::new(&db, program.to_string(), "test.cm".to_string());
+        let crate_id = single_file_crate(&db, file);
+        let semantic_index = get_main_semantic_index(&db, crate_id);
+
+        let validator = TypeValidator;
+        let sink = cairo_m_compiler_diagnostics::VecSink::new();
+        validator.validate(&db, crate_id, file, &semantic_index, &sink);
+        let diagnostics = DiagnosticCollection::new(sink.into_diagnostics());
+
+        let invalid_defaults: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == DiagnosticCode::InvalidParameterDefault)
+            .collect();
+        assert_eq!(
+            invalid_defaults.len(),
+            5,
+            "Should have 5 invalid defaults: {invalid_defaults:?}"
+        );
+        assert!(invalid_defaults[0].message.contains("'b' without default"));
+        assert!(invalid_defaults[4].message.contains("out of range"));
+    }
 }
@@ -154,12 +154,13 @@ impl ValidatorRegistry {
 /// - **TypeValidator**: Comprehensive type checking for all expressions and operations
 /// - **ControlFlowValidator**: Reachability analysis, dead code detection, break/continue validation
 /// - **LiteralValidator**: Range checking for bounded types (e.g., u16)
+/// - **TerminationValidator**: Unbounded recursion and non-terminating loops
+/// - **ConstValidator**: Cyclic constants and array sizes that are not integer constants
 ///
 /// TODO: Expand default registry with additional validators:
 /// - **AssignmentValidator**: Validate assignment compatibility and mutability
 /// - **ReturnValidator**: Validate return type consistency and placement
 /// - **RecursiveTypeValidator**: Detect recursive struct definitions without indirection
-/// - **ConstExpressionValidator**: Validate const expressions are compile-time evaluable
 /// - **ModuleValidator**: Import/export validation, module resolution
 /// - **StyleValidator**: Code style and best practices
 /// - **SecurityValidator**: Security-related checks
@@ -171,6 +172,8 @@ pub(crate) fn create_default_registry() -> ValidatorRegistry {
         .add_validator(crate::validation::structural_validator::StructuralValidator)
         .add_validator(crate::validation::control_flow_validator::ControlFlowValidator)
         .add_validator(crate::validation::literal_validator::LiteralValidator)
+        .add_validator(crate::validation::termination_validator::TerminationValidator)
+        .add_validator(crate::validation::const_validator::ConstValidator)
 }
 
 #[cfg(test)]
@@ -29,8 +29,8 @@
 //! ```
 
 use cairo_m_compiler_parser::parser::{
-    ConstDef, Expression, FunctionDef, Parameter, Spanned, Statement, StructDef, TopLevelItem,
-    TypeExpr, UseStmt,
+    ConstDef, Expression, FunctionDef, ImplDef, Parameter, Spanned, Statement, StructDef,
+    TopLevelItem, TypeExpr, UseStmt,
 };
 
 /// Core visitor trait for AST traversal.
@@ -77,6 +77,13 @@ pub trait Visitor<'ast> {
     /// Visit a use statement
     fn visit_use(&mut self, use_stmt: &'ast Spanned<UseStmt>);
 
+    /// Visit an impl block, whose methods are functions
+    fn visit_impl(&mut self, impl_def: &'ast Spanned<ImplDef>) {
+        for method in &impl_def.value().methods {
+            self.visit_function(method);
+        }
+    }
+
     /// Visit a function body (list of statements)
     fn visit_body(&mut self, stmts: &'ast [Spanned<Statement>]) {
         for stmt in stmts {
@@ -96,6 +103,7 @@ fn walk_top_level_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &
         TopLevelItem::Struct(struct_def) => visitor.visit_struct(struct_def),
         TopLevelItem::Const(const_def) => visitor.visit_const(const_def),
         TopLevelItem::Use(use_stmt) => visitor.visit_use(use_stmt),
+        TopLevelItem::Impl(impl_def) => visitor.visit_impl(impl_def),
     }
 }
 
@@ -25,7 +25,7 @@ fn test_array_type_declarations() {
                 in_function("let arr: [[felt; 3]; 2] = [[1, 2, 3], [4, 5, 6]];"),
 
             // Invalid size (must be compile-time constant)
-            in_function("let n = 5; let arr: [felt; n];"),
+            in_function("let n = 5; let arr: [felt; n] = [1, 2, 3, 4, 5];"),
         ]
     }
 }
@@ -189,7 +189,7 @@ pub(crate) fn assert_semantic_ok_impl_with_options(
     let filtered_diagnostics = if show_unused_warnings {
         diagnostics
     } else {
-        filter_unused_variable_warnings(&diagnostics)
+        filter_incidental_warnings(&diagnostics)
     };
 
     if !filtered_diagnostics.is_empty() {
@@ -217,7 +217,7 @@ pub(crate) fn assert_semantic_err_impl_with_options(
     let filtered_diagnostics = if show_unused_warnings {
         diagnostics
     } else {
-        filter_unused_variable_warnings(&diagnostics)
+        filter_incidental_warnings(&diagnostics)
     };
 
     if filtered_diagnostics.is_empty() {
@@ -245,14 +245,22 @@ pub(crate) fn in_function(code: &str) -> String {
     format!("fn test() {{ {code} return; }}")
 }
 
-/// Filter out unused variable warnings from diagnostics
-pub(crate) fn filter_unused_variable_warnings(
+/// Filter out unused variable, dead branch and non-terminating loop warnings from
+/// diagnostics, which many test snippets trigger incidentally
+pub(crate) fn filter_incidental_warnings(
     diagnostics: &DiagnosticCollection,
 ) -> DiagnosticCollection {
     let filtered: Vec<_> = diagnostics
         .all()
         .iter()
-        .filter(|d| d.code != DiagnosticCode::UnusedVariable)
+        .filter(|d| {
+            !matches!(
+                d.code,
+                DiagnosticCode::UnusedVariable
+                    | DiagnosticCode::DeadBranch
+                    | DiagnosticCode::NonTerminatingLoop
+            )
+        })
         .cloned()
         .collect();
     DiagnosticCollection::from(filtered)
@@ -354,7 +362,7 @@ pub(crate) fn assert_semantic_parameterized_impl(
         let mut filtered_diagnostics = if show_unused_warnings {
             diagnostics
         } else {
-            filter_unused_variable_warnings(&diagnostics)
+            filter_incidental_warnings(&diagnostics)
         };
 
         if *should_succeed {
@@ -0,0 +1,56 @@
+//! Tests for dead branch detection.
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+
+use crate::*;
+
+/// Returns the message and source text of each dead branch warning.
+fn dead_branches(code: &str) -> Vec<(String, String)> {
+    run_validation(code, "dead_branches")
+        .all()
+        .iter()
+        .filter(|d| d.code == DiagnosticCode::DeadBranch)
+        .map(|d| (d.message.clone(), code[d.span.into_range()].to_string()))
+        .collect()
+}
+
+#[test]
+fn test_dead_branch_detection() {
+    assert_eq!(
+        dead_branches(&in_function("if false { let x = 1; }")),
+        [(
+            "Dead branch: the condition is always false".to_string(),
+            "{ let x = 1; }".to_string()
+        )]
+    );
+    assert_eq!(
+        dead_branches(&in_function(
+            "if (1 == 1) && !false { let x = 1; } else { let y = 2; }"
+        )),
+        [(
+            "Dead branch: the condition is always true".to_string(),
+            "{ let y = 2; }".to_string()
+        )]
+    );
+    // Nested constant conditions are reported independently
+    assert_eq!(
+        dead_branches(&in_function(
+            "if 1u32 != 1u32 { let x = 1; } else { if true { let y = 2; } }"
+        ))
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn test_no_dead_branch() {
+    // A constant true condition without else has no dead branch
+    assert!(dead_branches(&in_function("if true { let x = 1; }")).is_empty());
+    // Conditions depending on runtime values
+    assert!(dead_branches("fn test(c: bool) { if c { return; } return; }").is_empty());
+    assert!(
+        dead_branches("fn test(c: bool) { if false && c { return; } return; }").is_empty()
+    );
+    assert!(
+        dead_branches("fn test(x: felt) { if x == 1 { return; } else { return; } }").is_empty()
+    );
+}
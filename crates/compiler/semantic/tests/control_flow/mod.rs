@@ -2,6 +2,8 @@
 //!
 //! Tests for control flow analysis including:
 //! - Unreachable code detection
+//! - Dead branch detection
+//! - Unbounded recursion and non-terminating loop detection
 //! - Missing return statement detection
 //! - Control flow path analysis
 //! - Dead code elimination validation
@@ -9,9 +11,11 @@
 //! - Loop scoping
 //! - Loop type checking
 
+pub mod dead_branches;
 pub mod for_loops;
 pub mod loop_scoping;
 pub mod loop_type_checking;
 pub mod loop_validation;
 pub mod missing_returns;
+pub mod termination;
 pub mod unreachable_code;
@@ -0,0 +1,127 @@
+//! Tests for unbounded recursion and non-terminating loop detection.
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+
+use crate::*;
+
+/// Returns the source text of each warning with the given code.
+fn warnings(code: &str, diagnostic_code: DiagnosticCode) -> Vec<String> {
+    run_validation(code, "termination")
+        .all()
+        .iter()
+        .filter(|d| d.code == diagnostic_code)
+        .map(|d| code[d.span.into_range()].to_string())
+        .collect()
+}
+
+fn unbounded_recursions(code: &str) -> Vec<String> {
+    warnings(code, DiagnosticCode::UnboundedRecursion)
+}
+
+fn non_terminating_loops(code: &str) -> Vec<String> {
+    warnings(code, DiagnosticCode::NonTerminatingLoop)
+}
+
+#[test]
+fn test_unbounded_recursion_detection() {
+    assert_eq!(
+        unbounded_recursions("fn f(n: felt) -> felt { if n == 0 { return 0; } return f(n); }"),
+        ["f(n)"]
+    );
+    assert_eq!(unbounded_recursions("fn f() { f(); return; }"), ["f()"]);
+    // Mutual recursion
+    assert_eq!(
+        unbounded_recursions(
+            "fn is_even(n: felt) -> bool { if n == 0 { return true; } return is_odd(n); }
+             fn is_odd(n: felt) -> bool { if n == 0 { return false; } return is_even(n); }"
+        ),
+        ["is_odd(n)", "is_even(n)"]
+    );
+}
+
+#[test]
+fn test_no_unbounded_recursion() {
+    // A decreasing argument
+    assert!(
+        unbounded_recursions("fn f(n: felt) -> felt { if n == 0 { return 0; } return f(n - 1); }")
+            .is_empty()
+    );
+    // An argument changed before the call, or shadowed
+    assert!(
+        unbounded_recursions(
+            "fn f(n: felt) -> felt { if n == 0 { return 0; } n = n - 1; return f(n); }"
+        )
+        .is_empty()
+    );
+    assert!(
+        unbounded_recursions(
+            "fn f(n: felt) -> felt { if n == 0 { return 0; } let n = n - 1; return f(n); }"
+        )
+        .is_empty()
+    );
+    // Swapped arguments
+    assert!(
+        unbounded_recursions(
+            "fn f(a: felt, b: felt) -> felt { if a == b { return 0; } return f(b, a); }"
+        )
+        .is_empty()
+    );
+    // Memory behind a pointer can change
+    assert!(
+        unbounded_recursions(
+            "fn f(p: felt*) { if p[0] == 0 { return; } g(p); f(p); return; }
+             fn g(p: felt*) { p[0] = p[0] - 1; return; }"
+        )
+        .is_empty()
+    );
+    // One of the calls of the cycle makes progress
+    assert!(
+        unbounded_recursions(
+            "fn is_even(n: felt) -> bool { if n == 0 { return true; } return is_odd(n); }
+             fn is_odd(n: felt) -> bool { if n == 0 { return false; } return is_even(n - 1); }"
+        )
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_non_terminating_loop_detection() {
+    assert_eq!(
+        non_terminating_loops(&in_function("loop { let x = 1; }")),
+        ["loop { let x = 1; }"]
+    );
+    assert_eq!(
+        non_terminating_loops("fn test(x: felt) { while x != 10 { let y = x + 1; } return; }"),
+        ["while x != 10 { let y = x + 1; }"]
+    );
+    // The break of a nested loop does not exit the outer loop
+    assert_eq!(
+        non_terminating_loops(&in_function("loop { loop { break; } }")),
+        ["loop { loop { break; } }"]
+    );
+}
+
+#[test]
+fn test_no_non_terminating_loop() {
+    assert!(non_terminating_loops(&in_function("loop { break; }")).is_empty());
+    assert!(non_terminating_loops("fn test() -> felt { loop { return 1; } }").is_empty());
+    assert!(
+        non_terminating_loops(&in_function("let x = 0; while x != 10 { x = x + 1; }")).is_empty()
+    );
+    // A call may change the memory read by the condition
+    assert!(
+        non_terminating_loops(
+            "fn step(p: felt*) { p[0] = p[0] + 1; return; }
+             fn test(p: felt*) { while p[0] != 10 { step(p); } return; }"
+        )
+        .is_empty()
+    );
+    // Never entered
+    assert!(non_terminating_loops(&in_function("while false { let x = 1; }")).is_empty());
+    // For loops change their state in their step
+    assert!(
+        non_terminating_loops(&in_function(
+            "for (let i = 0; i != 3; i = i + 1) { let x = i; }"
+        ))
+        .is_empty()
+    );
+}
@@ -6,6 +6,14 @@ fn test_casts() {
         ok: [
             // u32 -> felt
             in_function("let x: u32 = 10; let y: felt = x as felt;"),
+            // felt -> u32
+            in_function("let x: felt = 10; let y: u32 = x as u32;"),
+            // bool -> felt, bool -> u32
+            in_function("let x: bool = true; let y: felt = x as felt;"),
+            in_function("let x: bool = true; let y: u32 = x as u32;"),
+            // felt -> bool, u32 -> bool
+            in_function("let x: felt = 1; let y: bool = x as bool;"),
+            in_function("let x: u32 = 1; let y: bool = x as bool;"),
             ],
             err: [
             // Identity casts
@@ -13,11 +21,6 @@ fn test_casts() {
             in_function("let x: u32 = 10; let y: u32 = x as u32;"),
             in_function("let x: bool = true; let y: bool = x as bool;"),
 
-            // non-identity casts
-            in_function("let x: felt = 10; let y: u32 = x as u32;"),
-            in_function("let x: bool = true; let y: felt = x as felt;"),
-            in_function("let x: felt = 10; let y: bool = x as bool;"),
-
             // Custom type
             format!("struct Point {{ x: felt, y: felt }} {}", in_function("let val = 3; let res = val as Point;")),
             in_function("let val = 3; let res = val as (felt, felt);"),
@@ -0,0 +1,46 @@
+//! Tests for block expressions and if expressions
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+
+use crate::*;
+
+/// Returns the message of each type mismatch diagnostic.
+fn type_mismatches(code: &str) -> Vec<String> {
+    run_validation(code, "if_expressions")
+        .all()
+        .iter()
+        .filter(|d| d.code == DiagnosticCode::TypeMismatch)
+        .map(|d| d.message.clone())
+        .collect()
+}
+
+#[test]
+fn test_if_and_block_expressions() {
+    assert_semantic_ok!(
+        "fn abs(x: felt, negative: bool) -> felt {
+             let y = if negative { -x } else { x };
+             return y;
+         }
+         fn clamp(x: u32) -> u32 {
+             return if x > 10 { 10 } else if x < 2 { 2 } else { x };
+         }
+         fn main() -> u32 {
+             let doubled: u32 = { let t = clamp(4); t * 2 };
+             let _a = abs(3, doubled == 8);
+             return doubled;
+         }"
+    );
+}
+
+#[test]
+fn test_if_expression_type_errors() {
+    assert_eq!(
+        type_mismatches("fn main(n: felt) -> felt { let x = if n { 1 } else { 2 }; return x; }"),
+        ["If condition must be of type 'bool', found `felt`"]
+    );
+    assert_eq!(
+        type_mismatches(
+            "fn main(a: u32, c: bool) -> u32 { let x = if c { a } else { true }; return x; }"
+        ),
+        ["`if` and `else` have incompatible types: expected `u32`, found `bool`"]
+    );
+}
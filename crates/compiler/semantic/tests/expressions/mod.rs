@@ -5,6 +5,7 @@
 pub mod type_errors;
 
 pub mod binary_expressions;
+pub mod if_expressions;
 pub mod pointer_expr;
 pub mod tuple_index;
 pub mod unary_expressions;
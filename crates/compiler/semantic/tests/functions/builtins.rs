@@ -39,3 +39,22 @@ fn test_assert_built_in_parameterized() {
         ]
     }
 }
+
+#[test]
+fn test_debug_print_built_in_parameterized() {
+    assert_semantic_parameterized! {
+        ok: [
+            in_function("let x = 3; debug_print(x);"),
+            in_function("let x: u32 = 3u32; debug_print(x);"),
+            in_function("let b = true; debug_print(b);"),
+            in_function("debug_print(1 + 2);"),
+        ],
+        err: [
+            // Wrong arity
+            in_function("debug_print(1, 2);"),
+
+            // Aggregate types not supported
+            in_function("let a: [u32; 2] = [1u32, 2u32]; debug_print(a);"),
+        ]
+    }
+}
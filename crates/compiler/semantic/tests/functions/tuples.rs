@@ -9,12 +9,14 @@ fn test_tuple_destructuring() {
             in_function("let (a, b): (felt, felt) = (1, 2); let (c, d): (felt, felt) = (3, 4);"),
             "fn returns_tuple() -> (felt, felt) { return (100, 200); } fn test() -> felt { let (a, b) = returns_tuple(); return a + b; }",
             in_function("let (x, y) = (1, 2); { let (x, y) = (10, 20); let sum = x + y; } let sum = x + y;"),
+            "fn nested() -> (felt, (u32, (felt, felt))) { return (1, (2, (3, 4))); } fn test() -> felt { let (a, (b, (c, d))) = nested(); return a + c + d; }",
         ],
         err: [
             in_function("let (x, y) = 42;"), // Error: Cannot destructure non-tuple
             in_function("let (x, y) = (1, 2, 3);"), // Error: Pattern has 2 elements but value has 3
             in_function("let (x, y): felt = (1, 2);"), // Error: Expected felt, found tuple
             in_function("let (x, x) = (1, 2);"), // Error: Duplicate pattern identifier
+            in_function("let (a, (b, (c, d))) = (1, (2, (3, 4, 5)));"), // Error: Nested pattern has 2 elements but value has 3
             ]
     }
 }
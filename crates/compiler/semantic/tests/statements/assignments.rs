@@ -1,5 +1,7 @@
 //! Tests for assignment validation and type checking.
-use crate::{assert_semantic_parameterized, in_function};
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+
+use crate::{assert_semantic_ok, assert_semantic_parameterized, in_function, run_validation};
 
 #[test]
 fn test_assignments() {
@@ -124,3 +126,53 @@ fn test_const_assignment() {
         ]
     }
 }
+
+/// Returns the message of each error diagnostic with the given code.
+fn diagnostics(code: &str, diagnostic_code: DiagnosticCode) -> Vec<String> {
+    run_validation(code, "assignments")
+        .all()
+        .iter()
+        .filter(|d| d.code == diagnostic_code)
+        .map(|d| d.message.clone())
+        .collect()
+}
+
+#[test]
+fn test_compound_assignments() {
+    assert_semantic_ok!(
+        "struct Point { x: u32, y: u32 }
+         fn main() -> u32 {
+             let x: u32 = 1;
+             x += 2;
+             x -= 1;
+             x *= 3;
+             let p = Point { x: 1, y: 2 };
+             p.x += x;
+             let arr: [felt; 2] = [1, 2];
+             arr[0] *= 2;
+             return p.x;
+         }"
+    );
+
+    assert_eq!(
+        diagnostics(
+            &in_function("let b = true; b += true;"),
+            DiagnosticCode::TypeMismatch
+        ),
+        ["Operator `+` is not supported for type `bool`"]
+    );
+    assert_eq!(
+        diagnostics(
+            &in_function("let x: u32 = 1; let y: felt = 2; x -= y;"),
+            DiagnosticCode::TypeMismatch
+        ),
+        ["Invalid right operand for arithmetic operator `-`. Expected `u32`, found `felt`"]
+    );
+    assert_eq!(
+        diagnostics(
+            "const C: felt = 1; fn main() { C *= 2; return; }",
+            DiagnosticCode::AssignmentToConst
+        ),
+        ["cannot assign to const variable `C`"]
+    );
+}
@@ -0,0 +1,151 @@
+//! Tests for the evaluation of constants defined from other constants.
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+use cairo_m_compiler_semantic::DefinitionKind;
+use cairo_m_compiler_semantic::const_eval::{ConstValue, module_const_values};
+
+use crate::*;
+
+/// Returns the folded value of each constant of a program, by name.
+fn const_values(program: &str) -> Vec<(String, Option<ConstValue>)> {
+    let db = test_db();
+    let crate_id = crate_from_program(&db, program);
+    let file = crate_id.modules(&db)["main"];
+    let index = get_main_semantic_index(&db, crate_id);
+    let values = module_const_values(&db, crate_id, file);
+    index
+        .all_definitions()
+        .filter(|(_, def)| matches!(def.kind, DefinitionKind::Const(_)))
+        .map(|(def_idx, def)| (def.name.clone(), values.value(def_idx)))
+        .collect()
+}
+
+/// Returns the message of each diagnostic with the given code.
+fn diagnostics(code: &str, diagnostic_code: DiagnosticCode) -> Vec<String> {
+    run_validation(code, "const_expressions")
+        .all()
+        .iter()
+        .filter(|d| d.code == diagnostic_code)
+        .map(|d| d.message.clone())
+        .collect()
+}
+
+#[test]
+fn test_const_values_are_folded() {
+    let values = const_values(
+        "const B = A * 2 + SIZE;
+         const A = 5;
+         const SIZE = 3;
+         const MASK: u32 = 3;
+         const C: u32 = MASK << 2;
+         const D = -A;
+         const E = A / 2;
+         const F = MASK > 2 && true;",
+    );
+    assert_eq!(
+        values,
+        [
+            ("B".to_string(), Some(ConstValue::Felt(13))),
+            ("A".to_string(), Some(ConstValue::Felt(5))),
+            ("SIZE".to_string(), Some(ConstValue::Felt(3))),
+            ("MASK".to_string(), Some(ConstValue::U32(3))),
+            ("C".to_string(), Some(ConstValue::U32(12))),
+            ("D".to_string(), Some(ConstValue::Felt((1 << 31) - 1 - 5))),
+            ("E".to_string(), Some(ConstValue::Felt((1 << 30) + 2))),
+            ("F".to_string(), Some(ConstValue::Bool(true))),
+        ]
+    );
+
+    // Unsuffixed literals take the type of the other operand, and operands of different
+    // types are not folded
+    assert_eq!(
+        const_values("const A: u32 = 5; const B = A * 2 + 1; const C = A + 1felt;"),
+        [
+            ("A".to_string(), Some(ConstValue::U32(5))),
+            ("B".to_string(), Some(ConstValue::U32(11))),
+            ("C".to_string(), None),
+        ]
+    );
+
+    // Constants that are not scalars are not folded
+    assert_eq!(
+        const_values("const A: [felt; 2] = [1, 2];"),
+        [("A".to_string(), None)]
+    );
+}
+
+#[test]
+fn test_const_forward_references() {
+    assert_semantic_ok!(
+        "const B = A * 2 + SIZE;
+         const A = 5;
+         const SIZE = 3;
+         fn main() -> felt { return B + LATER; }
+         const LATER = 1;"
+    );
+}
+
+#[test]
+fn test_cyclic_const_definitions() {
+    assert_eq!(
+        diagnostics(
+            "const A = B + 1; const B = A * 2; fn main() -> felt { return A; }",
+            DiagnosticCode::CyclicConstDefinition
+        ),
+        ["Cyclic constant definition: A -> B -> A"]
+    );
+    assert_eq!(
+        diagnostics("const A = A;", DiagnosticCode::CyclicConstDefinition),
+        ["Cyclic constant definition: A -> A"]
+    );
+    // Cycles through values that are not folded are reported as well
+    assert_eq!(
+        diagnostics(
+            "const A = [B, 1]; const B = A[0];",
+            DiagnosticCode::CyclicConstDefinition
+        ),
+        ["Cyclic constant definition: A -> B -> A"]
+    );
+    assert_eq!(
+        const_values("const A = B; const B = A; const C = 1;"),
+        [
+            ("A".to_string(), None),
+            ("B".to_string(), None),
+            ("C".to_string(), Some(ConstValue::Felt(1))),
+        ]
+    );
+}
+
+#[test]
+fn test_const_array_sizes() {
+    assert_semantic_ok!(
+        "const N = 2 * SIZE;
+         const SIZE: u32 = 2;
+         struct Buffer { data: [felt; N] }
+         fn first(values: [felt; N]) -> felt { return values[3]; }
+         fn main() -> felt { let values: [felt; SIZE] = [1, 2]; return values[1]; }
+         fn zeros() -> [felt; N] { return [0; N]; }
+         fn ones() -> [u32; 4] { let ones: [u32; N] = [1; N]; return ones; }"
+    );
+
+    assert_eq!(
+        diagnostics(
+            "const FLAG = true; fn main() { let _a: [felt; FLAG] = [1]; return; }",
+            DiagnosticCode::InvalidArraySize
+        ),
+        ["Array size 'FLAG' is not an integer constant"]
+    );
+    assert_eq!(
+        diagnostics(
+            "fn main() { let n = 2; let _a: [felt; n] = [1, 2]; return; }",
+            DiagnosticCode::InvalidArraySize
+        ),
+        ["Array size 'n' is not an integer constant"]
+    );
+    assert_eq!(
+        diagnostics(
+            "fn main() { let n = 2; let _a = [0; n]; return; }",
+            DiagnosticCode::InvalidArraySize
+        ),
+        ["Array size 'n' is not an integer constant"]
+    );
+}
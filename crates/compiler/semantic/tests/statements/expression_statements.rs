@@ -11,8 +11,8 @@ fn test_expression_statements() {
             "fn helper() {return;} fn test() { { helper(); } return (); }",
             "fn helper() {return;} fn test() { if true { helper(); } else { helper(); } return (); }",
             "fn process(x: felt) {return;} fn test() { let a = 1; process(a + 2); return (); }",
-            // Return value ignored is OK
-            "fn get_value() -> felt { return 42; } fn test() { get_value(); return (); }",
+            // Return value explicitly discarded
+            "fn get_value() -> felt { return 42; } fn test() { let _ = get_value(); return (); }",
             // Recursive call as statement
             "fn recursive(n: u32) { if n > 0 { recursive(n - 1); } return (); } fn test() { recursive(5); return (); }",
         ],
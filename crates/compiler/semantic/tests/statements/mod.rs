@@ -3,5 +3,6 @@
 //! Tests for statement-level validation, including let statements and assignments.
 
 pub mod assignments;
+pub mod const_expressions;
 pub mod expression_statements;
 pub mod let_local_statements;
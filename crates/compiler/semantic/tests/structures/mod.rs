@@ -1,6 +1,8 @@
 //! # Struct Validation Tests
 //!
-//! Tests for struct definition, instantiation (literals), and member access.
+//! Tests for struct definition, instantiation (literals), member access and overloaded
+//! operators.
 
 pub mod literals;
 pub mod member_access;
+pub mod operators;
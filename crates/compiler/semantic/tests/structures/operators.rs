@@ -0,0 +1,98 @@
+//! Tests for operators overloaded by struct methods.
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+
+use crate::run_validation;
+
+const COMPLEX: &str = r#"
+    struct Complex { re: felt, im: felt }
+    impl Complex {
+        fn add(self, other: Complex) -> Complex {
+            return Complex { re: self.re + other.re, im: self.im + other.im };
+        }
+        fn mul(self, k: felt) -> Complex {
+            return Complex { re: self.re * k, im: self.im * k };
+        }
+    }
+"#;
+
+fn errors(code: &str) -> Vec<(DiagnosticCode, String)> {
+    let source = format!("{COMPLEX}\n{code}");
+    run_validation(&source, "operators.cm")
+        .errors()
+        .into_iter()
+        .map(|diag| (diag.code, diag.message.clone()))
+        .collect()
+}
+
+#[test]
+fn test_overloaded_operators() {
+    let errors = errors(
+        r#"
+        fn test(a: Complex, b: Complex) -> Complex {
+            let c: Complex = a + b;
+            let d = c * 2;
+            d += a;
+            return d;
+        }
+    "#,
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn test_overloaded_operator_invalid_operand() {
+    let errors = errors(
+        r#"
+        fn test(a: Complex, b: Complex) -> Complex {
+            return a * b;
+        }
+    "#,
+    );
+    assert_eq!(
+        errors,
+        [(
+            DiagnosticCode::TypeMismatch,
+            "Invalid right operand for operator `*`. Expected `felt`, found `Complex`".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_operator_not_overloaded() {
+    let errors = errors(
+        r#"
+        fn test(a: Complex, b: Complex) -> Complex {
+            return a - b;
+        }
+    "#,
+    );
+    assert_eq!(
+        errors,
+        [(
+            DiagnosticCode::TypeMismatch,
+            "Operator `-` is not supported for type `Complex`".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_operator_method_signature() {
+    let errors = errors(
+        r#"
+        struct Vec2 { x: felt, y: felt }
+        impl Vec2 {
+            fn sub(self) -> Vec2 {
+                return self;
+            }
+        }
+    "#,
+    );
+    assert_eq!(
+        errors,
+        [(
+            DiagnosticCode::InvalidImpl,
+            "Operator method `sub` must take `self` and the right operand as parameters"
+                .to_string()
+        )]
+    );
+}
@@ -3,15 +3,23 @@
 #![recursion_limit = "512"]
 
 pub mod db;
-use std::collections::HashMap;
+pub mod watch;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use cairo_m_common::Program;
-use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticSeverity, build_diagnostic_message};
+use cairo_m_compiler_diagnostics::{
+    Diagnostic, DiagnosticCode, DiagnosticSeverity, build_diagnostic_message,
+};
 use cairo_m_compiler_mir::pipeline::{OptimizationLevel, PipelineConfig};
+use cairo_m_compiler_mir::{MirModule, MirPass, PassKind};
 use cairo_m_compiler_parser::{SourceFile, parse_file};
 use cairo_m_compiler_semantic::Crate as SemanticCrate;
-use cairo_m_compiler_semantic::db::{crate_from_project, project_validate_semantics};
+use cairo_m_compiler_semantic::db::{
+    crate_from_project, crate_from_workspace_member, project_validate_semantics,
+};
+pub use cairo_m_project::LintLevel;
+use cairo_m_project::ProfileManifest;
 use db::CompilerDatabase;
 use thiserror::Error;
 
@@ -27,37 +35,84 @@ pub enum CompilerError {
     /// Semantic validation errors occurred
     #[error("Semantic errors: {count} errors found", count = .0.len())]
     SemanticErrors(Vec<Diagnostic>),
+    /// Workspace members depend on each other in a cycle, given by the member names
+    #[error("Dependency cycle between workspace members: {}", .0.join(" -> "))]
+    DependencyCycle(Vec<String>),
     /// MIR generation failed
     #[error("Failed to generate MIR")]
     MirGenerationFailed,
     /// Code generation failed
     #[error("Code generation failed: {0}")]
     CodeGenerationFailed(String),
+    /// A lint of the manifest is not a diagnostic code name
+    #[error("Unknown lint: {0}")]
+    UnknownLint(String),
+    /// A selected entrypoint is not a function of the crate
+    #[error("Unknown entrypoint: {0}")]
+    UnknownEntrypoint(String),
+    /// The MIR passes of the manifest profile name an unknown pass
+    #[error("Invalid MIR passes: {0}")]
+    InvalidMirPasses(String),
 }
 
 /// Options for compilation
-#[derive(Debug, Clone, Default)]
+#[derive(Default)]
 pub struct CompilerOptions {
     /// Enable verbose output
     pub verbose: bool,
+    /// Print the MIR diff of every optimization pass and per-pass statistics to stderr
+    pub verbose_passes: bool,
     /// Optimization level for MIR pipeline
     pub optimization_level: OptimizationLevel,
+    /// Lint levels per diagnostic code, taking precedence over the `[lints]` of the manifest
+    pub lint_levels: BTreeMap<DiagnosticCode, LintLevel>,
+    /// Functions to compile, along with the functions they transitively call.
+    /// All functions are compiled when `None`.
+    pub entrypoints: Option<Vec<String>>,
+    /// Custom passes run on each function of the MIR, after the built-in optimizations.
+    /// They run even when optimizations are disabled.
+    pub extra_mir_passes: Vec<Box<dyn MirPass>>,
+    /// Optimization passes to run, in order, instead of those of the optimization level,
+    /// taking precedence over the `mir_passes` of the manifest profile
+    pub mir_passes: Option<Vec<PassKind>>,
 }
 
 impl CompilerOptions {
     pub const fn no_opts() -> Self {
         Self {
             verbose: false,
+            verbose_passes: false,
             optimization_level: OptimizationLevel::None,
+            lint_levels: BTreeMap::new(),
+            entrypoints: None,
+            extra_mir_passes: Vec::new(),
+            mir_passes: None,
         }
     }
 }
 
+impl std::fmt::Debug for CompilerOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let extra_mir_passes: Vec<&str> = self.extra_mir_passes.iter().map(|p| p.name()).collect();
+        f.debug_struct("CompilerOptions")
+            .field("verbose", &self.verbose)
+            .field("verbose_passes", &self.verbose_passes)
+            .field("optimization_level", &self.optimization_level)
+            .field("lint_levels", &self.lint_levels)
+            .field("entrypoints", &self.entrypoints)
+            .field("extra_mir_passes", &extra_mir_passes)
+            .field("mir_passes", &self.mir_passes)
+            .finish()
+    }
+}
+
 /// Compilation output including the compiled program and any diagnostics
 #[derive(Debug)]
 pub struct CompilerOutput {
     /// The compiled program
     pub program: Arc<Program>,
+    /// The optimized MIR the program was generated from
+    pub mir: Arc<MirModule>,
     /// Any non-error diagnostics generated during compilation
     pub diagnostics: Vec<Diagnostic>,
 }
@@ -90,7 +145,7 @@ pub fn compile_cairo(
 pub fn compile_from_file(
     db: &CompilerDatabase,
     source: SourceFile,
-    options: CompilerOptions,
+    mut options: CompilerOptions,
 ) -> Result<CompilerOutput> {
     // Parse the program
     let parsed_program = parse_file(db, source);
@@ -113,9 +168,8 @@ pub fn compile_from_file(
     // Validate semantics using crate-based API
     let semantic_diagnostics = project_validate_semantics(db, crate_id);
 
-    let (semantic_errors, diagnostics): (Vec<_>, Vec<_>) = semantic_diagnostics
-        .into_iter()
-        .partition(|d| d.severity == DiagnosticSeverity::Error);
+    let (semantic_errors, diagnostics) =
+        partition_diagnostics(semantic_diagnostics, &options.lint_levels);
 
     if !semantic_errors.is_empty() {
         return Err(CompilerError::SemanticErrors(semantic_errors));
@@ -124,13 +178,23 @@ pub fn compile_from_file(
     let pipeline = PipelineConfig {
         optimization_level: options.optimization_level,
         debug: options.verbose,
+        verbose_passes: options.verbose_passes,
+        passes: options.mir_passes.clone(),
+        entrypoints: options.entrypoints.clone(),
     };
 
-    let program = cairo_m_compiler_codegen::db::compile_project_with_config(db, crate_id, pipeline)
-        .map_err(|e| CompilerError::CodeGenerationFailed(e.to_string()))?;
+    let (program, mir) = cairo_m_compiler_codegen::db::compile_project_with_mir(
+        db,
+        crate_id,
+        pipeline,
+        &mut options.extra_mir_passes,
+    )
+    .map_err(|e| CompilerError::CodeGenerationFailed(e.to_string()))?;
+    check_entrypoints(&program, options.entrypoints.as_deref())?;
 
     Ok(CompilerOutput {
         program,
+        mir,
         diagnostics,
     })
 }
@@ -141,8 +205,14 @@ pub fn compile_from_file(
 pub fn compile_project(
     db: &CompilerDatabase,
     project: cairo_m_project::Project,
-    options: CompilerOptions,
+    mut options: CompilerOptions,
 ) -> Result<CompilerOutput> {
+    let lint_levels = resolve_lint_levels(
+        &project.config.lints,
+        std::mem::take(&mut options.lint_levels),
+    )?;
+    let mir_passes = resolve_mir_passes(&project.config.profile, options.mir_passes.take())?;
+
     // Create a semantic crate from the project
     let crate_id = match crate_from_project(db, project) {
         Ok(crate_id) => crate_id,
@@ -152,12 +222,243 @@ pub fn compile_project(
         }
     };
 
+    compile_crate(db, crate_id, &lint_levels, mir_passes, &mut options)
+}
+
+/// Compiles a single member of a Cairo-M workspace
+///
+/// The modules of every member the package transitively depends on are compiled along with
+/// it, namespaced under the dependency's name (e.g. `use math::lib::add;`).
+///
+/// Diagnostics are tagged with the module path of their file, prefixed with the name of its
+/// package, see [`workspace_module_paths`].
+///
+/// # Arguments
+/// * `db` - The compiler database
+/// * `workspace` - The discovered workspace
+/// * `member` - ID of the member to compile
+/// * `options` - Compilation options
+pub fn compile_workspace_member(
+    db: &CompilerDatabase,
+    workspace: &cairo_m_project::Workspace,
+    member: cairo_m_project::ProjectId,
+    mut options: CompilerOptions,
+) -> Result<CompilerOutput> {
+    compile_member(db, workspace, member, &mut options)
+}
+
+/// Compiles a workspace member, borrowing `options` so that its MIR passes can be reused
+/// across members
+fn compile_member(
+    db: &CompilerDatabase,
+    workspace: &cairo_m_project::Workspace,
+    member: cairo_m_project::ProjectId,
+    options: &mut CompilerOptions,
+) -> Result<CompilerOutput> {
+    let lint_levels = resolve_lint_levels(
+        &workspace.projects[&member].config.lints,
+        options.lint_levels.clone(),
+    )?;
+    let mir_passes = resolve_mir_passes(
+        &workspace.projects[&member].config.profile,
+        options.mir_passes.clone(),
+    )?;
+
+    let module_paths = workspace_module_paths(workspace, member);
+    let with_module_paths = |diagnostics: Vec<Diagnostic>| -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| match module_paths.get(&diagnostic.file_path) {
+                Some(module_path) => diagnostic.with_module_path(module_path.clone()),
+                None => diagnostic,
+            })
+            .collect()
+    };
+
+    let crate_id = match crate_from_workspace_member(db, workspace, member) {
+        Ok(crate_id) => crate_id,
+        Err(diagnostics) => {
+            let errors = diagnostics.errors().into_iter().cloned().collect();
+            return Err(CompilerError::ParseErrors(with_module_paths(errors)));
+        }
+    };
+
+    match compile_crate(db, crate_id, &lint_levels, mir_passes, options) {
+        Ok(output) => Ok(CompilerOutput {
+            diagnostics: with_module_paths(output.diagnostics),
+            ..output
+        }),
+        Err(CompilerError::ParseErrors(errors)) => {
+            Err(CompilerError::ParseErrors(with_module_paths(errors)))
+        }
+        Err(CompilerError::SemanticErrors(errors)) => {
+            Err(CompilerError::SemanticErrors(with_module_paths(errors)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Maps the source files compiled along with a workspace member to their module path,
+/// prefixed with the name of the package they belong to (e.g. `math::lib`)
+///
+/// Unlike module names in the semantic crate, the modules of the member itself are prefixed
+/// too, so that diagnostics name the package of every file.
+pub fn workspace_module_paths(
+    workspace: &cairo_m_project::Workspace,
+    member: cairo_m_project::ProjectId,
+) -> HashMap<String, String> {
+    let mut module_paths = HashMap::new();
+    for id in workspace.build_order_for(member).unwrap_or_default() {
+        let project = &workspace.projects[&id];
+        let Ok(source_files) = project.source_files() else {
+            continue;
+        };
+        for file_path in source_files {
+            if let Ok(module_name) = project.module_name_from_path(&file_path) {
+                module_paths.insert(
+                    file_path.to_string_lossy().to_string(),
+                    format!("{}::{module_name}", project.name),
+                );
+            }
+        }
+    }
+    module_paths
+}
+
+/// Compiles every member of a Cairo-M workspace, in dependency order
+///
+/// # Returns
+/// * `Ok(Vec<(String, CompilerOutput)>)` - The output of each member, keyed by member name
+/// * `Err(CompilerError)` - The first member that failed to compile, or
+///   [`CompilerError::DependencyCycle`] if members depend on each other in a cycle
+pub fn compile_workspace(
+    db: &CompilerDatabase,
+    workspace: &cairo_m_project::Workspace,
+    mut options: CompilerOptions,
+) -> Result<Vec<(String, CompilerOutput)>> {
+    let build_order = workspace
+        .build_order()
+        .map_err(|cycle| CompilerError::DependencyCycle(cycle.0))?;
+
+    build_order
+        .into_iter()
+        .map(|id| {
+            let output = compile_member(db, workspace, id, &mut options)?;
+            Ok((workspace.projects[&id].name.clone(), output))
+        })
+        .collect()
+}
+
+/// Resolves the `[lints]` table of a manifest into lint levels per diagnostic code
+///
+/// The `warnings` key sets the level of every lint. The other keys are diagnostic code names
+/// in snake case (e.g. `unused_variable`) and take precedence over it.
+///
+/// # Arguments
+/// * `lints` - The `[lints]` table of the manifest
+/// * `overrides` - Lint levels taking precedence over the manifest
+///
+/// # Errors
+/// Returns [`CompilerError::UnknownLint`] if a key is neither `warnings` nor a code name
+pub fn resolve_lint_levels(
+    lints: &BTreeMap<String, LintLevel>,
+    overrides: BTreeMap<DiagnosticCode, LintLevel>,
+) -> Result<BTreeMap<DiagnosticCode, LintLevel>> {
+    let mut lint_levels = BTreeMap::new();
+    if let Some(&level) = lints.get("warnings") {
+        lint_levels.extend(DiagnosticCode::ALL.iter().map(|&code| (code, level)));
+    }
+    for (name, &level) in lints.iter().filter(|(name, _)| *name != "warnings") {
+        let code = DiagnosticCode::from_name(name)
+            .ok_or_else(|| CompilerError::UnknownLint(name.clone()))?;
+        lint_levels.insert(code, level);
+    }
+    lint_levels.extend(overrides);
+    Ok(lint_levels)
+}
+
+/// Resolves the optimization passes of a crate, from the `mir_passes` of its manifest
+/// profile unless `overrides` are given
+///
+/// # Errors
+/// Returns [`CompilerError::InvalidMirPasses`] if the profile names an unknown pass
+pub fn resolve_mir_passes(
+    profile: &ProfileManifest,
+    overrides: Option<Vec<PassKind>>,
+) -> Result<Option<Vec<PassKind>>> {
+    match (overrides, &profile.mir_passes) {
+        (Some(passes), _) => Ok(Some(passes)),
+        (None, Some(spec)) => PassKind::parse_list(spec)
+            .map(Some)
+            .map_err(CompilerError::InvalidMirPasses),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Applies lint levels to diagnostics
+///
+/// Lint levels only apply to non-error diagnostics: allowed ones are dropped and denied ones
+/// become errors.
+pub fn apply_lint_levels(
+    diagnostics: Vec<Diagnostic>,
+    lint_levels: &BTreeMap<DiagnosticCode, LintLevel>,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            if diagnostic.severity != DiagnosticSeverity::Error {
+                match lint_levels.get(&diagnostic.code) {
+                    Some(LintLevel::Allow) => return None,
+                    Some(LintLevel::Deny) => diagnostic.severity = DiagnosticSeverity::Error,
+                    Some(LintLevel::Warn) | None => {}
+                }
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Splits diagnostics into errors and other diagnostics, after applying the lint levels
+fn partition_diagnostics(
+    diagnostics: Vec<Diagnostic>,
+    lint_levels: &BTreeMap<DiagnosticCode, LintLevel>,
+) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+    apply_lint_levels(diagnostics, lint_levels)
+        .into_iter()
+        .partition(|d| d.severity == DiagnosticSeverity::Error)
+}
+
+/// Checks that every selected entrypoint was compiled
+///
+/// # Errors
+/// Returns [`CompilerError::UnknownEntrypoint`] for the first entrypoint that is not a
+/// function of the crate
+fn check_entrypoints(program: &Program, entrypoints: Option<&[String]>) -> Result<()> {
+    match entrypoints
+        .into_iter()
+        .flatten()
+        .find(|name| !program.entrypoints.contains_key(*name))
+    {
+        Some(name) => Err(CompilerError::UnknownEntrypoint(name.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Validates and generates code for an already-built semantic crate
+///
+/// `lint_levels` and `mir_passes` are the settings resolved for the crate, replacing those of
+/// `options`.
+fn compile_crate(
+    db: &CompilerDatabase,
+    crate_id: SemanticCrate,
+    lint_levels: &BTreeMap<DiagnosticCode, LintLevel>,
+    mir_passes: Option<Vec<PassKind>>,
+    options: &mut CompilerOptions,
+) -> Result<CompilerOutput> {
     // Validate semantics using crate-based API
     let semantic_diagnostics = project_validate_semantics(db, crate_id);
 
-    let (semantic_errors, diagnostics): (Vec<_>, Vec<_>) = semantic_diagnostics
-        .into_iter()
-        .partition(|d| d.severity == DiagnosticSeverity::Error);
+    let (semantic_errors, diagnostics) = partition_diagnostics(semantic_diagnostics, lint_levels);
 
     if !semantic_errors.is_empty() {
         return Err(CompilerError::SemanticErrors(semantic_errors));
@@ -166,13 +467,23 @@ pub fn compile_project(
     let pipeline = PipelineConfig {
         optimization_level: options.optimization_level,
         debug: options.verbose,
+        verbose_passes: options.verbose_passes,
+        passes: mir_passes,
+        entrypoints: options.entrypoints.clone(),
     };
 
-    let program = cairo_m_compiler_codegen::db::compile_project_with_config(db, crate_id, pipeline)
-        .map_err(|e| CompilerError::CodeGenerationFailed(e.to_string()))?;
+    let (program, mir) = cairo_m_compiler_codegen::db::compile_project_with_mir(
+        db,
+        crate_id,
+        pipeline,
+        &mut options.extra_mir_passes,
+    )
+    .map_err(|e| CompilerError::CodeGenerationFailed(e.to_string()))?;
+    check_entrypoints(&program, options.entrypoints.as_deref())?;
 
     Ok(CompilerOutput {
         program,
+        mir,
         diagnostics,
     })
 }
@@ -200,8 +511,12 @@ pub fn format_diagnostics(
 
 /// Formats diagnostics for display (multi-file)
 ///
+/// Sources are looked up by the full path of the diagnostic's file, so that files with the
+/// same name in different packages are kept apart. Diagnostics carrying a module path, such
+/// as those of [`compile_workspace_member`], are prefixed with it.
+///
 /// # Arguments
-/// * `source_map` - Map from file path to source code text
+/// * `source_map` - Map from full file path to source code text
 /// * `diagnostics` - The diagnostics to format
 /// * `use_color` - Whether to use color in the output
 ///
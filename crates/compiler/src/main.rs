@@ -1,14 +1,32 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "watch")]
+use std::sync::mpsc;
+#[cfg(feature = "watch")]
+use std::time::Duration;
 use std::{fs, process};
 
+#[cfg(feature = "watch")]
+use cairo_m_compiler::watch::{WatchBuild, WatchSession};
 use cairo_m_compiler::{
-    CompilerError, CompilerOptions, compile_project, format_diagnostics_multi_file,
+    CompilerError, CompilerOptions, CompilerOutput, compile_project, compile_workspace_member,
+    format_diagnostics_multi_file,
 };
+use cairo_m_compiler_mir::PassKind;
 use cairo_m_compiler_mir::pipeline::OptimizationLevel;
-use cairo_m_project::discover_project;
-use clap::Parser;
+use cairo_m_compiler_mir::visualization::{GraphFormat, call_graph, function_cfg};
+use cairo_m_project::{
+    Project, ProjectId, Workspace, discover_project, discover_workspace, find_workspace_root,
+};
+use clap::{Parser, ValueEnum};
+#[cfg(feature = "watch")]
+use notify::{Event, RecursiveMode, Watcher};
 use tracing::Level;
 
+/// Delay during which file events are coalesced into a single rebuild
+#[cfg(feature = "watch")]
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
 /// Cairo-M compiler
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -21,13 +39,155 @@ struct Args {
     #[arg(short, long)]
     output: Option<PathBuf>,
 
-    /// Enable verbose output (shows MIR)
+    /// Write the compiled program in the binary format, which the runner memory-maps
+    #[arg(long, requires = "output")]
+    binary: bool,
+
+    /// Enable verbose output (shows MIR and per-function frame-size reports)
     #[arg(short, long)]
     verbose: bool,
 
+    /// Print the MIR diff of every optimization pass that modifies a function, then the
+    /// instructions, blocks and values each pass removed over the program
+    #[arg(long)]
+    verbose_passes: bool,
+
+    /// Comma-separated MIR optimization passes to run, in order, instead of the standard
+    /// pipeline (e.g. "sroa,cse,dce"), overriding the `[profile]` of the manifest
+    #[arg(long)]
+    mir_passes: Option<String>,
+
+    /// Write visualizations of the optimized MIR: the control flow graph of each function,
+    /// annotated with instruction counts, and the call graph of the program
+    #[arg(long, value_enum)]
+    emit: Option<Emit>,
+
+    /// Directory to write the `--emit` files to (default: the current directory)
+    #[arg(long, requires = "emit")]
+    emit_dir: Option<PathBuf>,
+
     /// Optimization level (0: disabled, 1: enabled)
     #[arg(long = "opt-level", value_parser = clap::value_parser!(u8).range(0..=1), default_value_t = 1)]
     opt_level: u8,
+
+    /// Workspace member to compile, when the input is part of a workspace
+    #[arg(short, long)]
+    package: Option<String>,
+
+    /// Function to compile, along with the functions it calls (repeatable, default: all)
+    #[arg(long = "entrypoint")]
+    entrypoints: Vec<String>,
+
+    /// Recompile whenever a source file or manifest of the project changes
+    #[arg(short, long)]
+    watch: bool,
+
+    /// Entrypoint to run with `cairo-m-runner` after each successful build in watch mode
+    #[arg(long, requires = "watch")]
+    run: Option<String>,
+}
+
+/// Visualizations written by `--emit`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Emit {
+    /// Graphviz files, `<function>.cfg.dot` and `call_graph.dot`
+    CfgDot,
+    /// Mermaid files, `<function>.cfg.mmd` and `call_graph.mmd`
+    CfgMermaid,
+}
+
+impl Emit {
+    const fn format(self) -> GraphFormat {
+        match self {
+            Self::CfgDot => GraphFormat::Dot,
+            Self::CfgMermaid => GraphFormat::Mermaid,
+        }
+    }
+}
+
+/// What the compiler was asked to compile
+enum Target {
+    /// A standalone project
+    Project(Project),
+    /// A member of a workspace
+    Member {
+        workspace: Workspace,
+        member: ProjectId,
+    },
+}
+
+impl Target {
+    /// Discovers the project or workspace member designated by the arguments
+    fn discover(args: &Args) -> Result<Self, String> {
+        match &args.package {
+            Some(package) => {
+                let workspace_root = find_workspace_root(&args.input)
+                    .map_err(|e| format!("Failed to discover workspace: {}", e))?
+                    .ok_or_else(|| {
+                        format!(
+                            "No Cairo-M workspace found at '{}'\n\
+                             Make sure there's a cairom.toml file with a [workspace] table",
+                            args.input.display()
+                        )
+                    })?;
+                let workspace = discover_workspace(&workspace_root)
+                    .map_err(|e| format!("Failed to discover workspace: {:#}", e))?;
+                let (member, _) = workspace.project_by_name(package).ok_or_else(|| {
+                    format!("Package '{}' is not a member of the workspace", package)
+                })?;
+                // Fail early on dependency cycles
+                workspace
+                    .build_order_for(member)
+                    .map_err(|e| e.to_string())?;
+                Ok(Self::Member { workspace, member })
+            }
+            None => match discover_project(&args.input)
+                .map_err(|e| format!("Failed to discover project: {}", e))?
+            {
+                Some(project) => Ok(Self::Project(project)),
+                None => Err(format!(
+                    "No Cairo-M project found at '{}'\n\
+                     Make sure there's a cairom.toml file in the project root",
+                    args.input.display()
+                )),
+            },
+        }
+    }
+
+    /// The compiled project
+    #[cfg(feature = "watch")]
+    fn member(&self) -> &Project {
+        match self {
+            Self::Project(project) => project,
+            Self::Member { workspace, member } => &workspace.projects[member],
+        }
+    }
+
+    /// The projects whose modules are compiled, in build order, with the prefix of their
+    /// module names
+    fn projects(&self) -> Vec<(&Project, Option<&str>)> {
+        match self {
+            Self::Project(project) => vec![(project, None)],
+            Self::Member { workspace, member } => workspace
+                .build_order_for(*member)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|id| {
+                    let project = &workspace.projects[&id];
+                    (project, (id != *member).then_some(project.name.as_str()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Directory to watch for changes
+    #[cfg(feature = "watch")]
+    fn root_directory(&self) -> &Path {
+        match self {
+            Self::Project(project) => &project.root_directory,
+            Self::Member { workspace, .. } => &workspace.root_directory,
+        }
+    }
 }
 
 fn main() {
@@ -37,54 +197,46 @@ fn main() {
         tracing_subscriber::fmt().with_max_level(Level::INFO).init();
     }
 
-    let db = cairo_m_compiler::create_compiler_database();
-
-    // Discover the project
-    let project = match discover_project(&args.input).unwrap_or_else(|e| {
-        eprintln!("Failed to discover project: {}", e);
-        process::exit(1);
-    }) {
-        Some(project) => project,
-        None => {
-            eprintln!("No Cairo-M project found at '{}'", args.input.display());
-            eprintln!("Make sure there's a cairom.toml file in the project root");
+    let mir_passes = args.mir_passes.as_deref().map(|spec| {
+        PassKind::parse_list(spec).unwrap_or_else(|e| {
+            eprintln!("{}", e);
             process::exit(1);
-        }
-    };
+        })
+    });
 
     let options = CompilerOptions {
         verbose: args.verbose,
+        verbose_passes: args.verbose_passes,
+        mir_passes,
         optimization_level: match args.opt_level {
             0 => OptimizationLevel::None,
             _ => OptimizationLevel::Standard,
         },
+        entrypoints: (!args.entrypoints.is_empty()).then(|| args.entrypoints.clone()),
+        ..Default::default()
     };
 
-    // Build a map of file paths to source text for multi-file diagnostics
-    let mut source_map = std::collections::HashMap::new();
-    // We'll need to read the source files for error reporting
-    if let Ok(source_files) = project.source_files() {
-        for file_path in source_files {
-            if let Ok(content) = fs::read_to_string(&file_path) {
-                source_map.insert(file_path.to_string_lossy().to_string(), content);
-            }
-        }
+    if args.watch {
+        watch(&args, options);
+        return;
     }
 
-    let output = compile_project(&db, project, options).unwrap_or_else(|e| {
-        match &e {
-            CompilerError::ParseErrors(diagnostics)
-            | CompilerError::SemanticErrors(diagnostics) => {
-                let error_msg = format_diagnostics_multi_file(&source_map, diagnostics, true);
-                eprintln!("{}", error_msg);
-            }
-            CompilerError::MirGenerationFailed => {
-                eprintln!("Failed to generate MIR");
-            }
-            CompilerError::CodeGenerationFailed(msg) => {
-                eprintln!("Code generation failed: {}", msg);
-            }
+    let target = Target::discover(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    let db = cairo_m_compiler::create_compiler_database();
+    let source_map = build_source_map(&target.projects());
+    let result = match &target {
+        Target::Project(project) => compile_project(&db, project.clone(), options),
+        Target::Member { workspace, member } => {
+            compile_workspace_member(&db, workspace, *member, options)
         }
+    };
+
+    let output = result.unwrap_or_else(|e| {
+        report_error(&source_map, &e);
         process::exit(1);
     });
 
@@ -95,20 +247,23 @@ fn main() {
         println!("{}", diagnostic_messages);
     }
 
-    let json = sonic_rs::to_string_pretty(&*output.program).unwrap_or_else(|e| {
-        eprintln!("Failed to serialize program: {}", e);
+    if let Some(emit) = args.emit {
+        emit_graphs(&output, emit, args.emit_dir.as_deref()).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+    }
+
+    let serialized = serialize_program(&output, args.binary).unwrap_or_else(|e| {
+        eprintln!("{}", e);
         process::exit(1);
     });
 
     // Write output or print to stdout
     match args.output {
         Some(output_path) => {
-            fs::write(&output_path, &json).unwrap_or_else(|e| {
-                eprintln!(
-                    "Failed to write output file '{}': {}",
-                    output_path.display(),
-                    e
-                );
+            write_program(&output_path, &serialized).unwrap_or_else(|e| {
+                eprintln!("{}", e);
                 process::exit(1);
             });
             println!(
@@ -117,7 +272,264 @@ fn main() {
             );
         }
         None => {
-            println!("{}", json);
+            println!("{}", String::from_utf8_lossy(&serialized));
+        }
+    }
+}
+
+/// Prints a compilation error
+fn report_error(source_map: &HashMap<String, String>, error: &CompilerError) {
+    match error {
+        CompilerError::ParseErrors(diagnostics) | CompilerError::SemanticErrors(diagnostics) => {
+            let error_msg = format_diagnostics_multi_file(source_map, diagnostics, true);
+            eprintln!("{}", error_msg);
+        }
+        CompilerError::DependencyCycle(cycle) => {
+            eprintln!(
+                "Dependency cycle between workspace members: {}",
+                cycle.join(" -> ")
+            );
+        }
+        CompilerError::MirGenerationFailed => {
+            eprintln!("Failed to generate MIR");
+        }
+        CompilerError::CodeGenerationFailed(msg) => {
+            eprintln!("Code generation failed: {}", msg);
+        }
+        CompilerError::UnknownLint(lint) => {
+            eprintln!("Unknown lint '{}' in the manifest", lint);
+        }
+        CompilerError::UnknownEntrypoint(name) => {
+            eprintln!("Entrypoint '{}' is not a function of the crate", name);
+        }
+        CompilerError::InvalidMirPasses(message) => {
+            eprintln!("Invalid MIR passes in the manifest profile: {}", message);
+        }
+    }
+}
+
+/// Serializes the compiled program, as JSON or in the binary format
+fn serialize_program(output: &CompilerOutput, binary: bool) -> Result<Vec<u8>, String> {
+    if binary {
+        return Ok(output.program.to_binary());
+    }
+    sonic_rs::to_string_pretty(&*output.program)
+        .map(String::into_bytes)
+        .map_err(|e| format!("Failed to serialize program: {}", e))
+}
+
+fn write_program(output_path: &Path, serialized: &[u8]) -> Result<(), String> {
+    fs::write(output_path, serialized).map_err(|e| {
+        format!(
+            "Failed to write output file '{}': {}",
+            output_path.display(),
+            e
+        )
+    })
+}
+
+/// Writes the graphs requested by `--emit`, one file per function plus the call graph
+fn emit_graphs(output: &CompilerOutput, emit: Emit, dir: Option<&Path>) -> Result<(), String> {
+    let dir = dir.unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create directory '{}': {}", dir.display(), e))?;
+
+    let format = emit.format();
+    let mut graphs = vec![(
+        format!("call_graph.{}", format.extension()),
+        call_graph(&output.mir, format),
+    )];
+    for (_, function) in output.mir.functions() {
+        // Function names may contain module paths
+        let name: String = function
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        graphs.push((
+            format!("{}.cfg.{}", name, format.extension()),
+            function_cfg(function, format),
+        ));
+    }
+
+    for (file_name, graph) in &graphs {
+        let path = dir.join(file_name);
+        fs::write(&path, graph)
+            .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))?;
+    }
+    eprintln!("Wrote {} graphs to '{}'", graphs.len(), dir.display());
+    Ok(())
+}
+
+/// Recompiles the target whenever one of its source files or manifests changes
+///
+/// The database is reused across builds, and only the diagnostics that changed since the
+/// previous build are printed. Discovery is re-run on each change, so that added files and
+/// manifest edits are picked up.
+#[cfg(feature = "watch")]
+fn watch(args: &Args, mut options: CompilerOptions) {
+    let mut target = Target::discover(args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|e| {
+        eprintln!("Failed to create file watcher: {}", e);
+        process::exit(1);
+    });
+    let root = target.root_directory().to_path_buf();
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to watch '{}': {}", root.display(), e);
+            process::exit(1);
+        });
+
+    let mut session = WatchSession::new();
+    loop {
+        let build = session.build(&target.projects(), target.member(), &mut options);
+        report_build(args, &build);
+        println!("Watching '{}' for changes...", root.display());
+
+        // Wait for a relevant change, then coalesce the burst of events of a save
+        loop {
+            match rx.recv() {
+                Ok(Ok(event)) if is_relevant_event(&event) => break,
+                Ok(_) => {}
+                Err(_) => return,
+            }
+        }
+        while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+        match Target::discover(args) {
+            Ok(rediscovered) => target = rediscovered,
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Returns true for events on source files and manifests
+#[cfg(feature = "watch")]
+fn is_relevant_event(event: &Event) -> bool {
+    (event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove())
+        && event.paths.iter().any(|path| {
+            path.extension().is_some_and(|ext| ext == "cm")
+                || path.file_name().is_some_and(|name| name == "cairom.toml")
+        })
+}
+
+/// Prints the outcome of a watch build, and runs the configured entrypoint on success
+#[cfg(feature = "watch")]
+fn report_build(args: &Args, build: &WatchBuild) {
+    if !build.new_diagnostics.is_empty() {
+        eprintln!(
+            "{}",
+            format_diagnostics_multi_file(&build.source_map, &build.new_diagnostics, true)
+        );
+    }
+    if build.resolved_diagnostics > 0 {
+        println!("{} diagnostic(s) resolved", build.resolved_diagnostics);
+    }
+
+    let output = match &build.result {
+        Ok(output) => output,
+        Err(CompilerError::ParseErrors(_) | CompilerError::SemanticErrors(_)) => {
+            eprintln!("Build failed");
+            return;
+        }
+        Err(e) => {
+            report_error(&build.source_map, e);
+            eprintln!("Build failed");
+            return;
+        }
+    };
+
+    if let Some(emit) = args.emit {
+        if let Err(e) = emit_graphs(output, emit, args.emit_dir.as_deref()) {
+            eprintln!("{}", e);
+        }
+    }
+
+    let serialized = match serialize_program(output, args.binary) {
+        Ok(serialized) => serialized,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    // The entrypoint is run on the written program, in a temporary file by default
+    let output_path = match (&args.output, &args.run) {
+        (Some(path), _) => path.clone(),
+        (None, Some(_)) => std::env::temp_dir().join("cairo-m-watch.json"),
+        (None, None) => {
+            println!("Build succeeded");
+            return;
+        }
+    };
+    if let Err(e) = write_program(&output_path, &serialized) {
+        eprintln!("{}", e);
+        return;
+    }
+    println!(
+        "Build succeeded. Output written to '{}'",
+        output_path.display()
+    );
+
+    if let Some(entrypoint) = &args.run {
+        run_entrypoint(&output_path, entrypoint);
+    }
+}
+
+/// Runs an entrypoint of a compiled program with `cairo-m-runner`
+///
+/// The runner installed next to the compiler is preferred over the one in `PATH`.
+#[cfg(feature = "watch")]
+fn run_entrypoint(program_path: &Path, entrypoint: &str) {
+    let runner = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join("cairo-m-runner")))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("cairo-m-runner"));
+
+    match process::Command::new(&runner)
+        .arg(program_path)
+        .arg("--entrypoint")
+        .arg(entrypoint)
+        .status()
+    {
+        Ok(status) if !status.success() => {
+            eprintln!("Entrypoint '{}' failed ({})", entrypoint, status);
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to run '{}': {}", runner.display(), e),
+    }
+}
+
+/// Exits with an error, as watch mode is only available with the `watch` feature
+#[cfg(not(feature = "watch"))]
+fn watch(_args: &Args, _options: CompilerOptions) {
+    eprintln!("Watch mode requires cairo-m-compiler to be built with the `watch` feature");
+    process::exit(1);
+}
+
+/// Build a map of file paths to source text for multi-file diagnostics
+fn build_source_map(projects: &[(&Project, Option<&str>)]) -> HashMap<String, String> {
+    let mut source_map = HashMap::new();
+    for (project, _) in projects {
+        if let Ok(source_files) = project.source_files() {
+            for file_path in source_files {
+                if let Ok(content) = fs::read_to_string(&file_path) {
+                    source_map.insert(file_path.to_string_lossy().to_string(), content);
+                }
+            }
         }
     }
+    source_map
 }
@@ -0,0 +1,241 @@
+//! Incremental rebuilds for the `--watch` mode of the compiler CLI
+//!
+//! A [`WatchSession`] keeps the same database across builds: the source files of the
+//! project are Salsa inputs that are only updated when their content changes, so that a
+//! rebuild only recomputes the queries depending on the edited files. Each build reports the
+//! diagnostics that were not reported by the previous one, and how many were resolved.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use cairo_m_compiler_diagnostics::{Diagnostic, DiagnosticCode};
+use cairo_m_compiler_parser::SourceFile;
+use cairo_m_compiler_semantic::Crate as SemanticCrate;
+use cairo_m_project::Project;
+use salsa::Setter;
+
+use crate::db::CompilerDatabase;
+use crate::{
+    CompilerError, CompilerOptions, CompilerOutput, compile_crate, resolve_lint_levels,
+    resolve_mir_passes,
+};
+
+/// Outcome of a build of a [`WatchSession`]
+#[derive(Debug)]
+pub struct WatchBuild {
+    /// Result of the compilation
+    pub result: crate::Result<CompilerOutput>,
+    /// Diagnostics that were not reported by the previous build
+    pub new_diagnostics: Vec<Diagnostic>,
+    /// Number of diagnostics of the previous build that are no longer reported
+    pub resolved_diagnostics: usize,
+    /// Source text of each file of the build, for rendering diagnostics
+    pub source_map: HashMap<String, String>,
+}
+
+/// A compiler database reused across the builds of a project
+#[derive(Default)]
+pub struct WatchSession {
+    db: CompilerDatabase,
+    /// Source file input of each file loaded by a previous build
+    files: HashMap<PathBuf, SourceFile>,
+    crate_id: Option<SemanticCrate>,
+    /// Diagnostics of the previous build
+    reported: HashSet<Diagnostic>,
+}
+
+impl WatchSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the source files of `projects` into the database, updating only the inputs of
+    /// the files whose content changed
+    ///
+    /// # Arguments
+    /// * `projects` - Projects to load, in build order, with the prefix of their module names
+    ///   (`None` for the compiled project, the dependency name otherwise)
+    /// * `member` - The compiled project
+    fn load_crate(
+        &mut self,
+        projects: &[(&Project, Option<&str>)],
+        member: &Project,
+    ) -> Result<SemanticCrate, Vec<Diagnostic>> {
+        let mut modules = HashMap::new();
+        let mut loaded = HashSet::new();
+        let mut errors = Vec::new();
+
+        for (project, prefix) in projects {
+            let source_files = match project.source_files() {
+                Ok(files) => files,
+                Err(e) => {
+                    errors.push(Diagnostic::error(
+                        DiagnosticCode::InternalError,
+                        format!("Failed to discover source files: {}", e),
+                    ));
+                    continue;
+                }
+            };
+
+            for file_path in source_files {
+                let module_name = match project.module_name_from_path(&file_path) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        errors.push(Diagnostic::error(
+                            DiagnosticCode::InternalError,
+                            format!(
+                                "Failed to resolve module name for {}: {}",
+                                file_path.display(),
+                                e
+                            ),
+                        ));
+                        continue;
+                    }
+                };
+                let module_name = match prefix {
+                    Some(prefix) => format!("{prefix}::{module_name}"),
+                    None => module_name,
+                };
+
+                let content = match std::fs::read_to_string(&file_path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        errors.push(Diagnostic::error(
+                            DiagnosticCode::InternalError,
+                            format!("Failed to read file {}: {}", file_path.display(), e),
+                        ));
+                        continue;
+                    }
+                };
+
+                let file = match self.files.get(&file_path) {
+                    Some(&file) => {
+                        if *file.text(&self.db) != content {
+                            file.set_text(&mut self.db).to(content);
+                        }
+                        file
+                    }
+                    None => {
+                        let file = SourceFile::new(
+                            &self.db,
+                            content,
+                            file_path.to_string_lossy().to_string(),
+                        );
+                        self.files.insert(file_path.clone(), file);
+                        file
+                    }
+                };
+                loaded.insert(file_path);
+                modules.insert(module_name, file);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        // Forget the files that were removed from the project
+        self.files.retain(|path, _| loaded.contains(path));
+
+        let entry_point = member
+            .root_directory
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("main")
+            .to_string();
+
+        let crate_id = match self.crate_id {
+            Some(crate_id) => {
+                if *crate_id.modules(&self.db) != modules {
+                    crate_id.set_modules(&mut self.db).to(modules);
+                }
+                if crate_id.entry_point(&self.db) != entry_point {
+                    crate_id.set_entry_point(&mut self.db).to(entry_point);
+                }
+                if *crate_id.root_dir(&self.db) != member.root_directory {
+                    crate_id
+                        .set_root_dir(&mut self.db)
+                        .to(member.root_directory.clone());
+                }
+                if *crate_id.name(&self.db) != member.name {
+                    crate_id.set_name(&mut self.db).to(member.name.clone());
+                }
+                crate_id
+            }
+            None => SemanticCrate::new(
+                &self.db,
+                modules,
+                entry_point,
+                member.root_directory.clone(),
+                member.name.clone(),
+            ),
+        };
+        self.crate_id = Some(crate_id);
+        Ok(crate_id)
+    }
+
+    fn compile(
+        &mut self,
+        projects: &[(&Project, Option<&str>)],
+        member: &Project,
+        options: &mut CompilerOptions,
+    ) -> crate::Result<CompilerOutput> {
+        let lint_levels = resolve_lint_levels(&member.config.lints, options.lint_levels.clone())?;
+        let mir_passes = resolve_mir_passes(&member.config.profile, options.mir_passes.clone())?;
+        let crate_id = self
+            .load_crate(projects, member)
+            .map_err(CompilerError::ParseErrors)?;
+        compile_crate(&self.db, crate_id, &lint_levels, mir_passes, options)
+    }
+
+    /// Compiles `member`, along with the modules of its dependencies
+    ///
+    /// # Arguments
+    /// * `projects` - Projects to compile, in build order, with the prefix of their module
+    ///   names (`None` for `member`, the dependency name otherwise)
+    /// * `member` - The compiled project, whose `[lints]` and `[profile]` apply
+    /// * `options` - Compilation options, reused across builds
+    pub fn build(
+        &mut self,
+        projects: &[(&Project, Option<&str>)],
+        member: &Project,
+        options: &mut CompilerOptions,
+    ) -> WatchBuild {
+        let result = self.compile(projects, member, options);
+
+        let diagnostics: &[Diagnostic] = match &result {
+            Ok(output) => &output.diagnostics,
+            Err(
+                CompilerError::ParseErrors(diagnostics)
+                | CompilerError::SemanticErrors(diagnostics),
+            ) => diagnostics,
+            Err(_) => &[],
+        };
+        let current: HashSet<Diagnostic> = diagnostics.iter().cloned().collect();
+        let new_diagnostics = diagnostics
+            .iter()
+            .filter(|diagnostic| !self.reported.contains(*diagnostic))
+            .cloned()
+            .collect();
+        let resolved_diagnostics = self.reported.difference(&current).count();
+        self.reported = current;
+
+        let source_map = self
+            .files
+            .iter()
+            .map(|(path, file)| {
+                (
+                    path.to_string_lossy().to_string(),
+                    file.text(&self.db).clone(),
+                )
+            })
+            .collect();
+
+        WatchBuild {
+            result,
+            new_diagnostics,
+            resolved_diagnostics,
+            source_map,
+        }
+    }
+}
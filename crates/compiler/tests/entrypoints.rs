@@ -0,0 +1,66 @@
+use cairo_m_compiler::{CompilerError, CompilerOptions, compile_cairo};
+
+/// Tests for `CompilerOptions::entrypoints`, which restricts compilation to the selected
+/// functions and the functions they transitively call.
+
+const SOURCE: &str = r#"
+fn square(x: felt) -> felt {
+    return x * x;
+}
+
+fn sum_of_squares(x: felt, y: felt) -> felt {
+    return square(x) + square(y);
+}
+
+fn norm(x: felt, y: felt) -> felt {
+    return sum_of_squares(x, y);
+}
+
+fn unrelated() -> felt {
+    return 42;
+}
+"#;
+
+fn compile(entrypoints: Option<&[&str]>) -> Result<Vec<String>, CompilerError> {
+    let options = CompilerOptions {
+        entrypoints: entrypoints.map(|names| names.iter().map(|n| n.to_string()).collect()),
+        ..Default::default()
+    };
+    let output = compile_cairo(SOURCE.to_string(), "entrypoints.cm".to_string(), options)?;
+    let mut names: Vec<_> = output.program.entrypoints.keys().cloned().collect();
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn test_all_functions_compiled_by_default() {
+    assert_eq!(
+        compile(None).unwrap(),
+        ["norm", "square", "sum_of_squares", "unrelated"]
+    );
+}
+
+#[test]
+fn test_transitive_callees_compiled() {
+    assert_eq!(
+        compile(Some(&["norm"])).unwrap(),
+        ["norm", "square", "sum_of_squares"]
+    );
+    assert_eq!(compile(Some(&["square"])).unwrap(), ["square"]);
+}
+
+#[test]
+fn test_multiple_entrypoints() {
+    assert_eq!(
+        compile(Some(&["unrelated", "sum_of_squares"])).unwrap(),
+        ["square", "sum_of_squares", "unrelated"]
+    );
+}
+
+#[test]
+fn test_unknown_entrypoint() {
+    assert!(matches!(
+        compile(Some(&["square", "missing"])),
+        Err(CompilerError::UnknownEntrypoint(name)) if name == "missing"
+    ));
+}
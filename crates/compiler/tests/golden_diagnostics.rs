@@ -0,0 +1,26 @@
+use cairo_m_compiler::{CompilerError, CompilerOptions, compile_cairo, format_diagnostics};
+use cairo_m_test_utils::golden::GoldenRunner;
+
+/// Golden-file tests of the diagnostics reported for the programs of the `golden` directory,
+/// which are all expected to fail to compile.
+///
+/// Run with `CAIRO_M_BLESS=1` to update the expected diagnostics.
+
+#[test]
+fn test_golden_diagnostics() {
+    GoldenRunner::new(|source, name| {
+        match compile_cairo(
+            source.to_string(),
+            name.to_string(),
+            CompilerOptions::default(),
+        ) {
+            Err(CompilerError::ParseErrors(diagnostics))
+            | Err(CompilerError::SemanticErrors(diagnostics)) => {
+                Some(format_diagnostics(source, &diagnostics, false))
+            }
+            Err(error) => Some(error.to_string()),
+            Ok(_) => None,
+        }
+    })
+    .run_all();
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use cairo_m_compiler::{
+    CompilerError, CompilerOptions, CompilerOutput, LintLevel, compile_project,
+    create_compiler_database,
+};
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+use cairo_m_project::discover_project;
+
+/// Tests for the `[lints]` table of the manifest and `CompilerOptions::lint_levels`.
+
+const SOURCE: &str = r#"
+fn main() -> felt {
+    let unused = 1;
+    return 2;
+}
+"#;
+
+/// Compiles a project made of `SOURCE`, whose manifest ends with `lints`.
+fn compile_with_lints(
+    lints: &str,
+    options: CompilerOptions,
+) -> Result<CompilerOutput, CompilerError> {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("cairom.toml"),
+        format!("name = \"lints\"\nentry_point = \"main.cm\"\n{lints}"),
+    )
+    .unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/main.cm"), SOURCE).unwrap();
+
+    let project = discover_project(dir.path()).unwrap().unwrap();
+    compile_project(&create_compiler_database(), project, options)
+}
+
+fn diagnostic_codes(output: &CompilerOutput) -> Vec<DiagnosticCode> {
+    output.diagnostics.iter().map(|d| d.code).collect()
+}
+
+#[test]
+fn test_warnings_are_reported_by_default() {
+    let output = compile_with_lints("", CompilerOptions::default()).unwrap();
+    assert_eq!(
+        diagnostic_codes(&output),
+        vec![DiagnosticCode::UnusedVariable]
+    );
+}
+
+#[test]
+fn test_allowed_lint_is_not_reported() {
+    let output = compile_with_lints(
+        "[lints]\nunused_variable = \"allow\"",
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    assert!(output.diagnostics.is_empty());
+}
+
+#[test]
+fn test_denied_warnings_fail_the_build() {
+    match compile_with_lints("[lints]\nwarnings = \"deny\"", CompilerOptions::default()) {
+        Err(CompilerError::SemanticErrors(errors)) => {
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].code, DiagnosticCode::UnusedVariable);
+        }
+        other => panic!("expected a semantic error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_specific_lint_takes_precedence_over_warnings() {
+    let output = compile_with_lints(
+        "[lints]\nwarnings = \"deny\"\nunused_variable = \"warn\"",
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        diagnostic_codes(&output),
+        vec![DiagnosticCode::UnusedVariable]
+    );
+}
+
+#[test]
+fn test_options_take_precedence_over_manifest() {
+    let options = CompilerOptions {
+        lint_levels: BTreeMap::from([(DiagnosticCode::UnusedVariable, LintLevel::Allow)]),
+        ..Default::default()
+    };
+    let output = compile_with_lints("[lints]\nwarnings = \"deny\"", options).unwrap();
+    assert!(output.diagnostics.is_empty());
+}
+
+#[test]
+fn test_unknown_lint() {
+    assert!(matches!(
+        compile_with_lints("[lints]\nunused_variables = \"allow\"", CompilerOptions::default()),
+        Err(CompilerError::UnknownLint(lint)) if lint == "unused_variables"
+    ));
+}
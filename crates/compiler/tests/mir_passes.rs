@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cairo_m_compiler::{CompilerError, CompilerOptions, compile_cairo, resolve_mir_passes};
+use cairo_m_compiler_mir::{MirFunction, MirPass, PassKind};
+use cairo_m_project::ProfileManifest;
+
+/// Tests for `CompilerOptions::extra_mir_passes`, which runs custom passes in the MIR pipeline,
+/// and `CompilerOptions::mir_passes`, which replaces the built-in optimizations.
+
+const SOURCE: &str = r#"
+fn square(x: felt) -> felt {
+    return x * x;
+}
+
+fn main() -> felt {
+    return square(3);
+}
+"#;
+
+/// Records the names of the functions it runs on
+struct RecordingPass {
+    seen: Rc<RefCell<Vec<String>>>,
+}
+
+impl MirPass for RecordingPass {
+    fn run(&mut self, function: &mut MirFunction) -> bool {
+        self.seen.borrow_mut().push(function.name.clone());
+        false
+    }
+
+    fn name(&self) -> &'static str {
+        "RecordingPass"
+    }
+}
+
+fn compile_with_recording_pass(options: CompilerOptions) -> Vec<String> {
+    let seen = Rc::default();
+    let options = CompilerOptions {
+        extra_mir_passes: vec![Box::new(RecordingPass {
+            seen: Rc::clone(&seen),
+        })],
+        ..options
+    };
+    compile_cairo(SOURCE.to_string(), "mir_passes.cm".to_string(), options).unwrap();
+
+    let mut names = seen.borrow().clone();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_extra_passes_run_on_every_function() {
+    assert_eq!(
+        compile_with_recording_pass(CompilerOptions::default()),
+        vec!["main", "square"]
+    );
+}
+
+#[test]
+fn test_extra_passes_run_without_optimizations() {
+    assert_eq!(
+        compile_with_recording_pass(CompilerOptions::no_opts()),
+        vec!["main", "square"]
+    );
+}
+
+#[test]
+fn test_extra_passes_are_listed_in_debug_output() {
+    let options = CompilerOptions {
+        extra_mir_passes: vec![Box::new(RecordingPass {
+            seen: Rc::default(),
+        })],
+        ..Default::default()
+    };
+    assert!(format!("{options:?}").contains("extra_mir_passes: [\"RecordingPass\"]"));
+}
+
+#[test]
+fn test_custom_pass_order() {
+    let options = CompilerOptions {
+        mir_passes: Some(PassKind::parse_list("sroa, cse,dce").unwrap()),
+        ..Default::default()
+    };
+    let output = compile_cairo(SOURCE.to_string(), "mir_passes.cm".to_string(), options).unwrap();
+    assert!(output.program.entrypoints.contains_key("main"));
+}
+
+#[test]
+fn test_resolve_mir_passes() {
+    let profile = ProfileManifest {
+        mir_passes: Some("dce,cse".to_string()),
+    };
+    assert_eq!(
+        resolve_mir_passes(&profile, None).unwrap(),
+        Some(vec![PassKind::Dce, PassKind::Cse])
+    );
+    // The options take precedence over the profile
+    assert_eq!(
+        resolve_mir_passes(&profile, Some(vec![PassKind::Sroa])).unwrap(),
+        Some(vec![PassKind::Sroa])
+    );
+    assert_eq!(
+        resolve_mir_passes(&ProfileManifest::default(), None).unwrap(),
+        None
+    );
+
+    let invalid = ProfileManifest {
+        mir_passes: Some("sroa,inline".to_string()),
+    };
+    assert!(matches!(
+        resolve_mir_passes(&invalid, None),
+        Err(CompilerError::InvalidMirPasses(message)) if message.contains("'inline'")
+    ));
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+
+use cairo_m_compiler::CompilerOptions;
+use cairo_m_compiler::watch::{WatchBuild, WatchSession};
+use cairo_m_compiler_diagnostics::DiagnosticCode;
+use cairo_m_project::discover_project;
+
+/// Tests for the incremental rebuilds of `WatchSession`.
+
+const SOURCE: &str = r#"
+fn main() -> felt {
+    return 2;
+}
+"#;
+
+const SOURCE_WITH_UNUSED: &str = r#"
+fn main() -> felt {
+    let unused = 1;
+    return 2;
+}
+"#;
+
+fn write_project(root: &Path, source: &str) {
+    fs::write(
+        root.join("cairom.toml"),
+        "name = \"watch\"\nentry_point = \"main.cm\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/main.cm"), source).unwrap();
+}
+
+fn build(session: &mut WatchSession, root: &Path) -> WatchBuild {
+    let project = discover_project(root).unwrap().unwrap();
+    session.build(
+        &[(&project, None)],
+        &project,
+        &mut CompilerOptions::default(),
+    )
+}
+
+fn new_codes(build: &WatchBuild) -> Vec<DiagnosticCode> {
+    build.new_diagnostics.iter().map(|d| d.code).collect()
+}
+
+#[test]
+fn test_rebuild_reports_only_changed_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(dir.path(), SOURCE_WITH_UNUSED);
+    let mut session = WatchSession::new();
+
+    let first = build(&mut session, dir.path());
+    assert!(first.result.is_ok());
+    assert_eq!(new_codes(&first), vec![DiagnosticCode::UnusedVariable]);
+
+    // Unchanged diagnostics are not reported again
+    let second = build(&mut session, dir.path());
+    assert!(second.result.is_ok());
+    assert!(second.new_diagnostics.is_empty());
+    assert_eq!(second.resolved_diagnostics, 0);
+
+    fs::write(dir.path().join("src/main.cm"), SOURCE).unwrap();
+    let third = build(&mut session, dir.path());
+    assert!(third.result.is_ok());
+    assert!(third.new_diagnostics.is_empty());
+    assert_eq!(third.resolved_diagnostics, 1);
+}
+
+#[test]
+fn test_rebuild_picks_up_errors_and_fixes() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(dir.path(), SOURCE);
+    let mut session = WatchSession::new();
+    assert!(build(&mut session, dir.path()).result.is_ok());
+
+    fs::write(
+        dir.path().join("src/main.cm"),
+        "fn main() -> felt {\n    return missing;\n}\n",
+    )
+    .unwrap();
+    let broken = build(&mut session, dir.path());
+    assert!(broken.result.is_err());
+    assert_eq!(new_codes(&broken), vec![DiagnosticCode::UndeclaredVariable]);
+
+    fs::write(dir.path().join("src/main.cm"), SOURCE).unwrap();
+    let fixed = build(&mut session, dir.path());
+    assert!(fixed.result.is_ok());
+    assert_eq!(fixed.resolved_diagnostics, 1);
+}
+
+#[test]
+fn test_rebuild_picks_up_new_modules() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(dir.path(), SOURCE);
+    let mut session = WatchSession::new();
+    assert!(build(&mut session, dir.path()).result.is_ok());
+
+    fs::write(
+        dir.path().join("src/math.cm"),
+        "fn double(x: felt) -> felt {\n    return x * 2;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("src/main.cm"),
+        "use math::double;\n\nfn main() -> felt {\n    return double(2);\n}\n",
+    )
+    .unwrap();
+    let rebuilt = build(&mut session, dir.path());
+    assert!(rebuilt.result.is_ok(), "{:?}", rebuilt.result);
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use cairo_m_compiler::{
+    CompilerError, CompilerOptions, compile_workspace, compile_workspace_member,
+    create_compiler_database, format_diagnostics_multi_file,
+};
+use cairo_m_project::discover_workspace;
+
+/// Tests for the diagnostics of workspace members, whose files may share names across packages.
+
+fn write_member(root: &Path, name: &str, manifest: &str, source: &str) {
+    let member_dir = root.join(name);
+    fs::create_dir_all(member_dir.join("src")).unwrap();
+    fs::write(
+        member_dir.join("cairom.toml"),
+        format!("name = \"{name}\"\nentry_point = \"lib.cm\"\n{manifest}"),
+    )
+    .unwrap();
+    fs::write(member_dir.join("src/lib.cm"), source).unwrap();
+}
+
+#[test]
+fn test_workspace_diagnostics_name_their_module() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(
+        root.join("cairom.toml"),
+        "[workspace]\nmembers = [\"math\", \"app\"]\n",
+    )
+    .unwrap();
+    write_member(
+        root,
+        "math",
+        "",
+        "fn add(x: felt) -> felt {\n    let unused = 1;\n    return x + x;\n}\n",
+    );
+    write_member(
+        root,
+        "app",
+        "dependencies = [\"math\"]\n",
+        "use math::lib::add;\n\nfn main() -> felt {\n    let unused = 2;\n    return add(1);\n}\n",
+    );
+
+    let workspace = discover_workspace(root).unwrap();
+    let (app, _) = workspace.project_by_name("app").unwrap();
+    let output = compile_workspace_member(
+        &create_compiler_database(),
+        &workspace,
+        app,
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let mut module_paths: Vec<_> = output
+        .diagnostics
+        .iter()
+        .map(|d| d.module_path.as_deref().unwrap())
+        .collect();
+    module_paths.sort_unstable();
+    assert_eq!(module_paths, vec!["app::lib", "math::lib"]);
+
+    // Both files are named `src/lib.cm`: each diagnostic is rendered with its own source
+    let source_map: HashMap<String, String> = output
+        .diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.file_path.clone(),
+                fs::read_to_string(&d.file_path).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(source_map.len(), 2);
+    let formatted = format_diagnostics_multi_file(&source_map, &output.diagnostics, false);
+    assert!(formatted.contains("math::lib: "));
+    assert!(formatted.contains("app::lib: "));
+    assert!(formatted.contains("let unused = 1;"));
+    assert!(formatted.contains("let unused = 2;"));
+}
+
+#[test]
+fn test_workspace_dependency_cycle_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(
+        root.join("cairom.toml"),
+        "[workspace]\nmembers = [\"math\", \"app\"]\n",
+    )
+    .unwrap();
+    write_member(
+        root,
+        "math",
+        "",
+        "fn add(x: felt) -> felt {\n    return x + x;\n}\n",
+    );
+    write_member(
+        root,
+        "app",
+        "dependencies = [\"math\"]\n",
+        "use math::lib::add;\n\nfn main() -> felt {\n    return add(1);\n}\n",
+    );
+
+    // Discovery rejects cycles: introduce one afterwards
+    let mut workspace = discover_workspace(root).unwrap();
+    let (math, _) = workspace.project_by_name("math").unwrap();
+    let (app, _) = workspace.project_by_name("app").unwrap();
+    workspace.dependencies.entry(math).or_default().push(app);
+
+    let err = compile_workspace(
+        &create_compiler_database(),
+        &workspace,
+        CompilerOptions::default(),
+    )
+    .unwrap_err();
+    let CompilerError::DependencyCycle(cycle) = err else {
+        panic!("expected a dependency cycle, got {err:?}");
+    };
+    assert_eq!(cycle.len(), 3);
+    assert_eq!(cycle.first(), cycle.last());
+    assert!(cycle.contains(&"math".to_string()) && cycle.contains(&"app".to_string()));
+}
@@ -8,7 +8,7 @@ use std::path::Path;
 
 use cairo_m_common::{InputValue, Program};
 use cairo_m_compiler::{CompilerOptions, compile_cairo};
-use cairo_m_prover::adapter::import_from_runner_output;
+use cairo_m_prover::adapter::{import_from_runner_output, import_from_runner_output_ref};
 use cairo_m_prover::prover::prove_cairo_m;
 use cairo_m_runner::run_cairo_program;
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
@@ -68,6 +68,36 @@ fn main() {
 
     eprintln!("Fibonacci finished. Peak memory usage: {} bytes", peak_mem);
 
+    // Same run, imported from the borrowed runner output, which is dropped before
+    // the Merkle trees are built.
+    let borrowed_peak_mem = {
+        let program = compile_fibonacci();
+
+        let runner_output = run_cairo_program(
+            &program,
+            "fibonacci_loop",
+            &[InputValue::Number(N_ITERATIONS as i64)],
+            Default::default(),
+        )
+        .expect("Failed to run fibonacci program");
+
+        PEAK_ALLOC.reset_peak_usage();
+
+        let partial_input =
+            import_from_runner_output_ref(&runner_output).expect("Failed to import runner output");
+        drop(runner_output);
+        let mut prover_input = partial_input.build_merkle_trees();
+
+        let _proof =
+            prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).expect("Proving failed");
+        PEAK_ALLOC.peak_usage()
+    };
+
+    eprintln!(
+        "Fibonacci (borrowed import) finished. Peak memory usage: {} bytes",
+        borrowed_peak_mem
+    );
+
     // =============================
     // SHA-256 (1024-byte message)
     // =============================
@@ -106,11 +136,15 @@ fn main() {
     "name": "fibonacci_prove_peak_mem",
     "unit": "bytes",
     "value": {}
+}}, {{
+    "name": "fibonacci_prove_peak_mem_borrowed_import",
+    "unit": "bytes",
+    "value": {}
 }}, {{
     "name": "sha256_1kb_prove_peak_mem",
     "unit": "bytes",
     "value": {}
 }}]"#,
-        peak_mem, sha_peak_mem
+        peak_mem, borrowed_peak_mem, sha_peak_mem
     );
 }
@@ -0,0 +1,310 @@
+//! On-disk cache of prover inputs.
+//!
+//! Executing a program and adapting its trace into a [`ProverInput`] only depends on the
+//! program, its entrypoint, its arguments and its environment, yet they are redone for every
+//! proof. [`TraceCache`] persists the prover input of an execution in a directory, in one
+//! file per execution, so that proving the same execution again, e.g. with another PCS
+//! configuration or hasher, skips the VM and the adapter.
+//!
+//! A cache file is trusted no more than a fresh execution: the prover input it holds is
+//! validated before proving, and its public initial memory must hold the program and the
+//! arguments it is loaded for, see [`crate::adapter::validation::validate_initial_memory`].
+//!
+//! ## File Format
+//!
+//! 1. Magic bytes `CMTR` and the format version, as a little-endian `u32`
+//! 2. The prover input, encoded with the standard configuration of `bincode`
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use blake2::{Blake2s256, Digest};
+use cairo_m_common::{InputValue, Program, ProgramMetadata};
+use stwo_prover::core::fields::m31::M31;
+
+use crate::adapter::ProverInput;
+
+const MAGIC: &[u8; 4] = b"CMTR";
+const FORMAT_VERSION: u32 = 3;
+
+/// Cache of prover inputs, stored in a directory.
+#[derive(Debug, Clone)]
+pub struct TraceCache {
+    dir: PathBuf,
+}
+
+impl TraceCache {
+    /// Creates a cache storing its files in `dir`. The directory is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the directory holding the cache files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cache key of an execution.
+    ///
+    /// The key is the Blake2s hash of the format version, of the program, and of the
+    /// entrypoint, arguments and environment it is run with, each prefixed by its length.
+    ///
+    /// The program is hashed in its binary encoding, whose entrypoints are sorted by name,
+    /// without its compilation timestamp, so that the key is the same in every process and
+    /// for every compilation of the same program.
+    pub fn key(
+        program: &Program,
+        entrypoint: &str,
+        arguments: &[InputValue],
+        environment: &[M31],
+    ) -> String {
+        let mut hasher = Blake2s256::new();
+        let mut update = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        update(&FORMAT_VERSION.to_le_bytes());
+        let canonical = Program {
+            metadata: ProgramMetadata {
+                compiled_at: None,
+                ..program.metadata.clone()
+            },
+            ..program.clone()
+        };
+        update(&canonical.to_binary());
+        update(entrypoint.as_bytes());
+        update(&sonic_rs::to_vec(arguments).unwrap_or_default());
+        update(
+            &environment
+                .iter()
+                .flat_map(|value| value.0.to_le_bytes())
+                .collect::<Vec<_>>(),
+        );
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Returns the path of the cache file for an execution key.
+    pub fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("trace_{key}.bin"))
+    }
+
+    /// Loads the prover input of an execution from the cache.
+    ///
+    /// ## Returns
+    ///
+    /// `Ok(None)` if there is no cache file for this execution.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the file can't be read or isn't a valid cache file.
+    pub fn load(&self, key: &str) -> io::Result<Option<ProverInput>> {
+        let bytes = match fs::read(self.path_for(key)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let header = [MAGIC.as_slice(), &FORMAT_VERSION.to_le_bytes()].concat();
+        let Some(encoded) = bytes.strip_prefix(header.as_slice()) else {
+            return Err(invalid_data("unknown file format"));
+        };
+
+        let (input, len) = bincode::serde::decode_from_slice(encoded, bincode::config::standard())
+            .map_err(|err| invalid_data(&err.to_string()))?;
+        if len != encoded.len() {
+            return Err(invalid_data("trailing bytes"));
+        }
+        Ok(Some(input))
+    }
+
+    /// Stores the prover input of an execution in the cache.
+    ///
+    /// The file is written to a temporary path first, then renamed, so that
+    /// concurrent provers never observe a partially written file.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the directory or the file can't be written.
+    pub fn store(&self, key: &str, input: &ProverInput) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend(
+            bincode::serde::encode_to_vec(input, bincode::config::standard())
+                .map_err(|err| invalid_data(&err.to_string()))?,
+        );
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path_for(key);
+        let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_m_compiler::{CompilerOptions, compile_cairo};
+    use cairo_m_runner::run_cairo_program;
+    use cairo_m_test_utils::read_fixture;
+
+    use super::*;
+    use crate::adapter::import_from_runner_output;
+
+    fn compile(source: String) -> Program {
+        let output = compile_cairo(
+            source,
+            "fibonacci.cm".to_string(),
+            CompilerOptions::default(),
+        )
+        .unwrap();
+        (*output.program).clone()
+    }
+
+    fn program() -> Program {
+        compile(read_fixture("functions/fibonacci.cm"))
+    }
+
+    fn prover_input(program: &Program) -> ProverInput {
+        let runner_output =
+            run_cairo_program(program, "fib", &[InputValue::Number(5)], Default::default())
+                .unwrap();
+        import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TraceCache::new(dir.path());
+        let program = program();
+        let key = TraceCache::key(&program, "fib", &[InputValue::Number(5)], &[]);
+
+        assert!(cache.load(&key).unwrap().is_none());
+
+        let input = prover_input(&program);
+        cache.store(&key, &input).unwrap();
+        assert!(cache.path_for(&key).exists());
+        assert_eq!(cache.load(&key).unwrap().unwrap(), input);
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_execution() {
+        let program = program();
+        let key = |entrypoint: &str, argument: i64, environment: &[M31]| {
+            TraceCache::key(
+                &program,
+                entrypoint,
+                &[InputValue::Number(argument)],
+                environment,
+            )
+        };
+
+        assert_eq!(key("fib", 5, &[]), key("fib", 5, &[]));
+        assert_ne!(key("fib", 5, &[]), key("fib", 6, &[]));
+        assert_ne!(key("fib", 5, &[]), key("main", 5, &[]));
+        assert_ne!(key("fib", 5, &[]), key("fib", 5, &[M31(1)]));
+
+        let other = compile(read_fixture("functions/fibonacci.cm").replace("n - 2", "n - 3"));
+        assert_ne!(
+            key("fib", 5, &[]),
+            TraceCache::key(&other, "fib", &[InputValue::Number(5)], &[])
+        );
+    }
+
+    #[test]
+    fn test_cache_key_is_canonical() {
+        let with_entrypoints = |names: &[&str], compiled_at: &str| {
+            let mut program = program();
+            let fib = program.entrypoints["fib"].clone();
+            for name in names {
+                program.entrypoints.insert(name.to_string(), fib.clone());
+            }
+            program.metadata.compiled_at = Some(compiled_at.to_string());
+            program
+        };
+        let key =
+            |program: &Program| TraceCache::key(program, "fib", &[InputValue::Number(5)], &[]);
+
+        let names = ["a", "b", "c", "d", "e"];
+        let reversed = names.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(
+            key(&with_entrypoints(&names, "2025-01-01")),
+            key(&with_entrypoints(&reversed, "2025-01-02"))
+        );
+    }
+
+    #[test]
+    fn test_tampered_cache_is_rejected() {
+        use cairo_m_runner::encode_arguments;
+        use stwo_prover::core::fields::qm31::QM31;
+
+        use crate::adapter::validation::{TraceValidationError, validate_initial_memory};
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TraceCache::new(dir.path());
+        let program = program();
+        let arguments = |n: i64| {
+            encode_arguments(
+                &program,
+                "fib",
+                &[InputValue::Number(n)],
+                &Default::default(),
+            )
+            .unwrap()
+        };
+        let key = TraceCache::key(&program, "fib", &[InputValue::Number(5)], &[]);
+        cache.store(&key, &prover_input(&program)).unwrap();
+        let input = cache.load(&key).unwrap().unwrap();
+        assert!(validate_initial_memory(&input, &program, &[], &arguments(5)).is_empty());
+
+        // The input of another execution stored under the key
+        let argument = M31(input.public_address_ranges.input.start);
+        assert_eq!(
+            validate_initial_memory(&input, &program, &[], &arguments(6)),
+            [TraceValidationError::PublicValueMismatch {
+                address: argument,
+                expected: M31(6).into(),
+                found: M31(5).into(),
+            }]
+        );
+
+        // A program word altered in the cache file
+        let mut tampered = input;
+        tampered.memory.initial_memory.get_mut(&M31(0)).unwrap().0 += QM31::from(M31(1));
+        cache.store(&key, &tampered).unwrap();
+        let loaded = cache.load(&key).unwrap().unwrap();
+        assert!(matches!(
+            validate_initial_memory(&loaded, &program, &[], &arguments(5))[..],
+            [TraceValidationError::PublicValueMismatch { address, .. }] if address == M31(0)
+        ));
+    }
+
+    #[test]
+    fn test_corrupted_cache_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = TraceCache::new(dir.path());
+        let program = program();
+        let key = TraceCache::key(&program, "fib", &[InputValue::Number(5)], &[]);
+
+        fs::write(cache.path_for(&key), b"garbage").unwrap();
+        assert!(cache.load(&key).is_err());
+
+        // Truncated file
+        cache.store(&key, &prover_input(&program)).unwrap();
+        let bytes = fs::read(cache.path_for(&key)).unwrap();
+        fs::write(cache.path_for(&key), &bytes[..bytes.len() / 2]).unwrap();
+        assert!(cache.load(&key).is_err());
+    }
+}
@@ -1,16 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::iter::Peekable;
 
-use cairo_m_common::State as VmRegisters;
+use cairo_m_common::execution::ClockUpdate;
 use cairo_m_common::instruction::{INSTRUCTION_MAX_SIZE, Instruction};
 use cairo_m_common::state::MemoryEntry as RunnerMemoryEntry;
+use cairo_m_common::{SegmentKind, State as VmRegisters};
 use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 
 use crate::adapter::io::VmImportError;
 use crate::preprocessed::range_check::range_check_20::LOG_SIZE_RC_20;
+use crate::public_data::{PublicPage, page_ranges};
 
 /// Maximum clock difference that can be handled in a single range check (2^20 - 1)
 pub const RC20_LIMIT: u32 = (1 << LOG_SIZE_RC_20) - 1;
@@ -53,7 +56,7 @@ impl From<crate::adapter::io::IoMemoryEntry> for MemoryEntry {
 /// This structure captures the complete state transition for a memory access
 /// that is required for the memory lookups (use previous and emit new).
 /// Note that the current clock is in the ExecutionBundle.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DataAccess {
     /// The memory address being accessed
     pub address: M31,
@@ -66,7 +69,7 @@ pub struct DataAccess {
 }
 
 /// A contiguous range inside the global access log corresponding to memory accesses for a single instruction.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccessSpan {
     /// Start index in the global access log
     pub start: u32,
@@ -79,7 +82,7 @@ pub struct AccessSpan {
 /// Same as DataAccess but since instruction accesses are only reads, prev_value
 /// is the same as value (contained in Instruction). Also for instructions, the address
 /// is simply the current pc.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InstructionAccess {
     /// The complete instruction that was read from memory
     pub instruction: Instruction,
@@ -97,7 +100,7 @@ pub struct InstructionAccess {
 ///
 /// The execution bundle contains all the necessary data for generating the witnesses
 /// for opcodes. A row of the trace is basically a processed ExecutionBundle.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecutionBundle {
     /// The VM register state at this execution step
     pub registers: VmRegisters,
@@ -164,9 +167,10 @@ struct MemoryArg {
 /// CLOCK UPDATE COMPONENT: The clock update data is used by the clock_update component to add artificial "reads" when the clock difference
 /// is too large. So if a memory access reads/writes in a cell previously accessed at clk_1 with current_clk - clk_1 > RC20_LIMIT,
 /// the prover will:
-///     - use the memory access at clk_1 produce a new one at clk_1 + RC20_LIMIT,
-///     - if necessary, use this last memory access at clk_1 + RC20_LIMIT and produce one at clk_1 + 2*RC20_LIMIT,
-///     - and so on until current_clk - (clk_1 + i*RC20_LIMIT) < RC20_LIMIT.
+///     - use the memory access at clk_1 and produce a new one at the first clock update scheduled by the VM for that cell,
+///     - use this last memory access and produce one at the next scheduled clock update, and so on,
+///     - if the remaining clock difference is still larger than RC20_LIMIT, produce new accesses every RC20_LIMIT steps
+///       until current_clk - (clk_1 + i*RC20_LIMIT) <= RC20_LIMIT.
 ///
 ///
 /// ## Fields
@@ -182,14 +186,14 @@ struct MemoryArg {
 /// - Value: The QM31 value stored
 /// - Clock: When the access occurred
 /// - Multiplicity: can be -1 (for final memory entries), 0 (unused memory entries), 1 (initial entries)
-#[derive(Debug, Default, Eq, PartialEq, Clone)]
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Memory {
     /// Initial memory state: addr => (value, clock, multiplicity)
     pub initial_memory: HashMap<M31, (QM31, M31, M31)>,
     /// Final memory state: (addr, depth) => (value, clock, multiplicity)
     pub final_memory: HashMap<M31, (QM31, M31, M31)>,
-    /// Clock update data for handling large time gaps: (addr, clock, value)
-    pub clock_update_data: Vec<(M31, M31, QM31)>,
+    /// Clock update data for handling large time gaps: (addr, prev_clock, clock, value)
+    pub clock_update_data: Vec<(M31, M31, M31, QM31)>,
 }
 
 /// Iterator that converts runner execution traces into prover execution bundles.
@@ -215,6 +219,8 @@ where
     data_accesses: Vec<DataAccess>,
     /// Execution clock, incremented at each VM step
     clock: u32,
+    /// Clocks of the clock updates scheduled by the VM, by address
+    clock_updates: HashMap<M31, VecDeque<M31>>,
     /// Final register state (captured when trace ends)
     final_registers: Option<VmRegisters>,
 }
@@ -244,10 +250,42 @@ where
             memory: Memory::new(initial_memory),
             data_accesses: Vec::new(),
             clock: 1, // Clock 0 is reserved to preloaded values (like the program, inputs, etc.)
+            clock_updates: HashMap::new(),
             final_registers: None,
         }
     }
 
+    /// Uses the clock updates scheduled by the VM for the segment.
+    ///
+    /// ## Arguments
+    /// * `clock_updates` - Clock updates of the segment, in increasing clock order for each address
+    pub fn with_clock_updates(mut self, clock_updates: &[ClockUpdate]) -> Self {
+        for update in clock_updates {
+            self.clock_updates
+                .entry(update.addr)
+                .or_default()
+                .push_back(update.clock);
+        }
+        self
+    }
+
+    /// Pushes a memory access along with the clock updates of its cell scheduled since the
+    /// previous access.
+    fn push_memory(&mut self, memory_entry: MemoryEntry) -> MemoryArg {
+        let clock_updates = self
+            .clock_updates
+            .get_mut(&memory_entry.address)
+            .map(|clocks| {
+                let count = clocks
+                    .iter()
+                    .take_while(|clock| clock.0 < memory_entry.clock.0)
+                    .count();
+                clocks.drain(..count)
+            });
+        self.memory
+            .push_with_clock_updates(memory_entry, clock_updates.into_iter().flatten())
+    }
+
     pub fn peek_initial_registers(&mut self) -> Option<&VmRegisters> {
         self.trace_iter.peek()
     }
@@ -291,7 +329,7 @@ where
             clock: self.clock.into(),
         };
 
-        let instruction_arg = self.memory.push(instruction_entry);
+        let instruction_arg = self.push_memory(instruction_entry);
 
         // Step 2: Parse opcode from first M31 to determine instruction size
         let opcode_m31 = instruction_entry.value.0.0;
@@ -328,7 +366,7 @@ where
             };
 
             // Push to memory
-            self.memory.push(entry);
+            self.push_memory(entry);
 
             // Extract additional M31 limbs from the second QM31
             if instruction_size_m31 > 4 {
@@ -353,7 +391,7 @@ where
 
         // Step 4: Process operand memory accesses based on instruction's opcode
         // The number and type of memory accesses depends on the instruction
-        let num_operands = instruction.memory_accesses();
+        let num_operands = instruction.traced_memory_accesses();
 
         // Track the contiguous span in the global access log for this step
         let start_idx = self.data_accesses.len() as u32;
@@ -372,7 +410,7 @@ where
                 clock: self.clock.into(),
             };
 
-            let mem_arg = self.memory.push(mem_entry);
+            let mem_arg = self.push_memory(mem_entry);
 
             let data_access: DataAccess = DataAccess {
                 address: mem_arg.address,
@@ -426,7 +464,7 @@ impl Memory {
     /// Updates multiplicities for public address ranges based on their usage patterns
     pub fn update_multiplicities(&mut self, ranges: &cairo_m_common::PublicAddressRanges) {
         // For program and input addresses
-        for addr in ranges.program.clone().chain(ranges.input.clone()) {
+        for addr in ranges.initial_ranges().flatten() {
             let key = M31::from(addr);
 
             // Set initial memory multiplicity to 0
@@ -443,7 +481,7 @@ impl Memory {
         }
 
         // For output addresses
-        for addr in ranges.output.clone() {
+        for addr in ranges.final_ranges().flatten() {
             let key = M31::from(addr);
 
             // Set final memory multiplicity to 0
@@ -460,6 +498,39 @@ impl Memory {
         }
     }
 
+    /// Splits the input and output memory into pages, and commits to their values, see
+    /// [`PublicPage`].
+    ///
+    /// ## Arguments
+    /// * `ranges` - The public address ranges of the execution
+    ///
+    /// ## Returns
+    /// The pages, see [`page_ranges`]
+    pub fn page_public_memory(
+        &self,
+        ranges: &cairo_m_common::PublicAddressRanges,
+    ) -> Vec<PublicPage> {
+        page_ranges(ranges)
+            .map(|(kind, range)| {
+                let memory = match kind {
+                    SegmentKind::Input => &self.initial_memory,
+                    SegmentKind::Output => &self.final_memory,
+                };
+                let values = range
+                    .clone()
+                    .map(|addr| memory.get(&M31(addr)).map(|&(value, _, _)| value))
+                    .collect::<Vec<_>>();
+
+                PublicPage {
+                    kind,
+                    start: M31(range.start),
+                    len: range.len() as u32,
+                    commitment: PublicPage::commit(M31(range.start), &values),
+                }
+            })
+            .collect()
+    }
+
     /// Update Memory with the provided MemoryEntry.
     ///
     /// ## Arguments
@@ -467,7 +538,24 @@ impl Memory {
     ///
     /// ## Returns
     /// A MemoryArg containing the complete memory transition information
+    #[cfg(test)]
     fn push(&mut self, memory_entry: MemoryEntry) -> MemoryArg {
+        self.push_with_clock_updates(memory_entry, std::iter::empty())
+    }
+
+    /// Update Memory with the provided MemoryEntry, after the clock updates of its cell.
+    ///
+    /// ## Arguments
+    /// * `memory_entry` - The new memory access to process
+    /// * `clock_updates` - Clocks of the updates of the cell since its previous access, in increasing order
+    ///
+    /// ## Returns
+    /// A MemoryArg containing the complete memory transition information
+    fn push_with_clock_updates(
+        &mut self,
+        memory_entry: MemoryEntry,
+        clock_updates: impl IntoIterator<Item = M31>,
+    ) -> MemoryArg {
         // No matter what update the final memory with the new memory entry
         // The final memory tracks the "previous data" ie the previous clock and the previous value.
         // - if this memory access is a first write, there won't be any previous entry tracked, in that case
@@ -502,27 +590,25 @@ impl Memory {
             }
         };
 
-        // Because of sparse memory cases (output example mentioned), we need to use the initial memory entry updated as above.
-        // Indeed when writing the output, memory_entry.value is the output but initial_memory.get(addr) is 0 (filled by VM).
-        // The clock update must consume 0 (emitted by the initial memory) and emit 0. The store opcode will be the one consuming
-        // 0 and emitting the actual output.
-        let initial_memory_entry = self.initial_memory.get(&memory_entry.address);
-        // Check for large clock deltas and generate clock update data if needed
-        if current_clk.0 > prev_clk.0 {
-            let delta = current_clk.0 - prev_clk.0;
-            if delta > RC20_LIMIT {
-                // Generate clock update entries for this large delta
-                let num_steps = delta / RC20_LIMIT;
-
-                for _ in 0..num_steps {
-                    self.clock_update_data.push((
-                        memory_entry.address,
-                        prev_clk,
-                        initial_memory_entry.unwrap().0,
-                    ));
-                    prev_clk += M31::from(RC20_LIMIT);
-                }
-            }
+        // Clock updates keep the previous value of the cell. In sparse memory cases (output example mentioned), the
+        // previous value of a first write is the initial memory value: when writing the output, memory_entry.value is
+        // the output but the initial memory holds 0 (filled by VM). The clock update must consume 0 and emit 0. The
+        // store opcode will be the one consuming 0 and emitting the actual output.
+        let prev_val = prev_memory_entry.0;
+        let mut push_clock_update = |prev_clk: &mut M31, clk: M31| {
+            self.clock_update_data
+                .push((memory_entry.address, *prev_clk, clk, prev_val));
+            *prev_clk = clk;
+        };
+
+        // Clock updates scheduled by the VM
+        for clk in clock_updates {
+            push_clock_update(&mut prev_clk, clk);
+        }
+        // Check for remaining large clock deltas and generate clock update data if needed
+        while current_clk.0 > prev_clk.0 && current_clk.0 - prev_clk.0 > RC20_LIMIT {
+            let clk = prev_clk + M31::from(RC20_LIMIT);
+            push_clock_update(&mut prev_clk, clk);
         }
 
         MemoryArg {
@@ -735,6 +821,73 @@ mod tests {
         assert_eq!(update3.1, M31::from(10 + 2 * RC20_LIMIT)); // prev_clk
     }
 
+    #[test]
+    fn test_memory_push_clock_updates_keep_previous_value() {
+        let mut memory = Memory::default();
+        let address = M31::from(100);
+        for (value, clock) in [(1, 10), (2, 20), (3, 20 + 2 * RC20_LIMIT)] {
+            memory.push(MemoryEntry {
+                address,
+                value: QM31::from_u32_unchecked(value, 0, 0, 0),
+                clock: M31::from(clock),
+            });
+        }
+
+        // A delta of exactly 2 * RC20_LIMIT needs a single update, keeping the value written at clock 20
+        let prev_value = QM31::from_u32_unchecked(2, 0, 0, 0);
+        assert_eq!(
+            memory.clock_update_data,
+            vec![(
+                address,
+                M31::from(20),
+                M31::from(20 + RC20_LIMIT),
+                prev_value
+            )]
+        );
+    }
+
+    #[test]
+    fn test_memory_push_scheduled_clock_updates() {
+        let mut memory = Memory::default();
+        let address = M31::from(100);
+        let value = QM31::from_u32_unchecked(1, 2, 3, 4);
+        memory.push(MemoryEntry {
+            address,
+            value,
+            clock: M31::from(10),
+        });
+
+        let result = memory.push_with_clock_updates(
+            MemoryEntry {
+                address,
+                value,
+                clock: M31::from(10 + 3 * RC20_LIMIT + 500),
+            },
+            [M31::from(1000), M31::from(1000 + RC20_LIMIT)],
+        );
+
+        // The scheduled updates are used first, then the remaining delta is split
+        assert_eq!(
+            memory.clock_update_data,
+            vec![
+                (address, M31::from(10), M31::from(1000), value),
+                (
+                    address,
+                    M31::from(1000),
+                    M31::from(1000 + RC20_LIMIT),
+                    value
+                ),
+                (
+                    address,
+                    M31::from(1000 + RC20_LIMIT),
+                    M31::from(1000 + 2 * RC20_LIMIT),
+                    value
+                ),
+            ]
+        );
+        assert_eq!(result.prev_clock, M31::from(1000 + 2 * RC20_LIMIT));
+    }
+
     #[test]
     fn test_memory_push_no_clock_update_for_small_delta() {
         let mut memory = Memory::default();
@@ -855,4 +1008,51 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_page_public_memory() {
+        use cairo_m_common::{PublicAddressRanges, PublicSegment};
+
+        use crate::public_data::PUBLIC_PAGE_SIZE;
+
+        let data = 10..10 + 2 * PUBLIC_PAGE_SIZE + 1;
+        let value = |addr: u32| QM31::from_u32_unchecked(addr, 0, 0, 0);
+        let mut memory = Memory::new(HashMap::from_iter(
+            (0..data.end).map(|addr| (M31(addr), (value(addr), M31::zero(), M31::zero()))),
+        ));
+        let read = data.start + PUBLIC_PAGE_SIZE + 3;
+        memory.push(MemoryEntry {
+            address: M31(read),
+            value: value(read),
+            clock: M31::one(),
+        });
+
+        let ranges = PublicAddressRanges::new(10, 0, 0).with_segment(PublicSegment::new(
+            "data",
+            SegmentKind::Input,
+            data.clone(),
+        ));
+        let pages = memory.page_public_memory(&ranges);
+
+        assert_eq!(
+            pages
+                .iter()
+                .map(|page| (page.start.0, page.len))
+                .collect::<Vec<_>>(),
+            vec![
+                (data.start, PUBLIC_PAGE_SIZE),
+                (data.start + PUBLIC_PAGE_SIZE, PUBLIC_PAGE_SIZE),
+                (data.start + 2 * PUBLIC_PAGE_SIZE, 1),
+            ]
+        );
+        // Every page stays in the trace, read or not
+        assert_eq!(memory.initial_memory.len(), data.end as usize);
+        assert_eq!(memory.final_memory.len(), memory.initial_memory.len());
+        // Commitments cover the values of all the pages
+        assert!(
+            pages
+                .iter()
+                .all(|page| page.matches(|addr| Some(value(addr.0))))
+        );
+    }
 }
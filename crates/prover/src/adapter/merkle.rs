@@ -19,13 +19,18 @@ use std::collections::HashMap;
 
 use cairo_m_common::PublicAddressRanges;
 use num_traits::{One, Zero};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 
 pub use super::HashInput;
 
 /// MerkleValue represents a value in the Merkle tree with its multiplicity
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MerkleValue {
     pub value: M31,
     pub multiplicity: M31,
@@ -88,7 +93,7 @@ pub enum TreeType {
 /// - `left_value`: Hash value of the left child
 /// - `right_value`: Hash value of the right child
 /// - `parent_value`: Computed hash value of this node
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NodeData {
     /// Index of the left child node at this tree depth
     pub index: M31,
@@ -165,7 +170,8 @@ pub trait MerkleHasher {
 ///
 /// ## Process
 /// 1. **Leaf Generation**: Each QM31 memory value is split into 4 M31 leaves
-/// 2. **Tree Construction**: Build from leaves (depth 30) up to depth 1
+/// 2. **Tree Construction**: Build from leaves (depth 30) up to depth 1, hashing the nodes of
+///    each depth in parallel
 /// 3. **Missing Nodes**: Fill gaps with default hashes and add to memory map
 /// 4. **Root Computation**: Calculate single root hash at depth 0
 ///
@@ -184,6 +190,53 @@ pub fn build_partial_merkle_tree<H: MerkleHasher>(
     memory: &HashMap<M31, (QM31, M31, M31)>,
     tree_type: TreeType,
     public_address_ranges: &PublicAddressRanges,
+) -> (Vec<NodeData>, Option<M31>) {
+    build_tree::<H>(memory, tree_type, public_address_ranges, None)
+}
+
+/// Constructs the partial Merkle trees of the initial and final memory.
+///
+/// The final memory holds the same addresses as the initial memory, so both trees have the
+/// same shape: the final tree reuses the hashes of the initial tree for the subtrees whose
+/// values were not modified during the execution, and only hashes the paths of the written
+/// cells. The nodes and roots are the same as with [`build_partial_merkle_tree`].
+///
+/// ## Arguments
+/// * `initial_memory` - Memory state before the execution
+/// * `final_memory` - Memory state after the execution
+/// * `public_address_ranges` - The public address ranges of the execution
+///
+/// ## Returns
+/// The nodes and root of the initial tree, then of the final tree
+pub fn build_partial_merkle_trees<H: MerkleHasher>(
+    initial_memory: &HashMap<M31, (QM31, M31, M31)>,
+    final_memory: &HashMap<M31, (QM31, M31, M31)>,
+    public_address_ranges: &PublicAddressRanges,
+) -> ((Vec<NodeData>, Option<M31>), (Vec<NodeData>, Option<M31>)) {
+    let initial = build_tree::<H>(
+        initial_memory,
+        TreeType::Initial,
+        public_address_ranges,
+        None,
+    );
+    let final_ = build_tree::<H>(
+        final_memory,
+        TreeType::Final,
+        public_address_ranges,
+        Some(&initial.0),
+    );
+    (initial, final_)
+}
+
+/// Builds a partial Merkle tree, hashing the nodes of each depth in parallel.
+///
+/// When a `reference` tree built from the same addresses is given, nodes whose children
+/// have the same values as in the reference reuse its hashes instead of recomputing them.
+fn build_tree<H: MerkleHasher>(
+    memory: &HashMap<M31, (QM31, M31, M31)>,
+    tree_type: TreeType,
+    public_address_ranges: &PublicAddressRanges,
+    reference: Option<&[NodeData]>,
 ) -> (Vec<NodeData>, Option<M31>) {
     if memory.is_empty() {
         return (vec![], None);
@@ -196,100 +249,107 @@ pub fn build_partial_merkle_tree<H: MerkleHasher>(
         MAX_MEMORY_LOG_SIZE
     );
 
-    let mut nodes = Vec::new();
+    // Depth 30 (leaves): convert each QM31 to 4 M31 leaves, sorted by index
+    let mut current_depth_nodes: Vec<(u32, MerkleValue)> = memory
+        .par_iter()
+        .flat_map_iter(|(addr, (value, _, _))| {
+            let base_address = addr.0 << QM31_LOG_SIZE;
 
-    // Depth 30 (leaves): convert each QM31 to 4 M31 leaves
-    let mut current_depth_nodes: HashMap<u32, MerkleValue> = HashMap::new();
-
-    for (addr, (value, _, _)) in memory.iter() {
-        // no intermediate nodes yet so depth is leaf depth
-        let m31_values = value.to_m31_array();
-        let base_address = addr.0 << QM31_LOG_SIZE;
-
-        // Check if this address should have increased multiplicity
-        let is_public_address = match tree_type {
-            TreeType::Initial => {
-                public_address_ranges.program.contains(&addr.0)
-                    || public_address_ranges.input.contains(&addr.0)
-            }
-            TreeType::Final => public_address_ranges.output.contains(&addr.0),
-        };
-
-        for (i, &m31_value) in m31_values.iter().enumerate() {
-            let merkle_value = if is_public_address {
-                MerkleValue::new_public_node(m31_value)
-            } else {
-                MerkleValue::new_node(m31_value)
+            // Check if this address should have increased multiplicity
+            let is_public_address = match tree_type {
+                TreeType::Initial => public_address_ranges.is_initial(addr.0),
+                TreeType::Final => public_address_ranges.is_final(addr.0),
             };
 
-            current_depth_nodes.insert(base_address + i as u32, merkle_value);
-        }
-    }
+            value
+                .to_m31_array()
+                .into_iter()
+                .enumerate()
+                .map(move |(i, m31_value)| {
+                    let merkle_value = if is_public_address {
+                        MerkleValue::new_public_node(m31_value)
+                    } else {
+                        MerkleValue::new_node(m31_value)
+                    };
+                    (base_address + i as u32, merkle_value)
+                })
+        })
+        .collect();
+    current_depth_nodes.par_sort_unstable_by_key(|(index, _)| *index);
+
+    let mut nodes = Vec::new();
+    // Start of the nodes of the current depth in the reference tree
+    let mut reference_start = 0;
 
     // Build tree from leaves (depth 30) up to root excluded (depth 1)
     for depth in (1..=TREE_HEIGHT).rev() {
-        let mut parent_depth_nodes: HashMap<u32, MerkleValue> = HashMap::new();
-
-        // Process all nodes at this depth
-        let mut indices_to_process: Vec<u32> = current_depth_nodes.keys().copied().collect();
-        indices_to_process.sort_unstable();
-
-        let mut processed_indices = std::collections::HashSet::new();
-
-        for &index in &indices_to_process {
-            if processed_indices.contains(&index) {
-                continue;
-            }
-
-            // Get sibling and parent indexes
-            let sibling_index = index ^ 1;
-            let parent_index = index >> 1;
-
-            // Ensure we process both siblings together
-            let (left_index, right_index) = if index % 2 == 0 {
-                (index, sibling_index)
+        let default_value = || MerkleValue::new_intermediate(H::default_hashes()[depth as usize]);
+
+        // Pair each node with its sibling, or with a default node if the sibling is missing
+        let mut pairs = Vec::with_capacity(current_depth_nodes.len());
+        let mut i = 0;
+        while i < current_depth_nodes.len() {
+            let (index, value) = current_depth_nodes[i];
+            let left_index = index & !1;
+            let pair = if index % 2 == 1 {
+                (left_index, default_value(), value)
+            } else if let Some(&(_, right_value)) = current_depth_nodes
+                .get(i + 1)
+                .filter(|(right_index, _)| *right_index == index + 1)
+            {
+                i += 1;
+                (left_index, value, right_value)
             } else {
-                (sibling_index, index)
+                (left_index, value, default_value())
             };
+            pairs.push(pair);
+            i += 1;
+        }
 
-            let left_value = current_depth_nodes
-                .get(&left_index)
-                .copied()
-                .unwrap_or_else(|| {
-                    MerkleValue::new_intermediate(H::default_hashes()[depth as usize])
-                });
-            let right_value = current_depth_nodes
-                .get(&right_index)
-                .copied()
-                .unwrap_or_else(|| {
-                    MerkleValue::new_intermediate(H::default_hashes()[depth as usize])
-                });
-
-            // Calculate parent hash
-            let parent_hash = H::hash(left_value.value, right_value.value);
-            let parent_value = MerkleValue::new_node(parent_hash);
-
-            // Store node data
-            nodes.push(NodeData {
-                index: M31::from(left_index),
-                depth: depth as u8,
-                left_value,
-                right_value,
-                parent_value,
+        // The reference nodes of this depth, if the reference tree has the same shape
+        let reference_nodes = reference
+            .and_then(|reference| reference.get(reference_start..reference_start + pairs.len()))
+            .filter(|reference_nodes| {
+                reference_nodes
+                    .first()
+                    .map(|node| (node.index.0, node.depth))
+                    == Some((pairs[0].0, depth as u8))
             });
-
-            // Store parent value for next depth
-            parent_depth_nodes.insert(parent_index, parent_value);
-
-            // Mark both indices as processed
-            processed_indices.insert(left_index);
-            processed_indices.insert(right_index);
-        }
-        current_depth_nodes = parent_depth_nodes;
+        reference_start += pairs.len();
+
+        let depth_nodes: Vec<NodeData> = pairs
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, (left_index, left_value, right_value))| {
+                let reused_hash = reference_nodes
+                    .map(|reference_nodes| &reference_nodes[i])
+                    .filter(|node| {
+                        node.index.0 == left_index
+                            && node.left_value.value == left_value.value
+                            && node.right_value.value == right_value.value
+                    })
+                    .map(|node| node.parent_value.value);
+                let parent_hash =
+                    reused_hash.unwrap_or_else(|| H::hash(left_value.value, right_value.value));
+                NodeData {
+                    index: M31::from(left_index),
+                    depth: depth as u8,
+                    left_value,
+                    right_value,
+                    parent_value: MerkleValue::new_node(parent_hash),
+                }
+            })
+            .collect();
+
+        current_depth_nodes = depth_nodes
+            .iter()
+            .map(|node| (node.index.0 >> 1, node.parent_value))
+            .collect();
+        nodes.extend(depth_nodes);
     }
 
     assert_eq!(current_depth_nodes.len(), 1);
-    let root_value = current_depth_nodes[&0].value;
+    let root_value = current_depth_nodes[0].1.value;
 
     (nodes, Some(root_value))
 }
@@ -421,4 +481,55 @@ mod tests {
             TREE_HEIGHT
         );
     }
+
+    /// Poseidon2 hasher counting its hashes
+    struct CountingHash;
+
+    static HASH_COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    impl MerkleHasher for CountingHash {
+        fn hash(left: M31, right: M31) -> M31 {
+            HASH_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Poseidon2Hash::hash(left, right)
+        }
+
+        fn default_hashes() -> &'static [M31] {
+            Poseidon2Hash::default_hashes()
+        }
+    }
+
+    #[test]
+    fn test_final_tree_reuses_untouched_subtrees() {
+        let initial_memory: HashMap<_, _> = (0..8u32)
+            .map(|addr| {
+                (
+                    M31::from(addr),
+                    (QM31::from(addr + 1), M31::zero(), M31::zero()),
+                )
+            })
+            .collect();
+        let mut final_memory = initial_memory.clone();
+        final_memory.insert(M31::from(3), (QM31::from(42), M31::one(), M31::zero()));
+        let ranges = PublicAddressRanges::new(2, 1, 1);
+
+        let (initial, final_) =
+            build_partial_merkle_trees::<CountingHash>(&initial_memory, &final_memory, &ranges);
+        let initial_hashes = initial.0.len();
+        // Only the path of the written cell is hashed again
+        assert_eq!(
+            HASH_COUNT.load(std::sync::atomic::Ordering::Relaxed),
+            initial_hashes + TREE_HEIGHT as usize
+        );
+
+        // The trees are the same as when built from scratch
+        assert_eq!(
+            initial,
+            build_partial_merkle_tree::<Poseidon2Hash>(&initial_memory, TreeType::Initial, &ranges)
+        );
+        assert_eq!(
+            final_,
+            build_partial_merkle_tree::<Poseidon2Hash>(&final_memory, TreeType::Final, &ranges)
+        );
+        assert_ne!(initial.1, final_.1);
+    }
 }
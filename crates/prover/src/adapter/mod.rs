@@ -1,30 +1,39 @@
+pub mod cache;
 pub mod io;
 pub mod memory;
 pub mod merkle;
+pub mod validation;
 
 use std::collections::HashMap;
 use std::path::Path;
 
-use cairo_m_common::execution::Segment;
+use cairo_m_common::execution::{ClockUpdate, Segment};
+use cairo_m_common::instruction::{
+    POSEIDON2_ABSORB_FP_FP, POSEIDON2_DIGEST_SIZE, POSEIDON2_SQUEEZE_FP_FP, poseidon2_absorb_input,
+    poseidon2_squeeze_input,
+};
 use cairo_m_common::state::MemoryEntry as RunnerMemoryEntry;
 use cairo_m_common::{PublicAddressRanges, State as VmRegisters};
+use cairo_m_runner::RunnerOutput;
 use io::VmImportError;
 pub use memory::ExecutionBundle;
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
-use tracing::{Level, span};
+use tracing::{Level, field, span};
 
 use crate::adapter::io::{MemoryEntryFileIter, TraceFileIter};
 use crate::adapter::memory::{DataAccess, ExecutionBundleIterator, Memory};
-use crate::adapter::merkle::{NodeData, TreeType, build_partial_merkle_tree};
+use crate::adapter::merkle::{NodeData, build_partial_merkle_trees};
 use crate::poseidon2::{Poseidon2Hash, T};
+use crate::public_data::PublicPage;
 
 /// Hash input type for the merkle tree component (T M31 elements)
 pub type HashInput = [M31; T];
 
 /// Input data structure for proof generation.
 /// Contains all the hints for witness generation and the public data.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ProverInput {
     /// Merkle tree commitments for initial and final memory states
     pub merkle_trees: MerkleTrees,
@@ -34,8 +43,12 @@ pub struct ProverInput {
     pub instructions: Instructions,
     /// List of public memory addresses (program/inputs/outputs)
     pub public_address_ranges: PublicAddressRanges,
+    /// Pages of the input and output memory, with their commitments
+    pub public_pages: Vec<PublicPage>,
     /// Hash inputs for Poseidon2 computations in Merkle trees
     pub poseidon2_inputs: Vec<HashInput>,
+    /// Hash inputs for Poseidon2 computations in sponge instructions
+    pub poseidon2_sponge_inputs: Vec<HashInput>,
 }
 
 /// Merkle tree commitments for initial and final memory states for continuation.
@@ -44,7 +57,7 @@ pub struct ProverInput {
 /// MERKLE COMPONENT: only component using these hints.
 ///
 /// Each merkle tree contains a vec of tree nodes. The root is also stored.
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MerkleTrees {
     /// Vec of nodes for the initial memory state
     pub initial_tree: Vec<NodeData>,
@@ -61,7 +74,7 @@ pub struct MerkleTrees {
 /// ## For which component ?
 /// OPCODE COMPONENTS: a row of an opcode component's trace requires only the execution bundle for that opcode.
 /// PUBLIC DATA (not a component): initial and final registers are emitted/consumed by the public data.
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Instructions {
     /// VM register state at the start of execution (PC, FP)
     pub initial_registers: VmRegisters,
@@ -73,6 +86,113 @@ pub struct Instructions {
     pub data_accesses: Vec<DataAccess>,
 }
 
+impl Instructions {
+    /// Returns the data memory accesses of an execution bundle.
+    fn accesses_of(&self, bundle: &ExecutionBundle) -> &[DataAccess] {
+        let start = bundle.access_span.start as usize;
+        &self.data_accesses[start..start + bundle.access_span.len as usize]
+    }
+
+    /// Returns the inputs of the Poseidon2 permutations of the sponge instructions.
+    ///
+    /// The absorbed state and block, or the squeezed state and length, are read from the
+    /// memory accesses of the instructions, in the order of the VM.
+    pub fn poseidon2_sponge_inputs(&self) -> impl Iterator<Item = HashInput> + '_ {
+        let bundles = |opcode: u32| self.states_by_opcodes.get(&opcode).into_iter().flatten();
+        let absorbs = bundles(POSEIDON2_ABSORB_FP_FP).map(|bundle| {
+            let accesses = self.accesses_of(bundle);
+            poseidon2_absorb_input(
+                std::array::from_fn(|i| accesses[i].value),
+                std::array::from_fn(|i| accesses[POSEIDON2_DIGEST_SIZE + i].value),
+            )
+        });
+        let squeezes = bundles(POSEIDON2_SQUEEZE_FP_FP).map(|bundle| {
+            let accesses = self.accesses_of(bundle);
+            poseidon2_squeeze_input(
+                std::array::from_fn(|i| accesses[i].value),
+                accesses[POSEIDON2_DIGEST_SIZE].value,
+            )
+        });
+        absorbs.chain(squeezes)
+    }
+}
+
+/// Prover input whose Merkle trees are not built yet.
+///
+/// Building the Merkle trees of the initial and final memory roughly doubles the memory
+/// footprint of the input. When importing from a borrowed [`RunnerOutput`], the runner
+/// output can be dropped before calling [`Self::build_merkle_trees`], so that the
+/// execution trace and the Merkle trees are never alive at the same time.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PartialProverInput {
+    /// Boundary memory states and clock update data
+    pub memory: Memory,
+    /// Execution bundles organized by opcode for opcode witness generation
+    pub instructions: Instructions,
+    /// List of public memory addresses (program/inputs/outputs)
+    pub public_address_ranges: PublicAddressRanges,
+    /// Pages of the input and output memory, with their commitments
+    pub public_pages: Vec<PublicPage>,
+}
+
+impl PartialProverInput {
+    /// Builds the Merkle trees of the initial and final memory and the Poseidon2 inputs
+    /// used to prove their construction.
+    pub fn build_merkle_trees(self) -> ProverInput {
+        let _span = span!(
+            Level::INFO,
+            "build_merkle_trees",
+            initial_cells = self.memory.initial_memory.len(),
+            final_cells = self.memory.final_memory.len()
+        )
+        .entered();
+
+        let Self {
+            memory,
+            instructions,
+            public_address_ranges,
+            public_pages,
+        } = self;
+
+        // Build partial Merkle trees for memory commitments, the final tree reusing the
+        // hashes of the cells left untouched by the execution.
+        let ((initial_tree, initial_root), (final_tree, final_root)) =
+            build_partial_merkle_trees::<Poseidon2Hash>(
+                &memory.initial_memory,
+                &memory.final_memory,
+                &public_address_ranges,
+            );
+
+        // Extract Poseidon2 inputs from merkle trees.
+        // This data is used for the Poseidon2 component
+        let mut poseidon2_inputs =
+            Vec::<HashInput>::with_capacity(initial_tree.len() + final_tree.len());
+        initial_tree.iter().for_each(|node| {
+            poseidon2_inputs.push(node.to_hash_input());
+        });
+        final_tree.iter().for_each(|node| {
+            poseidon2_inputs.push(node.to_hash_input());
+        });
+        // The sponge instructions also use the Poseidon2 component
+        let poseidon2_sponge_inputs = instructions.poseidon2_sponge_inputs().collect();
+
+        ProverInput {
+            merkle_trees: MerkleTrees {
+                initial_tree,
+                final_tree,
+                initial_root,
+                final_root,
+            },
+            memory,
+            public_address_ranges,
+            public_pages,
+            instructions,
+            poseidon2_inputs,
+            poseidon2_sponge_inputs,
+        }
+    }
+}
+
 /// Internal function to convert runner output to prover input format.
 ///
 /// This is the core transformation logic that processes execution traces and
@@ -81,30 +201,35 @@ pub struct Instructions {
 /// ## Process Overview
 /// 1. **Bundle Generation** - Convert raw traces to execution bundles
 /// 2. **Opcode Grouping** - Organize bundles by opcode for components
-/// 3. **Public Address Handling** - Adjust multiplicities for public data
-/// 4. **Merkle Tree Construction** - Merkle tree data used to prove merkle tree construction
-/// 5. **Hash Collection** - Poseidon2 inputs used to prove hash computation
+/// 3. **Public Address Handling** - Adjust multiplicities for public data, and commit to the
+///    pages of public memory
+///
+/// The Merkle trees and Poseidon2 inputs are built separately by
+/// [`PartialProverInput::build_merkle_trees`].
 ///
 /// ## Arguments
 /// * `trace_iter` - Iterator over VM register states
 /// * `memory_iter` - Iterator over memory access entries
 /// * `initial_memory` - Initial memory state as QM31 values
+/// * `clock_updates` - Clock updates scheduled by the VM
 /// * `public_addresses` - List of public addresses
 ///
 /// ## Returns
-/// * `Ok(ProverInput)` - Complete prover input data
+/// * `Ok(PartialProverInput)` - Prover input without the Merkle trees
 /// * `Err(VmImportError)` - Import failed due to invalid trace data
 fn import_internal<TraceIter, MemoryIter>(
     trace_iter: TraceIter,
     memory_iter: MemoryIter,
     initial_memory: HashMap<M31, (QM31, M31, M31)>,
+    clock_updates: &[ClockUpdate],
     public_address_ranges: PublicAddressRanges,
-) -> Result<ProverInput, VmImportError>
+) -> Result<PartialProverInput, VmImportError>
 where
     TraceIter: Iterator<Item = VmRegisters>,
     MemoryIter: Iterator<Item = RunnerMemoryEntry>,
 {
-    let mut bundle_iter = ExecutionBundleIterator::new(trace_iter, memory_iter, initial_memory);
+    let mut bundle_iter = ExecutionBundleIterator::new(trace_iter, memory_iter, initial_memory)
+        .with_clock_updates(clock_updates);
     let mut states_by_opcodes = HashMap::<u32, Vec<ExecutionBundle>>::default();
 
     // Get initial registers by peeking at the trace
@@ -136,6 +261,8 @@ where
     let (mut memory, data_accesses) = bundle_iter.into_memory_and_data_accesses();
     // Extract global access log for opcode components consumption
     memory.update_multiplicities(&public_address_ranges);
+    // Commit to the pages of public memory
+    let public_pages = memory.page_public_memory(&public_address_ranges);
 
     // Assert that the keys are the same for both initial_memory and final_memory
     // This is a sanity check that uses memory so it's deactivated in release builds.
@@ -149,46 +276,16 @@ where
         );
     }
 
-    // Build partial Merkle trees for memory commitments.
-    // The memory is passed as mut since the merkle tree construction adds intermediate nodes to the memory map.
-    let (initial_tree, initial_root) = build_partial_merkle_tree::<Poseidon2Hash>(
-        &memory.initial_memory,
-        TreeType::Initial,
-        &public_address_ranges,
-    );
-    let (final_tree, final_root) = build_partial_merkle_tree::<Poseidon2Hash>(
-        &memory.final_memory,
-        TreeType::Final,
-        &public_address_ranges,
-    );
-
-    // Extract Poseidon2 inputs from merkle trees.
-    // This data is used for the Poseidon2 component
-    let mut poseidon2_inputs =
-        Vec::<HashInput>::with_capacity(initial_tree.len() + final_tree.len());
-    initial_tree.iter().for_each(|node| {
-        poseidon2_inputs.push(node.to_hash_input());
-    });
-    final_tree.iter().for_each(|node| {
-        poseidon2_inputs.push(node.to_hash_input());
-    });
-
-    Ok(ProverInput {
-        merkle_trees: MerkleTrees {
-            initial_tree,
-            final_tree,
-            initial_root,
-            final_root,
-        },
+    Ok(PartialProverInput {
         memory,
-        public_address_ranges,
         instructions: Instructions {
             initial_registers,
             final_registers,
             states_by_opcodes,
             data_accesses,
         },
-        poseidon2_inputs,
+        public_address_ranges,
+        public_pages,
     })
 }
 
@@ -228,8 +325,10 @@ pub fn import_from_runner_artifacts(
         trace_iter,
         memory_iter,
         HashMap::new(),
+        &[],
         PublicAddressRanges::default(),
     )
+    .map(PartialProverInput::build_merkle_trees)
 }
 
 /// Imports prover input directly from runner execution segment.
@@ -243,6 +342,7 @@ pub fn import_from_runner_artifacts(
 ///   - `trace`: Vector of VM register states
 ///   - `memory_trace`: Memory access trace
 ///   - `initial_memory`: Initial memory state as QM31 values
+///   - `clock_updates`: Clock updates scheduled by the VM
 /// * `public_addresses` - List of public input/output memory addresses
 ///
 /// ## Returns
@@ -252,7 +352,13 @@ pub fn import_from_runner_output(
     segment: Segment,
     public_address_ranges: PublicAddressRanges,
 ) -> Result<ProverInput, VmImportError> {
-    let _span = span!(Level::INFO, "import_from_runner_output").entered();
+    let _span = span!(
+        Level::INFO,
+        "import_from_runner_output",
+        n_steps = segment.trace.len(),
+        n_memory_accesses = segment.memory_trace.borrow().len()
+    )
+    .entered();
 
     let trace_iter = segment.trace.into_iter();
     let memory_iter = segment.memory_trace.into_inner().into_iter();
@@ -261,6 +367,50 @@ pub fn import_from_runner_output(
         trace_iter,
         memory_iter,
         segment.initial_memory,
+        &segment.clock_updates,
         public_address_ranges,
     )
+    .map(PartialProverInput::build_merkle_trees)
+}
+
+/// Imports prover input from a borrowed runner output, without cloning its segment.
+///
+/// The execution and memory traces of the first segment are read in place. Only the
+/// initial memory, which holds the program and inputs, is copied. The Merkle trees are
+/// not built: drop the runner output, then call [`PartialProverInput::build_merkle_trees`]
+/// to keep the peak memory usage of large runs down.
+///
+/// ## Arguments
+/// * `runner_output` - Output of the Cairo-M runner
+///
+/// ## Returns
+/// * `Ok(PartialProverInput)` - Prover input without the Merkle trees
+/// * `Err(VmImportError)` - The output has no segment or the segment data is invalid
+pub fn import_from_runner_output_ref(
+    runner_output: &RunnerOutput,
+) -> Result<PartialProverInput, VmImportError> {
+    let span = span!(
+        Level::INFO,
+        "import_from_runner_output_ref",
+        n_steps = field::Empty,
+        n_memory_accesses = field::Empty
+    )
+    .entered();
+
+    let segment = runner_output
+        .vm
+        .segments
+        .first()
+        .ok_or(VmImportError::NoMemorySegments)?;
+    let memory_trace = segment.memory_trace.borrow();
+    span.record("n_steps", segment.trace.len());
+    span.record("n_memory_accesses", memory_trace.len());
+
+    import_internal(
+        segment.trace.iter().copied(),
+        memory_trace.iter().copied(),
+        segment.initial_memory.clone(),
+        &segment.clock_updates,
+        runner_output.public_address_ranges.clone(),
+    )
 }
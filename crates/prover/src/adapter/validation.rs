@@ -0,0 +1,556 @@
+//! Pre-flight validation of the prover input.
+//!
+//! An ill-formed trace (a step fetching its instruction outside of the program, a memory
+//! access going back in time, inconsistent public ranges...) only makes the prover fail
+//! deep inside constraint evaluation, without pointing at the cause. The checks below run
+//! on the imported [`ProverInput`] before proving and report each problem precisely.
+
+use cairo_m_common::program::AbiType;
+use cairo_m_common::{Program, ProgramData, PublicAddressRanges};
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::QM31;
+use thiserror::Error;
+
+use crate::adapter::{ExecutionBundle, ProverInput};
+
+/// A problem found in the prover input.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TraceValidationError {
+    #[error(
+        "Public ranges are not contiguous: program {program:?}, environment {environment:?}, input {input:?}, output {output:?}"
+    )]
+    InconsistentPublicRanges {
+        program: std::ops::Range<u32>,
+        environment: std::ops::Range<u32>,
+        input: std::ops::Range<u32>,
+        output: std::ops::Range<u32>,
+    },
+    #[error("Public segment `{name}` {range:?} overlaps the `{other}` range")]
+    OverlappingPublicSegment {
+        name: String,
+        range: std::ops::Range<u32>,
+        other: String,
+    },
+    #[error("Program range holds {found} words, but the program has {expected}")]
+    ProgramLengthMismatch { expected: u32, found: u32 },
+    #[error(
+        "The program reads {expected} environment values, but the environment range holds {found}"
+    )]
+    EnvironmentTooShort { expected: u32, found: u32 },
+    #[error("Entrypoint `{0}` not found in the program")]
+    UnknownEntrypoint(String),
+    #[error(
+        "Entrypoint `{entrypoint}` takes {expected} input slots, but the input range holds {found}"
+    )]
+    InputLengthMismatch {
+        entrypoint: String,
+        expected: u32,
+        found: u32,
+    },
+    #[error(
+        "Entrypoint `{entrypoint}` returns {expected} output slots, but the output range holds {found}"
+    )]
+    OutputLengthMismatch {
+        entrypoint: String,
+        expected: u32,
+        found: u32,
+    },
+    #[error(
+        "Public address {address} starts with {found}, but the execution starts with {expected}"
+    )]
+    PublicValueMismatch {
+        address: M31,
+        expected: QM31,
+        found: QM31,
+    },
+    #[error("Public address {address} is missing from the {memory} memory")]
+    MissingPublicAddress { address: M31, memory: &'static str },
+    #[error("Step at clock {clock} executes pc {pc}, outside of the program range {program:?}")]
+    PcOutOfProgram {
+        clock: M31,
+        pc: M31,
+        program: std::ops::Range<u32>,
+    },
+    #[error(
+        "Step at clock {clock} executes an instruction that doesn't match the program at pc {pc}"
+    )]
+    InstructionMismatch { clock: M31, pc: M31 },
+    #[error(
+        "Step at clock {clock} accesses address {address} last accessed at clock {prev_clock}: clocks must increase"
+    )]
+    NonMonotoneClock {
+        clock: M31,
+        address: M31,
+        prev_clock: M31,
+    },
+    #[error(
+        "Step at clock {clock} refers to accesses {start}..{end}, but the access log holds {len}"
+    )]
+    AccessSpanOutOfBounds {
+        clock: M31,
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("Address {0} is in only one of the initial and final memories")]
+    MemoryKeysMismatch(M31),
+}
+
+/// Checks that the public ranges are contiguous and match the program, its environment and
+/// the ABI of the executed entrypoint, and that the user segments don't overlap any other
+/// range.
+///
+/// ## Arguments
+/// * `ranges` - The public address ranges of the execution
+/// * `program` - The executed program
+/// * `entrypoint` - The name of the executed function
+///
+/// ## Returns
+/// The list of problems found, empty if the ranges are valid.
+pub fn validate_public_ranges(
+    ranges: &PublicAddressRanges,
+    program: &Program,
+    entrypoint: &str,
+) -> Vec<TraceValidationError> {
+    let mut errors = vec![];
+
+    if ranges.program.start != 0
+        || ranges.program.end != ranges.environment.start
+        || ranges.environment.end != ranges.input.start
+        || ranges.input.end != ranges.output.start
+    {
+        errors.push(TraceValidationError::InconsistentPublicRanges {
+            program: ranges.program.clone(),
+            environment: ranges.environment.clone(),
+            input: ranges.input.clone(),
+            output: ranges.output.clone(),
+        });
+    }
+
+    let named_ranges = ranges.named_ranges().collect::<Vec<_>>();
+    // The program, environment, input and output ranges are checked above, the user segments
+    // come after.
+    for (i, (name, _, range)) in named_ranges.iter().enumerate().skip(4) {
+        let overlapping = named_ranges[..i].iter().find(|(_, _, other)| {
+            !range.is_empty()
+                && !other.is_empty()
+                && range.start < other.end
+                && other.start < range.end
+        });
+        if let Some((other, ..)) = overlapping {
+            errors.push(TraceValidationError::OverlappingPublicSegment {
+                name: name.to_string(),
+                range: range.clone(),
+                other: other.to_string(),
+            });
+        }
+    }
+
+    let program_length = program
+        .data
+        .iter()
+        .map(|data| match data {
+            ProgramData::Instruction(instruction) => instruction.size_in_qm31s(),
+            ProgramData::Value(_) => 1,
+        })
+        .sum::<u32>();
+    if ranges.program.len() as u32 != program_length {
+        errors.push(TraceValidationError::ProgramLengthMismatch {
+            expected: program_length,
+            found: ranges.program.len() as u32,
+        });
+    }
+
+    if (ranges.environment.len() as u32) < program.metadata.environment_size {
+        errors.push(TraceValidationError::EnvironmentTooShort {
+            expected: program.metadata.environment_size,
+            found: ranges.environment.len() as u32,
+        });
+    }
+
+    let Some(info) = program.get_entrypoint(entrypoint) else {
+        errors.push(TraceValidationError::UnknownEntrypoint(
+            entrypoint.to_string(),
+        ));
+        return errors;
+    };
+
+    let input_slots = info
+        .params
+        .iter()
+        .map(|p| AbiType::call_slot_size(&p.ty))
+        .sum::<usize>() as u32;
+    if ranges.input.len() as u32 != input_slots {
+        errors.push(TraceValidationError::InputLengthMismatch {
+            entrypoint: entrypoint.to_string(),
+            expected: input_slots,
+            found: ranges.input.len() as u32,
+        });
+    }
+
+    let output_slots = info
+        .returns
+        .iter()
+        .map(|r| AbiType::call_slot_size(&r.ty))
+        .sum::<usize>() as u32;
+    if ranges.output.len() as u32 != output_slots {
+        errors.push(TraceValidationError::OutputLengthMismatch {
+            entrypoint: entrypoint.to_string(),
+            expected: output_slots,
+            found: ranges.output.len() as u32,
+        });
+    }
+
+    errors
+}
+
+/// Checks that the initial memory of the prover input holds the program, the environment and
+/// the arguments of the execution in their public ranges, e.g. for a prover input that was not
+/// produced by running the program itself.
+///
+/// ## Arguments
+/// * `input` - The prover input
+/// * `program` - The executed program
+/// * `environment` - The values of the environment block
+/// * `arguments` - The cells written after the environment block by the runner, see
+///   `cairo_m_runner::encode_arguments`
+///
+/// ## Returns
+/// The list of problems found, empty if the public initial memory matches. Missing addresses
+/// are reported by [`validate_prover_input`].
+pub fn validate_initial_memory(
+    input: &ProverInput,
+    program: &Program,
+    environment: &[M31],
+    arguments: &[M31],
+) -> Vec<TraceValidationError> {
+    let ranges = &input.public_address_ranges;
+    let program_words = program.data.iter().flat_map(ProgramData::to_qm31_vec);
+    let environment = environment.iter().map(|&value| value.into());
+    let arguments = arguments.iter().map(|&value| value.into());
+
+    ranges
+        .program
+        .clone()
+        .zip(program_words)
+        .chain(ranges.environment.clone().zip(environment))
+        .chain(ranges.input.clone().zip(arguments))
+        .filter_map(|(address, expected): (u32, QM31)| {
+            let &(found, _, _) = input.memory.initial_memory.get(&M31(address))?;
+            (found != expected).then_some(TraceValidationError::PublicValueMismatch {
+                address: M31(address),
+                expected,
+                found,
+            })
+        })
+        .collect()
+}
+
+/// Checks that the prover input is well-formed.
+///
+/// The following properties are checked:
+/// - Program and input addresses are in the initial memory, output addresses in the final memory
+/// - Initial and final memories hold the same addresses
+/// - Each step executes an instruction of the program, read at its pc
+/// - Each memory access happens strictly after the previous access to the same address
+///
+/// ## Arguments
+/// * `input` - The imported prover input
+///
+/// ## Returns
+/// The list of problems found, ordered by clock, empty if the input is valid.
+pub fn validate_prover_input(input: &ProverInput) -> Vec<TraceValidationError> {
+    let mut errors = vec![];
+    let ranges = &input.public_address_ranges;
+    let memory = &input.memory;
+
+    for address in ranges.initial_ranges().flatten() {
+        if !memory.initial_memory.contains_key(&M31(address)) {
+            errors.push(TraceValidationError::MissingPublicAddress {
+                address: M31(address),
+                memory: "initial",
+            });
+        }
+    }
+    for address in ranges.final_ranges().flatten() {
+        if !memory.final_memory.contains_key(&M31(address)) {
+            errors.push(TraceValidationError::MissingPublicAddress {
+                address: M31(address),
+                memory: "final",
+            });
+        }
+    }
+
+    let mut mismatched_keys = memory
+        .initial_memory
+        .keys()
+        .filter(|address| !memory.final_memory.contains_key(address))
+        .chain(
+            memory
+                .final_memory
+                .keys()
+                .filter(|address| !memory.initial_memory.contains_key(address)),
+        )
+        .copied()
+        .collect::<Vec<_>>();
+    mismatched_keys.sort_unstable_by_key(|address| address.0);
+    errors.extend(
+        mismatched_keys
+            .into_iter()
+            .map(TraceValidationError::MemoryKeysMismatch),
+    );
+
+    let mut bundles = input
+        .instructions
+        .states_by_opcodes
+        .values()
+        .flatten()
+        .collect::<Vec<_>>();
+    bundles.sort_unstable_by_key(|bundle| bundle.clock.0);
+    for bundle in bundles {
+        validate_bundle(input, bundle, &mut errors);
+    }
+
+    errors
+}
+
+/// Checks a single execution step against the program and the access log.
+fn validate_bundle(
+    input: &ProverInput,
+    bundle: &ExecutionBundle,
+    errors: &mut Vec<TraceValidationError>,
+) {
+    let program = &input.public_address_ranges.program;
+    let clock = bundle.clock;
+    let pc = bundle.registers.pc;
+
+    if !program.contains(&pc.0) {
+        errors.push(TraceValidationError::PcOutOfProgram {
+            clock,
+            pc,
+            program: program.clone(),
+        });
+    } else {
+        let words = bundle.instruction.instruction.to_qm31_vec();
+        let matches_program = words.iter().enumerate().all(|(i, word)| {
+            input
+                .memory
+                .initial_memory
+                .get(&(pc + M31(i as u32)))
+                .is_some_and(|(value, _, _)| value == word)
+        });
+        if !matches_program {
+            errors.push(TraceValidationError::InstructionMismatch { clock, pc });
+        }
+    }
+
+    if bundle.instruction.prev_clock.0 >= clock.0 {
+        errors.push(TraceValidationError::NonMonotoneClock {
+            clock,
+            address: pc,
+            prev_clock: bundle.instruction.prev_clock,
+        });
+    }
+
+    let data_accesses = &input.instructions.data_accesses;
+    let start = bundle.access_span.start as usize;
+    let end = start + bundle.access_span.len as usize;
+    let Some(accesses) = data_accesses.get(start..end) else {
+        errors.push(TraceValidationError::AccessSpanOutOfBounds {
+            clock,
+            start,
+            end,
+            len: data_accesses.len(),
+        });
+        return;
+    };
+    for access in accesses {
+        if access.prev_clock.0 >= clock.0 {
+            errors.push(TraceValidationError::NonMonotoneClock {
+                clock,
+                address: access.address,
+                prev_clock: access.prev_clock,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cairo_m_common::program::{AbiSlot, EntrypointInfo, ProgramMetadata};
+    use cairo_m_common::{Instruction, Program, PublicSegment, SegmentKind};
+    use cairo_m_runner::RunnerOptions;
+    use cairo_m_runner::vm::VM;
+
+    use super::*;
+    use crate::adapter::import_from_runner_output;
+
+    fn program() -> Program {
+        let instructions = vec![
+            Instruction::StoreImm {
+                imm: M31(10),
+                dst_off: M31(0),
+            },
+            Instruction::StoreImm {
+                imm: M31(20),
+                dst_off: M31(1),
+            },
+            Instruction::StoreAddFpFp {
+                src0_off: M31(0),
+                src1_off: M31(1),
+                dst_off: M31(2),
+            },
+        ];
+        let entrypoints = HashMap::from([(
+            "main".to_string(),
+            EntrypointInfo {
+                pc: 0,
+                params: vec![],
+                returns: vec![AbiSlot {
+                    name: String::new(),
+                    ty: AbiType::Felt,
+                    default: None,
+                }],
+            },
+        )]);
+        Program::new(
+            instructions
+                .into_iter()
+                .map(ProgramData::Instruction)
+                .collect(),
+            entrypoints,
+            ProgramMetadata::default(),
+        )
+    }
+
+    fn prover_input(program: &Program) -> ProverInput {
+        let mut vm = VM::try_from(program).unwrap();
+        vm.run_from_entrypoint(0, 3, &[], 0, &RunnerOptions::default())
+            .unwrap();
+        let ranges = PublicAddressRanges::new(vm.program_length.0, 0, 0);
+        import_from_runner_output(vm.segments.into_iter().next().unwrap(), ranges).unwrap()
+    }
+
+    #[test]
+    fn test_valid_input() {
+        let program = program();
+        assert_eq!(validate_prover_input(&prover_input(&program)), vec![]);
+    }
+
+    #[test]
+    fn test_public_ranges_against_abi() {
+        let program = program();
+        let length = prover_input(&program).public_address_ranges.program.end;
+
+        let ranges = PublicAddressRanges::new(length, 0, 1);
+        assert_eq!(validate_public_ranges(&ranges, &program, "main"), vec![]);
+
+        let ranges = PublicAddressRanges::new(length, 2, 0);
+        assert_eq!(
+            validate_public_ranges(&ranges, &program, "main"),
+            vec![
+                TraceValidationError::InputLengthMismatch {
+                    entrypoint: "main".to_string(),
+                    expected: 0,
+                    found: 2,
+                },
+                TraceValidationError::OutputLengthMismatch {
+                    entrypoint: "main".to_string(),
+                    expected: 1,
+                    found: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            validate_public_ranges(&ranges, &program, "foo").last(),
+            Some(&TraceValidationError::UnknownEntrypoint("foo".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_public_ranges_against_environment() {
+        let mut program = program();
+        program.metadata.environment_size = 2;
+        let length = prover_input(&program).public_address_ranges.program.end;
+
+        let ranges = PublicAddressRanges::new(length, 0, 1).with_environment(2);
+        assert_eq!(validate_public_ranges(&ranges, &program, "main"), vec![]);
+
+        let ranges = PublicAddressRanges::new(length, 0, 1).with_environment(1);
+        assert_eq!(
+            validate_public_ranges(&ranges, &program, "main"),
+            vec![TraceValidationError::EnvironmentTooShort {
+                expected: 2,
+                found: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_overlapping_public_segments() {
+        let program = program();
+        let length = prover_input(&program).public_address_ranges.program.end;
+
+        let ranges = PublicAddressRanges::new(length, 0, 1)
+            .with_segment(PublicSegment::new("data", SegmentKind::Input, 100..104))
+            .with_segment(PublicSegment::new("log", SegmentKind::Output, 102..106))
+            .with_segment(PublicSegment::new("code", SegmentKind::Input, 0..1));
+        assert_eq!(
+            validate_public_ranges(&ranges, &program, "main"),
+            vec![
+                TraceValidationError::OverlappingPublicSegment {
+                    name: "log".to_string(),
+                    range: 102..106,
+                    other: "data".to_string(),
+                },
+                TraceValidationError::OverlappingPublicSegment {
+                    name: "code".to_string(),
+                    range: 0..1,
+                    other: "program".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pc_out_of_program() {
+        let program = program();
+        let mut input = prover_input(&program);
+        let bundle = input
+            .instructions
+            .states_by_opcodes
+            .values_mut()
+            .flat_map(|bundles| bundles.iter_mut())
+            .min_by_key(|bundle| bundle.clock.0)
+            .unwrap();
+        bundle.registers.pc = M31(100);
+        let clock = bundle.clock;
+
+        assert_eq!(
+            validate_prover_input(&input),
+            vec![TraceValidationError::PcOutOfProgram {
+                clock,
+                pc: M31(100),
+                program: input.public_address_ranges.program.clone(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_non_monotone_clock() {
+        let program = program();
+        let mut input = prover_input(&program);
+        let access = input.instructions.data_accesses.last_mut().unwrap();
+        access.prev_clock = M31(1000);
+        let address = access.address;
+
+        let errors = validate_prover_input(&input);
+        assert!(matches!(
+            errors.as_slice(),
+            [TraceValidationError::NonMonotoneClock { address: a, prev_clock, .. }]
+                if *a == address && *prev_clock == M31(1000)
+        ));
+    }
+}
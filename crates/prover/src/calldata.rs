@@ -0,0 +1,630 @@
+//! # Calldata Export
+//!
+//! Conversion of a [`Proof`] into calldata for an on-chain verifier contract: a flat array
+//! of 32-bit words, in the order of the serialization of the proof, with a layout
+//! descriptor giving the location of each field of the proof in the array.
+//!
+//! The proof is flattened through its `serde` implementation, so the export works for
+//! any Merkle hasher whose hashes are serializable. Values are encoded as follows:
+//!
+//! - `bool`, `u8`, `u16` and `u32` (e.g. M31 elements): one word
+//! - `u64` (e.g. proof-of-work nonces): two words, the low one first
+//! - byte arrays (e.g. hashes): packed into big-endian words, the last one zero-padded
+//! - sequences of variable length: their length, then their elements
+//! - tuples, arrays and structs: their elements, without length
+//! - options: `0` for `None`, `1` then the value for `Some`
+//! - enums: the index of the variant, then its fields
+//!
+//! The words are ABI-encoded as a `uint256[]` by [`ProofCalldata::abi_encode`].
+
+use std::fmt::Display;
+
+use serde::ser::{self, Serialize, Serializer};
+use serde::{Deserialize, Serialize as SerializeDerive};
+use stwo_prover::core::vcs::ops::MerkleHasher;
+use thiserror::Error;
+
+use crate::Proof;
+
+/// Size in bytes of an EVM word
+const EVM_WORD_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum CalldataError {
+    #[error("Unsupported type in calldata: {0}")]
+    UnsupportedType(&'static str),
+    #[error("Negative integer in calldata: {0}")]
+    NegativeInteger(i64),
+    #[error("Sequence of unknown length in calldata")]
+    UnknownLength,
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl ser::Error for CalldataError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// Location of a field of the proof in the calldata words
+#[derive(SerializeDerive, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LayoutEntry {
+    /// Path of the field, e.g. `stark_proof.commitments[1]`
+    pub path: String,
+    /// Index of the first word of the field
+    pub offset: usize,
+    /// Number of words of the field
+    pub len: usize,
+}
+
+/// Calldata-shaped proof: flat words and the layout of the fields in them
+#[derive(SerializeDerive, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProofCalldata {
+    /// Words of the proof, each below 2^32
+    pub words: Vec<u32>,
+    /// Location of every struct field, in serialization order (parents before children)
+    pub layout: Vec<LayoutEntry>,
+}
+
+impl ProofCalldata {
+    /// Returns the layout entry of a field.
+    ///
+    /// ## Arguments
+    /// * `path` - Path of the field, e.g. `public_data.initial_registers`
+    pub fn entry(&self, path: &str) -> Option<&LayoutEntry> {
+        self.layout.iter().find(|entry| entry.path == path)
+    }
+
+    /// Returns the words of a field.
+    ///
+    /// ## Arguments
+    /// * `path` - Path of the field, e.g. `public_data.initial_registers`
+    pub fn field(&self, path: &str) -> Option<&[u32]> {
+        self.entry(path)
+            .map(|entry| &self.words[entry.offset..entry.offset + entry.len])
+    }
+
+    /// ABI-encodes the words as a Solidity `uint256[]`.
+    ///
+    /// ## Returns
+    /// The offset of the array, its length and its elements, each as a 32-byte big-endian word
+    pub fn abi_encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity((self.words.len() + 2) * EVM_WORD_SIZE);
+        for word in [EVM_WORD_SIZE as u64, self.words.len() as u64]
+            .into_iter()
+            .chain(self.words.iter().map(|&word| word.into()))
+        {
+            encoded.extend_from_slice(&[0; EVM_WORD_SIZE - 8]);
+            encoded.extend_from_slice(&word.to_be_bytes());
+        }
+        encoded
+    }
+}
+
+/// Exports a proof as calldata.
+///
+/// ## Arguments
+/// * `proof` - The proof to export
+///
+/// ## Returns
+/// The words of the proof and their layout
+pub fn export_proof<H: MerkleHasher>(proof: &Proof<H>) -> Result<ProofCalldata, CalldataError> {
+    to_calldata(proof)
+}
+
+/// Flattens any serializable value as calldata, see the [module documentation](self).
+pub fn to_calldata<T: Serialize + ?Sized>(value: &T) -> Result<ProofCalldata, CalldataError> {
+    let mut exporter = Exporter::default();
+    value.serialize(&mut exporter)?;
+    Ok(ProofCalldata {
+        words: exporter.words,
+        layout: exporter.layout,
+    })
+}
+
+#[derive(Default)]
+struct Exporter {
+    words: Vec<u32>,
+    layout: Vec<LayoutEntry>,
+    path: Vec<String>,
+    /// Whether the last serialized value is a single byte
+    last_is_byte: bool,
+}
+
+impl Exporter {
+    fn push(&mut self, word: u32) {
+        self.words.push(word);
+        self.last_is_byte = false;
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(4) {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.push(u32::from_be_bytes(word));
+        }
+    }
+
+    fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+        path
+    }
+
+    fn compound(&mut self) -> Compound<'_> {
+        let start = self.words.len();
+        Compound {
+            exporter: self,
+            start,
+            len: 0,
+            all_bytes: true,
+        }
+    }
+}
+
+/// Serializer of the elements or fields of a compound value
+struct Compound<'a> {
+    exporter: &'a mut Exporter,
+    /// Index of the first word of the elements
+    start: usize,
+    len: usize,
+    /// Whether all the elements are single bytes, to be packed into words
+    all_bytes: bool,
+}
+
+impl Compound<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CalldataError> {
+        let before = self.exporter.words.len();
+        value.serialize(&mut *self.exporter)?;
+        self.all_bytes &= self.exporter.words.len() == before + 1 && self.exporter.last_is_byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        self.exporter.path.push(key.to_string());
+        let index = self.exporter.layout.len();
+        let offset = self.exporter.words.len();
+        self.exporter.layout.push(LayoutEntry {
+            path: self.exporter.path(),
+            offset,
+            len: 0,
+        });
+        value.serialize(&mut *self.exporter)?;
+        self.exporter.layout[index].len = self.exporter.words.len() - offset;
+        self.exporter.path.pop();
+        self.all_bytes = false;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), CalldataError> {
+        if self.all_bytes && self.len > 0 {
+            let bytes = self
+                .exporter
+                .words
+                .drain(self.start..)
+                .map(|word| word as u8)
+                .collect::<Vec<_>>();
+            self.exporter.push_bytes(&bytes);
+        }
+        self.exporter.last_is_byte = false;
+        Ok(())
+    }
+}
+
+impl<'a> Serializer for &'a mut Exporter {
+    type Ok = ();
+    type Error = CalldataError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), CalldataError> {
+        self.push(v.into());
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), CalldataError> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), CalldataError> {
+        self.serialize_i32(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), CalldataError> {
+        let v = u32::try_from(v).map_err(|_| CalldataError::NegativeInteger(v.into()))?;
+        self.serialize_u32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), CalldataError> {
+        let v = u64::try_from(v).map_err(|_| CalldataError::NegativeInteger(v))?;
+        self.serialize_u64(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), CalldataError> {
+        self.push(v.into());
+        self.last_is_byte = true;
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), CalldataError> {
+        self.push(v.into());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), CalldataError> {
+        self.push(v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), CalldataError> {
+        self.push(v as u32);
+        self.push((v >> 32) as u32);
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), CalldataError> {
+        Err(CalldataError::UnsupportedType("f32"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), CalldataError> {
+        Err(CalldataError::UnsupportedType("f64"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), CalldataError> {
+        Err(CalldataError::UnsupportedType("char"))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), CalldataError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CalldataError> {
+        self.push(v.len() as u32);
+        self.push_bytes(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), CalldataError> {
+        self.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), CalldataError> {
+        self.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), CalldataError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CalldataError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), CalldataError> {
+        self.push(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), CalldataError> {
+        self.push(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>, CalldataError> {
+        self.push(len.ok_or(CalldataError::UnknownLength)? as u32);
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, CalldataError> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, CalldataError> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, CalldataError> {
+        self.push(variant_index);
+        Ok(self.compound())
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>, CalldataError> {
+        self.push(len.ok_or(CalldataError::UnknownLength)? as u32);
+        Ok(self.compound())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, CalldataError> {
+        Ok(self.compound())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, CalldataError> {
+        self.push(variant_index);
+        Ok(self.compound())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.exporter.path.push(format!("[{}]", self.len));
+        let result = self.element(value);
+        self.exporter.path.pop();
+        result
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.all_bytes = false;
+        key.serialize(&mut *self.exporter)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(&mut *self.exporter)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = CalldataError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Compound::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(SerializeDerive)]
+    struct Layer {
+        commitment: [u8; 6],
+        values: Vec<u32>,
+    }
+
+    #[derive(SerializeDerive)]
+    enum Kind {
+        Empty,
+        Nonce(u64),
+    }
+
+    #[derive(SerializeDerive)]
+    struct MockProof {
+        flag: bool,
+        layers: Vec<Layer>,
+        kind: Kind,
+        last: Option<(u16, u8)>,
+    }
+
+    fn entry(path: &str, offset: usize, len: usize) -> LayoutEntry {
+        LayoutEntry {
+            path: path.to_string(),
+            offset,
+            len,
+        }
+    }
+
+    #[test]
+    fn test_to_calldata_golden() {
+        let proof = MockProof {
+            flag: true,
+            layers: vec![
+                Layer {
+                    commitment: [1, 2, 3, 4, 5, 6],
+                    values: vec![7],
+                },
+                Layer {
+                    commitment: [0xff; 6],
+                    values: vec![],
+                },
+            ],
+            kind: Kind::Nonce(0x1_0000_0002),
+            last: Some((3, 4)),
+        };
+
+        let calldata = to_calldata(&proof).unwrap();
+        assert_eq!(
+            calldata.words,
+            vec![
+                1, // flag
+                2, // layers.len()
+                0x0102_0304,
+                0x0506_0000, // layers[0].commitment
+                1,
+                7, // layers[0].values
+                0xffff_ffff,
+                0xffff_0000, // layers[1].commitment
+                0,           // layers[1].values
+                1,
+                2,
+                1, // kind
+                1,
+                3,
+                4, // last
+            ]
+        );
+        assert_eq!(
+            calldata.layout,
+            vec![
+                entry("flag", 0, 1),
+                entry("layers", 1, 8),
+                entry("layers[0].commitment", 2, 2),
+                entry("layers[0].values", 4, 2),
+                entry("layers[1].commitment", 6, 2),
+                entry("layers[1].values", 8, 1),
+                entry("kind", 9, 3),
+                entry("last", 12, 3),
+            ]
+        );
+        assert_eq!(calldata.field("layers[0].values"), Some(&[1, 7][..]));
+        assert!(calldata.field("missing").is_none());
+
+        assert_eq!(
+            to_calldata(&Kind::Empty).unwrap().words,
+            vec![0],
+            "unit variants are their index"
+        );
+    }
+
+    #[test]
+    fn test_abi_encode_golden() {
+        let calldata = ProofCalldata {
+            words: vec![1, 0xdead_beef],
+            layout: vec![],
+        };
+
+        let mut expected = vec![0; 4 * EVM_WORD_SIZE];
+        expected[EVM_WORD_SIZE - 1] = 0x20; // offset of the array
+        expected[2 * EVM_WORD_SIZE - 1] = 2; // length of the array
+        expected[3 * EVM_WORD_SIZE - 1] = 1;
+        expected[4 * EVM_WORD_SIZE - 4..].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(calldata.abi_encode(), expected);
+    }
+
+    #[test]
+    fn test_unsupported_values() {
+        assert!(matches!(
+            to_calldata(&1.5f64),
+            Err(CalldataError::UnsupportedType("f64"))
+        ));
+        assert!(matches!(
+            to_calldata(&-1i32),
+            Err(CalldataError::NegativeInteger(-1))
+        ));
+    }
+}
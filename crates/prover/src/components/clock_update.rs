@@ -5,6 +5,7 @@
 //! - enabler
 //! - addr
 //! - prev_clk
+//! - clk
 //! - QM31 value
 //!
 //! # Constraints
@@ -13,10 +14,14 @@
 //!   * `enabler * (1 - enabler)`
 //! * update the clock
 //!   * `- [addr, prev_clk, value]` in `Memory` relation
-//!   * `+ [addr, prev_clk + RC_20, value]` in `Memory` relation
+//!   * `+ [addr, clk, value]` in `Memory` relation
+//! * clock difference is in range
+//!   * `- [clk - prev_clk - enabler]` in `RangeCheck20` relation
 
 use num_traits::{One, Zero};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use serde::{Deserialize, Serialize};
 use stwo_air_utils::trace::component_trace::ComponentTrace;
 use stwo_air_utils_derive::{IterMut, ParIterMut, Uninitialized};
@@ -35,13 +40,15 @@ use stwo_prover::core::pcs::TreeVec;
 use stwo_prover::core::poly::BitReversedOrder;
 use stwo_prover::core::poly::circle::CircleEvaluation;
 
-use crate::adapter::memory::RC20_LIMIT;
 use crate::components::Relations;
+use crate::preprocessed::range_check::RangeCheckProvider;
 use crate::utils::enabler::Enabler;
 
-const N_TRACE_COLUMNS: usize = 7;
+const N_TRACE_COLUMNS: usize = 8;
 const N_MEMORY_LOOKUPS: usize = 2;
-const N_INTERACTION_COLUMNS: usize = SECURE_EXTENSION_DEGREE * N_MEMORY_LOOKUPS.div_ceil(2);
+const N_RANGE_CHECK_20_LOOKUPS: usize = 1;
+const N_INTERACTION_COLUMNS: usize =
+    SECURE_EXTENSION_DEGREE * (N_MEMORY_LOOKUPS + N_RANGE_CHECK_20_LOOKUPS).div_ceil(2);
 
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 pub struct Claim {
@@ -61,6 +68,14 @@ pub struct InteractionClaimData {
 #[derive(Uninitialized, IterMut, ParIterMut)]
 pub struct LookupData {
     pub memory: [Vec<[PackedM31; 6]>; N_MEMORY_LOOKUPS],
+    pub range_check_20: [Vec<PackedM31>; N_RANGE_CHECK_20_LOOKUPS],
+}
+
+// Implement RangeCheckProvider to expose range_check_20 data
+impl RangeCheckProvider for InteractionClaimData {
+    fn get_range_check_20(&self) -> impl ParallelIterator<Item = &PackedM31> {
+        self.lookup_data.range_check_20.par_iter().flatten()
+    }
 }
 
 impl Claim {
@@ -75,7 +90,7 @@ impl Claim {
     }
 
     pub fn write_trace<MC: MerkleChannel>(
-        clock_update_data: &[(M31, M31, QM31)],
+        clock_update_data: &[(M31, M31, M31, QM31)],
     ) -> (Self, ComponentTrace<N_TRACE_COLUMNS>, InteractionClaimData)
     where
         SimdBackend: BackendForChannel<MC>,
@@ -86,11 +101,12 @@ impl Claim {
         // Pack entries from the prover input
         let packed_inputs: Vec<[PackedM31; N_TRACE_COLUMNS - 1]> = clock_update_data
             .iter()
-            .map(|&(addr, prev_clk, value)| {
+            .map(|&(addr, prev_clk, clk, value)| {
                 let value_array = value.to_m31_array();
                 [
                     addr,
                     prev_clk,
+                    clk,
                     value_array[0],
                     value_array[1],
                     value_array[2],
@@ -124,28 +140,24 @@ impl Claim {
                 let enabler = enabler_col.packed_at(row_index);
                 let address = input[0];
                 let prev_clk = input[1];
-                let value0 = input[2];
-                let value1 = input[3];
-                let value2 = input[4];
-                let value3 = input[5];
+                let clk = input[2];
+                let value0 = input[3];
+                let value1 = input[4];
+                let value2 = input[5];
+                let value3 = input[6];
 
                 *row[0] = enabler;
                 *row[1] = address;
                 *row[2] = prev_clk;
-                *row[3] = value0;
-                *row[4] = value1;
-                *row[5] = value2;
-                *row[6] = value3;
+                *row[3] = clk;
+                *row[4] = value0;
+                *row[5] = value1;
+                *row[6] = value2;
+                *row[7] = value3;
 
                 *lookup_data.memory[0] = [address, prev_clk, value0, value1, value2, value3];
-                *lookup_data.memory[1] = [
-                    address,
-                    prev_clk + PackedM31::broadcast(M31::from(RC20_LIMIT)),
-                    value0,
-                    value1,
-                    value2,
-                    value3,
-                ];
+                *lookup_data.memory[1] = [address, clk, value0, value1, value2, value3];
+                *lookup_data.range_check_20[0] = clk - prev_clk - enabler;
             });
 
         // Return the trace and lookup data
@@ -197,6 +209,16 @@ impl InteractionClaim {
             });
         col.finalize_col();
 
+        let mut col = interaction_trace.new_col();
+        col.par_iter_mut()
+            .zip(&interaction_claim_data.lookup_data.range_check_20[0])
+            .for_each(|(writer, range_check_20)| {
+                let num = -PackedQM31::one();
+                let denom: PackedQM31 = relations.range_check_20.combine(&[*range_check_20]);
+                writer.write_frac(num, denom);
+            });
+        col.finalize_col();
+
         let (trace, claimed_sum) = interaction_trace.finalize_last();
         let interaction_claim = Self { claimed_sum };
         (interaction_claim, trace)
@@ -221,6 +243,7 @@ impl FrameworkEval for Eval {
         let enabler = eval.next_trace_mask();
         let address = eval.next_trace_mask();
         let prev_clk = eval.next_trace_mask();
+        let clk = eval.next_trace_mask();
         let value0 = eval.next_trace_mask();
         let value1 = eval.next_trace_mask();
         let value2 = eval.next_trace_mask();
@@ -246,15 +269,15 @@ impl FrameworkEval for Eval {
         ));
         eval.add_to_relation(RelationEntry::new(
             &self.relations.memory,
-            E::EF::from(enabler),
-            &[
-                address,
-                prev_clk + E::F::from(M31::from(RC20_LIMIT)),
-                value0,
-                value1,
-                value2,
-                value3,
-            ],
+            E::EF::from(enabler.clone()),
+            &[address, clk.clone(), value0, value1, value2, value3],
+        ));
+
+        // The clock difference is in [1, 2^20]
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.range_check_20,
+            -E::EF::one(),
+            &[clk - prev_clk - enabler],
         ));
 
         eval.finalize_logup_in_pairs();
@@ -101,11 +101,17 @@ impl Claim {
         let non_padded_length = initial_memory_len + inputs.final_memory.len();
         let log_size = std::cmp::max(non_padded_length.next_power_of_two(), N_LANES).ilog2();
 
+        // Rows are written in address order, so that the trace does not depend on the
+        // iteration order of the memory maps.
+        let mut initial_memory: Vec<_> = inputs.initial_memory.iter().collect();
+        initial_memory.sort_unstable_by_key(|(address, _)| address.0);
+        let mut final_memory: Vec<_> = inputs.final_memory.iter().collect();
+        final_memory.sort_unstable_by_key(|(address, _)| address.0);
+
         // Pack memory entries from the prover input
-        let packed_inputs: Vec<[PackedM31; N_INPUT_COLUMNS]> = inputs
-            .initial_memory
-            .iter()
-            .chain(inputs.final_memory.iter())
+        let packed_inputs: Vec<[PackedM31; N_INPUT_COLUMNS]> = initial_memory
+            .into_iter()
+            .chain(final_memory)
             .enumerate()
             .map(|(i, (address, (value, clock, multiplicity)))| {
                 let root = if i < initial_memory_len {
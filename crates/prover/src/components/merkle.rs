@@ -24,9 +24,10 @@
 //! * emit parent node
 //!   * `+ [index / 2, depth - 1, parent_value, root]` in `Memory` relation
 //! * poseidon2 hash computation
-//!   * `+ [left_value, right_value]` in `Poseidon2` relation (emit hash input)
-//!   * `- [parent_value]` in `Poseidon2` relation (use hash output)
+//!   * `+ [left_value, right_value, 0, ..., 0, parent_value, 0, ..., 0]` in `Poseidon2` relation
+//!     (emit hash input and output)
 
+use cairo_m_common::instruction::POSEIDON2_DIGEST_SIZE;
 use num_traits::{One, Zero};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
@@ -49,11 +50,12 @@ use stwo_prover::core::poly::circle::CircleEvaluation;
 
 use crate::adapter::MerkleTrees;
 use crate::components::Relations;
+use crate::poseidon2::T;
 use crate::utils::enabler::Enabler;
 
 const N_TRACE_COLUMNS: usize = 10;
 const N_MERKLE_LOOKUPS: usize = 3;
-const N_POSEIDON2_LOOKUPS: usize = 2;
+const N_POSEIDON2_LOOKUPS: usize = 1;
 const N_INTERACTION_COLUMNS: usize =
     SECURE_EXTENSION_DEGREE * (N_MERKLE_LOOKUPS + N_POSEIDON2_LOOKUPS).div_ceil(2);
 
@@ -75,7 +77,7 @@ pub struct InteractionClaimData {
 #[derive(Uninitialized, IterMut, ParIterMut)]
 pub struct LookupData {
     pub merkle: [Vec<[PackedM31; 5]>; N_MERKLE_LOOKUPS],
-    pub poseidon2: [Vec<[PackedM31; 2]>; N_POSEIDON2_LOOKUPS],
+    pub poseidon2: [Vec<[PackedM31; 3]>; N_POSEIDON2_LOOKUPS],
 }
 
 impl Claim {
@@ -132,7 +134,6 @@ impl Claim {
             })
             .collect();
 
-        let zero = PackedM31::zero();
         let one = PackedM31::from(M31::one());
         let m31_2 = M31::from(2);
         let m31_2_inv = PackedM31::from(M31::inverse(&m31_2));
@@ -185,8 +186,7 @@ impl Claim {
                     parent_multiplicity,
                 ];
 
-                *lookup_data.poseidon2[0] = [left_value, right_value];
-                *lookup_data.poseidon2[1] = [parent_value, zero];
+                *lookup_data.poseidon2[0] = [left_value, right_value, parent_value];
             });
 
         // Return the trace and lookup data
@@ -249,7 +249,7 @@ impl InteractionClaim {
                 let num0: PackedQM31 = -PackedQM31::from(value0[4]);
                 let denom0: PackedQM31 = relations.merkle.combine(&value0[..4]);
                 let num1: PackedQM31 = PackedQM31::from(enabler_col.packed_at(i));
-                let denom1: PackedQM31 = relations.poseidon2.combine(value1);
+                let denom1: PackedQM31 = relations.poseidon2.combine(&poseidon2_entry(value1));
 
                 let numerator = num0 * denom1 + num1 * denom0;
                 let denom = denom0 * denom1;
@@ -258,21 +258,6 @@ impl InteractionClaim {
             });
         col.finalize_col();
 
-        let mut col = interaction_trace.new_col();
-        (
-            col.par_iter_mut(),
-            &interaction_claim_data.lookup_data.poseidon2[1],
-        )
-            .into_par_iter()
-            .enumerate()
-            .for_each(|(i, (writer, value))| {
-                let num: PackedQM31 = -PackedQM31::from(enabler_col.packed_at(i));
-                let denom: PackedQM31 = relations.poseidon2.combine(value);
-
-                writer.write_frac(num, denom);
-            });
-        col.finalize_col();
-
         let (trace, claimed_sum) = interaction_trace.finalize_last();
         let interaction_claim = Self { claimed_sum };
         (interaction_claim, trace)
@@ -360,17 +345,11 @@ impl FrameworkEval for Eval {
             &[index * m31_2_inv, depth - one, parent_value.clone(), root],
         ));
 
-        // Emit initial state of permutation
+        // Emit initial state of permutation and first element of last state (hash)
         eval.add_to_relation(RelationEntry::new(
             &self.relations.poseidon2,
-            E::EF::from(enabler.clone()),
-            &[left_value, right_value],
-        ));
-        // Use first element of last state (hash)
-        eval.add_to_relation(RelationEntry::new(
-            &self.relations.poseidon2,
-            -E::EF::from(enabler),
-            &[parent_value],
+            E::EF::from(enabler),
+            &poseidon2_entry(&[left_value, right_value, parent_value]),
         ));
 
         eval.finalize_logup_in_pairs();
@@ -379,4 +358,16 @@ impl FrameworkEval for Eval {
     }
 }
 
+/// Returns the `Poseidon2` relation entry of a hash: the initial state `[left, right, 0, ..., 0]`
+/// followed by its first element `parent`, the Merkle hashes not using the rest of the digest.
+fn poseidon2_entry<F: Clone + Zero>(
+    [left, right, parent]: &[F; 3],
+) -> [F; T + POSEIDON2_DIGEST_SIZE + 1] {
+    let mut entry = std::array::from_fn(|_| F::zero());
+    entry[0] = left.clone();
+    entry[1] = right.clone();
+    entry[T] = parent.clone();
+    entry
+}
+
 pub type Component = FrameworkComponent<Eval>;
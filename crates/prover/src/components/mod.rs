@@ -4,6 +4,7 @@ pub mod merkle;
 pub mod opcodes;
 pub mod poseidon2;
 use num_traits::Zero;
+use rayon::iter::ParallelIterator;
 use serde::{Deserialize, Serialize};
 pub use stwo_air_utils::trace::component_trace::ComponentTrace;
 pub use stwo_air_utils_derive::{IterMut, ParIterMut, Uninitialized};
@@ -20,10 +21,36 @@ use stwo_prover::core::poly::BitReversedOrder;
 use stwo_prover::core::poly::circle::CircleEvaluation;
 
 use crate::adapter::ProverInput;
-use crate::preprocessed::bitwise;
-use crate::preprocessed::range_check::{range_check_8, range_check_16, range_check_20};
+use crate::errors::VerificationError;
+use crate::preprocessed::range_check::{
+    RangeCheckProvider, range_check_8, range_check_16, range_check_20,
+};
+use crate::preprocessed::{PreProcessedTrace, PreProcessedTraceBuilder, bitwise};
 use crate::public_data::PublicData;
 use crate::relations;
+use crate::trace_report::{ComponentReport, TraceReport};
+
+/// Generates the trace of a component in a `write_trace` span, which records the log size of
+/// its trace.
+macro_rules! write_component_trace {
+    ($component:literal, $write:expr) => {{
+        let span = tracing::info_span!(
+            "write_trace",
+            component = $component,
+            log_size = tracing::field::Empty
+        );
+        let (claim, trace, interaction_claim_data) = span.in_scope(|| $write);
+        span.record("log_size", claim.log_size);
+        (claim, trace, interaction_claim_data)
+    }};
+}
+
+/// Generates the interaction trace of a component in a `write_interaction_trace` span.
+macro_rules! write_component_interaction_trace {
+    ($component:literal, $write:expr) => {
+        tracing::info_span!("write_interaction_trace", component = $component).in_scope(|| $write)
+    };
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Claim {
@@ -103,6 +130,130 @@ impl Claim {
         self.bitwise.mix_into(channel);
     }
 
+    /// Returns the log size of each component, by name, in the order of
+    /// [`InteractionClaim::claimed_sums`].
+    /// Opcode components are listed individually.
+    pub fn component_log_sizes(&self) -> Vec<(&'static str, u32)> {
+        let mut log_sizes = self.opcodes.component_log_sizes();
+        log_sizes.extend([
+            ("memory", self.memory.log_size),
+            ("merkle", self.merkle.log_size),
+            ("clock_update", self.clock_update.log_size),
+            ("poseidon2", self.poseidon2.log_size),
+            ("range_check_8", self.range_check_8.log_size),
+            ("range_check_16", self.range_check_16.log_size),
+            ("range_check_20", self.range_check_20.log_size),
+            ("bitwise", self.bitwise.log_size),
+        ]);
+        log_sizes
+    }
+
+    /// Returns the preprocessed trace of the proof.
+    ///
+    /// The range check tables are sized from their usage, so their preprocessed columns depend on
+    /// the claim.
+    pub fn preprocessed_trace(&self) -> PreProcessedTrace {
+        PreProcessedTraceBuilder::new()
+            .with_bitwise(8)
+            .with_range_check(self.range_check_8.log_size)
+            .with_range_check(self.range_check_16.log_size)
+            .with_range_check(self.range_check_20.log_size)
+            .build()
+    }
+
+    /// Checks that the range check tables are within their bounds.
+    pub fn validate_range_checks(&self) -> Result<(), VerificationError> {
+        [
+            (
+                "range_check_8",
+                self.range_check_8.log_size,
+                self.range_check_8.is_valid(),
+            ),
+            (
+                "range_check_16",
+                self.range_check_16.log_size,
+                self.range_check_16.is_valid(),
+            ),
+            (
+                "range_check_20",
+                self.range_check_20.log_size,
+                self.range_check_20.is_valid(),
+            ),
+        ]
+        .into_iter()
+        .try_for_each(|(component, log_size, is_valid)| {
+            if is_valid {
+                Ok(())
+            } else {
+                Err(VerificationError::InvalidRangeCheckLogSize {
+                    component,
+                    log_size,
+                })
+            }
+        })
+    }
+
+    /// Returns the trace size statistics of each component.
+    /// Opcode components are listed individually.
+    ///
+    /// Range check and bitwise components are lookup tables: all their rows are used.
+    pub fn trace_report(&self, interaction_claim_data: &InteractionClaimData) -> TraceReport {
+        let mut components = self
+            .opcodes
+            .component_reports(&interaction_claim_data.opcodes);
+        components.extend([
+            ComponentReport::new(
+                "memory",
+                self.memory.log_size,
+                interaction_claim_data.memory.non_padded_length,
+                &self.memory.log_sizes(),
+            ),
+            ComponentReport::new(
+                "merkle",
+                self.merkle.log_size,
+                interaction_claim_data.merkle.non_padded_length,
+                &self.merkle.log_sizes(),
+            ),
+            ComponentReport::new(
+                "clock_update",
+                self.clock_update.log_size,
+                interaction_claim_data.clock_update.non_padded_length,
+                &self.clock_update.log_sizes(),
+            ),
+            ComponentReport::new(
+                "poseidon2",
+                self.poseidon2.log_size,
+                interaction_claim_data.poseidon2.non_padded_length,
+                &self.poseidon2.log_sizes(),
+            ),
+            ComponentReport::new(
+                "range_check_8",
+                self.range_check_8.log_size,
+                1 << self.range_check_8.log_size,
+                &self.range_check_8.log_sizes(),
+            ),
+            ComponentReport::new(
+                "range_check_16",
+                self.range_check_16.log_size,
+                1 << self.range_check_16.log_size,
+                &self.range_check_16.log_sizes(),
+            ),
+            ComponentReport::new(
+                "range_check_20",
+                self.range_check_20.log_size,
+                1 << self.range_check_20.log_size,
+                &self.range_check_20.log_sizes(),
+            ),
+            ComponentReport::new(
+                "bitwise",
+                self.bitwise.log_size,
+                1 << self.bitwise.log_size,
+                &self.bitwise.log_sizes(),
+            ),
+        ]);
+        TraceReport { components }
+    }
+
     pub fn write_trace<MC: MerkleChannel>(
         input: &mut ProverInput,
     ) -> (
@@ -118,38 +269,54 @@ impl Claim {
             opcodes::Claim::write_trace(&mut input.instructions);
 
         // Write memory trace
-        let (memory_claim, memory_trace, memory_interaction_claim_data) =
-            memory::Claim::write_trace(&input.memory, &input.merkle_trees);
+        let (memory_claim, memory_trace, memory_interaction_claim_data) = write_component_trace!(
+            "memory",
+            memory::Claim::write_trace(&input.memory, &input.merkle_trees)
+        );
 
         // Write merkle trace
-        let (merkle_claim, merkle_trace, merkle_interaction_claim_data) =
-            merkle::Claim::write_trace::<MC>(&input.merkle_trees);
+        let (merkle_claim, merkle_trace, merkle_interaction_claim_data) = write_component_trace!(
+            "merkle",
+            merkle::Claim::write_trace::<MC>(&input.merkle_trees)
+        );
 
         // Write poseidon2 trace
-        let (poseidon2_claim, poseidon2_trace, poseidon2_interaction_claim_data) =
-            poseidon2::Claim::write_trace(&input.poseidon2_inputs);
+        let (poseidon2_claim, poseidon2_trace, poseidon2_interaction_claim_data) = write_component_trace!(
+            "poseidon2",
+            poseidon2::Claim::write_trace(&input.poseidon2_inputs, &input.poseidon2_sponge_inputs)
+        );
 
         // Write clock update trace
-        let (clock_update_claim, clock_update_trace, clock_update_interaction_claim_data) =
-            clock_update::Claim::write_trace(&input.memory.clock_update_data);
+        let (clock_update_claim, clock_update_trace, clock_update_interaction_claim_data) = write_component_trace!(
+            "clock_update",
+            clock_update::Claim::write_trace(&input.memory.clock_update_data)
+        );
 
         // Write range_check components
         let range_check_8_data = opcodes_interaction_claim_data.range_check_8();
-        let (range_check_8_claim, range_check_8_trace, range_check_8_interaction_claim_data) =
-            range_check_8::Claim::write_trace(range_check_8_data);
+        let (range_check_8_claim, range_check_8_trace, range_check_8_interaction_claim_data) = write_component_trace!(
+            "range_check_8",
+            range_check_8::Claim::write_trace(range_check_8_data)
+        );
 
         let range_check_16_data = opcodes_interaction_claim_data.range_check_16();
-        let (range_check_16_claim, range_check_16_trace, range_check_16_interaction_claim_data) =
-            range_check_16::Claim::write_trace(range_check_16_data);
-
-        let range_check_20_data = opcodes_interaction_claim_data.range_check_20();
-        let (range_check_20_claim, range_check_20_trace, range_check_20_interaction_claim_data) =
-            range_check_20::Claim::write_trace(range_check_20_data);
+        let (range_check_16_claim, range_check_16_trace, range_check_16_interaction_claim_data) = write_component_trace!(
+            "range_check_16",
+            range_check_16::Claim::write_trace(range_check_16_data)
+        );
+
+        let range_check_20_data = opcodes_interaction_claim_data
+            .range_check_20()
+            .chain(clock_update_interaction_claim_data.get_range_check_20());
+        let (range_check_20_claim, range_check_20_trace, range_check_20_interaction_claim_data) = write_component_trace!(
+            "range_check_20",
+            range_check_20::Claim::write_trace(range_check_20_data)
+        );
 
         // Write bitwise components
         let bitwise_data = opcodes_interaction_claim_data.bitwise();
         let (bitwise_claim, bitwise_trace, bitwise_interaction_claim_data) =
-            bitwise::Claim::write_trace(bitwise_data);
+            write_component_trace!("bitwise", bitwise::Claim::write_trace(bitwise_data));
 
         // Gather all lookup data
         let interaction_claim_data = InteractionClaimData {
@@ -208,52 +375,68 @@ impl InteractionClaim {
                 &interaction_claim_data.opcodes,
             );
 
-        let (memory_interaction_claim, memory_interaction_trace) =
+        let (memory_interaction_claim, memory_interaction_trace) = write_component_interaction_trace!(
+            "memory",
             memory::InteractionClaim::write_interaction_trace(
                 relations,
                 &interaction_claim_data.memory,
-            );
+            )
+        );
 
-        let (merkle_interaction_claim, merkle_interaction_trace) =
+        let (merkle_interaction_claim, merkle_interaction_trace) = write_component_interaction_trace!(
+            "merkle",
             merkle::InteractionClaim::write_interaction_trace(
                 relations,
                 &interaction_claim_data.merkle,
-            );
+            )
+        );
 
-        let (clock_update_interaction_claim, clock_update_interaction_trace) =
+        let (clock_update_interaction_claim, clock_update_interaction_trace) = write_component_interaction_trace!(
+            "clock_update",
             clock_update::InteractionClaim::write_interaction_trace(
                 relations,
                 &interaction_claim_data.clock_update,
-            );
-        let (poseidon2_interaction_claim, poseidon2_interaction_trace) =
+            )
+        );
+        let (poseidon2_interaction_claim, poseidon2_interaction_trace) = write_component_interaction_trace!(
+            "poseidon2",
             poseidon2::InteractionClaim::write_interaction_trace(
                 relations,
                 &interaction_claim_data.poseidon2,
-            );
+            )
+        );
 
-        let (range_check_8_interaction_claim, range_check_8_interaction_trace) =
+        let (range_check_8_interaction_claim, range_check_8_interaction_trace) = write_component_interaction_trace!(
+            "range_check_8",
             range_check_8::InteractionClaim::write_interaction_trace(
                 &relations.range_check_8,
                 &interaction_claim_data.range_check_8,
-            );
+            )
+        );
 
-        let (range_check_16_interaction_claim, range_check_16_interaction_trace) =
+        let (range_check_16_interaction_claim, range_check_16_interaction_trace) = write_component_interaction_trace!(
+            "range_check_16",
             range_check_16::InteractionClaim::write_interaction_trace(
                 &relations.range_check_16,
                 &interaction_claim_data.range_check_16,
-            );
+            )
+        );
 
-        let (range_check_20_interaction_claim, range_check_20_interaction_trace) =
+        let (range_check_20_interaction_claim, range_check_20_interaction_trace) = write_component_interaction_trace!(
+            "range_check_20",
             range_check_20::InteractionClaim::write_interaction_trace(
                 &relations.range_check_20,
                 &interaction_claim_data.range_check_20,
-            );
+            )
+        );
 
-        let (bitwise_interaction_claim, bitwise_interaction_trace) =
+        let (bitwise_interaction_claim, bitwise_interaction_trace) = write_component_interaction_trace!(
+            "bitwise",
             bitwise::InteractionClaim::write_interaction_trace(
                 &relations.bitwise,
                 &interaction_claim_data.bitwise,
-            );
+            )
+        );
 
         (
             opcodes_interaction_trace
@@ -283,18 +466,34 @@ impl InteractionClaim {
     pub fn claimed_sum(&self, relations: &Relations, public_data: PublicData) -> SecureField {
         let mut sum = SecureField::zero();
         sum += public_data.initial_logup_sum(relations);
-        sum += self.opcodes.claimed_sum();
-        sum += self.memory.claimed_sum;
-        sum += self.merkle.claimed_sum;
-        sum += self.clock_update.claimed_sum;
-        sum += self.poseidon2.claimed_sum;
-        sum += self.range_check_8.claimed_sum;
-        sum += self.range_check_16.claimed_sum;
-        sum += self.range_check_20.claimed_sum;
-        sum += self.bitwise.claimed_sum;
+        sum += self.components_claimed_sum();
         sum
     }
 
+    /// Returns the sum of the claimed sums of all components, excluding the public data.
+    pub fn components_claimed_sum(&self) -> SecureField {
+        self.claimed_sums()
+            .into_iter()
+            .fold(SecureField::zero(), |acc, (_, sum)| acc + sum)
+    }
+
+    /// Returns the claimed sum of each component, by name.
+    /// Opcode components are listed individually.
+    pub fn claimed_sums(&self) -> Vec<(&'static str, SecureField)> {
+        let mut sums = self.opcodes.claimed_sums();
+        sums.extend([
+            ("memory", self.memory.claimed_sum),
+            ("merkle", self.merkle.claimed_sum),
+            ("clock_update", self.clock_update.claimed_sum),
+            ("poseidon2", self.poseidon2.claimed_sum),
+            ("range_check_8", self.range_check_8.claimed_sum),
+            ("range_check_16", self.range_check_16.claimed_sum),
+            ("range_check_20", self.range_check_20.claimed_sum),
+            ("bitwise", self.bitwise.claimed_sum),
+        ]);
+        sums
+    }
+
     pub fn mix_into(&self, channel: &mut impl Channel) {
         self.opcodes.mix_into(channel);
         self.memory.mix_into(channel);
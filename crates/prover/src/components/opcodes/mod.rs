@@ -38,6 +38,28 @@ macro_rules! define_opcodes {
                 $(self.$opcode.mix_into(channel);)*
             }
 
+            /// Returns the log size of each opcode component, by name.
+            pub fn component_log_sizes(&self) -> Vec<(&'static str, u32)> {
+                vec![
+                    $((stringify!($opcode), self.$opcode.log_size),)*
+                ]
+            }
+
+            /// Returns the trace size statistics of each opcode component.
+            pub fn component_reports(
+                &self,
+                interaction_claim_data: &InteractionClaimData,
+            ) -> Vec<$crate::trace_report::ComponentReport> {
+                vec![
+                    $($crate::trace_report::ComponentReport::new(
+                        stringify!($opcode),
+                        self.$opcode.log_size,
+                        interaction_claim_data.$opcode.non_padded_length,
+                        &self.$opcode.log_sizes(),
+                    ),)*
+                ]
+            }
+
             pub fn write_trace<MC: MerkleChannel>(
                 instructions: &mut Instructions
             ) -> (
@@ -58,8 +80,15 @@ macro_rules! define_opcodes {
                         )+
                     }
 
+                    let span = tracing::info_span!(
+                        "write_trace",
+                        component = stringify!($opcode),
+                        n_rows = grouped_states.len(),
+                        log_size = tracing::field::Empty
+                    );
                     let (paste::paste! { [<$opcode _claim>] }, paste::paste! { [<$opcode _trace_raw>] }, paste::paste! { [<$opcode _interaction_claim_data>] }) =
-                        $opcode::Claim::write_trace(&mut grouped_states, &instructions.data_accesses);
+                        span.in_scope(|| $opcode::Claim::write_trace(&mut grouped_states, &instructions.data_accesses));
+                    span.record("log_size", paste::paste! { [<$opcode _claim>] }.log_size);
                     let paste::paste! { [<$opcode _trace>] } = Box::new(paste::paste! { [<$opcode _trace_raw>] }.to_evals().into_iter());
                 )*
 
@@ -113,6 +142,13 @@ macro_rules! define_opcodes {
                 sum
             }
 
+            /// Returns the claimed sum of each opcode component, by name.
+            pub fn claimed_sums(&self) -> Vec<(&'static str, SecureField)> {
+                vec![
+                    $((stringify!($opcode), self.$opcode.claimed_sum),)*
+                ]
+            }
+
             pub fn mix_into(&self, channel: &mut impl Channel) {
                 $(self.$opcode.mix_into(channel);)*
             }
@@ -126,10 +162,11 @@ macro_rules! define_opcodes {
             ) {
                 $(
                     let ($opcode, paste::paste! { [<$opcode _interaction_trace>] }) =
-                        $opcode::InteractionClaim::write_interaction_trace(
-                            &relations,
-                            &interaction_claim_data.$opcode,
-                        );
+                        tracing::info_span!("write_interaction_trace", component = stringify!($opcode))
+                            .in_scope(|| $opcode::InteractionClaim::write_interaction_trace(
+                                &relations,
+                                &interaction_claim_data.$opcode,
+                            ));
                 )*
 
                 let interaction_claim = Self {
@@ -188,9 +225,6 @@ macro_rules! define_opcodes {
                     $(
                         Instruction::$opcode_variant { .. } => {},
                     )*
-                    // Unsound opcodes
-                    Instruction::PrintM31 { .. } => {},
-                    Instruction::PrintU32 { .. } => {} ,
                 }
             };
         };
@@ -265,4 +299,8 @@ define_opcodes!(
         u32_store_bitwise_fp_imm
     ),
     ([StoreLeFpImm], store_le_fp_imm),
+    ([StorePowFpImm], store_pow_fp_imm),
+    ([Poseidon2AbsorbFpFp], poseidon2_absorb_fp_fp),
+    ([Poseidon2SqueezeFpFp], poseidon2_squeeze_fp_fp),
+    ([PrintM31, PrintU32], debug_print),
 );
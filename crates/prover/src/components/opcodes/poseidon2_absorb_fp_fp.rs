@@ -0,0 +1,559 @@
+//! This component is used to prove the Poseidon2AbsorbFpFp opcode.
+//! ([fp + dst_off], ..., [fp + dst_off + 7]) = poseidon2_absorb(([fp + state_off], ..., [fp + state_off + 7]), ([fp + src_off], ..., [fp + src_off + 7]))
+//!
+//! The permutation itself is proven by the Poseidon2 component: this component emits the
+//! input state `[state_0, ..., state_7, block_0, ..., block_7]` together with the digest.
+//!
+//! # Columns
+//!
+//! - enabler
+//! - pc
+//! - fp
+//! - clock
+//! - inst_prev_clock
+//! - state_off
+//! - src_off
+//! - dst_off
+//! - src_prev_clock_0, ..., src_prev_clock_15 (state_0, ..., state_7, block_0, ..., block_7)
+//! - src_val_0, ..., src_val_15
+//! - dst_prev_clock_0, ..., dst_prev_clock_7
+//! - dst_prev_val_0, ..., dst_prev_val_7
+//! - dst_val_0, ..., dst_val_7
+//!
+//! # Constraints
+//!
+//! With `src_addr = [fp + state_off, ..., fp + state_off + 7, fp + src_off, ..., fp + src_off + 7]`
+//! and `dst_addr = [fp + dst_off, ..., fp + dst_off + 7]`:
+//!
+//! * enabler is a bool
+//!   * `enabler * (1 - enabler)`
+//! * registers update is regular
+//!   * `- [pc, fp, clock] + [pc + 1, fp, clock + 1]` in `Registers` relation
+//! * read instruction from memory
+//!   * `- [pc, inst_prev_clk, opcode_constant, state_off, src_off, dst_off] + [pc, clk, opcode_constant, state_off, src_off, dst_off]` in `Memory` relation
+//!   * `- [clk - inst_prev_clk - 1]` in `RangeCheck20` relation
+//! * read src_i for i in 0..16
+//!   * `- [src_addr_i, src_prev_clk_i, src_val_i] + [src_addr_i, clk, src_val_i]` in `Memory` relation
+//!   * `- [clk - src_prev_clk_i - 1]` in `RangeCheck20` relation
+//! * write dst_i for i in 0..8
+//!   * `- [dst_addr_i, dst_prev_clk_i, dst_prev_val_i] + [dst_addr_i, clk, dst_val_i]` in `Memory` relation
+//!   * `- [clk - dst_prev_clk_i - 1]` in `RangeCheck20` relation
+//! * dst is the digest of the permutation
+//!   * `+ [src_val_0, ..., src_val_15, dst_val_0, ..., dst_val_7, 1]` in `Poseidon2` relation
+
+use cairo_m_common::instruction::{
+    POSEIDON2_ABSORB_FP_FP, POSEIDON2_DIGEST_SIZE, POSEIDON2_SPONGE_RATE,
+};
+use itertools::izip;
+use num_traits::{One, Zero};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use rayon::slice::ParallelSlice;
+use serde::{Deserialize, Serialize};
+use stwo_air_utils::trace::component_trace::ComponentTrace;
+use stwo_air_utils_derive::{IterMut, ParIterMut, Uninitialized};
+use stwo_constraint_framework::logup::LogupTraceGenerator;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, Relation, RelationEntry,
+};
+use stwo_prover::core::backend::BackendForChannel;
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::simd::conversion::Pack;
+use stwo_prover::core::backend::simd::m31::{LOG_N_LANES, N_LANES, PackedM31};
+use stwo_prover::core::backend::simd::qm31::PackedQM31;
+use stwo_prover::core::channel::{Channel, MerkleChannel};
+use stwo_prover::core::fields::m31::{BaseField, M31};
+use stwo_prover::core::fields::qm31::{SECURE_EXTENSION_DEGREE, SecureField};
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::core::poly::BitReversedOrder;
+use stwo_prover::core::poly::circle::CircleEvaluation;
+
+use crate::adapter::ExecutionBundle;
+use crate::adapter::memory::DataAccess;
+use crate::components::Relations;
+use crate::poseidon2::T;
+use crate::preprocessed::bitwise::BitwiseProvider;
+use crate::preprocessed::range_check::RangeCheckProvider;
+use crate::utils::data_accesses::{get_prev_clock, get_prev_value, get_value};
+use crate::utils::enabler::Enabler;
+use crate::utils::execution_bundle::PackedExecutionBundle;
+
+/// Number of felts read: the state and the block.
+const N_SRC: usize = POSEIDON2_DIGEST_SIZE + POSEIDON2_SPONGE_RATE;
+/// Number of felts written: the digest.
+const N_DST: usize = POSEIDON2_DIGEST_SIZE;
+
+/// Index of the first source previous clock column in the trace.
+const SRC_PREV_CLOCK_COLUMNS_OFFSET: usize = 8;
+/// Index of the first source value column in the trace.
+const SRC_VAL_COLUMNS_OFFSET: usize = SRC_PREV_CLOCK_COLUMNS_OFFSET + N_SRC;
+/// Index of the first dst previous clock column in the trace.
+const DST_PREV_CLOCK_COLUMNS_OFFSET: usize = SRC_VAL_COLUMNS_OFFSET + N_SRC;
+/// Index of the first dst previous value column in the trace.
+const DST_PREV_VAL_COLUMNS_OFFSET: usize = DST_PREV_CLOCK_COLUMNS_OFFSET + N_DST;
+/// Index of the first dst value column in the trace.
+const DST_VAL_COLUMNS_OFFSET: usize = DST_PREV_VAL_COLUMNS_OFFSET + N_DST;
+
+const N_TRACE_COLUMNS: usize = DST_VAL_COLUMNS_OFFSET + N_DST;
+const N_MEMORY_LOOKUPS: usize = 2 * (1 + N_SRC + N_DST);
+const N_REGISTERS_LOOKUPS: usize = 2;
+const N_RANGE_CHECK_20_LOOKUPS: usize = 1 + N_SRC + N_DST;
+const N_POSEIDON2_LOOKUPS: usize = 1;
+
+const N_LOOKUPS_COLUMNS: usize = SECURE_EXTENSION_DEGREE
+    * (N_MEMORY_LOOKUPS + N_REGISTERS_LOOKUPS + N_RANGE_CHECK_20_LOOKUPS + N_POSEIDON2_LOOKUPS)
+        .div_ceil(2);
+
+pub struct InteractionClaimData {
+    pub lookup_data: LookupData,
+    pub non_padded_length: usize,
+}
+
+#[derive(Uninitialized, IterMut, ParIterMut)]
+pub struct LookupData {
+    pub memory: [Vec<[PackedM31; 6]>; N_MEMORY_LOOKUPS],
+    pub registers: [Vec<[PackedM31; 3]>; N_REGISTERS_LOOKUPS],
+    pub range_check_20: [Vec<PackedM31>; N_RANGE_CHECK_20_LOOKUPS],
+    pub poseidon2: [Vec<[PackedM31; T + N_DST + 1]>; N_POSEIDON2_LOOKUPS],
+}
+
+// Implement RangeCheckProvider to expose range_check_20 data
+impl RangeCheckProvider for InteractionClaimData {
+    fn get_range_check_20(&self) -> impl ParallelIterator<Item = &PackedM31> {
+        self.lookup_data.range_check_20.par_iter().flatten()
+    }
+}
+
+impl BitwiseProvider for InteractionClaimData {}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Claim {
+    pub log_size: u32,
+}
+
+impl Claim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+    }
+
+    pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
+        let trace = vec![self.log_size; N_TRACE_COLUMNS];
+        let interaction_trace = vec![self.log_size; N_LOOKUPS_COLUMNS];
+        TreeVec::new(vec![vec![], trace, interaction_trace])
+    }
+
+    /// Writes the trace for the Poseidon2AbsorbFpFp opcode.
+    ///
+    /// # Important
+    /// This function consumes the contents of `inputs` by clearing it after processing.
+    /// This is done to free memory during proof generation as the inputs are no longer needed
+    /// after being packed into SIMD-friendly format.
+    pub fn write_trace<MC: MerkleChannel>(
+        inputs: &mut Vec<ExecutionBundle>,
+        data_accesses: &[DataAccess],
+    ) -> (Self, ComponentTrace<N_TRACE_COLUMNS>, InteractionClaimData)
+    where
+        SimdBackend: BackendForChannel<MC>,
+    {
+        let non_padded_length = inputs.len();
+        let log_size = std::cmp::max(LOG_N_LANES, inputs.len().next_power_of_two().ilog2());
+
+        let (mut trace, mut lookup_data) = unsafe {
+            (
+                ComponentTrace::<N_TRACE_COLUMNS>::uninitialized(log_size),
+                LookupData::uninitialized(log_size - LOG_N_LANES),
+            )
+        };
+        inputs.resize(1 << log_size, ExecutionBundle::default());
+        let packed_inputs: Vec<PackedExecutionBundle> = inputs
+            .par_chunks_exact(N_LANES)
+            .map(|chunk| {
+                let array: [ExecutionBundle; N_LANES] = chunk.try_into().unwrap();
+                Pack::pack(array)
+            })
+            .collect();
+        // Clear the inputs to free memory early. The data has been packed into SIMD format
+        // and the original inputs are no longer needed. This reduces memory pressure during
+        // proof generation. Note: this preserves the vector's capacity for potential reuse.
+        inputs.clear();
+        inputs.shrink_to_fit();
+
+        let zero = PackedM31::from(M31::zero());
+        let one = PackedM31::from(M31::one());
+        let enabler_col = Enabler::new(non_padded_length);
+
+        (
+            trace.par_iter_mut(),
+            packed_inputs.par_iter(),
+            lookup_data.par_iter_mut(),
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(row_index, (mut row, input, lookup_data))| {
+                let enabler = enabler_col.packed_at(row_index);
+                let pc = input.pc;
+                let fp = input.fp;
+                let clock = input.clock;
+                let inst_prev_clock = input.inst_prev_clock;
+                let opcode_constant = PackedM31::from(M31::from(POSEIDON2_ABSORB_FP_FP));
+                let state_off = input.inst_value_1;
+                let src_off = input.inst_value_2;
+                let dst_off = input.inst_value_3;
+
+                // Accesses are the state, the block and dst, in this order
+                let src_addrs: [PackedM31; N_SRC] = std::array::from_fn(|i| {
+                    if i < POSEIDON2_DIGEST_SIZE {
+                        fp + state_off + PackedM31::from(M31::from(i as u32))
+                    } else {
+                        fp + src_off
+                            + PackedM31::from(M31::from((i - POSEIDON2_DIGEST_SIZE) as u32))
+                    }
+                });
+                let src_prev_clocks: [PackedM31; N_SRC] =
+                    std::array::from_fn(|i| get_prev_clock(input, data_accesses, i));
+                let src_vals: [PackedM31; N_SRC] =
+                    std::array::from_fn(|i| get_value(input, data_accesses, i));
+                let dst_addrs: [PackedM31; N_DST] =
+                    std::array::from_fn(|i| fp + dst_off + PackedM31::from(M31::from(i as u32)));
+                let dst_prev_clocks: [PackedM31; N_DST] =
+                    std::array::from_fn(|i| get_prev_clock(input, data_accesses, N_SRC + i));
+                let dst_prev_vals: [PackedM31; N_DST] =
+                    std::array::from_fn(|i| get_prev_value(input, data_accesses, N_SRC + i));
+                let dst_vals: [PackedM31; N_DST] =
+                    std::array::from_fn(|i| get_value(input, data_accesses, N_SRC + i));
+
+                *row[0] = enabler;
+                *row[1] = pc;
+                *row[2] = fp;
+                *row[3] = clock;
+                *row[4] = inst_prev_clock;
+                *row[5] = state_off;
+                *row[6] = src_off;
+                *row[7] = dst_off;
+                for (i, (prev_clock, val)) in izip!(src_prev_clocks, src_vals).enumerate() {
+                    *row[SRC_PREV_CLOCK_COLUMNS_OFFSET + i] = prev_clock;
+                    *row[SRC_VAL_COLUMNS_OFFSET + i] = val;
+                }
+                for (i, (prev_clock, prev_val, val)) in
+                    izip!(dst_prev_clocks, dst_prev_vals, dst_vals).enumerate()
+                {
+                    *row[DST_PREV_CLOCK_COLUMNS_OFFSET + i] = prev_clock;
+                    *row[DST_PREV_VAL_COLUMNS_OFFSET + i] = prev_val;
+                    *row[DST_VAL_COLUMNS_OFFSET + i] = val;
+                }
+
+                *lookup_data.registers[0] = [input.pc, input.fp, input.clock];
+                *lookup_data.registers[1] = [input.pc + one, input.fp, input.clock + one];
+
+                *lookup_data.memory[0] = [
+                    input.pc,
+                    inst_prev_clock,
+                    opcode_constant,
+                    state_off,
+                    src_off,
+                    dst_off,
+                ];
+                *lookup_data.memory[1] = [
+                    input.pc,
+                    clock,
+                    opcode_constant,
+                    state_off,
+                    src_off,
+                    dst_off,
+                ];
+                *lookup_data.range_check_20[0] = clock - inst_prev_clock - enabler;
+
+                // The sources are left unchanged: their previous value is their value
+                let accesses = izip!(
+                    src_addrs.into_iter().chain(dst_addrs),
+                    src_prev_clocks.into_iter().chain(dst_prev_clocks),
+                    src_vals.into_iter().chain(dst_prev_vals),
+                    src_vals.into_iter().chain(dst_vals),
+                );
+                for (i, (addr, prev_clock, prev_val, val)) in accesses.enumerate() {
+                    *lookup_data.memory[2 + 2 * i] = [addr, prev_clock, prev_val, zero, zero, zero];
+                    *lookup_data.memory[3 + 2 * i] = [addr, clock, val, zero, zero, zero];
+                    *lookup_data.range_check_20[1 + i] = clock - prev_clock - enabler;
+                }
+
+                let mut poseidon2_entry = [one; T + N_DST + 1];
+                poseidon2_entry[..T].copy_from_slice(&src_vals);
+                poseidon2_entry[T..T + N_DST].copy_from_slice(&dst_vals);
+                *lookup_data.poseidon2[0] = poseidon2_entry;
+            });
+
+        (
+            Self { log_size },
+            trace,
+            InteractionClaimData {
+                lookup_data,
+                non_padded_length,
+            },
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct InteractionClaim {
+    pub claimed_sum: SecureField,
+}
+impl InteractionClaim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.claimed_sum]);
+    }
+
+    pub fn write_interaction_trace(
+        relations: &Relations,
+        interaction_claim_data: &InteractionClaimData,
+    ) -> (
+        Self,
+        impl IntoIterator<Item = CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) {
+        let log_size = interaction_claim_data.lookup_data.memory[0].len().ilog2() + LOG_N_LANES;
+        let mut interaction_trace = LogupTraceGenerator::new(log_size);
+        let enabler_col = Enabler::new(interaction_claim_data.non_padded_length);
+
+        // Registers lookups
+        let mut col = interaction_trace.new_col();
+        (
+            col.par_iter_mut(),
+            &interaction_claim_data.lookup_data.registers[0],
+            &interaction_claim_data.lookup_data.registers[1],
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(i, (writer, registers_prev, registers_new))| {
+                let num_prev = -PackedQM31::from(enabler_col.packed_at(i));
+                let num_new = PackedQM31::from(enabler_col.packed_at(i));
+                let denom_prev: PackedQM31 = relations.registers.combine(registers_prev);
+                let denom_new: PackedQM31 = relations.registers.combine(registers_new);
+
+                let numerator = num_prev * denom_new + num_new * denom_prev;
+                let denom = denom_prev * denom_new;
+
+                writer.write_frac(numerator, denom);
+            });
+        col.finalize_col();
+
+        // Memory lookups
+        for i in 0..N_MEMORY_LOOKUPS / 2 {
+            let mut col = interaction_trace.new_col();
+            (
+                col.par_iter_mut(),
+                &interaction_claim_data.lookup_data.memory[i * 2],
+                &interaction_claim_data.lookup_data.memory[i * 2 + 1],
+            )
+                .into_par_iter()
+                .enumerate()
+                .for_each(|(i, (writer, memory_prev, memory_new))| {
+                    let num_prev = -PackedQM31::from(enabler_col.packed_at(i));
+                    let num_new = PackedQM31::from(enabler_col.packed_at(i));
+                    let denom_prev: PackedQM31 = relations.memory.combine(memory_prev);
+                    let denom_new: PackedQM31 = relations.memory.combine(memory_new);
+
+                    let numerator = num_prev * denom_new + num_new * denom_prev;
+                    let denom = denom_prev * denom_new;
+
+                    writer.write_frac(numerator, denom);
+                });
+            col.finalize_col();
+        }
+
+        // Range check 20 lookups
+        for i in 0..N_RANGE_CHECK_20_LOOKUPS / 2 {
+            let mut col = interaction_trace.new_col();
+            (
+                col.par_iter_mut(),
+                &interaction_claim_data.lookup_data.range_check_20[i * 2],
+                &interaction_claim_data.lookup_data.range_check_20[i * 2 + 1],
+            )
+                .into_par_iter()
+                .for_each(|(writer, range_check_20_0, range_check_20_1)| {
+                    let num = -PackedQM31::one();
+                    let denom_0: PackedQM31 =
+                        relations.range_check_20.combine(&[*range_check_20_0]);
+                    let denom_1: PackedQM31 =
+                        relations.range_check_20.combine(&[*range_check_20_1]);
+
+                    let numerator = num * denom_1 + num * denom_0;
+                    let denom = denom_0 * denom_1;
+
+                    writer.write_frac(numerator, denom);
+                });
+            col.finalize_col();
+        }
+
+        // Last range check 20 and Poseidon2 lookups
+        let mut col = interaction_trace.new_col();
+        (
+            col.par_iter_mut(),
+            &interaction_claim_data.lookup_data.range_check_20[N_RANGE_CHECK_20_LOOKUPS - 1],
+            &interaction_claim_data.lookup_data.poseidon2[0],
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(i, (writer, range_check_20, poseidon2))| {
+                let num_range_check = -PackedQM31::one();
+                let num_poseidon2 = PackedQM31::from(enabler_col.packed_at(i));
+                let denom_range_check: PackedQM31 =
+                    relations.range_check_20.combine(&[*range_check_20]);
+                let denom_poseidon2: PackedQM31 = relations.poseidon2.combine(poseidon2);
+
+                let numerator =
+                    num_range_check * denom_poseidon2 + num_poseidon2 * denom_range_check;
+                let denom = denom_range_check * denom_poseidon2;
+
+                writer.write_frac(numerator, denom);
+            });
+        col.finalize_col();
+
+        let (trace, claimed_sum) = interaction_trace.finalize_last();
+        (Self { claimed_sum }, trace)
+    }
+}
+
+pub struct Eval {
+    pub claim: Claim,
+    pub relations: Relations,
+}
+
+impl FrameworkEval for Eval {
+    fn log_size(&self) -> u32 {
+        self.claim.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size() + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let one = E::F::from(M31::one());
+        let opcode_constant = E::F::from(M31::from(POSEIDON2_ABSORB_FP_FP));
+
+        let enabler = eval.next_trace_mask();
+        let pc = eval.next_trace_mask();
+        let fp = eval.next_trace_mask();
+        let clock = eval.next_trace_mask();
+        let inst_prev_clock = eval.next_trace_mask();
+        let state_off = eval.next_trace_mask();
+        let src_off = eval.next_trace_mask();
+        let dst_off = eval.next_trace_mask();
+        let src_prev_clocks: [E::F; N_SRC] = std::array::from_fn(|_| eval.next_trace_mask());
+        let src_vals: [E::F; N_SRC] = std::array::from_fn(|_| eval.next_trace_mask());
+        let dst_prev_clocks: [E::F; N_DST] = std::array::from_fn(|_| eval.next_trace_mask());
+        let dst_prev_vals: [E::F; N_DST] = std::array::from_fn(|_| eval.next_trace_mask());
+        let dst_vals: [E::F; N_DST] = std::array::from_fn(|_| eval.next_trace_mask());
+
+        let src_addrs: [E::F; N_SRC] = std::array::from_fn(|i| {
+            if i < POSEIDON2_DIGEST_SIZE {
+                fp.clone() + state_off.clone() + E::F::from(M31::from(i as u32))
+            } else {
+                fp.clone()
+                    + src_off.clone()
+                    + E::F::from(M31::from((i - POSEIDON2_DIGEST_SIZE) as u32))
+            }
+        });
+        let dst_addrs: [E::F; N_DST] =
+            std::array::from_fn(|i| fp.clone() + dst_off.clone() + E::F::from(M31::from(i as u32)));
+
+        // Enabler is 1 or 0
+        eval.add_constraint(enabler.clone() * (one.clone() - enabler.clone()));
+
+        // Registers update
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.registers,
+            -E::EF::from(enabler.clone()),
+            &[pc.clone(), fp.clone(), clock.clone()],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.registers,
+            E::EF::from(enabler.clone()),
+            &[pc.clone() + one.clone(), fp, clock.clone() + one.clone()],
+        ));
+
+        // Read instruction from memory
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            -E::EF::from(enabler.clone()),
+            &[
+                pc.clone(),
+                inst_prev_clock.clone(),
+                opcode_constant.clone(),
+                state_off.clone(),
+                src_off.clone(),
+                dst_off.clone(),
+            ],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(enabler.clone()),
+            &[
+                pc,
+                clock.clone(),
+                opcode_constant,
+                state_off,
+                src_off,
+                dst_off,
+            ],
+        ));
+
+        // Read state and block
+        for (addr, prev_clock, val) in izip!(&src_addrs, &src_prev_clocks, &src_vals) {
+            eval.add_to_relation(RelationEntry::new(
+                &self.relations.memory,
+                -E::EF::from(enabler.clone()),
+                &[addr.clone(), prev_clock.clone(), val.clone()],
+            ));
+            eval.add_to_relation(RelationEntry::new(
+                &self.relations.memory,
+                E::EF::from(enabler.clone()),
+                &[addr.clone(), clock.clone(), val.clone()],
+            ));
+        }
+
+        // Write dst
+        for (addr, prev_clock, prev_val, val) in
+            izip!(&dst_addrs, &dst_prev_clocks, &dst_prev_vals, &dst_vals)
+        {
+            eval.add_to_relation(RelationEntry::new(
+                &self.relations.memory,
+                -E::EF::from(enabler.clone()),
+                &[addr.clone(), prev_clock.clone(), prev_val.clone()],
+            ));
+            eval.add_to_relation(RelationEntry::new(
+                &self.relations.memory,
+                E::EF::from(enabler.clone()),
+                &[addr.clone(), clock.clone(), val.clone()],
+            ));
+        }
+
+        // Range check 20
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.range_check_20,
+            -E::EF::one(),
+            &[clock.clone() - inst_prev_clock - enabler.clone()],
+        ));
+        for prev_clock in src_prev_clocks.into_iter().chain(dst_prev_clocks) {
+            eval.add_to_relation(RelationEntry::new(
+                &self.relations.range_check_20,
+                -E::EF::one(),
+                &[clock.clone() - prev_clock - enabler.clone()],
+            ));
+        }
+
+        // Permutation of [state, block], whose digest is dst
+        let poseidon2_entry: Vec<E::F> =
+            src_vals.into_iter().chain(dst_vals).chain([one]).collect();
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.poseidon2,
+            E::EF::from(enabler),
+            &poseidon2_entry,
+        ));
+
+        eval.finalize_logup_in_pairs();
+        eval
+    }
+}
+
+pub type Component = FrameworkComponent<Eval>;
@@ -0,0 +1,522 @@
+//! This component is used to prove the StorePowFpImm opcode.
+//! [fp + dst_off] = [fp + src_off] ^ imm, with 2 <= imm <= MAX_POW_EXPONENT
+//!
+//! All the powers of src from 2 to MAX_POW_EXPONENT are computed by repeated multiplication,
+//! and a one-hot selector picks the one matching the exponent.
+//!
+//! # Columns
+//!
+//! - enabler
+//! - pc
+//! - fp
+//! - clock
+//! - inst_prev_clock
+//! - src_off
+//! - imm
+//! - dst_off
+//! - src_prev_clock
+//! - src_val
+//! - dst_prev_clock
+//! - dst_prev_val
+//! - dst_val
+//! - pow_2, ..., pow_{MAX_POW_EXPONENT}
+//! - is_exp_2, ..., is_exp_{MAX_POW_EXPONENT}
+//!
+//! # Constraints
+//!
+//! * enabler is a bool
+//!   * `enabler * (1 - enabler)`
+//! * pow_k is src^k
+//!   * `pow_2 - src_val * src_val`
+//!   * `pow_k - pow_{k-1} * src_val` for k > 2
+//! * is_exp_k is a bool
+//!   * `is_exp_k * (1 - is_exp_k)`
+//! * exactly one of is_exp_k is set for enabled rows, and it matches the exponent
+//!   * `sum_k is_exp_k - enabler`
+//!   * `sum_k k * is_exp_k - imm`
+//! * dst_val is the selected power
+//!   * `dst_val - sum_k is_exp_k * pow_k`
+//! * registers update is regular
+//!   * `- [pc, fp, clock] + [pc + 1, fp, clock + 1]` in `Registers` relation
+//! * read instruction from memory
+//!   * `- [pc, inst_prev_clk, opcode_constant, src_off, imm, dst_off] + [pc, clk, opcode_constant, src_off, imm, dst_off]` in `Memory` relation
+//!   * `- [clk - inst_prev_clk - 1]` in `RangeCheck20` relation
+//! * read src
+//!   * `- [fp + src_off, src_prev_clk, src_val] + [fp + src_off, clk, src_val]` in `Memory` relation
+//!   * `- [clk - src_prev_clk - 1]` in `RangeCheck20` relation
+//! * write dst in [fp + dst_off]
+//!   * `- [fp + dst_off, dst_prev_clk, dst_prev_val] + [fp + dst_off, clk, dst_val]` in `Memory` relation
+//!   * `- [clk - dst_prev_clk - 1]` in `RangeCheck20` relation
+
+use cairo_m_common::instruction::{MAX_POW_EXPONENT, STORE_POW_FP_IMM};
+use num_traits::{One, Zero};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use rayon::slice::ParallelSlice;
+use serde::{Deserialize, Serialize};
+use stwo_air_utils::trace::component_trace::ComponentTrace;
+use stwo_air_utils_derive::{IterMut, ParIterMut, Uninitialized};
+use stwo_constraint_framework::logup::LogupTraceGenerator;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, Relation, RelationEntry,
+};
+use stwo_prover::core::backend::BackendForChannel;
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::simd::conversion::Pack;
+use stwo_prover::core::backend::simd::m31::{LOG_N_LANES, N_LANES, PackedM31};
+use stwo_prover::core::backend::simd::qm31::PackedQM31;
+use stwo_prover::core::channel::{Channel, MerkleChannel};
+use stwo_prover::core::fields::m31::{BaseField, M31};
+use stwo_prover::core::fields::qm31::{SECURE_EXTENSION_DEGREE, SecureField};
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::core::poly::BitReversedOrder;
+use stwo_prover::core::poly::circle::CircleEvaluation;
+
+use crate::adapter::ExecutionBundle;
+use crate::adapter::memory::DataAccess;
+use crate::components::Relations;
+use crate::preprocessed::bitwise::BitwiseProvider;
+use crate::preprocessed::range_check::RangeCheckProvider;
+use crate::utils::data_accesses::{get_prev_clock, get_prev_value, get_value};
+use crate::utils::enabler::Enabler;
+use crate::utils::execution_bundle::PackedExecutionBundle;
+
+/// Number of supported exponents, from 2 to MAX_POW_EXPONENT.
+const N_EXPONENTS: usize = MAX_POW_EXPONENT as usize - 1;
+/// Index of the first power column in the trace.
+const POW_COLUMNS_OFFSET: usize = 13;
+/// Index of the first exponent selector column in the trace.
+const IS_EXP_COLUMNS_OFFSET: usize = POW_COLUMNS_OFFSET + N_EXPONENTS;
+
+const N_TRACE_COLUMNS: usize = IS_EXP_COLUMNS_OFFSET + N_EXPONENTS;
+const N_MEMORY_LOOKUPS: usize = 6;
+const N_REGISTERS_LOOKUPS: usize = 2;
+const N_RANGE_CHECK_20_LOOKUPS: usize = 3;
+
+const N_LOOKUPS_COLUMNS: usize = SECURE_EXTENSION_DEGREE
+    * (N_MEMORY_LOOKUPS + N_REGISTERS_LOOKUPS + N_RANGE_CHECK_20_LOOKUPS).div_ceil(2);
+
+pub struct InteractionClaimData {
+    pub lookup_data: LookupData,
+    pub non_padded_length: usize,
+}
+
+#[derive(Uninitialized, IterMut, ParIterMut)]
+pub struct LookupData {
+    pub memory: [Vec<[PackedM31; 6]>; N_MEMORY_LOOKUPS],
+    pub registers: [Vec<[PackedM31; 3]>; N_REGISTERS_LOOKUPS],
+    pub range_check_20: [Vec<PackedM31>; N_RANGE_CHECK_20_LOOKUPS],
+}
+
+// Implement RangeCheckProvider to expose range_check_20 data
+impl RangeCheckProvider for InteractionClaimData {
+    fn get_range_check_20(&self) -> impl ParallelIterator<Item = &PackedM31> {
+        self.lookup_data.range_check_20.par_iter().flatten()
+    }
+}
+
+impl BitwiseProvider for InteractionClaimData {}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Claim {
+    pub log_size: u32,
+}
+
+impl Claim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u64(self.log_size as u64);
+    }
+
+    pub fn log_sizes(&self) -> TreeVec<Vec<u32>> {
+        let trace = vec![self.log_size; N_TRACE_COLUMNS];
+        let interaction_trace = vec![self.log_size; N_LOOKUPS_COLUMNS];
+        TreeVec::new(vec![vec![], trace, interaction_trace])
+    }
+
+    /// Writes the trace for the StorePowFpImm opcode.
+    ///
+    /// # Important
+    /// This function consumes the contents of `inputs` by clearing it after processing.
+    /// This is done to free memory during proof generation as the inputs are no longer needed
+    /// after being packed into SIMD-friendly format.
+    pub fn write_trace<MC: MerkleChannel>(
+        inputs: &mut Vec<ExecutionBundle>,
+        data_accesses: &[DataAccess],
+    ) -> (Self, ComponentTrace<N_TRACE_COLUMNS>, InteractionClaimData)
+    where
+        SimdBackend: BackendForChannel<MC>,
+    {
+        let non_padded_length = inputs.len();
+        let log_size = std::cmp::max(LOG_N_LANES, inputs.len().next_power_of_two().ilog2());
+
+        let (mut trace, mut lookup_data) = unsafe {
+            (
+                ComponentTrace::<N_TRACE_COLUMNS>::uninitialized(log_size),
+                LookupData::uninitialized(log_size - LOG_N_LANES),
+            )
+        };
+        inputs.resize(1 << log_size, ExecutionBundle::default());
+        let packed_inputs: Vec<PackedExecutionBundle> = inputs
+            .par_chunks_exact(N_LANES)
+            .map(|chunk| {
+                let array: [ExecutionBundle; N_LANES] = chunk.try_into().unwrap();
+                Pack::pack(array)
+            })
+            .collect();
+        // Clear the inputs to free memory early. The data has been packed into SIMD format
+        // and the original inputs are no longer needed. This reduces memory pressure during
+        // proof generation. Note: this preserves the vector's capacity for potential reuse.
+        inputs.clear();
+        inputs.shrink_to_fit();
+
+        let zero = PackedM31::from(M31::zero());
+        let one = PackedM31::from(M31::one());
+        let enabler_col = Enabler::new(non_padded_length);
+
+        (
+            trace.par_iter_mut(),
+            packed_inputs.par_iter(),
+            lookup_data.par_iter_mut(),
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(row_index, (mut row, input, lookup_data))| {
+                let enabler = enabler_col.packed_at(row_index);
+                let pc = input.pc;
+                let fp = input.fp;
+                let clock = input.clock;
+                let inst_prev_clock = input.inst_prev_clock;
+                let opcode_constant = PackedM31::from(M31::from(STORE_POW_FP_IMM));
+                let src_off = input.inst_value_1;
+                let imm = input.inst_value_2;
+                let dst_off = input.inst_value_3;
+
+                let src_prev_clock = get_prev_clock(input, data_accesses, 0);
+                let src_val = get_value(input, data_accesses, 0);
+                let dst_prev_clock = get_prev_clock(input, data_accesses, 1);
+                let dst_prev_val = get_prev_value(input, data_accesses, 1);
+                let dst_val = get_value(input, data_accesses, 1);
+
+                // pow_k = src^k for k in 2..=MAX_POW_EXPONENT
+                let mut pow = src_val;
+                let pows: [PackedM31; N_EXPONENTS] = std::array::from_fn(|_| {
+                    pow = pow * src_val;
+                    pow
+                });
+
+                // is_exp_k = 1 if imm == k, padding rows have imm == 0 and no selector set
+                let imm_values = imm.to_array();
+                let is_exp: [PackedM31; N_EXPONENTS] = std::array::from_fn(|i| {
+                    let exponent = (i + 2) as u32;
+                    PackedM31::from_array(imm_values.map(|v| M31::from(u32::from(v.0 == exponent))))
+                });
+
+                *row[0] = enabler;
+                *row[1] = pc;
+                *row[2] = fp;
+                *row[3] = clock;
+                *row[4] = inst_prev_clock;
+                *row[5] = src_off;
+                *row[6] = imm;
+                *row[7] = dst_off;
+                *row[8] = src_prev_clock;
+                *row[9] = src_val;
+                *row[10] = dst_prev_clock;
+                *row[11] = dst_prev_val;
+                *row[12] = dst_val;
+                for (i, (pow, is_exp_k)) in pows.into_iter().zip(is_exp).enumerate() {
+                    *row[POW_COLUMNS_OFFSET + i] = pow;
+                    *row[IS_EXP_COLUMNS_OFFSET + i] = is_exp_k;
+                }
+
+                *lookup_data.registers[0] = [input.pc, input.fp, input.clock];
+                *lookup_data.registers[1] = [input.pc + one, input.fp, input.clock + one];
+
+                *lookup_data.memory[0] = [
+                    input.pc,
+                    inst_prev_clock,
+                    opcode_constant,
+                    src_off,
+                    imm,
+                    dst_off,
+                ];
+                *lookup_data.memory[1] = [input.pc, clock, opcode_constant, src_off, imm, dst_off];
+
+                *lookup_data.memory[2] = [fp + src_off, src_prev_clock, src_val, zero, zero, zero];
+                *lookup_data.memory[3] = [fp + src_off, clock, src_val, zero, zero, zero];
+
+                *lookup_data.memory[4] =
+                    [fp + dst_off, dst_prev_clock, dst_prev_val, zero, zero, zero];
+                *lookup_data.memory[5] = [fp + dst_off, clock, dst_val, zero, zero, zero];
+
+                *lookup_data.range_check_20[0] = clock - inst_prev_clock - enabler;
+                *lookup_data.range_check_20[1] = clock - src_prev_clock - enabler;
+                *lookup_data.range_check_20[2] = clock - dst_prev_clock - enabler;
+            });
+
+        (
+            Self { log_size },
+            trace,
+            InteractionClaimData {
+                lookup_data,
+                non_padded_length,
+            },
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct InteractionClaim {
+    pub claimed_sum: SecureField,
+}
+impl InteractionClaim {
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_felts(&[self.claimed_sum]);
+    }
+
+    pub fn write_interaction_trace(
+        relations: &Relations,
+        interaction_claim_data: &InteractionClaimData,
+    ) -> (
+        Self,
+        impl IntoIterator<Item = CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) {
+        let log_size = interaction_claim_data.lookup_data.memory[0].len().ilog2() + LOG_N_LANES;
+        let mut interaction_trace = LogupTraceGenerator::new(log_size);
+        let enabler_col = Enabler::new(interaction_claim_data.non_padded_length);
+
+        // Registers lookups
+        let mut col = interaction_trace.new_col();
+        (
+            col.par_iter_mut(),
+            &interaction_claim_data.lookup_data.registers[0],
+            &interaction_claim_data.lookup_data.registers[1],
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(i, (writer, registers_prev, registers_new))| {
+                let num_prev = -PackedQM31::from(enabler_col.packed_at(i));
+                let num_new = PackedQM31::from(enabler_col.packed_at(i));
+                let denom_prev: PackedQM31 = relations.registers.combine(registers_prev);
+                let denom_new: PackedQM31 = relations.registers.combine(registers_new);
+
+                let numerator = num_prev * denom_new + num_new * denom_prev;
+                let denom = denom_prev * denom_new;
+
+                writer.write_frac(numerator, denom);
+            });
+        col.finalize_col();
+
+        // Memory lookups
+        for i in 0..N_MEMORY_LOOKUPS / 2 {
+            let mut col = interaction_trace.new_col();
+            (
+                col.par_iter_mut(),
+                &interaction_claim_data.lookup_data.memory[i * 2],
+                &interaction_claim_data.lookup_data.memory[i * 2 + 1],
+            )
+                .into_par_iter()
+                .enumerate()
+                .for_each(|(i, (writer, memory_prev, memory_new))| {
+                    let num_prev = -PackedQM31::from(enabler_col.packed_at(i));
+                    let num_new = PackedQM31::from(enabler_col.packed_at(i));
+                    let denom_prev: PackedQM31 = relations.memory.combine(memory_prev);
+                    let denom_new: PackedQM31 = relations.memory.combine(memory_new);
+
+                    let numerator = num_prev * denom_new + num_new * denom_prev;
+                    let denom = denom_prev * denom_new;
+
+                    writer.write_frac(numerator, denom);
+                });
+            col.finalize_col();
+        }
+
+        // Range check 20 lookups
+        let mut col = interaction_trace.new_col();
+        (
+            col.par_iter_mut(),
+            &interaction_claim_data.lookup_data.range_check_20[0],
+            &interaction_claim_data.lookup_data.range_check_20[1],
+        )
+            .into_par_iter()
+            .enumerate()
+            .for_each(|(_i, (writer, range_check_20_0, range_check_20_1))| {
+                let num = -PackedQM31::one();
+                let denom_0: PackedQM31 = relations.range_check_20.combine(&[*range_check_20_0]);
+                let denom_1: PackedQM31 = relations.range_check_20.combine(&[*range_check_20_1]);
+
+                let numerator = num * denom_1 + num * denom_0;
+                let denom = denom_0 * denom_1;
+
+                writer.write_frac(numerator, denom);
+            });
+        col.finalize_col();
+
+        let mut col = interaction_trace.new_col();
+        col.par_iter_mut()
+            .zip(&interaction_claim_data.lookup_data.range_check_20[2])
+            .for_each(|(writer, range_check_20_2)| {
+                let num = -PackedQM31::one();
+                let denom: PackedQM31 = relations.range_check_20.combine(&[*range_check_20_2]);
+                writer.write_frac(num, denom);
+            });
+        col.finalize_col();
+
+        let (trace, claimed_sum) = interaction_trace.finalize_last();
+        (Self { claimed_sum }, trace)
+    }
+}
+
+pub struct Eval {
+    pub claim: Claim,
+    pub relations: Relations,
+}
+
+impl FrameworkEval for Eval {
+    fn log_size(&self) -> u32 {
+        self.claim.log_size
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        self.log_size() + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let one = E::F::from(M31::one());
+        let opcode_constant = E::F::from(M31::from(STORE_POW_FP_IMM));
+
+        let enabler = eval.next_trace_mask();
+        let pc = eval.next_trace_mask();
+        let fp = eval.next_trace_mask();
+        let clock = eval.next_trace_mask();
+        let inst_prev_clock = eval.next_trace_mask();
+        let src_off = eval.next_trace_mask();
+        let imm = eval.next_trace_mask();
+        let dst_off = eval.next_trace_mask();
+        let src_prev_clock = eval.next_trace_mask();
+        let src_val = eval.next_trace_mask();
+        let dst_prev_clock = eval.next_trace_mask();
+        let dst_prev_val = eval.next_trace_mask();
+        let dst_val = eval.next_trace_mask();
+        let pows: [E::F; N_EXPONENTS] = std::array::from_fn(|_| eval.next_trace_mask());
+        let is_exp: [E::F; N_EXPONENTS] = std::array::from_fn(|_| eval.next_trace_mask());
+
+        // Enabler is 1 or 0
+        eval.add_constraint(enabler.clone() * (one.clone() - enabler.clone()));
+
+        // pow_k is src^k
+        let mut prev_pow = src_val.clone();
+        for pow in &pows {
+            eval.add_constraint(pow.clone() - prev_pow * src_val.clone());
+            prev_pow = pow.clone();
+        }
+
+        // Exactly one selector is set for enabled rows and it matches the exponent,
+        // dst_val is the selected power
+        let mut selector_sum = E::F::zero();
+        let mut exponent = E::F::zero();
+        let mut res = E::F::zero();
+        for (i, (is_exp_k, pow)) in is_exp.iter().zip(pows).enumerate() {
+            eval.add_constraint(is_exp_k.clone() * (one.clone() - is_exp_k.clone()));
+            selector_sum += is_exp_k.clone();
+            exponent += is_exp_k.clone() * E::F::from(M31::from(i as u32 + 2));
+            res += is_exp_k.clone() * pow;
+        }
+        eval.add_constraint(selector_sum - enabler.clone());
+        eval.add_constraint(exponent - imm.clone());
+        eval.add_constraint(dst_val.clone() - res);
+
+        // Registers update
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.registers,
+            -E::EF::from(enabler.clone()),
+            &[pc.clone(), fp.clone(), clock.clone()],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.registers,
+            E::EF::from(enabler.clone()),
+            &[pc.clone() + one.clone(), fp.clone(), clock.clone() + one],
+        ));
+
+        // Read instruction from memory
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            -E::EF::from(enabler.clone()),
+            &[
+                pc.clone(),
+                inst_prev_clock.clone(),
+                opcode_constant.clone(),
+                src_off.clone(),
+                imm.clone(),
+                dst_off.clone(),
+            ],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(enabler.clone()),
+            &[
+                pc,
+                clock.clone(),
+                opcode_constant,
+                src_off.clone(),
+                imm,
+                dst_off.clone(),
+            ],
+        ));
+
+        // Read src
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            -E::EF::from(enabler.clone()),
+            &[
+                fp.clone() + src_off.clone(),
+                src_prev_clock.clone(),
+                src_val.clone(),
+            ],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(enabler.clone()),
+            &[fp.clone() + src_off, clock.clone(), src_val],
+        ));
+
+        // Write dst
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            -E::EF::from(enabler.clone()),
+            &[
+                fp.clone() + dst_off.clone(),
+                dst_prev_clock.clone(),
+                dst_prev_val,
+            ],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.memory,
+            E::EF::from(enabler.clone()),
+            &[fp + dst_off, clock.clone(), dst_val],
+        ));
+
+        // Range check 20
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.range_check_20,
+            -E::EF::one(),
+            &[clock.clone() - inst_prev_clock - enabler.clone()],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.range_check_20,
+            -E::EF::one(),
+            &[clock.clone() - src_prev_clock - enabler.clone()],
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.relations.range_check_20,
+            -E::EF::one(),
+            &[clock - dst_prev_clock - enabler],
+        ));
+
+        eval.finalize_logup_in_pairs();
+        eval
+    }
+}
+
+pub type Component = FrameworkComponent<Eval>;
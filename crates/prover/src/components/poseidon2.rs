@@ -4,6 +4,8 @@
 //! # Columns
 //!
 //! - enabler: Boolean flag indicating active rows
+//! - is_sponge: Boolean flag indicating the permutations of the sponge instructions, whose digest
+//!   is the first POSEIDON2_DIGEST_SIZE elements of the final state instead of the first one
 //! - initial_state: T elements of the input state (16 elements)
 //! - first_half_full_rounds: 3 * T * (FULL_ROUNDS/2) intermediate values from external rounds
 //!   - squared_state_1: First squaring of state after adding round constants
@@ -20,6 +22,8 @@
 //!
 //! * enabler is a bool
 //!   * `enabler * (1 - enabler)`
+//! * is_sponge is a bool
+//!   * `is_sponge * (1 - is_sponge)`
 //! * first half full rounds:
 //!   - `enabler * (state[i]^2 - squared_state_1)`
 //!   - `enabler * (state[i]^2 - squared_state_2)`
@@ -33,12 +37,13 @@
 //!   - `enabler * (state[i]^2 - squared_state_2)`
 //!   - `enabler * (state[i] * initial_state[i] - final_state)`
 //! * lookup relations:
-//!   - `- enabler * [initial_state]` in `Poseidon2` relation
-//!   - `+ enabler * [final_state[0]]` in `Poseidon2` relation
+//!   - `- enabler * [initial_state, final_state[0], is_sponge * final_state[1], ...,
+//!     is_sponge * final_state[7], is_sponge]` in `Poseidon2` relation
 
 use std::ops::{Add, AddAssign, Mul, Sub};
 
-use num_traits::Zero;
+use cairo_m_common::instruction::POSEIDON2_DIGEST_SIZE;
+use num_traits::{One, Zero};
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use stwo_air_utils::trace::component_trace::ComponentTrace;
@@ -65,8 +70,10 @@ use crate::poseidon2::{
 };
 use crate::utils::enabler::Enabler;
 
-const N_TRACE_COLUMNS: usize = 1 + T * (1 + FULL_ROUNDS * 3) + 3 * PARTIAL_ROUNDS;
-const N_POSEIDON2_LOOKUPS: usize = 2;
+const N_TRACE_COLUMNS: usize = 2 + T * (1 + FULL_ROUNDS * 3) + 3 * PARTIAL_ROUNDS;
+const N_POSEIDON2_LOOKUPS: usize = 1;
+/// Number of elements of a `Poseidon2` relation entry
+const N_RELATION_ELEMENTS: usize = T + POSEIDON2_DIGEST_SIZE + 1;
 const N_INTERACTION_COLUMNS: usize = SECURE_EXTENSION_DEGREE * N_POSEIDON2_LOOKUPS.div_ceil(2);
 
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
@@ -86,7 +93,7 @@ pub struct InteractionClaimData {
 
 #[derive(Uninitialized, IterMut, ParIterMut)]
 pub struct LookupData {
-    pub poseidon2: [Vec<[PackedM31; T]>; N_POSEIDON2_LOOKUPS],
+    pub poseidon2: [Vec<[PackedM31; N_RELATION_ELEMENTS]>; N_POSEIDON2_LOOKUPS],
 }
 
 #[inline(always)]
@@ -168,20 +175,28 @@ impl Claim {
         channel.mix_u64(self.log_size as u64);
     }
 
+    /// Writes the trace of the permutations of the Merkle trees, then of the sponge
+    /// instructions.
     #[allow(clippy::needless_range_loop)]
     pub fn write_trace<MC: MerkleChannel>(
         inputs: &Vec<[M31; T]>,
+        sponge_inputs: &Vec<[M31; T]>,
     ) -> (Self, ComponentTrace<N_TRACE_COLUMNS>, InteractionClaimData)
     where
         SimdBackend: BackendForChannel<MC>,
     {
-        let non_padded_length = inputs.len();
+        let non_padded_length = inputs.len() + sponge_inputs.len();
         let log_size = std::cmp::max(non_padded_length.next_power_of_two(), N_LANES).ilog2();
 
-        // Pack round data from the prover input
-        let packed_inputs: Vec<[PackedM31; T]> = inputs
+        // Pack round data from the prover input, followed by the is_sponge flag
+        let packed_inputs: Vec<[PackedM31; T + 1]> = inputs
             .iter()
-            .chain(std::iter::repeat(&[M31::zero(); T]))
+            .map(|input| (input, M31::zero()))
+            .chain(sponge_inputs.iter().map(|input| (input, M31::one())))
+            .map(|(input, is_sponge)| {
+                std::array::from_fn(|i| if i < T { input[i] } else { is_sponge })
+            })
+            .chain(std::iter::repeat([M31::zero(); T + 1]))
             .take(1 << log_size)
             .array_chunks::<N_LANES>()
             .map(|chunk| {
@@ -207,19 +222,23 @@ impl Claim {
         )
             .into_par_iter()
             .enumerate()
-            .for_each(|(row_index, (mut row, mut state, lookup_data))| {
+            .for_each(|(row_index, (mut row, input, lookup_data))| {
                 let mut col_index = 0;
                 let enabler = enabler_col.packed_at(row_index);
+                let is_sponge = input[T];
+                let mut state: [PackedM31; T] = std::array::from_fn(|i| input[i]);
+                let initial_state = state;
 
                 *row[col_index] = enabler;
                 col_index += 1;
+                *row[col_index] = is_sponge;
+                col_index += 1;
 
-                // Add initial state to the trace and lookup data
+                // Add initial state to the trace
                 state.iter().for_each(|s| {
                     *row[col_index] = *s;
                     col_index += 1;
                 });
-                *lookup_data.poseidon2[0] = state;
 
                 // Apply initial linear layer
                 apply_external_round_matrix(&mut state);
@@ -308,10 +327,15 @@ impl Claim {
                     });
                 });
 
-                // Add digest to lookup data
-                let mut final_state = [zero; T];
-                final_state[0] = state[0];
-                *lookup_data.poseidon2[1] = final_state;
+                // Add initial state and digest to lookup data
+                let mut entry = [zero; N_RELATION_ELEMENTS];
+                entry[..T].copy_from_slice(&initial_state);
+                entry[T] = state[0];
+                for i in 1..POSEIDON2_DIGEST_SIZE {
+                    entry[T + i] = is_sponge * state[i];
+                }
+                entry[T + POSEIDON2_DIGEST_SIZE] = is_sponge;
+                *lookup_data.poseidon2[0] = entry;
             });
 
         (
@@ -344,20 +368,14 @@ impl InteractionClaim {
         (
             col.par_iter_mut(),
             &interaction_claim_data.lookup_data.poseidon2[0],
-            &interaction_claim_data.lookup_data.poseidon2[1],
         )
             .into_par_iter()
             .enumerate()
-            .for_each(|(i, (writer, value0, value1))| {
-                let num0: PackedQM31 = -PackedQM31::from(enabler_col.packed_at(i));
-                let denom0: PackedQM31 = relations.poseidon2.combine(value0);
-                let num1: PackedQM31 = PackedQM31::from(enabler_col.packed_at(i));
-                let denom1: PackedQM31 = relations.poseidon2.combine(value1);
-
-                let numerator = num0 * denom1 + num1 * denom0;
-                let denom = denom0 * denom1;
+            .for_each(|(i, (writer, value))| {
+                let num: PackedQM31 = -PackedQM31::from(enabler_col.packed_at(i));
+                let denom: PackedQM31 = relations.poseidon2.combine(value);
 
-                writer.write_frac(numerator, denom);
+                writer.write_frac(num, denom);
             });
         col.finalize_col();
 
@@ -390,10 +408,15 @@ impl FrameworkEval for Eval {
 
     #[allow(clippy::needless_range_loop)]
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let one = E::F::one();
         let enabler = eval.next_trace_mask();
+        let is_sponge = eval.next_trace_mask();
         let mut state: [_; T] = std::array::from_fn(|_| eval.next_trace_mask());
         let initial_state = state.clone();
 
+        // is_sponge is 1 or 0
+        eval.add_constraint(is_sponge.clone() * (one - is_sponge.clone()));
+
         // Apply initial linear layer
         apply_external_round_matrix(&mut state);
 
@@ -487,18 +510,18 @@ impl FrameworkEval for Eval {
             });
         });
 
-        // Use input state
-        eval.add_to_relation(RelationEntry::new(
-            &self.relations.poseidon2,
-            -E::EF::from(enabler.clone()),
-            &initial_state,
-        ));
-
-        // Emit next state (only the first element for the final round)
+        // Use input state and digest: the first element of the final state for the Merkle
+        // trees, its first POSEIDON2_DIGEST_SIZE elements for the sponge
+        let entry: Vec<E::F> = initial_state
+            .into_iter()
+            .chain([state[0].clone()])
+            .chain((1..POSEIDON2_DIGEST_SIZE).map(|i| is_sponge.clone() * state[i].clone()))
+            .chain([is_sponge.clone()])
+            .collect();
         eval.add_to_relation(RelationEntry::new(
             &self.relations.poseidon2,
-            E::EF::from(enabler),
-            &[state[0].clone()],
+            -E::EF::from(enabler),
+            &entry,
         ));
         eval.finalize_logup_in_pairs();
         eval
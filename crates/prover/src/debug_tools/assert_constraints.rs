@@ -1,17 +1,22 @@
 // Adapted from https://github.com/starkware-libs/stwo-cairo/blob/main/stwo_cairo_prover/crates/prover/src/debug_tools/assert_constraints.rs
 #![allow(unused)]
 
-use std::ops::Deref;
+use std::ops::{Deref, Mul};
 
 use itertools::Itertools;
+use num_traits::{One, Zero};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use stwo_constraint_framework::{
-    FrameworkComponent, FrameworkEval, PREPROCESSED_TRACE_IDX, TraceLocationAllocator,
-    assert_constraints_on_trace,
+    AssertEvaluator, Batching, EvalAtRow, FrameworkComponent, FrameworkEval, INTERACTION_TRACE_IDX,
+    PREPROCESSED_TRACE_IDX, TraceLocationAllocator, assert_constraints_on_trace,
 };
 use stwo_prover::core::ColumnVec;
 use stwo_prover::core::backend::{Backend, BackendForChannel, Column};
 use stwo_prover::core::channel::{Blake2sChannel, MerkleChannel};
+use stwo_prover::core::fields::FieldExpOps;
 use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::{SECURE_EXTENSION_DEGREE, SecureField};
+use stwo_prover::core::lookups::utils::Fraction;
 use stwo_prover::core::pcs::{TreeSubspan, TreeVec};
 use stwo_prover::core::poly::BitReversedOrder;
 use stwo_prover::core::poly::circle::CircleEvaluation;
@@ -19,19 +24,35 @@ use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
 
 use crate::adapter::ProverInput;
 use crate::components::{Claim, Components, InteractionClaim, Relations};
-use crate::preprocessed::PreProcessedTraceBuilder;
+use crate::errors::{ConstraintViolation, WitnessError};
+use crate::public_data::PublicData;
 
 pub fn assert_constraints(input: &mut ProverInput) {
+    let (commitment_scheme, components, ..) = mock_commit(input);
+    assert_components(commitment_scheme.trace_domain_evaluations(), &components);
+}
+
+/// Writes all traces to a [`MockCommitmentScheme`] and builds the components.
+fn mock_commit(
+    input: &mut ProverInput,
+) -> (
+    MockCommitmentScheme,
+    Components,
+    InteractionClaim,
+    Relations,
+) {
     let mut commitment_scheme = MockCommitmentScheme::default();
 
+    // The preprocessed trace depends on the sizes of the range check tables.
+    let (claim, trace, lookup_data) = Claim::write_trace::<Blake2sMerkleChannel>(input);
+
     // Preprocessed trace.
-    let preprocessed_trace = PreProcessedTraceBuilder::default().build();
+    let preprocessed_trace = claim.preprocessed_trace();
     let mut tree_builder = commitment_scheme.tree_builder();
     tree_builder.extend_evals(preprocessed_trace.gen_trace());
     tree_builder.finalize_interaction();
 
     // Base trace.
-    let (claim, trace, lookup_data) = Claim::write_trace::<Blake2sMerkleChannel>(input);
     let mut tree_builder = commitment_scheme.tree_builder();
     tree_builder.extend_evals(trace);
     tree_builder.finalize_interaction();
@@ -56,7 +77,7 @@ pub fn assert_constraints(input: &mut ProverInput) {
         &relations,
     );
 
-    assert_components(commitment_scheme.trace_domain_evaluations(), &components);
+    (commitment_scheme, components, interaction_claim, relations)
 }
 
 #[derive(Default)]
@@ -142,8 +163,46 @@ impl<B: BackendForChannel<MC>, MC: MerkleChannel> TreeBuilder<B>
 }
 
 /// Asserts that constraints are correctly enforced.
-/// When adding a component, this function should be updated.
 fn assert_components(trace: TreeVec<Vec<&Vec<M31>>>, components: &Components) {
+    for (_, check) in component_checks(&trace, components) {
+        check(CheckMode::Assert);
+    }
+}
+
+/// How a component check reports the constraints that don't hold.
+#[derive(Clone, Copy)]
+enum CheckMode {
+    /// Panics on the first failing constraint, with the checker of stwo.
+    Assert,
+    /// Returns the first failing row and constraint.
+    Locate,
+}
+
+/// A check of the constraints of a component, returning the first failing row and constraint
+/// when run in [`CheckMode::Locate`].
+type ComponentCheck<'a> = Box<dyn Fn(CheckMode) -> Option<(usize, usize)> + 'a>;
+
+/// Returns one named constraint check per component.
+/// When adding a component, this function should be updated.
+fn component_checks<'a>(
+    trace: &'a TreeVec<Vec<&Vec<M31>>>,
+    components: &'a Components,
+) -> Vec<(&'static str, ComponentCheck<'a>)> {
+    macro_rules! check {
+        ($name:expr, $component:expr) => {
+            (
+                $name,
+                Box::new(move |mode| match mode {
+                    CheckMode::Assert => {
+                        assert_component($component, trace);
+                        None
+                    }
+                    CheckMode::Locate => locate_violation($component, trace),
+                }) as ComponentCheck<'a>,
+            )
+        };
+    }
+
     let Components {
         opcodes,
         memory,
@@ -155,45 +214,99 @@ fn assert_components(trace: TreeVec<Vec<&Vec<M31>>>, components: &Components) {
         clock_update,
         bitwise,
     } = components;
-    assert_component(&opcodes.call_abs_imm, &trace);
-    assert_component(&opcodes.jmp_imm, &trace);
-    assert_component(&opcodes.jnz_fp_imm, &trace);
-    assert_component(&opcodes.ret, &trace);
-    assert_component(&opcodes.store_fp_fp, &trace);
-    assert_component(&opcodes.store_fp_imm, &trace);
-    assert_component(&opcodes.double_deref_fp_imm, &trace);
-    assert_component(&opcodes.double_deref_fp_fp, &trace);
-    assert_component(&opcodes.store_imm, &trace);
-    assert_component(&opcodes.store_frame_pointer, &trace);
-    assert_component(&opcodes.u32_store_imm, &trace);
-    assert_component(&opcodes.store_le_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_add_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_add_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_mul_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_mul_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_div_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_div_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_sub_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_eq_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_eq_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_lt_fp_imm, &trace);
-    assert_component(&opcodes.u32_store_lt_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_bitwise_fp_fp, &trace);
-    assert_component(&opcodes.u32_store_bitwise_fp_imm, &trace);
-    assert_component(memory, &trace);
-    assert_component(merkle, &trace);
-    assert_component(clock_update, &trace);
-    assert_component(poseidon2, &trace);
-    assert_component(range_check_8, &trace);
-    assert_component(range_check_16, &trace);
-    assert_component(range_check_20, &trace);
-    assert_component(bitwise, &trace);
+    vec![
+        check!("call_abs_imm", &opcodes.call_abs_imm),
+        check!("jmp_imm", &opcodes.jmp_imm),
+        check!("jnz_fp_imm", &opcodes.jnz_fp_imm),
+        check!("ret", &opcodes.ret),
+        check!("store_fp_fp", &opcodes.store_fp_fp),
+        check!("store_fp_imm", &opcodes.store_fp_imm),
+        check!("double_deref_fp_imm", &opcodes.double_deref_fp_imm),
+        check!("double_deref_fp_fp", &opcodes.double_deref_fp_fp),
+        check!("store_imm", &opcodes.store_imm),
+        check!("store_frame_pointer", &opcodes.store_frame_pointer),
+        check!("u32_store_imm", &opcodes.u32_store_imm),
+        check!("store_le_fp_imm", &opcodes.store_le_fp_imm),
+        check!("store_pow_fp_imm", &opcodes.store_pow_fp_imm),
+        check!("poseidon2_absorb_fp_fp", &opcodes.poseidon2_absorb_fp_fp),
+        check!("poseidon2_squeeze_fp_fp", &opcodes.poseidon2_squeeze_fp_fp),
+        check!("debug_print", &opcodes.debug_print),
+        check!("u32_store_add_fp_imm", &opcodes.u32_store_add_fp_imm),
+        check!("u32_store_add_fp_fp", &opcodes.u32_store_add_fp_fp),
+        check!("u32_store_mul_fp_imm", &opcodes.u32_store_mul_fp_imm),
+        check!("u32_store_mul_fp_fp", &opcodes.u32_store_mul_fp_fp),
+        check!("u32_store_div_fp_imm", &opcodes.u32_store_div_fp_imm),
+        check!("u32_store_div_fp_fp", &opcodes.u32_store_div_fp_fp),
+        check!("u32_store_sub_fp_fp", &opcodes.u32_store_sub_fp_fp),
+        check!("u32_store_eq_fp_fp", &opcodes.u32_store_eq_fp_fp),
+        check!("u32_store_eq_fp_imm", &opcodes.u32_store_eq_fp_imm),
+        check!("u32_store_lt_fp_imm", &opcodes.u32_store_lt_fp_imm),
+        check!("u32_store_lt_fp_fp", &opcodes.u32_store_lt_fp_fp),
+        check!("u32_store_bitwise_fp_fp", &opcodes.u32_store_bitwise_fp_fp),
+        check!(
+            "u32_store_bitwise_fp_imm",
+            &opcodes.u32_store_bitwise_fp_imm
+        ),
+        check!("memory", memory),
+        check!("merkle", merkle),
+        check!("clock_update", clock_update),
+        check!("poseidon2", poseidon2),
+        check!("range_check_8", range_check_8),
+        check!("range_check_16", range_check_16),
+        check!("range_check_20", range_check_20),
+        check!("bitwise", bitwise),
+    ]
 }
 
-fn assert_component<E: FrameworkEval + Sync>(
-    component: &FrameworkComponent<E>,
+/// Checks the constraints of each component and returns the first violation found.
+///
+/// Intended for debugging, as it regenerates all the traces.
+pub fn find_constraint_violation(input: &mut ProverInput) -> Option<ConstraintViolation> {
+    let (commitment_scheme, components, ..) = mock_commit(input);
+    first_violation(&commitment_scheme.trace_domain_evaluations(), &components)
+}
+
+/// Runs the checks of the verifier directly on the witness: the balance of the lookups, then
+/// the constraints of each component.
+///
+/// Proving a witness that fails these checks either fails or yields a proof that the verifier
+/// rejects. Checking the witness without committing to it allows testing that corrupted
+/// witnesses are rejected.
+pub fn check_witness(input: &mut ProverInput) -> Result<(), WitnessError> {
+    let public_data = PublicData::new(input);
+    let (commitment_scheme, components, interaction_claim, relations) = mock_commit(input);
+
+    if interaction_claim.claimed_sum(&relations, public_data) != SecureField::zero() {
+        return Err(WitnessError::InvalidLogupSum);
+    }
+    match first_violation(&commitment_scheme.trace_domain_evaluations(), &components) {
+        Some(violation) => Err(WitnessError::ConstraintsNotSatisfied { violation }),
+        None => Ok(()),
+    }
+}
+
+/// Checks the constraints of each component and returns the first violation found.
+fn first_violation(
     trace: &TreeVec<Vec<&Vec<M31>>>,
-) {
+    components: &Components,
+) -> Option<ConstraintViolation> {
+    component_checks(trace, components)
+        .into_iter()
+        .find_map(|(component, check)| {
+            let (row, constraint) = check(CheckMode::Locate)?;
+            Some(ConstraintViolation {
+                component,
+                row,
+                constraint,
+            })
+        })
+}
+
+/// Returns the columns of the trace used by a component.
+fn component_trace<'a, E: FrameworkEval>(
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&'a Vec<M31>>>,
+) -> TreeVec<Vec<&'a Vec<M31>>> {
     let mut component_trace = trace
         .sub_tree(component.trace_locations())
         .map(|tree| tree.into_iter().cloned().collect_vec());
@@ -202,7 +315,14 @@ fn assert_component<E: FrameworkEval + Sync>(
         .iter()
         .map(|idx| trace[PREPROCESSED_TRACE_IDX][*idx])
         .collect();
+    component_trace
+}
 
+fn assert_component<E: FrameworkEval + Sync>(
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+) {
+    let component_trace = component_trace(component, trace);
     let log_size = component.log_size();
 
     let component_eval = component.deref();
@@ -215,3 +335,124 @@ fn assert_component<E: FrameworkEval + Sync>(
         component.claimed_sum(),
     );
 }
+
+/// Returns the first row of a component at which a constraint doesn't hold, and the index of
+/// this constraint.
+fn locate_violation<E: FrameworkEval + Sync>(
+    component: &FrameworkComponent<E>,
+    trace: &TreeVec<Vec<&Vec<M31>>>,
+) -> Option<(usize, usize)> {
+    let component_trace = component_trace(component, trace);
+    let log_size = component.log_size();
+    let claimed_sum = component.claimed_sum();
+
+    let component_eval = component.deref();
+    (0..1 << log_size).into_par_iter().find_map_first(|row| {
+        let eval = RowEvaluator::new(&component_trace, row, log_size, claimed_sum);
+        let eval = component_eval.evaluate(eval);
+        eval.failing_constraint.map(|constraint| (row, constraint))
+    })
+}
+
+/// Evaluates the constraints of a component at a row of its trace, recording the first
+/// constraint that doesn't vanish instead of panicking.
+///
+/// The trace masks are read by the [`AssertEvaluator`] of stwo, and the logup constraints
+/// are built as in its `LogupAtRow`.
+struct RowEvaluator<'a> {
+    masks: AssertEvaluator<'a>,
+    /// Number of constraints added so far.
+    n_constraints: usize,
+    /// Index of the first constraint that doesn't vanish at the row.
+    failing_constraint: Option<usize>,
+    /// Fractions added to the relations since the last finalization.
+    fracs: Vec<Fraction<SecureField, SecureField>>,
+    /// Shift of the cumulative sum of each row, so that the logup constraint is uniform.
+    cumsum_shift: SecureField,
+}
+
+impl<'a> RowEvaluator<'a> {
+    fn new(
+        trace: &'a TreeVec<Vec<&'a Vec<M31>>>,
+        row: usize,
+        log_size: u32,
+        claimed_sum: SecureField,
+    ) -> Self {
+        Self {
+            masks: AssertEvaluator::new(trace, row, log_size, claimed_sum),
+            n_constraints: 0,
+            failing_constraint: None,
+            fracs: Vec::new(),
+            cumsum_shift: claimed_sum * M31::from(1 << log_size).inverse(),
+        }
+    }
+}
+
+impl EvalAtRow for RowEvaluator<'_> {
+    type F = M31;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        self.masks.next_interaction_mask(interaction, offsets)
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        if self.failing_constraint.is_none() && !(SecureField::one() * constraint).is_zero() {
+            self.failing_constraint = Some(self.n_constraints);
+        }
+        self.n_constraints += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn write_logup_frac(&mut self, fraction: Fraction<Self::EF, Self::EF>) {
+        self.fracs.push(fraction);
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        assert_eq!(batching.len(), self.fracs.len());
+        let n_batches = batching.iter().max().map_or(0, |last| last + 1);
+        let mut batches = vec![(SecureField::zero(), SecureField::one()); n_batches];
+        for (&batch, frac) in batching.iter().zip(std::mem::take(&mut self.fracs)) {
+            let (numerator, denominator) = &mut batches[batch];
+            *numerator = *numerator * frac.denominator + frac.numerator * *denominator;
+            *denominator *= frac.denominator;
+        }
+        let Some(((last_numerator, last_denominator), batches)) = batches.split_last() else {
+            return;
+        };
+
+        // All batches except the last are cumulatively summed in their own columns.
+        let mut prev_col_cumsum = SecureField::zero();
+        for (numerator, denominator) in batches {
+            let [cur_cumsum] = self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [0]);
+            let diff = cur_cumsum - prev_col_cumsum;
+            prev_col_cumsum = cur_cumsum;
+            self.add_constraint(diff * *denominator - *numerator);
+        }
+
+        let [prev_row_cumsum, cur_cumsum] =
+            self.next_extension_interaction_mask(INTERACTION_TRACE_IDX, [-1, 0]);
+        let diff = cur_cumsum - prev_row_cumsum - prev_col_cumsum + self.cumsum_shift;
+        self.add_constraint(diff * *last_denominator - *last_numerator);
+    }
+
+    fn finalize_logup(&mut self) {
+        let batching = (0..self.fracs.len()).collect();
+        self.finalize_logup_batched(&batching);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        let batching = (0..self.fracs.len()).map(|n| n / 2).collect();
+        self.finalize_logup_batched(&batching);
+    }
+}
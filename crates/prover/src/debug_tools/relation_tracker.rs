@@ -208,6 +208,10 @@ fn relation_entries(
         add_to_relation_entries(&opcodes.store_frame_pointer, trace),
         add_to_relation_entries(&opcodes.u32_store_imm, trace),
         add_to_relation_entries(&opcodes.store_le_fp_imm, trace),
+        add_to_relation_entries(&opcodes.store_pow_fp_imm, trace),
+        add_to_relation_entries(&opcodes.poseidon2_absorb_fp_fp, trace),
+        add_to_relation_entries(&opcodes.poseidon2_squeeze_fp_fp, trace),
+        add_to_relation_entries(&opcodes.debug_print, trace),
         add_to_relation_entries(&opcodes.u32_store_add_fp_imm, trace),
         add_to_relation_entries(&opcodes.u32_store_add_fp_fp, trace),
         add_to_relation_entries(&opcodes.u32_store_mul_fp_imm, trace),
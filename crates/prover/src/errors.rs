@@ -1,3 +1,8 @@
+use std::fmt;
+
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::prover::{
     ProvingError as StwoProvingError, VerificationError as StwoVerificationError,
 };
@@ -7,12 +12,139 @@ use thiserror::Error;
 pub enum VerificationError {
     #[error("Invalid logup sum.")]
     InvalidLogupSum,
+    #[error("The public data commitment does not match the public data.")]
+    InvalidPublicDataCommitment,
+    #[error("The public data commitment does not match the expected commitment.")]
+    PublicDataCommitmentMismatch,
+    #[error(
+        "The commitment of the public memory page at address {start} does not match its values."
+    )]
+    InvalidPublicPage { start: M31 },
+    #[error("Invalid log size {log_size} for the {component} table.")]
+    InvalidRangeCheckLogSize {
+        component: &'static str,
+        log_size: u32,
+    },
+    #[error("The proof was produced with a different PCS configuration than the verifier's.")]
+    PcsConfigMismatch,
+    #[error("The proof parameters are weaker than the security policy: {0}.")]
+    InsecureParameters(String),
+    #[error("Proof {index} of the batch is invalid: {source}")]
+    BatchProof {
+        index: usize,
+        source: Box<VerificationError>,
+    },
     #[error(transparent)]
     Stwo(#[from] StwoVerificationError),
 }
 
+/// Phase of the proving pipeline in which an error occurred.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProvingPhase {
+    PreprocessedTrace,
+    ExecutionTrace,
+    InteractionTrace,
+    Stark,
+}
+
+impl fmt::Display for ProvingPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PreprocessedTrace => write!(f, "preprocessed trace"),
+            Self::ExecutionTrace => write!(f, "execution trace"),
+            Self::InteractionTrace => write!(f, "interaction trace"),
+            Self::Stark => write!(f, "stark"),
+        }
+    }
+}
+
+/// Claimed logup sum of a single component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentLogupSum {
+    pub component: &'static str,
+    pub claimed_sum: SecureField,
+}
+
+/// Location of a constraint that doesn't hold on the trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// Name of the failing component.
+    pub component: &'static str,
+    /// First row of the component trace at which a constraint fails.
+    pub row: usize,
+    /// Index of the failing constraint, in the order the component adds its constraints.
+    /// The logup constraints come last.
+    pub constraint: usize,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "component `{}` at row {}, constraint {}",
+            self.component, self.row, self.constraint
+        )
+    }
+}
+
+/// Reason a witness is rejected, see [`crate::debug_tools::assert_constraints::check_witness`].
+#[derive(Clone, Debug, Error)]
+pub enum WitnessError {
+    #[error("Invalid logup sum.")]
+    InvalidLogupSum,
+    #[error("Constraints not satisfied in {violation}")]
+    ConstraintsNotSatisfied { violation: ConstraintViolation },
+}
+
 #[derive(Clone, Debug, Error)]
 pub enum ProvingError {
-    #[error(transparent)]
-    Stwo(#[from] StwoProvingError),
+    #[error("Stwo proving error during {phase} phase: {source}")]
+    Stwo {
+        phase: ProvingPhase,
+        #[source]
+        source: StwoProvingError,
+    },
+    /// The components' claimed sums don't cancel out the sum computed from the public data.
+    #[error("Invalid logup sum: claimed {claimed}, computed {computed}. Component sums: {}", format_component_sums(.components))]
+    InvalidLogupSum {
+        /// Sum of the claimed sums of all components.
+        claimed: SecureField,
+        /// Expected sum, computed from the public data.
+        computed: SecureField,
+        /// Claimed sum of each component.
+        components: Vec<ComponentLogupSum>,
+    },
+    #[error("Constraints not satisfied in {violation}")]
+    ConstraintsNotSatisfied { violation: ConstraintViolation },
+    /// The proof was cancelled through its [`crate::job::JobControl`].
+    #[error("Proof cancelled before the {phase} phase")]
+    Cancelled { phase: ProvingPhase },
+    /// The prover panicked while running as a [`crate::job::ProvingJob`].
+    #[error("Prover panicked during the {phase} phase: {message}")]
+    Panicked {
+        phase: ProvingPhase,
+        message: String,
+    },
+}
+
+fn format_component_sums(components: &[ComponentLogupSum]) -> String {
+    components
+        .iter()
+        .filter(|c| !c.claimed_sum.is_zero())
+        .map(|c| format!("{}={}", c.component, c.claimed_sum))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl ProvingError {
+    /// Returns the phase in which the error occurred.
+    pub const fn phase(&self) -> ProvingPhase {
+        match self {
+            Self::Stwo { phase, .. } | Self::Cancelled { phase } | Self::Panicked { phase, .. } => {
+                *phase
+            }
+            Self::InvalidLogupSum { .. } => ProvingPhase::InteractionTrace,
+            Self::ConstraintsNotSatisfied { .. } => ProvingPhase::Stark,
+        }
+    }
 }
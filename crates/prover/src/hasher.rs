@@ -0,0 +1,172 @@
+//! Selection of the Merkle hash function at runtime.
+//!
+//! The prover and verifier are generic over the [`MerkleChannel`](stwo_prover::core::channel::MerkleChannel), which fixes the hash
+//! function of the commitments at compile time. [`MerkleHasherKind`] picks it from a
+//! configuration value instead, e.g. a command line option:
+//! - [`MerkleHasherKind::Blake2s`] is the fastest to prove and to verify natively.
+//! - [`MerkleHasherKind::Poseidon252`] is an algebraic hash, cheaper to verify in a
+//!   Starknet contract or a recursive proof. It is not available on `wasm32`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use stwo_prover::core::pcs::PcsConfig;
+use stwo_prover::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+use stwo_prover::core::vcs::poseidon252_merkle::{
+    Poseidon252MerkleChannel, Poseidon252MerkleHasher,
+};
+
+use crate::Proof;
+use crate::adapter::ProverInput;
+use crate::errors::{ProvingError, VerificationError};
+use crate::prover::{ProverOptions, prove_cairo_m_with_report};
+use crate::trace_report::TraceReport;
+use crate::verification_report::VerificationReport;
+use crate::verifier::{SecurityPolicy, verify_cairo_m, verify_cairo_m_with_policy};
+
+/// The hash function of the Merkle commitments of a proof.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MerkleHasherKind {
+    #[default]
+    Blake2s,
+    Poseidon252,
+}
+
+impl MerkleHasherKind {
+    /// All the supported hash functions.
+    pub const ALL: [Self; 2] = [Self::Blake2s, Self::Poseidon252];
+
+    /// Returns the name of the hash function, as parsed by [`FromStr`].
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Blake2s => "blake2s",
+            Self::Poseidon252 => "poseidon252",
+        }
+    }
+}
+
+impl fmt::Display for MerkleHasherKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for MerkleHasherKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "unknown Merkle hasher '{s}', expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// A proof whose Merkle hash function was selected at runtime.
+#[derive(Clone)]
+pub enum AnyProof {
+    Blake2s(Proof<Blake2sMerkleHasher>),
+    Poseidon252(Proof<Poseidon252MerkleHasher>),
+}
+
+impl AnyProof {
+    /// Returns the hash function of the commitments of the proof.
+    pub const fn hasher(&self) -> MerkleHasherKind {
+        match self {
+            Self::Blake2s(_) => MerkleHasherKind::Blake2s,
+            Self::Poseidon252(_) => MerkleHasherKind::Poseidon252,
+        }
+    }
+
+    /// Serializes the proof to JSON, in the format of the underlying [`Proof`].
+    pub fn to_json(&self) -> sonic_rs::Result<String> {
+        match self {
+            Self::Blake2s(proof) => sonic_rs::to_string(proof),
+            Self::Poseidon252(proof) => sonic_rs::to_string(proof),
+        }
+    }
+
+    /// Deserializes a proof committed with `hasher` from JSON.
+    pub fn from_json(hasher: MerkleHasherKind, json: &str) -> sonic_rs::Result<Self> {
+        Ok(match hasher {
+            MerkleHasherKind::Blake2s => Self::Blake2s(sonic_rs::from_str(json)?),
+            MerkleHasherKind::Poseidon252 => Self::Poseidon252(sonic_rs::from_str(json)?),
+        })
+    }
+
+    /// Verifies the proof with the Merkle channel of its hash function.
+    ///
+    /// See [`verify_cairo_m`].
+    pub fn verify(
+        self,
+        pcs_config: Option<PcsConfig>,
+    ) -> Result<VerificationReport, VerificationError> {
+        match self {
+            Self::Blake2s(proof) => verify_cairo_m::<Blake2sMerkleChannel>(proof, pcs_config),
+            Self::Poseidon252(proof) => {
+                verify_cairo_m::<Poseidon252MerkleChannel>(proof, pcs_config)
+            }
+        }
+    }
+
+    /// Verifies the proof with the Merkle channel of its hash function, provided that its
+    /// parameters satisfy `policy`.
+    ///
+    /// See [`verify_cairo_m_with_policy`].
+    pub fn verify_with_policy(
+        self,
+        policy: &SecurityPolicy,
+    ) -> Result<VerificationReport, VerificationError> {
+        match self {
+            Self::Blake2s(proof) => {
+                verify_cairo_m_with_policy::<Blake2sMerkleChannel>(proof, policy)
+            }
+            Self::Poseidon252(proof) => {
+                verify_cairo_m_with_policy::<Poseidon252MerkleChannel>(proof, policy)
+            }
+        }
+    }
+}
+
+/// Proves a Cairo-M execution with the Merkle channel of `hasher`.
+///
+/// See [`prove_cairo_m_with_report`].
+pub fn prove_cairo_m_with_hasher(
+    hasher: MerkleHasherKind,
+    input: &mut ProverInput,
+    pcs_config: Option<PcsConfig>,
+    options: &ProverOptions,
+) -> Result<(AnyProof, TraceReport), ProvingError> {
+    Ok(match hasher {
+        MerkleHasherKind::Blake2s => {
+            let (proof, report) =
+                prove_cairo_m_with_report::<Blake2sMerkleChannel>(input, pcs_config, options)?;
+            (AnyProof::Blake2s(proof), report)
+        }
+        MerkleHasherKind::Poseidon252 => {
+            let (proof, report) =
+                prove_cairo_m_with_report::<Poseidon252MerkleChannel>(input, pcs_config, options)?;
+            (AnyProof::Poseidon252(proof), report)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hasher_kind() {
+        for kind in MerkleHasherKind::ALL {
+            assert_eq!(kind.to_string().parse::<MerkleHasherKind>(), Ok(kind));
+        }
+        assert_eq!("Blake2s".parse(), Ok(MerkleHasherKind::Blake2s));
+        assert!("sha256".parse::<MerkleHasherKind>().is_err());
+    }
+}
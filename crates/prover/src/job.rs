@@ -0,0 +1,208 @@
+//! Proofs run in the background, for servers embedding the prover.
+//!
+//! A proof takes minutes of CPU time: [`ProvingJob::spawn`] runs it on the rayon thread
+//! pool and returns a handle to follow its progress, cancel it or retrieve its result. The
+//! handle is a [`Future`], so that async runtimes await the proof without blocking one of
+//! their workers. A panic of the prover is caught and reported as
+//! [`ProvingError::Panicked`], so that it neither aborts the process nor leaves the job
+//! unfinished.
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use stwo_prover::core::backend::BackendForChannel;
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::channel::MerkleChannel;
+use stwo_prover::core::pcs::PcsConfig;
+
+use crate::Proof;
+use crate::adapter::ProverInput;
+use crate::errors::{ProvingError, ProvingPhase};
+use crate::prover::{ProverOptions, prove_cairo_m_with_options};
+
+/// Control of a running proof, shared between the prover and its owner.
+///
+/// The prover reports the phase it enters, and checks for cancellation before each phase:
+/// a cancelled proof stops with [`ProvingError::Cancelled`] at the next phase boundary.
+#[derive(Debug, Clone, Default)]
+pub struct JobControl(Arc<ControlState>);
+
+#[derive(Debug, Default)]
+struct ControlState {
+    cancelled: AtomicBool,
+    phase: Mutex<Option<ProvingPhase>>,
+}
+
+impl JobControl {
+    /// Requests the cancellation of the proof.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the cancellation of the proof was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the phase the proof is in, `None` if it has not started yet.
+    pub fn phase(&self) -> Option<ProvingPhase> {
+        *self.0.phase.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records the start of a phase, unless the proof was cancelled.
+    pub(crate) fn enter(&self, phase: ProvingPhase) -> Result<(), ProvingError> {
+        if self.is_cancelled() {
+            return Err(ProvingError::Cancelled { phase });
+        }
+        *self.0.phase.lock().unwrap_or_else(PoisonError::into_inner) = Some(phase);
+        Ok(())
+    }
+}
+
+/// Result of a job, along with the waker of the task awaiting it.
+struct JobState<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// Result slot of a job, filled by the worker.
+struct JobResult<T> {
+    state: Mutex<JobState<T>>,
+    done: Condvar,
+}
+
+impl<T> JobResult<T> {
+    fn lock(&self) -> MutexGuard<'_, JobState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn complete(&self, result: T) {
+        let mut state = self.lock();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.done.notify_all();
+    }
+}
+
+/// Handle to a proof running on the rayon thread pool.
+///
+/// Dropping the handle does not stop the proof: call [`ProvingJob::cancel`] to abort a
+/// superseded job.
+pub struct ProvingJob<MC: MerkleChannel> {
+    control: JobControl,
+    result: Arc<JobResult<Result<Proof<MC::H>, ProvingError>>>,
+}
+
+impl<MC: MerkleChannel + 'static> ProvingJob<MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+    Proof<MC::H>: Send + 'static,
+{
+    /// Starts proving `input` on the rayon thread pool.
+    ///
+    /// The control of `options` is replaced by the one of the job.
+    pub fn spawn(
+        mut input: ProverInput,
+        pcs_config: Option<PcsConfig>,
+        mut options: ProverOptions,
+    ) -> Self {
+        let control = JobControl::default();
+        options.control = Some(control.clone());
+        let result = Arc::new(JobResult {
+            state: Mutex::new(JobState {
+                result: None,
+                waker: None,
+            }),
+            done: Condvar::new(),
+        });
+
+        let worker_result = result.clone();
+        let worker_control = control.clone();
+        rayon::spawn(move || {
+            let proof = panic::catch_unwind(AssertUnwindSafe(|| {
+                prove_cairo_m_with_options::<MC>(&mut input, pcs_config, &options)
+            }))
+            .unwrap_or_else(|payload| {
+                Err(ProvingError::Panicked {
+                    phase: worker_control
+                        .phase()
+                        .unwrap_or(ProvingPhase::ExecutionTrace),
+                    message: panic_message(payload.as_ref()),
+                })
+            });
+            worker_result.complete(proof);
+        });
+
+        Self { control, result }
+    }
+}
+
+impl<MC: MerkleChannel> ProvingJob<MC> {
+    /// Requests the cancellation of the proof, see [`JobControl`].
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    /// Returns the phase the proof is in, `None` if it has not started yet.
+    pub fn phase(&self) -> Option<ProvingPhase> {
+        self.control.phase()
+    }
+
+    /// Returns whether the proof is over, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.result.lock().result.is_some()
+    }
+
+    /// Returns the result of the proof if it is over, without blocking, or gives the job
+    /// back if it is still running.
+    pub fn try_result(self) -> Result<Result<Proof<MC::H>, ProvingError>, Self> {
+        let result = self.result.lock().result.take();
+        result.ok_or(self)
+    }
+
+    /// Blocks until the proof is over and returns its result.
+    pub fn wait(self) -> Result<Proof<MC::H>, ProvingError> {
+        let mut state = self.result.lock();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self
+                .result
+                .done
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+/// Returns the message of a panic, as passed to `panic!`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+impl<MC: MerkleChannel> Future for ProvingJob<MC> {
+    type Output = Result<Proof<MC::H>, ProvingError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.result.lock();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
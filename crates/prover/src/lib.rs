@@ -20,32 +20,31 @@
 //! └─────────────┘    └──────────────┘    └─────────────┘
 
 pub mod adapter;
+pub mod calldata;
 pub mod components;
 pub mod debug_tools;
 pub mod errors;
+pub mod hasher;
+pub mod job;
 pub mod poseidon2;
 pub mod preprocessed;
 pub mod prover;
 pub mod prover_config;
 pub mod public_data;
 pub mod relations;
+pub mod trace_report;
 pub mod utils;
+pub mod verification_report;
 pub mod verifier;
 
-use std::collections::HashMap;
-
-use adapter::merkle::build_partial_merkle_tree;
-use cairo_m_common::PublicAddressRanges;
-use num_traits::Zero;
-use poseidon2::Poseidon2Hash;
 use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::M31;
-use stwo_prover::core::fields::qm31::QM31;
+use stwo_prover::core::pcs::PcsConfig;
 use stwo_prover::core::prover::StarkProof;
 use stwo_prover::core::vcs::ops::MerkleHasher;
 
 use crate::components::{Claim, InteractionClaim};
-use crate::public_data::PublicData;
+use crate::public_data::{PublicData, PublicDataCommitment};
 
 /// A complete cryptographic proof for a Cairo-M program execution.
 ///
@@ -66,33 +65,20 @@ pub struct Proof<H: MerkleHasher> {
     pub interaction_claim: InteractionClaim,
     /// Public data: VM initial and final state, public memory (program, input, output)
     pub public_data: PublicData,
+    /// Commitment to the public data, checked and mixed into the channel by the verifier
+    pub public_data_commitment: PublicDataCommitment,
     /// The underlying STARK proof containing polynomial commitments and evaluations
     pub stark_proof: StarkProof<H>,
     /// Proof-of-work nonce
     pub interaction_pow: u64,
+    /// PCS configuration the proof was produced with, checked against the verifier's
+    pub pcs_config: PcsConfig,
 }
 
 impl<H: MerkleHasher> Proof<H> {
-    pub fn program_id(&self) -> M31 {
-        // Reconstruct HashMap from program
-        let mut program_map = HashMap::<M31, (QM31, M31, M31)>::new();
-        for (addr, value, _clock) in self
-            .public_data
-            .public_memory
-            .program
-            .iter()
-            .map(|res| res.unwrap())
-        {
-            program_map.insert(addr, (value, M31::zero(), M31::zero()));
-        }
-
-        // Compute Poseidon2 hash of the program.
-        let (_, program_id) = build_partial_merkle_tree::<Poseidon2Hash>(
-            &program_map,
-            adapter::merkle::TreeType::Initial,
-            &PublicAddressRanges::default(),
-        );
-
-        program_id.unwrap()
+    /// Returns the Poseidon2 hash of the program of the proof, or `None` for malformed public
+    /// data.
+    pub fn program_id(&self) -> Option<M31> {
+        self.public_data.program_id()
     }
 }
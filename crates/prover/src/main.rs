@@ -2,13 +2,23 @@ use std::fs;
 use std::path::PathBuf;
 
 use anyhow::Context;
+use cairo_m_common::abi_codec::m31_from_i64;
 use cairo_m_common::{InputValue, Program, parse_cli_arg};
-use cairo_m_prover::adapter::import_from_runner_output;
-use cairo_m_prover::prover::prove_cairo_m;
-use cairo_m_prover::verifier::verify_cairo_m;
-use cairo_m_runner::run_cairo_program;
+use cairo_m_prover::adapter::cache::TraceCache;
+use cairo_m_prover::adapter::validation::{
+    validate_initial_memory, validate_prover_input, validate_public_ranges,
+};
+use cairo_m_prover::adapter::{ProverInput, import_from_runner_output_ref};
+use cairo_m_prover::hasher::{MerkleHasherKind, prove_cairo_m_with_hasher};
+use cairo_m_prover::preprocessed::cache::PreprocessedCache;
+use cairo_m_prover::prover::ProverOptions;
+use cairo_m_runner::{RunnerOptions, encode_arguments, run_cairo_program};
 use clap::{Parser, ValueHint};
-use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+use stwo_prover::core::fields::m31::M31;
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -29,11 +39,11 @@ struct Args {
     /// Arguments to pass to the entrypoint function
     ///
     /// Supported types:
-    ///   • Numbers: 42, -5 (use quotes for negative: "-5")
+    ///   • Numbers: 42, -5, 0x2a, 1e6 (use quotes for negative: "-5")
     ///   • Booleans: true, false
     ///   • Tuples: (1,2,3) or [1,2,3] or [1,[2,3]] for nested
     ///   • Structs: {1,2,3} or {1,{2,3}} for nested (fields are positional)
-    ///   • Mixed: {1,[true,{2,3}]} combining structs and tuples
+    ///   • Mixed: {1,[true,{2,3}]} or [{1,2},{3,4}] combining structs and tuples
     ///
     /// Note: Fixed-size arrays are not currently supported as input arguments.
     ///       See Linear issue CORE-1118 for array support tracking.
@@ -44,6 +54,10 @@ struct Args {
     #[arg(short, long, value_parser = parse_cli_arg, num_args = 0.., allow_hyphen_values = true, verbatim_doc_comment)]
     arguments: Vec<InputValue>,
 
+    /// Values of the environment block, read by the program with `env(index)` and made public
+    #[arg(long, num_args = 0.., allow_hyphen_values = true)]
+    env: Vec<i64>,
+
     /// Output file to write the proof to
     #[arg(short, long)]
     output: Option<PathBuf>,
@@ -52,48 +66,213 @@ struct Args {
     #[arg(long, default_value = "false")]
     verify: bool,
 
-    /// Enable verbose output
+    /// Enable verbose output, and log the proving phases with their durations
+    ///
+    /// Logs can also be filtered with the `RUST_LOG` environment variable, e.g.
+    /// `RUST_LOG=cairo_m_prover=info,stwo_prover=warn`.
     #[arg(short, long)]
     verbose: bool,
+
+    /// Directory caching the preprocessed trace across proofs
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    preprocessed_cache: Option<PathBuf>,
+
+    /// Directory caching the prover input of executions, keyed by program, entrypoint,
+    /// arguments and environment, so that proving an execution again skips its re-execution
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    trace_cache: Option<PathBuf>,
+
+    /// Output file to write the per-component trace size report to (JSON format)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    report: Option<PathBuf>,
+
+    /// Produce byte-identical proofs for identical inputs, e.g. for cached CI artifacts
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Locate the failing component and row when the constraints are not satisfied, at the
+    /// cost of a copy of the prover input
+    #[arg(long)]
+    localize_constraint_failures: bool,
+
+    /// Hash function of the Merkle commitments: blake2s or poseidon252
+    #[arg(long, default_value_t = MerkleHasherKind::Blake2s)]
+    hasher: MerkleHasherKind,
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    let default_level = if args.verbose {
+        LevelFilter::INFO
+    } else {
+        LevelFilter::WARN
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(default_level.into())
+                .from_env_lossy(),
+        )
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
     let file_content = fs::read_to_string(&args.compiled_file)
         .with_context(|| format!("Error reading file '{}'", args.compiled_file.display()))?;
 
     let compiled_program: Program =
         sonic_rs::from_str(&file_content).context("Failed to parse compiled program")?;
+    compiled_program
+        .metadata
+        .check_isa_version()
+        .context("Unsupported program")?;
 
-    let runner_output = run_cairo_program(
-        &compiled_program,
-        &args.entrypoint,
-        &args.arguments,
-        Default::default(),
-    )
-    .context("Execution failed")?;
+    let environment: Vec<M31> = args.env.iter().copied().map(m31_from_i64).collect();
+    let trace_cache = args.trace_cache.as_ref().map(|dir| {
+        let key = TraceCache::key(
+            &compiled_program,
+            &args.entrypoint,
+            &args.arguments,
+            &environment,
+        );
+        (TraceCache::new(dir), key)
+    });
+    let cached_input = trace_cache
+        .as_ref()
+        .and_then(|(cache, key)| match cache.load(key) {
+            Ok(input) => input,
+            Err(err) => {
+                warn!(
+                    "ignoring invalid trace cache file {}: {err}",
+                    cache.path_for(key).display()
+                );
+                None
+            }
+        });
 
-    let mut prover_input = import_from_runner_output(
-        runner_output.vm.segments.into_iter().next().unwrap(),
-        runner_output.public_address_ranges,
-    )
-    .context("Failed to import from runner output")?;
-    let proof: cairo_m_prover::Proof<stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher> =
-        prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None)
+    let from_cache = cached_input.is_some();
+    let mut prover_input = match cached_input {
+        Some(input) => {
+            info!("prover input loaded from the trace cache");
+            input
+        }
+        None => execute(&compiled_program, &args, environment.clone())?,
+    };
+    // Cached inputs are validated as well: the cache directory may be shared or tampered with
+    validate(&prover_input, &compiled_program, &args, environment)?;
+    if let Some((cache, key)) = trace_cache.as_ref().filter(|_| !from_cache) {
+        if let Err(err) = cache.store(key, &prover_input) {
+            warn!(
+                "failed to write trace cache file {}: {err}",
+                cache.path_for(key).display()
+            );
+        }
+    }
+
+    let options = ProverOptions {
+        preprocessed_cache: args.preprocessed_cache.map(PreprocessedCache::new),
+        deterministic: args.deterministic,
+        localize_constraint_failures: args.localize_constraint_failures,
+        ..Default::default()
+    };
+    let (proof, trace_report) =
+        prove_cairo_m_with_hasher(args.hasher, &mut prover_input, None, &options)
             .context("Failed to prove")?;
 
+    if args.verbose {
+        println!("{trace_report}");
+    }
+
+    if let Some(report) = args.report {
+        let report_output =
+            sonic_rs::to_string(&trace_report).context("Failed to serialize report to JSON")?;
+        fs::write(&report, report_output)?;
+        println!("Trace report written to {}", report.display());
+    }
+
     if let Some(output) = args.output {
-        let proof_output =
-            sonic_rs::to_string(&proof).context("Failed to serialize proof to JSON")?;
+        let proof_output = proof
+            .to_json()
+            .context("Failed to serialize proof to JSON")?;
         fs::write(&output, proof_output)?;
         println!("Proof written to {}", output.display());
     }
 
     if args.verify {
-        verify_cairo_m::<Blake2sMerkleChannel>(proof, None).context("Failed to verify proof")?;
+        let verification_report = proof.verify(None).context("Failed to verify proof")?;
+        if args.verbose {
+            println!("{verification_report}");
+        }
         println!("Proof verified successfully!");
     }
 
     Ok(())
 }
+
+/// Runs the entrypoint and adapts its execution trace into a prover input.
+fn execute(
+    compiled_program: &Program,
+    args: &Args,
+    environment: Vec<M31>,
+) -> anyhow::Result<ProverInput> {
+    let runner_output = run_cairo_program(
+        compiled_program,
+        &args.entrypoint,
+        &args.arguments,
+        RunnerOptions {
+            environment,
+            ..Default::default()
+        },
+    )
+    .context("Execution failed")?;
+
+    let partial_input = import_from_runner_output_ref(&runner_output)
+        .context("Failed to import from runner output")?;
+    // Release the execution trace before building the Merkle trees.
+    drop(runner_output);
+    Ok(partial_input.build_merkle_trees())
+}
+
+/// Checks the public ranges, the public initial memory and the execution trace of a prover
+/// input before proving it.
+///
+/// The public initial memory must hold the program and the arguments and environment of the
+/// command line, so that a cached input of another execution is rejected.
+fn validate(
+    prover_input: &ProverInput,
+    compiled_program: &Program,
+    args: &Args,
+    environment: Vec<M31>,
+) -> anyhow::Result<()> {
+    let options = RunnerOptions {
+        environment,
+        ..Default::default()
+    };
+    let arguments = encode_arguments(
+        compiled_program,
+        &args.entrypoint,
+        &args.arguments,
+        &options,
+    )
+    .context("Failed to encode the arguments")?;
+
+    let errors = validate_public_ranges(
+        &prover_input.public_address_ranges,
+        compiled_program,
+        &args.entrypoint,
+    )
+    .into_iter()
+    .chain(validate_initial_memory(
+        prover_input,
+        compiled_program,
+        &options.environment,
+        &arguments,
+    ))
+    .chain(validate_prover_input(prover_input))
+    .map(|err| format!("  - {err}"))
+    .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        anyhow::bail!("Invalid execution trace:\n{}", errors.join("\n"));
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use num_traits::Zero;
+use num_traits::{One, Zero};
 use stwo_prover::core::fields::m31::M31;
 use zkhash::ark_ff::PrimeField;
 use zkhash::fields::m31::FpM31;
@@ -15,6 +15,30 @@ pub const PARTIAL_ROUNDS: usize = 14;
 // Include the auto-generated constants from the build script
 include!(concat!(env!("OUT_DIR"), "/poseidon2_constants.rs"));
 
+/// Rate of the Poseidon2 sponge, in M31 elements
+pub const RATE: usize = 8;
+
+/// Hashes a sequence of M31 elements with a Poseidon2 sponge.
+///
+/// The elements are padded with a one and zeros to a multiple of [`RATE`], then absorbed
+/// [`RATE`] at a time into the first half of the state, the other half being the capacity.
+/// The digest is the first [`RATE`] elements of the final state.
+pub fn hash_m31s(values: &[M31]) -> [M31; RATE] {
+    let poseidon2 = Poseidon2::new(&POSEIDON2_M31_16_PARAMS);
+    let mut padded = values.to_vec();
+    padded.push(M31::one());
+    padded.resize(padded.len().next_multiple_of(RATE), M31::zero());
+
+    let mut state: Vec<FpM31> = vec![FpM31::zero(); T];
+    for chunk in padded.chunks_exact(RATE) {
+        for (element, value) in state.iter_mut().zip(chunk) {
+            *element += FpM31::from(value.0);
+        }
+        state = poseidon2.permutation(&state);
+    }
+    std::array::from_fn(|i| M31::from(state[i].into_bigint().0[0] as u32))
+}
+
 #[derive(Clone)]
 pub struct Poseidon2Hash;
 
@@ -0,0 +1,558 @@
+//! On-disk cache of preprocessed trace evaluations and commitments.
+//!
+//! The preprocessed columns (range checks, bitwise tables) only depend on the
+//! [`PreProcessedTrace`] configuration, yet they are regenerated for every proof.
+//! [`PreprocessedCache`] persists their evaluations in a directory, in one file per
+//! configuration, and reloads them on the next proofs.
+//!
+//! The committed preprocessed tree, i.e. the interpolated polynomials, their evaluations on
+//! the blown-up domain and the Merkle tree over them, additionally depends on the blowup
+//! factor and on the hash function. [`PreprocessedCache::commit`] persists it in one file per
+//! configuration, blowup factor and hash function, so that proofs reusing them skip the
+//! interpolation, the extension and the hashing of the preprocessed columns.
+//!
+//! ## File Formats
+//!
+//! Evaluations, all integers being little-endian `u32`:
+//! 1. Magic bytes `CMPP` and the format version
+//! 2. Number of columns
+//! 3. For each column: id length, id bytes, log size, and the `2^log_size` values
+//!    in bit-reversed order
+//!
+//! Committed trees:
+//! 1. Magic bytes `CMPT` and the format version, as a little-endian `u32`
+//! 2. The polynomials, evaluations and Merkle layers, encoded with the standard
+//!    configuration of `bincode`
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use blake2::{Blake2s256, Digest};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::simd::column::BaseColumn;
+use stwo_prover::core::backend::{BackendForChannel, Col, Column};
+use stwo_prover::core::channel::MerkleChannel;
+use stwo_prover::core::fields::m31::{BaseField, M31};
+use stwo_prover::core::pcs::{CommitmentSchemeProver, CommitmentTreeProver};
+use stwo_prover::core::poly::BitReversedOrder;
+use stwo_prover::core::poly::circle::{CanonicCoset, CircleEvaluation, CirclePoly};
+use stwo_prover::core::vcs::ops::MerkleHasher;
+use stwo_prover::core::vcs::prover::MerkleProver;
+use tracing::{info, warn};
+
+use crate::preprocessed::PreProcessedTrace;
+
+const MAGIC: &[u8; 4] = b"CMPP";
+const TREE_MAGIC: &[u8; 4] = b"CMPT";
+const FORMAT_VERSION: u32 = 1;
+
+type Evaluation = CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>;
+
+/// Cache of preprocessed trace evaluations, stored in a directory.
+#[derive(Debug, Clone)]
+pub struct PreprocessedCache {
+    dir: PathBuf,
+}
+
+impl PreprocessedCache {
+    /// Creates a cache storing its files in `dir`. The directory is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Returns the directory holding the cache files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cache key of a preprocessed trace configuration.
+    ///
+    /// The key is the Blake2s hash of the format version and of the id and log size of each
+    /// column, the ids being prefixed by their length.
+    pub fn key(trace: &PreProcessedTrace) -> String {
+        let mut hasher = Blake2s256::new();
+        hasher.update(FORMAT_VERSION.to_le_bytes());
+        for (id, log_size) in trace.ids().iter().zip(trace.log_sizes()) {
+            hasher.update((id.id.len() as u64).to_le_bytes());
+            hasher.update(id.id.as_bytes());
+            hasher.update(log_size.to_le_bytes());
+        }
+        hex(&hasher.finalize())
+    }
+
+    /// Returns the cache key of the committed tree of a preprocessed trace configuration.
+    ///
+    /// The key is the Blake2s hash of the configuration key, of the log blowup factor and of
+    /// the hash of a fixed node with the hash function of `MC`, which identifies it.
+    pub fn tree_key<MC: MerkleChannel>(
+        trace: &PreProcessedTrace,
+        log_blowup_factor: u32,
+    ) -> String {
+        let probe = MC::H::hash_node(None, &[M31(0)]);
+        let mut hasher = Blake2s256::new();
+        hasher.update(Self::key(trace).as_bytes());
+        hasher.update(log_blowup_factor.to_le_bytes());
+        hasher.update(
+            bincode::serde::encode_to_vec(probe, bincode::config::standard()).unwrap_or_default(),
+        );
+        hex(&hasher.finalize())
+    }
+
+    /// Returns the path of the cache file for a preprocessed trace configuration.
+    pub fn path_for(&self, trace: &PreProcessedTrace) -> PathBuf {
+        self.dir
+            .join(format!("preprocessed_{}.bin", Self::key(trace)))
+    }
+
+    /// Returns the path of the cache file for the committed tree of a preprocessed trace
+    /// configuration.
+    pub fn tree_path_for<MC: MerkleChannel>(
+        &self,
+        trace: &PreProcessedTrace,
+        log_blowup_factor: u32,
+    ) -> PathBuf {
+        self.dir.join(format!(
+            "preprocessed_tree_{}.bin",
+            Self::tree_key::<MC>(trace, log_blowup_factor)
+        ))
+    }
+
+    /// Commits the preprocessed trace as the next tree of `commitment_scheme`, reusing the
+    /// committed tree of a previous proof when available and storing it otherwise.
+    ///
+    /// Cache failures are not fatal: a missing, stale or unreadable file falls back to
+    /// committing the evaluations of [`Self::load_or_generate`], and a failed write only
+    /// emits a warning.
+    pub fn commit<MC: MerkleChannel>(
+        &self,
+        trace: &PreProcessedTrace,
+        commitment_scheme: &mut CommitmentSchemeProver<'_, SimdBackend, MC>,
+        channel: &mut MC::C,
+    ) where
+        SimdBackend: BackendForChannel<MC>,
+    {
+        let log_blowup_factor = commitment_scheme.config.fri_config.log_blowup_factor;
+        let path = self.tree_path_for::<MC>(trace, log_blowup_factor);
+        match self.load_tree::<MC>(trace, log_blowup_factor) {
+            Ok(Some(tree)) => {
+                info!("preprocessed tree loaded from {}", path.display());
+                MC::mix_root(channel, tree.commitment.root());
+                commitment_scheme.trees.push(tree);
+                return;
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                "ignoring invalid preprocessed tree cache file {}: {err}",
+                path.display()
+            ),
+        }
+
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(self.load_or_generate(trace));
+        tree_builder.commit(channel);
+
+        let tree = commitment_scheme
+            .trees
+            .last()
+            .expect("the preprocessed tree was just committed");
+        if let Err(err) = self.store_tree::<MC>(trace, log_blowup_factor, tree) {
+            warn!(
+                "failed to write preprocessed tree cache file {}: {err}",
+                path.display()
+            );
+        }
+    }
+
+    /// Returns the evaluations of the preprocessed trace, loading them from the cache
+    /// when available and storing them otherwise.
+    ///
+    /// Cache failures are not fatal: a missing, stale or unreadable file falls back to
+    /// generating the columns, and a failed write only emits a warning.
+    pub fn load_or_generate(&self, trace: &PreProcessedTrace) -> Vec<Evaluation> {
+        let path = self.path_for(trace);
+        match self.load(trace) {
+            Ok(Some(evals)) => {
+                info!("preprocessed trace loaded from {}", path.display());
+                return evals;
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                "ignoring invalid preprocessed cache file {}: {err}",
+                path.display()
+            ),
+        }
+
+        let evals = trace.gen_trace();
+        if let Err(err) = self.store(trace, &evals) {
+            warn!(
+                "failed to write preprocessed cache file {}: {err}",
+                path.display()
+            );
+        }
+        evals
+    }
+
+    /// Loads the evaluations of `trace` from the cache.
+    ///
+    /// ## Returns
+    ///
+    /// `Ok(None)` if there is no cache file for this configuration.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the file can't be read or doesn't match the configuration.
+    pub fn load(&self, trace: &PreProcessedTrace) -> io::Result<Option<Vec<Evaluation>>> {
+        let bytes = match fs::read(self.path_for(trace)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let mut reader = bytes.as_slice();
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u32(&mut reader)? != FORMAT_VERSION {
+            return Err(invalid_data("unknown file format"));
+        }
+
+        let ids = trace.ids();
+        let log_sizes = trace.log_sizes();
+        if read_u32(&mut reader)? as usize != ids.len() {
+            return Err(invalid_data("column count mismatch"));
+        }
+
+        let mut evals = Vec::with_capacity(ids.len());
+        for (id, log_size) in ids.iter().zip(log_sizes) {
+            let id_len = read_u32(&mut reader)? as usize;
+            let mut id_bytes = vec![0u8; id_len];
+            reader.read_exact(&mut id_bytes)?;
+            if id_bytes != id.id.as_bytes() || read_u32(&mut reader)? != log_size {
+                return Err(invalid_data("column mismatch"));
+            }
+
+            let values = (0..1usize << log_size)
+                .map(|_| read_u32(&mut reader).map(M31))
+                .collect::<io::Result<Vec<_>>>()?;
+            evals.push(CircleEvaluation::new(
+                CanonicCoset::new(log_size).circle_domain(),
+                BaseColumn::from_iter(values),
+            ));
+        }
+
+        if !reader.is_empty() {
+            return Err(invalid_data("trailing bytes"));
+        }
+        Ok(Some(evals))
+    }
+
+    /// Stores the evaluations of `trace` in the cache.
+    ///
+    /// The file is written to a temporary path first, then renamed, so that
+    /// concurrent provers never observe a partially written file.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the directory or the file can't be written.
+    pub fn store(&self, trace: &PreProcessedTrace, evals: &[Evaluation]) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(evals.len() as u32).to_le_bytes());
+        for (id, eval) in trace.ids().iter().zip(evals) {
+            bytes.extend_from_slice(&(id.id.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(id.id.as_bytes());
+            bytes.extend_from_slice(&eval.domain.log_size().to_le_bytes());
+            for value in eval.values.to_cpu() {
+                bytes.extend_from_slice(&value.0.to_le_bytes());
+            }
+        }
+
+        write_atomically(&self.dir, &self.path_for(trace), bytes)
+    }
+}
+
+/// Committed preprocessed tree, as stored in the cache.
+#[derive(Serialize, Deserialize)]
+struct CachedTree<H> {
+    /// Log size and coefficients of each polynomial
+    polynomials: Vec<(u32, Vec<u32>)>,
+    /// Log size and values of each evaluation on the blown-up domain, in bit-reversed order
+    evaluations: Vec<(u32, Vec<u32>)>,
+    /// Layers of the Merkle tree
+    layers: Vec<Vec<H>>,
+}
+
+impl PreprocessedCache {
+    /// Loads the committed tree of `trace` from the cache.
+    ///
+    /// ## Returns
+    ///
+    /// `Ok(None)` if there is no cache file for this configuration, blowup factor and hash
+    /// function.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the file can't be read or doesn't match the configuration.
+    fn load_tree<MC: MerkleChannel>(
+        &self,
+        trace: &PreProcessedTrace,
+        log_blowup_factor: u32,
+    ) -> io::Result<Option<CommitmentTreeProver<SimdBackend, MC>>>
+    where
+        SimdBackend: BackendForChannel<MC>,
+    {
+        let bytes = match fs::read(self.tree_path_for::<MC>(trace, log_blowup_factor)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let header = [TREE_MAGIC.as_slice(), &FORMAT_VERSION.to_le_bytes()].concat();
+        let Some(encoded) = bytes.strip_prefix(header.as_slice()) else {
+            return Err(invalid_data("unknown file format"));
+        };
+        let (cached, len): (CachedTree<<MC::H as MerkleHasher>::Hash>, _) =
+            bincode::serde::decode_from_slice(encoded, bincode::config::standard())
+                .map_err(|err| invalid_data(&err.to_string()))?;
+        if len != encoded.len() {
+            return Err(invalid_data("trailing bytes"));
+        }
+
+        let log_sizes = trace.log_sizes();
+        let matches = |columns: &[(u32, Vec<u32>)], log_extension: u32| {
+            columns.len() == log_sizes.len()
+                && columns
+                    .iter()
+                    .zip(&log_sizes)
+                    .all(|((log_size, values), expected)| {
+                        *log_size == expected + log_extension && values.len() == 1 << *log_size
+                    })
+        };
+        if !matches(&cached.polynomials, 0) || !matches(&cached.evaluations, log_blowup_factor) {
+            return Err(invalid_data("column mismatch"));
+        }
+
+        let column = |values: Vec<u32>| BaseColumn::from_iter(values.into_iter().map(M31));
+        Ok(Some(CommitmentTreeProver {
+            polynomials: cached
+                .polynomials
+                .into_iter()
+                .map(|(_, coeffs)| CirclePoly::new(column(coeffs)))
+                .collect(),
+            evaluations: cached
+                .evaluations
+                .into_iter()
+                .map(|(log_size, values)| {
+                    CircleEvaluation::new(
+                        CanonicCoset::new(log_size).circle_domain(),
+                        column(values),
+                    )
+                })
+                .collect(),
+            commitment: MerkleProver {
+                layers: cached
+                    .layers
+                    .into_iter()
+                    .map(|layer| layer.into_iter().collect::<Col<SimdBackend, _>>())
+                    .collect(),
+            },
+        }))
+    }
+
+    /// Stores the committed tree of `trace` in the cache.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an I/O error if the directory or the file can't be written.
+    fn store_tree<MC: MerkleChannel>(
+        &self,
+        trace: &PreProcessedTrace,
+        log_blowup_factor: u32,
+        tree: &CommitmentTreeProver<SimdBackend, MC>,
+    ) -> io::Result<()>
+    where
+        SimdBackend: BackendForChannel<MC>,
+    {
+        let values = |column: &BaseColumn| -> Vec<u32> {
+            column.to_cpu().into_iter().map(|v| v.0).collect()
+        };
+        let cached = CachedTree {
+            polynomials: tree
+                .polynomials
+                .iter()
+                .map(|poly| (poly.log_size(), values(&poly.coeffs)))
+                .collect(),
+            evaluations: tree
+                .evaluations
+                .iter()
+                .map(|eval| (eval.domain.log_size(), values(&eval.values)))
+                .collect(),
+            layers: tree
+                .commitment
+                .layers
+                .iter()
+                .map(|layer| layer.to_cpu())
+                .collect(),
+        };
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(TREE_MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend(
+            bincode::serde::encode_to_vec(&cached, bincode::config::standard())
+                .map_err(|err| invalid_data(&err.to_string()))?,
+        );
+        write_atomically(
+            &self.dir,
+            &self.tree_path_for::<MC>(trace, log_blowup_factor),
+            bytes,
+        )
+    }
+}
+
+/// Writes `bytes` to a temporary path first, then renames it to `path`, so that
+/// concurrent provers never observe a partially written file.
+fn write_atomically(dir: &Path, path: &Path, bytes: Vec<u8>) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(tmp_path, path)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn read_u32(reader: &mut &[u8]) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo_prover::core::pcs::PcsConfig;
+    use stwo_prover::core::poly::circle::PolyOps;
+    use stwo_prover::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+    use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleChannel;
+
+    use super::*;
+    use crate::preprocessed::PreProcessedTraceBuilder;
+
+    fn small_trace() -> PreProcessedTrace {
+        PreProcessedTraceBuilder::new()
+            .with_range_check(8)
+            .with_bitwise(4)
+            .build()
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PreprocessedCache::new(dir.path());
+        let trace = small_trace();
+
+        assert!(cache.load(&trace).unwrap().is_none());
+
+        let generated = cache.load_or_generate(&trace);
+        assert!(cache.path_for(&trace).exists());
+
+        let loaded = cache.load(&trace).unwrap().unwrap();
+        assert_eq!(loaded.len(), generated.len());
+        for (a, b) in loaded.iter().zip(&generated) {
+            assert_eq!(a.domain.log_size(), b.domain.log_size());
+            assert_eq!(a.values.to_cpu(), b.values.to_cpu());
+        }
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_columns() {
+        let a = PreProcessedTraceBuilder::new().with_range_check(8).build();
+        let b = PreProcessedTraceBuilder::new().with_range_check(16).build();
+        assert_ne!(PreprocessedCache::key(&a), PreprocessedCache::key(&b));
+        assert_eq!(
+            PreprocessedCache::key(&a),
+            PreprocessedCache::key(&PreProcessedTraceBuilder::new().with_range_check(8).build())
+        );
+    }
+
+    #[test]
+    fn test_corrupted_cache_is_regenerated() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PreprocessedCache::new(dir.path());
+        let trace = small_trace();
+
+        fs::write(cache.path_for(&trace), b"garbage").unwrap();
+        assert!(cache.load(&trace).is_err());
+
+        let evals = cache.load_or_generate(&trace);
+        assert_eq!(evals.len(), trace.ids().len());
+        assert!(cache.load(&trace).unwrap().is_some());
+    }
+
+    /// Commits `trace` through the cache and returns the root and the channel digest.
+    fn commit_through(
+        cache: &PreprocessedCache,
+        trace: &PreProcessedTrace,
+    ) -> (<Blake2sMerkleHasher as MerkleHasher>::Hash, String) {
+        let config = PcsConfig::default();
+        let twiddles = SimdBackend::precompute_twiddles(
+            CanonicCoset::new(10 + config.fri_config.log_blowup_factor + 2)
+                .circle_domain()
+                .half_coset,
+        );
+        let channel = &mut <Blake2sMerkleChannel as MerkleChannel>::C::default();
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+        cache.commit(trace, &mut commitment_scheme, channel);
+        (
+            commitment_scheme.roots()[0],
+            format!("{:?}", channel.digest()),
+        )
+    }
+
+    #[test]
+    fn test_tree_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PreprocessedCache::new(dir.path());
+        let trace = small_trace();
+        let log_blowup_factor = PcsConfig::default().fri_config.log_blowup_factor;
+        let tree_path = cache.tree_path_for::<Blake2sMerkleChannel>(&trace, log_blowup_factor);
+
+        let committed = commit_through(&cache, &trace);
+        assert!(tree_path.exists());
+        assert!(cache.path_for(&trace).exists());
+
+        // The second commitment is loaded from the cache
+        fs::remove_file(cache.path_for(&trace)).unwrap();
+        assert_eq!(commit_through(&cache, &trace), committed);
+        assert!(!cache.path_for(&trace).exists());
+
+        // A corrupted tree is committed again
+        fs::write(&tree_path, b"garbage").unwrap();
+        assert_eq!(commit_through(&cache, &trace), committed);
+    }
+
+    #[test]
+    fn test_tree_cache_key_depends_on_commitment() {
+        let trace = small_trace();
+        let key = PreprocessedCache::tree_key::<Blake2sMerkleChannel>(&trace, 1);
+        assert_eq!(
+            key,
+            PreprocessedCache::tree_key::<Blake2sMerkleChannel>(&trace, 1)
+        );
+        assert_ne!(
+            key,
+            PreprocessedCache::tree_key::<Blake2sMerkleChannel>(&trace, 2)
+        );
+        assert_ne!(
+            key,
+            PreprocessedCache::tree_key::<Poseidon252MerkleChannel>(&trace, 1)
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! A collection of preprocessed columns, whose values are publicly acknowledged, and independent of
-//! the proof.
+//! the execution.
 //!
 //! They are similar to regular components but are entirely known by the verifier.
+//! Only the sizes of the range check columns depend on the proof, through its claim.
 use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
 use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::fields::m31::BaseField;
@@ -12,6 +13,7 @@ use crate::preprocessed::bitwise::Bitwise;
 use crate::preprocessed::range_check::RangeCheck;
 
 pub mod bitwise;
+pub mod cache;
 pub mod range_check;
 
 pub trait PreProcessedColumn {
@@ -53,7 +55,10 @@ impl PreProcessedTraceBuilder {
 
     pub fn with_range_check(mut self, range: u32) -> Self {
         let range_check = RangeCheck::new(range);
-        self.columns.push(Box::new(range_check));
+        // Range check tables shrunk to the same size share their column
+        if !self.columns.iter().any(|c| c.id() == range_check.id()) {
+            self.columns.push(Box::new(range_check));
+        }
         self
     }
 
@@ -72,6 +77,10 @@ impl PreProcessedTraceBuilder {
     }
 }
 
+/// Preprocessed trace with the range check tables at their full size.
+///
+/// The preprocessed trace of a proof depends on the sizes of its range check tables,
+/// see [`crate::components::Claim::preprocessed_trace`].
 impl Default for PreProcessedTraceBuilder {
     fn default() -> Self {
         Self::new()
@@ -26,6 +26,12 @@ pub trait RangeCheckProvider {
         rayon::iter::empty()
     }
 }
+/// Smallest log size of a range check table.
+///
+/// Range check tables are shrunk to fit the largest checked value, but never below this size.
+/// A table of this size reuses the preprocessed column of the range check of the same range.
+pub const RANGE_CHECK_MIN_LOG_SIZE: u32 = 8;
+
 // Include the macro implementation
 #[macro_use]
 pub mod range_check_macro;
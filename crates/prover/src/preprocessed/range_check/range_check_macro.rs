@@ -27,7 +27,7 @@ macro_rules! define_range_check {
             use stwo_prover::core::poly::circle::{CanonicCoset, CircleEvaluation};
             use stwo_prover::core::poly::BitReversedOrder;
 
-            use $crate::preprocessed::range_check::RangeCheck;
+            use $crate::preprocessed::range_check::{RangeCheck, RANGE_CHECK_MIN_LOG_SIZE};
             use $crate::preprocessed::PreProcessedColumn;
 
             pub const [<LOG_SIZE_RC_ $bit_size>]: u32 = $bit_size;
@@ -52,6 +52,13 @@ macro_rules! define_range_check {
                     channel.mix_u64(self.log_size as u64);
                 }
 
+                /// Returns whether the table size is within its bounds.
+                ///
+                /// A table larger than 2**{range} would accept out of range values.
+                pub fn is_valid(&self) -> bool {
+                    (RANGE_CHECK_MIN_LOG_SIZE..=[<LOG_SIZE_RC_ $bit_size>]).contains(&self.log_size)
+                }
+
                 /// Writes the preprocessed range_check_{range} trace
                 ///
                 /// lookup_data contains all range_checks made in other components during main trace generation
@@ -59,6 +66,9 @@ macro_rules! define_range_check {
                 /// write_trace creates a column with all values from 0 to 2**{range} - 1 included and counts how many times other components
                 /// have range_checked each value: every occurrence of a range_checked value increases by 1 its multiplicity.
                 /// These multiplicities are stored in a new column.
+                ///
+                /// The table is then shrunk to the smallest power of two holding the largest range_checked value,
+                /// without going below RANGE_CHECK_MIN_LOG_SIZE, so that small programs don't pay for the full range.
                 pub fn write_trace<'a, MC: MerkleChannel>(
                     lookup_data: impl ParallelIterator<Item = &'a PackedM31>,
                 ) -> (
@@ -78,11 +88,18 @@ macro_rules! define_range_check {
                         }
                     });
 
-                    let mults: Vec<M31> = mults_atomic
+                    let mut mults: Vec<M31> = mults_atomic
                         .into_par_iter()
                         .map(|atomic| M31(atomic.into_inner()))
                         .collect();
 
+                    let n_used = mults.iter().rposition(|mult| mult.0 != 0).map_or(0, |i| i + 1);
+                    let log_size = n_used
+                        .next_power_of_two()
+                        .ilog2()
+                        .clamp(RANGE_CHECK_MIN_LOG_SIZE, [<LOG_SIZE_RC_ $bit_size>]);
+                    mults.truncate(1 << log_size);
+
                     let mults_packed: Vec<[PackedM31; 2]> = mults
                         .par_chunks(N_LANES)
                         .enumerate()
@@ -96,11 +113,9 @@ macro_rules! define_range_check {
                         })
                         .collect();
 
-                    let domain = CanonicCoset::new([<LOG_SIZE_RC_ $bit_size>]).circle_domain();
+                    let domain = CanonicCoset::new(log_size).circle_domain();
                     (
-                        Self {
-                            log_size: [<LOG_SIZE_RC_ $bit_size>],
-                        },
+                        Self { log_size },
                         [CircleEvaluation::<SimdBackend, M31, BitReversedOrder>::new(
                             domain,
                             BaseColumn::from_iter(mults),
@@ -169,7 +184,7 @@ macro_rules! define_range_check {
                 }
 
                 fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-                    let value = eval.get_preprocessed_column(RangeCheck::new([<LOG_SIZE_RC_ $bit_size>]).id());
+                    let value = eval.get_preprocessed_column(RangeCheck::new(self.claim.log_size).id());
                     let multiplicity = eval.next_trace_mask();
 
                     eval.add_to_relation(RelationEntry::new(
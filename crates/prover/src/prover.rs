@@ -1,108 +1,220 @@
 use std::time::Instant;
 
 use stwo_constraint_framework::TraceLocationAllocator;
-use stwo_prover::core::backend::BackendForChannel;
 use stwo_prover::core::backend::simd::SimdBackend;
+use stwo_prover::core::backend::{BackendForChannel, CpuBackend};
 use stwo_prover::core::channel::{Channel, MerkleChannel};
 use stwo_prover::core::pcs::{CommitmentSchemeProver, PcsConfig};
 use stwo_prover::core::poly::circle::{CanonicCoset, PolyOps};
 use stwo_prover::core::proof_of_work::GrindOps;
-use stwo_prover::core::prover::prove;
-use tracing::{Level, info, span};
+use stwo_prover::core::prover::{ProvingError as StwoProvingError, prove};
+use tracing::{Level, field, info, info_span, span};
 
 use crate::adapter::ProverInput;
 use crate::components::{Claim, Components, InteractionClaim, Relations};
-use crate::errors::ProvingError;
-use crate::preprocessed::PreProcessedTraceBuilder;
+use crate::debug_tools::assert_constraints::find_constraint_violation;
+use crate::errors::{ComponentLogupSum, ProvingError, ProvingPhase};
+use crate::job::JobControl;
+use crate::preprocessed::cache::PreprocessedCache;
 use crate::prover_config::REGULAR_96_BITS;
 use crate::public_data::PublicData;
+use crate::trace_report::TraceReport;
 use crate::{Proof, relations};
 
-pub(crate) const PREPROCESSED_TRACE_LOG_SIZE: u32 = 20;
+/// Optional settings of the prover.
+#[derive(Debug, Clone, Default)]
+pub struct ProverOptions {
+    /// Cache of the preprocessed trace evaluations and committed tree, shared across proofs.
+    pub preprocessed_cache: Option<PreprocessedCache>,
+    /// Searches the interaction proof of work sequentially, so that identical inputs yield
+    /// byte-identical proofs.
+    pub deterministic: bool,
+    /// Keeps a copy of the prover input to locate the failing component and row when the
+    /// constraints are not satisfied. This doubles the memory held by the input.
+    pub localize_constraint_failures: bool,
+    /// Control to follow the progress of the proof and cancel it between phases.
+    pub control: Option<JobControl>,
+}
+
+impl ProverOptions {
+    /// Reports the start of a phase to the control, failing if the proof was cancelled.
+    fn enter(&self, phase: ProvingPhase) -> Result<(), ProvingError> {
+        self.control
+            .as_ref()
+            .map_or(Ok(()), |control| control.enter(phase))
+    }
+}
 
 pub fn prove_cairo_m<MC: MerkleChannel>(
     input: &mut ProverInput,
     pcs_config: Option<PcsConfig>,
 ) -> Result<Proof<MC::H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    prove_cairo_m_with_options::<MC>(input, pcs_config, &ProverOptions::default())
+}
+
+pub fn prove_cairo_m_with_options<MC: MerkleChannel>(
+    input: &mut ProverInput,
+    pcs_config: Option<PcsConfig>,
+    options: &ProverOptions,
+) -> Result<Proof<MC::H>, ProvingError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    prove_cairo_m_with_report::<MC>(input, pcs_config, options).map(|(proof, _)| proof)
+}
+
+/// Proves the execution and returns the trace size report of the proof alongside it.
+///
+/// See [`TraceReport`] for the reported statistics.
+pub fn prove_cairo_m_with_report<MC: MerkleChannel>(
+    input: &mut ProverInput,
+    pcs_config: Option<PcsConfig>,
+    options: &ProverOptions,
+) -> Result<(Proof<MC::H>, TraceReport), ProvingError>
 where
     SimdBackend: BackendForChannel<MC>,
 {
     let _span = span!(Level::INFO, "prove_cairo_m").entered();
 
+    // Trace generation consumes the input: keep a copy to locate constraint failures, if requested.
+    let mut debug_input = options.localize_constraint_failures.then(|| input.clone());
+
     // Setup protocol.
     let channel = &mut MC::C::default();
 
     let pcs_config = pcs_config.unwrap_or(REGULAR_96_BITS);
     pcs_config.mix_into(channel);
 
-    let trace_log_size = std::cmp::max(
-        PREPROCESSED_TRACE_LOG_SIZE,
-        std::cmp::max(
-            (input.merkle_trees.initial_tree.len() + input.merkle_trees.final_tree.len())
-                .next_power_of_two()
-                .ilog2(),
-            input
-                .instructions
-                .states_by_opcodes
-                .values()
-                .map(|states| states.len().next_power_of_two())
-                .max()
-                .unwrap_or(1)
-                .ilog2(),
-        ),
-    );
+    let public_data = PublicData::new(input);
 
-    info!("twiddles");
-    let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(trace_log_size + pcs_config.fri_config.log_blowup_factor + 2)
-            .circle_domain()
-            .half_coset,
-    );
+    // Execution traces are generated first, as the preprocessed trace depends on the sizes of
+    // the range check tables.
+    options.enter(ProvingPhase::ExecutionTrace)?;
+    let execution_span = info_span!(
+        "execution_trace",
+        n_columns = field::Empty,
+        log_size = field::Empty
+    )
+    .entered();
+    let (claim, trace, lookup_data) = Claim::write_trace::<MC>(input);
+    let trace_report = claim.trace_report(&lookup_data);
+    let preprocessed_trace = claim.preprocessed_trace();
+
+    let trace_log_size = claim
+        .log_sizes()
+        .iter()
+        .flatten()
+        .chain(preprocessed_trace.log_sizes().iter())
+        .copied()
+        .max()
+        .unwrap_or_default();
+    execution_span.record("n_columns", claim.log_sizes().iter().flatten().count());
+    execution_span.record("log_size", trace_log_size);
+    execution_span.exit();
+
+    let twiddles_log_size = trace_log_size + pcs_config.fri_config.log_blowup_factor + 2;
+    let twiddles = info_span!("twiddles", log_size = twiddles_log_size).in_scope(|| {
+        SimdBackend::precompute_twiddles(
+            CanonicCoset::new(twiddles_log_size)
+                .circle_domain()
+                .half_coset,
+        )
+    });
 
     let mut commitment_scheme =
         CommitmentSchemeProver::<SimdBackend, MC>::new(pcs_config, &twiddles);
 
-    let public_data = PublicData::new(input);
     public_data.mix_into(channel);
+    let public_data_commitment = public_data
+        .commitment()
+        .expect("Program entries of the prover input are public");
+    public_data_commitment.mix_into(channel);
 
     // Preprocessed traces
-    info!("preprocessed trace");
-    let preprocessed_trace = PreProcessedTraceBuilder::default().build();
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(preprocessed_trace.gen_trace());
-    tree_builder.commit(channel);
-
-    // Execution traces
-    info!("execution trace");
-    let (claim, trace, lookup_data) = Claim::write_trace::<MC>(input);
-    claim.mix_into(channel);
+    options.enter(ProvingPhase::PreprocessedTrace)?;
+    match &options.preprocessed_cache {
+        Some(cache) => info_span!(
+            "commitment",
+            tree = "preprocessed",
+            n_columns = preprocessed_trace.log_sizes().len(),
+            cached = true
+        )
+        .in_scope(|| cache.commit(&preprocessed_trace, &mut commitment_scheme, channel)),
+        None => {
+            let preprocessed_evals = info_span!(
+                "preprocessed_trace",
+                n_columns = preprocessed_trace.log_sizes().len()
+            )
+            .in_scope(|| preprocessed_trace.gen_trace());
+            info_span!("commitment", tree = "preprocessed").in_scope(|| {
+                let mut tree_builder = commitment_scheme.tree_builder();
+                tree_builder.extend_evals(preprocessed_evals);
+                tree_builder.commit(channel);
+            });
+        }
+    }
 
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(trace);
-    tree_builder.commit(channel);
+    claim.mix_into(channel);
+    info_span!("commitment", tree = "execution").in_scope(|| {
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(trace);
+        tree_builder.commit(channel);
+    });
 
     // Interaction trace
     // Draw interaction elements.
-    info!(
-        "proof of work with {} bits",
-        relations::INTERACTION_POW_BITS
-    );
-    let interaction_pow = SimdBackend::grind(channel, relations::INTERACTION_POW_BITS);
+    // The sequential search always finds the smallest nonce.
+    let interaction_pow = info_span!(
+        "proof_of_work",
+        bits = relations::INTERACTION_POW_BITS,
+        deterministic = options.deterministic
+    )
+    .in_scope(|| {
+        if options.deterministic {
+            CpuBackend::grind(channel, relations::INTERACTION_POW_BITS)
+        } else {
+            SimdBackend::grind(channel, relations::INTERACTION_POW_BITS)
+        }
+    });
     channel.mix_u64(interaction_pow);
 
-    info!("interaction trace");
+    options.enter(ProvingPhase::InteractionTrace)?;
     let relations = Relations::draw(channel);
 
-    let (interaction_trace, interaction_claim) =
-        InteractionClaim::write_interaction_trace(&relations, &lookup_data);
+    let (interaction_trace, interaction_claim) = info_span!("interaction_trace")
+        .in_scope(|| InteractionClaim::write_interaction_trace(&relations, &lookup_data));
+
+    // The components' claimed sums must cancel out the public data contribution,
+    // otherwise the verifier rejects the proof.
+    let claimed = interaction_claim.components_claimed_sum();
+    let computed = -public_data.initial_logup_sum(&relations);
+    if claimed != computed {
+        return Err(ProvingError::InvalidLogupSum {
+            claimed,
+            computed,
+            components: interaction_claim
+                .claimed_sums()
+                .into_iter()
+                .map(|(component, claimed_sum)| ComponentLogupSum {
+                    component,
+                    claimed_sum,
+                })
+                .collect(),
+        });
+    }
     interaction_claim.mix_into(channel);
 
-    let mut tree_builder = commitment_scheme.tree_builder();
-    tree_builder.extend_evals(interaction_trace);
-    tree_builder.commit(channel);
+    info_span!("commitment", tree = "interaction").in_scope(|| {
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(interaction_trace);
+        tree_builder.commit(channel);
+    });
 
     // Prove stark.
-    info!("prove stark");
+    options.enter(ProvingPhase::Stark)?;
     let mut tree_span_provider =
         TraceLocationAllocator::new_with_preproccessed_columns(&preprocessed_trace.ids());
     let components = Components::new(
@@ -128,8 +240,29 @@ where
 
     let proving_start = Instant::now();
 
+    // The composition, out-of-domain sampling and FRI run in the spans of stwo, nested in this one.
+    let stark_span = info_span!(
+        "prove_stark",
+        log_size = trace_log_size,
+        log_blowup_factor = pcs_config.fri_config.log_blowup_factor,
+        n_queries = pcs_config.fri_config.n_queries
+    )
+    .entered();
     let stark_proof = prove::<SimdBackend, _>(&components.provers(), channel, commitment_scheme)
-        .map_err(ProvingError::from)?;
+        .map_err(|source| {
+            // If requested, locate the failing component and row.
+            if matches!(source, StwoProvingError::ConstraintsNotSatisfied)
+                && let Some(debug_input) = debug_input.as_mut()
+                && let Some(violation) = find_constraint_violation(debug_input)
+            {
+                return ProvingError::ConstraintsNotSatisfied { violation };
+            }
+            ProvingError::Stwo {
+                phase: ProvingPhase::Stark,
+                source,
+            }
+        })?;
+    stark_span.exit();
 
     let proving_duration = proving_start.elapsed();
     let proving_mhz = ((1 << trace_log_size) as f64) / proving_duration.as_secs_f64() / 1_000_000.0;
@@ -137,11 +270,16 @@ where
     info!("Proving time: {:?}", proving_duration);
     info!("Proving speed: {:.2} MHz", proving_mhz);
 
-    Ok(Proof {
-        claim,
-        interaction_claim,
-        public_data,
-        stark_proof,
-        interaction_pow,
-    })
+    Ok((
+        Proof {
+            claim,
+            interaction_claim,
+            public_data,
+            public_data_commitment,
+            stark_proof,
+            interaction_pow,
+            pcs_config,
+        },
+        trace_report,
+    ))
 }
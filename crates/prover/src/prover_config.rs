@@ -18,3 +18,15 @@ pub const REGULAR_96_BITS: PcsConfig = PcsConfig {
         n_queries: 80,
     },
 };
+
+/// Insecure configuration for tests, which only need a proof that verifies.
+///
+/// Grinding is disabled and a few queries are enough to exercise the FRI protocol.
+pub const DEV_FAST: PcsConfig = PcsConfig {
+    pow_bits: 0,
+    fri_config: FriConfig {
+        log_last_layer_degree_bound: 0,
+        log_blowup_factor: 1,
+        n_queries: 3,
+    },
+};
@@ -30,7 +30,7 @@
 use std::collections::HashMap;
 use std::ops::Range;
 
-use cairo_m_common::{PublicAddressRanges, State as VmRegisters};
+use cairo_m_common::{PublicAddressRanges, SegmentKind, State as VmRegisters};
 use num_traits::{One, Zero};
 use serde::{Deserialize, Serialize};
 use stwo_constraint_framework::Relation;
@@ -41,43 +41,213 @@ use stwo_prover::core::fields::qm31::{QM31, SecureField};
 
 use crate::adapter::ProverInput;
 use crate::adapter::memory::Memory;
-use crate::adapter::merkle::TREE_HEIGHT;
+use crate::adapter::merkle::{TREE_HEIGHT, TreeType, build_partial_merkle_tree};
 use crate::components::Relations;
+use crate::errors::VerificationError;
+use crate::poseidon2::{Poseidon2Hash, RATE, hash_m31s};
 use crate::relations;
 
+/// Number of memory cells of a page of public input or output memory.
+pub const PUBLIC_PAGE_SIZE: u32 = 1 << 8;
+
+/// Splits the environment, input and output ranges into pages of [`PUBLIC_PAGE_SIZE`] cells.
+///
+/// Pages don't span two ranges, so the last page of a range may be shorter. The program
+/// range is not paged: it is always part of the trace.
+///
+/// ## Returns
+/// The pages of the environment range, of the input range and of the input segments, then of
+/// the output range and of the output segments
+pub fn page_ranges(
+    ranges: &PublicAddressRanges,
+) -> impl Iterator<Item = (SegmentKind, Range<u32>)> + '_ {
+    let segments = move |kind: SegmentKind| {
+        ranges
+            .segments
+            .iter()
+            .filter(move |segment| segment.kind == kind)
+            .map(move |segment| (kind, segment.range.clone()))
+    };
+    [
+        (SegmentKind::Input, ranges.environment.clone()),
+        (SegmentKind::Input, ranges.input.clone()),
+    ]
+    .into_iter()
+    .chain(segments(SegmentKind::Input))
+    .chain(std::iter::once((
+        SegmentKind::Output,
+        ranges.output.clone(),
+    )))
+    .chain(segments(SegmentKind::Output))
+    .flat_map(|(kind, range)| {
+        range
+            .clone()
+            .step_by(PUBLIC_PAGE_SIZE as usize)
+            .map(move |start| (kind, start..(start + PUBLIC_PAGE_SIZE).min(range.end)))
+    })
+}
+
+/// A page of public input or output memory.
+///
+/// Every page is part of the trace, with its entries in [`PublicEntries`]. The commitments
+/// let a verifier check the public memory without the full entries.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PublicPage {
+    /// Memory in which the page is read: initial memory for inputs, final memory for outputs
+    pub kind: SegmentKind,
+    /// First address of the page
+    pub start: M31,
+    /// Number of cells of the page, at most [`PUBLIC_PAGE_SIZE`]
+    pub len: u32,
+    /// Commitment to the values of the page, see [`Self::commit`]
+    pub commitment: [M31; RATE],
+}
+
+impl PublicPage {
+    /// Computes the commitment to the values of a page.
+    ///
+    /// It is the Poseidon2 hash of the first address and the length of the page, followed by
+    /// a presence flag and the four limbs of each value, so that missing cells differ from
+    /// zero values.
+    ///
+    /// ## Arguments
+    /// * `start` - The first address of the page
+    /// * `values` - The values of the cells of the page, `None` for missing cells
+    pub fn commit(start: M31, values: &[Option<QM31>]) -> [M31; RATE] {
+        let mut elements = vec![start, M31::from(values.len())];
+        for value in values {
+            match value {
+                Some(value) => {
+                    elements.push(M31::one());
+                    elements.extend(value.to_m31_array());
+                }
+                None => elements.extend([M31::zero(); 5]),
+            }
+        }
+        hash_m31s(&elements)
+    }
+
+    /// Returns the addresses of the page
+    pub fn addresses(&self) -> impl Iterator<Item = M31> {
+        (self.start.0..self.start.0 + self.len).map(M31)
+    }
+
+    /// Returns true if the commitment of the page matches the given memory.
+    ///
+    /// ## Arguments
+    /// * `memory` - Values of the memory in which the page is read
+    pub fn matches(&self, memory: impl Fn(M31) -> Option<QM31>) -> bool {
+        let values = self.addresses().map(memory).collect::<Vec<_>>();
+        Self::commit(self.start, &values) == self.commitment
+    }
+}
+
 /// Structured public entries for initial and final memory
 ///
 /// This struct is used to store the public entries for the initial and final memory.
 /// It contains:
 /// - program: the instructions
-/// - input: the main arguments
-/// - output: the return values
+/// - input: the main arguments, followed by the input user segments
+/// - output: the return values, followed by the output user segments
 ///
 /// The entries are stored as a vector of tuples, where the first element is the address,
 /// the second element is the value, and the third element is the clock.
+///
+/// Input and output entries cover all the [`PublicPage`]s, listed with their commitments in
+/// `pages`.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PublicEntries {
     pub program: Vec<Option<(M31, QM31, M31)>>,
     pub input: Vec<Option<(M31, QM31, M31)>>,
     pub output: Vec<Option<(M31, QM31, M31)>>,
+    /// Pages of the input and output memory, see [`page_ranges`]
+    #[serde(default)]
+    pub pages: Vec<PublicPage>,
 }
 
 impl PublicEntries {
-    pub fn new(memory: &Memory, public_address_ranges: &PublicAddressRanges) -> Self {
+    pub fn new(
+        memory: &Memory,
+        public_address_ranges: &PublicAddressRanges,
+        pages: &[PublicPage],
+    ) -> Self {
+        let pages_of = |kind: SegmentKind| {
+            pages
+                .iter()
+                .filter(move |page| page.kind == kind)
+                .map(|page| page.start.0..page.start.0 + page.len)
+        };
+
         // Pre-allocate with known sizes for better memory efficiency
         let program = Self::extract_range_with_capacity(
             &memory.initial_memory,
             &public_address_ranges.program,
         );
-        let input =
-            Self::extract_range_with_capacity(&memory.initial_memory, &public_address_ranges.input);
-        let output =
-            Self::extract_range_with_capacity(&memory.final_memory, &public_address_ranges.output);
+        let input = pages_of(SegmentKind::Input)
+            .flat_map(|range| Self::extract_range_with_capacity(&memory.initial_memory, &range))
+            .collect();
+        let output = pages_of(SegmentKind::Output)
+            .flat_map(|range| Self::extract_range_with_capacity(&memory.final_memory, &range))
+            .collect();
 
         Self {
             program,
             input,
             output,
+            pages: pages.to_vec(),
+        }
+    }
+
+    /// Checks the commitments of the pages against their entries.
+    ///
+    /// Use [`Self::verify_public_memory`] to check them against the expected public memory.
+    ///
+    /// ## Errors
+    /// Returns [`VerificationError::InvalidPublicPage`] for the first page whose commitment
+    /// doesn't match its entries.
+    pub fn verify_pages(&self) -> Result<(), VerificationError> {
+        let values = |entries: &[Option<(M31, QM31, M31)>]| {
+            entries
+                .iter()
+                .flatten()
+                .map(|&(addr, value, _)| (addr, value))
+                .collect::<HashMap<_, _>>()
+        };
+        let input = values(&self.input);
+        let output = values(&self.output);
+        for page in &self.pages {
+            let values = match page.kind {
+                SegmentKind::Input => &input,
+                SegmentKind::Output => &output,
+            };
+            if !page.matches(|addr| values.get(&addr).copied()) {
+                return Err(VerificationError::InvalidPublicPage { start: page.start });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the commitments of all the pages against the public memory
+    /// expected by the verifier.
+    ///
+    /// ## Arguments
+    /// * `memory` - Expected values of the input cells in the initial memory, and of the
+    ///   output cells in the final memory
+    ///
+    /// ## Errors
+    /// Returns [`VerificationError::InvalidPublicPage`] for the first page whose commitment
+    /// doesn't match the expected values.
+    pub fn verify_public_memory(
+        &self,
+        memory: &HashMap<M31, QM31>,
+    ) -> Result<(), VerificationError> {
+        match self
+            .pages
+            .iter()
+            .find(|page| !page.matches(|addr| memory.get(&addr).copied()))
+        {
+            Some(page) => Err(VerificationError::InvalidPublicPage { start: page.start }),
+            None => Ok(()),
         }
     }
 
@@ -134,6 +304,7 @@ impl PublicEntries {
             self.program.len() as u32,
             self.input.len() as u32,
             self.output.len() as u32,
+            self.pages.len() as u32,
         ]);
         channel.mix_u32s(
             &self
@@ -186,6 +357,41 @@ impl PublicEntries {
                 })
                 .collect::<Vec<_>>(),
         );
+        channel.mix_u32s(
+            &self
+                .pages
+                .iter()
+                .flat_map(|page| {
+                    [page.kind as u32, page.start.0, page.len]
+                        .into_iter()
+                        .chain(page.commitment.map(|element| element.0))
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+}
+
+/// Commitment to the public data of a proof.
+///
+/// It is the Poseidon2 hash of the program id, the initial and final registers, and the
+/// commitments of the pages of input and output memory, serialized as the little-endian bytes of the digest elements.
+/// On-chain verifiers can bind a proof to a single 32-byte value instead of the full
+/// public data.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PublicDataCommitment(pub [u8; 32]);
+
+impl PublicDataCommitment {
+    /// Returns the commitment as little-endian 32-bit words.
+    pub fn to_u32s(&self) -> [u32; RATE] {
+        std::array::from_fn(|i| u32::from_le_bytes(self.0[4 * i..4 * i + 4].try_into().unwrap()))
+    }
+
+    /// Mixes the commitment into a channel.
+    ///
+    /// ## Arguments
+    /// * `channel` - the channel to mix the commitment into
+    pub fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u32s(&self.to_u32s());
     }
 }
 
@@ -222,7 +428,8 @@ pub struct PublicData {
     /// Merkle root hash of final memory state
     pub final_root: M31,
     /// Public memory entries: (address, value, clock) or None if unused
-    /// Includes program code, inputs, and outputs that verifier must see
+    /// Includes program code, inputs and outputs, with the commitments of the pages of
+    /// inputs and outputs
     pub public_memory: PublicEntries,
 }
 
@@ -260,8 +467,65 @@ impl PublicData {
                 .merkle_trees
                 .final_root
                 .expect("Final memory root is required for verification"),
-            public_memory: PublicEntries::new(&input.memory, &input.public_address_ranges),
+            public_memory: PublicEntries::new(
+                &input.memory,
+                &input.public_address_ranges,
+                &input.public_pages,
+            ),
+        }
+    }
+
+    /// Returns the Poseidon2 hash of the program, the root of its partial Merkle tree.
+    ///
+    /// ## Returns
+    /// The program id, or `None` if a program address has no public memory entry, which
+    /// only happens for malformed public data.
+    pub fn program_id(&self) -> Option<M31> {
+        let program_map = self
+            .public_memory
+            .program
+            .iter()
+            .map(|entry| {
+                let (addr, value, _clock) = (*entry)?;
+                Some((addr, (value, M31::zero(), M31::zero())))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        let (_, program_id) = build_partial_merkle_tree::<Poseidon2Hash>(
+            &program_map,
+            TreeType::Initial,
+            &PublicAddressRanges::default(),
+        );
+
+        program_id
+    }
+
+    /// Computes the commitment to the public data.
+    ///
+    /// The commitment binds the program id, the initial and final registers, and the input
+    /// and output memory through the commitments of its pages.
+    ///
+    /// ## Returns
+    /// The 32-byte commitment to the public data, or `None` if the program id can't be
+    /// computed (see [`Self::program_id`])
+    pub fn commitment(&self) -> Option<PublicDataCommitment> {
+        let mut values = vec![
+            self.program_id()?,
+            self.initial_registers.pc,
+            self.initial_registers.fp,
+            self.final_registers.pc,
+            self.final_registers.fp,
+        ];
+        values.push(self.public_memory.pages.len().into());
+        for page in &self.public_memory.pages {
+            values.extend(page.commitment);
+        }
+
+        let mut bytes = [0u8; 32];
+        for (chunk, element) in bytes.chunks_exact_mut(4).zip(hash_m31s(&values)) {
+            chunk.copy_from_slice(&element.0.to_le_bytes());
         }
+        Some(PublicDataCommitment(bytes))
     }
 
     /// Computes the initial logup sum for public data in the lookup argument system.
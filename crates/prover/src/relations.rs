@@ -32,11 +32,13 @@ relation!(Registers, 3);
 relation!(Merkle, 4);
 
 // Poseidon2 hash function relation for cryptographic computations.
-// Connects the Poseidon2 component to the rest of the components: Poseidon2 component uses the initial
-// state and emits the final digest. Other component that need to prove Poseidon2 computation should
-// emit the initial state and consume the final digest.
-// Structure: 16-element state array for Poseidon2 permutation
-relation!(Poseidon2, 16);
+// Connects the Poseidon2 component to the rest of the components: the Poseidon2 component uses the
+// initial state of each permutation together with its digest, and the components that need a
+// Poseidon2 computation emit them, so that a digest is bound to the state it is computed from.
+// The Merkle hashes only use the first element of the output state, the sponge hashes use
+// POSEIDON2_DIGEST_SIZE elements.
+// Structure: 16-element initial state, out0, is_sponge * out1, ..., is_sponge * out7, is_sponge
+relation!(Poseidon2, 25);
 
 // Bitwise operation relation for 8-bit values.
 // Handles AND (id=0), OR (id=1), and XOR (id=2) operations.
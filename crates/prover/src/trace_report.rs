@@ -0,0 +1,177 @@
+//! Per-component trace size report.
+//!
+//! Each component trace is padded to a power of two rows, so the proving cost is driven
+//! by the largest components and by how much of their trace is padding. A [`TraceReport`]
+//! summarizes, for each component, its log size, the rows actually used and the number of
+//! columns committed, to spot which builtin dominates the proof and whether a smaller
+//! execution would fit in a smaller trace.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::pcs::TreeVec;
+
+/// Trace size statistics of a single component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentReport {
+    /// Name of the component.
+    pub name: String,
+    /// Log2 of the number of rows of the component trace.
+    pub log_size: u32,
+    /// Number of rows holding actual data, the others being padding.
+    pub rows_used: usize,
+    /// Number of columns in the execution trace.
+    pub trace_columns: usize,
+    /// Number of columns in the interaction trace.
+    pub interaction_columns: usize,
+}
+
+impl ComponentReport {
+    /// Creates the report of a component from its claim's log sizes.
+    ///
+    /// ## Arguments
+    ///
+    /// * `name` - The component name.
+    /// * `log_size` - The log size of the component trace.
+    /// * `rows_used` - The number of non-padding rows.
+    /// * `log_sizes` - The log sizes of the component columns, per tree.
+    pub fn new(name: &str, log_size: u32, rows_used: usize, log_sizes: &TreeVec<Vec<u32>>) -> Self {
+        Self {
+            name: name.to_string(),
+            log_size,
+            rows_used,
+            trace_columns: log_sizes[1].len(),
+            interaction_columns: log_sizes[2].len(),
+        }
+    }
+
+    /// Total number of rows, padding included.
+    pub const fn rows(&self) -> usize {
+        1 << self.log_size
+    }
+
+    /// Number of padding rows.
+    pub const fn padding_rows(&self) -> usize {
+        self.rows().saturating_sub(self.rows_used)
+    }
+
+    /// Fraction of the rows that are padding, between 0 and 1.
+    pub fn padding_ratio(&self) -> f64 {
+        self.padding_rows() as f64 / self.rows() as f64
+    }
+
+    /// Number of committed cells (rows times columns) over both trees.
+    pub const fn cells(&self) -> usize {
+        self.rows() * (self.trace_columns + self.interaction_columns)
+    }
+}
+
+/// Trace size statistics of all the components of a proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceReport {
+    pub components: Vec<ComponentReport>,
+}
+
+impl TraceReport {
+    /// Total number of committed cells.
+    pub fn total_cells(&self) -> usize {
+        self.components.iter().map(ComponentReport::cells).sum()
+    }
+
+    /// Total number of padding cells.
+    pub fn padding_cells(&self) -> usize {
+        self.components
+            .iter()
+            .map(|c| c.padding_rows() * (c.trace_columns + c.interaction_columns))
+            .sum()
+    }
+
+    /// Returns the component committing the most cells.
+    pub fn dominant(&self) -> Option<&ComponentReport> {
+        self.components.iter().max_by_key(|c| c.cells())
+    }
+
+    /// Returns the components that would halve in size with at most `max_rows_to_drop` fewer rows.
+    ///
+    /// A component only uses the lower half of its trace plus a few rows when a slightly
+    /// shorter execution would let it shrink to the previous power of two.
+    pub fn shrinkable(&self, max_rows_to_drop: usize) -> Vec<&ComponentReport> {
+        self.components
+            .iter()
+            .filter(|c| {
+                let half = c.rows() / 2;
+                c.rows_used > half && c.rows_used - half <= max_rows_to_drop
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for TraceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<28} {:>8} {:>10} {:>10} {:>8} {:>7} {:>11}",
+            "component", "log_size", "rows_used", "rows", "padding", "columns", "cells"
+        )?;
+        for c in &self.components {
+            writeln!(
+                f,
+                "{:<28} {:>8} {:>10} {:>10} {:>7.1}% {:>7} {:>11}",
+                c.name,
+                c.log_size,
+                c.rows_used,
+                c.rows(),
+                c.padding_ratio() * 100.0,
+                c.trace_columns + c.interaction_columns,
+                c.cells(),
+            )?;
+        }
+        write!(
+            f,
+            "total cells: {}, padding cells: {}",
+            self.total_cells(),
+            self.padding_cells()
+        )?;
+        if let Some(dominant) = self.dominant() {
+            write!(f, ", dominant component: {}", dominant.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(name: &str, log_size: u32, rows_used: usize, columns: usize) -> ComponentReport {
+        ComponentReport::new(
+            name,
+            log_size,
+            rows_used,
+            &TreeVec::new(vec![vec![], vec![log_size; columns], vec![log_size; 4]]),
+        )
+    }
+
+    #[test]
+    fn test_component_report_padding() {
+        let report = component("memory", 4, 10, 8);
+        assert_eq!(report.rows(), 16);
+        assert_eq!(report.padding_rows(), 6);
+        assert_eq!(report.padding_ratio(), 0.375);
+        assert_eq!(report.cells(), 16 * 12);
+    }
+
+    #[test]
+    fn test_trace_report_totals() {
+        let report = TraceReport {
+            components: vec![component("memory", 4, 10, 8), component("ret", 6, 33, 2)],
+        };
+        assert_eq!(report.total_cells(), 16 * 12 + 64 * 6);
+        assert_eq!(report.padding_cells(), 6 * 12 + 31 * 6);
+        assert_eq!(report.dominant().unwrap().name, "ret");
+
+        let shrinkable = report.shrinkable(1);
+        assert_eq!(shrinkable.len(), 1);
+        assert_eq!(shrinkable[0].name, "ret");
+    }
+}
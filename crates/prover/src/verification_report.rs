@@ -0,0 +1,144 @@
+//! Verification report.
+//!
+//! A [`VerificationReport`] summarizes what the verifier checked on a proof: the log size and
+//! claimed sum of each component, the total logup sum once the public data is accounted for,
+//! the number of FRI queries and the time spent. It serves audits, and comparing the claimed
+//! sums of a rejected proof with the ones of a valid proof of the same program points to the
+//! component at fault.
+
+use std::fmt;
+use std::time::Duration;
+
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::fields::qm31::SecureField;
+
+use crate::components::{Claim, InteractionClaim};
+
+/// Claim of a single component, as checked by the verifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentVerification {
+    /// Name of the component.
+    pub name: String,
+    /// Log2 of the number of rows of the component trace.
+    pub log_size: u32,
+    /// Claimed sum of the component lookups.
+    pub claimed_sum: SecureField,
+}
+
+/// Summary of the verification of a proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub components: Vec<ComponentVerification>,
+    /// Sum of the components' claimed sums and of the public data contribution.
+    /// Zero for a valid proof.
+    pub total_logup_sum: SecureField,
+    /// Number of FRI queries checked.
+    pub n_fri_queries: usize,
+    /// Time spent verifying the proof.
+    pub duration: Duration,
+}
+
+impl VerificationReport {
+    /// Creates the report of a proof from its claims.
+    ///
+    /// ## Arguments
+    ///
+    /// * `claim` - The claim of the proof, holding the log sizes.
+    /// * `interaction_claim` - The interaction claim of the proof, holding the claimed sums.
+    /// * `total_logup_sum` - The claimed sums and the public data contribution, summed.
+    /// * `n_fri_queries` - The number of FRI queries of the PCS configuration.
+    /// * `duration` - The verification time.
+    pub fn new(
+        claim: &Claim,
+        interaction_claim: &InteractionClaim,
+        total_logup_sum: SecureField,
+        n_fri_queries: usize,
+        duration: Duration,
+    ) -> Self {
+        let components = claim
+            .component_log_sizes()
+            .into_iter()
+            .zip(interaction_claim.claimed_sums())
+            .map(
+                |((name, log_size), (_, claimed_sum))| ComponentVerification {
+                    name: name.to_string(),
+                    log_size,
+                    claimed_sum,
+                },
+            )
+            .collect();
+        Self {
+            components,
+            total_logup_sum,
+            n_fri_queries,
+            duration,
+        }
+    }
+
+    /// Returns the components with a non-zero claimed sum.
+    ///
+    /// Components only consuming from or yielding to the others have non-zero sums, which
+    /// cancel out across the proof.
+    pub fn unbalanced(&self) -> Vec<&ComponentVerification> {
+        self.components
+            .iter()
+            .filter(|c| !c.claimed_sum.is_zero())
+            .collect()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<28} {:>8} {}", "component", "log_size", "claimed_sum")?;
+        for c in &self.components {
+            writeln!(f, "{:<28} {:>8} {}", c.name, c.log_size, c.claimed_sum)?;
+        }
+        write!(
+            f,
+            "total logup sum: {}, FRI queries: {}, verified in {:?}",
+            self.total_logup_sum, self.n_fri_queries, self.duration
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo_prover::core::fields::m31::M31;
+
+    use super::*;
+
+    fn component(name: &str, claimed_sum: u32) -> ComponentVerification {
+        ComponentVerification {
+            name: name.to_string(),
+            log_size: 4,
+            claimed_sum: SecureField::from(M31::from(claimed_sum)),
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_components() {
+        let report = VerificationReport {
+            components: vec![component("memory", 0), component("ret", 3)],
+            total_logup_sum: SecureField::zero(),
+            n_fri_queries: 70,
+            duration: Duration::from_millis(12),
+        };
+        let unbalanced = report.unbalanced();
+        assert_eq!(unbalanced.len(), 1);
+        assert_eq!(unbalanced[0].name, "ret");
+    }
+
+    #[test]
+    fn test_display() {
+        let report = VerificationReport {
+            components: vec![component("memory", 0)],
+            total_logup_sum: SecureField::zero(),
+            n_fri_queries: 70,
+            duration: Duration::from_millis(12),
+        };
+        let display = report.to_string();
+        assert!(display.lines().nth(1).unwrap().starts_with("memory"));
+        assert!(display.ends_with("FRI queries: 70, verified in 12ms"));
+    }
+}
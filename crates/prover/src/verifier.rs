@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
 use num_traits::Zero;
 use stwo_constraint_framework::TraceLocationAllocator;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
 use stwo_prover::core::backend::BackendForChannel;
 use stwo_prover::core::backend::simd::SimdBackend;
 use stwo_prover::core::channel::{Channel, MerkleChannel};
@@ -8,88 +12,332 @@ use stwo_prover::core::pcs::{CommitmentSchemeVerifier, PcsConfig};
 use stwo_prover::core::prover::{VerificationError as StwoVerificationError, verify};
 use tracing::{Level, info, span};
 
-use crate::components::{Components, Relations};
+use crate::components::{Claim, Components, Relations};
 use crate::errors::VerificationError;
-use crate::preprocessed::PreProcessedTraceBuilder;
 use crate::prover_config::REGULAR_96_BITS;
+use crate::public_data::PublicDataCommitment;
+use crate::verification_report::VerificationReport;
 use crate::{Proof, relations};
 
+/// Minimum parameters a verifier accepts for the proofs it verifies.
+///
+/// Verifying a proof with the configuration embedded in it only shows that the proof is
+/// valid for that configuration: a proof produced with a development configuration such as
+/// [`DEV_FAST`](crate::prover_config::DEV_FAST) verifies while providing almost no
+/// soundness. The policy rejects such proofs before verifying them.
+///
+/// Each parameter is bounded on its own, and the conjectured security of the configuration,
+/// `n_queries * log_blowup_factor + pow_bits`, is bounded as a whole. The blowup factor is
+/// bounded in log units, as the `log_blowup_factor` of the FRI configuration: a bound of 2
+/// stands for a blowup factor of 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    /// Minimum number of FRI queries
+    pub min_queries: usize,
+    /// Minimum number of proof-of-work bits
+    pub min_pow_bits: u32,
+    /// Minimum log2 of the blowup factor, each FRI query providing that many bits of security
+    pub min_log_blowup: u32,
+    /// Maximum log2 of the blowup factor, bounding the evaluation domains the verifier works
+    /// with
+    pub max_log_blowup: u32,
+    /// Minimum conjectured security, in bits
+    pub min_security_bits: u32,
+}
+
+impl SecurityPolicy {
+    /// Policy accepting the proofs at least as strong as those produced with `config`.
+    pub const fn at_least(config: &PcsConfig) -> Self {
+        Self {
+            min_queries: config.fri_config.n_queries,
+            min_pow_bits: config.pow_bits,
+            min_log_blowup: config.fri_config.log_blowup_factor,
+            max_log_blowup: config.fri_config.log_blowup_factor,
+            min_security_bits: security_bits(config),
+        }
+    }
+
+    /// Checks that a PCS configuration satisfies the policy.
+    ///
+    /// ## Errors
+    /// Returns [`VerificationError::InsecureParameters`] with the first parameter violating
+    /// the policy.
+    pub fn check(&self, config: &PcsConfig) -> Result<(), VerificationError> {
+        let fri_config = &config.fri_config;
+        if fri_config.n_queries < self.min_queries {
+            return Err(VerificationError::InsecureParameters(format!(
+                "{} FRI queries, at least {} required",
+                fri_config.n_queries, self.min_queries
+            )));
+        }
+        if config.pow_bits < self.min_pow_bits {
+            return Err(VerificationError::InsecureParameters(format!(
+                "{} proof-of-work bits, at least {} required",
+                config.pow_bits, self.min_pow_bits
+            )));
+        }
+        if fri_config.log_blowup_factor < self.min_log_blowup {
+            return Err(VerificationError::InsecureParameters(format!(
+                "log blowup factor {}, at least {} required",
+                fri_config.log_blowup_factor, self.min_log_blowup
+            )));
+        }
+        if fri_config.log_blowup_factor > self.max_log_blowup {
+            return Err(VerificationError::InsecureParameters(format!(
+                "log blowup factor {}, at most {} allowed",
+                fri_config.log_blowup_factor, self.max_log_blowup
+            )));
+        }
+        if security_bits(config) < self.min_security_bits {
+            return Err(VerificationError::InsecureParameters(format!(
+                "{} bits of security, at least {} required",
+                security_bits(config),
+                self.min_security_bits
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Conjectured security of a PCS configuration, in bits.
+const fn security_bits(config: &PcsConfig) -> u32 {
+    config.fri_config.n_queries as u32 * config.fri_config.log_blowup_factor + config.pow_bits
+}
+
+impl Default for SecurityPolicy {
+    /// Policy matching [`REGULAR_96_BITS`].
+    fn default() -> Self {
+        Self::at_least(&REGULAR_96_BITS)
+    }
+}
+
+/// Verifies a proof with the PCS configuration embedded in it, provided that the
+/// configuration satisfies a security policy.
+///
+/// Unlike [`verify_cairo_m`], the verifier doesn't need to know the exact configuration the
+/// proof was produced with, only the minimum parameters it accepts.
+pub fn verify_cairo_m_with_policy<MC: MerkleChannel>(
+    proof: Proof<MC::H>,
+    policy: &SecurityPolicy,
+) -> Result<VerificationReport, VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let pcs_config = proof.pcs_config;
+    policy.check(&pcs_config)?;
+    verify_cairo_m::<MC>(proof, Some(pcs_config))
+}
+
+/// Verifies a proof whose public data must match an expected commitment.
+///
+/// This lets a verifier bind the proof to a single [`PublicDataCommitment`] instead of
+/// checking the full public data.
+///
+/// ## Arguments
+/// * `proof` - The proof to verify
+/// * `pcs_config` - The PCS configuration of the proof, [`REGULAR_96_BITS`] by default
+/// * `commitment` - The expected commitment to the public data of the proof
+pub fn verify_cairo_m_with_commitment<MC: MerkleChannel>(
+    proof: Proof<MC::H>,
+    pcs_config: Option<PcsConfig>,
+    commitment: &PublicDataCommitment,
+) -> Result<VerificationReport, VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    if proof.public_data_commitment != *commitment {
+        return Err(VerificationError::PublicDataCommitmentMismatch);
+    }
+    verify_cairo_m::<MC>(proof, pcs_config)
+}
+
+/// Verifies a proof and returns the report of its verification.
+///
+/// The proof must have been produced with `pcs_config`, [`REGULAR_96_BITS`] by default. See
+/// [`VerificationReport`] for the reported statistics.
 pub fn verify_cairo_m<MC: MerkleChannel>(
     proof: Proof<MC::H>,
     pcs_config: Option<PcsConfig>,
-) -> Result<(), VerificationError>
+) -> Result<VerificationReport, VerificationError>
 where
     SimdBackend: BackendForChannel<MC>,
 {
     let _span = span!(Level::INFO, "verify_cairo_m").entered();
+    VerifierSetup::<MC>::new(pcs_config).verify(proof)
+}
 
-    // Setup protocol.
-    let channel = &mut MC::C::default();
-
-    let pcs_config = pcs_config.unwrap_or(REGULAR_96_BITS);
-    pcs_config.mix_into(channel);
-    proof.public_data.mix_into(channel);
-
-    let commitment_scheme_verifier = &mut CommitmentSchemeVerifier::<MC>::new(pcs_config);
-
-    // Preprocessed trace.
-    info!("preprocessed trace");
-    let preprocessed_trace = PreProcessedTraceBuilder::default().build();
-    // TODO: assert proof.stark_proof.commitments[0] == known_root of preprocessed trace commitment
-    commitment_scheme_verifier.commit(
-        proof.stark_proof.commitments[0],
-        &preprocessed_trace.log_sizes(),
-        channel,
-    );
-
-    // Execution traces
-    info!("execution trace");
-    proof.claim.mix_into(channel);
-    commitment_scheme_verifier.commit(
-        proof.stark_proof.commitments[1],
-        &proof.claim.log_sizes()[1],
-        channel,
-    );
-
-    // Proof of work.
-    channel.mix_u64(proof.interaction_pow);
-    if channel.trailing_zeros() < relations::INTERACTION_POW_BITS {
-        return Err(VerificationError::Stwo(StwoVerificationError::ProofOfWork));
+/// Verifies several proofs, sharing the protocol setup across them.
+///
+/// The channel setup does not depend on the proof, and the preprocessed columns only depend
+/// on the sizes of the range check tables: both are computed once for all the proofs sharing
+/// them. This targets workloads verifying many small proofs, typically of the same program.
+///
+/// ## Arguments
+/// * `proofs` - The proofs to verify, consumed by the verification
+/// * `pcs_config` - The PCS configuration of the proofs, [`REGULAR_96_BITS`] by default
+///
+/// ## Returns
+/// * `Ok(())` - All the proofs are valid
+/// * `Err(VerificationError::BatchProof)` - The first invalid proof and the reason it was rejected
+pub fn verify_batch<MC: MerkleChannel>(
+    proofs: Vec<Proof<MC::H>>,
+    pcs_config: Option<PcsConfig>,
+) -> Result<(), VerificationError>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    let _span = span!(Level::INFO, "verify_batch", n_proofs = proofs.len()).entered();
+    let mut setup = VerifierSetup::<MC>::new(pcs_config);
+    for (index, proof) in proofs.into_iter().enumerate() {
+        setup
+            .verify(proof)
+            .map_err(|source| VerificationError::BatchProof {
+                index,
+                source: Box::new(source),
+            })?;
     }
+    Ok(())
+}
+
+/// Ids and log sizes of the preprocessed columns of a proof.
+struct PreprocessedColumns {
+    ids: Vec<PreProcessedColumnId>,
+    log_sizes: Vec<u32>,
+}
+
+impl PreprocessedColumns {
+    /// Key of the columns: the log sizes of the range check tables, the only part of the
+    /// preprocessed trace depending on the proof.
+    const fn key(claim: &Claim) -> [u32; 3] {
+        [
+            claim.range_check_8.log_size,
+            claim.range_check_16.log_size,
+            claim.range_check_20.log_size,
+        ]
+    }
+
+    fn new(claim: &Claim) -> Self {
+        let preprocessed_trace = claim.preprocessed_trace();
+        Self {
+            ids: preprocessed_trace.ids(),
+            log_sizes: preprocessed_trace.log_sizes(),
+        }
+    }
+}
+
+/// Proof-independent part of the verification.
+struct VerifierSetup<MC: MerkleChannel> {
+    pcs_config: PcsConfig,
+    /// Channel with the PCS configuration mixed in.
+    channel: MC::C,
+    /// Preprocessed columns of the proofs verified so far, by range check sizes.
+    preprocessed_columns: HashMap<[u32; 3], PreprocessedColumns>,
+}
+
+impl<MC: MerkleChannel> VerifierSetup<MC>
+where
+    SimdBackend: BackendForChannel<MC>,
+{
+    fn new(pcs_config: Option<PcsConfig>) -> Self {
+        let pcs_config = pcs_config.unwrap_or(REGULAR_96_BITS);
+        let mut channel = MC::C::default();
+        pcs_config.mix_into(&mut channel);
+        Self {
+            pcs_config,
+            channel,
+            preprocessed_columns: HashMap::new(),
+        }
+    }
+
+    fn verify(&mut self, proof: Proof<MC::H>) -> Result<VerificationReport, VerificationError> {
+        let verification_start = Instant::now();
+
+        if proof.pcs_config != self.pcs_config {
+            return Err(VerificationError::PcsConfigMismatch);
+        }
+
+        // Setup protocol.
+        let channel = &mut self.channel.clone();
+        proof.public_data.mix_into(channel);
+        proof.public_data.public_memory.verify_pages()?;
+        if proof.public_data.commitment() != Some(proof.public_data_commitment) {
+            return Err(VerificationError::InvalidPublicDataCommitment);
+        }
+        proof.public_data_commitment.mix_into(channel);
+
+        let commitment_scheme_verifier = &mut CommitmentSchemeVerifier::<MC>::new(self.pcs_config);
+
+        // Preprocessed trace.
+        info!("preprocessed trace");
+        // The range check tables are sized from their usage: a table larger than its range
+        // would accept out of range values.
+        proof.claim.validate_range_checks()?;
+        let preprocessed_columns = self
+            .preprocessed_columns
+            .entry(PreprocessedColumns::key(&proof.claim))
+            .or_insert_with(|| PreprocessedColumns::new(&proof.claim));
+        // TODO: assert proof.stark_proof.commitments[0] == known_root of preprocessed trace commitment
+        commitment_scheme_verifier.commit(
+            proof.stark_proof.commitments[0],
+            &preprocessed_columns.log_sizes,
+            channel,
+        );
+
+        // Execution traces
+        info!("execution trace");
+        proof.claim.mix_into(channel);
+        commitment_scheme_verifier.commit(
+            proof.stark_proof.commitments[1],
+            &proof.claim.log_sizes()[1],
+            channel,
+        );
+
+        // Proof of work.
+        channel.mix_u64(proof.interaction_pow);
+        if channel.trailing_zeros() < relations::INTERACTION_POW_BITS {
+            return Err(VerificationError::Stwo(StwoVerificationError::ProofOfWork));
+        }
+
+        info!("interaction trace");
+        let relations = Relations::draw(channel);
+
+        // Verify lookup argument.
+        let total_logup_sum = proof
+            .interaction_claim
+            .claimed_sum(&relations, proof.public_data);
+        if total_logup_sum != SecureField::zero() {
+            return Err(VerificationError::InvalidLogupSum);
+        }
+        proof.interaction_claim.mix_into(channel);
+        commitment_scheme_verifier.commit(
+            proof.stark_proof.commitments[2],
+            &proof.claim.log_sizes()[2],
+            channel,
+        );
 
-    info!("interaction trace");
-    let relations = Relations::draw(channel);
+        // Verify stark.
+        info!("verify stark");
+        let mut tree_span_provider =
+            TraceLocationAllocator::new_with_preproccessed_columns(&preprocessed_columns.ids);
+        let components = Components::new(
+            &mut tree_span_provider,
+            &proof.claim,
+            &proof.interaction_claim,
+            &relations,
+        );
+        verify(
+            &components.verifiers(),
+            channel,
+            commitment_scheme_verifier,
+            proof.stark_proof,
+        )?;
 
-    // Verify lookup argument.
-    if proof
-        .interaction_claim
-        .claimed_sum(&relations, proof.public_data)
-        != SecureField::zero()
-    {
-        return Err(VerificationError::InvalidLogupSum);
+        Ok(VerificationReport::new(
+            &proof.claim,
+            &proof.interaction_claim,
+            total_logup_sum,
+            self.pcs_config.fri_config.n_queries,
+            verification_start.elapsed(),
+        ))
     }
-    proof.interaction_claim.mix_into(channel);
-    commitment_scheme_verifier.commit(
-        proof.stark_proof.commitments[2],
-        &proof.claim.log_sizes()[2],
-        channel,
-    );
-
-    // Verify stark.
-    info!("verify stark");
-    let mut tree_span_provider =
-        TraceLocationAllocator::new_with_preproccessed_columns(&preprocessed_trace.ids());
-    let components = Components::new(
-        &mut tree_span_provider,
-        &proof.claim,
-        &proof.interaction_claim,
-        &relations,
-    );
-    verify(
-        &components.verifiers(),
-        channel,
-        commitment_scheme_verifier,
-        proof.stark_proof,
-    )
-    .map_err(VerificationError::from)
 }
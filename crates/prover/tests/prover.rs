@@ -1,19 +1,38 @@
 //! Run these tests with feature `relation-tracker` to see the relation tracker output.
 use std::collections::HashMap;
+use std::path::Path;
 
-use cairo_m_common::InputValue;
+use cairo_m_common::{InputValue, PublicAddressRanges, PublicSegment, SegmentKind, casm};
 use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_prover::adapter::cache::TraceCache;
 use cairo_m_prover::adapter::memory::Memory;
 use cairo_m_prover::adapter::merkle::{TreeType, build_partial_merkle_tree};
 use cairo_m_prover::adapter::{
     HashInput, Instructions, MerkleTrees, ProverInput, import_from_runner_output,
+    import_from_runner_output_ref,
 };
+use cairo_m_prover::calldata::{ProofCalldata, export_proof};
 use cairo_m_prover::debug_tools::assert_constraints::assert_constraints;
+use cairo_m_prover::errors::{ProvingError, ProvingPhase, VerificationError};
+use cairo_m_prover::hasher::{AnyProof, MerkleHasherKind, prove_cairo_m_with_hasher};
+use cairo_m_prover::job::{JobControl, ProvingJob};
 use cairo_m_prover::poseidon2::Poseidon2Hash;
-use cairo_m_prover::prover::prove_cairo_m;
-use cairo_m_prover::verifier::verify_cairo_m;
+use cairo_m_prover::preprocessed::cache::PreprocessedCache;
+use cairo_m_prover::prover::{
+    ProverOptions, prove_cairo_m, prove_cairo_m_with_options, prove_cairo_m_with_report,
+};
+use cairo_m_prover::prover_config::{DEV_FAST, REGULAR_96_BITS};
+use cairo_m_prover::public_data::PublicDataCommitment;
+use cairo_m_prover::verifier::{
+    SecurityPolicy, verify_batch, verify_cairo_m, verify_cairo_m_with_commitment,
+    verify_cairo_m_with_policy,
+};
+use cairo_m_runner::vm::instructions::poseidon2::poseidon2_hash;
+use cairo_m_runner::vm::{EntryFrame, VM};
 use cairo_m_runner::{RunnerOptions, run_cairo_program};
+use cairo_m_test_utils::golden::BLESS_ENV;
 use cairo_m_test_utils::read_fixture;
+use num_traits::{One, Zero};
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
@@ -89,14 +108,12 @@ fn test_prove_and_verify_unchanged_memory() {
             initial_root,
             final_root,
         },
-        public_address_ranges: cairo_m_common::PublicAddressRanges {
-            program: 0..0,
-            input: 0..0,
-            output: 0..0,
-        },
+        public_address_ranges: cairo_m_common::PublicAddressRanges::default(),
+        public_pages: vec![],
         memory,
         instructions: Instructions::default(),
         poseidon2_inputs,
+        poseidon2_sponge_inputs: vec![],
     };
 
     let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
@@ -136,6 +153,599 @@ fn test_prove_and_verify_fibonacci_program() {
     verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
 }
 
+/// Tests that the verification report lists every component of the proof, with claimed sums
+/// cancelling out with the public data.
+#[test]
+fn test_verify_with_report() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+    let claimed_sums = proof.interaction_claim.claimed_sums();
+    let memory_log_size = proof.claim.memory.log_size;
+
+    let report = verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+
+    assert_eq!(report.total_logup_sum, QM31::zero());
+    assert_eq!(report.n_fri_queries, REGULAR_96_BITS.fri_config.n_queries);
+    assert_eq!(report.components.len(), claimed_sums.len());
+    let memory = report
+        .components
+        .iter()
+        .find(|c| c.name == "memory")
+        .unwrap();
+    assert_eq!(memory.log_size, memory_log_size);
+    assert!(!report.unbalanced().is_empty());
+}
+
+/// Tests proving with a user segment made public along with the return values.
+#[test]
+fn test_prove_and_verify_with_public_segments() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    // The frame of the entrypoint call starts with the return fp and pc, after its arguments
+    // and return values.
+    let frame_start = run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default())
+        .unwrap()
+        .public_address_ranges
+        .output
+        .end;
+    let options = RunnerOptions {
+        public_segments: vec![PublicSegment::new(
+            "frame",
+            SegmentKind::Output,
+            frame_start..frame_start + 2,
+        )],
+        ..Default::default()
+    };
+    let runner_output = run_cairo_program(&compiled.program, "fib", &[5.into()], options).unwrap();
+    assert_eq!(runner_output.public_address_ranges.segments.len(), 1);
+
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+    let output = &proof.public_data.public_memory.output;
+    assert_eq!(output.len(), 3);
+    assert!(output.iter().all(Option::is_some));
+
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
+/// Tests that the verifier checks the public data commitment of a proof and that it binds
+/// the outputs of the program.
+#[test]
+fn test_verify_public_data_commitment() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let prove = |n: u32| {
+        let runner_output =
+            run_cairo_program(&compiled.program, "fib", &[n.into()], Default::default()).unwrap();
+        let mut prover_input = import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap();
+        prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap()
+    };
+
+    let proof = prove(5);
+    let commitment = proof.public_data.commitment().unwrap();
+    assert_eq!(proof.public_data_commitment, commitment);
+    assert_ne!(prove(6).public_data_commitment, commitment);
+
+    verify_cairo_m_with_commitment::<Blake2sMerkleChannel>(proof.clone(), None, &commitment)
+        .unwrap();
+
+    let other_commitment = PublicDataCommitment([0; 32]);
+    assert!(matches!(
+        verify_cairo_m_with_commitment::<Blake2sMerkleChannel>(
+            proof.clone(),
+            None,
+            &other_commitment
+        ),
+        Err(VerificationError::PublicDataCommitmentMismatch)
+    ));
+
+    let mut tampered_proof = proof.clone();
+    tampered_proof.public_data_commitment = other_commitment;
+    assert!(matches!(
+        verify_cairo_m::<Blake2sMerkleChannel>(tampered_proof, None),
+        Err(VerificationError::InvalidPublicDataCommitment)
+    ));
+
+    // A malformed proof hiding a program entry is rejected instead of panicking.
+    let mut malformed_proof = proof;
+    malformed_proof.public_data.public_memory.program[0] = None;
+    assert_eq!(malformed_proof.program_id(), None);
+    assert!(matches!(
+        verify_cairo_m::<Blake2sMerkleChannel>(malformed_proof, None),
+        Err(VerificationError::InvalidPublicDataCommitment)
+    ));
+}
+
+/// Tests that the pages of public memory are committed to, and that the verifier recomputes
+/// their commitments.
+#[test]
+fn test_verify_public_memory_pages() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let ranges = runner_output.public_address_ranges.clone();
+    let return_value: M31 = runner_output.return_values[0].clone().try_into().unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+
+    // One page for the argument, one for the return value
+    assert_eq!(proof.public_data.public_memory.pages.len(), 2);
+
+    let expected = |output: M31| {
+        HashMap::from([
+            (M31(ranges.input.start), M31::from(5).into()),
+            (M31(ranges.output.start), output.into()),
+        ])
+    };
+    let public_memory = &proof.public_data.public_memory;
+    public_memory
+        .verify_public_memory(&expected(return_value))
+        .unwrap();
+    assert!(matches!(
+        public_memory.verify_public_memory(&expected(return_value + M31::one())),
+        Err(VerificationError::InvalidPublicPage { start }) if start.0 == ranges.output.start
+    ));
+
+    let mut tampered_proof = proof;
+    tampered_proof.public_data.public_memory.pages[0].commitment[0] += M31::one();
+    assert!(matches!(
+        verify_cairo_m::<Blake2sMerkleChannel>(tampered_proof, None),
+        Err(VerificationError::InvalidPublicPage { start }) if start.0 == ranges.input.start
+    ));
+}
+
+/// Tests that the range check tables of a small program are shrunk to their usage and that
+/// the verifier rejects tables larger than their range.
+#[test]
+fn test_range_check_tables_sized_from_usage() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+
+    assert!(proof.claim.range_check_20.log_size < 20);
+    verify_cairo_m::<Blake2sMerkleChannel>(proof.clone(), None).unwrap();
+
+    let mut tampered_proof = proof;
+    tampered_proof.claim.range_check_8.log_size = 16;
+    assert!(matches!(
+        verify_cairo_m::<Blake2sMerkleChannel>(tampered_proof, None),
+        Err(VerificationError::InvalidRangeCheckLogSize {
+            component: "range_check_8",
+            log_size: 16,
+        })
+    ));
+}
+
+/// Tests that a batch of valid proofs verifies and that an invalid proof is reported by
+/// its index in the batch.
+#[test]
+fn test_verify_batch() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let prove = |n: u32| {
+        let runner_output =
+            run_cairo_program(&compiled.program, "fib", &[n.into()], Default::default()).unwrap();
+        let mut prover_input = import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap();
+        prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap()
+    };
+
+    let mut proofs: Vec<_> = (3..6).map(prove).collect();
+    verify_batch::<Blake2sMerkleChannel>(vec![], None).unwrap();
+    verify_batch::<Blake2sMerkleChannel>(proofs.clone(), None).unwrap();
+
+    proofs[1].public_data_commitment = PublicDataCommitment([0; 32]);
+    assert!(matches!(
+        verify_batch::<Blake2sMerkleChannel>(proofs, None),
+        Err(VerificationError::BatchProof { index: 1, source })
+            if matches!(*source, VerificationError::InvalidPublicDataCommitment)
+    ));
+}
+
+/// Tests that the calldata export of a proof lays out the top-level fields of the proof
+/// contiguously and in order, and matches the golden calldata of the deterministic proof.
+///
+/// Set `CAIRO_M_BLESS=1` to update the golden file after an intended change to the proof or
+/// its encoding.
+#[test]
+fn test_export_proof_calldata() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let options = ProverOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+    let proof = prove_cairo_m_with_options::<Blake2sMerkleChannel>(
+        &mut prover_input,
+        Some(DEV_FAST),
+        &options,
+    )
+    .unwrap();
+
+    let calldata = export_proof(&proof).unwrap();
+    assert_eq!(calldata, export_proof(&proof).unwrap());
+
+    let golden_path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/fibonacci_calldata.json");
+    if std::env::var_os(BLESS_ENV).is_some() {
+        std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        std::fs::write(&golden_path, sonic_rs::to_string_pretty(&calldata).unwrap()).unwrap();
+    }
+    let golden = std::fs::read_to_string(&golden_path).unwrap_or_else(|err| {
+        panic!(
+            "Failed to read '{}': {err} (set {BLESS_ENV}=1 to create it)",
+            golden_path.display()
+        )
+    });
+    let golden: ProofCalldata = sonic_rs::from_str(&golden).unwrap();
+    assert_eq!(
+        calldata.layout, golden.layout,
+        "calldata layout differs from the golden file (set {BLESS_ENV}=1 to update it)"
+    );
+    assert_eq!(
+        calldata.words, golden.words,
+        "calldata words differ from the golden file (set {BLESS_ENV}=1 to update it)"
+    );
+
+    let mut offset = 0;
+    for path in [
+        "claim",
+        "interaction_claim",
+        "public_data",
+        "public_data_commitment",
+        "stark_proof",
+        "interaction_pow",
+        "pcs_config",
+    ] {
+        let entry = calldata.entry(path).unwrap();
+        assert_eq!(entry.offset, offset, "{path} is not contiguous");
+        offset += entry.len;
+    }
+    assert_eq!(offset, calldata.words.len());
+
+    let commitment = proof
+        .public_data_commitment
+        .0
+        .chunks(4)
+        .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        calldata.field("public_data_commitment").unwrap(),
+        commitment
+    );
+    assert_eq!(
+        calldata.field("interaction_pow").unwrap(),
+        [
+            proof.interaction_pow as u32,
+            (proof.interaction_pow >> 32) as u32
+        ]
+    );
+    assert_eq!(calldata.abi_encode().len(), (calldata.words.len() + 2) * 32);
+}
+
+/// Tests that the verifier rejects proofs produced with parameters weaker than its security
+/// policy, or with another configuration than the one it expects.
+#[test]
+fn test_verify_with_security_policy() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, Some(DEV_FAST)).unwrap();
+    assert_eq!(proof.pcs_config, DEV_FAST);
+
+    assert!(matches!(
+        verify_cairo_m_with_policy::<Blake2sMerkleChannel>(
+            proof.clone(),
+            &SecurityPolicy::default()
+        ),
+        Err(VerificationError::InsecureParameters(_))
+    ));
+    assert!(matches!(
+        verify_cairo_m::<Blake2sMerkleChannel>(proof.clone(), None),
+        Err(VerificationError::PcsConfigMismatch)
+    ));
+
+    let report = verify_cairo_m_with_policy::<Blake2sMerkleChannel>(
+        proof,
+        &SecurityPolicy::at_least(&DEV_FAST),
+    )
+    .unwrap();
+    assert_eq!(report.n_fri_queries, DEV_FAST.fri_config.n_queries);
+
+    let policy = SecurityPolicy::default();
+    assert!(policy.check(&REGULAR_96_BITS).is_ok());
+    let mut weak_pow = REGULAR_96_BITS;
+    weak_pow.pow_bits -= 1;
+    assert!(policy.check(&weak_pow).is_err());
+    let mut large_blowup = REGULAR_96_BITS;
+    large_blowup.fri_config.log_blowup_factor += 1;
+    assert!(policy.check(&large_blowup).is_err());
+
+    // The queries of REGULAR_96_BITS with a blowup of 1 don't reach the security of a
+    // larger blowup.
+    let mut strong = REGULAR_96_BITS;
+    strong.fri_config.log_blowup_factor = 2;
+    let mut small_blowup = REGULAR_96_BITS;
+    small_blowup.fri_config.log_blowup_factor = 1;
+    assert!(matches!(
+        SecurityPolicy::at_least(&strong).check(&small_blowup),
+        Err(VerificationError::InsecureParameters(_))
+    ));
+    let bits_only = SecurityPolicy {
+        min_queries: 0,
+        min_pow_bits: 0,
+        min_log_blowup: 0,
+        max_log_blowup: strong.fri_config.log_blowup_factor,
+        min_security_bits: 96 + 80,
+    };
+    assert!(bits_only.check(&strong).is_ok());
+    assert!(matches!(
+        bits_only.check(&small_blowup),
+        Err(VerificationError::InsecureParameters(_))
+    ));
+}
+
+/// Tests that proofs generated with a preprocessed trace cache verify, both when the
+/// cache is populated and when it is reused.
+#[test]
+fn test_prove_and_verify_with_preprocessed_cache() {
+    init_tracing();
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let options = ProverOptions {
+        preprocessed_cache: Some(PreprocessedCache::new(cache_dir.path())),
+        ..Default::default()
+    };
+
+    for _ in 0..2 {
+        let runner_output =
+            run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+        let mut prover_input = import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap();
+        let proof =
+            prove_cairo_m_with_options::<Blake2sMerkleChannel>(&mut prover_input, None, &options)
+                .unwrap();
+
+        verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+    }
+    // The evaluations and the committed tree
+    assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 2);
+}
+
+/// Tests that a prover input reloaded from the trace cache proves the same execution.
+#[test]
+fn test_prove_and_verify_with_trace_cache() {
+    init_tracing();
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache = TraceCache::new(cache_dir.path());
+    let key = TraceCache::key(&compiled.program, "fib", &[5.into()], &[]);
+
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    cache.store(&key, &prover_input).unwrap();
+
+    let mut cached_input = cache.load(&key).unwrap().unwrap();
+    assert_eq!(cached_input, prover_input);
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut cached_input, None).unwrap();
+
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
+/// Tests that the deterministic mode yields byte-identical proofs for identical inputs.
+#[test]
+fn test_deterministic_proofs_are_identical() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let options = ProverOptions {
+        deterministic: true,
+        ..Default::default()
+    };
+    let proofs: Vec<_> = (0..2)
+        .map(|_| {
+            let runner_output =
+                run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default())
+                    .unwrap();
+            let mut prover_input = import_from_runner_output(
+                runner_output.vm.segments.into_iter().next().unwrap(),
+                runner_output.public_address_ranges,
+            )
+            .unwrap();
+            prove_cairo_m_with_options::<Blake2sMerkleChannel>(&mut prover_input, None, &options)
+                .unwrap()
+        })
+        .collect();
+
+    assert_eq!(
+        sonic_rs::to_string(&proofs[0]).unwrap(),
+        sonic_rs::to_string(&proofs[1]).unwrap()
+    );
+    verify_cairo_m::<Blake2sMerkleChannel>(proofs[0].clone(), None).unwrap();
+}
+
+/// Tests that the trace report matches the claim of the generated proof.
+#[test]
+fn test_prove_with_trace_report() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    let (proof, report) = prove_cairo_m_with_report::<Blake2sMerkleChannel>(
+        &mut prover_input,
+        None,
+        &ProverOptions::default(),
+    )
+    .unwrap();
+
+    let log_sizes = proof.claim.log_sizes();
+    let trace_columns: usize = report.components.iter().map(|c| c.trace_columns).sum();
+    let interaction_columns: usize = report
+        .components
+        .iter()
+        .map(|c| c.interaction_columns)
+        .sum();
+    assert_eq!(trace_columns, log_sizes[1].len());
+    assert_eq!(interaction_columns, log_sizes[2].len());
+
+    let memory = report
+        .components
+        .iter()
+        .find(|c| c.name == "memory")
+        .unwrap();
+    assert_eq!(memory.log_size, proof.claim.memory.log_size);
+    assert!(memory.rows_used > 0 && memory.rows_used <= memory.rows());
+
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
+/// Tests that importing a borrowed runner output matches the owned import.
+#[test]
+fn test_import_from_runner_output_ref() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let runner_output =
+        run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+    let borrowed_input = import_from_runner_output_ref(&runner_output)
+        .unwrap()
+        .build_merkle_trees();
+    let owned_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+
+    assert_eq!(borrowed_input, owned_input);
+}
+
 /// Tests proof generation for a Fibonacci(1M) calculation.
 ///
 /// This test validates that the prover can handle larger execution traces
@@ -156,6 +766,7 @@ fn test_prove_and_verify_large_fibonacci_program() {
         &[InputValue::Number(1_000_000)],
         RunnerOptions {
             max_steps: 2_usize.pow(30),
+            ..Default::default()
         },
     )
     .unwrap();
@@ -171,6 +782,41 @@ fn test_prove_and_verify_large_fibonacci_program() {
     verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
 }
 
+/// Tests that the clock updates scheduled by the VM for a small maximum clock difference
+/// are proven: the program inputs and code are accessed long after their previous access.
+#[test]
+fn test_prove_and_verify_with_small_max_clock_delta() {
+    let source = read_fixture("functions/fib_loop.cm");
+    let compiled = compile_cairo(
+        source,
+        "fib_loop.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    let runner_output = run_cairo_program(
+        &compiled.program,
+        "fibonacci_loop",
+        &[InputValue::Number(100)],
+        RunnerOptions {
+            max_clock_delta: 16,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let segment = runner_output.vm.segments.into_iter().next().unwrap();
+    assert!(!segment.clock_updates.is_empty());
+
+    let mut prover_input =
+        import_from_runner_output(segment, runner_output.public_address_ranges).unwrap();
+    assert!(!prover_input.memory.clock_update_data.is_empty());
+
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
 /// Tests proof generation for recursive Fibonacci implementation.
 #[test]
 fn test_prove_and_verify_recursive_fibonacci_program() {
@@ -210,7 +856,10 @@ fn test_hash_continuity_fibonacci() {
     )
     .unwrap();
 
-    let runner_options = RunnerOptions { max_steps: 10 };
+    let runner_options = RunnerOptions {
+        max_steps: 10,
+        ..Default::default()
+    };
 
     let runner_output = run_cairo_program(
         &compiled.program,
@@ -343,6 +992,85 @@ fn test_prove_and_verify_all_opcodes() {
     verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
 }
 
+/// Tests proving a program calling `debug_print`, whose prints are proven as steps advancing
+/// the pc.
+#[test]
+fn test_prove_and_verify_debug_print() {
+    let source = r"
+        fn main(n: felt) -> felt {
+            let doubled = n + n;
+            debug_print(doubled);
+            let count: u32 = 3u32;
+            debug_print(count);
+            debug_print(true);
+            return doubled;
+        }
+    ";
+    let compiled = compile_cairo(
+        source.to_string(),
+        "debug_print.cm".to_string(),
+        CompilerOptions::no_opts(),
+    )
+    .unwrap();
+
+    let runner_output =
+        run_cairo_program(&compiled.program, "main", &[21.into()], Default::default()).unwrap();
+    assert_eq!(runner_output.logs.len(), 3);
+
+    let mut prover_input = import_from_runner_output(
+        runner_output.vm.segments.into_iter().next().unwrap(),
+        runner_output.public_address_ranges,
+    )
+    .unwrap();
+    assert_constraints(&mut prover_input.clone());
+
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
+/// Tests proving the Poseidon2 sponge instructions, whose permutations are proven by the
+/// Poseidon2 component along with the Merkle tree hashes.
+#[test]
+fn test_prove_and_verify_poseidon2_sponge() {
+    // Hashes ten felts: two blocks, the second one zero-padded.
+    // Locals: [values[0..10], padding[10..16], initial state[16..24], len], followed by the
+    // chained states [25..33] and [33..41] and the digest [41..49]
+    let values: Vec<M31> = (1..=10).map(M31::from).collect();
+    let mut locals = values.clone();
+    locals.resize(24, M31::from(0));
+    locals.push(M31::from(10));
+    let frame = EntryFrame {
+        locals,
+        ..Default::default()
+    };
+    let instructions = casm::parse(
+        "
+        poseidon2_absorb_fp_fp 16, 0, 25
+        poseidon2_absorb_fp_fp 25, 8, 33
+        poseidon2_squeeze_fp_fp 33, 24, 41
+        ",
+    )
+    .unwrap();
+
+    let vm = VM::run_instructions(&instructions, &frame, &RunnerOptions::default()).unwrap();
+    let fp = frame.fp(vm.program_length);
+    let digest: Vec<M31> = (41..49)
+        .map(|offset| vm.memory.get_data(fp + M31::from(offset)).unwrap())
+        .collect();
+    assert_eq!(digest, poseidon2_hash(&values));
+
+    let public_address_ranges = PublicAddressRanges::new(vm.program_length.0, 0, 0);
+    let mut prover_input = import_from_runner_output(
+        vm.segments.into_iter().next().unwrap(),
+        public_address_ranges,
+    )
+    .unwrap();
+    assert_constraints(&mut prover_input.clone());
+
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+}
+
 /// Tests constraint satisfaction for all opcode types without full proving.
 ///
 /// This test validates that all opcode constraint systems are satisfied
@@ -484,3 +1212,89 @@ fn test_memory_profile_fibonacci_prover() {
     let _proof: cairo_m_prover::Proof<stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher> =
         prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None).unwrap();
 }
+
+/// Tests that a proof spawned as a job verifies, and that a cancelled proof stops.
+#[test]
+fn test_proving_job() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+    let prover_input = || {
+        let runner_output =
+            run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+        import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap()
+    };
+
+    let job = ProvingJob::<Blake2sMerkleChannel>::spawn(prover_input(), None, Default::default());
+    let proof = job.wait().unwrap();
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+
+    // Polling gives the job back until the proof is over
+    let mut job =
+        ProvingJob::<Blake2sMerkleChannel>::spawn(prover_input(), None, Default::default());
+    let proof = loop {
+        match job.try_result() {
+            Ok(result) => break result.unwrap(),
+            Err(running) => {
+                job = running;
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+        }
+    };
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).unwrap();
+
+    let control = JobControl::default();
+    control.cancel();
+    let options = ProverOptions {
+        control: Some(control.clone()),
+        ..Default::default()
+    };
+    let result =
+        prove_cairo_m_with_options::<Blake2sMerkleChannel>(&mut prover_input(), None, &options);
+    assert!(matches!(
+        result,
+        Err(ProvingError::Cancelled {
+            phase: ProvingPhase::ExecutionTrace
+        })
+    ));
+    assert_eq!(control.phase(), None);
+}
+
+/// Tests that proofs committed with each Merkle hasher verify after a JSON roundtrip.
+#[test]
+fn test_prove_and_verify_with_hasher() {
+    let source = read_fixture("functions/fibonacci.cm");
+    let compiled = compile_cairo(
+        source,
+        "fibonacci.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .unwrap();
+
+    for hasher in MerkleHasherKind::ALL {
+        let runner_output =
+            run_cairo_program(&compiled.program, "fib", &[5.into()], Default::default()).unwrap();
+        let mut prover_input = import_from_runner_output(
+            runner_output.vm.segments.into_iter().next().unwrap(),
+            runner_output.public_address_ranges,
+        )
+        .unwrap();
+
+        let (proof, _) =
+            prove_cairo_m_with_hasher(hasher, &mut prover_input, None, &Default::default())
+                .unwrap();
+        assert_eq!(proof.hasher(), hasher);
+
+        let json = proof.to_json().unwrap();
+        let proof = AnyProof::from_json(hasher, &json).unwrap();
+        proof.verify(None).unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+//! Soundness regression tests.
+//!
+//! Each test corrupts the witness of a valid execution and checks that it is rejected, either
+//! because the lookups are unbalanced or because a constraint of the expected component fails.
+//! A test starting to pass a corrupted witness means that a constraint or a lookup was lost.
+use cairo_m_common::instruction::{RET, STORE_ADD_FP_FP};
+use cairo_m_common::{PublicAddressRanges, casm};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_prover::adapter::{
+    PartialProverInput, ProverInput, import_from_runner_output, import_from_runner_output_ref,
+};
+use cairo_m_prover::debug_tools::assert_constraints::check_witness;
+use cairo_m_prover::errors::WitnessError;
+use cairo_m_runner::vm::{EntryFrame, VM};
+use cairo_m_runner::{RunnerOptions, run_cairo_program};
+use cairo_m_test_utils::read_fixture;
+use num_traits::One;
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::QM31;
+
+/// Runs a fixture program and imports its execution, without building the Merkle trees.
+fn partial_input(
+    fixture: &str,
+    entrypoint: &str,
+    n: u32,
+    options: RunnerOptions,
+) -> PartialProverInput {
+    let source = read_fixture(fixture);
+    let compiled = compile_cairo(source, fixture.to_string(), CompilerOptions::default()).unwrap();
+    let runner_output =
+        run_cairo_program(&compiled.program, entrypoint, &[n.into()], options).unwrap();
+    import_from_runner_output_ref(&runner_output).unwrap()
+}
+
+fn fibonacci_input() -> PartialProverInput {
+    partial_input("functions/fibonacci.cm", "fib", 5, RunnerOptions::default())
+}
+
+/// Hashes one block of eight felts with the Poseidon2 sponge instructions.
+fn sponge_input() -> ProverInput {
+    let mut locals: Vec<M31> = (1..=8).map(M31::from).collect();
+    locals.resize(16, M31::from(0));
+    locals.push(M31::from(8));
+    let frame = EntryFrame {
+        locals,
+        ..Default::default()
+    };
+    let instructions = casm::parse(
+        "
+        poseidon2_absorb_fp_fp 8, 0, 17
+        poseidon2_squeeze_fp_fp 17, 16, 25
+        ",
+    )
+    .unwrap();
+    let vm = VM::run_instructions(&instructions, &frame, &RunnerOptions::default()).unwrap();
+    let public_address_ranges = PublicAddressRanges::new(vm.program_length.0, 0, 0);
+    import_from_runner_output(
+        vm.segments.into_iter().next().unwrap(),
+        public_address_ranges,
+    )
+    .unwrap()
+}
+
+/// Returns the index in the data accesses of the `n`-th access of the first bundle of an opcode.
+fn access_index(input: &PartialProverInput, opcode: u32, n: usize) -> usize {
+    let bundle = &input.instructions.states_by_opcodes[&opcode][0];
+    bundle.access_span.start as usize + n
+}
+
+/// Adds one to the value written by a data access, and to the values read at the same address
+/// until it is overwritten, so that the memory lookups stay balanced.
+///
+/// The final memory is updated as well: the Merkle trees must be built afterwards.
+fn corrupt_write(input: &mut PartialProverInput, index: usize) {
+    let data_accesses = &mut input.instructions.data_accesses;
+    let address = data_accesses[index].address;
+    data_accesses[index].value += M31::one();
+
+    for access in data_accesses[index + 1..]
+        .iter_mut()
+        .filter(|access| access.address == address)
+    {
+        let is_read = access.value == access.prev_value;
+        access.prev_value += M31::one();
+        if !is_read {
+            return;
+        }
+        access.value += M31::one();
+    }
+    let (value, ..) = input.memory.final_memory.get_mut(&address).unwrap();
+    *value += QM31::one();
+}
+
+#[test]
+fn test_valid_witnesses_are_accepted() {
+    check_witness(&mut fibonacci_input().build_merkle_trees()).unwrap();
+}
+
+/// The result of an addition is checked by the StoreFpFp component, even when the memory
+/// is consistent with the wrong result.
+#[test]
+fn test_rejects_wrong_store_result() {
+    let mut input = fibonacci_input();
+    let dst_index = access_index(&input, STORE_ADD_FP_FP, 2);
+    corrupt_write(&mut input, dst_index);
+
+    match check_witness(&mut input.build_merkle_trees()) {
+        Err(WitnessError::ConstraintsNotSatisfied { violation }) => {
+            assert_eq!(violation.component, "store_fp_fp");
+        }
+        result => panic!("Expected a constraint violation, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_rejects_flipped_memory_value() {
+    let mut input = fibonacci_input().build_merkle_trees();
+    input.instructions.data_accesses[0].value += M31::one();
+
+    assert!(matches!(
+        check_witness(&mut input),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
+
+#[test]
+fn test_rejects_altered_return_pc() {
+    let mut input = fibonacci_input();
+    // The return pc is the first access of a ret, at fp - 1.
+    let return_pc_index = access_index(&input, RET, 0);
+    input.instructions.data_accesses[return_pc_index].value += M31::one();
+
+    assert!(matches!(
+        check_witness(&mut input.build_merkle_trees()),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
+
+#[test]
+fn test_rejects_skipped_instruction() {
+    let mut input = fibonacci_input().build_merkle_trees();
+    input
+        .instructions
+        .states_by_opcodes
+        .get_mut(&RET)
+        .unwrap()
+        .remove(0);
+
+    assert!(matches!(
+        check_witness(&mut input),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
+
+#[test]
+fn test_rejects_skipped_clock_update() {
+    let mut input = partial_input(
+        "functions/fib_loop.cm",
+        "fibonacci_loop",
+        100,
+        RunnerOptions {
+            max_clock_delta: 16,
+            ..Default::default()
+        },
+    )
+    .build_merkle_trees();
+    assert!(!input.memory.clock_update_data.is_empty());
+    input.memory.clock_update_data.remove(0);
+
+    assert!(matches!(
+        check_witness(&mut input),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
+
+#[test]
+fn test_rejects_altered_merkle_hash() {
+    let mut input = fibonacci_input().build_merkle_trees();
+    input.poseidon2_inputs[0][0] += M31::one();
+
+    assert!(matches!(
+        check_witness(&mut input),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
+
+#[test]
+fn test_rejects_altered_poseidon2_sponge() {
+    let mut input = sponge_input();
+    // The squeeze output is proven as the permutation of the absorb input.
+    assert_eq!(input.poseidon2_sponge_inputs.len(), 2);
+    input.poseidon2_sponge_inputs[1] = input.poseidon2_sponge_inputs[0];
+
+    assert!(matches!(
+        check_witness(&mut input),
+        Err(WitnessError::InvalidLogupSum)
+    ));
+}
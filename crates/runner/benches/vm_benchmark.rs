@@ -26,6 +26,7 @@ fn fibonacci_1m_benchmark(c: &mut Criterion) {
         &[InputValue::Number(N_ITERATIONS as i64)],
         RunnerOptions {
             max_steps: 2_usize.pow(30),
+            ..Default::default()
         },
     )
     .expect("Execution failed");
@@ -44,6 +45,7 @@ fn fibonacci_1m_benchmark(c: &mut Criterion) {
                 &[InputValue::Number(N_ITERATIONS as i64)],
                 RunnerOptions {
                     max_steps: 2_usize.pow(30),
+                    ..Default::default()
                 },
             )
             .expect("Execution failed");
@@ -0,0 +1,18 @@
+//! Runs generated instruction sequences in the VM.
+//!
+//! Generated programs only read initialized, well-typed operands and only jump forward:
+//! execution must always succeed and reach the end of the program.
+
+#![no_main]
+
+use cairo_m_runner::RunnerOptions;
+use cairo_m_runner::fuzz::FuzzProgram;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let program = FuzzProgram::generate(data);
+    let output = program
+        .run(&RunnerOptions::default())
+        .expect("generated program should execute");
+    assert_eq!(output.vm.state.pc, output.vm.final_pc);
+});
@@ -0,0 +1,30 @@
+//! Proves and verifies the execution of generated instruction sequences.
+//!
+//! The traces are honest: the verifier must accept every proof.
+
+#![no_main]
+
+use cairo_m_prover::adapter::import_from_runner_output;
+use cairo_m_prover::prover::prove_cairo_m;
+use cairo_m_prover::verifier::verify_cairo_m;
+use cairo_m_runner::RunnerOptions;
+use cairo_m_runner::fuzz::FuzzProgram;
+use libfuzzer_sys::fuzz_target;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+fuzz_target!(|data: &[u8]| {
+    let program = FuzzProgram::generate(data);
+    if program.instructions.is_empty() {
+        return;
+    }
+    let mut output = program
+        .run(&RunnerOptions::default())
+        .expect("generated program should execute");
+
+    let mut prover_input =
+        import_from_runner_output(output.vm.segments.remove(0), output.public_address_ranges)
+            .expect("honest trace should import");
+    let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None)
+        .expect("honest trace should prove");
+    verify_cairo_m::<Blake2sMerkleChannel>(proof, None).expect("honest proof should verify");
+});
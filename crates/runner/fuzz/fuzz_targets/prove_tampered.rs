@@ -0,0 +1,63 @@
+//! Tampers with the execution trace of generated instruction sequences before proving.
+//!
+//! The last bytes of the input select a register or a memory access of the trace and a
+//! non-zero delta to add to it. The tampered trace no longer matches an execution of the
+//! program: import, proving or verification must fail.
+
+#![no_main]
+
+use cairo_m_prover::adapter::import_from_runner_output;
+use cairo_m_prover::prover::prove_cairo_m;
+use cairo_m_prover::verifier::verify_cairo_m;
+use cairo_m_runner::RunnerOptions;
+use cairo_m_runner::fuzz::{ByteSource, FuzzProgram};
+use libfuzzer_sys::fuzz_target;
+use stwo_prover::core::fields::m31::{M31, P};
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
+
+/// Number of trailing input bytes describing the mutation.
+const MUTATION_SIZE: usize = 7;
+
+fuzz_target!(|data: &[u8]| {
+    let (program_data, mutation) = data.split_at(data.len().saturating_sub(MUTATION_SIZE));
+    let program = FuzzProgram::generate(program_data);
+    if program.instructions.is_empty() {
+        return;
+    }
+    let mut output = program
+        .run(&RunnerOptions::default())
+        .expect("generated program should execute");
+    let mut segment = output.vm.segments.remove(0);
+
+    let mut mutation = ByteSource::new(mutation);
+    let kind = mutation.u8() % 3;
+    let index = mutation.u16() as usize;
+    let delta = M31::from(mutation.u32() % (P - 1) + 1);
+    match kind {
+        0 => {
+            let state = &mut segment.trace[index % segment.trace.len()];
+            state.pc += delta;
+        }
+        1 => {
+            let state = &mut segment.trace[index % segment.trace.len()];
+            state.fp += delta;
+        }
+        _ => {
+            let memory_trace = segment.memory_trace.get_mut();
+            let len = memory_trace.len();
+            memory_trace[index % len].value.0.0 += delta;
+        }
+    }
+
+    let Ok(mut prover_input) = import_from_runner_output(segment, output.public_address_ranges)
+    else {
+        return;
+    };
+    let Ok(proof) = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, None) else {
+        return;
+    };
+    assert!(
+        verify_cairo_m::<Blake2sMerkleChannel>(proof, None).is_err(),
+        "tampered trace was accepted"
+    );
+});
@@ -0,0 +1,616 @@
+//! # Typed Rust bindings
+//!
+//! Generation of a Rust module from the ABI of a compiled [`Program`], so that host code
+//! calls Cairo-M entrypoints with Rust values instead of building [`InputValue`]s by hand.
+//!
+//! The generated module holds one struct per Cairo-M struct of the ABI and one function per
+//! entrypoint, which encodes its arguments, calls [`run_cairo_program`] and decodes the
+//! return values. It is meant to be generated from a `build.rs`:
+//!
+//! ```ignore
+//! let program: Program = sonic_rs::from_str(&std::fs::read_to_string("program.json")?)?;
+//! let out_dir = std::env::var("OUT_DIR")?;
+//! cairo_m_runner::bindings::generate_bindings(&program, format!("{out_dir}/bindings.rs"))?;
+//! ```
+//!
+//! and included with `include!(concat!(env!("OUT_DIR"), "/bindings.rs"));`.
+//!
+//! Cairo-M types map to Rust types as follows:
+//!
+//! - `felt` -> [`M31`], `bool` -> `bool`, `u32` -> `u32`, `()` -> `()`
+//! - tuples -> tuples, fixed-size arrays -> arrays, structs -> generated structs
+//!
+//! Generated structs are named after the Cairo-M struct without its module path. Structs
+//! sharing a name in different modules are qualified with their module path instead, so
+//! `a::Point` and `b::Point` become `APoint` and `BPoint`.
+//!
+//! Entrypoints whose signature contains a pointer have no typed wrapper and are listed in a
+//! comment of the generated module.
+//!
+//! [`run_cairo_program`]: crate::run_cairo_program
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+use cairo_m_common::program::{AbiSlot, AbiType, EntrypointInfo};
+use cairo_m_common::{AbiCodecError, CairoMValue, InputValue, Program};
+pub use stwo_prover::core::fields::m31::M31;
+
+/// Rust value that can be passed as an argument of a Cairo-M entrypoint
+pub trait IntoInputValue {
+    fn into_input_value(self) -> InputValue;
+}
+
+/// Rust value that can be decoded from a return value of a Cairo-M entrypoint
+pub trait FromCairoMValue: Sized {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError>;
+}
+
+fn mismatch(expected: &str, value: &CairoMValue) -> AbiCodecError {
+    AbiCodecError::TypeMismatch(format!("expected {}, got {:?}", expected, value))
+}
+
+impl IntoInputValue for M31 {
+    fn into_input_value(self) -> InputValue {
+        self.into()
+    }
+}
+
+impl FromCairoMValue for M31 {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+        match value {
+            CairoMValue::Felt(felt) => Ok(felt),
+            other => Err(mismatch("felt", &other)),
+        }
+    }
+}
+
+impl IntoInputValue for bool {
+    fn into_input_value(self) -> InputValue {
+        InputValue::Bool(self)
+    }
+}
+
+impl FromCairoMValue for bool {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+        match value {
+            CairoMValue::Bool(b) => Ok(b),
+            other => Err(mismatch("bool", &other)),
+        }
+    }
+}
+
+impl IntoInputValue for u32 {
+    fn into_input_value(self) -> InputValue {
+        self.into()
+    }
+}
+
+impl FromCairoMValue for u32 {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+        match value {
+            CairoMValue::U32(u) => Ok(u),
+            other => Err(mismatch("u32", &other)),
+        }
+    }
+}
+
+impl IntoInputValue for () {
+    fn into_input_value(self) -> InputValue {
+        InputValue::Unit
+    }
+}
+
+impl FromCairoMValue for () {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+        match value {
+            CairoMValue::Unit => Ok(()),
+            CairoMValue::Tuple(values) if values.is_empty() => Ok(()),
+            other => Err(mismatch("unit", &other)),
+        }
+    }
+}
+
+impl<T: IntoInputValue, const N: usize> IntoInputValue for [T; N] {
+    fn into_input_value(self) -> InputValue {
+        InputValue::List(self.into_iter().map(T::into_input_value).collect())
+    }
+}
+
+impl<T: FromCairoMValue, const N: usize> FromCairoMValue for [T; N] {
+    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+        match value {
+            CairoMValue::Array(values) if values.len() == N => {
+                let values = values
+                    .into_iter()
+                    .map(T::from_cairo_m_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(values
+                    .try_into()
+                    .unwrap_or_else(|_| unreachable!("length checked above")))
+            }
+            other => Err(mismatch(&format!("array of {} elements", N), &other)),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($len:literal: $($name:ident),+) => {
+        impl<$($name: IntoInputValue),+> IntoInputValue for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn into_input_value(self) -> InputValue {
+                let ($($name,)+) = self;
+                InputValue::List(vec![$($name.into_input_value()),+])
+            }
+        }
+
+        impl<$($name: FromCairoMValue),+> FromCairoMValue for ($($name,)+) {
+            fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {
+                match value {
+                    CairoMValue::Tuple(values) if values.len() == $len => {
+                        let mut values = values.into_iter();
+                        Ok(($($name::from_cairo_m_value(values.next().unwrap())?,)+))
+                    }
+                    other => Err(mismatch(concat!("tuple of ", $len, " elements"), &other)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(1: A);
+impl_tuple!(2: A, B);
+impl_tuple!(3: A, B, C);
+impl_tuple!(4: A, B, C, D);
+impl_tuple!(5: A, B, C, D, E);
+impl_tuple!(6: A, B, C, D, E, F);
+impl_tuple!(7: A, B, C, D, E, F, G);
+impl_tuple!(8: A, B, C, D, E, F, G, H);
+
+/// Decodes the return values of an entrypoint
+///
+/// A single return value is decoded as is, several return values as a tuple.
+pub fn decode_returns<T: FromCairoMValue>(
+    mut values: Vec<CairoMValue>,
+) -> Result<T, AbiCodecError> {
+    if values.len() == 1 {
+        T::from_cairo_m_value(values.remove(0))
+    } else {
+        T::from_cairo_m_value(CairoMValue::Tuple(values))
+    }
+}
+
+/// Generates the Rust bindings of a program and writes them to a file
+///
+/// ## Arguments
+/// * `program` - The compiled Cairo-M program
+/// * `path` - Path of the generated Rust module
+pub fn generate_bindings<P: AsRef<Path>>(program: &Program, path: P) -> std::io::Result<()> {
+    std::fs::write(path, bindings_source(program))
+}
+
+/// Generates the source of the Rust bindings of a program
+///
+/// ## Arguments
+/// * `program` - The compiled Cairo-M program
+///
+/// ## Returns
+/// The source of a Rust module with the structs and entrypoint wrappers of the program
+pub fn bindings_source(program: &Program) -> String {
+    let entrypoints = program.entrypoints.iter().collect::<BTreeMap<_, _>>();
+    let (supported, unsupported): (Vec<_>, Vec<_>) = entrypoints
+        .into_iter()
+        .partition(|(_, info)| !signature_slots(info).any(|slot| contains_pointer(&slot.ty)));
+
+    let mut structs = BTreeMap::new();
+    for slot in supported.iter().flat_map(|(_, info)| signature_slots(info)) {
+        collect_structs(&slot.ty, &mut structs);
+    }
+
+    let names = struct_names(structs.keys().copied());
+
+    let mut out = String::new();
+    out.push_str("// @generated by cairo_m_runner::bindings. Do not edit.\n\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use cairo_m_common::{AbiCodecError, CairoMValue, InputValue, Program};\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str(
+        "use cairo_m_runner::bindings::{decode_returns, FromCairoMValue, IntoInputValue, M31};\n",
+    );
+    out.push_str("use cairo_m_runner::{run_cairo_program, RunnerOptions};\n");
+
+    if !unsupported.is_empty() {
+        out.push_str("\n// Entrypoints taking or returning pointers, without bindings:\n");
+        for (name, _) in &unsupported {
+            writeln!(out, "// - {}", name).unwrap();
+        }
+    }
+
+    for (name, fields) in &structs {
+        write_struct(&mut out, name, fields, &names);
+    }
+    for (name, info) in &supported {
+        write_entrypoint(&mut out, name, info, &names);
+    }
+    out
+}
+
+fn signature_slots(info: &EntrypointInfo) -> impl Iterator<Item = &AbiSlot> {
+    info.params.iter().chain(info.returns.iter())
+}
+
+fn contains_pointer(ty: &AbiType) -> bool {
+    match ty {
+        AbiType::Pointer { .. } => true,
+        AbiType::Tuple(types) => types.iter().any(contains_pointer),
+        AbiType::Struct { fields, .. } => fields.iter().any(|(_, ty)| contains_pointer(ty)),
+        AbiType::FixedSizeArray { element, .. } => contains_pointer(element),
+        AbiType::Felt | AbiType::Bool | AbiType::U32 | AbiType::Unit => false,
+    }
+}
+
+/// Collects the structs of a type, nested ones included, by name
+fn collect_structs<'a>(ty: &'a AbiType, structs: &mut BTreeMap<&'a str, &'a [(String, AbiType)]>) {
+    match ty {
+        AbiType::Struct { name, fields } => {
+            structs.insert(name, fields);
+            for (_, field_ty) in fields {
+                collect_structs(field_ty, structs);
+            }
+        }
+        AbiType::Tuple(types) => types.iter().for_each(|ty| collect_structs(ty, structs)),
+        AbiType::FixedSizeArray { element, .. } | AbiType::Pointer { element, .. } => {
+            collect_structs(element, structs)
+        }
+        AbiType::Felt | AbiType::Bool | AbiType::U32 | AbiType::Unit => {}
+    }
+}
+
+/// Rust names of the generated structs, by Cairo-M struct name
+type StructNames<'a> = BTreeMap<&'a str, String>;
+
+/// Assigns a distinct Rust name to each Cairo-M struct
+///
+/// A struct is named without its module path, unless another struct has the same name in a
+/// different module, in which case both are qualified with their module path.
+fn struct_names<'a>(names: impl Iterator<Item = &'a str>) -> StructNames<'a> {
+    let names = names.collect::<Vec<_>>();
+    let short_name = |name: &str| sanitize_ident(name.rsplit("::").next().unwrap_or(name));
+    let mut counts = BTreeMap::<String, usize>::new();
+    for name in &names {
+        *counts.entry(short_name(name)).or_default() += 1;
+    }
+
+    let mut assigned = BTreeMap::new();
+    let mut taken = BTreeSet::new();
+    for name in names {
+        let short = short_name(name);
+        let mut rust_name = if counts[&short] == 1 {
+            short
+        } else {
+            qualified_struct_name(name)
+        };
+        // Qualified names may still collide, e.g. `a_b::Point` and `a::b::Point`.
+        if taken.contains(&rust_name) {
+            rust_name = (2..)
+                .map(|i| format!("{}{}", rust_name, i))
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap();
+        }
+        taken.insert(rust_name.clone());
+        assigned.insert(name, rust_name);
+    }
+    assigned
+}
+
+/// Rust name of a Cairo-M struct, prefixed with its module path in upper camel case
+fn qualified_struct_name(name: &str) -> String {
+    let mut segments = name.split("::").collect::<Vec<_>>();
+    let struct_name = segments.pop().unwrap_or(name);
+    let mut qualified = segments
+        .iter()
+        .flat_map(|segment| segment.split('_'))
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    qualified.push_str(struct_name);
+    sanitize_ident(&qualified)
+}
+
+/// Rust type of a pointer-free ABI type
+fn rust_type(ty: &AbiType, names: &StructNames<'_>) -> String {
+    match ty {
+        AbiType::Felt => "M31".to_string(),
+        AbiType::Bool => "bool".to_string(),
+        AbiType::U32 => "u32".to_string(),
+        AbiType::Unit => "()".to_string(),
+        AbiType::Tuple(types) => tuple_type(types.iter().map(|ty| rust_type(ty, names)).collect()),
+        AbiType::Struct { name, .. } => names[name.as_str()].clone(),
+        AbiType::FixedSizeArray { element, size } => {
+            format!("[{}; {}]", rust_type(element, names), size)
+        }
+        AbiType::Pointer { .. } => unreachable!("entrypoints with pointers have no bindings"),
+    }
+}
+
+fn tuple_type(types: Vec<String>) -> String {
+    match types.len() {
+        1 => format!("({},)", types[0]),
+        _ => format!("({})", types.join(", ")),
+    }
+}
+
+/// Turns a Cairo-M name into a valid Rust identifier
+fn sanitize_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while", "yield",
+    ];
+    let ident = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn write_struct(
+    out: &mut String,
+    name: &str,
+    fields: &[(String, AbiType)],
+    names: &StructNames<'_>,
+) {
+    let rust_name = &names[name];
+    let field_names = fields
+        .iter()
+        .map(|(field, _)| sanitize_ident(field))
+        .collect::<Vec<_>>();
+
+    writeln!(out, "\n/// Cairo-M struct `{}`", name).unwrap();
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub struct {} {{", rust_name).unwrap();
+    for (field_name, (_, ty)) in field_names.iter().zip(fields) {
+        writeln!(out, "    pub {}: {},", field_name, rust_type(ty, names)).unwrap();
+    }
+    out.push_str("}\n");
+
+    writeln!(out, "\nimpl IntoInputValue for {} {{", rust_name).unwrap();
+    out.push_str("    fn into_input_value(self) -> InputValue {\n");
+    out.push_str("        InputValue::Struct(vec![\n");
+    for field_name in &field_names {
+        writeln!(out, "            self.{}.into_input_value(),", field_name).unwrap();
+    }
+    out.push_str("        ])\n    }\n}\n");
+
+    writeln!(out, "\nimpl FromCairoMValue for {} {{", rust_name).unwrap();
+    out.push_str(
+        "    fn from_cairo_m_value(value: CairoMValue) -> Result<Self, AbiCodecError> {\n",
+    );
+    out.push_str("        match value {\n");
+    writeln!(
+        out,
+        "            CairoMValue::Struct(fields) if fields.len() == {} => {{",
+        fields.len()
+    )
+    .unwrap();
+    out.push_str("                #[allow(unused_mut, unused_variables)]\n");
+    out.push_str("                let mut fields = fields.into_iter().map(|(_, value)| value);\n");
+    out.push_str("                Ok(Self {\n");
+    for field_name in &field_names {
+        writeln!(
+            out,
+            "                    {}: FromCairoMValue::from_cairo_m_value(fields.next().unwrap())?,",
+            field_name
+        )
+        .unwrap();
+    }
+    out.push_str("                })\n            }\n");
+    writeln!(
+        out,
+        "            other => Err(AbiCodecError::TypeMismatch(format!(\"expected struct {}, got {{:?}}\", other))),",
+        name
+    )
+    .unwrap();
+    out.push_str("        }\n    }\n}\n");
+}
+
+fn write_entrypoint(out: &mut String, name: &str, info: &EntrypointInfo, names: &StructNames<'_>) {
+    let params = info
+        .params
+        .iter()
+        .enumerate()
+        .map(|(i, slot)| {
+            let param = if slot.name.is_empty() {
+                format!("arg{}", i)
+            } else {
+                sanitize_ident(&slot.name)
+            };
+            (param, rust_type(&slot.ty, names))
+        })
+        .collect::<Vec<_>>();
+    let return_type = match info.returns.as_slice() {
+        [slot] => rust_type(&slot.ty, names),
+        slots => tuple_type(
+            slots
+                .iter()
+                .map(|slot| rust_type(&slot.ty, names))
+                .collect(),
+        ),
+    };
+
+    writeln!(out, "\n/// Runs the `{}` entrypoint of the program", name).unwrap();
+    write!(
+        out,
+        "pub fn {}(program: &Program, options: RunnerOptions",
+        sanitize_ident(name)
+    )
+    .unwrap();
+    for (param, ty) in &params {
+        write!(out, ", {}: {}", param, ty).unwrap();
+    }
+    writeln!(out, ") -> cairo_m_runner::Result<{}> {{", return_type).unwrap();
+    write!(out, "    let args: [InputValue; {}] = [", params.len()).unwrap();
+    let args = params
+        .iter()
+        .map(|(param, _)| format!("{}.into_input_value()", param))
+        .collect::<Vec<_>>();
+    out.push_str(&args.join(", "));
+    out.push_str("];\n");
+    writeln!(
+        out,
+        "    let output = run_cairo_program(program, {:?}, &args, options)?;",
+        name
+    )
+    .unwrap();
+    out.push_str("    Ok(decode_returns(output.return_values)?)\n}\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn slot(name: &str, ty: AbiType) -> AbiSlot {
+        AbiSlot {
+            name: name.to_string(),
+            ty,
+            default: None,
+        }
+    }
+
+    fn point() -> AbiType {
+        AbiType::Struct {
+            name: "geometry::Point".to_string(),
+            fields: vec![
+                ("x".to_string(), AbiType::Felt),
+                ("y".to_string(), AbiType::U32),
+            ],
+        }
+    }
+
+    fn program() -> Program {
+        let entrypoints = HashMap::from([
+            (
+                "translate".to_string(),
+                EntrypointInfo {
+                    pc: 0,
+                    params: vec![slot("p", point()), slot("type", AbiType::Bool)],
+                    returns: vec![slot("", point()), slot("", AbiType::Felt)],
+                },
+            ),
+            (
+                "sum".to_string(),
+                EntrypointInfo {
+                    pc: 4,
+                    params: vec![slot(
+                        "values",
+                        AbiType::Pointer {
+                            element: Box::new(AbiType::Felt),
+                            len: None,
+                        },
+                    )],
+                    returns: vec![slot("", AbiType::Felt)],
+                },
+            ),
+            (
+                "noop".to_string(),
+                EntrypointInfo {
+                    pc: 8,
+                    params: vec![],
+                    returns: vec![],
+                },
+            ),
+        ]);
+        let mut program = Program::from(vec![]);
+        program.entrypoints = entrypoints;
+        program
+    }
+
+    #[test]
+    fn test_bindings_source() {
+        let source = bindings_source(&program());
+
+        assert!(source.contains("pub struct Point {\n    pub x: M31,\n    pub y: u32,\n}"));
+        assert!(source.contains(
+            "pub fn translate(program: &Program, options: RunnerOptions, p: Point, r#type: bool) \
+             -> cairo_m_runner::Result<(Point, M31)> {"
+        ));
+        assert!(source.contains(
+            "pub fn noop(program: &Program, options: RunnerOptions) -> cairo_m_runner::Result<()> {"
+        ));
+        assert!(source.contains("// - sum\n"));
+        assert!(!source.contains("pub fn sum("));
+    }
+
+    #[test]
+    fn test_struct_names_are_distinct() {
+        let names = struct_names(
+            [
+                "geometry::Point",
+                "physics::Point",
+                "my_mod::Vector",
+                "geometry::Line",
+            ]
+            .into_iter(),
+        );
+        assert_eq!(names["geometry::Point"], "GeometryPoint");
+        assert_eq!(names["physics::Point"], "PhysicsPoint");
+        assert_eq!(names["my_mod::Vector"], "Vector");
+        assert_eq!(names["geometry::Line"], "Line");
+
+        let names = struct_names(["a_b::Point", "a::b::Point"].into_iter());
+        assert_eq!(names["a_b::Point"], "ABPoint");
+        assert_eq!(names["a::b::Point"], "ABPoint2");
+    }
+
+    #[test]
+    fn test_decode_returns() {
+        let felt = M31::from(7);
+        assert_eq!(
+            decode_returns::<M31>(vec![CairoMValue::Felt(felt)]).unwrap(),
+            felt
+        );
+        assert_eq!(
+            decode_returns::<(u32, [bool; 2])>(vec![
+                CairoMValue::U32(3),
+                CairoMValue::Array(vec![CairoMValue::Bool(true), CairoMValue::Bool(false)]),
+            ])
+            .unwrap(),
+            (3, [true, false])
+        );
+        decode_returns::<()>(vec![]).unwrap();
+        assert!(decode_returns::<u32>(vec![CairoMValue::Felt(felt)]).is_err());
+    }
+
+    #[test]
+    fn test_into_input_value() {
+        assert_eq!(
+            (M31::from(1), [2u32, 3], true).into_input_value(),
+            InputValue::List(vec![
+                InputValue::Number(1),
+                InputValue::List(vec![InputValue::Number(2), InputValue::Number(3)]),
+                InputValue::Bool(true),
+            ])
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! # Fuzzing support
+//!
+//! Generation of random but valid instruction sequences from raw fuzzer input, used by the
+//! cargo-fuzz targets in `crates/runner/fuzz` to check that the VM executes them and that the
+//! prover accepts honest traces and rejects tampered ones.
+//!
+//! A generated program runs in a single entry frame whose locals are preloaded with random
+//! values, split into two regions so that every instruction reads well-formed operands:
+//!
+//! - `[fp + 0]..[fp + NUM_FELT_SLOTS]`: felt slots, read and written by felt instructions.
+//! - the following `NUM_U32_SLOTS` pairs of cells: u32 slots, holding 16-bit limbs, only
+//!   written by u32 instructions.
+//!
+//! Instructions that can fail on valid operands (felt division, u32 division by a register,
+//! asserts) or that need pointers (double derefs, calls) are not generated. Jumps only go
+//! forward, so every program terminates.
+
+use cairo_m_common::{Instruction, PublicAddressRanges};
+use stwo_prover::core::fields::m31::{M31, P};
+
+use crate::vm::{EntryFrame, VM, VmError};
+use crate::{RunnerOptions, RunnerOutput};
+
+/// Number of felt slots of the entry frame.
+pub const NUM_FELT_SLOTS: u32 = 8;
+/// Number of u32 slots of the entry frame, each spanning two cells.
+pub const NUM_U32_SLOTS: u32 = 4;
+/// Maximum number of generated instructions.
+pub const MAX_INSTRUCTIONS: usize = 64;
+
+/// Reads values from fuzzer input, yielding zeros once the input is exhausted.
+#[derive(Debug, Clone)]
+pub struct ByteSource<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ByteSource<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn u8(&mut self) -> u8 {
+        match self.data.split_first() {
+            Some((&byte, rest)) => {
+                self.data = rest;
+                byte
+            }
+            None => 0,
+        }
+    }
+
+    pub fn u16(&mut self) -> u16 {
+        u16::from_le_bytes([self.u8(), self.u8()])
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes([self.u8(), self.u8(), self.u8(), self.u8()])
+    }
+
+    /// Returns a field element, reduced modulo P.
+    pub fn felt(&mut self) -> M31 {
+        M31::from(self.u32() % P)
+    }
+
+    /// Returns the offset of a felt slot.
+    fn felt_slot(&mut self) -> M31 {
+        M31::from(self.u8() as u32 % NUM_FELT_SLOTS)
+    }
+
+    /// Returns the offset of the low limb of a u32 slot.
+    fn u32_slot(&mut self) -> M31 {
+        M31::from(NUM_FELT_SLOTS + 2 * (self.u8() as u32 % NUM_U32_SLOTS))
+    }
+
+    /// Returns the limbs of a u32 immediate.
+    fn u32_imm(&mut self) -> (M31, M31) {
+        (M31::from(self.u16() as u32), M31::from(self.u16() as u32))
+    }
+}
+
+/// A generated instruction, with jumps expressed as a number of skipped instructions.
+enum Op {
+    Instruction(Instruction),
+    Jmp { skip: usize },
+    Jnz { cond_off: M31, skip: usize },
+}
+
+impl Op {
+    fn size_in_qm31s(&self) -> u32 {
+        match self {
+            Self::Instruction(instruction) => instruction.size_in_qm31s(),
+            Self::Jmp { .. } => Instruction::JmpRelImm { offset: M31(0) }.size_in_qm31s(),
+            Self::Jnz { .. } => Instruction::JnzFpImm {
+                cond_off: M31(0),
+                offset: M31(0),
+            }
+            .size_in_qm31s(),
+        }
+    }
+}
+
+/// A random instruction sequence and the entry frame it runs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzProgram {
+    pub instructions: Vec<Instruction>,
+    pub frame: EntryFrame,
+}
+
+impl FuzzProgram {
+    /// Generates a program from fuzzer input.
+    ///
+    /// Any input yields a valid program: the same input always yields the same program, and
+    /// longer inputs yield longer programs, up to [`MAX_INSTRUCTIONS`].
+    pub fn generate(data: &[u8]) -> Self {
+        let mut source = ByteSource::new(data);
+
+        let mut locals = (0..NUM_FELT_SLOTS)
+            .map(|_| source.felt())
+            .collect::<Vec<_>>();
+        locals.extend((0..2 * NUM_U32_SLOTS).map(|_| M31::from(source.u16() as u32)));
+
+        let mut ops = vec![];
+        while !source.is_empty() && ops.len() < MAX_INSTRUCTIONS {
+            ops.push(Self::generate_op(&mut source));
+        }
+
+        Self {
+            instructions: Self::resolve_jumps(&ops),
+            frame: EntryFrame {
+                args: vec![],
+                num_return_values: 0,
+                locals,
+            },
+        }
+    }
+
+    fn generate_op(source: &mut ByteSource<'_>) -> Op {
+        let instruction = match source.u8() % 24 {
+            0 => Instruction::StoreAddFpFp {
+                src0_off: source.felt_slot(),
+                src1_off: source.felt_slot(),
+                dst_off: source.felt_slot(),
+            },
+            1 => Instruction::StoreSubFpFp {
+                src0_off: source.felt_slot(),
+                src1_off: source.felt_slot(),
+                dst_off: source.felt_slot(),
+            },
+            2 => Instruction::StoreMulFpFp {
+                src0_off: source.felt_slot(),
+                src1_off: source.felt_slot(),
+                dst_off: source.felt_slot(),
+            },
+            3 => Instruction::StoreAddFpImm {
+                src_off: source.felt_slot(),
+                imm: source.felt(),
+                dst_off: source.felt_slot(),
+            },
+            4 => Instruction::StoreMulFpImm {
+                src_off: source.felt_slot(),
+                imm: source.felt(),
+                dst_off: source.felt_slot(),
+            },
+            5 => Instruction::StoreImm {
+                imm: source.felt(),
+                dst_off: source.felt_slot(),
+            },
+            6 => Instruction::StoreFramePointer {
+                imm: M31::from(source.u8() as u32),
+                dst_off: source.felt_slot(),
+            },
+            7 => Instruction::U32StoreAddFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            8 => Instruction::U32StoreSubFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            9 => Instruction::U32StoreMulFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            10 => Instruction::U32StoreAndFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            11 => Instruction::U32StoreOrFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            12 => Instruction::U32StoreXorFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.u32_slot(),
+            },
+            13 => Instruction::U32StoreEqFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.felt_slot(),
+            },
+            14 => Instruction::U32StoreLtFpFp {
+                src0_off: source.u32_slot(),
+                src1_off: source.u32_slot(),
+                dst_off: source.felt_slot(),
+            },
+            15 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreImm {
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.u32_slot(),
+                }
+            }
+            16 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreAddFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.u32_slot(),
+                }
+            }
+            17 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreMulFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.u32_slot(),
+                }
+            }
+            18 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreXorFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.u32_slot(),
+                }
+            }
+            19 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreEqFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.felt_slot(),
+                }
+            }
+            20 => {
+                let (imm_lo, imm_hi) = source.u32_imm();
+                Instruction::U32StoreLtFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off: source.felt_slot(),
+                }
+            }
+            21 => {
+                // The divisor must be non-zero and the quotient and remainder distinct.
+                let (imm_lo, imm_hi) = source.u32_imm();
+                let imm_lo = if imm_lo.0 == 0 && imm_hi.0 == 0 {
+                    M31(1)
+                } else {
+                    imm_lo
+                };
+                let dst_off = source.u32_slot();
+                let dst_rem_off = M31::from(
+                    NUM_FELT_SLOTS + (dst_off.0 - NUM_FELT_SLOTS + 2) % (2 * NUM_U32_SLOTS),
+                );
+                Instruction::U32StoreDivRemFpImm {
+                    src_off: source.u32_slot(),
+                    imm_lo,
+                    imm_hi,
+                    dst_off,
+                    dst_rem_off,
+                }
+            }
+            22 => {
+                return Op::Jmp {
+                    skip: source.u8() as usize % 4,
+                };
+            }
+            _ => {
+                return Op::Jnz {
+                    cond_off: source.felt_slot(),
+                    skip: source.u8() as usize % 4,
+                };
+            }
+        };
+        Op::Instruction(instruction)
+    }
+
+    /// Lowers jumps to relative offsets, clamping their targets to the end of the program.
+    fn resolve_jumps(ops: &[Op]) -> Vec<Instruction> {
+        let mut pcs = Vec::with_capacity(ops.len() + 1);
+        let mut pc = 0;
+        for op in ops {
+            pcs.push(pc);
+            pc += op.size_in_qm31s();
+        }
+        pcs.push(pc);
+
+        ops.iter()
+            .enumerate()
+            .map(|(i, op)| {
+                let offset = |skip: usize| {
+                    let target = pcs[(i + 1 + skip).min(ops.len())];
+                    M31::from(target - pcs[i])
+                };
+                match op {
+                    Op::Instruction(instruction) => *instruction,
+                    Op::Jmp { skip } => Instruction::JmpRelImm {
+                        offset: offset(*skip),
+                    },
+                    Op::Jnz { cond_off, skip } => Instruction::JnzFpImm {
+                        cond_off: *cond_off,
+                        offset: offset(*skip),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Runs the program in a fresh VM.
+    ///
+    /// ## Returns
+    ///
+    /// The runner output, with no return values and an empty input and output range.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`VmError`] if execution fails, which is a bug for generated programs.
+    pub fn run(&self, options: &RunnerOptions) -> Result<RunnerOutput, VmError> {
+        let mut vm = VM::run_instructions(&self.instructions, &self.frame, options)?;
+        let public_address_ranges = PublicAddressRanges::new(vm.program_length.0, 0, 0);
+        let logs = std::mem::take(&mut vm.logs);
+        Ok(RunnerOutput {
+            return_values: vec![],
+            vm,
+            public_address_ranges,
+            logs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        assert_eq!(FuzzProgram::generate(&data), FuzzProgram::generate(&data));
+        assert!(FuzzProgram::generate(&[]).instructions.is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn test_generated_programs_run(data in prop::collection::vec(any::<u8>(), 0..1024)) {
+            let program = FuzzProgram::generate(&data);
+            let output = program.run(&RunnerOptions::default()).unwrap();
+            prop_assert_eq!(output.vm.state.pc, output.vm.final_pc);
+        }
+    }
+}
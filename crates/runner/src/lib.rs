@@ -1,19 +1,33 @@
+pub mod bindings;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod loader;
 pub mod memory;
 pub mod vm;
 
+use std::collections::HashMap;
+
 use cairo_m_common::abi_codec::m31_from_i64;
-use cairo_m_common::program::{AbiSlot, AbiType};
-use cairo_m_common::{AbiCodecError, CairoMValue, InputValue, Program, PublicAddressRanges};
+use cairo_m_common::program::{AbiSlot, AbiType, EntrypointInfo};
+use cairo_m_common::{
+    AbiCodecError, BinaryProgram, CairoMValue, InputValue, Program, PublicAddressRanges,
+    PublicSegment,
+};
 use memory::MemoryError;
 use stwo_prover::core::fields::m31::M31;
+pub use vm::instructions::print::{DebugLog, DebugValue};
 use vm::{VM, VmError};
 
 /// Result type for runner operations
 pub type Result<T> = std::result::Result<T, RunnerError>;
 
-// Current limitation is that the maximum clock difference must be < 2^20
+// Segments longer than the maximum clock difference need clock updates
 const DEFAULT_MAX_STEPS: usize = (1 << 20) - 1;
 
+/// Largest clock difference between two accesses of a memory cell that the prover can
+/// range check (2^20 - 1).
+pub const MAX_CLOCK_DELTA: u32 = (1 << 20) - 1;
+
 // Maximum value for the lower/upper 16-bit parts of a U32
 const U16_MAX: u32 = 0xFFFF;
 
@@ -34,6 +48,9 @@ pub enum RunnerError {
 
     #[error("ABI encode/decode error: {0}")]
     AbiError(#[from] AbiCodecError),
+
+    #[error("Environment too short: the program reads {expected} values, {provided} provided")]
+    EnvironmentTooShort { expected: u32, provided: usize },
 }
 
 /// Options for running a Cairo program
@@ -41,12 +58,35 @@ pub enum RunnerError {
 pub struct RunnerOptions {
     /// The maximum number of steps to execute, DEFAULT_max_steps by default.
     pub max_steps: usize,
+    /// The clock difference above which the VM inserts clock updates between two
+    /// accesses of a memory cell, at most (and by default) [`MAX_CLOCK_DELTA`].
+    pub max_clock_delta: u32,
+    /// The maximum number of allocated memory cells, heap included, unlimited by default.
+    /// Writes beyond it fail with [`MemoryError::ResourceExhausted`].
+    pub max_memory_cells: Option<usize>,
+    /// The maximum number of allocated heap cells, unlimited by default.
+    pub max_heap_cells: Option<usize>,
+    /// Segments made public in addition to the program, the arguments and the return values.
+    pub public_segments: Vec<PublicSegment>,
+    /// Whether reads of memory cells that were never written fail with
+    /// [`VmError::UninitializedRead`] instead of returning zero, to catch frame layout bugs.
+    /// Disabled by default.
+    pub strict_memory: bool,
+    /// Values of the environment block read by `env(index)`, written after the program and
+    /// public. Must hold at least as many values as the program reads, empty by default.
+    pub environment: Vec<M31>,
 }
 
 impl Default for RunnerOptions {
     fn default() -> Self {
         Self {
             max_steps: DEFAULT_MAX_STEPS,
+            max_clock_delta: MAX_CLOCK_DELTA,
+            max_memory_cells: None,
+            max_heap_cells: None,
+            public_segments: Vec::new(),
+            strict_memory: false,
+            environment: Vec::new(),
         }
     }
 }
@@ -60,6 +100,22 @@ pub struct RunnerOutput {
     pub vm: VM,
     /// The public address ranges for structured access to program, input, and output data
     pub public_address_ranges: PublicAddressRanges,
+    /// Values printed with `debug_print`, in execution order.
+    /// The printed values are not part of the proven memory.
+    pub logs: Vec<DebugLog>,
+}
+
+impl RunnerOutput {
+    /// Formats the return values as a list, with felts in their balanced representation
+    /// followed by their hexadecimal encoding (e.g. `[-5 (0x7ffffffa), true]`).
+    pub fn display_signed(&self) -> String {
+        let values: Vec<_> = self
+            .return_values
+            .iter()
+            .map(|value| value.display_signed().to_string())
+            .collect();
+        format!("[{}]", values.join(", "))
+    }
 }
 
 /// Calculates the total number of memory cells needed for materializing array data
@@ -371,12 +427,30 @@ fn decode_all_return_values(
     Ok(decoded_returns)
 }
 
+/// Completes the arguments of an entrypoint with the default values of the omitted trailing
+/// parameters.
+fn complete_arguments(params: &[AbiSlot], args: &[InputValue]) -> Result<Vec<InputValue>> {
+    let mismatch = || RunnerError::ArgumentCountMismatch {
+        expected: params.len(),
+        provided: args.len(),
+    };
+    if args.len() > params.len() {
+        return Err(mismatch());
+    }
+    let defaults = params[args.len()..]
+        .iter()
+        .map(|param| param.default.clone().ok_or_else(mismatch))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(args.iter().cloned().chain(defaults).collect())
+}
+
 /// Executes a Cairo-M program with the specified entrypoint and arguments.
 ///
 /// ## Arguments
 /// * `program` - The compiled Cairo-M program
 /// * `entrypoint` - Name of the function to execute
-/// * `args` - Input arguments for the function
+/// * `args` - Input arguments for the function. Trailing parameters with a default value
+///   can be omitted.
 /// * `options` - Execution options (e.g., max steps)
 ///
 /// ## Returns
@@ -387,21 +461,229 @@ pub fn run_cairo_program(
     args: &[InputValue],
     options: RunnerOptions,
 ) -> Result<RunnerOutput> {
-    let entrypoint_info = program.get_entrypoint(entrypoint).ok_or_else(|| {
+    run_entrypoint(
+        || VM::try_from(program),
+        &program.entrypoints,
+        program.metadata.environment_size,
+        entrypoint,
+        args,
+        options,
+    )
+}
+
+/// Executes a Cairo-M program in the binary format with the specified entrypoint and arguments.
+///
+/// The program is loaded without decoding its instructions ahead of execution, see
+/// [`BinaryProgram`]. The arguments and returned output are those of [`run_cairo_program`].
+pub fn run_binary_program<B: AsRef<[u8]>>(
+    program: &BinaryProgram<B>,
+    entrypoint: &str,
+    args: &[InputValue],
+    options: RunnerOptions,
+) -> Result<RunnerOutput> {
+    run_entrypoint(
+        || VM::try_from(program),
+        &program.entrypoints,
+        program.metadata.environment_size,
+        entrypoint,
+        args,
+        options,
+    )
+}
+
+/// Executes a Cairo-M program with the specified entrypoint once per set of arguments.
+///
+/// The program is loaded once: each run starts from a copy of its memory image, so that
+/// fuzzing campaigns and parameter sweeps don't pay for loading the program on every run.
+///
+/// ## Arguments
+/// * `program` - The compiled Cairo-M program
+/// * `entrypoint` - Name of the function to execute
+/// * `inputs` - Input arguments of each run, as for [`run_cairo_program`]
+/// * `options` - Execution options of every run
+///
+/// ## Returns
+/// The outcome of each run, in the order of `inputs`. A failing run does not stop the
+/// following ones.
+///
+/// ## Errors
+/// Returns an error if the program can't be loaded or has no such entrypoint.
+pub fn run_cairo_program_batch(
+    program: &Program,
+    entrypoint: &str,
+    inputs: &[Vec<InputValue>],
+    options: RunnerOptions,
+) -> Result<Vec<Result<RunnerOutput>>> {
+    find_entrypoint(&program.entrypoints, entrypoint)?;
+    let image = VM::try_from(program)?;
+
+    Ok(inputs
+        .iter()
+        .map(|args| {
+            run_entrypoint(
+                || Ok(image.clone()),
+                &program.entrypoints,
+                program.metadata.environment_size,
+                entrypoint,
+                args,
+                options.clone(),
+            )
+        })
+        .collect())
+}
+
+/// Encodes the arguments of an entrypoint as the VM writes them in memory, without executing
+/// it.
+///
+/// Arrays are passed as pointers to their elements, which are materialized in memory right
+/// after the environment block, before the argument slots, as for [`run_cairo_program`].
+///
+/// ## Returns
+/// The values of the cells following the environment block: the materialized arrays, then
+/// the argument slots.
+///
+/// ## Errors
+/// Returns an error if the program has no such entrypoint, or if the arguments or the
+/// environment of `options` don't match it.
+pub fn encode_arguments(
+    program: &Program,
+    entrypoint: &str,
+    args: &[InputValue],
+    options: &RunnerOptions,
+) -> Result<Vec<M31>> {
+    let entrypoint_info = find_entrypoint(&program.entrypoints, entrypoint)?;
+    let args = complete_arguments(&entrypoint_info.params, args)?;
+    let mut vm = VM::try_from(program)?;
+    let frame = prepare_call(
+        &mut vm,
+        entrypoint_info,
+        program.metadata.environment_size,
+        &args,
+        options,
+    )?;
+
+    // The arrays fill the frame up to the argument and return slots and the saved registers
+    let array_cells =
+        frame.frame_offset as usize - frame.arguments.len() - frame.return_slot_count - 2;
+    let mut cells = (0..array_cells)
+        .map(|i| {
+            vm.memory
+                .get_data_no_trace(vm.state.fp + M31::from(i as u32))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(VmError::from)?;
+    cells.extend(frame.arguments);
+    Ok(cells)
+}
+
+/// Looks up the information of an entrypoint by name.
+fn find_entrypoint<'a>(
+    entrypoints: &'a HashMap<String, EntrypointInfo>,
+    entrypoint: &str,
+) -> Result<&'a EntrypointInfo> {
+    entrypoints.get(entrypoint).ok_or_else(|| {
         RunnerError::EntryPointNotFound(
             entrypoint.to_string(),
-            program.entrypoints.keys().cloned().collect(),
+            entrypoints.keys().cloned().collect(),
         )
-    })?;
+    })
+}
 
-    if entrypoint_info.params.len() != args.len() {
-        return Err(RunnerError::ArgumentCountMismatch {
-            expected: entrypoint_info.params.len(),
-            provided: args.len(),
+/// Executes an entrypoint of a program, loaded in a VM by `load` once the arguments and the
+/// environment are checked.
+///
+/// The environment block is written right after the program, and the call frame after it.
+fn run_entrypoint(
+    load: impl FnOnce() -> std::result::Result<VM, VmError>,
+    entrypoints: &HashMap<String, EntrypointInfo>,
+    environment_size: u32,
+    entrypoint: &str,
+    args: &[InputValue],
+    options: RunnerOptions,
+) -> Result<RunnerOutput> {
+    let entrypoint_info = find_entrypoint(entrypoints, entrypoint)?;
+
+    let args = complete_arguments(&entrypoint_info.params, args)?;
+    let mut vm = load()?;
+    let CallFrame {
+        arguments: encoded_arguments,
+        frame_offset,
+        return_slot_count,
+    } = prepare_call(&mut vm, entrypoint_info, environment_size, &args, &options)?;
+
+    vm.run_from_entrypoint(
+        entrypoint_info.pc as u32,
+        frame_offset,
+        &encoded_arguments,
+        return_slot_count,
+        &options,
+    )?;
+
+    // Extract raw return values from the return frame
+    let mut raw_return_frame = Vec::with_capacity(return_slot_count);
+    for slot_index in 0..return_slot_count {
+        let return_slot_address =
+            vm.state.fp - M31::from((return_slot_count + 2 - slot_index) as u32);
+        let slot_value = vm.memory.get_data(return_slot_address)?;
+        raw_return_frame.push(slot_value);
+    }
+
+    let decoded_returns =
+        decode_all_return_values(&entrypoint_info.returns, &raw_return_frame, &vm)?;
+
+    // Create public address ranges for proof generation
+    let public_address_ranges = PublicAddressRanges {
+        segments: options.public_segments,
+        ..PublicAddressRanges::new(
+            vm.program_length.0,
+            encoded_arguments.len(),
+            return_slot_count,
+        )
+        .with_environment(options.environment.len() as u32)
+    };
+
+    let logs = std::mem::take(&mut vm.logs);
+
+    Ok(RunnerOutput {
+        return_values: decoded_returns,
+        vm,
+        public_address_ranges,
+        logs,
+    })
+}
+
+/// Encoded arguments and layout of the call frame of an entrypoint.
+struct CallFrame {
+    /// Values of the argument slots, arrays being passed as pointers
+    arguments: Vec<M31>,
+    /// Offset of the frame pointer of the call from the end of the environment block
+    frame_offset: u32,
+    /// Number of return value slots
+    return_slot_count: usize,
+}
+
+/// Writes the environment block of `options` and the arrays passed as arguments to the
+/// memory of `vm`, and encodes the arguments of the entrypoint.
+fn prepare_call(
+    vm: &mut VM,
+    entrypoint_info: &EntrypointInfo,
+    environment_size: u32,
+    args: &[InputValue],
+    options: &RunnerOptions,
+) -> Result<CallFrame> {
+    if options.environment.len() < environment_size as usize {
+        return Err(RunnerError::EnvironmentTooShort {
+            expected: environment_size,
+            provided: options.environment.len(),
         });
     }
 
-    let mut vm = VM::try_from(program)?;
+    for (i, value) in options.environment.iter().enumerate() {
+        vm.memory
+            .insert_no_trace(vm.state.fp + M31::from(i as u32), (*value).into())
+            .map_err(VmError::from)?;
+    }
+    vm.state.fp += M31::from(options.environment.len() as u32);
 
     // Calculate memory layout for function call frame
     // The frame consists of:
@@ -438,7 +720,7 @@ pub fn run_cairo_program(
     let mut encoded_arguments: Vec<M31> = Vec::with_capacity(argument_slot_count);
     for (param_spec, input_value) in entrypoint_info.params.iter().zip(args.iter()) {
         encode_value_for_call(
-            &mut vm,
+            vm,
             &mut array_memory_cursor,
             &param_spec.ty,
             input_value,
@@ -446,37 +728,10 @@ pub fn run_cairo_program(
         )?;
     }
 
-    vm.run_from_entrypoint(
-        entrypoint_info.pc as u32,
-        total_frame_offset as u32,
-        &encoded_arguments,
-        return_slot_count,
-        &options,
-    )?;
-
-    // Extract raw return values from the return frame
-    let mut raw_return_frame = Vec::with_capacity(return_slot_count);
-    for slot_index in 0..return_slot_count {
-        let return_slot_address =
-            vm.state.fp - M31::from((return_slot_count + 2 - slot_index) as u32);
-        let slot_value = vm.memory.get_data(return_slot_address)?;
-        raw_return_frame.push(slot_value);
-    }
-
-    let decoded_returns =
-        decode_all_return_values(&entrypoint_info.returns, &raw_return_frame, &vm)?;
-
-    // Create public address ranges for proof generation
-    let public_address_ranges = PublicAddressRanges::new(
-        vm.program_length.0,
-        encoded_arguments.len(),
+    Ok(CallFrame {
+        arguments: encoded_arguments,
+        frame_offset: total_frame_offset as u32,
         return_slot_count,
-    );
-
-    Ok(RunnerOutput {
-        return_values: decoded_returns,
-        vm,
-        public_address_ranges,
     })
 }
 
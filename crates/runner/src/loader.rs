@@ -0,0 +1,90 @@
+//! Loading of compiled programs from files.
+//!
+//! Programs are read in either format the compiler writes: JSON, or the binary format of
+//! [`BinaryProgram`]. Binary programs are memory-mapped, so that loading a large program
+//! neither reads nor parses its code up front.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use cairo_m_common::binary_program::is_binary_program;
+use cairo_m_common::{BinaryProgram, BinaryProgramError, InputValue, Program};
+use memmap2::Mmap;
+
+use crate::{Result, RunnerOptions, RunnerOutput, run_binary_program, run_cairo_program};
+
+/// A binary program backed by a memory-mapped file
+pub type MappedProgram = BinaryProgram<Mmap>;
+
+/// Errors that can occur when loading a program
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("Failed to read program: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Failed to parse program: {0}")]
+    Json(#[from] sonic_rs::Error),
+
+    #[error("Failed to parse binary program: {0}")]
+    Binary(#[from] BinaryProgramError),
+}
+
+/// A program loaded from a file, in the format it was written in
+#[derive(Debug)]
+pub enum LoadedProgram {
+    Json(Program),
+    Binary(MappedProgram),
+}
+
+impl LoadedProgram {
+    /// Executes the program with the specified entrypoint and arguments, see
+    /// [`run_cairo_program`].
+    pub fn run(
+        &self,
+        entrypoint: &str,
+        args: &[InputValue],
+        options: RunnerOptions,
+    ) -> Result<RunnerOutput> {
+        match self {
+            Self::Json(program) => run_cairo_program(program, entrypoint, args, options),
+            Self::Binary(program) => run_binary_program(program, entrypoint, args, options),
+        }
+    }
+}
+
+/// Memory-maps a program in the binary format.
+///
+/// ## Errors
+///
+/// Returns a [`LoadError`] if the file can't be mapped or isn't a binary program.
+pub fn map_program(path: impl AsRef<Path>) -> std::result::Result<MappedProgram, LoadError> {
+    let file = File::open(path)?;
+    Ok(BinaryProgram::from_bytes(map_file(&file)?)?)
+}
+
+/// Loads a program, memory-mapping it if it is in the binary format.
+///
+/// ## Errors
+///
+/// Returns a [`LoadError`] if the file can't be read, or doesn't hold a program.
+pub fn load_program(path: impl AsRef<Path>) -> std::result::Result<LoadedProgram, LoadError> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        // Mapping an empty file fails on some platforms.
+        return Ok(LoadedProgram::Json(sonic_rs::from_slice(&[])?));
+    }
+
+    let mmap = map_file(&file)?;
+    if is_binary_program(&mmap) {
+        Ok(LoadedProgram::Binary(BinaryProgram::from_bytes(mmap)?))
+    } else {
+        Ok(LoadedProgram::Json(sonic_rs::from_slice(&mmap)?))
+    }
+}
+
+fn map_file(file: &File) -> io::Result<Mmap> {
+    // SAFETY: the program file must not be modified while it is mapped, which holds for
+    // compiled programs being run.
+    unsafe { Mmap::map(file) }
+}
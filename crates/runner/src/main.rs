@@ -1,9 +1,11 @@
-use std::fs;
 use std::path::PathBuf;
 
 use anyhow::Context;
-use cairo_m_common::{Program, parse_cli_arg};
-use cairo_m_runner::run_cairo_program;
+use cairo_m_common::abi_codec::m31_from_i64;
+use cairo_m_common::parse_cli_arg;
+use cairo_m_runner::RunnerOptions;
+use cairo_m_runner::loader::load_program;
+use cairo_m_runner::vm::export::TraceFormat;
 use clap::{Parser, ValueHint};
 
 #[derive(Parser, Debug)]
@@ -14,7 +16,7 @@ use clap::{Parser, ValueHint};
     long_about = None
 )]
 struct Args {
-    /// Path to the compiled Cairo file (JSON format)
+    /// Path to the compiled Cairo file (JSON or binary format)
     #[arg(value_hint = ValueHint::FilePath)]
     compiled_file: PathBuf,
 
@@ -25,11 +27,11 @@ struct Args {
     /// Arguments to pass to the entrypoint function
     ///
     /// Supported types:
-    ///   • Numbers: 42, -5 (use quotes for negative: "-5")
+    ///   • Numbers: 42, -5, 0x2a, 1e6 (use quotes for negative: "-5")
     ///   • Booleans: true, false
     ///   • Tuples: (1,2,3) or [1,2,3] or [1,[2,3]] for nested
     ///   • Structs: {1,2,3} or {1,{2,3}} for nested (fields are positional)
-    ///   • Mixed: {1,[true,{2,3}]} combining structs and tuples
+    ///   • Mixed: {1,[true,{2,3}]} or [{1,2},{3,4}] combining structs and tuples
     ///
     /// Note: Fixed-size arrays are not currently supported as input arguments.
     ///       See Linear issue CORE-1118 for array support tracking.
@@ -40,29 +42,78 @@ struct Args {
     #[arg(short, long, value_parser = parse_cli_arg, num_args = 0.., allow_hyphen_values = true, verbatim_doc_comment)]
     arguments: Vec<cairo_m_common::InputValue>,
 
+    /// Values of the environment block, read by the program with `env(index)`
+    ///
+    /// Examples:
+    ///   --env 19000000 1
+    #[arg(long, num_args = 0.., allow_hyphen_values = true, verbatim_doc_comment)]
+    env: Vec<i64>,
+
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Export the register trace to this base path (one file per segment)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    trace_file: Option<PathBuf>,
+
+    /// Export the memory trace to this base path (one file per segment)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    memory_trace_file: Option<PathBuf>,
+
+    /// Format of the exported traces: binary, csv or parquet (requires the `parquet` feature)
+    #[arg(long, default_value = "binary")]
+    trace_format: TraceFormat,
+
+    /// Print felt return values as signed numbers (e.g. -5 instead of 2147483642) with their hex
+    #[arg(long)]
+    signed: bool,
+
+    /// Fail on reads of memory cells that were never written, instead of reading zero
+    #[arg(long)]
+    strict_memory: bool,
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let file_content = fs::read_to_string(&args.compiled_file)
-        .with_context(|| format!("Error reading file '{}'", args.compiled_file.display()))?;
+    let compiled_program = load_program(&args.compiled_file)
+        .with_context(|| format!("Error loading file '{}'", args.compiled_file.display()))?;
+
+    let output = compiled_program
+        .run(
+            &args.entrypoint,
+            &args.arguments,
+            RunnerOptions {
+                strict_memory: args.strict_memory,
+                environment: args.env.iter().copied().map(m31_from_i64).collect(),
+                ..Default::default()
+            },
+        )
+        .context("Execution failed")?;
 
-    let compiled_program: Program =
-        sonic_rs::from_str(&file_content).context("Failed to parse compiled program")?;
+    for log in &output.logs {
+        println!("{}", log);
+    }
 
-    let output = run_cairo_program(
-        &compiled_program,
-        &args.entrypoint,
-        &args.arguments,
-        Default::default(),
-    )
-    .context("Execution failed")?;
+    if let Some(path) = &args.trace_file {
+        output
+            .vm
+            .export_trace(path, args.trace_format)
+            .context("Failed to export trace")?;
+    }
+    if let Some(path) = &args.memory_trace_file {
+        output
+            .vm
+            .export_memory_trace(path, args.trace_format)
+            .context("Failed to export memory trace")?;
+    }
 
-    println!("Run succeeded and returned: {:?}", output.return_values);
+    if args.signed {
+        println!("Run succeeded and returned: {}", output.display_signed());
+    } else {
+        println!("Run succeeded and returned: {:?}", output.return_values);
+    }
 
     Ok(())
 }
@@ -39,6 +39,70 @@ pub enum MemoryError {
         limb_hi
     )]
     U32LimbOutOfRange { limb_lo: u32, limb_hi: u32 },
+    #[error(
+        "Writing address {addr} exceeds the {resource} limit of {limit} cells: it requires {memory_cells} memory cells, of which {heap_cells} heap cells"
+    )]
+    ResourceExhausted {
+        addr: M31,
+        resource: &'static str,
+        limit: usize,
+        memory_cells: usize,
+        heap_cells: usize,
+    },
+}
+
+/// Limits on the number of allocated memory cells, used to sandbox untrusted programs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryLimits {
+    /// Maximum number of cells, locals and heap included
+    pub max_memory_cells: Option<usize>,
+    /// Maximum number of heap cells
+    pub max_heap_cells: Option<usize>,
+}
+
+/// Tracks the memory cells written since strict mode was enabled, laid out like the memory.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WrittenCells {
+    locals: Vec<bool>,
+    heap: Vec<bool>,
+}
+
+impl WrittenCells {
+    /// Marks the cells currently allocated in `memory` as written.
+    fn from_memory(memory: &Memory) -> Self {
+        Self {
+            locals: vec![true; memory.locals.len()],
+            heap: vec![true; memory.heap.len()],
+        }
+    }
+
+    fn mark(cells: &mut Vec<bool>, index: usize) {
+        if index >= cells.len() {
+            cells.resize(index + 1, false);
+        }
+        cells[index] = true;
+    }
+
+    /// Marks a written address as such, given the number of local cells after the write.
+    fn insert(&mut self, addr: M31, locals_len: usize) {
+        let address = addr.0 as usize;
+        if address < locals_len {
+            Self::mark(&mut self.locals, address);
+        } else {
+            Self::mark(&mut self.heap, MAX_ADDRESS - address);
+        }
+    }
+
+    /// Returns true if a validated address was written.
+    fn contains(&self, addr: M31) -> bool {
+        let address = addr.0 as usize;
+        self.locals.get(address).copied().unwrap_or(false)
+            || self
+                .heap
+                .get(MAX_ADDRESS - address)
+                .copied()
+                .unwrap_or(false)
+    }
 }
 
 /// Represents the Cairo M VM's memory, a flat, read-write address space.
@@ -59,6 +123,14 @@ pub struct Memory {
     /// modify the trace. This design choice separates the logical immutability
     /// of an operation from the implementation detail of tracing.
     pub trace: RefCell<Vec<MemoryEntry>>,
+    /// Limits on the growth of the locals and the heap, unlimited by default.
+    pub(crate) limits: MemoryLimits,
+    /// Writes recorded since the last call to [`Self::take_recorded_writes`], if recording is
+    /// enabled. Used to notify the execution observers of the VM.
+    pub(crate) recorded_writes: Option<Vec<MemoryEntry>>,
+    /// Cells written since strict mode was enabled with [`Self::enable_strict_mode`], if it
+    /// is. Reads of any other cell fail instead of returning zero.
+    pub(crate) written_cells: Option<WrittenCells>,
 }
 
 impl Memory {
@@ -79,6 +151,38 @@ impl Memory {
         Ok(())
     }
 
+    /// Checks that growing the memory to the given number of cells stays within its limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The `M31` address whose write grows the memory.
+    /// * `locals_cells` - The number of local cells after the write.
+    /// * `heap_cells` - The number of heap cells after the write.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemoryError::ResourceExhausted`] if a limit is exceeded.
+    const fn check_limits(
+        &self,
+        addr: M31,
+        locals_cells: usize,
+        heap_cells: usize,
+    ) -> Result<(), MemoryError> {
+        let memory_cells = locals_cells + heap_cells;
+        let (resource, limit) = match (self.limits.max_heap_cells, self.limits.max_memory_cells) {
+            (Some(limit), _) if heap_cells > limit => ("heap", limit),
+            (_, Some(limit)) if memory_cells > limit => ("memory", limit),
+            _ => return Ok(()),
+        };
+        Err(MemoryError::ResourceExhausted {
+            addr,
+            resource,
+            limit,
+            memory_cells,
+            heap_cells,
+        })
+    }
+
     /// Retrieves a complete instruction from memory, handling both single and multi-word instructions.
     ///
     /// This method fetches the first QM31 word to determine the opcode, then fetches
@@ -170,8 +274,17 @@ impl Memory {
     /// Returns [`MemoryError::AddressOutOfBounds`] if the address exceeds the maximum allowed size.
     /// Returns [`MemoryError::BaseFieldProjectionFailed`] if the value at the address
     /// cannot be projected to a base field element.
+    /// Returns [`MemoryError::UninitializedMemoryCell`] in strict mode if the address was
+    /// never written.
     fn get_qm31_no_trace(&self, addr: M31) -> Result<QM31, MemoryError> {
         Self::validate_address(addr)?;
+        if self
+            .written_cells
+            .as_ref()
+            .is_some_and(|cells| !cells.contains(addr))
+        {
+            return Err(MemoryError::UninitializedMemoryCell { addr });
+        }
         let address = addr.0 as usize;
         let locals_address = address;
         let heap_address = MAX_ADDRESS - address;
@@ -237,12 +350,37 @@ impl Memory {
     /// # Errors
     ///
     /// Returns [`MemoryError::AddressOutOfBounds`] if the address exceeds the maximum allowed size.
+    /// Returns [`MemoryError::ResourceExhausted`] if the write grows the memory beyond its limits.
     pub fn insert(&mut self, addr: M31, value: QM31) -> Result<(), MemoryError> {
         self.insert_no_trace(addr, value)?;
         self.trace.borrow_mut().push(MemoryEntry { addr, value });
+        if let Some(writes) = &mut self.recorded_writes {
+            writes.push(MemoryEntry { addr, value });
+        }
         Ok(())
     }
 
+    /// Starts recording the writes made with [`Self::insert`].
+    pub(crate) fn record_writes(&mut self) {
+        self.recorded_writes.get_or_insert_default();
+    }
+
+    /// Enables strict mode: reads of cells not allocated yet, nor written afterwards, fail with
+    /// [`MemoryError::UninitializedMemoryCell`] instead of returning zero.
+    pub(crate) fn enable_strict_mode(&mut self) {
+        if self.written_cells.is_none() {
+            self.written_cells = Some(WrittenCells::from_memory(self));
+        }
+    }
+
+    /// Returns the writes recorded since the last call, and clears them.
+    pub(crate) fn take_recorded_writes(&mut self) -> Vec<MemoryEntry> {
+        self.recorded_writes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     /// Inserts a `QM31` value at a specified validated memory address without logging a trace entry.
     ///
     /// # Arguments
@@ -253,7 +391,18 @@ impl Memory {
     /// # Errors
     ///
     /// Returns [`MemoryError::AddressOutOfBounds`] if the address exceeds the maximum allowed size.
+    /// Returns [`MemoryError::ResourceExhausted`] if the write grows the memory beyond its limits.
     pub(crate) fn insert_no_trace(&mut self, addr: M31, value: QM31) -> Result<(), MemoryError> {
+        self.write_cell(addr, value)?;
+        let locals_len = self.locals.len();
+        if let Some(cells) = &mut self.written_cells {
+            cells.insert(addr, locals_len);
+        }
+        Ok(())
+    }
+
+    /// Writes a cell, growing the locals or the heap, whichever is nearest, if needed.
+    fn write_cell(&mut self, addr: M31, value: QM31) -> Result<(), MemoryError> {
         Self::validate_address(addr)?;
         let locals_address = addr.0 as usize;
         let heap_address = MAX_ADDRESS - addr.0 as usize;
@@ -270,10 +419,12 @@ impl Memory {
         let locals_distance = locals_address - self.locals.len();
         let heap_distance = heap_address - self.heap.len();
         if locals_distance < heap_distance {
+            self.check_limits(addr, locals_address + 1, self.heap.len())?;
             self.locals.resize(locals_address + 1, QM31::zero());
             self.locals[locals_address] = value;
             return Ok(());
         }
+        self.check_limits(addr, self.locals.len(), heap_address + 1)?;
         self.heap.resize(heap_address + 1, QM31::zero());
         self.heap[heap_address] = value;
 
@@ -319,6 +470,11 @@ impl Memory {
 
         self.locals[fp_min_two_addr] = QM31::from_m31_array([fp.0, 0, 0, 0].map(Into::into));
         self.locals[fp_min_one_addr] = QM31::from_m31_array([final_pc.0, 0, 0, 0].map(Into::into));
+        let locals_len = self.locals.len();
+        if let Some(cells) = &mut self.written_cells {
+            cells.insert(fp_min_two, locals_len);
+            cells.insert(fp_min_one, locals_len);
+        }
 
         Ok(())
     }
@@ -333,7 +489,12 @@ impl Memory {
     where
         I: IntoIterator<Item = QM31>,
     {
+        let start = self.locals.len();
         self.locals.extend(iter);
+        if let Some(cells) = &mut self.written_cells {
+            cells.locals.resize(start, false);
+            cells.locals.resize(self.locals.len(), true);
+        }
     }
 
     /// Serializes the trace to a byte vector.
@@ -416,6 +577,9 @@ impl FromIterator<QM31> for Memory {
             locals: iter.into_iter().collect(),
             heap: vec![],
             trace: RefCell::new(Vec::new()),
+            limits: MemoryLimits::default(),
+            recorded_writes: None,
+            written_cells: None,
         }
     }
 }
@@ -447,6 +611,9 @@ mod tests {
             locals: data,
             heap: vec![],
             trace: RefCell::new(Vec::new()),
+            limits: MemoryLimits::default(),
+            recorded_writes: None,
+            written_cells: None,
         };
 
         let instruction_m31s = memory.get_instruction(addr).unwrap();
@@ -477,6 +644,9 @@ mod tests {
             locals: data,
             heap: vec![],
             trace: RefCell::new(Vec::new()),
+            limits: MemoryLimits::default(),
+            recorded_writes: None,
+            written_cells: None,
         };
 
         assert_eq!(memory.get_data(addr).unwrap(), M31(123));
@@ -555,6 +725,47 @@ mod tests {
         assert_eq!(memory.trace.borrow()[1], MemoryEntry { addr, value });
     }
 
+    #[test]
+    fn test_insert_within_limits() {
+        let mut memory = Memory {
+            limits: MemoryLimits {
+                max_memory_cells: Some(10),
+                max_heap_cells: Some(2),
+            },
+            ..Default::default()
+        };
+        let value = QM31::from_m31_array([1, 0, 0, 0].map(Into::into));
+
+        memory.insert(M31(7), value).unwrap();
+        memory.insert(M31(MAX_ADDRESS as u32 - 1), value).unwrap();
+        // Overwriting an allocated cell does not grow the memory
+        memory.insert(M31(3), value).unwrap();
+        assert_eq!((memory.locals.len(), memory.heap.len()), (8, 2));
+
+        assert_eq!(
+            memory.insert(M31(MAX_ADDRESS as u32 - 2), value),
+            Err(MemoryError::ResourceExhausted {
+                addr: M31(MAX_ADDRESS as u32 - 2),
+                resource: "heap",
+                limit: 2,
+                memory_cells: 11,
+                heap_cells: 3,
+            })
+        );
+        assert_eq!(
+            memory.insert(M31(8), value),
+            Err(MemoryError::ResourceExhausted {
+                addr: M31(8),
+                resource: "memory",
+                limit: 10,
+                memory_cells: 11,
+                heap_cells: 2,
+            })
+        );
+        assert_eq!((memory.locals.len(), memory.heap.len()), (8, 2));
+        assert_eq!(memory.trace.borrow().len(), 3);
+    }
+
     #[test]
     fn test_validate_address() {
         assert!(Memory::validate_address(100.into()).is_ok());
@@ -804,4 +1015,26 @@ mod tests {
         assert_eq!(memory.locals[1000], locals_value);
         assert_eq!(memory.heap.len(), 1); // Heap unchanged
     }
+
+    #[test]
+    fn test_strict_mode_rejects_unwritten_reads() {
+        let mut memory = Memory::from_iter([7].map(Into::into));
+        memory.enable_strict_mode();
+
+        // Cells allocated before strict mode, and written ones, can be read
+        assert_eq!(memory.get_data(M31(0)).unwrap(), M31(7));
+        memory.insert(M31(3), M31(5).into()).unwrap();
+        assert_eq!(memory.get_data(M31(3)).unwrap(), M31(5));
+        let heap_addr = M31(MAX_ADDRESS as u32);
+        memory.insert(heap_addr, M31(9).into()).unwrap();
+        assert_eq!(memory.get_data_no_trace(heap_addr).unwrap(), M31(9));
+
+        // Cells zero-filled by the write of [3], or never allocated, can't
+        for addr in [M31(2), M31(10), heap_addr - M31(1)] {
+            assert_eq!(
+                memory.get_data(addr),
+                Err(MemoryError::UninitializedMemoryCell { addr })
+            );
+        }
+    }
 }
@@ -0,0 +1,96 @@
+//! Call stack reconstruction for failed executions.
+//!
+//! Every call stores the caller's frame pointer at `[fp - 2]` and the return address at
+//! `[fp - 1]` of the callee frame. Walking this chain from the failing state gives the
+//! active calls, which are named with the symbol table of the program and located with its
+//! source locations: the failing instruction, then the call site of each caller.
+
+use std::fmt;
+
+use cairo_m_common::{Instruction, Symbol, SymbolKind};
+use num_traits::{One, Zero};
+use stwo_prover::core::fields::m31::M31;
+
+use super::VM;
+
+/// Maximum number of frames of a backtrace, to bound the walk of corrupted chains.
+const MAX_FRAMES: usize = 256;
+
+/// A frame of the call stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// The address of the failing instruction for the innermost frame, of the `call`
+    /// instruction for the other frames.
+    pub pc: u32,
+    /// The frame pointer of the frame.
+    pub fp: u32,
+    /// The name of the function containing `pc`, if the program has a symbol table.
+    pub function: Option<String>,
+    /// The source location of the instruction at `pc`, if known.
+    pub location: Option<String>,
+}
+
+/// The call stack of a failed execution, innermost frame first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Backtrace {
+    pub frames: Vec<StackFrame>,
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "backtrace:")?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let function = frame.function.as_deref().unwrap_or("<unknown>");
+            write!(f, "\n  {i}: {function} at pc {}", frame.pc)?;
+            if let Some(location) = &frame.location {
+                write!(f, " ({location})")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VM {
+    /// Reconstructs the call stack from the current state.
+    ///
+    /// The walk stops at the entry frame, whose return address is the final PC, or at the
+    /// first frame whose saved registers can't be read.
+    pub fn backtrace(&self) -> Backtrace {
+        let call_size = M31::from(
+            Instruction::CallAbsImm {
+                frame_off: M31::zero(),
+                target: M31::zero(),
+            }
+            .size_in_qm31s(),
+        );
+
+        let mut frames = vec![self.stack_frame(self.state.pc, self.state.fp)];
+        let mut fp = self.state.fp;
+        while frames.len() < MAX_FRAMES {
+            let (Ok(caller_fp), Ok(return_pc)) = (
+                self.memory.get_data_no_trace(fp - M31::from(2)),
+                self.memory.get_data_no_trace(fp - M31::one()),
+            ) else {
+                break;
+            };
+            if return_pc == self.final_pc || caller_fp == fp {
+                break;
+            }
+            frames.push(self.stack_frame(return_pc - call_size, caller_fp));
+            fp = caller_fp;
+        }
+        Backtrace { frames }
+    }
+
+    /// Builds the frame of the instruction at `pc`, named with the debug info of the program.
+    fn stack_frame(&self, pc: M31, fp: M31) -> StackFrame {
+        let pc = pc.0;
+        StackFrame {
+            pc,
+            fp: fp.0,
+            function: Symbol::find(&self.symbols, pc, SymbolKind::Function)
+                .map(|symbol| symbol.name.clone()),
+            location: self.source_locations.get(&pc).cloned(),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! # Execution trace export
+//!
+//! Writers for the register and memory traces of an executed [`VM`] in formats
+//! consumed by external analysis tools:
+//!
+//! - [`TraceFormat::Binary`]: the serialized bytes consumed by the prover.
+//! - [`TraceFormat::Csv`]: one header row followed by one row per trace entry.
+//! - [`TraceFormat::Parquet`] (requires the `parquet` feature): columnar files
+//!   that can be loaded directly with pandas or DuckDB.
+//!
+//! As for the binary writers, one file is written per segment, using the naming
+//! pattern `<base_path>_segment_<index>.<extension>`.
+//! Columns are described by [`TRACE_COLUMNS`] and [`MEMORY_TRACE_COLUMNS`]; the
+//! descriptions are also stored as field metadata in Parquet files.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use cairo_m_common::execution::Segment;
+
+use super::{VM, VmError};
+
+/// Output format of an exported trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Raw little-endian bytes, as produced by [`VM::write_binary_trace`].
+    Binary,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Apache Parquet.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl fmt::Display for TraceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary => write!(f, "binary"),
+            Self::Csv => write!(f, "csv"),
+            #[cfg(feature = "parquet")]
+            Self::Parquet => write!(f, "parquet"),
+        }
+    }
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "binary" | "bin" => Ok(Self::Binary),
+            "csv" => Ok(Self::Csv),
+            #[cfg(feature = "parquet")]
+            "parquet" => Ok(Self::Parquet),
+            other => Err(format!("Unsupported trace format: {other}")),
+        }
+    }
+}
+
+/// Description of a column of an exported trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// Column name, used as CSV header and Parquet field name.
+    pub name: &'static str,
+    /// Human readable description of the column content.
+    pub description: &'static str,
+}
+
+/// Columns of the exported register trace.
+pub const TRACE_COLUMNS: [ColumnInfo; 3] = [
+    ColumnInfo {
+        name: "step",
+        description: "Index of the step within the segment",
+    },
+    ColumnInfo {
+        name: "pc",
+        description: "Program counter before executing the step",
+    },
+    ColumnInfo {
+        name: "fp",
+        description: "Frame pointer before executing the step",
+    },
+];
+
+/// Columns of the exported memory trace.
+pub const MEMORY_TRACE_COLUMNS: [ColumnInfo; 6] = [
+    ColumnInfo {
+        name: "access",
+        description: "Index of the memory access within the segment",
+    },
+    ColumnInfo {
+        name: "addr",
+        description: "Accessed memory address",
+    },
+    ColumnInfo {
+        name: "value_0",
+        description: "First M31 coordinate of the QM31 value",
+    },
+    ColumnInfo {
+        name: "value_1",
+        description: "Second M31 coordinate of the QM31 value",
+    },
+    ColumnInfo {
+        name: "value_2",
+        description: "Third M31 coordinate of the QM31 value",
+    },
+    ColumnInfo {
+        name: "value_3",
+        description: "Fourth M31 coordinate of the QM31 value",
+    },
+];
+
+/// Returns the register trace of a segment as rows matching [`TRACE_COLUMNS`].
+fn trace_rows(segment: &Segment) -> Vec<[u32; 3]> {
+    segment
+        .trace
+        .iter()
+        .enumerate()
+        .map(|(i, state)| [i as u32, state.pc.0, state.fp.0])
+        .collect()
+}
+
+/// Returns the memory trace of a segment as rows matching [`MEMORY_TRACE_COLUMNS`].
+fn memory_trace_rows(segment: &Segment) -> Vec<[u32; 6]> {
+    segment
+        .memory_trace
+        .borrow()
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            [
+                i as u32,
+                entry.addr.0,
+                entry.value.0.0.0,
+                entry.value.0.1.0,
+                entry.value.1.0.0,
+                entry.value.1.1.0,
+            ]
+        })
+        .collect()
+}
+
+/// Builds the path of the file holding the `index`-th segment.
+///
+/// `trace.csv` becomes `trace_segment_<index>.csv`.
+pub(super) fn segment_path(path: &Path, index: usize) -> String {
+    let path_str = path.to_string_lossy();
+    let (base, ext) = path_str.rfind('.').map_or_else(
+        || (path_str.as_ref(), ""),
+        |dot_pos| (&path_str[..dot_pos], &path_str[dot_pos..]),
+    );
+    format!("{}_segment_{}{}", base, index, ext)
+}
+
+/// Writes rows as CSV, with the column names as header.
+fn write_csv<const N: usize>(
+    path: &str,
+    columns: &[ColumnInfo; N],
+    rows: &[[u32; N]],
+) -> Result<(), VmError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    let header = columns.iter().map(|c| c.name).collect::<Vec<_>>().join(",");
+    writeln!(writer, "{header}")?;
+
+    for row in rows {
+        let line = row.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+        writeln!(writer, "{line}")?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes rows as a Parquet file of `UInt32` columns.
+///
+/// Each field carries its description under the `description` metadata key.
+#[cfg(feature = "parquet")]
+fn write_parquet<const N: usize>(
+    path: &str,
+    columns: &[ColumnInfo; N],
+    rows: &[[u32; N]],
+) -> Result<(), VmError> {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, UInt32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|c| {
+                Field::new(c.name, DataType::UInt32, false).with_metadata(HashMap::from([(
+                    "description".to_string(),
+                    c.description.to_string(),
+                )]))
+            })
+            .collect::<Vec<_>>(),
+    ));
+
+    let arrays = (0..N)
+        .map(|col| {
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|row| row[col]),
+            )) as ArrayRef
+        })
+        .collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Writes rows in the given text or columnar format.
+fn write_rows<const N: usize>(
+    path: &str,
+    format: TraceFormat,
+    columns: &[ColumnInfo; N],
+    rows: &[[u32; N]],
+) -> Result<(), VmError> {
+    match format {
+        TraceFormat::Binary => unreachable!("binary traces are serialized by the segment"),
+        TraceFormat::Csv => write_csv(path, columns, rows),
+        #[cfg(feature = "parquet")]
+        TraceFormat::Parquet => write_parquet(path, columns, rows),
+    }
+}
+
+impl VM {
+    /// Exports the register trace (`pc`, `fp`) in the given format, one file per segment.
+    ///
+    /// See [`TRACE_COLUMNS`] for the exported columns.
+    ///
+    /// ## Arguments
+    ///
+    /// * `path` - The base file path for the trace files.
+    /// * `format` - The output format.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`VmError`] if any file cannot be created or written.
+    pub fn export_trace<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: TraceFormat,
+    ) -> Result<(), VmError> {
+        if format == TraceFormat::Binary {
+            return self.write_binary_trace(path);
+        }
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let segment_path = segment_path(path.as_ref(), i);
+            write_rows(&segment_path, format, &TRACE_COLUMNS, &trace_rows(segment))?;
+        }
+
+        Ok(())
+    }
+
+    /// Exports the memory trace (address and QM31 value of each access) in the given format,
+    /// one file per segment.
+    ///
+    /// See [`MEMORY_TRACE_COLUMNS`] for the exported columns.
+    ///
+    /// ## Arguments
+    ///
+    /// * `path` - The base file path for the memory trace files.
+    /// * `format` - The output format.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`VmError`] if any file cannot be created or written.
+    pub fn export_memory_trace<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: TraceFormat,
+    ) -> Result<(), VmError> {
+        if format == TraceFormat::Binary {
+            return self.write_binary_memory_trace(path);
+        }
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            let segment_path = segment_path(path.as_ref(), i);
+            write_rows(
+                &segment_path,
+                format,
+                &MEMORY_TRACE_COLUMNS,
+                &memory_trace_rows(segment),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./export_tests.rs"]
+mod export_tests;
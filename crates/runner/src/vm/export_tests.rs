@@ -0,0 +1,169 @@
+use std::fs;
+
+use cairo_m_common::{Instruction, Program};
+use stwo_prover::core::fields::m31::M31;
+
+use super::*;
+use crate::RunnerOptions;
+
+fn run_segmented_program() -> VM {
+    let instructions = vec![
+        Instruction::StoreImm {
+            imm: M31(10),
+            dst_off: M31(0),
+        }, // store_imm: [fp + 0] = 10
+        Instruction::StoreImm {
+            imm: M31(20),
+            dst_off: M31(1),
+        }, // store_imm: [fp + 1] = 20
+        Instruction::StoreAddFpFp {
+            src0_off: M31(0),
+            src1_off: M31(1),
+            dst_off: M31(2),
+        }, // store_add_fp_fp: [fp + 2] = [fp + 0] + [fp + 1]
+    ];
+    let program = Program::from(instructions);
+    let mut vm = VM::try_from(&program).unwrap();
+    vm.run_from_entrypoint(
+        0,
+        3,
+        &[],
+        0,
+        &RunnerOptions {
+            max_steps: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    vm
+}
+
+#[test]
+fn test_trace_format_from_str() {
+    assert_eq!("csv".parse::<TraceFormat>(), Ok(TraceFormat::Csv));
+    assert_eq!("BIN".parse::<TraceFormat>(), Ok(TraceFormat::Binary));
+    assert!("xlsx".parse::<TraceFormat>().is_err());
+    assert_eq!(TraceFormat::Csv.to_string(), "csv");
+}
+
+#[test]
+fn test_segment_path() {
+    assert_eq!(
+        segment_path(Path::new("out/trace.csv"), 1),
+        "out/trace_segment_1.csv"
+    );
+    assert_eq!(segment_path(Path::new("trace"), 0), "trace_segment_0");
+}
+
+#[test]
+fn test_export_trace_csv() {
+    let vm = run_segmented_program();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let trace_path = temp_dir.path().join("trace.csv");
+
+    vm.export_trace(&trace_path, TraceFormat::Csv).unwrap();
+
+    for (i, segment) in vm.segments.iter().enumerate() {
+        let segment_file = temp_dir.path().join(format!("trace_segment_{i}.csv"));
+        let contents = fs::read_to_string(&segment_file).unwrap();
+        let mut lines = contents.lines();
+
+        assert_eq!(lines.next(), Some("step,pc,fp"));
+        let rows = lines.collect::<Vec<_>>();
+        assert_eq!(rows.len(), segment.trace.len());
+        let first = segment.trace[0];
+        assert_eq!(rows[0], format!("0,{},{}", first.pc.0, first.fp.0));
+    }
+}
+
+#[test]
+fn test_export_memory_trace_csv() {
+    let vm = run_segmented_program();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let memory_trace_path = temp_dir.path().join("memory_trace.csv");
+
+    vm.export_memory_trace(&memory_trace_path, TraceFormat::Csv)
+        .unwrap();
+
+    for (i, segment) in vm.segments.iter().enumerate() {
+        let segment_file = temp_dir
+            .path()
+            .join(format!("memory_trace_segment_{i}.csv"));
+        let contents = fs::read_to_string(&segment_file).unwrap();
+        let mut lines = contents.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("access,addr,value_0,value_1,value_2,value_3")
+        );
+        assert_eq!(lines.count(), segment.memory_trace.borrow().len());
+    }
+}
+
+#[test]
+fn test_export_trace_binary_matches_binary_writer() {
+    let vm = run_segmented_program();
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    vm.export_trace(temp_dir.path().join("a.bin"), TraceFormat::Binary)
+        .unwrap();
+    vm.write_binary_trace(temp_dir.path().join("b.bin"))
+        .unwrap();
+
+    for i in 0..vm.segments.len() {
+        let a = fs::read(temp_dir.path().join(format!("a_segment_{i}.bin"))).unwrap();
+        let b = fs::read(temp_dir.path().join(format!("b_segment_{i}.bin"))).unwrap();
+        assert_eq!(a, b);
+    }
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn test_export_memory_trace_parquet_roundtrip() {
+    use arrow::array::{Array, UInt32Array};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let vm = run_segmented_program();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let memory_trace_path = temp_dir.path().join("memory_trace.parquet");
+
+    vm.export_memory_trace(&memory_trace_path, TraceFormat::Parquet)
+        .unwrap();
+
+    for (i, segment) in vm.segments.iter().enumerate() {
+        let segment_file = temp_dir
+            .path()
+            .join(format!("memory_trace_segment_{i}.parquet"));
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&segment_file).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        let schema = batches[0].schema();
+        for (field, column) in schema.fields().iter().zip(&MEMORY_TRACE_COLUMNS) {
+            assert_eq!(field.name(), column.name);
+            assert_eq!(
+                field.metadata().get("description").map(String::as_str),
+                Some(column.description)
+            );
+        }
+
+        let mut rows = Vec::new();
+        for batch in &batches {
+            let columns = (0..MEMORY_TRACE_COLUMNS.len())
+                .map(|col| {
+                    batch
+                        .column(col)
+                        .as_any()
+                        .downcast_ref::<UInt32Array>()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            rows.extend(
+                (0..batch.num_rows()).map(|row| std::array::from_fn(|col| columns[col].value(row))),
+            );
+        }
+        assert_eq!(rows, memory_trace_rows(segment));
+    }
+}
@@ -0,0 +1,134 @@
+//! Reconstruction of the memory contents at any point of an execution.
+//!
+//! The memory trace logs the accesses of each step in order: the words of the instruction,
+//! then its operands, as many as [`Instruction::memory_accesses`]. Replaying it gives each
+//! access the clock of its step, the same way the prover does.
+//!
+//! Clocks count the steps from the start of the execution, across segments: the accesses
+//! of the `n`-th step happen at clock `n`, and clock `0` is the initial memory.
+
+use cairo_m_common::Instruction;
+use cairo_m_common::instruction::INSTRUCTION_MAX_SIZE;
+use cairo_m_common::state::MemoryEntry;
+use smallvec::SmallVec;
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::QM31;
+
+use super::VM;
+use crate::memory::MAX_ADDRESS;
+
+/// An access to a memory cell, with the value of the cell after the access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryAccess {
+    /// The clock of the step performing the access, `0` for the initial value.
+    pub clock: u32,
+    /// The value of the cell after the access.
+    pub value: QM31,
+}
+
+impl VM {
+    /// Returns the accesses to a memory cell, in execution order.
+    ///
+    /// The first access is the initial value of the cell at clock `0`, if the cell was
+    /// initialized before the execution (program, arguments, ...).
+    ///
+    /// ## Arguments
+    ///
+    /// * `addr` - The address of the cell.
+    pub fn history(&self, addr: M31) -> Vec<MemoryAccess> {
+        let mut history: Vec<MemoryAccess> = self
+            .initial_value(addr)
+            .map(|value| MemoryAccess { clock: 0, value })
+            .into_iter()
+            .collect();
+        self.replay_memory_trace(|clock, entry| {
+            if entry.addr == addr {
+                history.push(MemoryAccess {
+                    clock,
+                    value: entry.value,
+                });
+            }
+        });
+        history
+    }
+
+    /// Returns the value of a memory cell after the step at `clock`.
+    ///
+    /// Returns `None` if the cell was neither initialized nor accessed by then.
+    ///
+    /// ## Arguments
+    ///
+    /// * `addr` - The address of the cell.
+    /// * `clock` - The clock of the step, `0` for the initial memory.
+    pub fn memory_at_clock(&self, addr: M31, clock: u32) -> Option<QM31> {
+        self.history(addr)
+            .into_iter()
+            .take_while(|access| access.clock <= clock)
+            .last()
+            .map(|access| access.value)
+    }
+
+    /// Returns the value of a cell before the first step of the execution.
+    fn initial_value(&self, addr: M31) -> Option<QM31> {
+        if let Some(segment) = self.segments.first() {
+            return segment.initial_memory.get(&addr).map(|(value, ..)| *value);
+        }
+        // The first segment is not finalized yet.
+        self.initial_memory_locals
+            .get(addr.0 as usize)
+            .or_else(|| {
+                let index = MAX_ADDRESS.checked_sub(addr.0 as usize)?;
+                self.initial_memory_heap.get(index)
+            })
+            .copied()
+    }
+
+    /// Calls `visit` with the clock of each entry of the memory trace, from the first
+    /// segment to the current one.
+    ///
+    /// The replay stops at the first step whose entries are incomplete, as when the step
+    /// failed.
+    fn replay_memory_trace(&self, mut visit: impl FnMut(u32, &MemoryEntry)) {
+        let traces: Vec<_> = self
+            .segments
+            .iter()
+            .map(|segment| segment.memory_trace.borrow())
+            .chain(std::iter::once(self.memory.trace.borrow()))
+            .collect();
+        let mut entries = traces.iter().flat_map(|trace| trace.iter());
+
+        let mut clock = 0;
+        while let Some(first_word) = entries.next() {
+            clock += 1;
+            let Some(size) = Instruction::size_in_m31s_for_opcode(first_word.value.0.0.0) else {
+                return;
+            };
+            let mut words = vec![first_word];
+            for _ in 1..size.div_ceil(4) {
+                let Some(word) = entries.next() else {
+                    return;
+                };
+                words.push(word);
+            }
+            let m31s: SmallVec<[M31; INSTRUCTION_MAX_SIZE]> = words
+                .iter()
+                .flat_map(|word| word.value.to_m31_array())
+                .take(size)
+                .collect();
+            let Ok(instruction) = Instruction::try_from(m31s) else {
+                return;
+            };
+
+            let operands: Vec<_> = entries
+                .by_ref()
+                .take(instruction.traced_memory_accesses())
+                .collect();
+            if operands.len() < instruction.traced_memory_accesses() {
+                return;
+            }
+            for entry in words.into_iter().chain(operands) {
+                visit(clock, entry);
+            }
+        }
+    }
+}
@@ -23,6 +23,7 @@ use cairo_m_common::{Instruction, State};
 use crate::vm::instructions::call::*;
 use crate::vm::instructions::jnz::*;
 use crate::vm::instructions::jump::*;
+use crate::vm::instructions::poseidon2::*;
 use crate::vm::instructions::print::*;
 use crate::vm::instructions::store::*;
 use crate::vm::{Memory, MemoryError};
@@ -31,6 +32,7 @@ pub mod assert;
 pub mod call;
 pub mod jnz;
 pub mod jump;
+pub mod poseidon2;
 pub mod print;
 pub mod store;
 
@@ -43,6 +45,8 @@ pub enum InstructionExecutionError {
     Instruction(#[from] InstructionError),
     #[error("Invalid operand: {0}")]
     InvalidOperand(String),
+    #[error("Division by zero")]
+    DivisionByZero,
     #[error("Invalid instruction type for handler")]
     InvalidInstructionType,
 }
@@ -105,7 +109,10 @@ pub fn instruction_to_fn(instruction: Instruction) -> Result<InstructionFn, Inst
         Instruction::PrintM31 { .. } => print_m31,
         Instruction::PrintU32 { .. } => print_u32,
         Instruction::StoreLeFpImm { .. } => store_le_fp_imm,
+        Instruction::StorePowFpImm { .. } => store_pow_fp_imm,
         Instruction::AssertEqFpImm { .. } => assert_eq_fp_imm,
+        Instruction::Poseidon2AbsorbFpFp { .. } => poseidon2_absorb_fp_fp,
+        Instruction::Poseidon2SqueezeFpFp { .. } => poseidon2_squeeze_fp_fp,
     };
     Ok(f)
 }
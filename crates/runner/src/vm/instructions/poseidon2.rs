@@ -0,0 +1,126 @@
+//! Poseidon2 sponge instructions for the Cairo M VM.
+//!
+//! A sequence of felts is hashed by absorbing it in zero-padded blocks of
+//! [`POSEIDON2_SPONGE_RATE`] felts into a zero state of [`POSEIDON2_DIGEST_SIZE`] felts,
+//! then squeezing the state with the length of the sequence. Each instruction is a single
+//! Poseidon2 permutation whose digest is the first [`POSEIDON2_DIGEST_SIZE`] elements of the
+//! output state, stored in consecutive cells like the state it was computed from.
+
+use cairo_m_common::instruction::{
+    POSEIDON2_DIGEST_SIZE, POSEIDON2_SPONGE_RATE, POSEIDON2_STATE_SIZE, poseidon2_absorb_input,
+    poseidon2_squeeze_input,
+};
+use cairo_m_common::{Instruction, State, extract_as};
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::M31;
+use zkhash::ark_ff::PrimeField;
+use zkhash::fields::m31::FpM31;
+use zkhash::poseidon2::poseidon2::Poseidon2;
+use zkhash::poseidon2::poseidon2_instance_m31::POSEIDON2_M31_16_PARAMS;
+
+use super::InstructionExecutionError;
+use crate::memory::Memory;
+use crate::vm::state::VmState;
+
+fn read_felts<const N: usize>(
+    memory: &Memory,
+    addr: M31,
+) -> Result<[M31; N], InstructionExecutionError> {
+    let mut values = [M31::zero(); N];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = memory.get_data(addr + M31::from(i as u32))?;
+    }
+    Ok(values)
+}
+
+fn write_felts(
+    memory: &mut Memory,
+    addr: M31,
+    values: [M31; POSEIDON2_DIGEST_SIZE],
+) -> Result<(), InstructionExecutionError> {
+    for (i, value) in values.into_iter().enumerate() {
+        memory.insert(addr + M31::from(i as u32), value.into())?;
+    }
+    Ok(())
+}
+
+/// Returns the Poseidon2 permutation of `input`.
+pub fn poseidon2_permutation(input: [M31; POSEIDON2_STATE_SIZE]) -> [M31; POSEIDON2_STATE_SIZE] {
+    let poseidon2 = Poseidon2::new(&POSEIDON2_M31_16_PARAMS);
+    let input: Vec<FpM31> = input.iter().map(|value| FpM31::from(value.0)).collect();
+    let output = poseidon2.permutation(&input);
+    std::array::from_fn(|i| M31::from(output[i].into_bigint().0[0] as u32))
+}
+
+/// Returns the first [`POSEIDON2_DIGEST_SIZE`] elements of the Poseidon2 permutation of
+/// `input`.
+pub fn poseidon2_digest(input: [M31; POSEIDON2_STATE_SIZE]) -> [M31; POSEIDON2_DIGEST_SIZE] {
+    let output = poseidon2_permutation(input);
+    std::array::from_fn(|i| output[i])
+}
+
+/// Hashes a sequence of felts as a program would with the sponge instructions.
+pub fn poseidon2_hash(values: &[M31]) -> [M31; POSEIDON2_DIGEST_SIZE] {
+    let state = values.chunks(POSEIDON2_SPONGE_RATE).fold(
+        [M31::zero(); POSEIDON2_DIGEST_SIZE],
+        |state, chunk| {
+            let mut block = [M31::zero(); POSEIDON2_SPONGE_RATE];
+            block[..chunk.len()].copy_from_slice(chunk);
+            poseidon2_digest(poseidon2_absorb_input(state, block))
+        },
+    );
+    poseidon2_digest(poseidon2_squeeze_input(
+        state,
+        M31::from(values.len() as u32),
+    ))
+}
+
+/// CASM equivalent:
+/// ```casm
+/// ([fp + dst_off], ..., [fp + dst_off + 7]) = poseidon2_absorb(([fp + state_off], ..., [fp + state_off + 7]), ([fp + src_off], ..., [fp + src_off + 7]))
+/// ```
+pub fn poseidon2_absorb_fp_fp(
+    memory: &mut Memory,
+    state: State,
+    instruction: &Instruction,
+) -> Result<State, InstructionExecutionError> {
+    let (state_off, src_off, dst_off) = extract_as!(
+        instruction,
+        Poseidon2AbsorbFpFp,
+        (state_off, src_off, dst_off)
+    );
+
+    let sponge_state = read_felts(memory, state.fp + state_off)?;
+    let block = read_felts(memory, state.fp + src_off)?;
+    let digest = poseidon2_digest(poseidon2_absorb_input(sponge_state, block));
+
+    write_felts(memory, state.fp + dst_off, digest)?;
+    Ok(state.advance_by(instruction.size_in_qm31s()))
+}
+
+/// CASM equivalent:
+/// ```casm
+/// ([fp + dst_off], ..., [fp + dst_off + 7]) = poseidon2_squeeze(([fp + state_off], ..., [fp + state_off + 7]), [fp + len_off])
+/// ```
+pub fn poseidon2_squeeze_fp_fp(
+    memory: &mut Memory,
+    state: State,
+    instruction: &Instruction,
+) -> Result<State, InstructionExecutionError> {
+    let (state_off, len_off, dst_off) = extract_as!(
+        instruction,
+        Poseidon2SqueezeFpFp,
+        (state_off, len_off, dst_off)
+    );
+
+    let sponge_state = read_felts(memory, state.fp + state_off)?;
+    let len = memory.get_data(state.fp + len_off)?;
+    let digest = poseidon2_digest(poseidon2_squeeze_input(sponge_state, len));
+
+    write_felts(memory, state.fp + dst_off, digest)?;
+    Ok(state.advance_by(instruction.size_in_qm31s()))
+}
+
+#[cfg(test)]
+#[path = "./poseidon2_tests.rs"]
+mod poseidon2_tests;
@@ -0,0 +1,108 @@
+use stwo_prover::core::fields::m31::M31;
+
+use super::*;
+
+#[test]
+fn test_poseidon2_absorb_fp_fp() -> Result<(), InstructionExecutionError> {
+    // [state[0..8], block[8..16], dst[16..24]]
+    let mut memory = Memory::from_iter((0..24).map(|v: u32| M31(v).into()));
+    let instruction = Instruction::Poseidon2AbsorbFpFp {
+        state_off: M31(0),
+        src_off: M31(8),
+        dst_off: M31(16),
+    };
+
+    let new_state = poseidon2_absorb_fp_fp(&mut memory, State::default(), &instruction)?;
+
+    let state = std::array::from_fn(|i| M31(i as u32));
+    let block = std::array::from_fn(|i| M31(i as u32 + 8));
+    let digest = poseidon2_digest(poseidon2_absorb_input(state, block));
+    for (i, value) in digest.into_iter().enumerate() {
+        assert_eq!(memory.get_data(M31(16 + i as u32))?, value);
+    }
+    assert_eq!(new_state.pc, M31(1));
+    Ok(())
+}
+
+#[test]
+fn test_poseidon2_squeeze_fp_fp() -> Result<(), InstructionExecutionError> {
+    // [state[0..8], len, dst[9..17]]
+    let mut memory =
+        Memory::from_iter((1..=8).chain([3]).chain([0; 8]).map(|v: u32| M31(v).into()));
+    let instruction = Instruction::Poseidon2SqueezeFpFp {
+        state_off: M31(0),
+        len_off: M31(8),
+        dst_off: M31(9),
+    };
+
+    let new_state = poseidon2_squeeze_fp_fp(&mut memory, State::default(), &instruction)?;
+
+    let state = std::array::from_fn(|i| M31(i as u32 + 1));
+    let digest = poseidon2_digest(poseidon2_squeeze_input(state, M31(3)));
+    for (i, value) in digest.into_iter().enumerate() {
+        assert_eq!(memory.get_data(M31(9 + i as u32))?, value);
+    }
+    assert_eq!(new_state.pc, M31(1));
+    Ok(())
+}
+
+#[test]
+fn test_poseidon2_hash_matches_instructions() -> Result<(), InstructionExecutionError> {
+    // Ten felts: two blocks, the second one zero-padded.
+    // [values[0..10], padding[10..16], state[16..24], len, states[25..41], digest[41..49]]
+    let values: Vec<M31> = (1..=10).map(M31).collect();
+    let mut memory = Memory::from_iter(
+        (1..=10)
+            .chain([0; 6])
+            .chain([0; 8])
+            .chain([10])
+            .map(|v: u32| M31(v).into()),
+    );
+
+    for (block_start, state_off, dst_off) in [(0, 16, 25), (8, 25, 33)] {
+        let instruction = Instruction::Poseidon2AbsorbFpFp {
+            state_off: M31(state_off),
+            src_off: M31(block_start),
+            dst_off: M31(dst_off),
+        };
+        poseidon2_absorb_fp_fp(&mut memory, State::default(), &instruction)?;
+    }
+    let instruction = Instruction::Poseidon2SqueezeFpFp {
+        state_off: M31(33),
+        len_off: M31(24),
+        dst_off: M31(41),
+    };
+    poseidon2_squeeze_fp_fp(&mut memory, State::default(), &instruction)?;
+
+    for (i, value) in poseidon2_hash(&values).into_iter().enumerate() {
+        assert_eq!(memory.get_data(M31(41 + i as u32))?, value);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_poseidon2_hash_depends_on_length() {
+    let values = [M31(1), M31(2)];
+    assert_ne!(
+        poseidon2_hash(&values),
+        poseidon2_hash(&[M31(1), M31(2), M31(0)])
+    );
+    assert_ne!(poseidon2_hash(&[]), poseidon2_hash(&[M31(0)]));
+}
+
+#[test]
+fn test_poseidon2_sponge_is_domain_separated() {
+    // A Merkle tree hash of the same first elements
+    let mut merkle_input = [M31(0); POSEIDON2_STATE_SIZE];
+    merkle_input[0] = M31(7);
+    merkle_input[1] = M31(3);
+    let merkle_digest = poseidon2_digest(merkle_input);
+
+    let mut state = [M31(0); POSEIDON2_DIGEST_SIZE];
+    state[0] = M31(7);
+    state[1] = M31(3);
+    assert_ne!(
+        poseidon2_digest(poseidon2_squeeze_input(state, M31(0)))[0],
+        merkle_digest[0]
+    );
+}
@@ -1,39 +1,107 @@
 //! PRINT instructions for the Cairo M VM.
 //!
-//! PRINT instructions are no-op instructions that output values to stdout for debugging purposes.
+//! PRINT instructions are debugging instructions: the VM reads the printed value without
+//! tracing the memory access and appends it to its log buffer. They are traced as steps that
+//! only advance the pc, proven by the prover's `debug_print` component.
+
+use std::fmt;
 
 use cairo_m_common::{Instruction, State, extract_as};
+use stwo_prover::core::fields::m31::M31;
 
 use super::InstructionExecutionError;
 use crate::memory::Memory;
 use crate::vm::state::VmState;
 
+/// A value printed by a debug print instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugValue {
+    M31(M31),
+    U32(u32),
+}
+
+impl fmt::Display for DebugValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::M31(value) => write!(f, "{}", value),
+            Self::U32(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A log entry captured from a debug print instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugLog {
+    /// PC of the print instruction
+    pub pc: M31,
+    /// Address of the printed value
+    pub addr: M31,
+    /// The printed value
+    pub value: DebugValue,
+}
+
+impl fmt::Display for DebugLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.value {
+            DebugValue::M31(_) => "PrintM31",
+            DebugValue::U32(_) => "PrintU32",
+        };
+        write!(f, "[{}] [{}] = {}", kind, self.addr, self.value)
+    }
+}
+
+/// Reads the value printed by a PrintM31 or PrintU32 instruction, without tracing the
+/// memory access.
+///
+/// ## Errors
+///
+/// Returns [`InstructionExecutionError::InvalidInstructionType`] if `instruction` is not a
+/// print instruction, or a memory error if the printed cell is not initialized.
+pub fn debug_log(
+    memory: &Memory,
+    state: State,
+    instruction: &Instruction,
+) -> Result<DebugLog, InstructionExecutionError> {
+    let (addr, value) = match instruction {
+        Instruction::PrintM31 { offset } => {
+            let addr = state.fp + *offset;
+            (addr, DebugValue::M31(memory.get_data_no_trace(addr)?))
+        }
+        Instruction::PrintU32 { offset } => {
+            let addr = state.fp + *offset;
+            (addr, DebugValue::U32(memory.get_u32_no_trace(addr)?))
+        }
+        _ => return Err(InstructionExecutionError::InvalidInstructionType),
+    };
+    Ok(DebugLog {
+        pc: state.pc,
+        addr,
+        value,
+    })
+}
+
 /// Execute PrintM31 instruction.
-/// Reads a value from [fp + offset] and prints it as an M31 field element.
-/// This is a debugging instruction that doesn't modify the trace.
+/// Validates that [fp + offset] holds an M31 field element. The printed value is
+/// captured by the VM through [`debug_log`].
 pub fn print_m31(
     memory: &mut Memory,
     state: State,
     instruction: &Instruction,
 ) -> Result<State, InstructionExecutionError> {
-    let offset = extract_as!(instruction, PrintM31, offset);
-    let addr = state.fp + offset;
-    let value = memory.get_data_no_trace(addr)?;
-    println!("[PrintM31] [{}] = {}", addr, value);
+    let _offset = extract_as!(instruction, PrintM31, offset);
+    debug_log(memory, state, instruction)?;
     Ok(state.advance_by(instruction.size_in_qm31s()))
 }
 
 /// Execute PrintU32 instruction.
-/// Reads a U32 value from [fp + offset] and prints it as a 32-bit unsigned integer.
-/// This is a debugging instruction that doesn't modify the trace.
+/// Validates that [fp + offset] holds a U32 value. The printed value is captured by the
+/// VM through [`debug_log`].
 pub fn print_u32(
     memory: &mut Memory,
     state: State,
     instruction: &Instruction,
 ) -> Result<State, InstructionExecutionError> {
-    let offset = extract_as!(instruction, PrintU32, offset);
-    let addr = state.fp + offset;
-    let value = memory.get_u32_no_trace(addr)?;
-    println!("[PrintU32] [{}] = {}", addr, value);
+    let _offset = extract_as!(instruction, PrintU32, offset);
+    debug_log(memory, state, instruction)?;
     Ok(state.advance_by(instruction.size_in_qm31s()))
 }
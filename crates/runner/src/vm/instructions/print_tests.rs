@@ -3,7 +3,7 @@ use stwo_prover::core::fields::m31::M31;
 
 use crate::memory::Memory;
 use crate::vm::instructions::InstructionExecutionError;
-use crate::vm::instructions::print::{print_m31, print_u32};
+use crate::vm::instructions::print::{DebugLog, DebugValue, debug_log, print_m31, print_u32};
 
 #[test]
 fn test_print_m31() -> Result<(), InstructionExecutionError> {
@@ -20,7 +20,7 @@ fn test_print_m31() -> Result<(), InstructionExecutionError> {
     // Create PrintM31 instruction with offset 5
     let instruction = Instruction::PrintM31 { offset: M31(5) };
 
-    // Execute the instruction - should print "[PrintM31] [10] = 42"
+    // Execute the instruction
     let new_state = print_m31(&mut memory, state, &instruction)?;
 
     // Verify state advances by instruction size (1 QM31)
@@ -51,7 +51,7 @@ fn test_print_u32() -> Result<(), InstructionExecutionError> {
     // Create PrintU32 instruction with offset 10
     let instruction = Instruction::PrintU32 { offset: M31(10) };
 
-    // Execute the instruction - should print "[PrintU32] [20] = 305419896" (0x12345678)
+    // Execute the instruction
     let new_state = print_u32(&mut memory, state, &instruction)?;
 
     // Verify state advances by instruction size (1 QM31)
@@ -121,3 +121,41 @@ fn test_print_u32_invalid_limbs() -> Result<(), InstructionExecutionError> {
 
     Ok(())
 }
+
+#[test]
+fn test_debug_log() -> Result<(), InstructionExecutionError> {
+    let mut memory = Memory::default();
+    memory.insert(M31(10), M31(42).into())?;
+    memory.insert_u32(M31(11), 0x12345678)?;
+    let trace_len = memory.trace.borrow().len();
+
+    let state = State {
+        pc: M31(3),
+        fp: M31(5),
+    };
+
+    let log = debug_log(&memory, state, &Instruction::PrintM31 { offset: M31(5) })?;
+    assert_eq!(
+        log,
+        DebugLog {
+            pc: M31(3),
+            addr: M31(10),
+            value: DebugValue::M31(M31(42)),
+        }
+    );
+    assert_eq!(log.to_string(), "[PrintM31] [10] = 42");
+
+    let log = debug_log(&memory, state, &Instruction::PrintU32 { offset: M31(6) })?;
+    assert_eq!(log.value, DebugValue::U32(0x12345678));
+    assert_eq!(log.to_string(), "[PrintU32] [11] = 305419896");
+
+    // Reading the printed values is not traced
+    assert_eq!(memory.trace.borrow().len(), trace_len);
+
+    assert_eq!(
+        debug_log(&memory, state, &Instruction::Ret {}),
+        Err(InstructionExecutionError::InvalidInstructionType)
+    );
+
+    Ok(())
+}
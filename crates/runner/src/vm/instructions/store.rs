@@ -2,6 +2,7 @@
 //!
 //! STORE instructions are used to store values in the memory.
 
+use cairo_m_common::instruction::MAX_POW_EXPONENT;
 use cairo_m_common::{Instruction, State, extract_as};
 use num_traits::{One, Zero};
 use stwo_prover::core::fields::m31::M31;
@@ -161,7 +162,6 @@ macro_rules! impl_store_bin_op_fp_imm {
 impl_store_bin_op_fp_fp!(store_add_fp_fp, StoreAddFpFp, +);
 impl_store_bin_op_fp_fp!(store_sub_fp_fp, StoreSubFpFp, -);
 impl_store_bin_op_fp_fp!(store_mul_fp_fp, StoreMulFpFp, *);
-impl_store_bin_op_fp_fp!(store_div_fp_fp, StoreDivFpFp, /);
 
 // -------------------------------------------------------------------------------------------------
 // Automatically-generated STORE FP-IMM operations (scalar)
@@ -170,6 +170,30 @@ impl_store_bin_op_fp_fp!(store_div_fp_fp, StoreDivFpFp, /);
 impl_store_bin_op_fp_imm!(store_add_fp_imm, StoreAddFpImm, +);
 impl_store_bin_op_fp_imm!(store_mul_fp_imm, StoreMulFpImm, *);
 
+/// CASM equivalent:
+/// ```casm
+/// [fp + dst_off] = [fp + src0_off] / [fp + src1_off]
+/// ```
+///
+/// Returns an error if attempting to divide by zero.
+pub fn store_div_fp_fp(
+    memory: &mut Memory,
+    state: State,
+    instruction: &Instruction,
+) -> Result<State, InstructionExecutionError> {
+    let (src0_off, src1_off, dst_off) =
+        extract_as!(instruction, StoreDivFpFp, (src0_off, src1_off, dst_off));
+
+    let divisor = memory.get_data(state.fp + src1_off)?;
+    if divisor.is_zero() {
+        return Err(InstructionExecutionError::DivisionByZero);
+    }
+    let value = memory.get_data(state.fp + src0_off)? / divisor;
+
+    memory.insert(state.fp + dst_off, value.into())?;
+    Ok(state.advance_by(instruction.size_in_qm31s()))
+}
+
 /// CASM equivalent:
 /// ```casm
 /// [fp + dst_off] = [fp + src_off] <= imm
@@ -190,6 +214,32 @@ pub fn store_le_fp_imm(
     Ok(state.advance_by(instruction.size_in_qm31s()))
 }
 
+/// CASM equivalent:
+/// ```casm
+/// [fp + dst_off] = [fp + src_off] ^ imm
+/// ```
+///
+/// The exponent must be in `2..=MAX_POW_EXPONENT`, the range covered by the prover.
+pub fn store_pow_fp_imm(
+    memory: &mut Memory,
+    state: State,
+    instruction: &Instruction,
+) -> Result<State, InstructionExecutionError> {
+    let (src_off, imm, dst_off) = extract_as!(instruction, StorePowFpImm, (src_off, imm, dst_off));
+
+    if !(2..=MAX_POW_EXPONENT).contains(&imm.0) {
+        return Err(InstructionExecutionError::InvalidOperand(format!(
+            "Exponent {imm} out of range 2..={MAX_POW_EXPONENT}"
+        )));
+    }
+
+    let src_value = memory.get_data(state.fp + src_off)?;
+    let value = (1..imm.0).fold(src_value, |acc, _| acc * src_value);
+
+    memory.insert(state.fp + dst_off, value.into())?;
+    Ok(state.advance_by(instruction.size_in_qm31s()))
+}
+
 // -------------------------------------------------------------------------------------------------
 // Singular / less-regular STORE instructions (scalar)
 // -------------------------------------------------------------------------------------------------
@@ -360,9 +410,7 @@ pub fn u32_store_div_rem_fp_fp(
     let src1_value = memory.get_u32(state.fp + src1_off)?;
 
     if src1_value == 0 {
-        return Err(InstructionExecutionError::InvalidOperand(
-            "Division by zero".to_string(),
-        ));
+        return Err(InstructionExecutionError::DivisionByZero);
     }
 
     let quotient = src0_value / src1_value;
@@ -410,9 +458,7 @@ pub fn u32_store_div_rem_fp_imm(
 
     // Check for division by zero
     if imm_value == 0 {
-        return Err(InstructionExecutionError::InvalidOperand(
-            "Division by zero".to_string(),
-        ));
+        return Err(InstructionExecutionError::DivisionByZero);
     }
 
     let src_value = memory.get_u32(state.fp + src_off)?;
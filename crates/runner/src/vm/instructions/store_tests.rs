@@ -1,6 +1,7 @@
 use num_traits::Zero;
 use proptest::prelude::*;
 use proptest::proptest;
+use stwo_prover::core::fields::FieldExpOps;
 use stwo_prover::core::fields::m31::M31;
 
 use super::{InstructionExecutionError, *};
@@ -345,7 +346,6 @@ proptest! {
 }
 
 #[test]
-#[should_panic(expected = "0 has no inverse")]
 fn test_store_div_fp_fp_by_zero() {
     let mut memory = Memory::from_iter([0u32, 4, 0].map(Into::into));
     let state = State::default();
@@ -355,7 +355,10 @@ fn test_store_div_fp_fp_by_zero() {
         dst_off: M31(3),
     };
 
-    let _ = store_div_fp_fp(&mut memory, state, &instruction);
+    assert_eq!(
+        store_div_fp_fp(&mut memory, state, &instruction),
+        Err(InstructionExecutionError::DivisionByZero)
+    );
 }
 
 // -----------------------------------------------------------------------------
@@ -497,8 +500,8 @@ proptest! {
 
         // Should return an error for division by zero
         match result {
-            Err(InstructionExecutionError::InvalidOperand(msg)) if msg == "Division by zero" => {},
-            _ => panic!("Expected InvalidOperand error for division by zero"),
+            Err(InstructionExecutionError::DivisionByZero) => {},
+            _ => panic!("Expected DivisionByZero error"),
         }
     }
 
@@ -621,7 +624,7 @@ proptest! {
             4,
             2,
         );
-        assert_eq!(err.unwrap_err(), InstructionExecutionError::InvalidOperand("Division by zero".to_string()));
+        assert_eq!(err.unwrap_err(), InstructionExecutionError::DivisionByZero);
     }
 
     #[test]
@@ -742,6 +745,43 @@ proptest! {
             1,
         ).unwrap();
     }
+
+    #[test]
+    fn test_store_pow_fp_imm(src_val: u32, exponent in 2..=MAX_POW_EXPONENT) {
+        let src = M31::from(src_val);
+        let expected_res = src.pow(exponent as u128);
+
+        let mut initial_mem = vec![0; 4];
+        initial_mem[1] = src.0;
+        let mut expected_mem = initial_mem.clone();
+        expected_mem[3] = expected_res.0;
+
+        run_simple_store_test(
+            &initial_mem,
+            Instruction::StorePowFpImm {
+                src_off: M31(1),
+                imm: M31(exponent),
+                dst_off: M31(3),
+            },
+            store_pow_fp_imm,
+            &expected_mem,
+            1,
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_store_pow_fp_imm_exponent_out_of_range(
+        exponent in prop_oneof![0..2u32, MAX_POW_EXPONENT + 1..1000],
+    ) {
+        let mut memory = Memory::from_iter([0u32, 3, 0, 0].into_iter().map(Into::into));
+        let instruction = Instruction::StorePowFpImm {
+            src_off: M31(1),
+            imm: M31(exponent),
+            dst_off: M31(3),
+        };
+        let err = store_pow_fp_imm(&mut memory, State::default(), &instruction);
+        assert!(matches!(err, Err(InstructionExecutionError::InvalidOperand(_))));
+    }
 }
 
 // -----------------------------------------------------------------------------
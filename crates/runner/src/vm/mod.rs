@@ -1,22 +1,33 @@
+pub mod backtrace;
+pub mod export;
+pub mod history;
 pub mod instructions;
+pub mod observer;
 pub mod state;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{self, Write};
 use std::path::Path;
 
-use cairo_m_common::execution::Segment;
+use backtrace::Backtrace;
+use cairo_m_common::execution::{ClockUpdate, Segment};
 use cairo_m_common::instruction::InstructionError;
-use cairo_m_common::{Instruction, Program, ProgramData, State};
+use cairo_m_common::{
+    AbortInfo, BinaryProgram, Instruction, Program, ProgramData, ProgramMetadata, State, Symbol,
+    UnsupportedIsaError,
+};
 use instructions::instruction_to_fn;
+use instructions::print::{DebugLog, debug_log};
 use num_traits::Zero;
+use observer::{Observers, SharedObserver};
+use state::VmState;
 use stwo_prover::core::fields::m31::M31;
 use stwo_prover::core::fields::qm31::QM31;
 use thiserror::Error;
 
-use crate::RunnerOptions;
-use crate::memory::{MAX_ADDRESS, Memory, MemoryError};
+use crate::memory::{MAX_ADDRESS, Memory, MemoryError, MemoryLimits};
+use crate::{MAX_CLOCK_DELTA, RunnerOptions};
 
 /// The status of the overall program execution.
 ///
@@ -36,8 +47,49 @@ pub enum VmError {
     Instruction(#[from] InstructionError),
     #[error("VM instruction execution error: {0}")]
     InstructionExecution(#[from] instructions::InstructionExecutionError),
+    #[error("execution aborted at pc {pc}: {info}")]
+    Aborted { pc: u32, info: AbortInfo },
+    #[error("division by zero at pc {pc}{}", .source_loc.as_ref().map(|loc| format!(" ({loc})")).unwrap_or_default())]
+    DivisionByZero { pc: u32, source_loc: Option<String> },
+    #[error(
+        "read of uninitialized memory at address {addr} ([fp{fp_offset:+}]) by the instruction at pc {pc}"
+    )]
+    UninitializedRead { pc: u32, addr: M31, fp_offset: i64 },
+    #[error("{error}\n{backtrace}")]
+    WithBacktrace {
+        error: Box<VmError>,
+        backtrace: Backtrace,
+    },
+    #[error(transparent)]
+    UnsupportedIsa(#[from] UnsupportedIsaError),
+    #[error("Invalid max clock delta {0}: must be between 1 and {MAX_CLOCK_DELTA}")]
+    InvalidMaxClockDelta(u32),
     #[error("VM I/O error: {0}")]
     Io(#[from] io::Error),
+    #[cfg(feature = "parquet")]
+    #[error("VM trace export error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+    #[cfg(feature = "parquet")]
+    #[error("VM trace export error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+}
+
+impl VmError {
+    /// Returns the error without its backtrace.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::WithBacktrace { error, .. } => error.inner(),
+            _ => self,
+        }
+    }
+
+    /// Returns the call stack of the failed execution, if it was reconstructed.
+    pub const fn backtrace(&self) -> Option<&Backtrace> {
+        match self {
+            Self::WithBacktrace { backtrace, .. } => Some(backtrace),
+            _ => None,
+        }
+    }
 }
 
 /// The Cairo M Virtual Machine.
@@ -51,6 +103,15 @@ pub enum VmError {
 /// - `program_length`: Length of linearized program data (instructions + rodata)
 /// - `trace`: Execution trace
 /// - `segments`: chunks of execution containing necessary data for continuation.
+/// - `logs`: values captured by debug print instructions, in execution order.
+/// - `abort_messages`: `assert` and `panic` messages, keyed by the PC of their trapping instruction.
+/// - `division_locations`: source locations of the divisions, keyed by PC.
+/// - `source_locations`: source locations of the calls, divisions and aborts, keyed by PC,
+///   locating the frames of backtraces.
+/// - `symbols`: symbol table of the program, naming the frames of backtraces.
+/// - `clock_updates`: clock updates of the current segment.
+/// - `last_access_clocks`: clock of the last access of each memory cell in the current segment.
+/// - `observers`: execution observers notified at each step, see [`VM::add_observer`].
 #[derive(Debug, Default, Clone)]
 pub struct VM {
     pub final_pc: M31,
@@ -61,6 +122,14 @@ pub struct VM {
     pub program_length: M31,
     pub trace: Vec<State>,
     pub segments: Vec<Segment>,
+    pub logs: Vec<DebugLog>,
+    pub abort_messages: BTreeMap<u32, AbortInfo>,
+    pub division_locations: BTreeMap<u32, String>,
+    pub source_locations: BTreeMap<u32, String>,
+    pub symbols: Vec<Symbol>,
+    pub clock_updates: Vec<ClockUpdate>,
+    last_access_clocks: HashMap<M31, u32>,
+    observers: Observers,
 }
 
 impl TryFrom<&Program> for VM {
@@ -82,8 +151,11 @@ impl TryFrom<&Program> for VM {
     ///
     /// ## Errors
     ///
-    /// Returns a [`VmError::Memory`] if memory insertion fails.
+    /// Returns a [`VmError::UnsupportedIsa`] if the program targets a newer instruction set,
+    /// or a [`VmError::Memory`] if memory insertion fails.
     fn try_from(program: &Program) -> Result<Self, Self::Error> {
+        program.metadata.check_isa_version()?;
+
         // Flatten program data (instructions + rodata) into memory words
         let mut memory_words = Vec::new();
         for item in &program.data {
@@ -95,6 +167,65 @@ impl TryFrom<&Program> for VM {
             }
         }
 
+        let mut vm = Self::from_memory_words(memory_words);
+        vm.load_metadata(&program.metadata);
+        Ok(vm)
+    }
+}
+
+impl<B: AsRef<[u8]>> TryFrom<&BinaryProgram<B>> for VM {
+    type Error = VmError;
+
+    /// Creates a VM instance from a given [`BinaryProgram`].
+    ///
+    /// The VM state is initialized as for a [`Program`], but the memory words are copied from
+    /// the encoded program as they are: its instructions are only decoded when executed.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`VmError::UnsupportedIsa`] if the program targets a newer instruction set.
+    fn try_from(program: &BinaryProgram<B>) -> Result<Self, Self::Error> {
+        program.metadata.check_isa_version()?;
+
+        let mut vm = Self::from_memory_words(program.memory_words().collect());
+        vm.load_metadata(&program.metadata);
+        Ok(vm)
+    }
+}
+
+/// Layout of the entry frame of an instruction sequence run with [`VM::run_instructions`].
+///
+/// The frame follows the calling convention of a function call: arguments, then return
+/// value slots, then the caller's FP and the return address are stored right below the
+/// frame pointer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFrame {
+    /// Arguments, written at `[fp - M - K - 2 + i]` for `M` arguments and `K` return values.
+    pub args: Vec<M31>,
+    /// Number of return value slots reserved below the frame pointer.
+    pub num_return_values: usize,
+    /// Values preloaded at `[fp + i]`, e.g. to set up operands without emitting stores.
+    pub locals: Vec<M31>,
+}
+
+impl EntryFrame {
+    /// Offset of the entry frame pointer from the end of the loaded instructions.
+    pub const fn fp_offset(&self) -> u32 {
+        (self.args.len() + self.num_return_values + 2) as u32
+    }
+
+    /// Frame pointer of the entry frame for a VM whose program is `program_length` words long.
+    pub fn fp(&self, program_length: M31) -> M31 {
+        program_length + M31::from(self.fp_offset())
+    }
+}
+
+impl VM {
+    /// Creates a VM with the given words loaded in memory starting at address `0`.
+    ///
+    /// The program counter (`pc`) is set to `0` and the frame pointer (`fp`) to the
+    /// address immediately following the loaded words.
+    fn from_memory_words(memory_words: Vec<QM31>) -> Self {
         // Create memory and load instructions starting at address 0
         let program_length = M31(memory_words.len() as u32);
         let final_pc = program_length;
@@ -106,7 +237,7 @@ impl TryFrom<&Program> for VM {
             fp: final_pc,
         };
 
-        Ok(Self {
+        Self {
             final_pc,
             initial_memory_locals: vec![],
             initial_memory_heap: vec![],
@@ -115,11 +246,105 @@ impl TryFrom<&Program> for VM {
             program_length,
             trace: vec![],
             segments: vec![],
-        })
+            logs: vec![],
+            abort_messages: BTreeMap::new(),
+            division_locations: BTreeMap::new(),
+            source_locations: BTreeMap::new(),
+            symbols: vec![],
+            clock_updates: vec![],
+            last_access_clocks: HashMap::new(),
+            observers: Observers::default(),
+        }
+    }
+
+    /// Loads the abort messages, source locations and symbols of a program, used to
+    /// report its errors.
+    fn load_metadata(&mut self, metadata: &ProgramMetadata) {
+        self.abort_messages = metadata.abort_messages.clone();
+        self.division_locations = metadata.division_locations.clone();
+        self.source_locations = metadata
+            .call_locations
+            .iter()
+            .chain(&metadata.division_locations)
+            .map(|(&pc, location)| (pc, location.clone()))
+            .chain(
+                metadata
+                    .abort_messages
+                    .iter()
+                    .filter_map(|(&pc, info)| Some((pc, info.location.clone()?))),
+            )
+            .collect();
+        self.symbols = metadata.symbols.clone();
+    }
+
+    /// Executes a raw instruction sequence, without packaging it into a [`Program`].
+    ///
+    /// The instructions are loaded at address `0` and executed from the first one until
+    /// the PC reaches the end of the sequence, either by falling through the last
+    /// instruction or by returning from the entry frame. This is meant for ISA-level tests,
+    /// assembled snippets and fuzzing, where building a compiled program is overkill.
+    ///
+    /// The entry frame pointer is `frame.fp(vm.program_length)`: the resulting memory can be
+    /// inspected relative to it.
+    ///
+    /// ## Arguments
+    ///
+    /// * `instructions` - The instructions to execute.
+    /// * `frame` - The layout of the entry frame.
+    /// * `options` - The runner options.
+    ///
+    /// ## Returns
+    ///
+    /// The VM after execution, holding the final memory, state, trace and segments.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`VmError`] if writing the entry frame or executing an instruction fails.
+    pub fn run_instructions(
+        instructions: &[Instruction],
+        frame: &EntryFrame,
+        options: &RunnerOptions,
+    ) -> Result<Self, VmError> {
+        let memory_words = instructions
+            .iter()
+            .flat_map(Instruction::to_qm31_vec)
+            .collect();
+        let mut vm = Self::from_memory_words(memory_words);
+
+        // Preloaded locals are part of the initial memory, without trace entries.
+        let fp = frame.fp(vm.program_length);
+        for (i, value) in frame.locals.iter().enumerate() {
+            vm.memory
+                .insert_no_trace(fp + M31::from(i as u32), (*value).into())?;
+        }
+
+        vm.run_from_entrypoint(
+            0,
+            frame.fp_offset(),
+            &frame.args,
+            frame.num_return_values,
+            options,
+        )?;
+        Ok(vm)
+    }
+
+    /// Registers an observer notified at each step of the execution.
+    ///
+    /// The owner keeps a handle on the observer to read its results after the run:
+    ///
+    /// ```ignore
+    /// let coverage = Arc::new(Mutex::new(Coverage::default()));
+    /// vm.add_observer(coverage.clone());
+    /// ```
+    ///
+    /// ## Arguments
+    ///
+    /// * `observer` - The observer, notified after the observers already registered.
+    pub fn add_observer(&mut self, observer: SharedObserver) {
+        self.memory.record_writes();
+        self.observers.push(observer);
     }
-}
 
-impl VM {
     /// Executes a single instruction at the current program counter (PC).
     ///
     /// ## Errors
@@ -132,36 +357,153 @@ impl VM {
         let instruction_m31s = self.memory.get_instruction(self.state.pc)?;
 
         let instruction: Instruction = instruction_m31s.try_into()?;
+        let state = self.state;
+        if !self.observers.is_empty() {
+            self.observers
+                .notify(|observer| observer.on_instruction(state.pc, state.fp, &instruction));
+        }
+
+        // Debug prints are captured in the log buffer. They are traced as steps advancing the
+        // pc, but the printed cells are read outside of the memory trace.
+        if instruction.is_debug_print() {
+            let log =
+                debug_log(&self.memory, self.state, &instruction).map_err(|err| match err {
+                    instructions::InstructionExecutionError::Memory(
+                        MemoryError::UninitializedMemoryCell { addr },
+                    ) => self.uninitialized_read(addr),
+                    err => err.into(),
+                })?;
+            self.logs.push(log);
+        }
 
         // Get opcode from the instruction for dispatch
         let instruction_fn = instruction_to_fn(instruction)?;
         self.trace.push(self.state);
-        self.state = instruction_fn(&mut self.memory, self.state, &instruction)?;
+        self.state = match instruction_fn(&mut self.memory, self.state, &instruction) {
+            Ok(state) => state,
+            Err(instructions::InstructionExecutionError::DivisionByZero) => {
+                let pc = self.state.pc.0;
+                return Err(VmError::DivisionByZero {
+                    pc,
+                    source_loc: self.division_locations.get(&pc).cloned(),
+                });
+            }
+            Err(instructions::InstructionExecutionError::Memory(
+                MemoryError::UninitializedMemoryCell { addr },
+            )) => return Err(self.uninitialized_read(addr)),
+            Err(err) => {
+                // Failures of `assert` and `panic` traps are reported with their message.
+                let pc = self.state.pc.0;
+                return Err(match self.abort_messages.get(&pc) {
+                    Some(info) => VmError::Aborted {
+                        pc,
+                        info: info.clone(),
+                    },
+                    None => err.into(),
+                });
+            }
+        };
+        if !self.observers.is_empty() {
+            self.notify_step(state, &instruction);
+        }
         Ok(())
     }
 
+    /// Builds the error of a strict mode read of the uninitialized cell `addr` by the current
+    /// instruction, locating the cell relative to the frame pointer.
+    fn uninitialized_read(&self, addr: M31) -> VmError {
+        VmError::UninitializedRead {
+            pc: self.state.pc.0,
+            addr,
+            fp_offset: i64::from(addr.0) - i64::from(self.state.fp.0),
+        }
+    }
+
+    /// Notifies the observers of the memory writes and the control flow of a step.
+    ///
+    /// ## Arguments
+    ///
+    /// * `state` - The state before the step.
+    /// * `instruction` - The executed instruction.
+    fn notify_step(&mut self, state: State, instruction: &Instruction) {
+        let writes = self.memory.take_recorded_writes();
+        let next = self.state;
+        self.observers.notify(|observer| {
+            for entry in &writes {
+                observer.on_memory_write(entry.addr, entry.value);
+            }
+            match instruction {
+                Instruction::CallAbsImm { .. } => observer.on_call(state.pc, next.pc, next.fp),
+                Instruction::Ret {} => observer.on_return(state.pc, next.pc, next.fp),
+                _ => {}
+            }
+        });
+    }
+
+    /// Schedules the clock updates needed by the memory accesses of the last step.
+    ///
+    /// The clock of a step is its index in the segment trace, starting at 1, as clock 0 is
+    /// reserved to the initial memory. When a memory cell is accessed more than
+    /// `max_clock_delta` steps after its previous access in the segment, clock updates are
+    /// inserted every `max_clock_delta` steps in between.
+    ///
+    /// ## Arguments
+    ///
+    /// * `memory_trace_start` - Index of the first memory trace entry of the last step.
+    /// * `max_clock_delta` - The maximum clock difference between two accesses of a cell.
+    fn schedule_clock_updates(&mut self, memory_trace_start: usize, max_clock_delta: u32) {
+        let clock = self.trace.len() as u32;
+        for entry in &self.memory.trace.borrow()[memory_trace_start..] {
+            let mut prev_clock = self
+                .last_access_clocks
+                .insert(entry.addr, clock)
+                .unwrap_or(0);
+            while clock - prev_clock > max_clock_delta {
+                prev_clock += max_clock_delta;
+                self.clock_updates.push(ClockUpdate {
+                    addr: entry.addr,
+                    clock: M31::from(prev_clock),
+                });
+            }
+        }
+    }
+
     /// Executes the loaded program from start to either completion or target number of steps is reached.
     ///
     /// This method runs the VM by repeatedly calling [`step()`](Self::step) until the program
     /// counter reaches the end of the loaded instructions. It assumes that the program is loaded
     /// at the beginning of the memory ([0..instructions_length]).
     ///
+    /// Clock updates are only tracked when the segment is long enough for a memory cell to
+    /// stay untouched for more than `options.max_clock_delta` steps.
+    ///
     /// ## Arguments
     ///
-    /// * `max_steps` - The maximum number of steps to execute, DEFAULT_max_steps by default.
+    /// * `options` - The runner options, holding the maximum number of steps of a segment and
+    ///   the maximum clock difference between two accesses of a memory cell.
     ///
     /// ## Errors
     ///
     /// Returns a [`VmError`] if any instruction execution fails:
     /// - Invalid opcodes ([`VmError::Instruction`])
     /// - Memory errors ([`VmError::Memory`])
-    fn execute(&mut self, max_steps: usize) -> Result<ExecutionStatus, VmError> {
+    /// - Failed `assert` or `panic` calls ([`VmError::Aborted`])
+    /// - Divisions by zero ([`VmError::DivisionByZero`])
+    fn execute(&mut self, options: &RunnerOptions) -> Result<ExecutionStatus, VmError> {
         if self.final_pc.is_zero() {
             return Ok(ExecutionStatus::Complete);
         }
 
+        let max_steps = options.max_steps;
+        let max_clock_delta = Some(options.max_clock_delta)
+            .filter(|&max_clock_delta| max_steps > max_clock_delta as usize);
+
         while self.state.pc != self.final_pc && self.trace.len() < max_steps {
+            let memory_trace_start = self.memory.trace.borrow().len();
             self.step()?;
+            if let Some(max_clock_delta) = max_clock_delta {
+                self.schedule_clock_updates(memory_trace_start, max_clock_delta);
+            }
         }
 
         // Push the final state to the trace
@@ -219,10 +561,14 @@ impl VM {
                 ),
         );
 
+        // Clocks restart at each segment
+        self.last_access_clocks.clear();
+
         self.segments.push(Segment {
             initial_memory,
             memory_trace: std::mem::take(&mut self.memory.trace),
             trace: std::mem::take(&mut self.trace),
+            clock_updates: std::mem::take(&mut self.clock_updates),
         });
     }
 
@@ -240,12 +586,22 @@ impl VM {
     /// * `fp_offset` - The frame pointer (FP) offset to start execution from.
     /// * `args` - The arguments to pass to the function.
     /// * `num_return_values` - The number of return values to expect from the function.
+    /// * `options` - The runner options.
     ///
     /// ## Errors
     ///
+    /// Returns a [`VmError::InvalidMaxClockDelta`] if `options.max_clock_delta` is zero or
+    /// above [`MAX_CLOCK_DELTA`].
+    ///
     /// Returns a [`VmError`] if any instruction execution fails:
     /// - Invalid opcodes ([`VmError::Instruction`])
     /// - Memory errors ([`VmError::Memory`])
+    /// - Failed `assert` or `panic` calls ([`VmError::Aborted`])
+    /// - Divisions by zero ([`VmError::DivisionByZero`])
+    /// - Reads of never written cells in strict mode ([`VmError::UninitializedRead`])
+    ///
+    /// When the program has a symbol table, execution errors are wrapped in a
+    /// [`VmError::WithBacktrace`] holding the call stack at the failing instruction.
     pub fn run_from_entrypoint(
         &mut self,
         pc_entrypoint: u32,
@@ -254,6 +610,17 @@ impl VM {
         num_return_values: usize,
         options: &RunnerOptions,
     ) -> Result<(), VmError> {
+        if !(1..=MAX_CLOCK_DELTA).contains(&options.max_clock_delta) {
+            return Err(VmError::InvalidMaxClockDelta(options.max_clock_delta));
+        }
+        self.memory.limits = MemoryLimits {
+            max_memory_cells: options.max_memory_cells,
+            max_heap_cells: options.max_heap_cells,
+        };
+        if options.strict_memory {
+            self.memory.enable_strict_mode();
+        }
+
         // Write arguments to memory before the frame pointer
         // Arguments should be at [new_fp - M - K - 2 + i] for arg i
         // Writing the arguments does not log an trace entry.
@@ -275,10 +642,16 @@ impl VM {
         self.initial_memory_heap = self.memory.heap.clone();
 
         loop {
-            match self.execute(options.max_steps) {
+            match self.execute(options) {
                 Ok(ExecutionStatus::Complete) => break self.finalize_segment(true),
                 Ok(ExecutionStatus::Ongoing) => self.finalize_segment(false),
-                Err(e) => return Err(e),
+                Err(e) if self.symbols.is_empty() => return Err(e),
+                Err(e) => {
+                    return Err(VmError::WithBacktrace {
+                        error: Box::new(e),
+                        backtrace: self.backtrace(),
+                    });
+                }
             }
         }
         Ok(())
@@ -304,18 +677,9 @@ impl VM {
     /// - Any file cannot be created or opened for writing
     /// - Writing to any file fails
     pub fn write_binary_trace<P: AsRef<Path>>(&self, path: P) -> Result<(), VmError> {
-        let path = path.as_ref();
-        let path_str = path.to_string_lossy();
-
-        // Extract base name and extension
-        let (base, ext) = path_str.rfind('.').map_or_else(
-            || (path_str.as_ref(), ""),
-            |dot_pos| (&path_str[..dot_pos], &path_str[dot_pos..]),
-        );
-
         // Write each segment's trace
         for (i, segment) in self.segments.iter().enumerate() {
-            let segment_path = format!("{}_segment_{}{}", base, i, ext);
+            let segment_path = export::segment_path(path.as_ref(), i);
             let serialized_trace = segment.serialize_segment_trace();
             let mut file = File::create(&segment_path)?;
             file.write_all(&serialized_trace)?;
@@ -347,18 +711,9 @@ impl VM {
     /// - Any file cannot be created or opened for writing
     /// - Writing to any file fails
     pub fn write_binary_memory_trace<P: AsRef<Path>>(&self, path: P) -> Result<(), VmError> {
-        let path = path.as_ref();
-        let path_str = path.to_string_lossy();
-
-        // Extract base name and extension
-        let (base, ext) = path_str.rfind('.').map_or_else(
-            || (path_str.as_ref(), ""),
-            |dot_pos| (&path_str[..dot_pos], &path_str[dot_pos..]),
-        );
-
         // Write each segment's memory trace
         for (i, segment) in self.segments.iter().enumerate() {
-            let segment_path = format!("{}_segment_{}{}", base, i, ext);
+            let segment_path = export::segment_path(path.as_ref(), i);
             let mut file = File::create(&segment_path)?;
 
             // Write program length
@@ -0,0 +1,83 @@
+//! Hooks for external observers of the execution of the VM.
+//!
+//! Observers are registered on a [`VM`](super::VM) with
+//! [`VM::add_observer`](super::VM::add_observer) and notified at each step, which lets
+//! coverage tools, debuggers and profilers follow an execution without forking the VM loop.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use cairo_m_common::Instruction;
+use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::fields::qm31::QM31;
+
+/// Observer of the execution of the VM.
+///
+/// Every event has an empty default implementation, so that observers only implement the
+/// events they need. The events of a step are emitted in order: [`on_instruction`] before
+/// the execution of the instruction, then its memory writes, then the call or return.
+///
+/// [`on_instruction`]: ExecutionObserver::on_instruction
+pub trait ExecutionObserver: Send {
+    /// Called before the execution of each instruction, debug prints included.
+    ///
+    /// ## Arguments
+    /// * `pc` - The address of the instruction
+    /// * `fp` - The frame pointer of the instruction
+    /// * `instruction` - The decoded instruction
+    fn on_instruction(&mut self, _pc: M31, _fp: M31, _instruction: &Instruction) {}
+
+    /// Called after the execution of a `call`.
+    ///
+    /// ## Arguments
+    /// * `pc` - The address of the `call` instruction
+    /// * `target_pc` - The address of the callee
+    /// * `callee_fp` - The frame pointer of the callee
+    fn on_call(&mut self, _pc: M31, _target_pc: M31, _callee_fp: M31) {}
+
+    /// Called after the execution of a `ret`.
+    ///
+    /// ## Arguments
+    /// * `pc` - The address of the `ret` instruction
+    /// * `return_pc` - The address execution returns to
+    /// * `caller_fp` - The restored frame pointer of the caller
+    fn on_return(&mut self, _pc: M31, _return_pc: M31, _caller_fp: M31) {}
+
+    /// Called after the execution of an instruction, for each memory cell it wrote.
+    ///
+    /// ## Arguments
+    /// * `addr` - The address of the written cell
+    /// * `value` - The written value
+    fn on_memory_write(&mut self, _addr: M31, _value: QM31) {}
+}
+
+/// An observer shared between the VM and its owner, who reads its results after the run.
+pub type SharedObserver = Arc<Mutex<dyn ExecutionObserver>>;
+
+/// The observers registered on a VM.
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<SharedObserver>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: SharedObserver) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Notifies every observer, in registration order.
+    pub(crate) fn notify(&self, mut event: impl FnMut(&mut dyn ExecutionObserver)) {
+        for observer in &self.0 {
+            // A panicking observer must not prevent the others from being notified.
+            event(&mut *observer.lock().unwrap_or_else(PoisonError::into_inner));
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
+use std::sync::{Arc, Mutex};
 
-use cairo_m_common::instruction::InstructionError;
+use cairo_m_common::instruction::{ISA_VERSION, InstructionError};
 use cairo_m_common::{Instruction, Program, ProgramData, State};
 use num_traits::{One, Zero};
 use stwo_prover::core::fields::m31::M31;
@@ -10,8 +12,11 @@ use stwo_prover::core::fields::qm31::QM31;
 // Import test utilities
 use super::test_utils::*;
 use crate::RunnerOptions;
-use crate::memory::Memory;
-use crate::vm::{VM, VmError};
+use crate::memory::{Memory, MemoryError};
+use crate::vm::instructions::InstructionExecutionError;
+use crate::vm::instructions::print::DebugValue;
+use crate::vm::observer::ExecutionObserver;
+use crate::vm::{EntryFrame, VM, VmError};
 
 #[test]
 fn test_program_from_vec_instructions() {
@@ -109,6 +114,20 @@ fn test_vm_try_from() {
     assert_eq!(loaded_instruction_2, instructions[1]);
 }
 
+#[test]
+fn test_vm_try_from_unsupported_isa() {
+    let mut program: Program = vec![Instruction::Ret {}].into();
+    program.metadata.isa_version = Some(ISA_VERSION);
+    assert!(VM::try_from(&program).is_ok());
+
+    program.metadata.isa_version = Some(ISA_VERSION + 1);
+    let error = VM::try_from(&program).unwrap_err();
+    assert!(matches!(
+        error,
+        VmError::UnsupportedIsa(error) if error.required == ISA_VERSION + 1
+    ));
+}
+
 #[test]
 fn test_step_single_instruction() {
     // Create a program with a single store_imm instruction: [fp + 0] = 42
@@ -159,7 +178,7 @@ fn test_step_invalid_instruction() {
 fn test_execute_empty_program() {
     let program = Program::from(vec![]);
     let mut vm = VM::try_from(&program).unwrap();
-    let result = vm.execute(RunnerOptions::default().max_steps);
+    let result = vm.execute(&RunnerOptions::default());
     assert!(result.is_ok());
     assert_vm_state!(vm.state, 0, 0);
     assert_eq!(vm.memory.locals.len(), 0);
@@ -176,7 +195,7 @@ fn test_execute_single_instruction() {
     let program = Program::from(instructions);
     let mut vm = VM::try_from(&program).unwrap();
 
-    let result = vm.execute(RunnerOptions::default().max_steps);
+    let result = vm.execute(&RunnerOptions::default());
     assert!(result.is_ok());
 
     // PC should be at final position (memory.len() = 1)
@@ -213,7 +232,7 @@ fn test_execute_multiple_instructions() {
     // Initial state
     assert_vm_state!(vm.state, 0, 3); // FP should be after 3 instructions
 
-    let result = vm.execute(RunnerOptions::default().max_steps);
+    let result = vm.execute(&RunnerOptions::default());
     assert!(result.is_ok());
 
     // PC should be at final position (memory.len() = 3)
@@ -225,6 +244,41 @@ fn test_execute_multiple_instructions() {
     assert_memory_value!(vm, addr = 5, value = 15); // [fp + 2] = 15
 }
 
+#[test]
+fn test_execute_debug_print_traces_only_the_step() {
+    // 1. [fp + 0] = 42 (store_imm)
+    // 2. print [fp + 0]
+    // 3. [fp + 1] = 7 (store_imm)
+    let instructions = vec![
+        Instruction::StoreImm {
+            imm: M31(42),
+            dst_off: M31(0),
+        },
+        Instruction::PrintM31 { offset: M31(0) },
+        Instruction::StoreImm {
+            imm: M31(7),
+            dst_off: M31(1),
+        },
+    ];
+    let program = Program::from(instructions);
+    let mut vm = VM::try_from(&program).unwrap();
+
+    let result = vm.execute(&RunnerOptions::default());
+    assert!(result.is_ok());
+    assert_vm_state!(vm.state, 3, 3);
+
+    // The print is captured in the logs...
+    assert_eq!(vm.logs.len(), 1);
+    assert_eq!(vm.logs[0].pc, M31(1));
+    assert_eq!(vm.logs[0].value, DebugValue::M31(M31(42)));
+
+    // ...and traced as a step (2 stores + print + final state)
+    assert_eq!(vm.trace.len(), 4);
+    assert_eq!(vm.trace[1].pc, M31(1));
+    // whose printed cell is not in the memory trace (2 x (fetch + write) + fetch)
+    assert_eq!(vm.memory.trace.borrow().len(), 5);
+}
+
 #[test]
 fn test_execute_with_error() {
     // Create a program with an invalid instructions
@@ -246,9 +300,12 @@ fn test_execute_with_error() {
         program_length: M31::from(instructions.len() as u32),
         trace: vec![],
         segments: vec![],
+        logs: vec![],
+        abort_messages: BTreeMap::new(),
+        ..Default::default()
     };
     // Execute should fail when it hits the invalid instruction
-    let result = vm.execute(RunnerOptions::default().max_steps);
+    let result = vm.execute(&RunnerOptions::default());
     assert!(result.is_err());
     assert!(matches!(
         result.err().unwrap(),
@@ -295,7 +352,7 @@ fn test_execute_arithmetic_operations() {
     let program = Program::from(instructions);
     let mut vm = VM::try_from(&program).unwrap();
 
-    let result = vm.execute(RunnerOptions::default().max_steps);
+    let result = vm.execute(&RunnerOptions::default());
     assert!(result.is_ok());
 
     // Check all computed values
@@ -333,6 +390,109 @@ fn test_run_from_entrypoint() {
     );
 }
 
+#[test]
+fn test_run_instructions_with_entry_frame() {
+    let instructions = [
+        Instruction::StoreAddFpImm {
+            src_off: M31(0),
+            imm: M31(5),
+            dst_off: M31(1),
+        }, // [fp + 1] = [fp] + 5
+        Instruction::StoreAddFpFp {
+            src0_off: M31::from(-4),
+            src1_off: M31(1),
+            dst_off: M31::from(-3),
+        }, // return value = arg + [fp + 1]
+        Instruction::Ret {},
+    ];
+    let frame = EntryFrame {
+        args: vec![M31(3)],
+        num_return_values: 1,
+        locals: vec![M31(7)],
+    };
+
+    let vm = VM::run_instructions(&instructions, &frame, &RunnerOptions::default()).unwrap();
+    let fp = frame.fp(vm.program_length);
+    assert_eq!(fp, M31(7));
+
+    // Returning from the entry frame jumps to the final PC and restores the initial FP.
+    assert_vm_state!(vm.state, 3, 3);
+    assert_eq!(vm.trace.len(), 0);
+    assert_eq!(vm.segments[0].trace.len(), 4);
+    assert_eq!(vm.memory.get_data(fp + M31::one()).unwrap(), M31(12));
+    assert_eq!(vm.memory.get_data(fp - M31(3)).unwrap(), M31(15));
+}
+
+#[test]
+fn test_run_instructions_from_casm() {
+    let instructions = cairo_m_common::casm::parse(
+        "
+        store_imm 10, 0
+        store_imm 20, 1
+        store_add_fp_fp 0, 1, 2
+        ",
+    )
+    .unwrap();
+
+    let vm = VM::run_instructions(
+        &instructions,
+        &EntryFrame::default(),
+        &RunnerOptions::default(),
+    )
+    .unwrap();
+    let fp = EntryFrame::default().fp(vm.program_length);
+
+    // The sequence falls through its last instruction to the final PC.
+    assert_vm_state!(vm.state, 3, fp.0);
+    assert_eq!(vm.memory.get_data(fp + M31(2)).unwrap(), M31(30));
+}
+
+#[test]
+fn test_memory_history() {
+    let instructions = cairo_m_common::casm::parse(
+        "
+        store_imm 10, 0
+        store_imm 20, 0
+        store_add_fp_fp 0, 0, 1
+        ",
+    )
+    .unwrap();
+    // Two steps per segment, so that the history spans segments.
+    let options = RunnerOptions {
+        max_steps: 2,
+        ..Default::default()
+    };
+    let vm = VM::run_instructions(&instructions, &EntryFrame::default(), &options).unwrap();
+    assert_eq!(vm.segments.len(), 2);
+    let fp = EntryFrame::default().fp(vm.program_length);
+
+    let history: Vec<_> = vm
+        .history(fp)
+        .iter()
+        .map(|access| (access.clock, access.value))
+        .collect();
+    assert_eq!(
+        history,
+        [
+            (1, M31(10).into()),
+            (2, M31(20).into()),
+            (3, M31(20).into()),
+            (3, M31(20).into())
+        ]
+    );
+
+    // Instructions are part of the initial memory, and fetched at each execution.
+    let program_word = vm.history(M31(1));
+    assert_eq!(program_word.len(), 2);
+    assert_eq!((program_word[0].clock, program_word[1].clock), (0, 2));
+
+    assert_eq!(vm.memory_at_clock(fp, 0), None);
+    assert_eq!(vm.memory_at_clock(fp, 1), Some(M31(10).into()));
+    assert_eq!(vm.memory_at_clock(fp, 5), Some(M31(20).into()));
+    assert_eq!(vm.memory_at_clock(fp + M31::one(), 2), None);
+    assert_eq!(vm.memory_at_clock(fp + M31::one(), 3), Some(M31(40).into()));
+}
+
 #[test]
 fn test_serialize_trace() {
     // Create a program with two instructions to generate a trace.
@@ -350,7 +510,7 @@ fn test_serialize_trace() {
     let mut vm = VM::try_from(&program).unwrap();
 
     // Execute the program to generate a trace.
-    assert!(vm.execute(RunnerOptions::default().max_steps).is_ok());
+    assert!(vm.execute(&RunnerOptions::default()).is_ok());
 
     // The trace should have 3 entries, one for each instruction executed.
     // The last one is the final state of the VM.
@@ -471,7 +631,7 @@ fn run_fib_test(n: u32) {
     let program = Program::from(instructions);
     let mut vm = VM::try_from(&program).unwrap();
 
-    assert!(vm.execute(RunnerOptions::default().max_steps).is_ok());
+    assert!(vm.execute(&RunnerOptions::default()).is_ok());
     // Verify that FP is still at the end of the program
     // Verify PC reached the end of the program
     assert_vm_state!(vm.state, instructions_len, instructions_len);
@@ -616,6 +776,117 @@ fn run_exponential_recursive_fib_test(n: u32) {
     assert_memory_value!(vm, addr = vm.state.fp - M31(3), value = fib(n));
 }
 
+#[derive(Debug, PartialEq, Eq)]
+enum ObservedEvent {
+    Step { pc: M31, fp: M31 },
+    Call { pc: M31, target_pc: M31, fp: M31 },
+    Return { pc: M31, return_pc: M31, fp: M31 },
+    Write { addr: M31, value: QM31 },
+}
+
+#[derive(Default)]
+struct RecordingObserver(Vec<ObservedEvent>);
+
+impl ExecutionObserver for RecordingObserver {
+    fn on_instruction(&mut self, pc: M31, fp: M31, _instruction: &Instruction) {
+        self.0.push(ObservedEvent::Step { pc, fp });
+    }
+
+    fn on_call(&mut self, pc: M31, target_pc: M31, callee_fp: M31) {
+        self.0.push(ObservedEvent::Call {
+            pc,
+            target_pc,
+            fp: callee_fp,
+        });
+    }
+
+    fn on_return(&mut self, pc: M31, return_pc: M31, caller_fp: M31) {
+        self.0.push(ObservedEvent::Return {
+            pc,
+            return_pc,
+            fp: caller_fp,
+        });
+    }
+
+    fn on_memory_write(&mut self, addr: M31, value: QM31) {
+        self.0.push(ObservedEvent::Write { addr, value });
+    }
+}
+
+#[test]
+fn test_execution_observers() {
+    use ObservedEvent::*;
+
+    let instructions = vec![
+        Instruction::CallAbsImm {
+            frame_off: M31(0),
+            target: M31(2),
+        }, // 0: call the function at 2
+        Instruction::Ret {}, // 1: ret
+        Instruction::StoreImm {
+            imm: M31(5),
+            dst_off: M31(0),
+        }, // 2: [fp] = 5
+        Instruction::Ret {}, // 3: ret
+    ];
+    let program = Program::from(instructions);
+    let mut vm = VM::try_from(&program).unwrap();
+    let observers = [
+        Arc::new(Mutex::new(RecordingObserver::default())),
+        Arc::new(Mutex::new(RecordingObserver::default())),
+    ];
+    for observer in &observers {
+        vm.add_observer(observer.clone());
+    }
+    vm.run_from_entrypoint(0, 2, &[], 0, &RunnerOptions::default())
+        .unwrap();
+
+    let fp = M31(6);
+    let callee_fp = fp + M31(2);
+    let expected = vec![
+        Step { pc: M31(0), fp },
+        Write {
+            addr: fp,
+            value: fp.into(),
+        },
+        Write {
+            addr: fp + M31(1),
+            value: M31(1).into(),
+        },
+        Call {
+            pc: M31(0),
+            target_pc: M31(2),
+            fp: callee_fp,
+        },
+        Step {
+            pc: M31(2),
+            fp: callee_fp,
+        },
+        Write {
+            addr: callee_fp,
+            value: M31(5).into(),
+        },
+        Step {
+            pc: M31(3),
+            fp: callee_fp,
+        },
+        Return {
+            pc: M31(3),
+            return_pc: M31(1),
+            fp,
+        },
+        Step { pc: M31(1), fp },
+        Return {
+            pc: M31(1),
+            return_pc: M31(4),
+            fp: M31(4),
+        },
+    ];
+    for observer in &observers {
+        assert_eq!(observer.lock().unwrap().0, expected);
+    }
+}
+
 #[test]
 fn test_write_binary_trace_per_segment() {
     // Create a program that will be executed with segments
@@ -638,7 +909,16 @@ fn test_write_binary_trace_per_segment() {
     let mut vm = VM::try_from(&program).unwrap();
 
     // Execute with segments - this will hit step limit and create segments
-    let _ = vm.run_from_entrypoint(0, 3, &[], 0, &RunnerOptions { max_steps: 2 });
+    let _ = vm.run_from_entrypoint(
+        0,
+        3,
+        &[],
+        0,
+        &RunnerOptions {
+            max_steps: 2,
+            ..Default::default()
+        },
+    );
 
     // Create a temporary directory for the trace files
     let temp_dir = tempfile::tempdir().unwrap();
@@ -688,7 +968,16 @@ fn test_write_binary_memory_trace_per_segment() {
     let mut vm = VM::try_from(&program).unwrap();
 
     // Execute with segments (limit steps to create multiple segments)
-    let _ = vm.run_from_entrypoint(0, 3, &[], 0, &RunnerOptions { max_steps: 2 });
+    let _ = vm.run_from_entrypoint(
+        0,
+        3,
+        &[],
+        0,
+        &RunnerOptions {
+            max_steps: 2,
+            ..Default::default()
+        },
+    );
 
     // Create a temporary directory for the memory trace files
     let temp_dir = tempfile::tempdir().unwrap();
@@ -737,6 +1026,142 @@ fn test_write_binary_memory_trace_per_segment() {
     }
 }
 
+/// Writes 4 cells, then adds the first two of them at clock 5.
+fn run_with_max_clock_delta(max_clock_delta: u32) -> Result<VM, VmError> {
+    run_stores_and_add(&RunnerOptions {
+        max_clock_delta,
+        ..Default::default()
+    })
+}
+
+/// Writes 4 cells, then adds the first two of them at clock 5, with the given options.
+fn run_stores_and_add(options: &RunnerOptions) -> Result<VM, VmError> {
+    let mut instructions = (0..4)
+        .map(|i| Instruction::StoreImm {
+            imm: M31(i + 1),
+            dst_off: M31(i),
+        })
+        .collect::<Vec<_>>();
+    instructions.push(Instruction::StoreAddFpFp {
+        src0_off: M31(0),
+        src1_off: M31(1),
+        dst_off: M31(4),
+    });
+    let program = Program::from(instructions);
+    let mut vm = VM::try_from(&program).unwrap();
+    vm.run_from_entrypoint(0, 3, &[], 0, options)?;
+    Ok(vm)
+}
+
+#[test]
+fn test_clock_updates_are_scheduled_for_large_clock_gaps() {
+    let vm = run_with_max_clock_delta(2).unwrap();
+    let fp = vm.state.fp;
+    let clock_updates = &vm.segments[0].clock_updates;
+    let update_clocks = |addr: M31| {
+        clock_updates
+            .iter()
+            .filter(|update| update.addr == addr)
+            .map(|update| update.clock.0)
+            .collect::<Vec<_>>()
+    };
+
+    // [fp] is written at clock 1 and read at clock 5
+    assert_eq!(update_clocks(fp), vec![3]);
+    // [fp + 1] is written at clock 2 and read at clock 5
+    assert_eq!(update_clocks(fp + M31(1)), vec![4]);
+    // [fp + 4] is first written at clock 5, after the initial memory at clock 0
+    assert_eq!(update_clocks(fp + M31(4)), vec![2, 4]);
+}
+
+#[test]
+fn test_no_clock_updates_by_default() {
+    let vm = run_with_max_clock_delta(RunnerOptions::default().max_clock_delta).unwrap();
+    assert!(vm.segments[0].clock_updates.is_empty());
+}
+
+#[test]
+fn test_max_memory_cells() {
+    let memory_cells = run_stores_and_add(&RunnerOptions::default())
+        .unwrap()
+        .memory
+        .locals
+        .len();
+    let run = |max_memory_cells| {
+        run_stores_and_add(&RunnerOptions {
+            max_memory_cells: Some(max_memory_cells),
+            max_heap_cells: Some(0),
+            ..Default::default()
+        })
+    };
+
+    assert!(run(memory_cells).is_ok());
+    assert!(matches!(
+        run(memory_cells - 1),
+        Err(VmError::InstructionExecution(
+            InstructionExecutionError::Memory(MemoryError::ResourceExhausted {
+                resource: "memory",
+                limit,
+                memory_cells: required,
+                heap_cells: 0,
+                ..
+            })
+        )) if limit == memory_cells - 1 && required == memory_cells
+    ));
+}
+
+#[test]
+fn test_strict_memory_reports_uninitialized_reads() {
+    let program = Program::from(vec![
+        Instruction::StoreImm {
+            imm: M31(1),
+            dst_off: M31(0),
+        },
+        Instruction::StoreAddFpFp {
+            src0_off: M31(0),
+            src1_off: M31(5),
+            dst_off: M31(1),
+        },
+    ]);
+    let run = |strict_memory| {
+        let mut vm = VM::try_from(&program).unwrap();
+        vm.run_from_entrypoint(
+            0,
+            3,
+            &[],
+            0,
+            &RunnerOptions {
+                strict_memory,
+                ..Default::default()
+            },
+        )
+        .map(|()| vm)
+    };
+
+    // [fp + 5] is read as zero by default
+    assert!(run(false).is_ok());
+
+    let fp = VM::try_from(&program).unwrap().state.fp + M31(3);
+    assert!(matches!(
+        run(true),
+        Err(VmError::UninitializedRead {
+            pc: 1,
+            addr,
+            fp_offset: 5,
+        }) if addr == fp + M31(5)
+    ));
+}
+
+#[test]
+fn test_invalid_max_clock_delta() {
+    for max_clock_delta in [0, crate::MAX_CLOCK_DELTA + 1] {
+        assert!(matches!(
+            run_with_max_clock_delta(max_clock_delta),
+            Err(VmError::InvalidMaxClockDelta(delta)) if delta == max_clock_delta
+        ));
+    }
+}
+
 #[test]
 fn test_5_m31_u32_instruction_execution_and_pc_advancement() {
     use cairo_m_common::Instruction;
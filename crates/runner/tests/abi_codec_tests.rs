@@ -1,6 +1,7 @@
 use cairo_m_common::{CairoMValue, InputValue};
 use cairo_m_compiler::{CompilerOptions, compile_cairo};
-use cairo_m_runner::{RunnerOptions, run_cairo_program};
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+use stwo_prover::core::fields::m31::M31;
 
 /// Tests for the ABI encoding and decoding of values when calling entrypoints.
 /// These tests verify the proper handling of:
@@ -501,3 +502,59 @@ fn test_large_array() {
         _ => panic!("Expected Felt return value"),
     }
 }
+
+#[test]
+fn test_default_arguments() {
+    let source = r#"
+        fn scale(x: felt, factor: felt = 3, enabled: bool = true) -> felt {
+            if enabled {
+                return x * factor;
+            }
+            return x;
+        }
+    "#;
+
+    let compiled = compile_cairo(
+        source.to_string(),
+        "test.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile");
+    let params = &compiled.program.entrypoints["scale"].params;
+    assert_eq!(params[0].default, None);
+    assert_eq!(params[1].default, Some(InputValue::Number(3)));
+    assert_eq!(params[2].default, Some(InputValue::Bool(true)));
+
+    let run = |args: &[InputValue]| {
+        run_cairo_program(&compiled.program, "scale", args, RunnerOptions::default())
+            .map(|output| output.return_values)
+    };
+
+    // Omitted trailing arguments take their default values
+    assert_eq!(
+        run(&[InputValue::Number(5)]).unwrap(),
+        vec![CairoMValue::Felt(M31::from(15))]
+    );
+    assert_eq!(
+        run(&[InputValue::Number(5), InputValue::Number(4)]).unwrap(),
+        vec![CairoMValue::Felt(M31::from(20))]
+    );
+    assert_eq!(
+        run(&[
+            InputValue::Number(5),
+            InputValue::Number(4),
+            InputValue::Bool(false)
+        ])
+        .unwrap(),
+        vec![CairoMValue::Felt(M31::from(5))]
+    );
+
+    // Parameters without default cannot be omitted
+    assert!(matches!(
+        run(&[]),
+        Err(RunnerError::ArgumentCountMismatch {
+            expected: 3,
+            provided: 0
+        })
+    ));
+}
@@ -0,0 +1,108 @@
+use cairo_m_common::{CairoMValue, InputValue};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::vm::VmError;
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+use stwo_prover::core::fields::m31::M31;
+
+/// Tests for the `assert(cond, "msg")` and `panic("msg")` builtins: a failing call
+/// aborts execution with its message and source location.
+
+const SOURCE: &str = r#"fn checked_div(x: felt, y: felt) -> felt {
+    assert(y != 0, "division by zero");
+    return x / y;
+}
+
+fn always_panics(x: felt) -> felt {
+    if x == 0 {
+        return 1;
+    }
+    panic("unsupported input");
+}
+
+fn calls_checked_div(x: felt) -> felt {
+    let y = x - 1;
+    return checked_div(x, y);
+}
+"#;
+
+fn run(entrypoint: &str, args: &[InputValue]) -> Result<CairoMValue, RunnerError> {
+    let compiled = compile_cairo(
+        SOURCE.to_string(),
+        "abort.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile");
+    let output = run_cairo_program(
+        &compiled.program,
+        entrypoint,
+        args,
+        RunnerOptions::default(),
+    )?;
+    Ok(output.return_values[0].clone())
+}
+
+fn expect_abort(result: Result<CairoMValue, RunnerError>) -> (String, Option<String>) {
+    match result {
+        Err(RunnerError::VmError(error)) => match error.inner() {
+            VmError::Aborted { info, .. } => (info.message.clone(), info.location.clone()),
+            other => panic!("Expected an abort, got {other:?}"),
+        },
+        other => panic!("Expected an abort, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_assert_with_message_passes() {
+    let value = run(
+        "checked_div",
+        &[InputValue::Number(10), InputValue::Number(2)],
+    )
+    .expect("Failed to run program");
+    assert_eq!(value, CairoMValue::Felt(M31::from(5)));
+}
+
+#[test]
+fn test_assert_with_message_aborts() {
+    let (message, location) = expect_abort(run(
+        "checked_div",
+        &[InputValue::Number(10), InputValue::Number(0)],
+    ));
+    assert_eq!(message, "division by zero");
+    assert_eq!(location.as_deref(), Some("abort.cm:2:5"));
+}
+
+#[test]
+fn test_panic_aborts() {
+    let value = run("always_panics", &[InputValue::Number(0)]).expect("Failed to run program");
+    assert_eq!(value, CairoMValue::Felt(M31::from(1)));
+
+    let (message, location) = expect_abort(run("always_panics", &[InputValue::Number(3)]));
+    assert_eq!(message, "unsupported input");
+    assert_eq!(location.as_deref(), Some("abort.cm:9:5"));
+}
+
+#[test]
+fn test_abort_backtrace() {
+    let error = match run("calls_checked_div", &[InputValue::Number(1)]) {
+        Err(RunnerError::VmError(error)) => error,
+        other => panic!("Expected an abort, got {other:?}"),
+    };
+    let backtrace = error.backtrace().expect("Expected a backtrace");
+    let functions: Vec<_> = backtrace
+        .frames
+        .iter()
+        .map(|frame| frame.function.as_deref())
+        .collect();
+    assert_eq!(functions, [Some("checked_div"), Some("calls_checked_div")]);
+    let locations: Vec<_> = backtrace
+        .frames
+        .iter()
+        .map(|frame| frame.location.as_deref())
+        .collect();
+    assert_eq!(locations, [Some("abort.cm:2:5"), Some("abort.cm:15:12")]);
+    assert!(matches!(error.inner(), VmError::Aborted { .. }));
+    assert!(
+        error.to_string().contains("1: calls_checked_div at pc"),
+        "{error}"
+    );
+}
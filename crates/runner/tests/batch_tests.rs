@@ -0,0 +1,84 @@
+use cairo_m_common::{InputValue, Program};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program, run_cairo_program_batch};
+
+/// Tests for running one program over many sets of arguments.
+
+const SOURCE: &str = r#"fn scale(x: felt, factor: felt) -> felt {
+    assert(factor != 0, "zero factor");
+    let acc = 0;
+    let i = 0;
+    while i != factor {
+        acc = acc + x;
+        i = i + 1;
+    }
+    return acc;
+}
+"#;
+
+fn compile() -> Program {
+    let compiled = compile_cairo(
+        SOURCE.to_string(),
+        "batch.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile");
+    (*compiled.program).clone()
+}
+
+#[test]
+fn test_batch_runs_match_single_runs() {
+    let program = compile();
+    let inputs: Vec<Vec<InputValue>> = (1..5)
+        .map(|factor| vec![InputValue::Number(7), InputValue::Number(factor)])
+        .collect();
+
+    let outputs =
+        run_cairo_program_batch(&program, "scale", &inputs, RunnerOptions::default()).unwrap();
+    assert_eq!(outputs.len(), inputs.len());
+
+    for (args, output) in inputs.iter().zip(outputs) {
+        let output = output.unwrap();
+        let expected =
+            run_cairo_program(&program, "scale", args, RunnerOptions::default()).unwrap();
+        assert_eq!(output.return_values, expected.return_values);
+        assert_eq!(output.vm.trace, expected.vm.trace);
+        assert_eq!(output.public_address_ranges, expected.public_address_ranges);
+    }
+}
+
+#[test]
+fn test_batch_failures_are_reported_per_run() {
+    let program = compile();
+    let inputs = vec![
+        vec![InputValue::Number(3), InputValue::Number(0)],
+        vec![InputValue::Number(3)],
+        vec![InputValue::Number(3), InputValue::Number(2)],
+    ];
+
+    let outputs =
+        run_cairo_program_batch(&program, "scale", &inputs, RunnerOptions::default()).unwrap();
+    assert!(matches!(outputs[0], Err(RunnerError::VmError(_))));
+    assert!(matches!(
+        outputs[1],
+        Err(RunnerError::ArgumentCountMismatch { .. })
+    ));
+    assert_eq!(
+        outputs[2].as_ref().unwrap().return_values,
+        run_cairo_program(&program, "scale", &inputs[2], RunnerOptions::default())
+            .unwrap()
+            .return_values
+    );
+}
+
+#[test]
+fn test_batch_with_unknown_entrypoint_fails() {
+    let program = compile();
+    let result = run_cairo_program_batch(
+        &program,
+        "missing",
+        &[vec![InputValue::Number(1)]],
+        RunnerOptions::default(),
+    );
+    assert!(matches!(result, Err(RunnerError::EntryPointNotFound(..))));
+}
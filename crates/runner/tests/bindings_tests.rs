@@ -0,0 +1,58 @@
+//! Compilation of the Rust bindings generated for a program.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use cairo_m_common::Program;
+use cairo_m_common::program::{AbiSlot, AbiType, EntrypointInfo};
+use cairo_m_runner::bindings::bindings_source;
+
+fn slot(name: &str, ty: AbiType) -> AbiSlot {
+    AbiSlot {
+        name: name.to_string(),
+        ty,
+        default: None,
+    }
+}
+
+fn point(module: &str, fields: &[(&str, AbiType)]) -> AbiType {
+    AbiType::Struct {
+        name: format!("{}::Point", module),
+        fields: fields
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.clone()))
+            .collect(),
+    }
+}
+
+/// Structs named `Point` in two modules must generate two distinct Rust structs.
+#[test]
+fn test_bindings_with_homonym_structs_compile() {
+    let a_point = point("a", &[("x", AbiType::Felt), ("y", AbiType::U32)]);
+    let b_point = point("b", &[("x", AbiType::Felt)]);
+    let mut program = Program::from(vec![]);
+    program.entrypoints = HashMap::from([(
+        "swap".to_string(),
+        EntrypointInfo {
+            pc: 0,
+            params: vec![slot("p", a_point.clone()), slot("q", b_point.clone())],
+            returns: vec![slot("", b_point), slot("", a_point)],
+        },
+    )]);
+
+    let source = format!(
+        "{}
+fn main() {{
+    let _ = APoint {{ x: M31::from(1), y: 2 }};
+    let _ = BPoint {{ x: M31::from(3) }};
+    let _: fn(&Program, RunnerOptions, APoint, BPoint) -> cairo_m_runner::Result<(BPoint, APoint)> =
+        swap;
+}}
+",
+        bindings_source(&program)
+    );
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("homonym_structs_bindings.rs");
+    std::fs::write(&path, source).unwrap();
+
+    trybuild::TestCases::new().pass(&path);
+}
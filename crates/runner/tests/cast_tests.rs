@@ -0,0 +1,42 @@
+use cairo_m_common::{CairoMValue, InputValue, Program};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+
+/// Tests for the failing casts to `bool`, which abort unless the value is 0 or 1.
+/// The successful casts are checked in `mdtest/01-basics/08-type-casts.md`.
+
+const SOURCE: &str = r#"
+fn felt_to_bool(x: felt) -> bool {
+    return x as bool;
+}
+
+fn u32_to_bool(x: u32) -> bool {
+    return x as bool;
+}
+"#;
+
+fn compile(options: CompilerOptions) -> Program {
+    compile_cairo(SOURCE.to_string(), "cast.cm".to_string(), options)
+        .expect("Failed to compile")
+        .program
+}
+
+fn run(program: &Program, entrypoint: &str, arg: InputValue) -> Result<CairoMValue, RunnerError> {
+    let output = run_cairo_program(program, entrypoint, &[arg], RunnerOptions::default())?;
+    Ok(output.return_values[0].clone())
+}
+
+#[test]
+fn test_numeric_to_bool_out_of_range() {
+    let program = compile(CompilerOptions::default());
+    for x in [2, 65536, 0x7fff_fffe] {
+        assert!(
+            run(&program, "felt_to_bool", InputValue::Number(x)).is_err(),
+            "{x} as bool should fail"
+        );
+        assert!(
+            run(&program, "u32_to_bool", InputValue::Number(x)).is_err(),
+            "{x}u32 as bool should fail"
+        );
+    }
+}
@@ -1,9 +1,14 @@
 // Common test utilities for mdtest runners
 
-use cairo_m_common::program::{AbiSlot, AbiType};
-use cairo_m_common::{CairoMValue, InputValue};
+use cairo_m_common::abi_codec::m31_from_i64;
+use cairo_m_common::program::{AbiSlot, AbiType, EntrypointInfo};
+use cairo_m_common::{parse_cli_arg, CairoMValue, InputValue, Program};
 use cairo_m_compiler::{compile_cairo, CompilerError, CompilerOptions};
-use cairo_m_runner::run_cairo_program;
+use cairo_m_prover::adapter::import_from_runner_output;
+use cairo_m_prover::prover::prove_cairo_m;
+use cairo_m_prover::prover_config::DEV_FAST;
+use cairo_m_prover::verifier::verify_cairo_m;
+use cairo_m_runner::{run_cairo_program, RunnerOptions, RunnerOutput};
 use cairo_m_test_utils::mdtest;
 use once_cell::sync::Lazy;
 use rand::rngs::StdRng;
@@ -12,6 +17,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Command;
 use stwo_prover::core::fields::m31::M31;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleChannel;
 
 /// Lazily extract all tests once and store them in a static HashMap
 static ALL_TESTS: Lazy<HashMap<String, mdtest::MdTest>> = Lazy::new(|| {
@@ -65,13 +71,34 @@ fn run_mdtest_diff_inner(
                     }
                     return Err(error_str);
                 }
-                CompilerError::MirGenerationFailed | CompilerError::CodeGenerationFailed(_) => {
+                CompilerError::DependencyCycle(_)
+                | CompilerError::MirGenerationFailed
+                | CompilerError::CodeGenerationFailed(_)
+                | CompilerError::UnknownLint(_)
+                | CompilerError::UnknownEntrypoint(_)
+                | CompilerError::InvalidMirPasses(_) => {
                     return Err(format!("Compilation failed: {:?}", e));
                 }
             }
         }
     };
 
+    // Configure runner
+    let runner_options = test
+        .config
+        .as_ref()
+        .map(|c| RunnerOptions {
+            max_steps: c.mdtest.max_steps,
+            environment: c.mdtest.environment.iter().copied().map(M31::from).collect(),
+            ..Default::default()
+        })
+        .unwrap_or_default();
+
+    // Run the explicit cases of the test
+    for case in &test.metadata.cases {
+        run_test_case(&compiled.program, case, runner_options.clone())?;
+    }
+
     // Find the entry point function
     let entry_point = find_test_function(&test.cairo_source);
 
@@ -85,15 +112,6 @@ fn run_mdtest_diff_inner(
     let mut rng = StdRng::seed_from_u64(42);
     let args = generate_random_args(&entrypoint_info.params, &mut rng);
 
-    // Configure runner
-    let runner_options = test
-        .config
-        .as_ref()
-        .map(|c| cairo_m_runner::RunnerOptions {
-            max_steps: c.mdtest.max_steps,
-        })
-        .unwrap_or_default();
-
     // Execute Cairo-M program
     let cairo_output_info =
         match run_cairo_program(&compiled.program, &entry_point, &args, runner_options) {
@@ -113,6 +131,30 @@ fn run_mdtest_diff_inner(
             }
         };
 
+    check_output(
+        test,
+        &entry_point,
+        &args,
+        entrypoint_info,
+        &cairo_output_info,
+    )?;
+
+    // Prove and verify the execution if requested
+    if test.config.as_ref().is_some_and(|c| c.mdtest.prove) {
+        prove_and_verify(cairo_output_info)?;
+    }
+
+    Ok(())
+}
+
+/// Check the output of an execution against the expected output or the Rust differential
+fn check_output(
+    test: &mdtest::MdTest,
+    entry_point: &str,
+    args: &[InputValue],
+    entrypoint_info: &EntrypointInfo,
+    cairo_output_info: &RunnerOutput,
+) -> Result<(), String> {
     // Format output
     let cairo_output = format_output(&cairo_output_info.return_values, &entrypoint_info.returns);
 
@@ -129,17 +171,17 @@ fn run_mdtest_diff_inner(
 
     // Run differential testing with Rust
     let (rust_source, rust_entry_point) = if let Some(rust) = &test.rust_source {
-        (rust.to_string(), entry_point.clone())
+        (rust.to_string(), entry_point.to_string())
     } else {
         let converted_rust = convert_cairo_to_rust(&test.cairo_source);
-        let converted_entrypoint = convert_cairo_to_rust(&entry_point);
+        let converted_entrypoint = convert_cairo_to_rust(entry_point);
         (converted_rust, converted_entrypoint)
     };
 
     let rust_output = run_rust_differential(
         &rust_source,
         &rust_entry_point,
-        &args,
+        args,
         &entrypoint_info.params,
         &entrypoint_info.returns,
     )?;
@@ -172,6 +214,82 @@ fn run_mdtest_diff_inner(
     Ok(())
 }
 
+/// Prove each segment of an execution with the dev-fast configuration and verify the proofs
+fn prove_and_verify(output: RunnerOutput) -> Result<(), String> {
+    for (i, segment) in output.vm.segments.into_iter().enumerate() {
+        let mut prover_input =
+            import_from_runner_output(segment, output.public_address_ranges.clone())
+                .map_err(|e| format!("Failed to import segment {}: {:?}", i, e))?;
+        let proof = prove_cairo_m::<Blake2sMerkleChannel>(&mut prover_input, Some(DEV_FAST))
+            .map_err(|e| format!("Failed to prove segment {}: {:?}", i, e))?;
+        verify_cairo_m::<Blake2sMerkleChannel>(proof, Some(DEV_FAST))
+            .map_err(|e| format!("Failed to verify segment {}: {:?}", i, e))?;
+    }
+    Ok(())
+}
+
+/// Run an explicit `//! case:` of a test and check its return values
+fn run_test_case(
+    program: &Program,
+    case: &mdtest::TestCase,
+    runner_options: RunnerOptions,
+) -> Result<(), String> {
+    let call = format!("{}({})", case.function, case.args.join(", "));
+    let parse = |value: &String| {
+        parse_cli_arg(value)
+            .map_err(|e| format!("Invalid value `{}` in case `{}`: {}", value, call, e))
+    };
+    let args = case.args.iter().map(parse).collect::<Result<Vec<_>, _>>()?;
+    let expected = case
+        .expected
+        .iter()
+        .map(parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = run_cairo_program(program, &case.function, &args, runner_options)
+        .map_err(|e| format!("Runtime error in case `{}`: {:?}", call, e))?;
+
+    let matches = expected.len() == output.return_values.len()
+        && expected
+            .iter()
+            .zip(&output.return_values)
+            .all(|(expected, value)| value_matches(expected, value));
+    if !matches {
+        return Err(format!(
+            "Case `{}` failed! Expected: {}, Got: {:?}",
+            call,
+            case.expected.join(", "),
+            output.return_values
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check a returned value against an expected value, interpreted with the returned type
+fn value_matches(expected: &InputValue, value: &CairoMValue) -> bool {
+    match (expected, value) {
+        (InputValue::Number(n), CairoMValue::Felt(v) | CairoMValue::Pointer(v)) => {
+            m31_from_i64(*n) == *v
+        }
+        (InputValue::Number(n), CairoMValue::U32(v)) => *n == i64::from(*v),
+        (InputValue::Bool(b), CairoMValue::Bool(v)) => b == v,
+        (InputValue::List(items), CairoMValue::Tuple(values) | CairoMValue::Array(values)) => {
+            items.len() == values.len()
+                && items.iter().zip(values).all(|(e, v)| value_matches(e, v))
+        }
+        (InputValue::Struct(items), CairoMValue::Struct(fields)) => {
+            items.len() == fields.len()
+                && items
+                    .iter()
+                    .zip(fields)
+                    .all(|(e, (_, v))| value_matches(e, v))
+        }
+        (InputValue::Unit, CairoMValue::Unit) => true,
+        _ => false,
+    }
+}
+
 fn sanitize_test_name(name: &str) -> String {
     name.replace(" - ", "_")
         .replace(" ", "_")
@@ -0,0 +1,66 @@
+use cairo_m_common::{CairoMValue, InputValue};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::vm::VmError;
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+
+/// Tests for divisions by zero, which trap with the source location of the division.
+/// Divisions by non-zero values are checked in `mdtest/01-basics`.
+
+const SOURCE: &str = r#"fn felt_div(x: felt, y: felt) -> felt {
+    return x / y;
+}
+
+fn u32_div(x: u32, y: u32) -> u32 {
+    return x / y;
+}
+
+fn u32_rem(x: u32, y: u32) -> u32 {
+    return x % y;
+}
+"#;
+
+fn run(entrypoint: &str, x: i64, y: i64) -> Result<CairoMValue, RunnerError> {
+    let compiled = compile_cairo(
+        SOURCE.to_string(),
+        "division.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile");
+    let output = run_cairo_program(
+        &compiled.program,
+        entrypoint,
+        &[InputValue::Number(x), InputValue::Number(y)],
+        RunnerOptions::default(),
+    )?;
+    Ok(output.return_values[0].clone())
+}
+
+fn expect_division_by_zero(result: Result<CairoMValue, RunnerError>) -> Option<String> {
+    match result {
+        Err(RunnerError::VmError(error)) => match error.inner() {
+            VmError::DivisionByZero { source_loc, .. } => source_loc.clone(),
+            other => panic!("Expected a division by zero, got {other:?}"),
+        },
+        other => panic!("Expected a division by zero, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_felt_division_by_zero() {
+    assert_eq!(
+        expect_division_by_zero(run("felt_div", 10, 0)).as_deref(),
+        Some("division.cm:2:12")
+    );
+}
+
+#[test]
+fn test_u32_division_by_zero() {
+    assert_eq!(
+        expect_division_by_zero(run("u32_div", 10, 0)).as_deref(),
+        Some("division.cm:6:12")
+    );
+    assert_eq!(
+        expect_division_by_zero(run("u32_rem", 10, 0)).as_deref(),
+        Some("division.cm:10:12")
+    );
+}
@@ -0,0 +1,87 @@
+use cairo_m_common::InputValue;
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+use stwo_prover::core::fields::m31::M31;
+
+/// Tests for `env(index)`, which reads the environment block supplied at run time.
+/// The values read are checked in `mdtest/04-advanced/05-environment.md`.
+
+const SOURCE: &str = r#"
+fn block_number() -> felt {
+    return env(0);
+}
+
+fn blocks_since(start: felt) -> felt {
+    let chain_id = env(1);
+    return (env(0) - start) * chain_id;
+}
+"#;
+
+fn compile(source: &str) -> cairo_m_common::Program {
+    compile_cairo(
+        source.to_string(),
+        "env.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile")
+    .program
+}
+
+#[test]
+fn test_env_block_is_public() {
+    let program = compile(SOURCE);
+    assert_eq!(program.metadata.environment_size, 2);
+
+    let output = run_cairo_program(
+        &program,
+        "blocks_since",
+        &[InputValue::Number(1000)],
+        RunnerOptions {
+            environment: vec![M31::from(1234), M31::from(2)],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let ranges = &output.public_address_ranges;
+    assert_eq!(ranges.environment.len(), 2);
+    assert_eq!(ranges.environment.start, ranges.program.end);
+    assert_eq!(ranges.input.start, ranges.environment.end);
+    for (i, address) in ranges.environment.clone().enumerate() {
+        assert_eq!(
+            output.vm.memory.get_data(M31::from(address)).unwrap(),
+            [M31::from(1234), M31::from(2)][i]
+        );
+    }
+}
+
+#[test]
+fn test_env_errors() {
+    // Too few environment values for the program
+    let program = compile(SOURCE);
+    let result = run_cairo_program(
+        &program,
+        "block_number",
+        &[],
+        RunnerOptions {
+            environment: vec![M31::from(1234)],
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(RunnerError::EnvironmentTooShort {
+            expected: 2,
+            provided: 1
+        })
+    ));
+
+    // The index must be known at compile time
+    assert!(
+        compile_cairo(
+            "fn main(i: felt) -> felt { return env(i); }".to_string(),
+            "env.cm".to_string(),
+            CompilerOptions::default(),
+        )
+        .is_err()
+    );
+}
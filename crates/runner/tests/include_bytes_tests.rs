@@ -0,0 +1,81 @@
+use cairo_m_common::{CairoMValue, InputValue};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::{RunnerOptions, run_cairo_program};
+
+/// Tests for `include_bytes`, which embeds the bytes of a file as a const felt array.
+
+const SOURCE: &str = r#"
+const TABLE: [felt; 4] = include_bytes("table.bin");
+const KEY = include_bytes("data/key.bin");
+
+fn lookup(i: felt) -> felt {
+    return TABLE[i];
+}
+
+fn key_sum() -> felt {
+    return KEY[0] + KEY[1] + KEY[2];
+}
+"#;
+
+fn run(entrypoint: &str, args: &[i64]) -> u32 {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("table.bin"), [0u8, 7, 42, 255]).unwrap();
+    std::fs::create_dir(dir.path().join("data")).unwrap();
+    std::fs::write(dir.path().join("data/key.bin"), [1u8, 2, 3]).unwrap();
+
+    // Included paths are relative to the source file
+    let source_name = dir.path().join("main.cm").to_string_lossy().to_string();
+    let compiled = compile_cairo(SOURCE.to_string(), source_name, CompilerOptions::default())
+        .expect("Failed to compile");
+    let args = args
+        .iter()
+        .map(|&arg| InputValue::Number(arg))
+        .collect::<Vec<_>>();
+    let output = run_cairo_program(
+        &compiled.program,
+        entrypoint,
+        &args,
+        RunnerOptions::default(),
+    )
+    .expect("Failed to run program");
+    match &output.return_values[0] {
+        CairoMValue::Felt(value) => value.0,
+        other => panic!("Expected a felt, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_include_bytes_lookup() {
+    assert_eq!(run("lookup", &[0]), 0);
+    assert_eq!(run("lookup", &[2]), 42);
+    assert_eq!(run("lookup", &[3]), 255);
+    assert_eq!(run("key_sum", &[]), 6);
+}
+
+#[test]
+fn test_include_bytes_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let source_name = dir.path().join("main.cm").to_string_lossy().to_string();
+    let compile = |source: &str| {
+        compile_cairo(
+            source.to_string(),
+            source_name.clone(),
+            CompilerOptions::default(),
+        )
+    };
+
+    // Missing file
+    assert!(
+        compile(
+            "const DATA = include_bytes(\"missing.bin\");\nfn main() -> felt { return DATA[0]; }"
+        )
+        .is_err()
+    );
+
+    // Not the value of a const
+    std::fs::write(dir.path().join("data.bin"), [1u8]).unwrap();
+    assert!(
+        compile("fn main() -> felt { let data = include_bytes(\"data.bin\"); return data[0]; }")
+            .is_err()
+    );
+}
@@ -0,0 +1,105 @@
+use cairo_m_common::{CairoMValue, InputValue, Program};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+use cairo_m_runner::loader::{LoadedProgram, load_program, map_program};
+use cairo_m_runner::vm::VmError;
+use cairo_m_runner::{RunnerError, RunnerOptions, run_cairo_program};
+use stwo_prover::core::fields::m31::M31;
+
+/// Tests for loading compiled programs from files, in the JSON and binary formats.
+
+const SOURCE: &str = r#"const TABLE: [felt; 3] = [3, 5, 7];
+
+fn sum_table(n: felt) -> felt {
+    assert(n != 0, "empty sum");
+    let acc = 0;
+    let i = 0;
+    while i != 3 {
+        acc = acc + TABLE[i] * n;
+        i = i + 1;
+    }
+    return acc;
+}
+"#;
+
+fn compile() -> Program {
+    let compiled = compile_cairo(
+        SOURCE.to_string(),
+        "loader.cm".to_string(),
+        CompilerOptions::default(),
+    )
+    .expect("Failed to compile");
+    (*compiled.program).clone()
+}
+
+#[test]
+fn test_binary_program_runs_like_json_program() {
+    let program = compile();
+    let dir = tempfile::tempdir().unwrap();
+    let json_path = dir.path().join("program.json");
+    let binary_path = dir.path().join("program.bin");
+    std::fs::write(&json_path, sonic_rs::to_string(&program).unwrap()).unwrap();
+    std::fs::write(&binary_path, program.to_binary()).unwrap();
+
+    let expected = run_cairo_program(
+        &program,
+        "sum_table",
+        &[InputValue::Number(2)],
+        RunnerOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        expected.return_values,
+        vec![CairoMValue::Felt(M31::from(30))]
+    );
+
+    let json = load_program(&json_path).unwrap();
+    assert!(matches!(json, LoadedProgram::Json(_)));
+    let binary = load_program(&binary_path).unwrap();
+    assert!(matches!(binary, LoadedProgram::Binary(_)));
+
+    for loaded in [json, binary] {
+        let output = loaded
+            .run(
+                "sum_table",
+                &[InputValue::Number(2)],
+                RunnerOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(output.return_values, expected.return_values);
+        assert_eq!(output.vm.trace, expected.vm.trace);
+    }
+}
+
+#[test]
+fn test_binary_program_reports_aborts() {
+    let program = compile();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("program.bin");
+    std::fs::write(&path, program.to_binary()).unwrap();
+
+    let mapped = map_program(&path).unwrap();
+    assert_eq!(mapped.entrypoints, program.entrypoints);
+
+    let result = LoadedProgram::Binary(mapped).run(
+        "sum_table",
+        &[InputValue::Number(0)],
+        RunnerOptions::default(),
+    );
+    match result {
+        Err(RunnerError::VmError(error)) => match error.inner() {
+            VmError::Aborted { info, .. } => assert_eq!(info.message, "empty sum"),
+            other => panic!("Expected an abort, got {other:?}"),
+        },
+        other => panic!("Expected an abort, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_map_program_rejects_json() {
+    let program = compile();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("program.json");
+    std::fs::write(&path, sonic_rs::to_string(&program).unwrap()).unwrap();
+
+    assert!(map_program(&path).is_err());
+}
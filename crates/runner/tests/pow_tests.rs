@@ -0,0 +1,58 @@
+use cairo_m_common::instruction::MAX_POW_EXPONENT;
+use cairo_m_common::{Instruction, Program, ProgramData};
+use cairo_m_compiler::{CompilerOptions, compile_cairo};
+
+/// Tests for the fusion of repeated felt multiplications into the `StorePowFpImm` opcode.
+/// The results of the fused programs are checked in `mdtest/01-basics/04-arithmetic.md`.
+
+const SOURCE: &str = r#"
+fn square(x: felt) -> felt {
+    return x * x;
+}
+
+fn cube(x: felt) -> felt {
+    return x * x * x;
+}
+
+fn pow_8(x: felt) -> felt {
+    let x2 = x * x;
+    let x3 = x2 * x;
+    return x3 * x * x * x * x * x;
+}
+
+fn pow_9(x: felt) -> felt {
+    return x * x * x * x * x * x * x * x * x;
+}
+
+// The square is used twice, so it cannot be fused away.
+fn shared_square(x: felt) -> felt {
+    let x2 = x * x;
+    return x2 * x + x2;
+}
+"#;
+
+fn compile(options: CompilerOptions) -> Program {
+    compile_cairo(SOURCE.to_string(), "pow.cm".to_string(), options)
+        .expect("Failed to compile")
+        .program
+}
+
+/// Returns the exponents of the `StorePowFpImm` instructions of the program.
+fn pow_exponents(program: &Program) -> Vec<u32> {
+    program
+        .data
+        .iter()
+        .filter_map(|data| match data {
+            ProgramData::Instruction(Instruction::StorePowFpImm { imm, .. }) => Some(imm.0),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_multiplication_chains_use_pow_opcode() {
+    let mut exponents = pow_exponents(&compile(CompilerOptions::default()));
+    exponents.sort_unstable();
+    // cube, pow_8, and pow_9 as x^MAX_POW_EXPONENT * x; squares stay multiplications.
+    assert_eq!(exponents, vec![3, MAX_POW_EXPONENT, MAX_POW_EXPONENT]);
+}
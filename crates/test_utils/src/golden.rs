@@ -0,0 +1,183 @@
+//! Golden-file harness for the diagnostics of programs expected to fail.
+//!
+//! Each `.cm` fixture of the `golden` directory is compiled, and its diagnostics, formatted
+//! without colors, are compared to the expected output stored next to it with the
+//! [`EXPECTED_EXTENSION`] extension. Mismatches are reported as line diffs, so that changes
+//! to the wording or the spans of diagnostics show up in review.
+//!
+//! Setting the [`BLESS_ENV`] environment variable writes the current output to the expected
+//! files instead of comparing them, to add fixtures or accept intended changes.
+
+use std::path::{Path, PathBuf};
+
+/// Environment variable which, when set, updates the expected files instead of checking them
+pub const BLESS_ENV: &str = "CAIRO_M_BLESS";
+
+/// Extension of the files holding the expected diagnostics of a fixture
+pub const EXPECTED_EXTENSION: &str = "diagnostics";
+
+/// Get the path to the golden fixtures directory
+pub fn golden_path() -> PathBuf {
+    crate::WORKSPACE_ROOT.join("golden")
+}
+
+/// Discover all golden fixtures
+///
+/// ## Returns
+/// The paths of the `.cm` fixtures relative to the golden directory, sorted
+pub fn discover_golden_fixtures() -> Vec<String> {
+    let golden = golden_path();
+    let mut fixtures = Vec::new();
+    discover_recursive(&golden, &golden, &mut fixtures);
+    fixtures.sort();
+    fixtures
+}
+
+fn discover_recursive(base_path: &Path, current_path: &Path, fixtures: &mut Vec<String>) {
+    if let Ok(entries) = std::fs::read_dir(current_path) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                discover_recursive(base_path, &path, fixtures);
+            } else if path.extension().and_then(|s| s.to_str()) == Some("cm") {
+                if let Ok(relative_path) = path.strip_prefix(base_path) {
+                    fixtures.push(relative_path.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+}
+
+type ProcessorType<'a> = Box<dyn Fn(&str, &str) -> Option<String> + 'a>;
+
+/// Runner checking the diagnostics of the golden fixtures against their expected files
+pub struct GoldenRunner<'a> {
+    /// Function compiling a source with its fixture name, returning its formatted diagnostics
+    /// if it fails to compile, `None` if it compiles
+    pub processor: ProcessorType<'a>,
+    /// Whether the expected files are written instead of checked
+    pub bless: bool,
+}
+
+impl<'a> GoldenRunner<'a> {
+    /// Create a runner, blessing the expected files if [`BLESS_ENV`] is set
+    pub fn new(processor: impl Fn(&str, &str) -> Option<String> + 'a) -> Self {
+        Self {
+            processor: Box::new(processor),
+            bless: std::env::var_os(BLESS_ENV).is_some(),
+        }
+    }
+
+    /// Check the diagnostics of a fixture against its expected file
+    ///
+    /// ## Arguments
+    /// * `name` - The path of the fixture relative to the golden directory
+    ///
+    /// ## Errors
+    /// Returns a description of the failure if the fixture compiles, if its expected file is
+    /// missing, or if its diagnostics differ from the expected ones
+    pub fn check_fixture(&self, name: &str) -> Result<(), String> {
+        let path = golden_path().join(name);
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read fixture '{}': {}", path.display(), e))?;
+        let actual = (self.processor)(&source, name)
+            .map(|output| normalize(&output))
+            .ok_or_else(|| format!("Fixture '{name}' compiled without errors"))?;
+
+        let expected_path = path.with_extension(EXPECTED_EXTENSION);
+        if self.bless {
+            return std::fs::write(&expected_path, actual)
+                .map_err(|e| format!("Failed to write '{}': {}", expected_path.display(), e));
+        }
+
+        let expected = std::fs::read_to_string(&expected_path).map_err(|e| {
+            format!(
+                "Failed to read '{}': {} (set {BLESS_ENV}=1 to create it)",
+                expected_path.display(),
+                e
+            )
+        })?;
+        let expected = normalize(&expected);
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(format!(
+                "Diagnostics of fixture '{name}' differ from '{}' (set {BLESS_ENV}=1 to update it):\n{}",
+                expected_path.display(),
+                line_diff(&expected, &actual)
+            ))
+        }
+    }
+
+    /// Check every golden fixture
+    ///
+    /// ## Panics
+    /// Panics with the failures of all fixtures if any fails
+    pub fn run_all(&self) {
+        let fixtures = discover_golden_fixtures();
+        assert!(!fixtures.is_empty(), "No golden fixtures found");
+
+        let failures: Vec<String> = fixtures
+            .iter()
+            .filter_map(|name| self.check_fixture(name).err())
+            .collect();
+        assert!(
+            failures.is_empty(),
+            "{} of {} golden fixtures failed:\n\n{}",
+            failures.len(),
+            fixtures.len(),
+            failures.join("\n\n")
+        );
+    }
+}
+
+/// Normalize diagnostics output for comparison
+///
+/// Trailing whitespace, which the diagnostics renderer emits after labels, is stripped from
+/// each line, and the output ends with a single newline.
+pub fn normalize(output: &str) -> String {
+    let mut normalized = output
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string();
+    normalized.push('\n');
+    normalized
+}
+
+/// Line diff between two texts, with removed lines prefixed by `-` and added ones by `+`
+pub fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence lengths of the suffixes
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            diff.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    diff
+}
@@ -1,6 +1,8 @@
 #![allow(clippy::option_if_let_else)]
 
+pub mod golden;
 pub mod mdtest;
+pub mod opt_levels;
 
 use std::path::PathBuf;
 
@@ -16,6 +16,12 @@ pub struct MdTestSection {
     /// Maximum number of steps for execution
     #[serde(rename = "max-steps", default = "default_max_steps")]
     pub max_steps: usize,
+    /// Whether to prove and verify the execution of the tests
+    #[serde(default)]
+    pub prove: bool,
+    /// Environment block of the executions, read with `env(index)`
+    #[serde(default)]
+    pub environment: Vec<u32>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -40,6 +46,24 @@ pub struct TestMetadata {
     pub rust_equiv: Option<String>,
     pub tags: Vec<String>,
     pub ignore: Option<String>,
+    /// Explicit cases declared with `//! case:` annotations
+    pub cases: Vec<TestCase>,
+}
+
+/// A call of a named function with explicit arguments and expected return values,
+/// declared with `//! case: function(arg1, arg2) -> value1, value2`
+///
+/// Arguments and return values are kept as written, in the syntax of the runner's CLI
+/// arguments (numbers, `true`/`false`, `(..)` tuples, `[..]` arrays and `{..}` structs),
+/// and are interpreted against the function's ABI when the case is run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    /// Name of the called function
+    pub function: String,
+    /// Arguments of the call
+    pub args: Vec<String>,
+    /// Expected return values, one per returned value
+    pub expected: Vec<String>,
 }
 
 /// Represents a location in a source file
@@ -4,7 +4,7 @@ pub mod runner;
 
 use std::path::{Path, PathBuf};
 
-pub use config::{Location, MdTestConfig, TestMetadata};
+pub use config::{Location, MdTestConfig, TestCase, TestMetadata};
 pub use parser::{MdTest, ParseError, extract_tests};
 pub use runner::{MdTestRunner, TestSnapshot};
 
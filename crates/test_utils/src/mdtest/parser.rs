@@ -4,7 +4,7 @@ use std::path::Path;
 use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
 use thiserror::Error;
 
-use crate::mdtest::config::{Location, MdTestConfig, TestMetadata};
+use crate::mdtest::config::{Location, MdTestConfig, TestCase, TestMetadata};
 
 #[derive(Debug, Error)]
 pub enum ParseError {
@@ -212,10 +212,7 @@ fn format_test_name(h1: &str, h2: &str, h3: &str) -> String {
     }
 }
 
-fn parse_annotations(
-    code: &str,
-    _line_number: usize,
-) -> Result<(String, TestMetadata), ParseError> {
+fn parse_annotations(code: &str, line_number: usize) -> Result<(String, TestMetadata), ParseError> {
     let mut metadata = TestMetadata::default();
     let mut source_lines = Vec::new();
 
@@ -234,6 +231,10 @@ fn parse_annotations(
                 metadata.tags = tags_str.split(',').map(|s| s.trim().to_string()).collect();
             } else if let Some(ignore) = annotation.strip_prefix("ignore:") {
                 metadata.ignore = Some(ignore.trim().to_string());
+            } else if let Some(case) = annotation.strip_prefix("case:") {
+                metadata
+                    .cases
+                    .push(parse_test_case(case.trim(), line_number)?);
             }
         } else {
             source_lines.push(line);
@@ -242,3 +243,58 @@ fn parse_annotations(
 
     Ok((source_lines.join("\n"), metadata))
 }
+
+/// Parses a `function(arg1, arg2) -> value1, value2` test case
+fn parse_test_case(case: &str, line: usize) -> Result<TestCase, ParseError> {
+    let invalid = |message: &str| ParseError::InvalidAnnotation {
+        line,
+        message: format!("{message} in case `{case}`"),
+    };
+
+    let (call, expected) = case
+        .split_once("->")
+        .ok_or_else(|| invalid("missing `->`"))?;
+    let (function, args) = call
+        .trim()
+        .split_once('(')
+        .ok_or_else(|| invalid("missing argument list"))?;
+    let args = args
+        .strip_suffix(')')
+        .ok_or_else(|| invalid("unclosed argument list"))?;
+    let function = function.trim();
+    if function.is_empty() {
+        return Err(invalid("missing function name"));
+    }
+
+    Ok(TestCase {
+        function: function.to_string(),
+        args: split_values(args),
+        expected: split_values(expected),
+    })
+}
+
+/// Splits a comma-separated list of values, ignoring the commas nested in brackets
+fn split_values(list: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+
+    for c in list.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                values.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        values.push(current.trim().to_string());
+    }
+
+    values
+}
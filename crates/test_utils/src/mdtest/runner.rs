@@ -111,7 +111,7 @@ pub struct TestSnapshot {
     pub suffix: String,
 }
 
-fn sanitize_test_name(name: &str) -> String {
+pub(crate) fn sanitize_test_name(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '_' {
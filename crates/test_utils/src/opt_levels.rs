@@ -0,0 +1,92 @@
+//! Harness compiling the test_data fixtures at several optimization levels.
+//!
+//! The MIR and CASM of each level are snapshotted, so that optimizer changes show up as
+//! reviewable diffs, and the executions of every level must return the same results.
+
+use crate::mdtest::runner::sanitize_test_name;
+use crate::mdtest::TestSnapshot;
+use crate::read_fixture;
+
+/// Artifacts of the compilation and execution of a fixture at one optimization level
+#[derive(Debug, Clone)]
+pub struct LevelArtifacts {
+    /// Pretty-printed MIR
+    pub mir: String,
+    /// Annotated CASM
+    pub casm: String,
+    /// Result of the execution of each entrypoint, as (entrypoint, result) in a stable order
+    pub executions: Vec<(String, String)>,
+}
+
+type ProcessorType<'a, L> = Box<dyn Fn(&str, &str, L) -> Result<LevelArtifacts, String> + 'a>;
+
+/// Runner compiling fixtures at several optimization levels
+pub struct OptLevelRunner<'a, L> {
+    /// Optimization levels with their name, the first one being the baseline
+    pub levels: Vec<(&'a str, L)>,
+    /// Function compiling and executing a source at a level
+    pub processor: ProcessorType<'a, L>,
+}
+
+impl<'a, L: Copy> OptLevelRunner<'a, L> {
+    pub fn new(
+        levels: Vec<(&'a str, L)>,
+        processor: impl Fn(&str, &str, L) -> Result<LevelArtifacts, String> + 'a,
+    ) -> Self {
+        Self {
+            levels,
+            processor: Box::new(processor),
+        }
+    }
+
+    /// Compile a fixture at every level and generate the snapshot of each level
+    ///
+    /// ## Arguments
+    /// * `name` - The relative path to the fixture file (e.g., "functions/fib.cm")
+    ///
+    /// ## Returns
+    /// One snapshot per level, suffixed with the fixture and level names
+    ///
+    /// ## Panics
+    /// Panics if the fixture fails to compile at a level, or if the executions of a level
+    /// differ from the ones of the baseline level
+    pub fn run_fixture(&self, name: &str) -> Vec<TestSnapshot> {
+        let source = read_fixture(name);
+        let fixture = name.strip_suffix(".cm").unwrap_or(name);
+        let mut baseline: Option<(&str, Vec<(String, String)>)> = None;
+
+        self.levels
+            .iter()
+            .map(|&(level_name, level)| {
+                let artifacts = (self.processor)(&source, name, level).unwrap_or_else(|e| {
+                    panic!(
+                        "Failed to compile fixture '{}' at level '{}': {}",
+                        name, level_name, e
+                    )
+                });
+
+                match &baseline {
+                    Some((baseline_name, executions)) => assert_eq!(
+                        executions, &artifacts.executions,
+                        "Fixture '{}' executes differently at levels '{}' and '{}'",
+                        name, baseline_name, level_name
+                    ),
+                    None => baseline = Some((level_name, artifacts.executions.clone())),
+                }
+
+                TestSnapshot {
+                    name: format!("{} ({})", fixture, level_name),
+                    content: format!(
+                        "Source:\n{}\n{}\nGenerated MIR:\n{}\n{}\nGenerated CASM:\n{}",
+                        source,
+                        "=".repeat(60),
+                        artifacts.mir,
+                        "=".repeat(60),
+                        artifacts.casm
+                    ),
+                    suffix: format!("{}_{}", sanitize_test_name(fixture), level_name),
+                }
+            })
+            .collect()
+    }
+}
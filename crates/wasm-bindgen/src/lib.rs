@@ -0,0 +1,179 @@
+//! # Cairo-M JavaScript bindings
+//!
+//! A `wasm32-unknown-unknown` facade over the compiler and the runner, built with
+//! `wasm-pack`, so that the playground and the docs site compile and run Cairo-M programs
+//! client-side.
+//!
+//! Programs cross the JS boundary as the JSON of a compiled [`Program`], and return values
+//! as JSON: felts, u32s and pointers are numbers, tuples and arrays are arrays, structs are
+//! objects and unit is `null`.
+
+use cairo_m_common::{CairoMValue, Program, parse_cli_arg};
+use cairo_m_compiler::{CompilerError, CompilerOptions, compile_cairo, format_diagnostics};
+use cairo_m_compiler_diagnostics::Diagnostic;
+use cairo_m_runner::{RunnerOptions, run_cairo_program};
+use serde_json::{Map, Value};
+use wasm_bindgen::prelude::*;
+
+/// Name of the source file of the compiled programs, used in diagnostics
+const SOURCE_NAME: &str = "main.cm";
+
+/// A diagnostic of the compilation of a program
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsDiagnostic {
+    /// `error`, `warning`, `info` or `hint`
+    pub severity: String,
+    /// Name of the diagnostic code, in snake case (e.g. `unused_variable`)
+    pub code: String,
+    pub message: String,
+    /// Byte offset of the start of the span of the diagnostic
+    pub start: usize,
+    /// Byte offset of the end of the span of the diagnostic
+    pub end: usize,
+    /// The diagnostic rendered with its source snippet, without colors
+    pub rendered: String,
+}
+
+/// Result of the compilation of a program
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone)]
+pub struct CompileResult {
+    /// Whether the compilation succeeded
+    pub success: bool,
+    /// JSON of the compiled program, if the compilation succeeded
+    pub program: Option<String>,
+    /// Errors if the compilation failed, warnings otherwise
+    pub diagnostics: Vec<JsDiagnostic>,
+}
+
+/// Result of the execution of a program
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    /// JSON array of the return values of the entrypoint
+    #[wasm_bindgen(js_name = returnValues)]
+    pub return_values: String,
+    /// Values printed with `debug_print`, in execution order
+    pub logs: Vec<String>,
+    /// Number of steps of the execution
+    pub steps: usize,
+}
+
+/// Compiles a Cairo-M source
+///
+/// ## Arguments
+/// * `source` - The source code to compile
+///
+/// ## Returns
+/// The JSON of the compiled program and the diagnostics of the compilation
+#[wasm_bindgen]
+pub fn compile(source: &str) -> CompileResult {
+    let to_js = |diagnostics: &[Diagnostic]| {
+        diagnostics
+            .iter()
+            .map(|d| JsDiagnostic {
+                severity: d.severity.to_string(),
+                code: d.code.name().to_string(),
+                message: d.message.clone(),
+                start: d.span.start,
+                end: d.span.end,
+                rendered: format_diagnostics(source, std::slice::from_ref(d), false),
+            })
+            .collect()
+    };
+
+    match compile_cairo(
+        source.to_string(),
+        SOURCE_NAME.to_string(),
+        CompilerOptions::default(),
+    ) {
+        Ok(output) => CompileResult {
+            success: true,
+            program: Some(
+                serde_json::to_string(&*output.program)
+                    .expect("a compiled program is serializable"),
+            ),
+            diagnostics: to_js(&output.diagnostics),
+        },
+        Err(CompilerError::ParseErrors(errors) | CompilerError::SemanticErrors(errors)) => {
+            CompileResult {
+                success: false,
+                program: None,
+                diagnostics: to_js(&errors),
+            }
+        }
+        Err(e) => CompileResult {
+            success: false,
+            program: None,
+            diagnostics: vec![JsDiagnostic {
+                severity: "error".to_string(),
+                code: "compilation_failed".to_string(),
+                message: e.to_string(),
+                start: 0,
+                end: 0,
+                rendered: e.to_string(),
+            }],
+        },
+    }
+}
+
+/// Runs an entrypoint of a compiled program
+///
+/// ## Arguments
+/// * `program_json` - The JSON of the compiled program, as returned by [`compile`]
+/// * `entrypoint` - Name of the function to execute
+/// * `args` - Arguments of the function, in the syntax of the runner CLI (e.g. `[1, 2]`)
+///
+/// ## Returns
+/// The return values and the logs of the execution
+#[wasm_bindgen]
+pub fn run(program_json: &str, entrypoint: &str, args: Vec<String>) -> Result<RunResult, JsError> {
+    run_program(program_json, entrypoint, &args).map_err(|e| JsError::new(&e))
+}
+
+/// Runs an entrypoint of a compiled program, see [`run`]
+///
+/// ## Errors
+/// Returns a message if the program or an argument is invalid, or if the execution fails
+pub fn run_program(
+    program_json: &str,
+    entrypoint: &str,
+    args: &[String],
+) -> Result<RunResult, String> {
+    let program: Program =
+        serde_json::from_str(program_json).map_err(|e| format!("Invalid program: {}", e))?;
+    let args = args
+        .iter()
+        .map(|arg| parse_cli_arg(arg).map_err(|e| format!("Invalid argument `{}`: {}", arg, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = run_cairo_program(&program, entrypoint, &args, RunnerOptions::default())
+        .map_err(|e| e.to_string())?;
+
+    Ok(RunResult {
+        return_values: Value::Array(output.return_values.into_iter().map(to_json).collect())
+            .to_string(),
+        logs: output.logs.iter().map(ToString::to_string).collect(),
+        steps: output.vm.segments.iter().map(|s| s.trace.len()).sum(),
+    })
+}
+
+/// Converts a return value to JSON
+fn to_json(value: CairoMValue) -> Value {
+    match value {
+        CairoMValue::Felt(felt) | CairoMValue::Pointer(felt) => felt.0.into(),
+        CairoMValue::Bool(b) => b.into(),
+        CairoMValue::U32(u) => u.into(),
+        CairoMValue::Tuple(values) | CairoMValue::Array(values) => {
+            Value::Array(values.into_iter().map(to_json).collect())
+        }
+        CairoMValue::Struct(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, to_json(value)))
+                .collect::<Map<_, _>>(),
+        ),
+        CairoMValue::Unit => Value::Null,
+    }
+}
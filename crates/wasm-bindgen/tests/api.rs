@@ -0,0 +1,54 @@
+use cairo_m_wasm_bindgen::{compile, run_program};
+
+/// Tests of the JS API, run natively.
+
+const SOURCE: &str = r#"
+struct Point {
+    x: felt,
+    y: u32,
+}
+
+fn make_point(x: felt, y: u32) -> Point {
+    let unused = 1;
+    return Point { x: x, y: y };
+}
+
+fn swap(a: felt, b: felt) -> (felt, felt) {
+    return (b, a);
+}
+"#;
+
+#[test]
+fn test_compile_and_run() {
+    let result = compile(SOURCE);
+    assert!(result.success);
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(result.diagnostics[0].severity, "warning");
+    assert_eq!(result.diagnostics[0].code, "unused_variable");
+    let program = result.program.unwrap();
+
+    let output = run_program(&program, "make_point", &["3".into(), "4".into()]).unwrap();
+    assert_eq!(output.return_values, r#"[{"x":3,"y":4}]"#);
+    assert!(output.steps > 0);
+
+    let output = run_program(&program, "swap", &["1".into(), "2".into()]).unwrap();
+    assert_eq!(output.return_values, "[2,1]");
+}
+
+#[test]
+fn test_compile_errors() {
+    let result = compile("fn main() -> felt { return x; }");
+    assert!(!result.success);
+    assert!(result.program.is_none());
+    assert_eq!(result.diagnostics[0].severity, "error");
+    assert!(result.diagnostics[0].rendered.contains("x"));
+}
+
+#[test]
+fn test_run_errors() {
+    let program = compile(SOURCE).program.unwrap();
+    assert!(run_program(&program, "missing", &[]).is_err());
+    assert!(run_program(&program, "swap", &["1".into()]).is_err());
+    assert!(run_program(&program, "swap", &["1".into(), "(".into()]).is_err());
+    assert!(run_program("{}", "swap", &[]).is_err());
+}
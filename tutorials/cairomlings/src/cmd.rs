@@ -114,7 +114,11 @@ impl CmdRunner {
                         }
                         return Err(anyhow::anyhow!(error_str));
                     }
-                    CompilerError::MirGenerationFailed | CompilerError::CodeGenerationFailed(_) => {
+                    CompilerError::MirGenerationFailed
+                    | CompilerError::CodeGenerationFailed(_)
+                    | CompilerError::UnknownLint(_)
+                    | CompilerError::UnknownEntrypoint(_)
+                    | CompilerError::InvalidMirPasses(_) => {
                         return Err(anyhow::anyhow!("Compilation failed: {:?}", e));
                     }
                 }
@@ -53,6 +53,8 @@ export async function activate(context: vscode.ExtensionContext) {
     // Client options
     const clientOptions: LanguageClientOptions = {
       documentSelector: [{ scheme: "file", language: "cairo-m" }],
+      // Settings of the 'cairo-m' section, reloaded by the server when they change
+      initializationOptions: { "cairo-m": config },
       synchronize: {
         // Synchronize the setting section 'cairo-m' to the server
         configurationSection: "cairo-m",